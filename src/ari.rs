@@ -0,0 +1,222 @@
+use parser::Parser;
+use problem::{condition_mode, operator_name, variable_name};
+use sexp::{read_sexps, symbol, Sexp};
+use std::collections::HashMap;
+use {ConditionConfig, ParseError, Problem, Rule, Signature, Term, TRS};
+
+use itertools::Itertools;
+
+/// Parse a string in the ARI format of the termination and confluence competitions as a
+/// [`Problem`].
+///
+/// A problem is a sequence of s-expressions: a `format` declaration, either `(format TRS)` or
+/// `(format CTRS <condition type>)`, then a `fun` declaration giving the arity of each
+/// [`Operator`], then the rules. Symbols not declared by `fun` are [`Variable`]s. A conditional
+/// rule lists its conditions after its right-hand side, as in `(rule (f x) y (= (g x) y))`.
+/// Comments of the form `; @key value` give the metadata.
+///
+/// [`Problem`]: struct.Problem.html
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_ari};
+/// let mut sig = Signature::default();
+///
+/// let problem = parse_ari(
+///     &mut sig,
+///     "; @origin COPS #1
+///      ; @author Aart Middeldorp
+///      (format TRS)
+///      (fun 0 0)
+///      (fun s 1)
+///      (fun plus 2)
+///      (rule (plus 0 y) y)
+///      (rule (plus (s x) y) (s (plus x y)))",
+/// ).expect("parse of ARI problem");
+///
+/// assert_eq!(problem.trs.display(&sig), "plus(0 y_) = y_;\nplus(s(x_) y_) = s(plus(x_ y_));");
+/// assert_eq!(problem.metadata[0], ("origin".to_string(), "COPS #1".to_string()));
+/// ```
+pub fn parse_ari(sig: &mut Signature, input: &str) -> Result<Problem, ParseError> {
+    let metadata = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with(';'))
+        .map(|line| line.trim_start_matches(';').trim())
+        .filter(|line| line.starts_with('@'))
+        .map(|line| {
+            let mut parts = line[1..].splitn(2, char::is_whitespace);
+            let key = parts.next().unwrap_or("").to_string();
+            let value = parts.next().unwrap_or("").trim().to_string();
+            (key, value)
+        })
+        .collect();
+    let mut parser = Parser::new(sig);
+    let mut arities = HashMap::new();
+    let mut rules = vec![];
+    let mut mode = None;
+    for sexp in read_sexps(input)? {
+        let list = match sexp {
+            Sexp::List(list) => list,
+            Sexp::Atom(_) => return Err(ParseError::ParseFailed),
+        };
+        let (head, rest) = list.split_first().ok_or(ParseError::ParseFailed)?;
+        match (head.atom(), rest) {
+            (Some("format"), [format]) if format.atom() == Some("TRS") => (),
+            (Some("format"), [format, condition_type]) if format.atom() == Some("CTRS") => {
+                let condition_type = condition_type.atom().ok_or(ParseError::ParseFailed)?;
+                mode = Some(condition_mode(condition_type).ok_or(ParseError::ParseFailed)?);
+            }
+            (Some("fun"), [name, arity]) => {
+                let name = name.atom().ok_or(ParseError::ParseFailed)?;
+                let arity = arity
+                    .atom()
+                    .and_then(|a| a.parse::<u32>().ok())
+                    .ok_or(ParseError::ParseFailed)?;
+                parser.get_op(name, arity);
+                arities.insert(name.to_string(), arity);
+            }
+            (Some("rule"), [lhs, rhs, conditions @ ..]) => {
+                parser.clear_variables();
+                let lhs = ari_term(&mut parser, &arities, lhs)?;
+                let rhs = ari_term(&mut parser, &arities, rhs)?;
+                let conditions = conditions
+                    .iter()
+                    .map(|condition| match *condition {
+                        Sexp::List(ref c) if c.len() == 3 && c[0].atom() == Some("=") => Ok((
+                            ari_term(&mut parser, &arities, &c[1])?,
+                            ari_term(&mut parser, &arities, &c[2])?,
+                        )),
+                        _ => Err(ParseError::ParseFailed),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                rules.push(
+                    Rule::new_conditional(lhs, vec![rhs], conditions)
+                        .ok_or(ParseError::ParseFailed)?,
+                );
+            }
+            _ => return Err(ParseError::ParseFailed),
+        }
+    }
+    let mut trs = TRS::new(rules);
+    if let Some(mode) = mode {
+        trs.set_condition_config(ConditionConfig {
+            mode,
+            ..trs.condition_config()
+        });
+    }
+    Ok(Problem { trs, metadata })
+}
+
+// Build a Term from an s-expression, given the arity of each declared operator.
+fn ari_term(
+    parser: &mut Parser,
+    arities: &HashMap<String, u32>,
+    sexp: &Sexp,
+) -> Result<Term, ParseError> {
+    let (name, args) = match *sexp {
+        Sexp::Atom(ref name) => (name.as_str(), &[][..]),
+        Sexp::List(ref list) => match list.split_first() {
+            Some((head, args)) if !args.is_empty() => {
+                (head.atom().ok_or(ParseError::ParseFailed)?, args)
+            }
+            _ => return Err(ParseError::ParseFailed),
+        },
+    };
+    match arities.get(name) {
+        Some(&arity) if arity as usize == args.len() => {
+            let args = args
+                .iter()
+                .map(|arg| ari_term(parser, arities, arg))
+                .collect::<Result<Vec<_>, _>>()?;
+            let op = parser.get_op(name, arity);
+            Ok(Term::Application { op, args })
+        }
+        None if args.is_empty() => Ok(Term::Variable(parser.get_var(name))),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+impl Problem {
+    /// Serialize a `Problem` in the ARI format, declaring each [`Operator`] of its [`TRS`].
+    /// Each clause of a [`Rule`] becomes its own ARI rule, and weights are dropped.
+    ///
+    /// See [`parse_ari`] for more information.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`TRS`]: struct.TRS.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`parse_ari`]: fn.parse_ari.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Problem, Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let trs = parse_trs(&mut sig, "F(x_) = A <= x_ = B;").expect("parse of F(x_) = A <= x_ = B;");
+    /// let problem = Problem {
+    ///     trs,
+    ///     metadata: vec![("origin".to_string(), "COPS #1".to_string())],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     problem.to_ari(&sig),
+    ///     "; @origin COPS #1\n(format CTRS oriented)\n(fun F 1)\n(fun A 0)\n(fun B 0)\n(rule (F x) A (= x B))\n"
+    /// );
+    /// ```
+    pub fn to_ari(&self, sig: &Signature) -> String {
+        let mut ari = String::new();
+        for (key, value) in &self.metadata {
+            for line in value.lines() {
+                ari.push_str(&format!("; @{} {}\n", key, line));
+            }
+        }
+        if self.is_conditional() {
+            ari.push_str(&format!("(format CTRS {})\n", self.condition_type()));
+        } else {
+            ari.push_str("(format TRS)\n");
+        }
+        for op in self.trs.operators() {
+            ari.push_str(&format!(
+                "(fun {} {})\n",
+                symbol(&operator_name(sig, &op)),
+                op.arity()
+            ));
+        }
+        for rule in &self.trs.rules {
+            let conditions = rule
+                .conditions
+                .iter()
+                .map(|(c, d)| format!(" (= {} {})", ari_display(sig, c), ari_display(sig, d)))
+                .join("");
+            for rhs in &rule.rhs {
+                ari.push_str(&format!(
+                    "(rule {} {}{})\n",
+                    ari_display(sig, &rule.lhs),
+                    ari_display(sig, rhs),
+                    conditions
+                ));
+            }
+        }
+        ari
+    }
+}
+
+// Serialize a Term with ARI syntax.
+fn ari_display(sig: &Signature, term: &Term) -> String {
+    match *term {
+        Term::Variable(ref v) => symbol(&variable_name(sig, v)),
+        Term::Application { ref op, ref args } if args.is_empty() => {
+            symbol(&operator_name(sig, op))
+        }
+        Term::Application { ref op, ref args } => format!(
+            "({} {})",
+            symbol(&operator_name(sig, op)),
+            args.iter().map(|arg| ari_display(sig, arg)).join(" ")
+        ),
+    }
+}
@@ -0,0 +1,350 @@
+use json::{create_atoms, Atoms, BundleAtoms};
+use std::collections::HashMap;
+use {ParseError, Rule, Signature, Term, TRS};
+
+// The version of the encoding, written as its first byte.
+const VERSION: u8 = 1;
+
+// The kinds of encoded values, written as the second byte.
+const TERM: u8 = 0;
+const RULE: u8 = 1;
+const TRS_KIND: u8 = 2;
+
+/// Decode a [`Term`] encoded by [`Term::to_bytes`], adding its [`Operator`]s to `sig` where
+/// `sig` lacks them and creating fresh [`Variable`]s.
+///
+/// [`Term`]: enum.Term.html
+/// [`Term::to_bytes`]: enum.Term.html#method.to_bytes
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, decode_term, parse_term};
+/// let mut sig = Signature::default();
+/// let t = parse_term(&mut sig, "F(A x_ x_)").expect("parse of F(A x_ x_)");
+///
+/// let mut sig2 = Signature::default();
+/// let t2 = decode_term(&mut sig2, &t.to_bytes(&sig)).expect("decoded term");
+///
+/// assert_eq!(t2.display(&sig2), "F(A x_ x_)");
+/// ```
+pub fn decode_term(sig: &mut Signature, bytes: &[u8]) -> Result<Term, ParseError> {
+    let mut decoder = Decoder::new(bytes, TERM)?;
+    let atoms = decoder.atoms(sig)?;
+    let term = decoder.term(&atoms)?;
+    decoder.finish(term)
+}
+
+/// Decode a [`Rule`] encoded by [`Rule::to_bytes`], like [`decode_term`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Rule::to_bytes`]: struct.Rule.html#method.to_bytes
+/// [`decode_term`]: fn.decode_term.html
+pub fn decode_rule(sig: &mut Signature, bytes: &[u8]) -> Result<Rule, ParseError> {
+    let mut decoder = Decoder::new(bytes, RULE)?;
+    let atoms = decoder.atoms(sig)?;
+    let rule = decoder.rule(&atoms)?;
+    decoder.finish(rule)
+}
+
+/// Decode a [`TRS`] encoded by [`TRS::to_bytes`], like [`decode_term`].
+///
+/// [`TRS`]: struct.TRS.html
+/// [`TRS::to_bytes`]: struct.TRS.html#method.to_bytes
+/// [`decode_term`]: fn.decode_term.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, decode_trs, parse_trs};
+/// let mut sig = Signature::default();
+/// let t = parse_trs(&mut sig, "PLUS(ZERO y_) = y_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));")
+///     .expect("parse of TRS");
+///
+/// let bytes = t.to_bytes(&sig);
+/// let mut sig2 = Signature::default();
+/// let t2 = decode_trs(&mut sig2, &bytes).expect("decoded TRS");
+///
+/// assert_eq!(t2.display(&sig2), t.display(&sig));
+/// assert!(bytes.len() < t.display(&sig).len());
+/// ```
+pub fn decode_trs(sig: &mut Signature, bytes: &[u8]) -> Result<TRS, ParseError> {
+    let mut decoder = Decoder::new(bytes, TRS_KIND)?;
+    let atoms = decoder.atoms(sig)?;
+    let n = decoder.varint()?;
+    let rules = (0..n)
+        .map(|_| decoder.rule(&atoms))
+        .collect::<Result<Vec<_>, _>>()?;
+    decoder.finish(TRS::new(rules))
+}
+
+impl Term {
+    /// Encode a `Term` in a compact, versioned binary format which, like [`Term::to_json`],
+    /// carries the names and arities of its [`Operator`]s and the names of its [`Variable`]s.
+    /// Names are stored once in a string table and atoms are referenced by varint indices.
+    ///
+    /// See [`decode_term`] for more information.
+    ///
+    /// [`Term::to_json`]: enum.Term.html#method.to_json
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`decode_term`]: fn.decode_term.html
+    pub fn to_bytes(&self, sig: &Signature) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.term(self);
+        encoder.finish(sig, TERM)
+    }
+}
+
+impl Rule {
+    /// Encode a `Rule` in a compact, versioned binary format, like [`Term::to_bytes`].
+    ///
+    /// See [`decode_rule`] for more information.
+    ///
+    /// [`Term::to_bytes`]: enum.Term.html#method.to_bytes
+    /// [`decode_rule`]: fn.decode_rule.html
+    pub fn to_bytes(&self, sig: &Signature) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.rule(self);
+        encoder.finish(sig, RULE)
+    }
+}
+
+impl TRS {
+    /// Encode the [`Rule`]s of a `TRS` in a compact, versioned binary format, like
+    /// [`Term::to_bytes`].
+    ///
+    /// See [`decode_trs`] for more information.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Term::to_bytes`]: enum.Term.html#method.to_bytes
+    /// [`decode_trs`]: fn.decode_trs.html
+    pub fn to_bytes(&self, sig: &Signature) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        push_varint(&mut encoder.body, self.rules.len());
+        for rule in &self.rules {
+            encoder.rule(rule);
+        }
+        encoder.finish(sig, TRS_KIND)
+    }
+}
+
+// Append `n` as an unsigned LEB128 varint.
+fn push_varint(bytes: &mut Vec<u8>, mut n: usize) {
+    while n >= 0x80 {
+        bytes.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+// Encode the body of a value, collecting its atoms for the header.
+#[derive(Default)]
+struct Encoder {
+    atoms: Atoms,
+    body: Vec<u8>,
+}
+impl Encoder {
+    // Each operator is tagged by an even number and each variable by an odd one.
+    fn term(&mut self, term: &Term) {
+        match *term {
+            Term::Variable(ref v) => {
+                let i = self.atoms.variable(v);
+                push_varint(&mut self.body, 2 * i + 1);
+            }
+            Term::Application { ref op, ref args } => {
+                let i = self.atoms.operator(op);
+                push_varint(&mut self.body, 2 * i);
+                for arg in args {
+                    self.term(arg);
+                }
+            }
+        }
+    }
+    fn rule(&mut self, rule: &Rule) {
+        self.term(&rule.lhs);
+        push_varint(&mut self.body, rule.rhs.len());
+        for rhs in &rule.rhs {
+            self.term(rhs);
+        }
+        push_varint(&mut self.body, rule.conditions.len());
+        for (c, d) in &rule.conditions {
+            self.term(c);
+            self.term(d);
+        }
+        match rule.weights {
+            Some(ref weights) => {
+                self.body.push(1);
+                for w in weights {
+                    self.body.extend_from_slice(&w.to_bits().to_le_bytes());
+                }
+            }
+            None => self.body.push(0),
+        }
+    }
+    // Write the header, with its string table, operators, and variables, followed by the body.
+    fn finish(self, sig: &Signature, kind: u8) -> Vec<u8> {
+        let mut strings: Vec<String> = vec![];
+        let mut string_index = HashMap::new();
+        let mut name_index = |name: Option<String>| match name {
+            Some(name) => {
+                let n = strings.len();
+                1 + *string_index.entry(name.clone()).or_insert_with(|| {
+                    strings.push(name);
+                    n
+                })
+            }
+            None => 0,
+        };
+        let operators: Vec<(usize, usize)> = self
+            .atoms
+            .operators
+            .iter()
+            .map(|op| (name_index(op.name(sig)), op.arity() as usize))
+            .collect();
+        let variables: Vec<usize> = self
+            .atoms
+            .variables
+            .iter()
+            .map(|v| name_index(v.name(sig)))
+            .collect();
+        let mut bytes = vec![VERSION, kind];
+        push_varint(&mut bytes, strings.len());
+        for s in &strings {
+            push_varint(&mut bytes, s.len());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+        push_varint(&mut bytes, operators.len());
+        for (name, arity) in operators {
+            push_varint(&mut bytes, name);
+            push_varint(&mut bytes, arity);
+        }
+        push_varint(&mut bytes, variables.len());
+        for name in variables {
+            push_varint(&mut bytes, name);
+        }
+        bytes.extend(self.body);
+        bytes
+    }
+}
+
+// Read an encoded value.
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+impl<'a> Decoder<'a> {
+    // Check the version and kind of the encoding.
+    fn new(bytes: &'a [u8], kind: u8) -> Result<Decoder<'a>, ParseError> {
+        let mut decoder = Decoder { bytes, pos: 0 };
+        if decoder.byte()? != VERSION || decoder.byte()? != kind {
+            return Err(ParseError::ParseFailed);
+        }
+        Ok(decoder)
+    }
+    fn byte(&mut self) -> Result<u8, ParseError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or(ParseError::ParseIncomplete)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.bytes.len() - self.pos < n {
+            return Err(ParseError::ParseIncomplete);
+        }
+        self.pos += n;
+        Ok(&self.bytes[self.pos - n..self.pos])
+    }
+    fn varint(&mut self) -> Result<usize, ParseError> {
+        let mut n: usize = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as usize)
+                .checked_shl(shift)
+                .ok_or(ParseError::ParseFailed)?;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(ParseError::ParseFailed)
+    }
+    // Read the header and create its atoms in `sig`.
+    fn atoms(&mut self, sig: &mut Signature) -> Result<BundleAtoms, ParseError> {
+        let n = self.varint()?;
+        let strings = (0..n)
+            .map(|_| {
+                let len = self.varint()?;
+                String::from_utf8(self.take(len)?.to_vec()).map_err(|_| ParseError::ParseFailed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let name = |i: usize| match i {
+            0 => Ok(None),
+            i => strings
+                .get(i - 1)
+                .cloned()
+                .map(Some)
+                .ok_or(ParseError::ParseFailed),
+        };
+        let n = self.varint()?;
+        let operators = (0..n)
+            .map(|_| Ok((name(self.varint()?)?, self.varint()? as u32)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let n = self.varint()?;
+        let variables = (0..n)
+            .map(|_| name(self.varint()?))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(create_atoms(sig, operators, variables))
+    }
+    fn term(&mut self, atoms: &BundleAtoms) -> Result<Term, ParseError> {
+        let tag = self.varint()?;
+        if tag % 2 == 1 {
+            let v = atoms.1.get(tag / 2).ok_or(ParseError::ParseFailed)?;
+            return Ok(Term::Variable(*v));
+        }
+        let op = *atoms.0.get(tag / 2).ok_or(ParseError::ParseFailed)?;
+        let args = (0..op.arity())
+            .map(|_| self.term(atoms))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Term::Application { op, args })
+    }
+    fn rule(&mut self, atoms: &BundleAtoms) -> Result<Rule, ParseError> {
+        let lhs = self.term(atoms)?;
+        let n = self.varint()?;
+        let rhs = (0..n)
+            .map(|_| self.term(atoms))
+            .collect::<Result<Vec<_>, _>>()?;
+        let n = self.varint()?;
+        let conditions = (0..n)
+            .map(|_| Ok((self.term(atoms)?, self.term(atoms)?)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut rule =
+            Rule::new_conditional(lhs, rhs, conditions).ok_or(ParseError::ParseFailed)?;
+        match self.byte()? {
+            0 => (),
+            1 => {
+                let weights = (0..rule.rhs.len())
+                    .map(|_| {
+                        let mut bits = [0; 8];
+                        bits.copy_from_slice(self.take(8)?);
+                        Ok(f64::from_bits(u64::from_le_bytes(bits)))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                rule.weights = Some(weights);
+            }
+            _ => return Err(ParseError::ParseFailed),
+        }
+        Ok(rule)
+    }
+    // Check that every byte was read.
+    fn finish<T>(self, value: T) -> Result<T, ParseError> {
+        if self.pos == self.bytes.len() {
+            Ok(value)
+        } else {
+            Err(ParseError::ParseIncomplete)
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! [Combinatory logic] with the `S`, `K`, and `I` combinators in applicative form.
+//!
+//! [`install`] adds the combinators and the binary application operator `.` to a [`Signature`]
+//! along with a [`TRS`] of their standard rules:
+//!
+//! ```text
+//! S x_ y_ z_ = x_ z_ (y_ z_);
+//! K x_ y_ = x_;
+//! I x_ = x_;
+//! ```
+//!
+//! [`Combinators::reduce_cl`] normalizes a [`Term`] with these rules directly, without the
+//! matching and substitution of the general rewrite engine.
+//!
+//! [Combinatory logic]: https://en.wikipedia.org/wiki/Combinatory_logic
+//! [`install`]: fn.install.html
+//! [`Combinators::reduce_cl`]: struct.Combinators.html#method.reduce_cl
+//! [`Signature`]: ../struct.Signature.html
+//! [`TRS`]: ../struct.TRS.html
+//! [`Term`]: ../enum.Term.html
+//!
+//! # Examples
+//!
+//! ```
+//! use term_rewriting::{combinators::install, parse_term, Normalization, Signature};
+//!
+//! let mut sig = Signature::default();
+//! let (cl, _trs) = install(&mut sig);
+//!
+//! let term = parse_term(&mut sig, "S K K x_").expect("parse of S K K x_");
+//!
+//! match cl.reduce_cl(&term, 100) {
+//!     Normalization::Normalized(t) => assert_eq!(t.display(&sig), "x_"),
+//!     n => panic!("unexpected {:?}", n),
+//! }
+//! ```
+
+use parser::Parser;
+use {parse_trs, Normalization, Operator, Signature, Term, TRS};
+
+/// The [`Operator`]s of combinatory logic in some [`Signature`].
+///
+/// [`Operator`]: ../struct.Operator.html
+/// [`Signature`]: ../struct.Signature.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combinators {
+    /// `S x y z = x z (y z)`
+    pub s: Operator,
+    /// `K x y = x`
+    pub k: Operator,
+    /// `I x = x`
+    pub i: Operator,
+    /// The binary application operator, `.`.
+    pub app: Operator,
+}
+
+/// Add the `S`, `K`, `I`, and `.` [`Operator`]s to `sig`, reusing any it already has, and
+/// return them along with a [`TRS`] of their rules.
+///
+/// [`Operator`]: ../struct.Operator.html
+/// [`TRS`]: ../struct.TRS.html
+///
+/// # Examples
+///
+/// ```
+/// use term_rewriting::{combinators::install, Signature};
+///
+/// let mut sig = Signature::default();
+/// let (cl, trs) = install(&mut sig);
+///
+/// assert_eq!(cl.s.display(&sig), "S");
+/// assert_eq!(cl.app.arity(), 2);
+/// assert_eq!(trs.pretty(&sig), "S x_ y_ z_ = x_ z_ (y_ z_);\nK x_ y_ = x_;\nI x_ = x_;");
+/// ```
+pub fn install(sig: &mut Signature) -> (Combinators, TRS) {
+    let trs = parse_trs(sig, "S x_ y_ z_ = x_ z_ (y_ z_); K x_ y_ = x_; I x_ = x_;")
+        .expect("parse of combinator rules");
+    let mut parser = Parser::new(sig);
+    let combinators = Combinators {
+        s: parser.get_op("S", 0),
+        k: parser.get_op("K", 0),
+        i: parser.get_op("I", 0),
+        app: parser.get_op(".", 2),
+    };
+    (combinators, trs)
+}
+
+impl Combinators {
+    /// Apply `f` to `x`.
+    pub fn apply(&self, f: Term, x: Term) -> Term {
+        Term::Application {
+            op: self.app,
+            args: vec![f, x],
+        }
+    }
+    /// Normalize `term` in normal order, contracting the leftmost-outermost redex first as
+    /// [`Strategy::Normal`] does, but taking at most `limit` steps. Returns
+    /// [`Normalization::Normalized`] with the normal form, or [`Normalization::StepLimit`]
+    /// with the last `Term` reached.
+    ///
+    /// [`Strategy::Normal`]: ../enum.Strategy.html#variant.Normal
+    /// [`Normalization::Normalized`]: ../enum.Normalization.html#variant.Normalized
+    /// [`Normalization::StepLimit`]: ../enum.Normalization.html#variant.StepLimit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{combinators::install, parse_term, Normalization, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let (cl, _trs) = install(&mut sig);
+    ///
+    /// let term = parse_term(&mut sig, "K I (S I I (S I I)) F((I A))").expect("parse of K I (S I I (S I I)) F((I A))");
+    ///
+    /// match cl.reduce_cl(&term, 10) {
+    ///     Normalization::Normalized(t) => assert_eq!(t.display(&sig), "F(A)"),
+    ///     n => panic!("unexpected {:?}", n),
+    /// }
+    ///
+    /// let omega = parse_term(&mut sig, "S I I (S I I)").expect("parse of S I I (S I I)");
+    ///
+    /// match cl.reduce_cl(&omega, 10) {
+    ///     Normalization::StepLimit(_) => (),
+    ///     n => panic!("unexpected {:?}", n),
+    /// }
+    /// ```
+    pub fn reduce_cl(&self, term: &Term, limit: usize) -> Normalization {
+        let mut reducer = Reducer {
+            cl: self,
+            steps: 0,
+            limit,
+            limited: false,
+        };
+        let term = reducer.reduce(term.clone());
+        if reducer.limited {
+            Normalization::StepLimit(term)
+        } else {
+            Normalization::Normalized(term)
+        }
+    }
+    // The combinator at the head of `term`, if any.
+    fn combinator(&self, term: &Term) -> Option<Combinator> {
+        match *term {
+            Term::Application { ref op, .. } if *op == self.s => Some(Combinator::S),
+            Term::Application { ref op, .. } if *op == self.k => Some(Combinator::K),
+            Term::Application { ref op, .. } if *op == self.i => Some(Combinator::I),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Combinator {
+    S,
+    K,
+    I,
+}
+impl Combinator {
+    // The number of arguments the combinator needs to be a redex.
+    fn arity(self) -> usize {
+        match self {
+            Combinator::S => 3,
+            Combinator::K => 2,
+            Combinator::I => 1,
+        }
+    }
+}
+
+struct Reducer<'a> {
+    cl: &'a Combinators,
+    steps: usize,
+    limit: usize,
+    // Did reduction stop at the step limit?
+    limited: bool,
+}
+impl<'a> Reducer<'a> {
+    // Move the arguments of the applications at the root of `head` onto `spine`, first
+    // argument on top, and return the `Term` being applied.
+    fn unwind(&self, mut head: Term, spine: &mut Vec<Term>) -> Term {
+        loop {
+            match head {
+                Term::Application { op, mut args } if op == self.cl.app => {
+                    spine.push(args.pop().expect("argument"));
+                    head = args.pop().expect("function");
+                }
+                head => return head,
+            }
+        }
+    }
+    // Normalize `head` in normal order, within the step limit.
+    fn reduce(&mut self, head: Term) -> Term {
+        let mut spine = vec![];
+        let mut head = self.unwind(head, &mut spine);
+        while let Some(combinator) = self.cl.combinator(&head) {
+            if spine.len() < combinator.arity() {
+                break;
+            }
+            if self.steps >= self.limit {
+                self.limited = true;
+                break;
+            }
+            self.steps += 1;
+            let x = spine.pop().expect("first argument");
+            match combinator {
+                Combinator::S => {
+                    let y = spine.pop().expect("second argument");
+                    let z = spine.pop().expect("third argument");
+                    spine.push(self.cl.apply(y, z.clone()));
+                    spine.push(z);
+                }
+                Combinator::K => {
+                    spine.pop();
+                }
+                Combinator::I => (),
+            }
+            head = self.unwind(x, &mut spine);
+        }
+        // `head` is now stuck, so normalize its arguments and those of the spine, left to right.
+        let head = match head {
+            Term::Application { op, args } => Term::Application {
+                op,
+                args: args.into_iter().map(|arg| self.reduce(arg)).collect(),
+            },
+            head => head,
+        };
+        spine.into_iter().rev().fold(head, |f, x| {
+            let x = self.reduce(x);
+            self.cl.apply(f, x)
+        })
+    }
+}
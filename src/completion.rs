@@ -0,0 +1,259 @@
+//! [Knuth-Bendix completion] of equations into convergent [`TRS`]s.
+//!
+//! Completion orients equations into [`Rule`]s using a [`ReductionOrdering`] and adds the
+//! [`CriticalPair`]s of those [`Rule`]s as new equations until every [`CriticalPair`] is
+//! joinable. When it succeeds, the result is a terminating and confluent [`TRS`] whose
+//! equational theory is that of the input equations. It fails if an equation cannot be oriented
+//! or if the step limit runs out first.
+//!
+//! [Knuth-Bendix completion]: https://en.wikipedia.org/wiki/Knuth%E2%80%93Bendix_completion_algorithm
+//! [`TRS`]: ../struct.TRS.html
+//! [`Rule`]: ../struct.Rule.html
+//! [`CriticalPair`]: ../struct.CriticalPair.html
+//! [`ReductionOrdering`]: ../orderings/trait.ReductionOrdering.html
+//!
+//! # Examples
+//!
+//! Completing the axioms of a group:
+//!
+//! ```
+//! use term_rewriting::{completion::complete_trs, orderings::Lpo, parse_term, parse_trs, Signature, Strategy};
+//!
+//! let mut sig = Signature::default();
+//! let inv = sig.new_op(1, Some("I".to_string()));
+//! let times = sig.new_op(2, Some("TIMES".to_string()));
+//! let e = sig.new_op(0, Some("E".to_string()));
+//! let axioms = parse_trs(&mut sig,
+//! "TIMES(E x_) = x_;
+//! TIMES(I(x_) x_) = E;
+//! TIMES(TIMES(x_ y_) z_) = TIMES(x_ TIMES(y_ z_));").expect("parse of group axioms");
+//!
+//! let lpo = Lpo::new(vec![inv, times, e]);
+//! let group = complete_trs(&mut sig, &axioms, &lpo, 100).expect("completion of group axioms");
+//!
+//! assert_eq!(group.len(), 10);
+//!
+//! let term = parse_term(&mut sig, "I(TIMES(A I(A)))").expect("parse of I(TIMES(A I(A)))");
+//! let mut rewritten = term;
+//! while let Some(mut ts) = group.rewrite(&rewritten, Strategy::Normal) {
+//!     rewritten = ts.remove(0);
+//! }
+//!
+//! assert_eq!(rewritten.display(&sig), "E");
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use orderings::ReductionOrdering;
+use {CriticalPair, Rule, Signature, Strategy, Term, TRS};
+
+/// Complete a set of equations into a convergent [`TRS`], orienting each equation with
+/// `ordering`.
+///
+/// Each step orients one equation into a new [`Rule`]; completion gives up rather than take more
+/// than `max_steps` steps. [`Rule`]s are renamed apart in `sig` to find their
+/// [`CriticalPair`]s.
+///
+/// [`TRS`]: ../struct.TRS.html
+/// [`Rule`]: ../struct.Rule.html
+/// [`CriticalPair`]: ../struct.CriticalPair.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{completion::{complete, CompletionError}, orderings::Lpo, parse_rule, Signature};
+/// let mut sig = Signature::default();
+/// let f = sig.new_op(1, Some("F".to_string()));
+/// let a = sig.new_op(0, Some("A".to_string()));
+/// let b = sig.new_op(0, Some("B".to_string()));
+///
+/// let r0 = parse_rule(&mut sig, "F(F(x_)) = x_").expect("parse of F(F(x_)) = x_");
+/// let r1 = parse_rule(&mut sig, "F(A) = B").expect("parse of F(A) = B");
+/// let equations = vec![
+///     (r0.lhs.clone(), r0.rhs[0].clone()),
+///     (r1.lhs.clone(), r1.rhs[0].clone()),
+/// ];
+///
+/// let trs = complete(&mut sig, &equations, &Lpo::new(vec![f, a, b]), 100)
+///     .expect("completion");
+///
+/// assert_eq!(trs.display(&sig), "F(F(x_)) = x_;\nF(A) = B;\nF(B) = A;");
+///
+/// // A and B can be oriented neither way without a precedence between them.
+/// match complete(&mut sig, &equations, &Lpo::new(vec![f]), 100) {
+///     Err(CompletionError::Unorientable { lhs, rhs, .. }) => {
+///         assert_eq!((lhs.display(&sig), rhs.display(&sig)), ("F(A)".to_string(), "B".to_string()));
+///     }
+///     _ => panic!("expected an unorientable equation"),
+/// }
+/// ```
+pub fn complete<O: ReductionOrdering>(
+    sig: &mut Signature,
+    equations: &[(Term, Term)],
+    ordering: &O,
+    max_steps: usize,
+) -> Result<TRS, CompletionError> {
+    let mut equations: VecDeque<(Term, Term)> = equations.iter().cloned().collect();
+    let mut rules: Vec<Rule> = vec![];
+    let mut steps = 0;
+    while let Some((s, t)) = equations.pop_front() {
+        // simplify the equation and delete it if it has become trivial.
+        let trs = TRS::new(rules);
+        let s = normal_form(&trs, &s);
+        let t = normal_form(&trs, &t);
+        rules = trs.rules;
+        if s == t {
+            continue;
+        }
+
+        // orient the equation.
+        if steps == max_steps {
+            equations.push_front((s, t));
+            return Err(CompletionError::StepLimit {
+                rules: Box::new(TRS::new(rules)),
+                equations: equations.into_iter().collect(),
+            });
+        }
+        steps += 1;
+        let (lhs, rhs) = if ordering.gt(&s, &t) {
+            (s, t)
+        } else if ordering.gt(&t, &s) {
+            (t, s)
+        } else {
+            let rules = Box::new(TRS::new(rules));
+            return Err(CompletionError::Unorientable {
+                lhs: s,
+                rhs: t,
+                rules,
+            });
+        };
+        let rule = match Rule::new(lhs.clone(), vec![rhs.clone()]) {
+            Some(rule) => rule,
+            None => {
+                let rules = Box::new(TRS::new(rules));
+                return Err(CompletionError::Unorientable { lhs, rhs, rules });
+            }
+        };
+
+        // rules whose LHS the new rule rewrites become equations again.
+        let new_rule = TRS::new(vec![rule.clone()]);
+        let mut kept = vec![];
+        for r in rules {
+            if new_rule.rewrite(&r.lhs, Strategy::Normal).is_some() {
+                equations.push_back((r.lhs, r.rhs[0].clone()));
+            } else {
+                kept.push(r);
+            }
+        }
+        kept.push(rule);
+
+        // the remaining RHSs are kept in normal form.
+        let trs = TRS::new(kept);
+        rules = trs
+            .rules
+            .iter()
+            .map(|r| Rule {
+                lhs: r.lhs.clone(),
+                rhs: vec![normal_form(&trs, &r.rhs[0])],
+                conditions: vec![],
+                weights: None,
+            })
+            .collect();
+
+        // the new rule's critical pairs become equations.
+        let n = rules.len() - 1;
+        for (i, r) in rules.iter().enumerate() {
+            let mut pairs = CriticalPair::overlaps(sig, r, i, &rules[n], n);
+            if i != n {
+                pairs.append(&mut CriticalPair::overlaps(sig, &rules[n], n, r, i));
+            }
+            equations.extend(pairs.into_iter().map(|cp| (cp.left, cp.right)));
+        }
+    }
+    Ok(TRS::new(rules))
+}
+
+/// Complete the clauses of a [`TRS`], treating each as an equation. See [`complete`] for more
+/// information.
+///
+/// [`TRS`]: ../struct.TRS.html
+/// [`complete`]: fn.complete.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{completion::complete_trs, orderings::Lpo, parse_trs, Signature};
+/// let mut sig = Signature::default();
+/// let f = sig.new_op(1, Some("F".to_string()));
+/// let g = sig.new_op(1, Some("G".to_string()));
+/// let trs = parse_trs(&mut sig, "F(G(x_)) = G(x_); G(F(x_)) = F(x_);")
+///     .expect("parse of F(G(x_)) = G(x_); G(F(x_)) = F(x_);");
+///
+/// let complete = complete_trs(&mut sig, &trs, &Lpo::new(vec![f, g]), 100).expect("completion");
+///
+/// assert_eq!(complete.len(), 4);
+/// ```
+pub fn complete_trs<O: ReductionOrdering>(
+    sig: &mut Signature,
+    trs: &TRS,
+    ordering: &O,
+    max_steps: usize,
+) -> Result<TRS, CompletionError> {
+    let equations: Vec<_> = trs
+        .rules
+        .iter()
+        .flat_map(|r| r.rhs.iter().map(move |rhs| (r.lhs.clone(), rhs.clone())))
+        .collect();
+    complete(sig, &equations, ordering, max_steps)
+}
+
+// Rewrite `term` until it reaches a normal form. `trs` must be terminating.
+fn normal_form(trs: &TRS, term: &Term) -> Term {
+    let mut term = term.clone();
+    while let Some(next) = trs
+        .rewrite(&term, Strategy::Normal)
+        .and_then(|ts| ts.into_iter().next())
+    {
+        term = next;
+    }
+    term
+}
+
+#[derive(Debug, Clone)]
+/// The error type for [`complete`] and [`complete_trs`]. Each variant reports the [`Rule`]s
+/// oriented before completion stopped.
+///
+/// [`complete`]: fn.complete.html
+/// [`complete_trs`]: fn.complete_trs.html
+/// [`Rule`]: ../struct.Rule.html
+pub enum CompletionError {
+    /// Returned when an equation, once simplified, can be oriented in neither direction.
+    Unorientable {
+        lhs: Term,
+        rhs: Term,
+        rules: Box<TRS>,
+    },
+    /// Returned when the step limit is reached with equations left to process.
+    StepLimit {
+        rules: Box<TRS>,
+        equations: Vec<(Term, Term)>,
+    },
+}
+impl fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CompletionError::Unorientable { .. } => write!(f, "cannot orient equation"),
+            CompletionError::StepLimit { ref equations, .. } => write!(
+                f,
+                "step limit reached with {} equations remaining",
+                equations.len()
+            ),
+        }
+    }
+}
+impl ::std::error::Error for CompletionError {
+    fn description(&self) -> &'static str {
+        "completion error"
+    }
+}
@@ -0,0 +1,518 @@
+use parser::Parser;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+use {Operator, ParseError, Rule, Signature, Term, Variable, TRS};
+
+use itertools::Itertools;
+
+/// Parse a JSON bundle written by [`Term::to_json`], adding its [`Operator`]s to `sig` where
+/// `sig` lacks them and creating fresh [`Variable`]s.
+///
+/// [`Term::to_json`]: enum.Term.html#method.to_json
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term, parse_term_json};
+/// let mut sig = Signature::default();
+/// let t = parse_term(&mut sig, "F(A x_)").expect("parse of F(A x_)");
+///
+/// let json = t.to_json(&sig);
+///
+/// let mut sig2 = Signature::default();
+/// let t2 = parse_term_json(&mut sig2, &json).expect("parse of JSON bundle");
+///
+/// assert_eq!(t2.display(&sig2), "F(A x_)");
+/// ```
+pub fn parse_term_json(sig: &mut Signature, input: &str) -> Result<Term, ParseError> {
+    let (bundle, atoms) = read_bundle(sig, input)?;
+    term_from_json(get(&bundle, "term")?, &atoms)
+}
+
+/// Parse a JSON bundle written by [`Rule::to_json`], like [`parse_term_json`].
+///
+/// [`Rule::to_json`]: struct.Rule.html#method.to_json
+/// [`parse_term_json`]: fn.parse_term_json.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_rule, parse_rule_json};
+/// let mut sig = Signature::default();
+/// let r = parse_rule(&mut sig, "F(x_) = A | G(x_) <= x_ = B").expect("parse of F(x_) = A | G(x_) <= x_ = B");
+///
+/// let mut sig2 = Signature::default();
+/// let r2 = parse_rule_json(&mut sig2, &r.to_json(&sig)).expect("parse of JSON bundle");
+///
+/// assert_eq!(r2.display(&sig), "F(x_) = A | G(x_) <= x_ = B");
+/// ```
+pub fn parse_rule_json(sig: &mut Signature, input: &str) -> Result<Rule, ParseError> {
+    let (bundle, atoms) = read_bundle(sig, input)?;
+    rule_from_json(get(&bundle, "rule")?, &atoms)
+}
+
+/// Parse a JSON bundle written by [`TRS::to_json`], like [`parse_term_json`].
+///
+/// [`TRS::to_json`]: struct.TRS.html#method.to_json
+/// [`parse_term_json`]: fn.parse_term_json.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs, parse_trs_json};
+/// let mut sig = Signature::default();
+/// let t = parse_trs(&mut sig, "PLUS(ZERO y_) = y_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));")
+///     .expect("parse of TRS");
+///
+/// let mut sig2 = Signature::default();
+/// let t2 = parse_trs_json(&mut sig2, &t.to_json(&sig)).expect("parse of JSON bundle");
+///
+/// assert_eq!(t2.display(&sig2), t.display(&sig));
+/// ```
+pub fn parse_trs_json(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
+    let (bundle, atoms) = read_bundle(sig, input)?;
+    match *get(&bundle, "trs")? {
+        Json::Array(ref rules) => rules
+            .iter()
+            .map(|rule| rule_from_json(rule, &atoms))
+            .collect::<Result<Vec<_>, _>>()
+            .map(TRS::new),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+impl Term {
+    /// Serialize a `Term` as a self-contained JSON bundle, which lists the name and arity of
+    /// each [`Operator`] and the name of each [`Variable`] it uses, so that it can be loaded
+    /// into any [`Signature`] with [`parse_term_json`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`parse_term_json`]: fn.parse_term_json.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    /// let t = parse_term(&mut sig, "F(A x_)").expect("parse of F(A x_)");
+    ///
+    /// assert_eq!(
+    ///     t.to_json(&sig),
+    ///     r#"{"operators":[["F",2],["A",0]],"variables":["x"],"term":{"op":0,"args":[{"op":1,"args":[]},{"var":0}]}}"#
+    /// );
+    /// ```
+    pub fn to_json(&self, sig: &Signature) -> String {
+        let mut atoms = Atoms::default();
+        let term = atoms.term(self);
+        atoms.bundle(sig, "term", &term)
+    }
+}
+
+impl Rule {
+    /// Serialize a `Rule` as a self-contained JSON bundle, like [`Term::to_json`].
+    ///
+    /// [`Term::to_json`]: enum.Term.html#method.to_json
+    pub fn to_json(&self, sig: &Signature) -> String {
+        let mut atoms = Atoms::default();
+        let rule = atoms.rule(self);
+        atoms.bundle(sig, "rule", &rule)
+    }
+}
+
+impl TRS {
+    /// Serialize the [`Rule`]s of a `TRS` as a self-contained JSON bundle, like
+    /// [`Term::to_json`]. A [`Variable`] shared by several [`Rule`]s stays shared when loaded.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Term::to_json`]: enum.Term.html#method.to_json
+    /// [`Variable`]: struct.Variable.html
+    pub fn to_json(&self, sig: &Signature) -> String {
+        let mut atoms = Atoms::default();
+        let rules = self.rules.iter().map(|rule| atoms.rule(rule)).join(",");
+        atoms.bundle(sig, "trs", &format!("[{}]", rules))
+    }
+}
+
+// The operators and variables of a bundle, indexed in order of first occurrence.
+#[derive(Default)]
+pub(crate) struct Atoms {
+    pub(crate) operators: Vec<Operator>,
+    pub(crate) variables: Vec<Variable>,
+    // Map the id of each atom to its index.
+    operator_index: HashMap<usize, usize>,
+    variable_index: HashMap<usize, usize>,
+}
+impl Atoms {
+    // The index of `op`, adding it if necessary.
+    pub(crate) fn operator(&mut self, op: &Operator) -> usize {
+        let operators = &mut self.operators;
+        *self.operator_index.entry(op.id).or_insert_with(|| {
+            operators.push(*op);
+            operators.len() - 1
+        })
+    }
+    // The index of `v`, adding it if necessary.
+    pub(crate) fn variable(&mut self, v: &Variable) -> usize {
+        let variables = &mut self.variables;
+        *self.variable_index.entry(v.id).or_insert_with(|| {
+            variables.push(*v);
+            variables.len() - 1
+        })
+    }
+    fn term(&mut self, term: &Term) -> String {
+        match *term {
+            Term::Variable(ref v) => format!("{{\"var\":{}}}", self.variable(v)),
+            Term::Application { ref op, ref args } => {
+                let i = self.operator(op);
+                let args = args.iter().map(|arg| self.term(arg)).join(",");
+                format!("{{\"op\":{},\"args\":[{}]}}", i, args)
+            }
+        }
+    }
+    fn rule(&mut self, rule: &Rule) -> String {
+        let lhs = self.term(&rule.lhs);
+        let rhs = rule.rhs.iter().map(|t| self.term(t)).join(",");
+        let conditions = rule
+            .conditions
+            .iter()
+            .map(|(c, d)| {
+                let c = self.term(c);
+                format!("[{},{}]", c, self.term(d))
+            })
+            .join(",");
+        let mut json = format!(
+            "{{\"lhs\":{},\"rhs\":[{}],\"conditions\":[{}]",
+            lhs, rhs, conditions
+        );
+        if let Some(ref weights) = rule.weights {
+            json.push_str(&format!(",\"weights\":[{}]", weights.iter().join(",")));
+        }
+        json.push('}');
+        json
+    }
+    fn bundle(&self, sig: &Signature, key: &str, value: &str) -> String {
+        let operators = self
+            .operators
+            .iter()
+            .map(|op| format!("[{},{}]", json_name(op.name(sig)), op.arity()))
+            .join(",");
+        let variables = self
+            .variables
+            .iter()
+            .map(|v| json_name(v.name(sig)))
+            .join(",");
+        format!(
+            "{{\"operators\":[{}],\"variables\":[{}],\"{}\":{}}}",
+            operators, variables, key, value
+        )
+    }
+}
+
+// Serialize an optional name as a JSON string or null.
+fn json_name(name: Option<String>) -> String {
+    match name {
+        Some(name) => {
+            let mut json = String::from("\"");
+            for c in name.chars() {
+                match c {
+                    '"' => json.push_str("\\\""),
+                    '\\' => json.push_str("\\\\"),
+                    c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => json.push(c),
+                }
+            }
+            json.push('"');
+            json
+        }
+        None => String::from("null"),
+    }
+}
+
+// The operators and variables of a bundle, as created in a Signature.
+pub(crate) type BundleAtoms = (Vec<Operator>, Vec<Variable>);
+
+// Read a bundle, reusing or creating its operators and creating its variables in `sig`.
+fn read_bundle(sig: &mut Signature, input: &str) -> Result<(Json, BundleAtoms), ParseError> {
+    let bundle = read_json(input)?;
+    let operators = array(get(&bundle, "operators")?)?
+        .iter()
+        .map(|op| match *op {
+            Json::Array(ref pair) if pair.len() == 2 => {
+                Ok((name(&pair[0])?, index(&pair[1])? as u32))
+            }
+            _ => Err(ParseError::ParseFailed),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let variables = array(get(&bundle, "variables")?)?
+        .iter()
+        .map(name)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((bundle, create_atoms(sig, operators, variables)))
+}
+
+// Create the atoms of a bundle in `sig` from their names and arities, reusing each named
+// operator which `sig` already has.
+pub(crate) fn create_atoms(
+    sig: &mut Signature,
+    operators: Vec<(Option<String>, u32)>,
+    variables: Vec<Option<String>>,
+) -> BundleAtoms {
+    let operators = operators
+        .into_iter()
+        .map(|(name, arity)| match name {
+            Some(name) => Parser::new(sig).get_op(&name, arity),
+            None => sig.new_op(arity, None),
+        })
+        .collect();
+    let variables = variables
+        .into_iter()
+        .map(|name| sig.new_var(name))
+        .collect();
+    (operators, variables)
+}
+
+// Build a Term from JSON, given the atoms of its bundle.
+fn term_from_json(json: &Json, atoms: &BundleAtoms) -> Result<Term, ParseError> {
+    if let Ok(var) = get(json, "var") {
+        let v = atoms.1.get(index(var)?).ok_or(ParseError::ParseFailed)?;
+        return Ok(Term::Variable(*v));
+    }
+    let op = *atoms
+        .0
+        .get(index(get(json, "op")?)?)
+        .ok_or(ParseError::ParseFailed)?;
+    let args = array(get(json, "args")?)?
+        .iter()
+        .map(|arg| term_from_json(arg, atoms))
+        .collect::<Result<Vec<_>, _>>()?;
+    if args.len() == op.arity() as usize {
+        Ok(Term::Application { op, args })
+    } else {
+        Err(ParseError::ParseFailed)
+    }
+}
+
+// Build a Rule from JSON, given the atoms of its bundle.
+fn rule_from_json(json: &Json, atoms: &BundleAtoms) -> Result<Rule, ParseError> {
+    let lhs = term_from_json(get(json, "lhs")?, atoms)?;
+    let rhs = array(get(json, "rhs")?)?
+        .iter()
+        .map(|t| term_from_json(t, atoms))
+        .collect::<Result<Vec<_>, _>>()?;
+    let conditions = array(get(json, "conditions")?)?
+        .iter()
+        .map(|condition| match *condition {
+            Json::Array(ref pair) if pair.len() == 2 => Ok((
+                term_from_json(&pair[0], atoms)?,
+                term_from_json(&pair[1], atoms)?,
+            )),
+            _ => Err(ParseError::ParseFailed),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut rule = Rule::new_conditional(lhs, rhs, conditions).ok_or(ParseError::ParseFailed)?;
+    if let Ok(weights) = get(json, "weights") {
+        let weights = array(weights)?
+            .iter()
+            .map(|w| match *w {
+                Json::Number(w) => Ok(w),
+                _ => Err(ParseError::ParseFailed),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if weights.len() != rule.rhs.len() {
+            return Err(ParseError::ParseFailed);
+        }
+        rule.weights = Some(weights);
+    }
+    Ok(rule)
+}
+
+// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+// The value of `key` in a JSON object.
+fn get<'a>(json: &'a Json, key: &str) -> Result<&'a Json, ParseError> {
+    match *json {
+        Json::Object(ref fields) => fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .ok_or(ParseError::ParseFailed),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// The elements of a JSON array.
+fn array(json: &Json) -> Result<&[Json], ParseError> {
+    match *json {
+        Json::Array(ref elements) => Ok(elements),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// A JSON string or null.
+fn name(json: &Json) -> Result<Option<String>, ParseError> {
+    match *json {
+        Json::String(ref name) => Ok(Some(name.clone())),
+        Json::Null => Ok(None),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// A JSON number which is a valid index.
+fn index(json: &Json) -> Result<usize, ParseError> {
+    match *json {
+        Json::Number(n) if n >= 0.0 && n.fract() == 0.0 => Ok(n as usize),
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// Read a single JSON value.
+fn read_json(input: &str) -> Result<Json, ParseError> {
+    let mut chars = input.chars().peekable();
+    let json = read_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    match chars.next() {
+        None => Ok(json),
+        Some(_) => Err(ParseError::ParseIncomplete),
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while let Some(&c) = chars.peek() {
+        if !c.is_whitespace() {
+            break;
+        }
+        chars.next();
+    }
+}
+
+fn read_value(chars: &mut Peekable<Chars>) -> Result<Json, ParseError> {
+    skip_whitespace(chars);
+    match chars.peek().cloned() {
+        Some('{') => {
+            chars.next();
+            let mut fields = vec![];
+            read_sequence(chars, '}', |chars| {
+                skip_whitespace(chars);
+                let key = read_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next() != Some(':') {
+                    return Err(ParseError::ParseFailed);
+                }
+                fields.push((key, read_value(chars)?));
+                Ok(())
+            })?;
+            Ok(Json::Object(fields))
+        }
+        Some('[') => {
+            chars.next();
+            let mut elements = vec![];
+            read_sequence(chars, ']', |chars| {
+                elements.push(read_value(chars)?);
+                Ok(())
+            })?;
+            Ok(Json::Array(elements))
+        }
+        Some('"') => read_string(chars).map(Json::String),
+        Some(c) if c == '-' || c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                if !(c.is_ascii_digit() || "+-.eE".contains(c)) {
+                    break;
+                }
+                number.push(c);
+                chars.next();
+            }
+            number
+                .parse::<f64>()
+                .map(Json::Number)
+                .map_err(|_| ParseError::ParseFailed)
+        }
+        Some(_) => {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if !c.is_ascii_alphabetic() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            match word.as_str() {
+                "null" => Ok(Json::Null),
+                _ => Err(ParseError::ParseFailed),
+            }
+        }
+        None => Err(ParseError::ParseIncomplete),
+    }
+}
+
+// Read comma-separated items up to `close`, which follows the opening bracket.
+fn read_sequence<'a, F>(
+    chars: &mut Peekable<Chars<'a>>,
+    close: char,
+    mut item: F,
+) -> Result<(), ParseError>
+where
+    F: FnMut(&mut Peekable<Chars<'a>>) -> Result<(), ParseError>,
+{
+    skip_whitespace(chars);
+    if chars.peek() == Some(&close) {
+        chars.next();
+        return Ok(());
+    }
+    loop {
+        item(chars)?;
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => (),
+            Some(c) if c == close => return Ok(()),
+            Some(_) => return Err(ParseError::ParseFailed),
+            None => return Err(ParseError::ParseIncomplete),
+        }
+    }
+}
+
+fn read_string(chars: &mut Peekable<Chars>) -> Result<String, ParseError> {
+    if chars.next() != Some('"') {
+        return Err(ParseError::ParseFailed);
+    }
+    let mut string = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(string),
+            Some('\\') => {
+                let c = match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('r') => '\r',
+                    Some('b') => '\u{8}',
+                    Some('f') => '\u{c}',
+                    Some('u') => {
+                        let code: String = chars.by_ref().take(4).collect();
+                        u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(::std::char::from_u32)
+                            .ok_or(ParseError::ParseFailed)?
+                    }
+                    Some(c) => c,
+                    None => return Err(ParseError::ParseIncomplete),
+                };
+                string.push(c);
+            }
+            Some(c) => string.push(c),
+            None => return Err(ParseError::ParseIncomplete),
+        }
+    }
+}
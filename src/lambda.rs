@@ -0,0 +1,420 @@
+//! Lambda terms encoded as first-order [`Term`]s.
+//!
+//! An abstraction `λx.b` is the [`Term`] `LAMBDA(x_ b)`, whose first argument is the bound
+//! [`Variable`], and an application `f a` uses the binary application operator `.`, written
+//! `(f a)` or `f a` by the parser. [`Lambda`] provides capture-avoiding substitution, normal-order
+//! beta reduction, and conversion to and from the applicative encoding of combinatory logic.
+//!
+//! [`Term`]: ../enum.Term.html
+//! [`Variable`]: ../struct.Variable.html
+//! [`Lambda`]: struct.Lambda.html
+//!
+//! # Examples
+//!
+//! ```
+//! use term_rewriting::{lambda::Lambda, parse_term, Normalization, Signature};
+//!
+//! let mut sig = Signature::default();
+//! let lambda = Lambda::new(&mut sig);
+//!
+//! let term = parse_term(&mut sig, "LAMBDA(x_ LAMBDA(y_ x_)) y_").expect("parse of LAMBDA(x_ LAMBDA(y_ x_)) y_");
+//!
+//! match lambda.normalize(&mut sig, &term, 10) {
+//!     Normalization::Normalized(t) => assert_eq!(t.display(&sig), "LAMBDA(var2_ y_)"),
+//!     n => panic!("unexpected {:?}", n),
+//! }
+//! ```
+
+use combinators::Combinators;
+use parser::Parser;
+use {Normalization, Operator, Signature, Term, Variable};
+
+/// The [`Operator`]s encoding lambda terms in some [`Signature`].
+///
+/// [`Operator`]: ../struct.Operator.html
+/// [`Signature`]: ../struct.Signature.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lambda {
+    /// The binder, `LAMBDA`.
+    pub lambda: Operator,
+    /// The binary application operator, `.`.
+    pub app: Operator,
+}
+impl Lambda {
+    /// Add the `LAMBDA` and `.` [`Operator`]s to `sig`, reusing any it already has.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    pub fn new(sig: &mut Signature) -> Lambda {
+        let mut parser = Parser::new(sig);
+        Lambda {
+            lambda: parser.get_op("LAMBDA", 2),
+            app: parser.get_op(".", 2),
+        }
+    }
+    /// The abstraction of `body` over `var`.
+    pub fn abstraction(&self, var: Variable, body: Term) -> Term {
+        Term::Application {
+            op: self.lambda,
+            args: vec![Term::Variable(var), body],
+        }
+    }
+    /// Apply `f` to `x`.
+    pub fn apply(&self, f: Term, x: Term) -> Term {
+        Term::Application {
+            op: self.app,
+            args: vec![f, x],
+        }
+    }
+    /// The bound [`Variable`] and body of `term`, if it is an abstraction.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    pub fn binding<'a>(&self, term: &'a Term) -> Option<(&'a Variable, &'a Term)> {
+        match *term {
+            Term::Application { ref op, ref args } if *op == self.lambda => match args[0] {
+                Term::Variable(ref var) => Some((var, &args[1])),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    /// The [`Variable`]s occurring free in `term`, in order of first occurrence.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{lambda::Lambda, parse_term, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let term = parse_term(&mut sig, "LAMBDA(x_ (x_ y_)) x_").expect("parse of LAMBDA(x_ (x_ y_)) x_");
+    /// let free: Vec<String> = lambda.free_variables(&term).iter().map(|v| v.display(&sig)).collect();
+    ///
+    /// assert_eq!(free, vec!["y_", "x_"]);
+    /// ```
+    pub fn free_variables(&self, term: &Term) -> Vec<Variable> {
+        let mut free = vec![];
+        self.collect_free(term, &mut vec![], &mut free);
+        free
+    }
+    fn collect_free(&self, term: &Term, bound: &mut Vec<Variable>, free: &mut Vec<Variable>) {
+        if let Some((var, body)) = self.binding(term) {
+            bound.push(*var);
+            self.collect_free(body, bound, free);
+            bound.pop();
+            return;
+        }
+        match *term {
+            Term::Variable(ref var) => {
+                if !bound.contains(var) && !free.contains(var) {
+                    free.push(*var);
+                }
+            }
+            Term::Application { ref args, .. } => {
+                for arg in args {
+                    self.collect_free(arg, bound, free);
+                }
+            }
+        }
+    }
+    /// Replace the free occurrences of `var` in `term` with `value`, renaming bound
+    /// [`Variable`]s as needed so that no free [`Variable`] of `value` is captured.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{lambda::Lambda, parse_term, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let t = parse_term(&mut sig, "F(LAMBDA(y_ (x_ y_)) y_)").expect("parse of F(LAMBDA(y_ (x_ y_)) y_)");
+    /// let args = t.args();
+    /// let vars = t.variables();
+    ///
+    /// let substituted = lambda.substitute(&mut sig, &args[0], &vars[1], &args[1]);
+    ///
+    /// assert_eq!(substituted.display(&sig), "LAMBDA(var2_ .(y_ var2_))");
+    /// ```
+    pub fn substitute(
+        &self,
+        sig: &mut Signature,
+        term: &Term,
+        var: &Variable,
+        value: &Term,
+    ) -> Term {
+        let value_vars = self.free_variables(value);
+        self.substitute_helper(sig, term, var, value, &value_vars)
+    }
+    fn substitute_helper(
+        &self,
+        sig: &mut Signature,
+        term: &Term,
+        var: &Variable,
+        value: &Term,
+        value_vars: &[Variable],
+    ) -> Term {
+        if let Some((bound, body)) = self.binding(term) {
+            if bound == var || !self.free_variables(body).contains(var) {
+                return term.clone();
+            }
+            return if value_vars.contains(bound) {
+                let fresh = sig.new_var(None);
+                let renamed = self.substitute(sig, body, bound, &Term::Variable(fresh));
+                let body = self.substitute_helper(sig, &renamed, var, value, value_vars);
+                self.abstraction(fresh, body)
+            } else {
+                let body = self.substitute_helper(sig, body, var, value, value_vars);
+                self.abstraction(*bound, body)
+            };
+        }
+        match *term {
+            Term::Variable(ref v) if v == var => value.clone(),
+            Term::Variable(_) => term.clone(),
+            Term::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args
+                    .iter()
+                    .map(|arg| self.substitute_helper(sig, arg, var, value, value_vars))
+                    .collect(),
+            },
+        }
+    }
+    /// Contract the leftmost-outermost beta-redex in `term`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{lambda::Lambda, parse_term, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let term = parse_term(&mut sig, "LAMBDA(x_ F(x_ x_)) (LAMBDA(y_ y_) A)").expect("parse of LAMBDA(x_ F(x_ x_)) (LAMBDA(y_ y_) A)");
+    ///
+    /// let term = lambda.beta_step(&mut sig, &term).expect("first step");
+    /// assert_eq!(term.display(&sig), "F(.(LAMBDA(y_ y_) A) .(LAMBDA(y_ y_) A))");
+    ///
+    /// let term = lambda.beta_step(&mut sig, &term).expect("second step");
+    /// assert_eq!(term.display(&sig), "F(A .(LAMBDA(y_ y_) A))");
+    /// ```
+    pub fn beta_step(&self, sig: &mut Signature, term: &Term) -> Option<Term> {
+        match *term {
+            Term::Variable(_) => None,
+            Term::Application { ref op, ref args } => {
+                if *op == self.app {
+                    if let Some((var, body)) = self.binding(&args[0]) {
+                        return Some(self.substitute(sig, body, var, &args[1]));
+                    }
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    if let Some(reduct) = self.beta_step(sig, arg) {
+                        let mut args = args.clone();
+                        args[i] = reduct;
+                        return Some(Term::Application { op: *op, args });
+                    }
+                }
+                None
+            }
+        }
+    }
+    /// Beta-reduce `term` in normal order, taking at most `limit` steps. Returns
+    /// [`Normalization::Normalized`] with the beta-normal form, or
+    /// [`Normalization::StepLimit`] with the last `Term` reached.
+    ///
+    /// [`Normalization::Normalized`]: ../enum.Normalization.html#variant.Normalized
+    /// [`Normalization::StepLimit`]: ../enum.Normalization.html#variant.StepLimit
+    pub fn normalize(&self, sig: &mut Signature, term: &Term, limit: usize) -> Normalization {
+        let mut term = term.clone();
+        for _ in 0..limit {
+            match self.beta_step(sig, &term) {
+                Some(reduct) => term = reduct,
+                None => return Normalization::Normalized(term),
+            }
+        }
+        if self.beta_step(sig, &term).is_some() {
+            Normalization::StepLimit(term)
+        } else {
+            Normalization::Normalized(term)
+        }
+    }
+    /// Are `t1` and `t2` the same up to renaming bound [`Variable`]s?
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{lambda::Lambda, parse_term, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let t = parse_term(&mut sig, "F(LAMBDA(x_ (x_ z_)) LAMBDA(y_ (y_ z_)) LAMBDA(z_ (z_ z_)))").expect("parse of lambda terms");
+    /// let args = t.args();
+    ///
+    /// assert!(lambda.alpha_equivalent(&args[0], &args[1]));
+    /// assert!(!lambda.alpha_equivalent(&args[0], &args[2]));
+    /// ```
+    pub fn alpha_equivalent(&self, t1: &Term, t2: &Term) -> bool {
+        self.alpha_helper(t1, t2, &mut vec![])
+    }
+    fn alpha_helper(&self, t1: &Term, t2: &Term, bound: &mut Vec<(Variable, Variable)>) -> bool {
+        match (self.binding(t1), self.binding(t2)) {
+            (Some((v1, b1)), Some((v2, b2))) => {
+                bound.push((*v1, *v2));
+                let result = self.alpha_helper(b1, b2, bound);
+                bound.pop();
+                return result;
+            }
+            (None, None) => (),
+            _ => return false,
+        }
+        match (t1, t2) {
+            (Term::Variable(v1), Term::Variable(v2)) => {
+                let left = bound.iter().rev().find(|(b1, _)| b1 == v1);
+                let right = bound.iter().rev().find(|(_, b2)| b2 == v2);
+                match (left, right) {
+                    (Some((_, b2)), Some((b1, _))) => b1 == v1 && b2 == v2,
+                    (None, None) => v1 == v2,
+                    _ => false,
+                }
+            }
+            (
+                Term::Application {
+                    op: op1,
+                    args: args1,
+                },
+                Term::Application {
+                    op: op2,
+                    args: args2,
+                },
+            ) => {
+                op1 == op2
+                    && args1.len() == args2.len()
+                    && args1
+                        .iter()
+                        .zip(args2)
+                        .all(|(a1, a2)| self.alpha_helper(a1, a2, bound))
+            }
+            _ => false,
+        }
+    }
+    /// Translate `term` into combinatory logic by bracket abstraction, removing every
+    /// abstraction. Returns `None` if a bound [`Variable`] occurs as the argument of an
+    /// [`Operator`] other than `.`, as it cannot then be abstracted away.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    /// [`Operator`]: ../struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{combinators, lambda::Lambda, parse_term, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let (cl, _) = combinators::install(&mut sig);
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let term = parse_term(&mut sig, "LAMBDA(x_ LAMBDA(y_ (y_ x_)))").expect("parse of LAMBDA(x_ LAMBDA(y_ (y_ x_)))");
+    /// let cl_term = lambda.to_combinators(&term, &cl).expect("translation");
+    ///
+    /// assert_eq!(cl_term.pretty(&sig), "S (K (S I)) (S (K K) I)");
+    ///
+    /// let term = parse_term(&mut sig, "LAMBDA(x_ F(x_))").expect("parse of LAMBDA(x_ F(x_))");
+    ///
+    /// assert_eq!(lambda.to_combinators(&term, &cl), None);
+    /// ```
+    pub fn to_combinators(&self, term: &Term, cl: &Combinators) -> Option<Term> {
+        if let Some((var, body)) = self.binding(term) {
+            let body = self.to_combinators(body, cl)?;
+            return self.bracket(var, &body, cl);
+        }
+        match *term {
+            Term::Variable(_) => Some(term.clone()),
+            Term::Application { ref op, ref args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.to_combinators(arg, cl))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Term::Application { op: *op, args })
+            }
+        }
+    }
+    // Abstract `var` out of the abstraction-free `term`.
+    fn bracket(&self, var: &Variable, term: &Term, cl: &Combinators) -> Option<Term> {
+        if !term.variables().contains(var) {
+            return Some(cl.apply(constant(&cl.k), term.clone()));
+        }
+        match *term {
+            Term::Variable(_) => Some(constant(&cl.i)),
+            Term::Application { ref op, ref args } if *op == cl.app => {
+                let f = self.bracket(var, &args[0], cl)?;
+                let x = self.bracket(var, &args[1], cl)?;
+                Some(cl.apply(cl.apply(constant(&cl.s), f), x))
+            }
+            Term::Application { .. } => None,
+        }
+    }
+    /// Translate the combinators in `term` into their definitions as abstractions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use term_rewriting::{combinators, lambda::Lambda, parse_term, Normalization, Signature};
+    ///
+    /// let mut sig = Signature::default();
+    /// let (cl, _) = combinators::install(&mut sig);
+    /// let lambda = Lambda::new(&mut sig);
+    ///
+    /// let t = parse_term(&mut sig, "F((K I) LAMBDA(y_ LAMBDA(z_ z_)))").expect("parse of F((K I) LAMBDA(y_ LAMBDA(z_ z_)))");
+    /// let args = t.args();
+    /// let term = lambda.from_combinators(&mut sig, &args[0], &cl);
+    ///
+    /// assert_eq!(term.display(&sig), ".(LAMBDA(var8_ LAMBDA(var9_ var8_)) LAMBDA(var10_ var10_))");
+    ///
+    /// match lambda.normalize(&mut sig, &term, 10) {
+    ///     Normalization::Normalized(t) => assert!(lambda.alpha_equivalent(&t, &args[1])),
+    ///     n => panic!("unexpected {:?}", n),
+    /// }
+    /// ```
+    pub fn from_combinators(&self, sig: &mut Signature, term: &Term, cl: &Combinators) -> Term {
+        match *term {
+            Term::Variable(_) => term.clone(),
+            Term::Application { ref op, .. } if *op == cl.s => {
+                let (x, y, z) = (sig.new_var(None), sig.new_var(None), sig.new_var(None));
+                let (tx, ty, tz) = (Term::Variable(x), Term::Variable(y), Term::Variable(z));
+                let body = self.apply(self.apply(tx, tz.clone()), self.apply(ty, tz));
+                self.abstraction(x, self.abstraction(y, self.abstraction(z, body)))
+            }
+            Term::Application { ref op, .. } if *op == cl.k => {
+                let (x, y) = (sig.new_var(None), sig.new_var(None));
+                let body = Term::Variable(x);
+                self.abstraction(x, self.abstraction(y, body))
+            }
+            Term::Application { ref op, .. } if *op == cl.i => {
+                let x = sig.new_var(None);
+                self.abstraction(x, Term::Variable(x))
+            }
+            Term::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args
+                    .iter()
+                    .map(|arg| self.from_combinators(sig, arg, cl))
+                    .collect(),
+            },
+        }
+    }
+}
+
+// The constant `Term` for the nullary `op`.
+fn constant(op: &Operator) -> Term {
+    Term::Application {
+        op: *op,
+        args: vec![],
+    }
+}
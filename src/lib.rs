@@ -76,21 +76,61 @@
 //! - [`parse_context`]: a [`Context`] (`top-level-context`)
 //! - [`parse_rulecontext`]: a [`RuleContext`] (`rulecontext`)
 //!
+//! [`parse_trs_strict`] and [`parse_term_strict`] parse like [`parse_trs`] and [`parse_term`],
+//! but report symbols missing from the [`Signature`] rather than creating them.
+//! [`parse_trs_with_lists`] and [`parse_term_with_lists`] desugar lists into other
+//! constructors than `CONS` and `NIL`. [`parse_trs_pretty`] and [`parse_term_pretty`] read
+//! the output of `pretty`, which has numerals and separates arguments only by commas.
+//! [`parse_trs_stream`] reads a `trs` from a `BufRead` source one [`Rule`] at a time.
+//!
 //! ```text
 //! program = *wsp *( *comment statement ";" *comment ) *wsp
 //!
-//! statement = rule / top-level-term
+//! trs = *wsp *( *comment ( operators / declaration / rule ) ";" *comment ) *wsp
+//!
+//! statement = operators / declaration / rule / top-level-term
+//!
+//! ; declares the arities of operators; later terms and rules must use them
+//! ; with those arities.
+//! operators = "ops" *wsp ":" *wsp operator-arity *( *wsp "," *wsp operator-arity )
+//!
+//! operator-arity = identifier "/" 1*DIGIT
+//!
+//! ; declares the argument sorts and result sort of an operator; later terms
+//! ; and rules using it must be well-sorted.
+//! declaration = identifier *wsp ":" *wsp sort *( *wsp "->" *wsp sort )
+//!
+//! sort = 1*( ALPHA / DIGIT )
+//!
+//! rule = top-level-term *wsp "=" *wsp clause
+//! rule /= rule *wsp "|" *wsp clause
+//! rule /= rule *wsp "<=" *wsp condition *( *wsp "," *wsp condition )
+//!
+//! ; a rule's clauses are either all weighted or all unweighted.
+//! clause = top-level-term [ *wsp "::" *wsp weight ]
+//!
+//! weight = 1*( DIGIT / "." / "e" / "E" / "+" / "-" )
 //!
-//! rule = top-level-term *wsp "=" *wsp top-level-term
-//! rule /= rule *wsp "|" *wsp top-level-term
+//! condition = top-level-term *wsp "=" *wsp top-level-term
 //!
 //! top-level-term = term
 //! top-level-term /= top-level-term 1*wsp top-level-term
 //!
 //! term = variable
 //! term /= application
+//! term /= string
+//! term /= list
 //! term /= "(" *wsp top-level-term *wsp ")"
 //!
+//! ; a string desugars to the binary application of its characters, each the
+//! ; nullary operator named by the character in single quotes: "ab" is ('a' 'b').
+//! string = DQUOTE 1*( any-char-but-DQUOTE-or-backslash / escape ) DQUOTE
+//!
+//! escape = "\" ( DQUOTE / "'" / "\" / "n" / "t" )
+//!
+//! ; a list desugars to CONS and NIL: [a, b] is CONS(a CONS(b NIL)).
+//! list = "[" *wsp [ top-level-term *( *wsp "," *wsp top-level-term ) ] *wsp "]"
+//!
 //! rulecontext = top-level-term *wsp "=" *wsp top-level-term
 //! rulecontext /= rule *wsp "|" *wsp top-level-term
 //!
@@ -100,13 +140,18 @@
 //! context = variable
 //! context /= application
 //! context /= hole
+//! context /= string
+//! context /= "[" *wsp [ top-level-context *( *wsp "," *wsp top-level-context ) ] *wsp "]"
 //! context /= "(" *wsp top-level-context *wsp ")"
 //!
 //! hole = "[!]"
 //!
 //! variable = identifier"_"
 //!
+//! ; more than two arguments to an associative binary operator nest to the right:
+//! ; if T is associative, T(a, b, c) is T(a T(b c)).
 //! application = identifier "(" [ term *( 1*wsp term ) ] ")"
+//! application /= identifier "(" *wsp top-level-term 1*( *wsp "," *wsp top-level-term ) *wsp ")"
 //! application /= identifier
 //! application /= binary-application
 //!
@@ -115,11 +160,31 @@
 //!
 //! identifier = 1*( ALPHA / DIGIT )
 //!
+//! ; a quoted identifier may contain any character, e.g. '++' or 'my name'.
+//! identifier /= "'" 1*( any-char-but-quote-or-backslash / escape ) "'"
+//!
 //! comment = "#" *any-char-but-newline "\n"
 //!
 //! wsp = SP / TAB / CR / LF
 //! ```
 //!
+//! # Other formats
+//!
+//! [`TRS`]s can also be exchanged with other tools and processes:
+//!
+//! - [`parse_trs_tpdb`] and [`TRS::to_tpdb`]: the TPDB format of termination tools
+//! - [`parse_cops`], [`parse_ari`], [`Problem::to_cops`], and [`Problem::to_ari`]: the COPS
+//!   and ARI formats of the confluence and termination competitions
+//! - [`parse_trs_sexp`] and [`TRS::to_sexp`]: canonical s-expressions
+//! - [`parse_trs_json`] and [`TRS::to_json`]: self-contained JSON bundles
+//! - [`decode_trs`] and [`TRS::to_bytes`]: a compact, versioned binary encoding
+//!
+//! and likewise for [`Term`]s and [`Rule`]s. None of these formats depend on the internal ids
+//! of [`Operator`]s, so they can be loaded into any [`Signature`], whatever order it was built
+//! in: each [`Operator`] is referenced by its name and arity, and resolves to the [`Operator`]
+//! of the target [`Signature`] with that name and arity, which is created if necessary.
+//! [`Variable`]s are always created fresh.
+//!
 //! # Term Rewriting Systems
 //!
 //! Term Rewriting Systems (TRS) are a simple formalism from theoretical
@@ -160,24 +225,69 @@
 //! [`parse_rule`]: fn.parse_rule.html
 //! [`parse_context`]: fn.parse_context.html
 //! [`parse_rulecontext`]: fn.parse_rulecontext.html
+//! [`parse_trs_strict`]: fn.parse_trs_strict.html
+//! [`parse_term_strict`]: fn.parse_term_strict.html
+//! [`parse_trs_with_lists`]: fn.parse_trs_with_lists.html
+//! [`parse_term_with_lists`]: fn.parse_term_with_lists.html
+//! [`parse_trs_pretty`]: fn.parse_trs_pretty.html
+//! [`parse_term_pretty`]: fn.parse_term_pretty.html
+//! [`parse_trs_stream`]: fn.parse_trs_stream.html
 //! [`TRS`]: struct.TRS.html
 //! [`Term`s]: enum.Term.html
 //! [`Term`]: enum.Term.html
 //! [`Rule`]: struct.Rule.html
 //! [`Context`]: enum.Context.html
 //! [`RuleContext`]: struct.RuleContext.html
+//! [`Operator`]: struct.Operator.html
+//! [`Variable`]: struct.Variable.html
+//! [`Signature`]: struct.Signature.html
+//! [`Problem::to_cops`]: struct.Problem.html#method.to_cops
+//! [`Problem::to_ari`]: struct.Problem.html#method.to_ari
+//! [`TRS::to_tpdb`]: struct.TRS.html#method.to_tpdb
+//! [`TRS::to_sexp`]: struct.TRS.html#method.to_sexp
+//! [`TRS::to_json`]: struct.TRS.html#method.to_json
+//! [`TRS::to_bytes`]: struct.TRS.html#method.to_bytes
+//! [`parse_trs_tpdb`]: fn.parse_trs_tpdb.html
+//! [`parse_cops`]: fn.parse_cops.html
+//! [`parse_ari`]: fn.parse_ari.html
+//! [`parse_trs_sexp`]: fn.parse_trs_sexp.html
+//! [`parse_trs_json`]: fn.parse_trs_json.html
+//! [`decode_trs`]: fn.decode_trs.html
 
 extern crate itertools;
 #[macro_use]
 extern crate nom;
 extern crate rand;
 
+mod ari;
+mod binary;
+pub mod combinators;
+pub mod completion;
+mod json;
+pub mod lambda;
+pub mod mutation;
+pub mod orderings;
 mod parser;
 mod pretty;
+mod problem;
+mod sexp;
+mod stream;
+pub mod termination;
+mod tpdb;
 pub mod trace;
 mod types;
 
+pub use ari::parse_ari;
+pub use binary::{decode_rule, decode_term, decode_trs};
+pub use json::{parse_rule_json, parse_term_json, parse_trs_json};
 pub use parser::{
-    parse, parse_context, parse_rule, parse_rulecontext, parse_term, parse_trs, ParseError,
+    parse, parse_context, parse_rule, parse_rulecontext, parse_term, parse_term_pretty,
+    parse_term_strict, parse_term_with_lists, parse_trs, parse_trs_pretty, parse_trs_strict,
+    parse_trs_with_lists, ParseError, SyntaxError,
 };
+pub use pretty::{DisplayStyle, PrettyConfig};
+pub use problem::Problem;
+pub use sexp::{parse_rule_sexp, parse_term_sexp, parse_trs_sexp};
+pub use stream::{parse_trs_stream, RuleStream};
+pub use tpdb::{parse_cops, parse_trs_tpdb};
 pub use types::*;
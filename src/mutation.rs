@@ -0,0 +1,394 @@
+//! Mutation operators for genetic programming over [`Term`]s and [`Rule`]s.
+//!
+//! Each operator returns a mutated copy of its input, or `None` if the operator cannot apply.
+//! New subterms are sampled as in [`Signature::sample_term_with`].
+//!
+//! The [`Rule`] operators act on the lhs and rhs of a [`Rule`] and only return [`Rule`]s
+//! satisfying the variable condition: the lhs is not a [`Variable`], and every [`Variable`] of
+//! the rhs and conditions occurs in the lhs. Conditions and weights are kept.
+//!
+//! [`Term`]: ../enum.Term.html
+//! [`Rule`]: ../struct.Rule.html
+//! [`Variable`]: ../struct.Variable.html
+//! [`Signature::sample_term_with`]: ../struct.Signature.html#method.sample_term_with
+//!
+//! # Examples
+//!
+//! ```
+//! # extern crate rand;
+//! # extern crate term_rewriting;
+//! # fn main(){
+//! use term_rewriting::{mutation, parse_rule, Signature, TermSampleConfig};
+//!
+//! let mut sig = Signature::default();
+//! let rule = parse_rule(&mut sig, "F(x_ A) = G(x_)").expect("parse of F(x_ A) = G(x_)");
+//! let mut r = rand::thread_rng();
+//!
+//! let config = TermSampleConfig::default();
+//! let mutant = mutation::replace_rule_subterm(&sig, &rule, &config, 2, &mut r).expect("a mutant");
+//!
+//! assert!(mutant.rhs[0].variables().iter().all(|v| mutant.lhs.variables().contains(v)));
+//! # }
+//! ```
+
+use rand::Rng;
+use std::collections::HashMap;
+use {Place, Rule, Signature, Term, TermSampleConfig, Variable};
+
+/// Replace a random subterm of `term` with a [`Term`] no deeper than `max_depth`.
+///
+/// [`Term`]: ../enum.Term.html
+pub fn replace_subterm<R: Rng>(
+    sig: &Signature,
+    term: &Term,
+    config: &TermSampleConfig,
+    max_depth: usize,
+    rng: &mut R,
+) -> Option<Term> {
+    let variables = sig.variables();
+    mutate_term(
+        term,
+        |_| true,
+        rng,
+        |_, rng| sig.sample_term_over(config, &variables, max_depth, rng),
+    )
+}
+
+/// Replace the [`Operator`] of a random subterm of `term` with a different [`Operator`] of the
+/// same arity.
+///
+/// [`Operator`]: ../struct.Operator.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{mutation, parse_term, Signature};
+/// let mut sig = Signature::default();
+/// let term = parse_term(&mut sig, "F(A x_)").expect("parse of F(A x_)");
+/// let mut r = rand::thread_rng();
+///
+/// assert_eq!(mutation::point_mutation(&sig, &term, &mut r), None);
+///
+/// sig.new_op(0, Some("B".to_string()));
+///
+/// let mutant = mutation::point_mutation(&sig, &term, &mut r).expect("a mutant");
+///
+/// assert_eq!(mutant.display(&sig), "F(B x_)");
+/// # }
+/// ```
+pub fn point_mutation<R: Rng>(sig: &Signature, term: &Term, rng: &mut R) -> Option<Term> {
+    mutate_term(
+        term,
+        |t| has_alternative(sig, t),
+        rng,
+        |t, rng| point_mutate(sig, t, rng),
+    )
+}
+
+/// Rename every occurrence of a random [`Variable`] of `term` to a different [`Variable`] of
+/// `sig`.
+///
+/// [`Variable`]: ../struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{mutation, parse_term, Signature};
+/// let mut sig = Signature::default();
+/// let term = parse_term(&mut sig, "F(x_ G(x_))").expect("parse of F(x_ G(x_))");
+/// sig.new_var(Some("y".to_string()));
+/// let mut r = rand::thread_rng();
+///
+/// let mutant = mutation::rename_variable(&sig, &term, &mut r).expect("a mutant");
+///
+/// assert_eq!(mutant.display(&sig), "F(y_ G(y_))");
+/// # }
+/// ```
+pub fn rename_variable<R: Rng>(sig: &Signature, term: &Term, rng: &mut R) -> Option<Term> {
+    let (from, to) = choose_renaming(sig, &term.variables(), rng)?;
+    let to = Term::Variable(to);
+    let mut sub = HashMap::new();
+    sub.insert(&from, &to);
+    Some(term.substitute(&sub))
+}
+
+/// Replace a random [`Variable`] or constant of `term` with a [`Term`] no deeper than
+/// `max_depth` whose head takes arguments.
+///
+/// [`Variable`]: ../struct.Variable.html
+/// [`Term`]: ../enum.Term.html
+pub fn grow<R: Rng>(
+    sig: &Signature,
+    term: &Term,
+    config: &TermSampleConfig,
+    max_depth: usize,
+    rng: &mut R,
+) -> Option<Term> {
+    let variables = sig.variables();
+    mutate_term(
+        term,
+        |t| t.args().is_empty(),
+        rng,
+        |_, rng| sig.sample_application_over(config, &variables, max_depth, rng),
+    )
+}
+
+/// Replace a random subterm of `term` which has arguments with one of its arguments.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{mutation, parse_term, Signature};
+/// let mut sig = Signature::default();
+/// let term = parse_term(&mut sig, "F(G(A))").expect("parse of F(G(A))");
+/// let mut r = rand::thread_rng();
+///
+/// let mutant = mutation::shrink(&term, &mut r).expect("a mutant");
+///
+/// assert!(mutant.display(&sig) == "G(A)" || mutant.display(&sig) == "F(A)");
+/// # }
+/// ```
+pub fn shrink<R: Rng>(term: &Term, rng: &mut R) -> Option<Term> {
+    mutate_term(
+        term,
+        |t| !t.args().is_empty(),
+        rng,
+        |t, rng| rng.choose(&t.args()).cloned(),
+    )
+}
+
+/// Replace a random subterm of the lhs or rhs of `rule`, as in [`replace_subterm`]. A new rhs
+/// subterm only uses [`Variable`]s of the lhs.
+///
+/// [`replace_subterm`]: fn.replace_subterm.html
+/// [`Variable`]: ../struct.Variable.html
+pub fn replace_rule_subterm<R: Rng>(
+    sig: &Signature,
+    rule: &Rule,
+    config: &TermSampleConfig,
+    max_depth: usize,
+    rng: &mut R,
+) -> Option<Rule> {
+    mutate_rule(
+        sig,
+        rule,
+        |_| true,
+        rng,
+        |_, variables, rng| sig.sample_term_over(config, variables, max_depth, rng),
+    )
+}
+
+/// Replace the [`Operator`] of a random subterm of the lhs or rhs of `rule`, as in
+/// [`point_mutation`].
+///
+/// [`Operator`]: ../struct.Operator.html
+/// [`point_mutation`]: fn.point_mutation.html
+pub fn point_mutation_rule<R: Rng>(sig: &Signature, rule: &Rule, rng: &mut R) -> Option<Rule> {
+    mutate_rule(
+        sig,
+        rule,
+        |t| has_alternative(sig, t),
+        rng,
+        |t, _, rng| point_mutate(sig, t, rng),
+    )
+}
+
+/// Rename every occurrence of a random [`Variable`] of `rule` to a different [`Variable`] of
+/// `sig`, as in [`rename_variable`].
+///
+/// [`Variable`]: ../struct.Variable.html
+/// [`rename_variable`]: fn.rename_variable.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{mutation, parse_rule, Signature};
+/// let mut sig = Signature::default();
+/// let rule = parse_rule(&mut sig, "F(x_) = G(x_)").expect("parse of F(x_) = G(x_)");
+/// sig.new_var(Some("y".to_string()));
+/// let mut r = rand::thread_rng();
+///
+/// let mutant = mutation::rename_rule_variable(&sig, &rule, &mut r).expect("a mutant");
+///
+/// assert_eq!(mutant.display(&sig), "F(y_) = G(y_)");
+/// # }
+/// ```
+pub fn rename_rule_variable<R: Rng>(sig: &Signature, rule: &Rule, rng: &mut R) -> Option<Rule> {
+    let (from, to) = choose_renaming(sig, &rule.variables(), rng)?;
+    let to = Term::Variable(to);
+    let mut sub = HashMap::new();
+    sub.insert(&from, &to);
+    Some(rule.substitute(&sub))
+}
+
+/// Replace a random [`Variable`] or constant of the lhs or rhs of `rule`, as in [`grow`]. A new
+/// rhs subterm only uses [`Variable`]s of the lhs.
+///
+/// [`Variable`]: ../struct.Variable.html
+/// [`grow`]: fn.grow.html
+pub fn grow_rule<R: Rng>(
+    sig: &Signature,
+    rule: &Rule,
+    config: &TermSampleConfig,
+    max_depth: usize,
+    rng: &mut R,
+) -> Option<Rule> {
+    mutate_rule(
+        sig,
+        rule,
+        |t| t.args().is_empty(),
+        rng,
+        |_, variables, rng| sig.sample_application_over(config, variables, max_depth, rng),
+    )
+}
+
+/// Replace a random subterm of the lhs or rhs of `rule` which has arguments with one of its
+/// arguments, as in [`shrink`].
+///
+/// [`shrink`]: fn.shrink.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{mutation, parse_rule, Signature};
+/// let mut sig = Signature::default();
+/// let rule = parse_rule(&mut sig, "F(x_) = x_").expect("parse of F(x_) = x_");
+/// let mut r = rand::thread_rng();
+///
+/// // shrinking the lhs to x_ would break the variable condition
+/// assert_eq!(mutation::shrink_rule(&sig, &rule, &mut r), None);
+/// # }
+/// ```
+pub fn shrink_rule<R: Rng>(sig: &Signature, rule: &Rule, rng: &mut R) -> Option<Rule> {
+    mutate_rule(
+        sig,
+        rule,
+        |t| !t.args().is_empty(),
+        rng,
+        |t, _, rng| rng.choose(&t.args()).cloned(),
+    )
+}
+
+// Replace the subterm at a random place of `term` satisfying `eligible` with `mutate` of it,
+// trying other places if `mutate` fails.
+fn mutate_term<R, P, F>(term: &Term, eligible: P, rng: &mut R, mut mutate: F) -> Option<Term>
+where
+    R: Rng,
+    P: Fn(&Term) -> bool,
+    F: FnMut(&Term, &mut R) -> Option<Term>,
+{
+    let mut places: Vec<(&Term, Place)> = term
+        .subterms()
+        .into_iter()
+        .filter(|(t, _)| eligible(t))
+        .collect();
+    rng.shuffle(&mut places);
+    places.into_iter().find_map(|(t, place)| {
+        let subterm = mutate(t, rng)?;
+        term.replace(&place, subterm)
+    })
+}
+
+// Replace the subterm at a random place of the lhs or rhs of `rule` satisfying `eligible` with
+// `mutate` of it, given the Variables the new subterm may use, trying other places if `mutate`
+// fails or breaks the variable condition.
+fn mutate_rule<R, P, F>(
+    sig: &Signature,
+    rule: &Rule,
+    eligible: P,
+    rng: &mut R,
+    mut mutate: F,
+) -> Option<Rule>
+where
+    R: Rng,
+    P: Fn(&Term) -> bool,
+    F: FnMut(&Term, &[Variable], &mut R) -> Option<Term>,
+{
+    let lhs_variables = rule.lhs.variables();
+    let all_variables = sig.variables();
+    let mut places: Vec<(&Term, Place)> = rule
+        .subterms()
+        .into_iter()
+        .filter(|(t, _)| eligible(t))
+        .collect();
+    rng.shuffle(&mut places);
+    places.into_iter().find_map(|(t, place)| {
+        let variables = if place[0] == 0 {
+            &all_variables
+        } else {
+            &lhs_variables
+        };
+        let subterm = mutate(t, variables, rng)?;
+        replace_in_rule(rule, &place, subterm)
+    })
+}
+
+// Replace the subterm of `rule` at `place`, as in `Rule::replace`, but keeping its conditions
+// and weights.
+fn replace_in_rule(rule: &Rule, place: &[usize], subterm: Term) -> Option<Rule> {
+    let mut lhs = rule.lhs.clone();
+    let mut rhs = rule.rhs.clone();
+    if place[0] == 0 {
+        lhs = lhs.replace(&place[1..], subterm)?;
+    } else {
+        rhs[place[0] - 1] = rhs[place[0] - 1].replace(&place[1..], subterm)?;
+    }
+    let mut mutant = Rule::new_conditional(lhs, rhs, rule.conditions.clone())?;
+    mutant.weights = rule.weights.clone();
+    Some(mutant)
+}
+
+// Does `sig` have another Operator like the head of `term`?
+fn has_alternative(sig: &Signature, term: &Term) -> bool {
+    match term {
+        Term::Application { op, .. } => {
+            sig.operators_with_arity(op.arity()).iter().any(|o| o != op)
+        }
+        Term::Variable(_) => false,
+    }
+}
+
+// Replace the head of `term` with another Operator of `sig` of the same arity.
+fn point_mutate<R: Rng>(sig: &Signature, term: &Term, rng: &mut R) -> Option<Term> {
+    match term {
+        Term::Application { op, args } => {
+            let alternatives: Vec<_> = sig
+                .operators_with_arity(op.arity())
+                .into_iter()
+                .filter(|o| o != op)
+                .collect();
+            Some(Term::Application {
+                op: *rng.choose(&alternatives)?,
+                args: args.clone(),
+            })
+        }
+        Term::Variable(_) => None,
+    }
+}
+
+// Choose one of `variables` and a different Variable of `sig` to rename it to.
+fn choose_renaming<R: Rng>(
+    sig: &Signature,
+    variables: &[Variable],
+    rng: &mut R,
+) -> Option<(Variable, Variable)> {
+    let from = *rng.choose(variables)?;
+    let alternatives: Vec<Variable> = sig.variables().into_iter().filter(|v| *v != from).collect();
+    let to = *rng.choose(&alternatives)?;
+    Some((from, to))
+}
@@ -0,0 +1,371 @@
+//! Reduction orderings on [`Term`]s.
+//!
+//! A reduction ordering is a well-founded ordering on [`Term`]s which is closed under contexts
+//! and substitutions. A [`Rule`] whose left-hand side is greater than each of its right-hand
+//! sides in some reduction ordering can only be applied finitely many times in a row, which makes
+//! these orderings the basic tool for orienting equations and proving termination.
+//!
+//! [`Term`]: ../enum.Term.html
+//! [`Rule`]: ../struct.Rule.html
+//!
+//! # Examples
+//!
+//! ```
+//! use term_rewriting::{parse_rule, orderings::{Lpo, ReductionOrdering}, Signature};
+//!
+//! let mut sig = Signature::default();
+//! let times = sig.new_op(2, Some("TIMES".to_string()));
+//! let plus = sig.new_op(2, Some("PLUS".to_string()));
+//! let succ = sig.new_op(1, Some("SUCC".to_string()));
+//! let rule = parse_rule(&mut sig, "TIMES(SUCC(x_) y_) = PLUS(y_ TIMES(x_ y_))")
+//!     .expect("parse of TIMES(SUCC(x_) y_) = PLUS(y_ TIMES(x_ y_))");
+//!
+//! // TIMES > PLUS > SUCC
+//! let lpo = Lpo::new(vec![times, plus, succ]);
+//!
+//! assert!(lpo.orients(&rule));
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use {Operator, Rule, Term, TRS};
+
+/// A [reduction ordering] on [`Term`]s.
+///
+/// Implementors only need to provide the strict comparison [`gt`]; the remaining methods are
+/// derived from it.
+///
+/// [reduction ordering]: https://en.wikipedia.org/wiki/Rewrite_order#Reduction_ordering
+/// [`Term`]: ../enum.Term.html
+/// [`gt`]: #tymethod.gt
+pub trait ReductionOrdering {
+    /// Is `s` strictly greater than `t`?
+    fn gt(&self, s: &Term, t: &Term) -> bool;
+    /// Is `s` greater than or equal to `t`?
+    fn ge(&self, s: &Term, t: &Term) -> bool {
+        s == t || self.gt(s, t)
+    }
+    /// Does the ordering orient every clause of `rule` from left to right?
+    fn orients(&self, rule: &Rule) -> bool {
+        rule.rhs.iter().all(|rhs| self.gt(&rule.lhs, rhs))
+    }
+    /// Does the ordering orient every [`Rule`] of `trs` from left to right? If so, `trs`
+    /// terminates.
+    ///
+    /// [`Rule`]: ../struct.Rule.html
+    fn orients_trs(&self, trs: &TRS) -> bool {
+        trs.rules.iter().all(|rule| self.orients(rule))
+    }
+}
+
+/// A strict precedence on [`Operator`]s, listed from greatest to least.
+///
+/// [`Operator`]s absent from the list are incomparable to every other [`Operator`].
+///
+/// [`Operator`]: ../struct.Operator.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Precedence {
+    ops: Vec<Operator>,
+}
+impl Precedence {
+    /// Create a `Precedence` in which each [`Operator`] is greater than those following it.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    pub fn new(ops: Vec<Operator>) -> Precedence {
+        Precedence { ops }
+    }
+    /// Compare two [`Operator`]s, returning `None` if they are incomparable.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    pub fn compare(&self, f: &Operator, g: &Operator) -> Option<Ordering> {
+        if f == g {
+            Some(Ordering::Equal)
+        } else {
+            let rank = |op| self.ops.iter().position(|o| o == op);
+            match (rank(f), rank(g)) {
+                (Some(rf), Some(rg)) => Some(rg.cmp(&rf)),
+                _ => None,
+            }
+        }
+    }
+    /// Is `f` strictly greater than `g`?
+    pub fn gt(&self, f: &Operator, g: &Operator) -> bool {
+        self.compare(f, g) == Some(Ordering::Greater)
+    }
+}
+
+/// The [lexicographic path ordering] induced by a [`Precedence`].
+///
+/// [lexicographic path ordering]: https://en.wikipedia.org/wiki/Path_ordering_(term_rewriting)
+/// [`Precedence`]: struct.Precedence.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lpo {
+    precedence: Precedence,
+}
+impl Lpo {
+    /// Create an `Lpo` in which each [`Operator`] is greater than those following it.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    pub fn new(ops: Vec<Operator>) -> Lpo {
+        Lpo {
+            precedence: Precedence::new(ops),
+        }
+    }
+    /// The [`Precedence`] underlying the `Lpo`.
+    ///
+    /// [`Precedence`]: struct.Precedence.html
+    pub fn precedence(&self) -> &Precedence {
+        &self.precedence
+    }
+}
+impl ReductionOrdering for Lpo {
+    fn gt(&self, s: &Term, t: &Term) -> bool {
+        match (s, t) {
+            (Term::Variable(_), _) => false,
+            (_, Term::Variable(v)) => s != t && s.variables().contains(v),
+            (
+                Term::Application {
+                    op: ref f,
+                    args: ref ss,
+                },
+                Term::Application {
+                    op: ref g,
+                    args: ref ts,
+                },
+            ) => {
+                if ss.iter().any(|si| self.ge(si, t)) {
+                    true
+                } else if self.precedence.gt(f, g) {
+                    ts.iter().all(|tj| self.gt(s, tj))
+                } else if f == g {
+                    let first_difference = ss.iter().zip(ts).find(|(si, ti)| si != ti);
+                    match first_difference {
+                        Some((si, ti)) => self.gt(si, ti) && ts.iter().all(|tj| self.gt(s, tj)),
+                        None => false,
+                    }
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// The [Knuth-Bendix ordering] induced by weights on [`Operator`]s and [`Variable`]s and a
+/// [`Precedence`].
+///
+/// [Knuth-Bendix ordering]: https://en.wikipedia.org/wiki/Knuth%E2%80%93Bendix_completion_algorithm
+/// [`Operator`]: ../struct.Operator.html
+/// [`Variable`]: ../struct.Variable.html
+/// [`Precedence`]: struct.Precedence.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{parse_trs, orderings::{Kbo, ReductionOrdering}, Signature};
+/// let mut sig = Signature::default();
+/// let inv = sig.new_op(1, Some("I".to_string()));
+/// let times = sig.new_op(2, Some("TIMES".to_string()));
+/// let e = sig.new_op(0, Some("E".to_string()));
+/// let trs = parse_trs(&mut sig,
+/// "TIMES(E x_) = x_;
+/// TIMES(I(x_) x_) = E;
+/// TIMES(TIMES(x_ y_) z_) = TIMES(x_ TIMES(y_ z_));").expect("parse of group axioms");
+///
+/// let kbo = Kbo::new(
+///     vec![(inv.clone(), 0), (times.clone(), 0), (e.clone(), 1)],
+///     1,
+///     vec![inv, times, e],
+/// ).expect("admissible weights");
+///
+/// assert!(kbo.orients_trs(&trs));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Kbo {
+    weights: Vec<(Operator, usize)>,
+    variable_weight: usize,
+    precedence: Precedence,
+}
+impl Kbo {
+    /// Create a `Kbo` from [`Operator`] weights, the weight shared by all [`Variable`]s, and a
+    /// precedence listed from greatest to least. [`Operator`]s without a weight weigh as much as
+    /// a [`Variable`].
+    ///
+    /// The weights must be admissible: [`Variable`]s weigh more than `0`, constants weigh at
+    /// least as much as [`Variable`]s, and a unary [`Operator`] of weight `0` must be greater
+    /// than every other [`Operator`] in the precedence.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    /// [`Variable`]: ../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{orderings::{Kbo, KboError}, Signature};
+    /// let mut sig = Signature::default();
+    /// let f = sig.new_op(1, Some("F".to_string()));
+    /// let g = sig.new_op(1, Some("G".to_string()));
+    ///
+    /// assert!(Kbo::new(vec![(f.clone(), 0)], 1, vec![f.clone(), g.clone()]).is_ok());
+    ///
+    /// match Kbo::new(vec![(f.clone(), 0)], 1, vec![g, f]) {
+    ///     Err(KboError::UnaryWeightZero(op)) => assert_eq!(op.display(&sig), "F"),
+    ///     _ => panic!("expected inadmissible weights"),
+    /// }
+    /// ```
+    pub fn new(
+        weights: Vec<(Operator, usize)>,
+        variable_weight: usize,
+        precedence: Vec<Operator>,
+    ) -> Result<Kbo, KboError> {
+        let kbo = Kbo {
+            weights,
+            variable_weight,
+            precedence: Precedence::new(precedence),
+        };
+        if variable_weight == 0 {
+            return Err(KboError::VariableWeightZero);
+        }
+        for &(ref op, weight) in &kbo.weights {
+            if op.arity() == 0 && weight < variable_weight {
+                return Err(KboError::ConstantTooLight(*op));
+            }
+            if op.arity() == 1 && weight == 0 {
+                let others = kbo
+                    .weights
+                    .iter()
+                    .map(|(o, _)| o)
+                    .chain(&kbo.precedence.ops);
+                if others
+                    .filter(|o| *o != op)
+                    .any(|o| !kbo.precedence.gt(op, o))
+                {
+                    return Err(KboError::UnaryWeightZero(*op));
+                }
+            }
+        }
+        Ok(kbo)
+    }
+    /// The weight of an [`Operator`].
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    pub fn weight(&self, op: &Operator) -> usize {
+        self.weights
+            .iter()
+            .find(|(o, _)| o == op)
+            .map_or(self.variable_weight, |(_, w)| *w)
+    }
+    /// The weight of a [`Term`]: the sum of the weights of its [`Operator`]s and [`Variable`]s.
+    ///
+    /// [`Term`]: ../enum.Term.html
+    /// [`Operator`]: ../struct.Operator.html
+    /// [`Variable`]: ../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{orderings::Kbo, parse_term, Signature};
+    /// let mut sig = Signature::default();
+    /// let f = sig.new_op(2, Some("F".to_string()));
+    /// let a = sig.new_op(0, Some("A".to_string()));
+    /// let term = parse_term(&mut sig, "F(A x_)").expect("parse of F(A x_)");
+    ///
+    /// let kbo = Kbo::new(vec![(f.clone(), 3), (a.clone(), 2)], 1, vec![f, a]).expect("admissible weights");
+    ///
+    /// assert_eq!(kbo.term_weight(&term), 6);
+    /// ```
+    pub fn term_weight(&self, term: &Term) -> usize {
+        match *term {
+            Term::Variable(_) => self.variable_weight,
+            Term::Application { ref op, ref args } => {
+                self.weight(op) + args.iter().map(|arg| self.term_weight(arg)).sum::<usize>()
+            }
+        }
+    }
+    /// The [`Precedence`] underlying the `Kbo`.
+    ///
+    /// [`Precedence`]: struct.Precedence.html
+    pub fn precedence(&self) -> &Precedence {
+        &self.precedence
+    }
+}
+impl ReductionOrdering for Kbo {
+    fn gt(&self, s: &Term, t: &Term) -> bool {
+        let s_vars = s.variable_occurrences();
+        let t_vars = t.variable_occurrences();
+        let enough_vars = t_vars
+            .iter()
+            .all(|(v, n)| s_vars.iter().any(|(w, m)| w == v && m >= n));
+        if !enough_vars {
+            return false;
+        }
+        let (ws, wt) = (self.term_weight(s), self.term_weight(t));
+        if ws != wt {
+            return ws > wt;
+        }
+        match (s, t) {
+            (Term::Variable(_), _) => false,
+            // s = f(f(...f(t)...)) for some unary f of weight 0.
+            (_, Term::Variable(_)) => s != t,
+            (
+                Term::Application {
+                    op: ref f,
+                    args: ref ss,
+                },
+                Term::Application {
+                    op: ref g,
+                    args: ref ts,
+                },
+            ) => {
+                if self.precedence.gt(f, g) {
+                    true
+                } else if f == g {
+                    match ss.iter().zip(ts).find(|(si, ti)| si != ti) {
+                        Some((si, ti)) => self.gt(si, ti),
+                        None => false,
+                    }
+                } else {
+                    false
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The error type for [`Kbo::new`], reporting inadmissible weights.
+///
+/// [`Kbo::new`]: struct.Kbo.html#method.new
+pub enum KboError {
+    /// Returned when [`Variable`]s weigh `0`.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    VariableWeightZero,
+    /// Returned when a constant weighs less than a [`Variable`].
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    ConstantTooLight(Operator),
+    /// Returned when a unary [`Operator`] weighs `0` but is not greater than every other
+    /// [`Operator`] in the precedence.
+    ///
+    /// [`Operator`]: ../struct.Operator.html
+    UnaryWeightZero(Operator),
+}
+impl fmt::Display for KboError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KboError::VariableWeightZero => write!(f, "variables must weigh more than 0"),
+            KboError::ConstantTooLight(_) => write!(f, "constant weighs less than a variable"),
+            KboError::UnaryWeightZero(_) => write!(
+                f,
+                "unary operator weighs 0 but is not greatest in the precedence"
+            ),
+        }
+    }
+}
+impl ::std::error::Error for KboError {
+    fn description(&self) -> &'static str {
+        "inadmissible KBO weights"
+    }
+}
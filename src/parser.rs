@@ -1,28 +1,157 @@
 use super::types::*;
 
 use nom::types::CompleteStr;
-use nom::{multispace0, multispace1};
+use nom::{
+    digit, multispace0, multispace1, Context as NomContext, Err as NomErr, ErrorKind, IResult,
+};
+use std::collections::HashMap;
 use std::fmt;
 
 named!(lparen<CompleteStr, CompleteStr>,     tag!("("));
 named!(rparen<CompleteStr, CompleteStr>,     tag!(")"));
+named!(lbracket<CompleteStr, CompleteStr>,   tag!("["));
+named!(rbracket<CompleteStr, CompleteStr>,   tag!("]"));
 named!(pipe<CompleteStr, CompleteStr>,       tag!("|"));
 named!(semicolon<CompleteStr, CompleteStr>,  tag!(";"));
 named!(rule_kw<CompleteStr, CompleteStr>,    tag!("="));
+named!(cond_kw<CompleteStr, CompleteStr>,    tag!("<="));
+named!(comma<CompleteStr, CompleteStr>,      tag!(","));
+named!(weight_kw<CompleteStr, CompleteStr>,  tag!("::"));
+named!(sort_kw<CompleteStr, CompleteStr>,    tag!(":"));
+named!(arrow<CompleteStr, CompleteStr>,      tag!("->"));
 named!(underscore<CompleteStr, CompleteStr>, tag!("_"));
-named!(identifier<CompleteStr, CompleteStr>, is_not!("[!]| #_:()=;"));
+// '#' only starts a comment at the start of an identifier, so "F#" is an identifier
+named!(identifier<CompleteStr, String>,
+       alt!(quoted_name |
+            map!(preceded!(not!(cond_kw),
+                           recognize!(pair!(is_not!("[!]| #_:()=;,"),
+                                            opt!(is_not!("[!]| _:()=;,"))))),
+                 |s| s.0.to_string())));
+named!(ops_kw<CompleteStr, CompleteStr>,     tag!("ops"));
+named!(slash<CompleteStr, CompleteStr>,      tag!("/"));
+named!(op_name<CompleteStr, String>,
+       alt!(quoted_name |
+            map!(preceded!(not!(cond_kw),
+                           recognize!(pair!(is_not!("[!]| #_:()=;,/"),
+                                            opt!(is_not!("[!]| _:()=;,/"))))),
+                 |s| s.0.to_string())));
+named!(arity<CompleteStr, u32>,
+       map_res!(digit, |d: CompleteStr| d.0.parse::<u32>()));
+named!(sort_name<CompleteStr, CompleteStr>,
+       is_not!("[!]| #_:()=;,->"));
+named!(weight<CompleteStr, f64>,
+       map_res!(is_a!("0123456789.eE+-"), |w: CompleteStr| w.0.parse::<f64>()));
+
+// A double-quoted string literal.
+fn string_literal(input: CompleteStr) -> IResult<CompleteStr, String> {
+    quoted(input, '"')
+}
+
+// A bare name made only of digits, which isn't applied like an operator or named like a
+// variable.
+fn numeral_literal(input: CompleteStr) -> IResult<CompleteStr, String> {
+    let len = bare_len(input.0, DELIMITERS);
+    let (numeral, rest) = input.0.split_at(len);
+    if is_numeral(numeral) && !rest.starts_with(['(', '_']) {
+        Ok((CompleteStr(rest), numeral.to_string()))
+    } else {
+        Err(NomErr::Error(NomContext::Code(input, ErrorKind::Custom(2))))
+    }
+}
+
+pub(crate) fn is_numeral(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(|b| b.is_ascii_digit())
+}
+
+// A single-quoted name, which may contain any character.
+fn quoted_name(input: CompleteStr) -> IResult<CompleteStr, String> {
+    quoted(input, '\'')
+}
+
+// Non-empty text between `quote`s, with `\"`, `\'`, `\\`, `\n`, and `\t` escapes.
+// Malformed text is a failure rather than an error, so it isn't read as an
+// identifier instead.
+fn quoted(input: CompleteStr, quote: char) -> IResult<CompleteStr, String> {
+    let code = NomContext::Code(input, ErrorKind::Custom(1));
+    let mut chars = input.0.char_indices();
+    if chars.next().map(|(_, c)| c) != Some(quote) {
+        return Err(NomErr::Error(code));
+    }
+    let mut string = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c == quote && string.is_empty() => break,
+            c if c == quote => return Ok((CompleteStr(&input.0[i + 1..]), string)),
+            '\\' => match chars.next().and_then(|(_, c)| unescape(c)) {
+                Some(c) => string.push(c),
+                None => break,
+            },
+            c => string.push(c),
+        }
+    }
+    Err(NomErr::Failure(code))
+}
+
+// The character an escape sequence `\c` stands for.
+fn unescape(c: char) -> Option<char> {
+    match c {
+        '"' | '\'' | '\\' => Some(c),
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        _ => None,
+    }
+}
 
 #[derive(Debug, PartialEq)]
 /// The error type for parsing operations.
 pub enum ParseError {
+    /// Only part of the input could be read.
     ParseIncomplete,
+    /// The input could not be read, or describes an invalid [`Rule`] or
+    /// [`RuleContext`] (e.g. one whose right-hand side has unbound variables).
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`RuleContext`]: struct.RuleContext.html
     ParseFailed,
+    /// The input is syntactically malformed.
+    Syntax(SyntaxError),
+    IllSorted(SortError),
+    /// Strict parsing found an [`Operator`] with this name and arity which is not in the
+    /// [`Signature`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Signature`]: struct.Signature.html
+    UnknownOperator(String, u32),
+    /// Strict parsing found a [`Variable`] with this name which is not in the [`Signature`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    UnknownVariable(String),
+    /// An [`Operator`] with this name was declared with the first arity but used or
+    /// redeclared with the second.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    ArityMismatch(String, u32, u32),
+    /// Reading the input failed with this message.
+    Io(String),
 }
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ParseError::ParseIncomplete => write!(f, "incomplete parse"),
             ParseError::ParseFailed => write!(f, "failed parse"),
+            ParseError::Syntax(ref e) => write!(f, "{}", e),
+            ParseError::IllSorted(ref e) => write!(f, "{}", e),
+            ParseError::UnknownOperator(ref name, arity) => {
+                write!(f, "unknown operator {} with arity {}", name, arity)
+            }
+            ParseError::UnknownVariable(ref name) => write!(f, "unknown variable {}_", name),
+            ParseError::ArityMismatch(ref name, declared, found) => write!(
+                f,
+                "operator {} declared with arity {} but used with arity {}",
+                name, declared, found
+            ),
+            ParseError::Io(ref message) => write!(f, "I/O error: {}", message),
         }
     }
 }
@@ -32,6 +161,150 @@ impl ::std::error::Error for ParseError {
     }
 }
 
+/// The location of a syntax error, together with the offending token and the tokens the parser
+/// would have accepted in its place.
+///
+/// Its `Display` shows the offending line with a caret under the token.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, ParseError, parse_trs};
+/// let mut sig = Signature::default();
+/// let e = match parse_trs(&mut sig, "A = B;\nF(x_ = x_;") {
+///     Err(ParseError::Syntax(e)) => e,
+///     res => panic!("unexpected result {:?}", res),
+/// };
+///
+/// assert_eq!((e.line, e.column), (2, 6));
+/// assert_eq!(e.found, Some("=".to_string()));
+/// assert_eq!(e.expected, vec!["term", "`)`"]);
+/// assert_eq!(
+///     e.to_string(),
+///     "2:6: expected term or `)`, found `=`\nF(x_ = x_;\n     ^"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    /// The byte offset of the offending token in the input.
+    pub offset: usize,
+    /// The line of the offending token, counting from 1.
+    pub line: usize,
+    /// The column of the offending token in characters, counting from 1.
+    pub column: usize,
+    /// The offending token, or `None` at the end of the input.
+    pub found: Option<String>,
+    /// The tokens and constructs (e.g. `term`) which would have been accepted instead.
+    pub expected: Vec<String>,
+    /// The line of input containing the offending token.
+    pub source: String,
+}
+impl SyntaxError {
+    pub(crate) fn new(input: &str, offset: usize, expected: Vec<String>) -> SyntaxError {
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        SyntaxError {
+            offset,
+            line: input[..offset].matches('\n').count() + 1,
+            column: input[line_start..offset].chars().count() + 1,
+            found: token_at(&input[offset..]),
+            expected,
+            source: input[line_start..].lines().next().unwrap_or("").to_string(),
+        }
+    }
+}
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let found = match self.found {
+            Some(ref t) if t.trim().is_empty() => "whitespace".to_string(),
+            Some(ref t) => format!("`{}`", t),
+            None => "end of input".to_string(),
+        };
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match self.expected.split_last() {
+            None => write!(f, "unexpected {}", found)?,
+            Some((last, [])) => write!(f, "expected {}, found {}", last, found)?,
+            Some((last, init)) => write!(
+                f,
+                "expected {} or {}, found {}",
+                init.join(", "),
+                last,
+                found
+            )?,
+        }
+        // keep tabs so the caret lines up however the line is rendered
+        let indent: String = self
+            .source
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = self.found.as_ref().map_or(1, |t| t.chars().count());
+        write!(f, "\n{}\n{}{}", self.source, indent, "^".repeat(width))
+    }
+}
+impl ::std::error::Error for SyntaxError {
+    fn description(&self) -> &'static str {
+        "syntax error"
+    }
+}
+
+// The characters which end an identifier.
+pub(crate) const DELIMITERS: &str = "[!]| #_:()=;,";
+
+// The length of the bare identifier at the start of `input`, which ends at any
+// of `delimiters`, except for a '#' after its first character.
+fn bare_len(input: &str, delimiters: &str) -> usize {
+    let mut chars = input.char_indices();
+    match chars.next() {
+        Some((_, c)) if !delimiters.contains(c) => chars
+            .find(|&(_, c)| c != '#' && delimiters.contains(c))
+            .map_or(input.len(), |(i, _)| i),
+        _ => 0,
+    }
+}
+
+// `name` as the parser reads it: bare if it is an identifier, else quoted.
+pub(crate) fn quote_name(name: &str) -> String {
+    let bare = !name.is_empty()
+        && !name.starts_with(['\'', '"'])
+        && !name.starts_with("<=")
+        && !name.contains(char::is_whitespace)
+        && bare_len(name, DELIMITERS) == name.len();
+    if bare {
+        return name.to_string();
+    }
+    let mut quoted = String::from("'");
+    for c in name.chars() {
+        match c {
+            '\'' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+// The token at the start of `input`, if any.
+fn token_at(input: &str) -> Option<String> {
+    let first = input.chars().next()?;
+    if let Some(t) = ["<=", "::", "->", "[!]"]
+        .iter()
+        .find(|t| input.starts_with(*t))
+    {
+        Some(t.to_string())
+    } else if first.is_whitespace() || DELIMITERS.contains(first) {
+        Some(first.to_string())
+    } else {
+        let identifier = &input[..bare_len(input, DELIMITERS)];
+        identifier.split_whitespace().next().map(str::to_string)
+    }
+}
+
 /// Parse a string as a [`TRS`] and a list of [`Term`]s.
 ///
 /// ```
@@ -53,7 +326,7 @@ impl ::std::error::Error for ParseError {
 /// [`TRS`]: struct.TRS.html
 /// [`Term`]: enum.Term.html
 pub fn parse(sig: &mut Signature, input: &str) -> Result<(TRS, Vec<Term>), ParseError> {
-    let (_parser, result) = Parser::new(sig).program(CompleteStr(input));
+    let (mut parser, result) = Parser::new(sig).program(CompleteStr(input));
     match result {
         Ok((CompleteStr(""), stmts)) => {
             let mut terms = Vec::new();
@@ -62,24 +335,42 @@ pub fn parse(sig: &mut Signature, input: &str) -> Result<(TRS, Vec<Term>), Parse
                 match stmt {
                     Statement::Term(t) => terms.push(t),
                     Statement::Rule(r) => rules.push(r),
+                    Statement::Declaration(_) | Statement::Operators(_) => (),
                 }
             }
-            Ok((TRS::new(rules), terms))
+            parser.known((TRS::new(rules), terms))
         }
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        _ => Err(parser.error(syntax_error(input, Start::Program))),
     }
 }
 
 /// Parse a string as a [`TRS`].
 ///
+/// An `ops` block declares the arities of [`Operator`]s, and using a declared [`Operator`]
+/// with another arity in the rules that follow it is an error.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, ParseError, parse_trs};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig, "ops: CONS/2, NIL/0; LEN(CONS(x_ y_)) = LEN(y_);")
+///     .expect("parse of TRS with operators block");
+/// assert_eq!(trs.len(), 1);
+///
+/// assert_eq!(
+///     parse_trs(&mut sig, "ops: CONS/2, NIL/0; LEN(CONS(x_)) = NIL;"),
+///     Err(ParseError::ArityMismatch("CONS".to_string(), 2, 1)),
+/// );
+/// ```
+///
 /// [`TRS`]: struct.TRS.html
+/// [`Operator`]: struct.Operator.html
 pub fn parse_trs(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
-    let (_parser, result) = Parser::new(sig).trs(CompleteStr(input));
+    let (mut parser, result) = Parser::new(sig).trs(CompleteStr(input));
     match result {
-        Ok((CompleteStr(""), trs)) => Ok(trs),
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        Ok((CompleteStr(""), trs)) => parser.known(trs),
+        _ => Err(parser.error(syntax_error(input, Start::Trs))),
     }
 }
 
@@ -87,23 +378,260 @@ pub fn parse_trs(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
 ///
 /// [`Rule`]: struct.Rule.html
 pub fn parse_rule(sig: &mut Signature, input: &str) -> Result<Rule, ParseError> {
-    let (_parser, result) = Parser::new(sig).rule(CompleteStr(input));
+    let (mut parser, result) = Parser::new(sig).rule(CompleteStr(input));
     match result {
         Ok((CompleteStr(""), rule)) => Ok(rule),
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        _ => Err(parser.error(syntax_error(input, Start::Rule))),
     }
 }
 
 /// Parse a string as a [`Term`].
 ///
+/// List literals like `[A, B]` desugar into the `CONS` and `NIL` [`Operator`]s, and string
+/// literals like `"ab"` into the binary application of their characters, `('a' 'b')`, so
+/// much of the output of [`Term::pretty`] can be read back. Read all of it with
+/// [`parse_term_pretty`]: here, a name made only of digits is an identifier rather than a
+/// number, and `F(A B)` applies `F` to two arguments rather than to `(A B)`.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term};
+/// let mut sig = Signature::default();
+/// let term = parse_term(&mut sig, "F([A, B(x_)] [])").expect("parse of F([A, B(x_)] [])");
+///
+/// assert_eq!(term.display(&sig), "F(CONS(A CONS(B(x_) NIL)) NIL)");
+/// assert_eq!(term.pretty(&sig), "F([A, B(x_)], [])");
+///
+/// let term = parse_term(&mut sig, r#""ab""#).expect("parse of \"ab\"");
+/// assert_eq!(term.display(&sig), r".('\'a\'' '\'b\'')");
+/// assert_eq!(term.pretty(&sig), r#""ab""#);
+/// ```
+///
 /// [`Term`]: enum.Term.html
+/// [`Operator`]: struct.Operator.html
+/// [`Term::pretty`]: enum.Term.html#method.pretty
+/// [`parse_term_pretty`]: fn.parse_term_pretty.html
 pub fn parse_term(sig: &mut Signature, input: &str) -> Result<Term, ParseError> {
-    let (_parser, result) = Parser::new(sig).top_term(CompleteStr(input));
+    let (mut parser, result) = Parser::new(sig).sorted_term(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), t)) => Ok(t),
+        _ => Err(parser.error(syntax_error(input, Start::Term))),
+    }
+}
+
+/// Parse a string as a [`TRS`], like [`parse_trs`], but without creating any [`Operator`]s
+/// or [`Variable`]s: a symbol missing from `sig` is an error. [`Variable`]s are looked up by
+/// name among all those in `sig`, and declarations may still introduce [`Operator`]s, as
+/// they do so explicitly.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, ParseError, parse_trs_strict};
+/// let mut sig = Signature::default();
+/// sig.new_op(1, Some("SUCC".to_string()));
+/// sig.new_op(2, Some("PLUS".to_string()));
+/// sig.new_var(Some("x".to_string()));
+///
+/// let trs = parse_trs_strict(&mut sig, "ZERO : Nat; PLUS(ZERO x_) = x_;")
+///     .expect("parse of PLUS(ZERO x_) = x_;");
+/// assert_eq!(trs.display(&sig), "PLUS(ZERO x_) = x_;");
+///
+/// assert_eq!(
+///     parse_trs_strict(&mut sig, "PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));"),
+///     Err(ParseError::UnknownVariable("y".to_string())),
+/// );
+/// assert_eq!(
+///     parse_trs_strict(&mut sig, "PLUS(SUCC(x_) x_) = SUCC(PLUS(x_ ZREO));"),
+///     Err(ParseError::UnknownOperator("ZREO".to_string(), 0)),
+/// );
+/// assert_eq!(sig.operators().len(), 3);
+/// assert_eq!(sig.variables().len(), 1);
+/// ```
+///
+/// [`TRS`]: struct.TRS.html
+/// [`parse_trs`]: fn.parse_trs.html
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+pub fn parse_trs_strict(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
+    let (mut parser, result) = Parser::new_strict(sig).trs(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), trs)) => parser.known(trs),
+        _ => Err(parser.error(syntax_error(input, Start::Trs))),
+    }
+}
+
+/// Parse a string as a [`Term`], like [`parse_term`], but without creating any [`Operator`]s
+/// or [`Variable`]s: a symbol missing from `sig` is an error. [`Variable`]s are looked up by
+/// name among all those in `sig`.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, ParseError, parse_term_strict};
+/// let mut sig = Signature::default();
+/// sig.new_op(2, Some("A".to_string()));
+/// sig.new_op(0, Some("B".to_string()));
+/// sig.new_var(Some("x".to_string()));
+///
+/// let term = parse_term_strict(&mut sig, "A(B x_)").expect("parse of A(B x_)");
+/// assert_eq!(term.display(&sig), "A(B x_)");
+///
+/// assert_eq!(
+///     parse_term_strict(&mut sig, "A(B C)"),
+///     Err(ParseError::UnknownOperator("C".to_string(), 0)),
+/// );
+/// assert_eq!(sig.operators().len(), 2);
+/// ```
+///
+/// [`Term`]: enum.Term.html
+/// [`parse_term`]: fn.parse_term.html
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+pub fn parse_term_strict(sig: &mut Signature, input: &str) -> Result<Term, ParseError> {
+    let (mut parser, result) = Parser::new_strict(sig).sorted_term(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), t)) => parser.known(t),
+        _ => Err(parser.error(syntax_error(input, Start::Term))),
+    }
+}
+
+/// Parse a string as a [`TRS`], like [`parse_trs`], but desugaring list literals into the
+/// given `cons` and `nil` [`Operator`]s rather than `CONS` and `NIL`.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs_with_lists};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs_with_lists(&mut sig, "LEN([]) = 0; LEN([x_, y_]) = 2;", "PAIR", "UNIT")
+///     .expect("parse of TRS with lists");
+///
+/// assert_eq!(
+///     trs.display(&sig),
+///     "LEN(UNIT) = 0;\nLEN(PAIR(x_ PAIR(y_ UNIT))) = 2;"
+/// );
+/// ```
+///
+/// [`TRS`]: struct.TRS.html
+/// [`parse_trs`]: fn.parse_trs.html
+/// [`Operator`]: struct.Operator.html
+pub fn parse_trs_with_lists(
+    sig: &mut Signature,
+    input: &str,
+    cons: &str,
+    nil: &str,
+) -> Result<TRS, ParseError> {
+    let mut parser = Parser::new(sig);
+    parser.lists = (cons.to_string(), nil.to_string());
+    let (mut parser, result) = parser.trs(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), trs)) => parser.known(trs),
+        _ => Err(parser.error(syntax_error(input, Start::Trs))),
+    }
+}
+
+/// Parse a string as a [`Term`], like [`parse_term`], but desugaring list literals into the
+/// given `cons` and `nil` [`Operator`]s rather than `CONS` and `NIL`.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term_with_lists};
+/// let mut sig = Signature::default();
+/// let term = parse_term_with_lists(&mut sig, "[A, [B]]", "PAIR", "UNIT")
+///     .expect("parse of [A, [B]]");
+///
+/// assert_eq!(term.display(&sig), "PAIR(A PAIR(PAIR(B UNIT) UNIT))");
+/// ```
+///
+/// [`Term`]: enum.Term.html
+/// [`parse_term`]: fn.parse_term.html
+/// [`Operator`]: struct.Operator.html
+pub fn parse_term_with_lists(
+    sig: &mut Signature,
+    input: &str,
+    cons: &str,
+    nil: &str,
+) -> Result<Term, ParseError> {
+    let mut parser = Parser::new(sig);
+    parser.lists = (cons.to_string(), nil.to_string());
+    let (mut parser, result) = parser.sorted_term(CompleteStr(input));
     match result {
         Ok((CompleteStr(""), t)) => Ok(t),
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        _ => Err(parser.error(syntax_error(input, Start::Term))),
+    }
+}
+
+/// Parse a string as a [`TRS`], like [`parse_trs`], but reading the output of
+/// [`TRS::pretty`], which differs in two ways, whatever the [`Signature`]:
+///
+/// - A bare name made only of digits is a number: `DIGIT` and `DECC` applied to the nullary
+///   [`Operator`]s named by its digits, so `42` is `DECC(DIGIT(4) 2)`. A quoted name, such as
+///   `'42'`, is still an identifier.
+/// - Arguments are separated only by commas, so juxtaposed items are a binary application:
+///   `F(A B)` is `F(.(A B))`.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs, parse_trs_pretty};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig, "F(x_) = G(.(x_ DECC(DIGIT(4) 2)) '7');")
+///     .expect("parse of F(x_) = G(.(x_ DECC(DIGIT(4) 2)) '7');");
+///
+/// let pretty = trs.pretty(&sig);
+/// assert_eq!(pretty, "F(x_) = G(x_ 42, '7');");
+///
+/// let trs2 = parse_trs_pretty(&mut sig, &pretty).expect("parse of F(x_) = G(x_ 42, '7');");
+/// assert_eq!(trs2.display(&sig), trs.display(&sig));
+/// ```
+///
+/// [`TRS`]: struct.TRS.html
+/// [`parse_trs`]: fn.parse_trs.html
+/// [`TRS::pretty`]: struct.TRS.html#method.pretty
+/// [`Signature`]: struct.Signature.html
+/// [`Operator`]: struct.Operator.html
+pub fn parse_trs_pretty(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
+    let mut parser = Parser::new(sig);
+    parser.pretty = true;
+    let (mut parser, result) = parser.trs(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), trs)) => parser.known(trs),
+        _ => Err(parser.error(syntax_error(input, Start::Trs))),
+    }
+}
+
+/// Parse a string as a [`Term`], like [`parse_term`], but reading the output of
+/// [`Term::pretty`], as [`parse_trs_pretty`] does.
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term, parse_term_pretty};
+/// let mut sig = Signature::default();
+/// let term = parse_term(&mut sig, "F(DECC(DIGIT(4) 2) 10)").expect("parse of F(DECC(DIGIT(4) 2) 10)");
+/// assert_eq!(term.pretty(&sig), "F(42, '10')");
+///
+/// let term = parse_term_pretty(&mut sig, "F(42, '10')").expect("parse of F(42, '10')");
+/// assert_eq!(term.display(&sig), "F(DECC(DIGIT(4) 2) 10)");
+///
+/// let term = parse_term_pretty(&mut sig, "F(4 2)").expect("parse of F(4 2)");
+/// assert_eq!(term.display(&sig), "F(.(DIGIT(4) DIGIT(2)))");
+/// ```
+///
+/// [`Term`]: enum.Term.html
+/// [`parse_term`]: fn.parse_term.html
+/// [`Term::pretty`]: enum.Term.html#method.pretty
+/// [`parse_trs_pretty`]: fn.parse_trs_pretty.html
+pub fn parse_term_pretty(sig: &mut Signature, input: &str) -> Result<Term, ParseError> {
+    let mut parser = Parser::new(sig);
+    parser.pretty = true;
+    let (mut parser, result) = parser.sorted_term(CompleteStr(input));
+    match result {
+        Ok((CompleteStr(""), t)) => Ok(t),
+        _ => Err(parser.error(syntax_error(input, Start::Term))),
     }
 }
 
@@ -114,8 +642,7 @@ pub fn parse_rulecontext(sig: &mut Signature, input: &str) -> Result<RuleContext
     let (_parser, result) = Parser::new(sig).rulecontext(CompleteStr(input));
     match result {
         Ok((CompleteStr(""), r)) => Ok(r),
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        _ => Err(syntax_error(input, Start::RuleContext)),
     }
 }
 
@@ -126,21 +653,417 @@ pub fn parse_context(sig: &mut Signature, input: &str) -> Result<Context, ParseE
     let (_parser, result) = Parser::new(sig).top_context(CompleteStr(input));
     match result {
         Ok((CompleteStr(""), c)) => Ok(c),
-        Ok((CompleteStr(_), _)) => Err(ParseError::ParseIncomplete),
-        Err(_) => Err(ParseError::ParseFailed),
+        _ => Err(syntax_error(input, Start::Context)),
+    }
+}
+
+// The grammar rule a parse starts from.
+#[derive(Clone, Copy)]
+enum Start {
+    Program,
+    Trs,
+    Rule,
+    Term,
+    Context,
+    RuleContext,
+}
+
+// The error to report for input the parser rejected: a `SyntaxError` if the
+// input is malformed, else `ParseFailed`, as the input is well-formed but
+// describes something invalid (e.g. a rule with unbound variables).
+fn syntax_error(input: &str, start: Start) -> ParseError {
+    match Checker::diagnose(input, start) {
+        Some((offset, expected)) => ParseError::Syntax(SyntaxError::new(input, offset, expected)),
+        None => ParseError::ParseFailed,
+    }
+}
+
+// The position after a successful check.
+type Check = Option<usize>;
+
+// A recognizer for the grammar which, unlike the parser, reports where it
+// failed: at the farthest position any alternative reached, together with
+// everything that would have let it continue there. It only runs after the
+// parser has rejected the input.
+struct Checker<'a> {
+    input: &'a str,
+    farthest: usize,
+    expected: Vec<String>,
+}
+impl<'a> Checker<'a> {
+    // The offset and expectations of the first syntax error in `input`, if any.
+    fn diagnose(input: &str, start: Start) -> Option<(usize, Vec<String>)> {
+        let mut c = Checker {
+            input,
+            farthest: 0,
+            expected: vec![],
+        };
+        let end = match start {
+            Start::Program => Some(c.statements(0, true)),
+            Start::Trs => Some(c.statements(0, false)),
+            Start::Rule => c.rule(0),
+            Start::Term => c.top_term(0, false),
+            Start::Context => c.top_term(0, true),
+            Start::RuleContext => c.rulecontext(0),
+        };
+        match end {
+            Some(end) if end == input.len() => None,
+            Some(end) => {
+                c.fail(end, "end of input");
+                Some((c.farthest, c.expected))
+            }
+            None => Some((c.farthest, c.expected)),
+        }
+    }
+    fn fail(&mut self, at: usize, what: &str) -> Check {
+        if at > self.farthest {
+            self.farthest = at;
+            self.expected.clear();
+        }
+        if at == self.farthest && !self.expected.iter().any(|e| e == what) {
+            self.expected.push(what.to_string());
+        }
+        None
+    }
+    // Report a failure of `check` which makes no progress past `at` as a
+    // failure to find `what`.
+    fn label<F>(&mut self, at: usize, what: &str, check: F) -> Check
+    where
+        F: FnOnce(&mut Checker<'a>) -> Check,
+    {
+        let (farthest, len) = (self.farthest, self.expected.len());
+        let end = check(self);
+        if end.is_none() && self.farthest == at {
+            if farthest == at {
+                self.expected.truncate(len);
+            } else {
+                self.expected.clear();
+            }
+            self.fail(at, what);
+        }
+        end
+    }
+    fn rest(&self, at: usize) -> &'a str {
+        &self.input[at..]
+    }
+    fn ws(&self, at: usize) -> usize {
+        let rest = self.rest(at);
+        at + rest.len() - rest.trim_start_matches(|c| " \t\r\n".contains(c)).len()
+    }
+    fn ws1(&self, at: usize) -> Check {
+        Some(self.ws(at)).filter(|&end| end > at)
+    }
+    fn token(&mut self, at: usize, token: &str) -> Check {
+        if self.rest(at).starts_with(token) {
+            Some(at + token.len())
+        } else {
+            self.fail(at, &format!("`{}`", token))
+        }
+    }
+    // A token surrounded by optional whitespace.
+    fn separator(&mut self, at: usize, token: &str) -> Check {
+        let at = self.ws(at);
+        let end = self.token(at, token)?;
+        Some(self.ws(end))
+    }
+    fn identifier(&mut self, at: usize) -> Check {
+        self.name(at, DELIMITERS)
+    }
+    // An identifier, quoted or ending at any of `delimiters`.
+    fn name(&mut self, at: usize, delimiters: &str) -> Check {
+        if self.rest(at).starts_with('\'') {
+            return self.quoted(at, '\'');
+        }
+        let rest = self.rest(at);
+        let len = bare_len(rest, delimiters);
+        if len == 0 || rest.starts_with("<=") {
+            self.fail(at, "identifier")
+        } else {
+            Some(at + len)
+        }
+    }
+    // A term, or with `context`, a context.
+    fn term(&mut self, at: usize, context: bool) -> Check {
+        let what = if context { "context" } else { "term" };
+        self.label(at, what, |c| {
+            if c.rest(at).starts_with('"') {
+                return c.quoted(at, '"');
+            }
+            let name = c.identifier(at);
+            if let Some(name) = name {
+                if c.rest(name).starts_with('_') {
+                    return Some(name + 1);
+                }
+                return Some(c.arguments(name, context).unwrap_or(name));
+            }
+            if let Some(end) = c.binary_application(at, context) {
+                return Some(end);
+            }
+            if context {
+                if let Some(end) = c.token(at, "[!]") {
+                    return Some(end);
+                }
+            }
+            if let Some(end) = c.list(at, context) {
+                return Some(end);
+            }
+            let inner = c.token(at, "(")?;
+            let inner = c.top_term(inner, context)?;
+            c.token(inner, ")")
+        })
+    }
+    fn quoted(&mut self, at: usize, quote: char) -> Check {
+        let mut chars = self.rest(at + 1).char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                c if c == quote && i == 0 => return self.fail(at + 1, "character"),
+                c if c == quote => return Some(at + i + 2),
+                '\\' => match chars.next() {
+                    Some((_, c)) if unescape(c).is_some() => (),
+                    Some((j, _)) => return self.fail(at + 1 + j, "escape sequence"),
+                    None => break,
+                },
+                _ => (),
+            }
+        }
+        self.fail(self.input.len(), &format!("`{}`", quote))
+    }
+    fn list(&mut self, at: usize, context: bool) -> Check {
+        let start = self.token(at, "[")?;
+        let mut end = self.ws(start);
+        if let Some(next) = self.top_term(end, context) {
+            end = next;
+            while let Some(next) = self
+                .separator(end, ",")
+                .and_then(|at| self.top_term(at, context))
+            {
+                end = next;
+            }
+        }
+        let end = self.ws(end);
+        self.token(end, "]")
+    }
+    fn arguments(&mut self, at: usize, context: bool) -> Check {
+        if !self.rest(at).starts_with('(') {
+            return None;
+        }
+        let mut end = self.ws(at + 1);
+        if let Some(next) = self.term(end, context) {
+            end = next;
+            while let Some(next) = self
+                .ws1(end)
+                .or_else(|| self.separator(end, ","))
+                .and_then(|sep| self.term(sep, context))
+            {
+                end = next;
+            }
+        }
+        let end = self.ws(end);
+        self.token(end, ")")
+    }
+    fn binary_application(&mut self, at: usize, context: bool) -> Check {
+        if !self.rest(at).starts_with('(') {
+            return None;
+        }
+        let first = self.ws(at + 1);
+        let first = self.term(first, context)?;
+        let second = self.ws1(first)?;
+        let second = self.term(second, context)?;
+        let end = self.ws(second);
+        self.token(end, ")")
+    }
+    // A top-level term, or with `context`, a top-level context.
+    fn top_term(&mut self, at: usize, context: bool) -> Check {
+        let at = self.ws(at);
+        let mut end = self.top_item(at, context)?;
+        while let Some(next) = self.ws1(end).and_then(|sep| self.top_item(sep, context)) {
+            end = next;
+        }
+        Some(self.ws(end))
+    }
+    fn top_item(&mut self, at: usize, context: bool) -> Check {
+        if !context {
+            return self.term(at, false);
+        }
+        self.label(at, "context", |c| {
+            if let Some(end) = c.term(at, true) {
+                return Some(end);
+            }
+            let inner = c.token(at, "(")?;
+            let inner = c.top_term(inner, true)?;
+            c.token(inner, ")")
+        })
+    }
+    fn rule(&mut self, at: usize) -> Check {
+        let lhs = self.top_term(at, false)?;
+        let rhs = self.separator(lhs, "=")?;
+        let mut end = self.clause(rhs)?;
+        while let Some(next) = self.separator(end, "|").and_then(|at| self.clause(at)) {
+            end = next;
+        }
+        if let Some(conditions) = self.separator(end, "<=") {
+            if let Some(next) = self.conditions(conditions) {
+                end = next;
+            }
+        }
+        Some(self.ws(end))
+    }
+    fn clause(&mut self, at: usize) -> Check {
+        let end = self.top_term(at, false)?;
+        Some(self.weight(end).unwrap_or(end))
+    }
+    fn weight(&mut self, at: usize) -> Check {
+        let at = self.separator(at, "::")?;
+        let rest = self.rest(at);
+        let len = rest.len()
+            - rest
+                .trim_start_matches(|c| "0123456789.eE+-".contains(c))
+                .len();
+        if len > 0 && rest[..len].parse::<f64>().is_ok() {
+            Some(at + len)
+        } else {
+            self.fail(at, "weight")
+        }
+    }
+    fn conditions(&mut self, at: usize) -> Check {
+        let mut end = self.condition(at)?;
+        while let Some(next) = self.separator(end, ",").and_then(|at| self.condition(at)) {
+            end = next;
+        }
+        Some(end)
+    }
+    fn condition(&mut self, at: usize) -> Check {
+        let lhs = self.top_term(at, false)?;
+        let rhs = self.separator(lhs, "=")?;
+        self.top_term(rhs, false)
+    }
+    fn rulecontext(&mut self, at: usize) -> Check {
+        let lhs = self.top_term(at, true)?;
+        let rhs = self.separator(lhs, "=")?;
+        let mut end = self.top_term(rhs, true)?;
+        while let Some(next) = self
+            .separator(end, "|")
+            .and_then(|at| self.top_term(at, true))
+        {
+            end = next;
+        }
+        Some(end)
+    }
+    fn declaration(&mut self, at: usize) -> Check {
+        let name = self.ws(at);
+        let name = self.identifier(name)?;
+        let mut end = self.separator(name, ":")?;
+        end = self.sort(end)?;
+        while let Some(next) = self.separator(end, "->").and_then(|at| self.sort(at)) {
+            end = next;
+        }
+        Some(self.ws(end))
+    }
+    fn operators(&mut self, at: usize) -> Check {
+        let at = self.ws(at);
+        if !self.rest(at).starts_with("ops") {
+            return None;
+        }
+        let mut end = self.separator(at + 3, ":")?;
+        end = self.operator_arity(end)?;
+        while let Some(next) = self
+            .separator(end, ",")
+            .and_then(|at| self.operator_arity(at))
+        {
+            end = next;
+        }
+        Some(self.ws(end))
+    }
+    fn operator_arity(&mut self, at: usize) -> Check {
+        let at = self.name(at, "[!]| #_:()=;,/")?;
+        let at = self.token(at, "/")?;
+        let rest = self.rest(at);
+        let len = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if len == 0 || rest[..len].parse::<u32>().is_err() {
+            self.fail(at, "arity")
+        } else {
+            Some(at + len)
+        }
+    }
+    fn sort(&mut self, at: usize) -> Check {
+        let rest = self.rest(at);
+        let len = rest.len()
+            - rest
+                .trim_start_matches(|c| !"[!]| #_:()=;,->".contains(c))
+                .len();
+        if len == 0 {
+            self.fail(at, "sort")
+        } else {
+            Some(at + len)
+        }
+    }
+    fn comments(&mut self, mut at: usize) -> usize {
+        loop {
+            at = self.ws(at);
+            if !self.rest(at).starts_with('#') {
+                return at;
+            }
+            match self.rest(at).find('\n') {
+                Some(newline) => at += newline + 1,
+                None => {
+                    self.fail(self.input.len(), "newline");
+                    return at;
+                }
+            }
+        }
+    }
+    // Semicolon-terminated statements, including terms if `terms`.
+    fn statements(&mut self, at: usize, terms: bool) -> usize {
+        let mut end = self.ws(at);
+        loop {
+            let start = self.comments(end);
+            let statement = self.label(start, "statement", |c| {
+                c.operators(start)
+                    .or_else(|| c.declaration(start))
+                    .or_else(|| c.rule(start))
+                    .or_else(|| {
+                        if terms {
+                            c.top_term(start, false)
+                        } else {
+                            None
+                        }
+                    })
+            });
+            match statement.and_then(|next| self.separator(next, ";")) {
+                Some(next) => end = self.comments(next),
+                None => return self.ws(end),
+            }
+        }
     }
 }
 
+// The conditions of a conditional rule.
+type Conditions = Vec<(Term, Term)>;
+// A RHS and its optional weight.
+type Clause = (Term, Option<f64>);
+
 #[derive(Debug, PartialEq)]
 pub enum Statement {
     Term(Term),
     Rule(Rule),
+    Declaration(Operator),
+    Operators(Vec<Operator>),
 }
 
 #[derive(Debug)]
 pub struct Parser<'a> {
     sig: &'a mut Signature,
     dv: usize,
+    sort_error: Option<SortError>,
+    // whether to refuse to create symbols, and the first one refused
+    strict: bool,
+    unknown: Option<ParseError>,
+    // the names of the (cons, nil) operators list literals desugar to
+    lists: (String, String),
+    // the arities of operators declared by `ops` blocks
+    arities: HashMap<String, u32>,
+    // whether to read `pretty` output, with numerals and only comma-separated arguments
+    pretty: bool,
 }
 impl<'a> Parser<'a> {
     /// Returns `Some(v)` where `v` has the lowest `id` of any [`Variable`] in
@@ -151,19 +1074,16 @@ impl<'a> Parser<'a> {
         if name == "" {
             None
         } else {
+            let first = if self.strict { 0 } else { self.dv };
             self.sig
                 .sig
                 .read()
                 .expect("poisoned signature")
-                .variables
+                .var_names
+                .get(name)?
                 .iter()
-                .enumerate()
-                .skip(self.dv)
-                .find(|&(_, ref var_name)| var_name.as_ref().map(String::as_str) == Some(name))
-                .map(|(id, _)| Variable {
-                    id,
-                    sig: self.sig.clone(),
-                })
+                .find(|&&id| id >= first)
+                .map(|&id| Variable { id })
         }
     }
     /// Returns a [`Variable`] `v` where `v` has the lowest `id` of any [`Variable`] in
@@ -173,6 +1093,10 @@ impl<'a> Parser<'a> {
     pub fn get_var(&mut self, name: &str) -> Variable {
         match self.has_var(name) {
             Some(var) => var,
+            None if self.strict => {
+                self.refuse(ParseError::UnknownVariable(name.to_string()));
+                Variable { id: usize::MAX }
+            }
             None => self.sig.new_var(Some(name.to_string())),
         }
     }
@@ -182,26 +1106,36 @@ impl<'a> Parser<'a> {
     ///
     /// [`Operator`]: struct.Operator.html
     pub fn has_op(&self, name: &str, arity: u32) -> Option<Operator> {
-        self.sig
-            .sig
-            .read()
-            .expect("poisoned signature")
-            .operators
-            .iter()
-            .enumerate()
-            .find(|&(_, &(op_arity, ref op_name))| {
-                op_arity == arity && op_name.as_ref().map(String::as_str) == Some(name)
-            })
-            .map(|(id, _)| Operator {
-                id,
-                sig: self.sig.clone(),
-            })
+        self.sig.operator(name, arity)
     }
     /// Returns an [`Operator`] with the given `name` with arity `arity`,
     /// creating it if necessary.
     ///
     /// [`Operator`]: struct.Operator.html
     pub fn get_op(&mut self, name: &str, arity: u32) -> Operator {
+        match (self.arities.get(name), self.has_op(name, arity)) {
+            (Some(&declared), _) if declared != arity => {
+                self.refuse(ParseError::ArityMismatch(name.to_string(), declared, arity));
+                self.placeholder_op(arity)
+            }
+            (_, Some(op)) => op,
+            (_, None) if self.strict => {
+                self.refuse(ParseError::UnknownOperator(name.to_string(), arity));
+                self.placeholder_op(arity)
+            }
+            (_, None) => self.sig.new_op(arity, Some(name.to_string())),
+        }
+    }
+    // Stands in for an operator `get_op` refused to return.
+    fn placeholder_op(&self, arity: u32) -> Operator {
+        Operator {
+            id: usize::MAX,
+            arity,
+        }
+    }
+    // Returns the operator named by a declaration, creating it if necessary,
+    // even in strict mode.
+    fn declare_op(&mut self, name: &str, arity: u32) -> Operator {
         match self.has_op(name, arity) {
             Some(op) => op,
             None => self.sig.new_op(arity, Some(name.to_string())),
@@ -213,16 +1147,178 @@ impl<'a> Parser<'a> {
     }
     pub fn new(sig: &'a mut Signature) -> Parser<'a> {
         let dv = sig.variables().len();
-        Parser { sig, dv }
+        Parser {
+            sig,
+            dv,
+            sort_error: None,
+            strict: false,
+            unknown: None,
+            lists: ("CONS".to_string(), "NIL".to_string()),
+            arities: HashMap::new(),
+            pretty: false,
+        }
+    }
+    // A parser which refuses to create symbols, standing in placeholders for
+    // them so parsing can go on, and failing once it is done.
+    fn new_strict(sig: &'a mut Signature) -> Parser<'a> {
+        let mut parser = Parser::new(sig);
+        parser.strict = true;
+        parser
+    }
+    fn refuse(&mut self, error: ParseError) {
+        if self.unknown.is_none() {
+            self.unknown = Some(error);
+        }
+    }
+    // Desugar a string literal into a left-nested application of its characters.
+    fn desugar_string<T, F>(&mut self, string: &str, apply: F) -> T
+    where
+        F: Fn(Operator, Vec<T>) -> T,
+    {
+        let mut chars: Vec<T> = string
+            .chars()
+            .map(|c| apply(self.get_op(&format!("'{}'", c), 0), vec![]))
+            .collect();
+        let first = chars.remove(0);
+        if chars.is_empty() {
+            return first;
+        }
+        let app = self.get_op(".", 2);
+        chars
+            .into_iter()
+            .fold(first, |acc, c| apply(app, vec![acc, c]))
+    }
+    // Desugar juxtaposed items into a left-nested binary application.
+    fn desugar_application<T, F>(&mut self, items: Vec<T>, apply: F) -> T
+    where
+        F: Fn(Operator, Vec<T>) -> T,
+    {
+        let mut it = items.into_iter();
+        let init = it.next().unwrap();
+        it.fold(init, |acc, x| apply(self.get_op(".", 2), vec![acc, x]))
+    }
+    // Desugar a numeral into `DIGIT` and `DECC` applied to its digits, as `pretty`
+    // abbreviates them: 42 is DECC(DIGIT(4) 2). Only `pretty` output has numerals.
+    fn desugar_numeral<T, F>(&mut self, numeral: &str, apply: F) -> Option<T>
+    where
+        F: Fn(Operator, Vec<T>) -> T,
+    {
+        if !self.pretty {
+            return None;
+        }
+        let mut digits: Vec<T> = numeral
+            .chars()
+            .map(|d| apply(self.get_op(&d.to_string(), 0), vec![]))
+            .collect();
+        let first = digits.remove(0);
+        let first = apply(self.get_op("DIGIT", 1), vec![first]);
+        let decc = self.get_op("DECC", 2);
+        Some(
+            digits
+                .into_iter()
+                .fold(first, |acc, d| apply(decc, vec![acc, d])),
+        )
+    }
+    // Apply the operator `name` to its arguments, given as comma-separated groups of
+    // juxtaposed items. A single group holds the arguments themselves, except in `pretty`
+    // output, which separates arguments only by commas; otherwise, each group is an argument.
+    // Where the signature has no operator of the resulting arity, more than two arguments to
+    // an associative binary operator nest to the right, as in `pretty`.
+    fn apply_named<T, F>(&mut self, name: &str, groups: Option<Vec<Vec<T>>>, apply: F) -> T
+    where
+        F: Fn(Operator, Vec<T>) -> T,
+    {
+        let mut groups = groups.unwrap_or_default();
+        let mut args = if groups.len() == 1 && !self.pretty {
+            groups.remove(0)
+        } else {
+            groups
+                .into_iter()
+                .map(|group| self.desugar_application(group, &apply))
+                .collect()
+        };
+        let arity = args.len() as u32;
+        if arity > 2 && self.has_op(name, arity).is_none() {
+            if let Some(op) = self
+                .has_op(name, 2)
+                .filter(|op| op.is_associative(self.sig))
+            {
+                let last = args.pop().unwrap();
+                return args
+                    .into_iter()
+                    .rev()
+                    .fold(last, |acc, arg| apply(op, vec![arg, acc]));
+            }
+        }
+        apply(self.get_op(name, arity), args)
+    }
+    // Desugar a list literal into applications of the list constructors.
+    fn desugar_list<T, F>(&mut self, items: Vec<T>, apply: F) -> T
+    where
+        F: Fn(Operator, Vec<T>) -> T,
+    {
+        let (cons, nil) = self.lists.clone();
+        let nil = apply(self.get_op(&nil, 0), vec![]);
+        if items.is_empty() {
+            return nil;
+        }
+        let cons = self.get_op(&cons, 2);
+        items
+            .into_iter()
+            .rev()
+            .fold(nil, |tail, head| apply(cons, vec![head, tail]))
+    }
+    // The parsed value, unless it relies on a refused symbol.
+    fn known<T>(&mut self, value: T) -> Result<T, ParseError> {
+        match self.unknown.take() {
+            Some(e) => Err(e),
+            None => Ok(value),
+        }
+    }
+    // The error to report for a failed parse: the last sort error, if any, else `default`.
+    // Refused symbols come first, as their placeholders may be part of the sort error, but
+    // not before a syntax error, which may have caused the refusal.
+    fn error(&mut self, default: ParseError) -> ParseError {
+        match (self.unknown.take(), self.sort_error.take()) {
+            (Some(_), _) if matches!(default, ParseError::Syntax(_)) => default,
+            (Some(e), _) => e,
+            (None, Some(e)) => ParseError::IllSorted(e),
+            (None, None) => default,
+        }
+    }
+    // Fail, recording the sort error, if the parsed value is ill-sorted.
+    fn check_sorts<'b, T, F>(
+        mut self,
+        input: CompleteStr<'b>,
+        result: IResult<CompleteStr<'b>, T>,
+        sort: F,
+    ) -> (Parser<'a>, IResult<CompleteStr<'b>, T>)
+    where
+        F: Fn(&T, &Signature) -> Result<Option<String>, SortError>,
+    {
+        if let Ok((_, ref value)) = result {
+            if let Err(e) = sort(value, self.sig) {
+                self.sort_error = Some(e);
+                let error = NomErr::Error(NomContext::Code(input, ErrorKind::Custom(0)));
+                return (self, Err(error));
+            }
+        }
+        (self, result)
     }
 
     method!(variable<Parser<'a>, CompleteStr, Term>, mut self,
             map!(terminated!(identifier, underscore),
-                 |v| Term::Variable(self.get_var(v.0)))
+                 |v| Term::Variable(self.get_var(&v)))
+    );
+
+    method!(numeral<Parser<'a>, CompleteStr, Term>, mut self,
+            map_opt!(numeral_literal,
+                     |n: String| self.desugar_numeral(&n, |op, args| Term::Application { op, args }))
     );
 
     method!(application<Parser<'a>, CompleteStr, Term>, mut self,
-            alt!(call_m!(self.standard_application) |
+            alt!(call_m!(self.numeral) |
+                 call_m!(self.standard_application) |
                  call_m!(self.binary_application))
     );
 
@@ -233,16 +1329,14 @@ impl<'a> Parser<'a> {
                               lparen >>
                               multispace0 >>
                               args: separated_list!(
-                                  multispace1,
-                                  call_m!(self.term)) >>
+                                  ws!(comma),
+                                  separated_nonempty_list!(
+                                      multispace1,
+                                      call_m!(self.term))) >>
                               multispace0 >>
                               rparen >>
                               (args))) >>
-                      args: expr_opt!(Some(args.unwrap_or_default())) >>
-                      (Term::Application {
-                          op: self.get_op(name.0, args.len() as u32),
-                          args
-                      })
+                      (self.apply_named(&name, args, |op, args| Term::Application { op, args }))
             )
     );
 
@@ -261,9 +1355,26 @@ impl<'a> Parser<'a> {
             )
     );
 
+    method!(list<Parser<'a>, CompleteStr, Term>, mut self,
+            do_parse!(lbracket >>
+                      multispace0 >>
+                      items: separated_list!(ws!(comma), call_m!(self.top_term)) >>
+                      multispace0 >>
+                      rbracket >>
+                      (self.desugar_list(items, |op, args| Term::Application { op, args })))
+    );
+
+    method!(string<Parser<'a>, CompleteStr, Term>, mut self,
+            map!(string_literal,
+                 |s| self.desugar_string(&s, |op, args| Term::Application { op, args }))
+    );
+
+    // strings come first, as identifiers may contain '"'
     method!(term<Parser<'a>, CompleteStr, Term>, mut self,
-            alt!(call_m!(self.variable) |
+            alt!(call_m!(self.string) |
+                 call_m!(self.variable) |
                  call_m!(self.application) |
+                 call_m!(self.list) |
                  do_parse!(lparen >>
                            term: call_m!(self.top_term) >>
                            rparen >>
@@ -276,24 +1387,22 @@ impl<'a> Parser<'a> {
                     separated_nonempty_list!(
                         multispace1,
                         call_m!(self.term)),
-                    |a| {
-                        let mut it = a.into_iter();
-                        let init = it.next().unwrap();
-                        it.fold(init, |acc, x| {
-                            let args = vec![acc, x];
-                            let op = self.get_op(".", 2);
-                            Term::Application{ op, args }
-                        })
-                    }))
+                    |a| self.desugar_application(a, |op, args| Term::Application { op, args })))
     );
 
     method!(context_variable<Parser<'a>, CompleteStr, Context>, mut self,
             map!(terminated!(identifier, underscore),
-                 |v| Context::Variable(self.get_var(v.0)))
+                 |v| Context::Variable(self.get_var(&v)))
+    );
+
+    method!(context_numeral<Parser<'a>, CompleteStr, Context>, mut self,
+            map_opt!(numeral_literal,
+                     |n: String| self.desugar_numeral(&n, |op, args| Context::Application { op, args }))
     );
 
     method!(context_application<Parser<'a>, CompleteStr, Context>, mut self,
-            alt!(call_m!(self.context_standard_application) |
+            alt!(call_m!(self.context_numeral) |
+                 call_m!(self.context_standard_application) |
                  call_m!(self.context_binary_application))
     );
 
@@ -304,16 +1413,14 @@ impl<'a> Parser<'a> {
                               lparen >>
                               multispace0 >>
                               args: separated_list!(
-                                  multispace1,
-                                  call_m!(self.context)) >>
+                                  ws!(comma),
+                                  separated_nonempty_list!(
+                                      multispace1,
+                                      call_m!(self.context))) >>
                               multispace0 >>
                               rparen >>
                               (args))) >>
-                      args: expr_opt!(Some(args.unwrap_or_default())) >>
-                      (Context::Application {
-                          op: self.get_op(name.0, args.len() as u32),
-                          args,
-                      })
+                      (self.apply_named(&name, args, |op, args| Context::Application { op, args }))
             )
     );
 
@@ -334,10 +1441,30 @@ impl<'a> Parser<'a> {
         do_parse!(tag!("[!]") >> (Context::Hole))
     );
 
+    method!(context_list<Parser<'a>, CompleteStr, Context>, mut self,
+            do_parse!(lbracket >>
+                      multispace0 >>
+                      items: separated_list!(ws!(comma), call_m!(self.top_context)) >>
+                      multispace0 >>
+                      rbracket >>
+                      (self.desugar_list(items, |op, args| Context::Application { op, args })))
+    );
+
+    method!(context_string<Parser<'a>, CompleteStr, Context>, mut self,
+            map!(string_literal,
+                 |s| self.desugar_string(&s, |op, args| Context::Application { op, args }))
+    );
+
     method!(context<Parser<'a>, CompleteStr, Context>, mut self,
-            alt!(call_m!(self.context_variable) |
+            alt!(call_m!(self.context_string) |
+                 call_m!(self.context_variable) |
                  call_m!(self.context_application) |
-                 call_m!(self.context_hole))
+                 call_m!(self.context_hole) |
+                 call_m!(self.context_list) |
+                 do_parse!(lparen >>
+                           context: call_m!(self.top_context) >>
+                           rparen >>
+                           (context)))
     );
 
     method!(top_context<Parser<'a>, CompleteStr, Context>, mut self,
@@ -349,26 +1476,62 @@ impl<'a> Parser<'a> {
                                    context: call_m!(self.top_context) >>
                                    rparen >>
                                    (context)))),
-                |a| {
-                    let mut it = a.into_iter();
-                    let init = it.next().unwrap();
-                    it.fold(init, |acc, x| {
-                        let op = self.get_op(".", 2);
-                        let args = vec![acc, x];
-                        Context::Application{ op, args }
-                    })
-                }))
+                |a| self.desugar_application(a, |op, args| Context::Application { op, args })))
+    );
+
+    method!(condition<Parser<'a>, CompleteStr, (Term, Term)>, mut self,
+            do_parse!(lhs: call_m!(self.top_term) >>
+                      ws!(rule_kw) >>
+                      rhs: call_m!(self.top_term) >>
+                      (lhs, rhs))
+    );
+
+    method!(conditions<Parser<'a>, CompleteStr, Conditions>, mut self,
+            do_parse!(ws!(cond_kw) >>
+                      conditions: separated_nonempty_list!(
+                          ws!(comma),
+                          call_m!(self.condition)) >>
+                      (conditions))
     );
 
-    method!(rule<Parser<'a>, CompleteStr, Rule>, mut self,
+    method!(clause<Parser<'a>, CompleteStr, Clause>, mut self,
+            do_parse!(rhs: call_m!(self.top_term) >>
+                      weight: opt!(preceded!(ws!(weight_kw), weight)) >>
+                      (rhs, weight))
+    );
+
+    fn sorted_term<'b>(
+        self,
+        input: CompleteStr<'b>,
+    ) -> (Parser<'a>, IResult<CompleteStr<'b>, Term>) {
+        let (parser, result) = self.top_term(input);
+        parser.check_sorts(input, result, Term::sort)
+    }
+
+    fn rule<'b>(self, input: CompleteStr<'b>) -> (Parser<'a>, IResult<CompleteStr<'b>, Rule>) {
+        let (parser, result) = self.unsorted_rule(input);
+        parser.check_sorts(input, result, Rule::sort)
+    }
+
+    method!(unsorted_rule<Parser<'a>, CompleteStr, Rule>, mut self,
             ws!(map_opt!(
                 do_parse!(lhs: call_m!(self.top_term) >>
                           ws!(rule_kw) >>
-                          rhs: separated_nonempty_list!(
+                          clauses: separated_nonempty_list!(
                               ws!(pipe),
-                              call_m!(self.top_term)) >>
-                          (lhs, rhs)),
-                |(lhs, rhs)| Rule::new(lhs, rhs)))
+                              call_m!(self.clause)) >>
+                          conditions: opt!(call_m!(self.conditions)) >>
+                          (lhs, clauses, conditions)),
+                |(lhs, clauses, conditions): (Term, Vec<Clause>, Option<Conditions>)| {
+                    let (rhs, weights): (Vec<_>, Vec<_>) = clauses.into_iter().unzip();
+                    let rule = Rule::new_conditional(lhs, rhs, conditions.unwrap_or_default())?;
+                    if weights.iter().all(Option::is_none) {
+                        Some(rule)
+                    } else {
+                        // every clause must be weighted
+                        rule.with_weights(weights.into_iter().collect::<Option<_>>()?)
+                    }
+                }))
     );
 
     method!(rule_statement<Parser<'a>, CompleteStr, Statement>, mut self,
@@ -388,31 +1551,99 @@ impl<'a> Parser<'a> {
     );
 
     method!(term_statement<Parser<'a>, CompleteStr, Statement>, mut self,
-            do_parse!(term: call_m!(self.top_term) >>
+            do_parse!(term: call_m!(self.sorted_term) >>
                       (Statement::Term(term)))
     );
 
+    method!(declaration<Parser<'a>, CompleteStr, Statement>, mut self,
+            ws!(do_parse!(name: identifier >>
+                          ws!(sort_kw) >>
+                          sorts: separated_nonempty_list!(ws!(arrow), sort_name) >>
+                          ({
+                              let mut args: Vec<String> = sorts.iter().map(|s| s.0.to_string()).collect();
+                              let result = args.pop().expect("result sort");
+                              let op = self.declare_op(&name, args.len() as u32);
+                              self.sig.set_sort(&op, OperatorSort { args, result });
+                              Statement::Declaration(op)
+                          })))
+    );
+
+    method!(operators<Parser<'a>, CompleteStr, Statement>, mut self,
+            ws!(do_parse!(ops_kw >>
+                          ws!(sort_kw) >>
+                          ops: separated_nonempty_list!(
+                              ws!(comma),
+                              do_parse!(name: op_name >> slash >> arity: arity >> (name, arity))) >>
+                          ({
+                              let ops = ops.into_iter().map(|(name, arity)| {
+                                  match self.arities.insert(name.clone(), arity) {
+                                      Some(declared) if declared != arity => {
+                                          let e = ParseError::ArityMismatch(name.clone(), declared, arity);
+                                          self.refuse(e);
+                                      }
+                                      _ => (),
+                                  }
+                                  self.declare_op(&name, arity)
+                              }).collect();
+                              Statement::Operators(ops)
+                          })))
+    );
+
     method!(
         comment<Parser<'a>, CompleteStr, CompleteStr>,
         self,
         preceded!(tag!("#"), take_until_and_consume!("\n"))
     );
 
+    method!(trs_statement<Parser<'a>, CompleteStr, Statement>, mut self,
+            ws!(do_parse!(statement: alt!(call_m!(self.operators) |
+                                          call_m!(self.declaration) |
+                                          call_m!(self.rule_statement)) >>
+                          semicolon >>
+                          (statement)))
+    );
+
+    // Parse a single semicolon-terminated TRS statement with fresh variables,
+    // returning it if it is a rule.
+    pub(crate) fn next_rule(
+        mut self,
+        input: &str,
+    ) -> (Parser<'a>, Result<Option<Rule>, ParseError>) {
+        self.clear_variables();
+        let (mut parser, result) = self.trs_statement(CompleteStr(input));
+        let rule = match result {
+            Ok((CompleteStr(""), Statement::Rule(rule))) => parser.known(Some(rule)),
+            Ok((CompleteStr(""), _)) => parser.known(None),
+            _ => Err(parser.error(syntax_error(input, Start::Trs))),
+        };
+        (parser, rule)
+    }
+
     method!(trs<Parser<'a>, CompleteStr, TRS>, mut self,
             ws!(do_parse!(
-                    rules: many0!(
+                    statements: many0!(
                         do_parse!(
                             many0!(ws!(call_m!(self.comment))) >>
-                            rule: call_m!(self.rule) >>
+                            statement: alt!(call_m!(self.operators) |
+                                            call_m!(self.declaration) |
+                                            call_m!(self.rule_statement)) >>
                             ws!(semicolon) >>
                             many0!(ws!(call_m!(self.comment))) >>
-                            ({ self.clear_variables(); rule }))) >>
-                    (TRS::new(rules))))
+                            ({ self.clear_variables(); statement }))) >>
+                    (TRS::new(statements
+                              .into_iter()
+                              .filter_map(|statement| match statement {
+                                  Statement::Rule(rule) => Some(rule),
+                                  _ => None,
+                              })
+                              .collect()))))
     );
 
     method!(program<Parser<'a>, CompleteStr, Vec<Statement>>, mut self,
             ws!(many0!(do_parse!(many0!(ws!(call_m!(self.comment))) >>
-                                 statement: alt!(call_m!(self.rule_statement) |
+                                 statement: alt!(call_m!(self.operators) |
+                                            call_m!(self.declaration) |
+                                                 call_m!(self.rule_statement) |
                                                  call_m!(self.term_statement)) >>
                                  ws!(semicolon) >>
                                  many0!(ws!(call_m!(self.comment))) >>
@@ -565,7 +1796,7 @@ mod tests {
         let x1 = p.get_var("x");
         let y1 = p.get_var("y");
         let (_, parsed_term) = p.term(CompleteStr("a(x_ a(y_ x_))"));
-        let a2 = a1.clone();
+        let a2 = a1;
         let x1 = Term::Variable(x1);
         let x2 = x1.clone();
         let y1 = Term::Variable(y1);
@@ -593,48 +1824,48 @@ mod tests {
         let k = p.get_op("K", 0);
         let app = p.get_op(".", 2);
         let term = Term::Application {
-            op: app.clone(),
+            op: app,
             args: vec![
                 Term::Application {
-                    op: app.clone(),
+                    op: app,
                     args: vec![
                         Term::Application {
-                            op: app.clone(),
+                            op: app,
                             args: vec![
                                 Term::Application {
-                                    op: s.clone(),
+                                    op: s,
                                     args: vec![],
                                 },
                                 Term::Application {
-                                    op: k.clone(),
+                                    op: k,
                                     args: vec![],
                                 },
                             ],
                         },
                         Term::Application {
-                            op: k.clone(),
+                            op: k,
                             args: vec![],
                         },
                     ],
                 },
                 Term::Application {
-                    op: app.clone(),
+                    op: app,
                     args: vec![
                         Term::Application {
-                            op: app.clone(),
+                            op: app,
                             args: vec![
                                 Term::Application {
-                                    op: k.clone(),
+                                    op: k,
                                     args: vec![],
                                 },
                                 Term::Application {
-                                    op: s.clone(),
+                                    op: s,
                                     args: vec![],
                                 },
                             ],
                         },
                         Term::Application {
-                            op: k.clone(),
+                            op: k,
                             args: vec![],
                         },
                     ],
@@ -668,6 +1899,93 @@ mod tests {
         assert_eq!(parsed_rule, Ok((CompleteStr(""), rule)));
     }
 
+    #[test]
+    fn conditional_rule_test() {
+        let mut sig = Signature::default();
+        let p = Parser::new(&mut sig);
+        let (p, parsed_rule) = p.rule(CompleteStr("F(x_) = A | B <= G(x_) = C, H(x_ x_) = x_"));
+        let (_, rule) = parsed_rule.expect("parse of conditional rule");
+
+        assert_eq!(rule.rhs.len(), 2);
+        assert_eq!(rule.conditions.len(), 2);
+        assert_eq!(
+            rule.display(p.sig),
+            "F(x_) = A | B <= G(x_) = C, H(x_ x_) = x_"
+        );
+
+        let (p, parsed_rule) = p.rule(CompleteStr("F(x_) = A <= G(y_) = C"));
+
+        assert!(parsed_rule.is_err());
+
+        let (_, parsed_trs) = p.trs(CompleteStr("F(x_) = A <= x_ = B; C = D;"));
+        let (_, trs) = parsed_trs.expect("parse of conditional TRS");
+
+        assert_eq!(trs.display(&sig), "F(x_) = A <= x_ = B;\nC = D;");
+    }
+
+    #[test]
+    fn weighted_rule_test() {
+        let mut sig = Signature::default();
+        let p = Parser::new(&mut sig);
+        let (p, parsed_rule) = p.rule(CompleteStr("F(x_) = A :: 0.7 | G(x_) :: 0.3"));
+        let (_, rule) = parsed_rule.expect("parse of weighted rule");
+
+        assert_eq!(rule.weights, Some(vec![0.7, 0.3]));
+        assert_eq!(rule.display(p.sig), "F(x_) = A :: 0.7 | G(x_) :: 0.3");
+
+        let (p, parsed_rule) = p.rule(CompleteStr("F(x_) = A :: 0.7 | B :: 0.7"));
+
+        assert!(parsed_rule.is_err());
+
+        let (p, parsed_rule) = p.rule(CompleteStr("F(x_) = A :: 0.7 | B"));
+
+        assert!(parsed_rule.is_err());
+
+        let (_, parsed_trs) = p.trs(CompleteStr(
+            "F(x_) = A :: 1 <= x_ = B; C = D :: 0.5 | E :: 0.5;",
+        ));
+        let (_, trs) = parsed_trs.expect("parse of weighted TRS");
+
+        assert_eq!(
+            trs.display(&sig),
+            "F(x_) = A :: 1 <= x_ = B;\nC = D :: 0.5 | E :: 0.5;"
+        );
+    }
+
+    #[test]
+    fn declaration_test() {
+        let mut sig = Signature::default();
+        let p = Parser::new(&mut sig);
+        let (p, parsed) = p.declaration(CompleteStr("LT : Nat->Nat -> Bool"));
+        let (_, statement) = parsed.expect("parse of declaration");
+
+        match statement {
+            Statement::Declaration(op) => {
+                assert_eq!(op.display(p.sig), "LT");
+                assert_eq!(op.arity(), 2);
+                assert_eq!(op.sort(p.sig).unwrap().display(), "Nat -> Nat -> Bool");
+            }
+            _ => panic!("declaration parsed as {:?}", statement),
+        }
+
+        let (mut p, parsed) = p.rule(CompleteStr("LT(x_ y_) = x_"));
+
+        assert!(parsed.is_err());
+        assert!(p.sort_error.is_some());
+        assert_eq!(
+            p.error(ParseError::ParseFailed).to_string(),
+            "ill-sorted term: expected sort Bool, found sort Nat"
+        );
+
+        let (_, parsed) = p.program(CompleteStr(
+            "ZERO : Nat; LT(ZERO ZERO); LT(LT(ZERO ZERO) ZERO);",
+        ));
+        let (rest, statements) = parsed.expect("parse of program");
+
+        assert_eq!(statements.len(), 2);
+        assert_eq!(rest, CompleteStr("LT(LT(ZERO ZERO) ZERO);"));
+    }
+
     #[test]
     fn rulecontext_test() {
         let mut sig = Signature::default();
@@ -760,6 +2078,212 @@ mod tests {
     fn parser_incomplete() {
         let mut sig = Signature::default();
         let res = parse(&mut sig, "(a b c");
-        assert_eq!(res, Err(ParseError::ParseIncomplete));
+        match res {
+            Err(ParseError::Syntax(e)) => {
+                assert_eq!((e.offset, e.line, e.column), (6, 1, 7));
+                assert_eq!(e.found, None);
+                assert_eq!(e.expected, vec!["`)`"]);
+            }
+            _ => panic!("incomplete parse gave {:?}", res),
+        }
+    }
+    #[test]
+    fn operators_test() {
+        let mut sig = Signature::default();
+        let (trs, terms) = parse(
+            &mut sig,
+            "ops: A/2, B/0;\nops : A/2;\nA(B x_) = x_;\nA(B B);\nops: C/1;\nB = C(B);",
+        )
+        .expect("parse of program with operators blocks");
+        assert_eq!(trs.len(), 2);
+        assert_eq!(terms.len(), 1);
+        assert_eq!(sig.operators().len(), 3);
+
+        // ops is still usable as an operator
+        let trs = parse_trs(&mut sig, "ops = B; ops : Nat;").expect("parse of ops operator");
+        assert_eq!(trs.rules[0].display(&sig), "ops = B");
+
+        let cases = vec![
+            (
+                "ops: A/2; A(B) = B;",
+                ParseError::ArityMismatch("A".to_string(), 2, 1),
+            ),
+            (
+                "ops: A/2; B A = B;",
+                ParseError::ArityMismatch("A".to_string(), 2, 0),
+            ),
+            (
+                "ops: A/2, A/1;",
+                ParseError::ArityMismatch("A".to_string(), 2, 1),
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(parse_trs(&mut sig, input), Err(error), "{}", input);
+        }
+        match parse_trs(&mut sig, "ops: A/2, B;") {
+            Err(ParseError::Syntax(e)) => {
+                assert_eq!(e.column, 12);
+                assert_eq!(e.expected, vec!["`/`"]);
+            }
+            res => panic!("parse of ops: A/2, B; gave {:?}", res),
+        }
+    }
+    #[test]
+    fn quoted_identifier_test() {
+        let mut sig = Signature::default();
+        let input = r"'++'('my var'_ 'a\'b\\' naïve F#(x_))";
+        let term = parse_term(&mut sig, input).expect("parse of quoted identifiers");
+        assert_eq!(term.operators()[0].name(&sig), Some("a'b\\".to_string()));
+        assert_eq!(term.variables()[0].name(&sig), Some("my var".to_string()));
+        // only names which aren't identifiers are quoted
+        assert_eq!(term.display(&sig), r"++('my var'_ a'b\ naïve F#(x_))");
+        let printed = term.display(&sig);
+        let reparsed = parse_term(&mut sig, &printed).expect("parse of display");
+        assert_eq!(reparsed.display(&sig), term.display(&sig));
+        assert_eq!(reparsed.operators(), term.operators());
+
+        let trs = parse_trs(&mut sig, "ops: '+ +'/2; '+ +'(x_ y_) = x_;").expect("parse of TRS");
+        assert_eq!(trs.display(&sig), "'+ +'(x_ y_) = x_;");
+
+        for &(input, column, expected) in &[
+            ("''(x_)", 2, "character"),
+            (r"'a\qb'", 4, "escape sequence"),
+            ("F('ab", 6, "`'`"),
+        ] {
+            match parse_term(&mut sig, input) {
+                Err(ParseError::Syntax(e)) => {
+                    assert_eq!(e.column, column, "{}", input);
+                    assert_eq!(e.expected, vec![expected], "{}", input);
+                }
+                res => panic!("parse of {} gave {:?}", input, res),
+            }
+        }
+    }
+    #[test]
+    fn string_test() {
+        let mut sig = Signature::default();
+        let input = r#"F("ab" "c\"d\\ e\n" "f")"#;
+        let term = parse_term(&mut sig, input).expect("parse of strings");
+        assert_eq!(
+            term.display(&sig),
+            r#"F(.('\'a\'' '\'b\'') .(.(.(.(.(.('\'c\'' '\'"\'') '\'d\'') '\'\\\'') '\' \'') '\'e\'') '\'\n\'') '\'f\'')"#
+        );
+        assert_eq!(term.pretty(&sig), r#"F("ab", "c\"d\\ e\n", "f")"#);
+        let string = &term.args()[1];
+        let printed = string.pretty(&sig);
+        let reparsed = parse_term(&mut sig, &printed).expect("parse of pretty string");
+        assert_eq!(&reparsed, string);
+
+        // only chains of characters are strings
+        let term = parse_term(&mut sig, r#""ab" X"#).expect("parse of string and operator");
+        assert_eq!(term.pretty(&sig), r#""ab" X"#);
+
+        let context = parse_context(&mut sig, r#"F("ab" [!])"#).expect("parse of context");
+        assert_eq!(context.pretty(&sig), r#"F("ab", [!])"#);
+
+        for &(input, column, expected) in &[
+            (r#""""#, 2, "character"),
+            (r#""a\qb""#, 4, "escape sequence"),
+            (r#"F("ab"#, 6, "`\"`"),
+        ] {
+            match parse_term(&mut sig, input) {
+                Err(ParseError::Syntax(e)) => {
+                    assert_eq!(e.column, column, "{}", input);
+                    assert_eq!(e.expected, vec![expected], "{}", input);
+                }
+                res => panic!("parse of {} gave {:?}", input, res),
+            }
+        }
+    }
+    #[test]
+    fn list_test() {
+        let mut sig = Signature::default();
+        let term = parse_term(&mut sig, "[B, C D, [ ], [E(x_)]]").expect("parse of list");
+        assert_eq!(term.pretty(&sig), "[B, C D, [], [E(x_)]]");
+        let printed = term.pretty(&sig);
+        let reparsed = parse_term(&mut sig, &printed).expect("parse of pretty list");
+        assert_eq!(reparsed.display(&sig), term.display(&sig));
+
+        let context = parse_context(&mut sig, "[[!], B]").expect("parse of list context");
+        assert_eq!(context.display(&sig), "CONS([!] CONS(B NIL))");
+
+        match parse_term(&mut sig, "[B, ]") {
+            Err(ParseError::Syntax(e)) => {
+                assert_eq!(e.column, 5);
+                assert_eq!(e.expected, vec!["term"]);
+            }
+            res => panic!("parse of [B, ] gave {:?}", res),
+        }
+    }
+    #[test]
+    fn strict_test() {
+        let mut sig = Signature::default();
+        let f = sig.new_op(1, Some("F".to_string()));
+        let x = sig.new_var(Some("x".to_string()));
+
+        // variables are shared across rules rather than created per rule
+        let trs = parse_trs_strict(&mut sig, "F(x_) = x_; F(F(x_)) = x_;").expect("parse of TRS");
+        assert_eq!(trs.rules[1].lhs.variables(), vec![x]);
+        assert_eq!(trs.rules[1].lhs.operators(), vec![f]);
+
+        // juxtaposition needs the binary application operator
+        assert_eq!(
+            parse_term_strict(&mut sig, "F(x_) x_"),
+            Err(ParseError::UnknownOperator(".".to_string(), 2))
+        );
+
+        // malformed input is a syntax error, not a refused symbol
+        match parse_term_strict(&mut sig, "F(x_") {
+            Err(ParseError::Syntax(e)) => assert_eq!(e.expected, vec!["`,`", "`)`"]),
+            res => panic!("parse of F(x_ gave {:?}", res),
+        }
+        assert_eq!(sig.operators().len(), 1);
+        assert_eq!(sig.variables().len(), 1);
+    }
+    #[test]
+    fn syntax_error_test() {
+        let mut sig = Signature::default();
+        let cases = vec![
+            (
+                "a b",
+                "1:4: expected `=` or `;`, found end of input\na b\n   ^",
+            ),
+            (
+                "# rules\nF(x_) = ;",
+                "2:9: expected term, found `;`\nF(x_) = ;\n        ^",
+            ),
+            (
+                "F(x_) = x_ :: heavy;",
+                "1:15: expected weight, found `heavy`\nF(x_) = x_ :: heavy;\n              ^^^^^",
+            ),
+            (
+                "LT : Nat -> ;",
+                "1:13: expected sort, found `;`\nLT : Nat -> ;\n            ^",
+            ),
+            (
+                "A;\n\tB = C D);",
+                "2:9: expected `::`, `|`, `<=` or `;`, found `)`\n\tB = C D);\n\t       ^",
+            ),
+            (
+                "A; # trailing",
+                "1:14: expected newline, found end of input\nA; # trailing\n             ^",
+            ),
+        ];
+        for (input, message) in cases {
+            match parse(&mut sig, input) {
+                Err(ParseError::Syntax(e)) => assert_eq!(e.to_string(), message),
+                res => panic!("parse of {:?} gave {:?}", input, res),
+            }
+        }
+
+        // well-formed but invalid: y_ is unbound
+        assert_eq!(
+            parse_rule(&mut sig, "F(x_) = y_"),
+            Err(ParseError::ParseFailed)
+        );
+        match parse_context(&mut sig, "F([!] x_") {
+            Err(ParseError::Syntax(e)) => assert_eq!(e.expected, vec!["`,`", "`)`"]),
+            res => panic!("parse of context gave {:?}", res),
+        }
     }
 }
@@ -1,158 +1,490 @@
 use itertools::Itertools;
 
-use super::{Context, Operator, Term};
+use super::parser::is_numeral;
+use super::{Context, Operator, Signature, Term};
+
+/// Settings for the human-readable serializations, such as [`Term::pretty_with`].
+///
+/// The default prints on a single line with every abbreviation, as [`Term::pretty`] does.
+///
+/// [`Term::pretty_with`]: enum.Term.html#method.pretty_with
+/// [`Term::pretty`]: enum.Term.html#method.pretty
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, PrettyConfig, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let term = parse_term(&mut sig, "F(G(A B) CONS(A CONS(B NIL)) DECC(DIGIT(4) 2))")
+///     .expect("parse of F(G(A B) CONS(A CONS(B NIL)) DECC(DIGIT(4) 2))");
+///
+/// let config = PrettyConfig {
+///     width: Some(16),
+///     lists: false,
+///     ..PrettyConfig::default()
+/// };
+/// assert_eq!(
+///     term.pretty_with(&sig, &config),
+///     "F(\n  G(A, B),\n  CONS(\n    A,\n    CONS(B, NIL)\n  ),\n  42\n)"
+/// );
+///
+/// let config = PrettyConfig {
+///     max_depth: Some(1),
+///     ..PrettyConfig::default()
+/// };
+/// assert_eq!(term.pretty_with(&sig, &config), "F(G(…, …), […, …], 42)");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PrettyConfig {
+    /// The widest a line may be before applications and lists break across lines, one
+    /// argument or item per line, or `None` to print on a single line.
+    pub width: Option<usize>,
+    /// The number of spaces by which each broken application or list indents its contents.
+    pub indent: usize,
+    /// Whether to print `CONS` and `NIL` as lists, e.g. `[A, B]`.
+    pub lists: bool,
+    /// Whether to print `DECC` and `DIGIT` applied to the digits `0` to `9` as numerals, e.g.
+    /// `42` for `DECC(DIGIT(4) 2)`.
+    pub numbers: bool,
+    /// The deepest subterm to print, eliding deeper subterms as `…`, or `None` to print
+    /// every subterm. Elided output doesn't parse.
+    pub max_depth: Option<usize>,
+}
+impl Default for PrettyConfig {
+    /// Print on a single line with every abbreviation, indenting by 2 if `width` is set.
+    fn default() -> PrettyConfig {
+        PrettyConfig {
+            width: None,
+            indent: 2,
+            lists: true,
+            numbers: true,
+            max_depth: None,
+        }
+    }
+}
+
+/// How the human-readable serializations, such as [`Term::pretty`], write applications of an
+/// [`Operator`].
+///
+/// See [`Signature::set_display_style`] for more information.
+///
+/// [`Term::pretty`]: enum.Term.html#method.pretty
+/// [`Operator`]: struct.Operator.html
+/// [`Signature::set_display_style`]: struct.Signature.html#method.set_display_style
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum DisplayStyle {
+    /// The name of the [`Operator`], followed by its arguments in parentheses, e.g.
+    /// `PLUS(x_, y_)`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    #[default]
+    Prefix,
+    /// A symbol between the two arguments of a binary [`Operator`], e.g. `x_ + y_`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    Infix(String),
+    /// A template in which each `_` stands for the next argument, e.g. `if _ then _ else _`.
+    Mixfix(String),
+}
+impl DisplayStyle {
+    // The text around the arguments, if not `Prefix`.
+    pub(crate) fn template(&self) -> Option<Vec<String>> {
+        match *self {
+            DisplayStyle::Prefix => None,
+            DisplayStyle::Infix(ref symbol) => {
+                Some(vec![String::new(), format!(" {} ", symbol), String::new()])
+            }
+            DisplayStyle::Mixfix(ref template) => {
+                Some(template.split('_').map(str::to_string).collect())
+            }
+        }
+    }
+}
+
+impl Operator {
+    /// The [`DisplayStyle`] of the `Operator`.
+    ///
+    /// [`DisplayStyle`]: enum.DisplayStyle.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, DisplayStyle};
+    /// let mut sig = Signature::default();
+    /// let plus = sig.new_op(2, Some("PLUS".to_string()));
+    ///
+    /// assert_eq!(plus.display_style(&sig), DisplayStyle::Prefix);
+    ///
+    /// sig.set_display_style(&plus, DisplayStyle::Infix("+".to_string()));
+    ///
+    /// assert_eq!(plus.display_style(&sig), DisplayStyle::Infix("+".to_string()));
+    /// ```
+    pub fn display_style(&self, sig: &Signature) -> DisplayStyle {
+        sig.sig
+            .read()
+            .expect("poisoned signature")
+            .styles
+            .get(&self.id)
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+// Is `term` an application written with a template, which needs parentheses as an argument?
+fn is_styled<T: Pretty>(term: &T, sig: &Signature) -> bool {
+    term.as_application()
+        .is_some_and(|(op, args)| !args.is_empty() && op.display_style(sig) != DisplayStyle::Prefix)
+}
+
+// Parenthesize `doc`.
+fn parenthesized(doc: Doc) -> Doc {
+    Doc::Group {
+        open: "(".to_string(),
+        items: vec![doc],
+        separator: "",
+        close: ")",
+    }
+}
+
+// Pretty output before layout: text, a sequence of docs, or items between delimiters which may
+// break across lines.
+pub enum Doc {
+    Text(String),
+    Concat(Vec<Doc>),
+    Group {
+        open: String,
+        items: Vec<Doc>,
+        separator: &'static str,
+        close: &'static str,
+    },
+}
+impl Doc {
+    fn flat(&self) -> String {
+        match *self {
+            Doc::Text(ref s) => s.clone(),
+            Doc::Concat(ref docs) => docs.iter().map(Doc::flat).collect(),
+            Doc::Group {
+                ref open,
+                ref items,
+                separator,
+                close,
+            } => format!(
+                "{}{}{}",
+                open,
+                items.iter().map(Doc::flat).join(separator),
+                close
+            ),
+        }
+    }
+    // Lay out the `Doc` starting at `column` on a line indented by `indent`.
+    fn layout(&self, config: &PrettyConfig, indent: usize, column: usize) -> String {
+        let flat = self.flat();
+        let fits = config
+            .width
+            .is_none_or(|width| column + flat.chars().count() <= width);
+        match *self {
+            Doc::Group {
+                ref open,
+                ref items,
+                separator,
+                close,
+            } if !fits => {
+                let inner = indent + config.indent;
+                let mut out = open.to_string();
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 || !open.is_empty() {
+                        out.push('\n');
+                        out.push_str(&" ".repeat(inner));
+                    }
+                    let at = end_column(column, &out);
+                    out.push_str(&item.layout(config, inner, at));
+                    if i + 1 < items.len() {
+                        out.push_str(separator.trim_end());
+                    }
+                }
+                if !close.is_empty() {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent));
+                    out.push_str(close);
+                }
+                out
+            }
+            Doc::Concat(ref docs) if !fits => {
+                let mut out = String::new();
+                for doc in docs {
+                    let at = end_column(column, &out);
+                    out.push_str(&doc.layout(config, indent, at));
+                }
+                out
+            }
+            _ => flat,
+        }
+    }
+}
+
+// The column after printing `text` from `column`.
+fn end_column(column: usize, text: &str) -> usize {
+    match text.rfind('\n') {
+        Some(i) => text[i + 1..].chars().count(),
+        None => column + text.chars().count(),
+    }
+}
+
+// Builds the human-readable serialization of a sequence of `Pretty` items and text, such as
+// a `Rule`, laying out each item where it starts.
+pub(crate) struct Printer<'a> {
+    sig: &'a Signature,
+    config: &'a PrettyConfig,
+    out: String,
+}
+impl<'a> Printer<'a> {
+    pub(crate) fn new(sig: &'a Signature, config: &'a PrettyConfig) -> Printer<'a> {
+        Printer {
+            sig,
+            config,
+            out: String::new(),
+        }
+    }
+    pub(crate) fn text(&mut self, text: &str) {
+        self.out.push_str(text);
+    }
+    pub(crate) fn item<T: Pretty>(&mut self, item: &T) {
+        let column = end_column(0, &self.out);
+        let doc = item.doc(self.sig, self.config, 0, true);
+        self.out.push_str(&doc.layout(self.config, 0, column));
+    }
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+}
 
 pub trait Pretty: Sized {
     fn as_application(&self) -> Option<(Operator, &[Self])>;
-    fn display(&self) -> String;
+    fn display(&self, sig: &Signature) -> String;
 
-    fn pretty(&self) -> String {
-        self.pretty_inner(true)
+    fn pretty(&self, sig: &Signature) -> String {
+        self.pretty_with(sig, &PrettyConfig::default())
+    }
+    fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
+        let mut printer = Printer::new(sig, config);
+        printer.item(self);
+        printer.finish()
     }
     /// `spaces_allowed` informs whether most top-level prettified item can contain spaces.
-    fn pretty_inner(&self, spaces_allowed: bool) -> String {
+    fn doc(
+        &self,
+        sig: &Signature,
+        config: &PrettyConfig,
+        depth: usize,
+        spaces_allowed: bool,
+    ) -> Doc {
+        if config.max_depth.is_some_and(|max| depth > max) {
+            return Doc::Text("…".to_string());
+        }
         if let Some((op, args)) = self.as_application() {
-            let op_str = op.display();
+            let op_str = op.display(sig);
             // the following match `return`s applicable special cases
-            match (op_str.as_str(), args.len()) {
-                (".", 2) => return pretty_binary_application(args, spaces_allowed),
-                ("NIL", 0) => return "[]".to_string(),
-                ("CONS", 2) => {
-                    if let Some(s) = pretty_list(args) {
-                        return s;
+            if let Some(s) = pretty_string(self, sig) {
+                return Doc::Text(s);
+            }
+            // a display style takes precedence over the other abbreviations
+            if let Some(parts) = op.display_style(sig).template() {
+                let mut docs = vec![Doc::Text(parts[0].clone())];
+                for (i, (arg, part)) in args.iter().zip(&parts[1..]).enumerate() {
+                    let doc = arg.doc(sig, config, depth + 1, true);
+                    // x + (y + z) is x + y + z if + is associative
+                    let chained = i == 1
+                        && op.is_associative(sig)
+                        && arg.as_application().is_some_and(|(inner, _)| inner == op);
+                    if is_styled(arg, sig) && !chained {
+                        docs.push(parenthesized(doc));
+                    } else {
+                        docs.push(doc);
                     }
+                    docs.push(Doc::Text(part.clone()));
                 }
-                ("ZERO", 0) => return "0".to_string(),
-                ("SUCC", 1) => {
-                    if let Some(s) = pretty_unary(args) {
-                        return s;
+                return Doc::Concat(docs);
+            }
+            match (op_str.as_str(), args.len()) {
+                (".", 2) => {
+                    return pretty_binary_application(args, sig, config, depth, spaces_allowed)
+                }
+                ("NIL", 0) if config.lists => return Doc::Text("[]".to_string()),
+                ("CONS", 2) if config.lists => {
+                    if let Some(doc) = pretty_list(args, sig, config, depth) {
+                        return doc;
                     }
                 }
-                ("DIGIT", 1) => {
-                    if let Some(s) = digit_to_number(args) {
-                        return format!("{}", s);
+                ("DIGIT", 1) if config.numbers => {
+                    if let Some(d) = digit(&args[0], sig) {
+                        return Doc::Text(d.to_string());
                     }
                 }
-                ("DECC", 2) => {
-                    if let Some(s) = pretty_decc(args) {
-                        return s;
+                ("DECC", 2) if config.numbers => {
+                    if let Some(s) = pretty_decc(args, sig) {
+                        return Doc::Text(s);
                     }
                 }
-                (_, 0) => return op_str,
+                // quoted, so as not to be read as a numeral
+                (name, 0) if is_numeral(name) => return Doc::Text(format!("'{}'", name)),
+                (_, 0) => return Doc::Text(op_str),
                 _ => (),
             }
-            let args_str = args.iter().map(|arg| arg.pretty_inner(true)).join(", ");
-            format!("{}({})", op_str, args_str)
+            let mut items = vec![];
+            if args.len() == 2 && op.is_associative(sig) {
+                associative_leaves(op, args, &mut items);
+                // the parser nests the arguments only if no operator takes that many
+                let arity = items.len() as u32;
+                if op
+                    .name(sig)
+                    .is_some_and(|name| sig.operator(&name, arity).is_some())
+                {
+                    items = args.iter().collect();
+                }
+            } else {
+                items.extend(args);
+            }
+            Doc::Group {
+                open: format!("{}(", op_str),
+                items: items
+                    .iter()
+                    .map(|arg| arg.doc(sig, config, depth + 1, true))
+                    .collect(),
+                separator: ", ",
+                close: ")",
+            }
         } else {
-            self.display()
+            Doc::Text(self.display(sig))
         }
     }
 }
 impl Pretty for Context {
     fn as_application(&self) -> Option<(Operator, &[Context])> {
         match *self {
-            Context::Application { ref op, ref args } => Some((op.clone(), &args)),
+            Context::Application { op, ref args } => Some((op, args)),
             _ => None,
         }
     }
-    fn display(&self) -> String {
-        self.display()
+    fn display(&self, sig: &Signature) -> String {
+        self.display(sig)
     }
 }
 impl Pretty for Term {
     fn as_application(&self) -> Option<(Operator, &[Term])> {
         match *self {
-            Term::Application { ref op, ref args } => Some((op.clone(), &args)),
+            Term::Application { op, ref args } => Some((op, args)),
             _ => None,
         }
     }
-    fn display(&self) -> String {
-        self.display()
+    fn display(&self, sig: &Signature) -> String {
+        self.display(sig)
     }
 }
 
-fn pretty_unary<T: Pretty>(args: &[T]) -> Option<String> {
-    let mut increments = 1;
-    let mut arg = &args[0];
-    while let Some((op, args)) = arg.as_application() {
-        match (op.display().as_str(), args.len()) {
-            ("SUCC", 1) => {
-                increments += 1;
-                arg = &args[0]
+// Collect the arguments of applications of the associative `op` nested to the right, as the
+// parser nests them again.
+fn associative_leaves<'a, T: Pretty>(op: Operator, args: &'a [T], items: &mut Vec<&'a T>) {
+    items.push(&args[0]);
+    match args[1].as_application() {
+        Some((inner, inner_args)) if inner == op => associative_leaves(op, inner_args, items),
+        _ => items.push(&args[1]),
+    }
+}
+
+// A string is a left-nested chain of "." applications of characters, each the
+// nullary operator named by the character in single quotes, e.g. ('a' 'b').
+fn pretty_string<T: Pretty>(term: &T, sig: &Signature) -> Option<String> {
+    let mut chars = vec![];
+    let mut term = term;
+    while let Some((op, args)) = term.as_application() {
+        match (op.display(sig).as_str(), args.len()) {
+            (".", 2) => {
+                chars.push(character(&args[1], sig)?);
+                term = &args[0];
             }
-            ("ZERO", 0) | ("0", 0) => return Some(increments.to_string()),
-            // number does not terminate with ZERO, so we use the
-            // non-special-case printing style
             _ => break,
         }
     }
-    None
+    chars.push(character(term, sig)?);
+    let mut string = String::from("\"");
+    for c in chars.into_iter().rev() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            '\n' => string.push_str("\\n"),
+            '\t' => string.push_str("\\t"),
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    Some(string)
 }
 
-fn digit_to_number<T: Pretty>(args: &[T]) -> Option<i32> {
-    if args.len() == 1 {
-        if let Some((op, args)) = &args[0].as_application() {
-            if args.is_empty() {
-                return str_to_number(&op.display());
+fn character<T: Pretty>(term: &T, sig: &Signature) -> Option<char> {
+    match term.as_application() {
+        Some((op, [])) => {
+            let name = op.name(sig)?;
+            let mut chars = name.strip_prefix('\'')?.strip_suffix('\'')?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Some(c),
+                _ => None,
             }
         }
+        _ => None,
     }
-    None
 }
 
-fn str_to_number(s: &str) -> Option<i32> {
-    match s {
-        "0" | "ZERO" => Some(0),
-        "1" | "ONE" => Some(1),
-        "2" | "TWO" => Some(2),
-        "3" | "THREE" => Some(3),
-        "4" | "FOUR" => Some(4),
-        "5" | "FIVE" => Some(5),
-        "6" | "SIX" => Some(6),
-        "7" | "SEVEN" => Some(7),
-        "8" | "EIGHT" => Some(8),
-        "9" | "NINE" => Some(9),
+// The digit named by `term`, if it is one of the nullary operators `0` to `9`.
+fn digit<T: Pretty>(term: &T, sig: &Signature) -> Option<char> {
+    match term.as_application() {
+        Some((op, [])) => {
+            let name = op.name(sig)?;
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(d), None) if d.is_ascii_digit() => Some(d),
+                _ => None,
+            }
+        }
         _ => None,
     }
 }
 
-fn pretty_decc<T: Pretty>(args: &[T]) -> Option<String> {
+// The digits are gathered rather than summed, so long numbers don't overflow.
+fn pretty_decc<T: Pretty>(args: &[T], sig: &Signature) -> Option<String> {
+    let mut digits = vec![digit(&args[1], sig)?];
     let mut arg = &args[0];
-    let mut gathered_digits;
-    let mut order_of_mag = 10;
-    if let Some(val) = digit_to_number(&args[1..2]) {
-        gathered_digits = val;
-        while let Some((op, args)) = arg.as_application() {
-            match (op.display().as_str(), args.len()) {
-                ("DECC", 2) => {
-                    if let Some(digit) = digit_to_number(&args[1..2]) {
-                        arg = &args[0];
-                        gathered_digits += digit * order_of_mag;
-                        order_of_mag *= 10;
-                    } else {
-                        break;
-                    }
-                }
-                ("DIGIT", 1) => {
-                    if let Some(digit) = digit_to_number(&args[0..1]) {
-                        gathered_digits += digit * order_of_mag;
-                        return Some(gathered_digits.to_string());
-                    } else {
-                        break;
-                    }
-                }
-                _ => break,
+    while let Some((op, args)) = arg.as_application() {
+        match (op.display(sig).as_str(), args.len()) {
+            ("DECC", 2) => {
+                digits.push(digit(&args[1], sig)?);
+                arg = &args[0];
+            }
+            ("DIGIT", 1) => {
+                digits.push(digit(&args[0], sig)?);
+                return Some(digits.into_iter().rev().collect());
             }
+            _ => break,
         }
     }
     None
 }
 
-fn pretty_binary_application<T: Pretty>(args: &[T], spaces_allowed: bool) -> String {
+fn pretty_binary_application<T: Pretty>(
+    args: &[T],
+    sig: &Signature,
+    config: &PrettyConfig,
+    depth: usize,
+    spaces_allowed: bool,
+) -> Doc {
     let mut first = &args[0];
     let mut rest = vec![&args[1]]; // in reverse order for fast `push`ing
     while let Some((op, args)) = first.as_application() {
-        match (op.display().as_str(), args.len()) {
+        // keep strings whole
+        if pretty_string(first, sig).is_some() {
+            break;
+        }
+        match (op.display(sig).as_str(), args.len()) {
             (".", 2) => {
                 first = &args[0];
                 rest.push(&args[1]);
@@ -162,31 +494,49 @@ fn pretty_binary_application<T: Pretty>(args: &[T], spaces_allowed: bool) -> Str
     }
     rest.push(first);
     rest.reverse();
-    let interior = rest.into_iter().map(|x| x.pretty_inner(false)).join(" ");
-    if spaces_allowed {
-        interior
-    } else {
-        format!("({})", interior)
+    let (open, close) = if spaces_allowed { ("", "") } else { ("(", ")") };
+    Doc::Group {
+        open: open.to_string(),
+        items: rest
+            .into_iter()
+            .map(|x| {
+                let doc = x.doc(sig, config, depth + 1, false);
+                if is_styled(x, sig) {
+                    parenthesized(doc)
+                } else {
+                    doc
+                }
+            })
+            .collect(),
+        separator: " ",
+        close,
     }
 }
 
-fn pretty_list<T: Pretty>(args: &[T]) -> Option<String> {
+fn pretty_list<T: Pretty>(
+    args: &[T],
+    sig: &Signature,
+    config: &PrettyConfig,
+    depth: usize,
+) -> Option<Doc> {
     let mut items = vec![&args[0]];
     let mut cdr = &args[1];
     while let Some((op, args)) = cdr.as_application() {
-        match (op.display().as_str(), args.len()) {
+        match (op.display(sig).as_str(), args.len()) {
             ("CONS", 2) => {
                 items.push(&args[0]);
                 cdr = &args[1];
             }
             ("NIL", 0) => {
-                return Some(format!(
-                    "[{}]",
-                    items
+                return Some(Doc::Group {
+                    open: "[".to_string(),
+                    items: items
                         .into_iter()
-                        .map(|item| item.pretty_inner(true))
-                        .join(", ")
-                ));
+                        .map(|item| item.doc(sig, config, depth + 1, true))
+                        .collect(),
+                    separator: ", ",
+                    close: "]",
+                });
             }
             // list does not terminate with NIL, so we use the
             // non-special-case printing style
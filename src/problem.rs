@@ -0,0 +1,57 @@
+use {ConditionMode, Operator, Rule, Signature, Variable, TRS};
+
+/// A rewriting problem as exchanged in the ARI and COPS formats: a [`TRS`] together with
+/// metadata such as its author and origin.
+///
+/// The [`ConditionMode`] of the [`TRS`] records the condition type of a conditional problem.
+///
+/// See [`parse_ari`] and [`parse_cops`] for more information.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`ConditionMode`]: enum.ConditionMode.html
+/// [`parse_ari`]: fn.parse_ari.html
+/// [`parse_cops`]: fn.parse_cops.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// The rules of the problem.
+    pub trs: TRS,
+    /// Metadata as `(key, value)` pairs, like `("author", "Aart Middeldorp")`, in the order
+    /// given. Free-form comments use the key `comment`.
+    pub metadata: Vec<(String, String)>,
+}
+impl Problem {
+    // Does the problem have conditional rules?
+    pub(crate) fn is_conditional(&self) -> bool {
+        self.trs.rules.iter().any(Rule::is_conditional)
+    }
+    // The condition type of the problem, in lowercase.
+    pub(crate) fn condition_type(&self) -> &'static str {
+        match self.trs.condition_config().mode {
+            ConditionMode::Oriented => "oriented",
+            ConditionMode::Join => "join",
+            ConditionMode::SemiEquational => "semi-equational",
+        }
+    }
+}
+
+// The ConditionMode named by a condition type, ignoring case.
+pub(crate) fn condition_mode(name: &str) -> Option<ConditionMode> {
+    match name.to_lowercase().as_str() {
+        "oriented" => Some(ConditionMode::Oriented),
+        "join" => Some(ConditionMode::Join),
+        "semi-equational" => Some(ConditionMode::SemiEquational),
+        _ => None,
+    }
+}
+
+// The name of a Variable without its trailing underscore, as written in the TPDB, COPS, and ARI
+// formats.
+pub(crate) fn variable_name(sig: &Signature, v: &Variable) -> String {
+    v.name(sig).unwrap_or_else(|| format!("var{}", v.id))
+}
+
+// The name of an Operator, unquoted, as written in the TPDB, COPS, ARI, and s-expression
+// formats.
+pub(crate) fn operator_name(sig: &Signature, op: &Operator) -> String {
+    op.name(sig).unwrap_or_else(|| format!("op{}", op.id))
+}
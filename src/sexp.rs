@@ -0,0 +1,357 @@
+use parser::Parser;
+use problem::operator_name;
+use std::collections::HashMap;
+use std::iter;
+use {ParseError, Rule, Signature, Term, TRS};
+
+use itertools::Itertools;
+
+/// Parse a string as a [`Term`] in s-expression syntax: an application is a list of its
+/// [`Operator`] followed by its arguments, as in `(F (A) x)`, and any other symbol is a
+/// [`Variable`]. Symbols may be quoted as `|...|`.
+///
+/// [`Term`]: enum.Term.html
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term_sexp};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term_sexp(&mut sig, "(F (A) (|G H| x) x)").expect("parse of (F (A) (|G H| x) x)");
+///
+/// assert_eq!(t.display(&sig), "F(A 'G H'(x_) x_)");
+/// assert_eq!(t.to_sexp(&sig), "(F (A) (|G H| v0) v0)");
+/// ```
+pub fn parse_term_sexp(sig: &mut Signature, input: &str) -> Result<Term, ParseError> {
+    let sexp = read_sexp(input)?;
+    term_from_sexp(&mut Parser::new(sig), &sexp)
+}
+
+/// Parse a string as a [`Rule`] in s-expression syntax: `(= lhs rhs ...)`, or
+/// `(<= (= lhs rhs ...) (= c d) ...)` for a conditional [`Rule`]. [`Term`]s are written as for
+/// [`parse_term_sexp`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Term`]: enum.Term.html
+/// [`parse_term_sexp`]: fn.parse_term_sexp.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_rule_sexp};
+/// let mut sig = Signature::default();
+///
+/// let r = parse_rule_sexp(&mut sig, "(<= (= (F x y) (G y) (B)) (= x (A)))")
+///     .expect("parse of (<= (= (F x y) (G y) (B)) (= x (A)))");
+///
+/// assert_eq!(r.display(&sig), "F(x_ y_) = G(y_) | B <= x_ = A");
+/// ```
+pub fn parse_rule_sexp(sig: &mut Signature, input: &str) -> Result<Rule, ParseError> {
+    let sexp = read_sexp(input)?;
+    rule_from_sexp(&mut Parser::new(sig), &sexp)
+}
+
+/// Parse a string as a [`TRS`] in s-expression syntax: a list of [`Rule`]s, each written as for
+/// [`parse_rule_sexp`].
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Rule`]: struct.Rule.html
+/// [`parse_rule_sexp`]: fn.parse_rule_sexp.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs_sexp};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_trs_sexp(&mut sig, "((= (PLUS (ZERO) y) y) (= (PLUS (SUCC x) y) (SUCC (PLUS x y))))")
+///     .expect("parse of TRS");
+///
+/// assert_eq!(t.display(&sig), "PLUS(ZERO y_) = y_;\nPLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));");
+/// ```
+pub fn parse_trs_sexp(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
+    let mut parser = Parser::new(sig);
+    match read_sexp(input)? {
+        Sexp::List(rules) => rules
+            .iter()
+            .map(|rule| rule_from_sexp(&mut parser, rule))
+            .collect::<Result<Vec<_>, _>>()
+            .map(TRS::new),
+        Sexp::Atom(_) => Err(ParseError::ParseFailed),
+    }
+}
+
+impl Term {
+    /// Serialize a `Term` in canonical s-expression syntax, naming each [`Variable`] `vN` in
+    /// order of first occurrence.
+    ///
+    /// See [`parse_term_sexp`] for more information.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`parse_term_sexp`]: fn.parse_term_sexp.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "A(B y_)").expect("parse of A(B y_)");
+    ///
+    /// assert_eq!(t.to_sexp(&sig), "(A (B) v0)");
+    /// ```
+    pub fn to_sexp(&self, sig: &Signature) -> String {
+        sexp_term(sig, self, &mut HashMap::new())
+    }
+}
+
+impl Rule {
+    /// Serialize a `Rule` in canonical s-expression syntax, naming each [`Variable`] `vN` in
+    /// order of first occurrence. Weights are dropped.
+    ///
+    /// See [`parse_rule_sexp`] for more information.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`parse_rule_sexp`]: fn.parse_rule_sexp.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_ y_) = G(y_) <= x_ = A").expect("parse of F(x_ y_) = G(y_) <= x_ = A");
+    ///
+    /// assert_eq!(r.to_sexp(&sig), "(<= (= (F v0 v1) (G v1)) (= v0 (A)))");
+    /// ```
+    pub fn to_sexp(&self, sig: &Signature) -> String {
+        let mut names = HashMap::new();
+        let equation = format!(
+            "(= {})",
+            iter::once(&self.lhs)
+                .chain(&self.rhs)
+                .map(|t| sexp_term(sig, t, &mut names))
+                .join(" ")
+        );
+        if self.conditions.is_empty() {
+            equation
+        } else {
+            let conditions = self
+                .conditions
+                .iter()
+                .map(|(c, d)| {
+                    let c = sexp_term(sig, c, &mut names);
+                    format!("(= {} {})", c, sexp_term(sig, d, &mut names))
+                })
+                .join(" ");
+            format!("(<= {} {})", equation, conditions)
+        }
+    }
+}
+
+impl TRS {
+    /// Serialize a `TRS` in canonical s-expression syntax, naming the [`Variable`]s of each
+    /// [`Rule`] as in [`Rule::to_sexp`].
+    ///
+    /// See [`parse_trs_sexp`] for more information.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::to_sexp`]: struct.Rule.html#method.to_sexp
+    /// [`parse_trs_sexp`]: fn.parse_trs_sexp.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B; C(x_) = x_;").expect("parse of A = B; C(x_) = x_;");
+    ///
+    /// assert_eq!(t.to_sexp(&sig), "((= (A) (B)) (= (C v0) v0))");
+    /// ```
+    pub fn to_sexp(&self, sig: &Signature) -> String {
+        format!("({})", self.rules.iter().map(|r| r.to_sexp(sig)).join(" "))
+    }
+}
+
+// Serialize a Term, numbering its variables in order of first occurrence.
+fn sexp_term(sig: &Signature, term: &Term, names: &mut HashMap<usize, usize>) -> String {
+    match *term {
+        Term::Variable(ref v) => {
+            let n = names.len();
+            format!("v{}", names.entry(v.id).or_insert(n))
+        }
+        Term::Application { ref op, ref args } if args.is_empty() => {
+            format!("({})", symbol(&operator_name(sig, op)))
+        }
+        Term::Application { ref op, ref args } => {
+            let args = args.iter().map(|arg| sexp_term(sig, arg, names)).join(" ");
+            format!("({} {})", symbol(&operator_name(sig, op)), args)
+        }
+    }
+}
+
+// Build a Term from an s-expression.
+fn term_from_sexp(parser: &mut Parser, sexp: &Sexp) -> Result<Term, ParseError> {
+    match *sexp {
+        Sexp::Atom(ref name) => Ok(Term::Variable(parser.get_var(name))),
+        Sexp::List(ref list) => {
+            let (head, args) = list.split_first().ok_or(ParseError::ParseFailed)?;
+            let name = head.atom().ok_or(ParseError::ParseFailed)?;
+            let args = args
+                .iter()
+                .map(|arg| term_from_sexp(parser, arg))
+                .collect::<Result<Vec<_>, _>>()?;
+            let op = parser.get_op(name, args.len() as u32);
+            Ok(Term::Application { op, args })
+        }
+    }
+}
+
+// Build a Rule from an s-expression, with fresh variables.
+fn rule_from_sexp(parser: &mut Parser, sexp: &Sexp) -> Result<Rule, ParseError> {
+    parser.clear_variables();
+    let list = match *sexp {
+        Sexp::List(ref list) => list,
+        Sexp::Atom(_) => return Err(ParseError::ParseFailed),
+    };
+    match list.split_first() {
+        Some((head, _)) if head.atom() == Some("=") => {
+            let (lhs, rhs) = equation_from_sexp(parser, sexp)?;
+            Rule::new(lhs, rhs).ok_or(ParseError::ParseFailed)
+        }
+        Some((head, rest)) if head.atom() == Some("<=") && !rest.is_empty() => {
+            let (lhs, rhs) = equation_from_sexp(parser, &rest[0])?;
+            let conditions = rest[1..]
+                .iter()
+                .map(|condition| {
+                    let (c, mut d) = equation_from_sexp(parser, condition)?;
+                    if d.len() == 1 {
+                        Ok((c, d.remove(0)))
+                    } else {
+                        Err(ParseError::ParseFailed)
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Rule::new_conditional(lhs, rhs, conditions).ok_or(ParseError::ParseFailed)
+        }
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// Build the sides of `(= lhs rhs ...)` from an s-expression.
+fn equation_from_sexp(parser: &mut Parser, sexp: &Sexp) -> Result<(Term, Vec<Term>), ParseError> {
+    match *sexp {
+        Sexp::List(ref list) if list.len() > 2 && list[0].atom() == Some("=") => {
+            let lhs = term_from_sexp(parser, &list[1])?;
+            let rhs = list[2..]
+                .iter()
+                .map(|t| term_from_sexp(parser, t))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((lhs, rhs))
+        }
+        _ => Err(ParseError::ParseFailed),
+    }
+}
+
+// Read exactly one s-expression.
+fn read_sexp(input: &str) -> Result<Sexp, ParseError> {
+    let mut sexps = read_sexps(input)?;
+    match sexps.len() {
+        0 => Err(ParseError::ParseFailed),
+        1 => Ok(sexps.remove(0)),
+        _ => Err(ParseError::ParseIncomplete),
+    }
+}
+
+// Quote a symbol as `|...|` if it contains special characters.
+pub(crate) fn symbol(name: &str) -> String {
+    if name.is_empty()
+        || name
+            .chars()
+            .any(|c| c.is_whitespace() || "();|".contains(c))
+    {
+        format!("|{}|", name)
+    } else {
+        name.to_string()
+    }
+}
+
+// An s-expression: a symbol or a parenthesized list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+impl Sexp {
+    // The symbol of an Atom.
+    pub(crate) fn atom(&self) -> Option<&str> {
+        match *self {
+            Sexp::Atom(ref a) => Some(a),
+            Sexp::List(_) => None,
+        }
+    }
+}
+
+// Read a sequence of s-expressions, skipping `;` comments. Symbols may be quoted as `|...|`.
+pub(crate) fn read_sexps(input: &str) -> Result<Vec<Sexp>, ParseError> {
+    let mut stack: Vec<Vec<Sexp>> = vec![vec![]];
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                }
+            }
+            '(' => stack.push(vec![]),
+            ')' => {
+                let list = stack.pop().expect("open list");
+                match stack.last_mut() {
+                    Some(parent) => parent.push(Sexp::List(list)),
+                    None => return Err(ParseError::ParseFailed),
+                }
+            }
+            '|' => {
+                let mut symbol = String::new();
+                loop {
+                    match chars.next() {
+                        Some('|') => break,
+                        Some(c) => symbol.push(c),
+                        None => return Err(ParseError::ParseIncomplete),
+                    }
+                }
+                stack
+                    .last_mut()
+                    .expect("open list")
+                    .push(Sexp::Atom(symbol));
+            }
+            c if c.is_whitespace() => (),
+            c => {
+                let mut symbol = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "();|".contains(c) {
+                        break;
+                    }
+                    symbol.push(c);
+                    chars.next();
+                }
+                stack
+                    .last_mut()
+                    .expect("open list")
+                    .push(Sexp::Atom(symbol));
+            }
+        }
+    }
+    match stack.len() {
+        1 => Ok(stack.pop().expect("top level")),
+        _ => Err(ParseError::ParseIncomplete),
+    }
+}
@@ -0,0 +1,205 @@
+use parser::{Parser, DELIMITERS};
+use std::io::BufRead;
+use {ParseError, Rule, Signature};
+
+/// Parse a [`TRS`] from a [`BufRead`] source one [`Rule`] at a time.
+///
+/// The source is read line by line as the returned [`RuleStream`] is iterated, so only the
+/// statement being parsed is held in memory, rather than the whole input. The input follows the
+/// `trs` grammar of [`parse_trs`]: operator blocks and declarations update the [`Signature`] and
+/// are otherwise skipped. After an error, whether reading or parsing, the stream ends. The
+/// position of a [`SyntaxError`] is given relative to the whole input.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`BufRead`]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+/// [`Rule`]: struct.Rule.html
+/// [`RuleStream`]: struct.RuleStream.html
+/// [`parse_trs`]: fn.parse_trs.html
+/// [`Signature`]: struct.Signature.html
+/// [`SyntaxError`]: struct.SyntaxError.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs_stream, ParseError};
+/// let mut sig = Signature::default();
+/// let input = "# addition
+///              PLUS(ZERO y_) = y_;
+///              PLUS(SUCC(x_)
+///                   y_) = SUCC(PLUS(x_ y_));
+///              ZERO = ;".as_bytes();
+///
+/// let mut rules = parse_trs_stream(&mut sig, input);
+///
+/// let first = rules.next().unwrap().unwrap();
+/// let second = rules.next().unwrap().unwrap();
+/// match rules.next() {
+///     Some(Err(ParseError::Syntax(e))) => assert_eq!((e.line, e.column), (5, 21)),
+///     _ => panic!("expected a syntax error"),
+/// }
+/// assert!(rules.next().is_none());
+///
+/// assert_eq!(first.display(&sig), "PLUS(ZERO y_) = y_");
+/// assert_eq!(second.display(&sig), "PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_))");
+/// ```
+pub fn parse_trs_stream<'a, R: BufRead>(sig: &'a mut Signature, source: R) -> RuleStream<'a, R> {
+    RuleStream {
+        parser: Some(Parser::new(sig)),
+        source,
+        pending: String::new(),
+        offset: 0,
+        line: 1,
+        column: 1,
+        line_prefix: String::new(),
+        done: false,
+    }
+}
+
+/// An `Iterator` over the [`Rule`]s read from a [`BufRead`] source, created by
+/// [`parse_trs_stream`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`BufRead`]: https://doc.rust-lang.org/std/io/trait.BufRead.html
+/// [`parse_trs_stream`]: fn.parse_trs_stream.html
+pub struct RuleStream<'a, R> {
+    parser: Option<Parser<'a>>,
+    source: R,
+    // Input read but not yet parsed.
+    pending: String,
+    // The position of the start of `pending` in the whole input.
+    offset: usize,
+    line: usize,
+    column: usize,
+    // The text of the current line before the start of `pending`.
+    line_prefix: String,
+    done: bool,
+}
+
+impl<'a, R: BufRead> RuleStream<'a, R> {
+    // Read another line into `pending`, returning `false` at the end of the input.
+    fn read(&mut self) -> Result<bool, ParseError> {
+        self.source
+            .read_line(&mut self.pending)
+            .map(|n| n > 0)
+            .map_err(|e| ParseError::Io(e.to_string()))
+    }
+    // Drop the first `n` bytes of `pending`, moving the position past them.
+    fn advance(&mut self, n: usize) {
+        for c in self.pending[..n].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+                self.line_prefix.clear();
+            } else {
+                self.column += 1;
+                self.line_prefix.push(c);
+            }
+        }
+        self.offset += n;
+        self.pending.drain(..n);
+    }
+    // Skip whitespace and comments, then find the length of the next statement in `pending`,
+    // reading as much input as it needs.
+    fn next_statement(&mut self) -> Result<Option<usize>, ParseError> {
+        loop {
+            let skip = self.pending.len()
+                - self
+                    .pending
+                    .trim_start_matches(|c| " \t\r\n".contains(c))
+                    .len();
+            if self.pending[skip..].starts_with('#') {
+                if let Some(newline) = self.pending[skip..].find('\n') {
+                    self.advance(skip + newline + 1);
+                    continue;
+                }
+            } else {
+                self.advance(skip);
+                if let Some(len) = statement_len(&self.pending) {
+                    return Ok(Some(len));
+                }
+            }
+            if !self.read()? {
+                // Anything left is an unterminated statement or comment, which fails to parse.
+                return Ok(if self.pending.trim().is_empty() {
+                    None
+                } else {
+                    Some(self.pending.len())
+                });
+            }
+        }
+    }
+    // Move the position of a `SyntaxError` in `pending` to its position in the whole input.
+    fn locate(&self, error: ParseError) -> ParseError {
+        match error {
+            ParseError::Syntax(mut e) => {
+                if e.line == 1 {
+                    e.column += self.column - 1;
+                    e.source = format!("{}{}", self.line_prefix, e.source);
+                }
+                e.line += self.line - 1;
+                e.offset += self.offset;
+                ParseError::Syntax(e)
+            }
+            e => e,
+        }
+    }
+}
+
+impl<'a, R: BufRead> Iterator for RuleStream<'a, R> {
+    type Item = Result<Rule, ParseError>;
+    fn next(&mut self) -> Option<Result<Rule, ParseError>> {
+        while !self.done {
+            let len = match self.next_statement() {
+                Ok(Some(len)) => len,
+                Ok(None) => break,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+            let parser = self.parser.take().expect("parser");
+            let (parser, result) = parser.next_rule(&self.pending[..len]);
+            self.parser = Some(parser);
+            match result {
+                Ok(Some(rule)) => {
+                    self.advance(len);
+                    return Some(Ok(rule));
+                }
+                Ok(None) => self.advance(len),
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(self.locate(e)));
+                }
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+// The length of the statement at the start of `text` through its `;`, if it is complete. A `;`
+// in a quoted name or string doesn't end the statement.
+fn statement_len(text: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut prev = None;
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            // Quotes only open at the start of a token: `don't` is a bare identifier.
+            None if (c == '\'' || c == '"')
+                && prev.is_none_or(|p: char| p.is_whitespace() || DELIMITERS.contains(p)) =>
+            {
+                quote = Some(c)
+            }
+            None if c == ';' => return Some(i + 1),
+            None => (),
+        }
+        prev = Some(c);
+    }
+    None
+}
@@ -0,0 +1,519 @@
+//! The [dependency pair framework] for proving termination.
+//!
+//! Each [`Rule`] `f(s1 ... sn) = C[g(t1 ... tm)]` with `g` a defined symbol, i.e. the root of
+//! some LHS, yields a [`DependencyPair`] `f#(s1 ... sn) -> g#(t1 ... tm)`, where `f#` and `g#`
+//! are fresh marked [`Operator`]s. A [`TRS`] terminates if there is no infinite chain of
+//! [`DependencyPair`]s. Such a chain must stay within one strongly connected component of the
+//! [`DependencyGraph`], so each component is handled separately by [`Processor`]s which delete
+//! [`DependencyPair`]s that cannot occur infinitely often.
+//!
+//! [dependency pair framework]: https://doi.org/10.1016/S0304-3975(99)00207-8
+//! [`Rule`]: ../../struct.Rule.html
+//! [`Operator`]: ../../struct.Operator.html
+//! [`TRS`]: ../../struct.TRS.html
+//! [`DependencyPair`]: struct.DependencyPair.html
+//! [`DependencyGraph`]: struct.DependencyGraph.html
+//! [`Processor`]: trait.Processor.html
+//!
+//! # Examples
+//!
+//! ```
+//! use term_rewriting::{parse_trs, Signature};
+//! use term_rewriting::orderings::Lpo;
+//! use term_rewriting::termination::dp::{prove_termination, ReductionPairProcessor, SubtermCriterion};
+//!
+//! let mut sig = Signature::default();
+//! let trs = parse_trs(&mut sig,
+//! "PLUS(x_ ZERO) = x_;
+//! PLUS(x_ SUCC(y_)) = PLUS(SUCC(x_) y_);").expect("parse of PLUS rules");
+//!
+//! // No LPO orients the second rule, but the dependency pairs can be handled.
+//! let lpo = ReductionPairProcessor::new(Lpo::new(vec![]));
+//! assert!(prove_termination(&mut sig, &trs, &[&lpo]).is_err());
+//! assert!(prove_termination(&mut sig, &trs, &[&lpo, &SubtermCriterion]).is_ok());
+//! ```
+
+use itertools::Itertools;
+use std::collections::HashMap;
+
+use orderings::ReductionOrdering;
+use {Operator, Signature, Term, TRS};
+
+/// A dependency pair `lhs -> rhs` between two [`Term`]s with marked roots.
+///
+/// [`Term`]: ../../enum.Term.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DependencyPair {
+    /// The marked LHS of the [`Rule`] from which the pair arises.
+    ///
+    /// [`Rule`]: ../../struct.Rule.html
+    pub lhs: Term,
+    /// A marked subterm of a RHS of the [`Rule`] from which the pair arises.
+    ///
+    /// [`Rule`]: ../../struct.Rule.html
+    pub rhs: Term,
+}
+impl DependencyPair {
+    /// Serialize a `DependencyPair`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{parse_trs, Signature};
+    /// # use term_rewriting::termination::dp::dependency_pairs;
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig, "F(SUCC(x_)) = G(F(x_));").expect("parse of F(SUCC(x_)) = G(F(x_));");
+    ///
+    /// assert_eq!(dependency_pairs(&mut sig, &trs)[0].display(&sig), "F#(SUCC(x_)) -> F#(x_)");
+    /// ```
+    pub fn display(&self, sig: &Signature) -> String {
+        format!("{} -> {}", self.lhs.display(sig), self.rhs.display(sig))
+    }
+}
+
+/// The [`DependencyPair`]s of a [`TRS`].
+///
+/// [`DependencyPair`]: struct.DependencyPair.html
+/// [`TRS`]: ../../struct.TRS.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{parse_trs, Signature};
+/// # use term_rewriting::termination::dp::dependency_pairs;
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig,
+/// "PLUS(ZERO y_) = y_;
+/// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));
+/// TIMES(SUCC(x_) y_) = PLUS(y_ TIMES(x_ y_));").expect("parse of PLUS and TIMES rules");
+///
+/// let pairs: Vec<String> = dependency_pairs(&mut sig, &trs)
+///     .iter()
+///     .map(|p| p.display(&sig))
+///     .collect();
+///
+/// assert_eq!(pairs, vec![
+///     "PLUS#(SUCC(x_) y_) -> PLUS#(x_ y_)",
+///     "TIMES#(SUCC(x_) y_) -> PLUS#(y_ TIMES(x_ y_))",
+///     "TIMES#(SUCC(x_) y_) -> TIMES#(x_ y_)",
+/// ]);
+/// ```
+pub fn dependency_pairs(sig: &mut Signature, trs: &TRS) -> Vec<DependencyPair> {
+    let defined = defined_symbols(trs);
+    let mut pairs = vec![];
+    for rule in &trs.rules {
+        let lhs = mark(sig, &rule.lhs);
+        let lhs_subterms: Vec<&Term> = rule.lhs.subterms().into_iter().map(|(t, _)| t).collect();
+        for rhs in &rule.rhs {
+            for (subterm, _) in rhs.subterms() {
+                let is_defined = match *subterm {
+                    Term::Application { ref op, .. } => defined.contains(op),
+                    Term::Variable(_) => false,
+                };
+                if is_defined && !lhs_subterms[1..].contains(&subterm) {
+                    let pair = DependencyPair {
+                        lhs: lhs.clone(),
+                        rhs: mark(sig, subterm),
+                    };
+                    if !pairs.contains(&pair) {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// The estimated dependency graph of a [`TRS`]. There is an edge from one [`DependencyPair`] to
+/// another if the first might be followed by the second in a chain.
+///
+/// [`TRS`]: ../../struct.TRS.html
+/// [`DependencyPair`]: struct.DependencyPair.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyGraph {
+    /// The [`DependencyPair`]s which make up the nodes of the graph.
+    ///
+    /// [`DependencyPair`]: struct.DependencyPair.html
+    pub pairs: Vec<DependencyPair>,
+    edges: Vec<Vec<usize>>,
+}
+impl DependencyGraph {
+    /// Estimate the dependency graph of a [`TRS`]. `s -> t` may be followed by `u -> v` if `u`
+    /// unifies with `t` once each subterm of `t` with a defined root and each [`Variable`] of
+    /// `t` is replaced by a fresh [`Variable`].
+    ///
+    /// [`TRS`]: ../../struct.TRS.html
+    /// [`Variable`]: ../../struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{parse_trs, Signature};
+    /// # use term_rewriting::termination::dp::DependencyGraph;
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig,
+    /// "F(A x_) = G(x_);
+    /// G(B) = F(A B);").expect("parse of F(A x_) = G(x_); G(B) = F(A B);");
+    ///
+    /// let graph = DependencyGraph::new(&mut sig, &trs);
+    ///
+    /// assert_eq!(graph.successors(0), &[1]);
+    /// assert_eq!(graph.successors(1), &[0]);
+    /// ```
+    pub fn new(sig: &mut Signature, trs: &TRS) -> DependencyGraph {
+        let defined = defined_symbols(trs);
+        let pairs = dependency_pairs(sig, trs);
+        let mut edges = vec![];
+        for p in &pairs {
+            let capped = cap(sig, &p.rhs, &defined);
+            let mut successors = vec![];
+            for (j, q) in pairs.iter().enumerate() {
+                let lhs = rename(sig, &q.lhs);
+                if Term::unify(vec![(&capped, &lhs)]).is_some() {
+                    successors.push(j);
+                }
+            }
+            edges.push(successors);
+        }
+        DependencyGraph { pairs, edges }
+    }
+    /// The indices of the [`DependencyPair`]s which may follow the `i`th [`DependencyPair`].
+    ///
+    /// [`DependencyPair`]: struct.DependencyPair.html
+    pub fn successors(&self, i: usize) -> &[usize] {
+        &self.edges[i]
+    }
+    /// The strongly connected components of the graph which contain a cycle, as lists of
+    /// indices into [`pairs`].
+    ///
+    /// [`pairs`]: #structfield.pairs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{parse_trs, Signature};
+    /// # use term_rewriting::termination::dp::DependencyGraph;
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig,
+    /// "PLUS(ZERO y_) = y_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));
+    /// TIMES(SUCC(x_) y_) = PLUS(y_ TIMES(x_ y_));").expect("parse of PLUS and TIMES rules");
+    ///
+    /// let graph = DependencyGraph::new(&mut sig, &trs);
+    ///
+    /// assert_eq!(graph.sccs(), vec![vec![0], vec![2]]);
+    /// ```
+    pub fn sccs(&self) -> Vec<Vec<usize>> {
+        let nodes: Vec<usize> = (0..self.pairs.len()).collect();
+        self.sccs_within(&nodes)
+    }
+    // The cyclic strongly connected components of the subgraph induced by `nodes`, using
+    // Tarjan's algorithm.
+    fn sccs_within(&self, nodes: &[usize]) -> Vec<Vec<usize>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            nodes,
+            index: HashMap::new(),
+            lowlink: HashMap::new(),
+            stack: vec![],
+            sccs: vec![],
+        };
+        for &n in nodes {
+            if !tarjan.index.contains_key(&n) {
+                tarjan.visit(n);
+            }
+        }
+        let mut sccs: Vec<Vec<usize>> = tarjan
+            .sccs
+            .into_iter()
+            .filter(|scc| scc.len() > 1 || self.edges[scc[0]].contains(&scc[0]))
+            .map(|mut scc| {
+                scc.sort();
+                scc
+            })
+            .collect();
+        sccs.sort();
+        sccs
+    }
+}
+
+struct Tarjan<'a> {
+    graph: &'a DependencyGraph,
+    nodes: &'a [usize],
+    index: HashMap<usize, usize>,
+    lowlink: HashMap<usize, usize>,
+    stack: Vec<usize>,
+    sccs: Vec<Vec<usize>>,
+}
+impl<'a> Tarjan<'a> {
+    fn visit(&mut self, n: usize) {
+        let i = self.index.len();
+        self.index.insert(n, i);
+        self.lowlink.insert(n, i);
+        self.stack.push(n);
+        for &m in &self.graph.edges[n] {
+            if !self.nodes.contains(&m) {
+                continue;
+            }
+            if !self.index.contains_key(&m) {
+                self.visit(m);
+                let low = self.lowlink[&n].min(self.lowlink[&m]);
+                self.lowlink.insert(n, low);
+            } else if self.stack.contains(&m) {
+                let low = self.lowlink[&n].min(self.index[&m]);
+                self.lowlink.insert(n, low);
+            }
+        }
+        if self.lowlink[&n] == self.index[&n] {
+            let pos = self.stack.iter().position(|&m| m == n).unwrap();
+            self.sccs.push(self.stack.split_off(pos));
+        }
+    }
+}
+
+/// A [reduction pair]: a weakly monotonic quasi-ordering `ge` together with a compatible
+/// well-founded ordering `gt`. Every [`ReductionOrdering`] gives a reduction pair.
+///
+/// [reduction pair]: https://doi.org/10.1016/S0304-3975(99)00207-8
+/// [`ReductionOrdering`]: ../../orderings/trait.ReductionOrdering.html
+pub trait ReductionPair {
+    /// Is `s` strictly greater than `t`?
+    fn gt(&self, s: &Term, t: &Term) -> bool;
+    /// Is `s` greater than or equivalent to `t`?
+    fn ge(&self, s: &Term, t: &Term) -> bool;
+}
+impl<O: ReductionOrdering> ReductionPair for O {
+    fn gt(&self, s: &Term, t: &Term) -> bool {
+        ReductionOrdering::gt(self, s, t)
+    }
+    fn ge(&self, s: &Term, t: &Term) -> bool {
+        ReductionOrdering::ge(self, s, t)
+    }
+}
+
+/// A dependency pair processor, which simplifies a set of [`DependencyPair`]s forming a cycle
+/// in the [`DependencyGraph`].
+///
+/// [`DependencyPair`]: struct.DependencyPair.html
+/// [`DependencyGraph`]: struct.DependencyGraph.html
+pub trait Processor {
+    /// The indices of those `pairs` which cannot occur infinitely often in a chain, or `None` if
+    /// the processor does not apply.
+    fn process(&self, trs: &TRS, pairs: &[DependencyPair]) -> Option<Vec<usize>>;
+}
+
+/// The reduction pair processor. If every [`Rule`] and [`DependencyPair`] is weakly decreasing
+/// in a [`ReductionPair`], the strictly decreasing [`DependencyPair`]s can be removed.
+///
+/// [`Rule`]: ../../struct.Rule.html
+/// [`DependencyPair`]: struct.DependencyPair.html
+/// [`ReductionPair`]: trait.ReductionPair.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReductionPairProcessor<P: ReductionPair> {
+    pair: P,
+}
+impl<P: ReductionPair> ReductionPairProcessor<P> {
+    /// Create a `ReductionPairProcessor` from a [`ReductionPair`].
+    ///
+    /// [`ReductionPair`]: trait.ReductionPair.html
+    pub fn new(pair: P) -> ReductionPairProcessor<P> {
+        ReductionPairProcessor { pair }
+    }
+}
+impl<P: ReductionPair> Processor for ReductionPairProcessor<P> {
+    fn process(&self, trs: &TRS, pairs: &[DependencyPair]) -> Option<Vec<usize>> {
+        let rules_decrease = trs
+            .rules
+            .iter()
+            .all(|r| r.rhs.iter().all(|rhs| self.pair.ge(&r.lhs, rhs)));
+        if !rules_decrease || !pairs.iter().all(|p| self.pair.ge(&p.lhs, &p.rhs)) {
+            return None;
+        }
+        let strict: Vec<usize> = pairs
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| self.pair.gt(&p.lhs, &p.rhs))
+            .map(|(i, _)| i)
+            .collect();
+        if strict.is_empty() {
+            None
+        } else {
+            Some(strict)
+        }
+    }
+}
+
+/// The subterm criterion. If some choice of an argument for each marked root makes every
+/// [`DependencyPair`] weakly decrease in the subterm relation, the strictly decreasing
+/// [`DependencyPair`]s can be removed. No [`Rule`]s need be considered.
+///
+/// [`DependencyPair`]: struct.DependencyPair.html
+/// [`Rule`]: ../../struct.Rule.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubtermCriterion;
+impl Processor for SubtermCriterion {
+    fn process(&self, _trs: &TRS, pairs: &[DependencyPair]) -> Option<Vec<usize>> {
+        let roots: Vec<Operator> = pairs
+            .iter()
+            .flat_map(|p| vec![root(&p.lhs), root(&p.rhs)])
+            .unique()
+            .collect();
+        if roots.iter().any(|op| op.arity() == 0) {
+            return None;
+        }
+        let projections = roots.iter().map(|op| 0..op.arity() as usize);
+        for projection in projections.multi_cartesian_product() {
+            let project = |t: &Term| {
+                let i = roots.iter().position(|op| *op == root(t)).unwrap();
+                t.args()[projection[i]].clone()
+            };
+            let mut strict = vec![];
+            let mut weak = true;
+            for (i, p) in pairs.iter().enumerate() {
+                let (s, t) = (project(&p.lhs), project(&p.rhs));
+                if s == t {
+                    continue;
+                } else if s.subterms().iter().any(|(u, _)| **u == t) {
+                    strict.push(i);
+                } else {
+                    weak = false;
+                    break;
+                }
+            }
+            if weak && !strict.is_empty() {
+                return Some(strict);
+            }
+        }
+        None
+    }
+}
+
+/// Try to prove that a [`TRS`] terminates. Each cycle of the [`DependencyGraph`] is given to the
+/// `processors` in turn until one removes some [`DependencyPair`]s, and what remains is split
+/// into cycles again. Returns the cycles which no processor could simplify.
+///
+/// [`TRS`]: ../../struct.TRS.html
+/// [`DependencyGraph`]: struct.DependencyGraph.html
+/// [`DependencyPair`]: struct.DependencyPair.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{parse_trs, Signature};
+/// # use term_rewriting::orderings::Lpo;
+/// # use term_rewriting::termination::dp::{prove_termination, ReductionPairProcessor};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig, "F(x_) = G(F(x_));").expect("parse of F(x_) = G(F(x_));");
+///
+/// let lpo = ReductionPairProcessor::new(Lpo::new(vec![]));
+/// let cycles = prove_termination(&mut sig, &trs, &[&lpo]).unwrap_err();
+///
+/// assert_eq!(cycles.len(), 1);
+/// assert_eq!(cycles[0][0].display(&sig), "F#(x_) -> F#(x_)");
+/// ```
+pub fn prove_termination(
+    sig: &mut Signature,
+    trs: &TRS,
+    processors: &[&dyn Processor],
+) -> Result<(), Vec<Vec<DependencyPair>>> {
+    let graph = DependencyGraph::new(sig, trs);
+    let mut cycles = graph.sccs();
+    let mut unsolved = vec![];
+    while let Some(cycle) = cycles.pop() {
+        let pairs: Vec<DependencyPair> = cycle.iter().map(|&i| graph.pairs[i].clone()).collect();
+        match processors
+            .iter()
+            .filter_map(|p| p.process(trs, &pairs))
+            .next()
+        {
+            Some(removed) => {
+                let remaining: Vec<usize> = cycle
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !removed.contains(i))
+                    .map(|(_, &n)| n)
+                    .collect();
+                cycles.append(&mut graph.sccs_within(&remaining));
+            }
+            None => unsolved.push(pairs),
+        }
+    }
+    if unsolved.is_empty() {
+        Ok(())
+    } else {
+        Err(unsolved)
+    }
+}
+
+// The roots of the LHSs of `trs`.
+fn defined_symbols(trs: &TRS) -> Vec<Operator> {
+    trs.rules
+        .iter()
+        .filter_map(|r| match r.lhs {
+            Term::Application { ref op, .. } => Some(*op),
+            Term::Variable(_) => None,
+        })
+        .unique()
+        .collect()
+}
+
+// The root of an application.
+fn root(term: &Term) -> Operator {
+    match *term {
+        Term::Application { ref op, .. } => *op,
+        Term::Variable(_) => panic!("dependency pairs have marked roots"),
+    }
+}
+
+// Replace the root of `term` by its marked version, creating it in `sig` if need be.
+fn mark(sig: &mut Signature, term: &Term) -> Term {
+    match *term {
+        Term::Application { op, ref args } => {
+            let name = format!("{}#", op.display(sig));
+            let marked = match sig.operator(&name, op.arity()) {
+                Some(marked) => marked,
+                None => sig.new_op(op.arity(), Some(name)),
+            };
+            Term::Application {
+                op: marked,
+                args: args.clone(),
+            }
+        }
+        Term::Variable(_) => term.clone(),
+    }
+}
+
+// Replace each proper subterm of `term` with a defined root and each variable with a fresh
+// variable from `sig`.
+fn cap(sig: &mut Signature, term: &Term, defined: &[Operator]) -> Term {
+    match *term {
+        Term::Application { op, ref args } => Term::Application {
+            op,
+            args: args
+                .iter()
+                .map(|arg| match *arg {
+                    Term::Application { ref op, .. } if defined.contains(op) => {
+                        Term::Variable(sig.new_var(None))
+                    }
+                    _ => cap(sig, arg, defined),
+                })
+                .collect(),
+        },
+        Term::Variable(_) => Term::Variable(sig.new_var(None)),
+    }
+}
+
+// Rename the variables of `term` apart, with fresh variables from `sig`.
+fn rename(sig: &mut Signature, term: &Term) -> Term {
+    let fresh: Vec<_> = term
+        .variables()
+        .into_iter()
+        .map(|v| {
+            let w = sig.new_var(v.name(sig));
+            (v, Term::Variable(w))
+        })
+        .collect();
+    let sub = fresh.iter().map(|(v, t)| (v, t)).collect();
+    term.substitute(&sub)
+}
@@ -0,0 +1,12 @@
+//! Tools for proving that a [`TRS`] terminates.
+//!
+//! A [`TRS`] whose [`Rule`]s are all oriented by a single
+//! [`ReductionOrdering`] terminates, but many terminating systems have no such ordering. The
+//! [`dp`] module implements the more powerful dependency pair framework.
+//!
+//! [`TRS`]: ../struct.TRS.html
+//! [`Rule`]: ../struct.Rule.html
+//! [`ReductionOrdering`]: ../orderings/trait.ReductionOrdering.html
+//! [`dp`]: dp/index.html
+
+pub mod dp;
@@ -0,0 +1,406 @@
+use parser::Parser;
+use problem::{condition_mode, operator_name, variable_name};
+use {ConditionConfig, ParseError, Problem, Rule, Signature, Term, TRS};
+
+use itertools::Itertools;
+
+// Tokens with special meaning, which cannot be used as identifiers.
+const SPECIAL: [&str; 7] = ["(", ")", ",", "|", "->=", "->", "=="];
+
+/// Parse a string in the old TPDB format of the Termination Problems Data Base as a [`TRS`],
+/// as read by termination tools like AProVE and TTT2.
+///
+/// Identifiers not declared by `VAR` are [`Operator`]s, with an [`Operator`] for each arity at
+/// which a name is used. Conditional rules list their conditions after a `|`, as in
+/// `f(x) -> y | g(x) -> y`. Other declarations, such as `COMMENT`, `STRATEGY`, and `THEORY`,
+/// are skipped.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Operator`]: struct.Operator.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs_tpdb};
+/// let mut sig = Signature::default();
+///
+/// let trs = parse_trs_tpdb(
+///     &mut sig,
+///     "(VAR x y)
+///      (RULES
+///        plus(0, y) -> y
+///        plus(s(x), y) -> s(plus(x, y))
+///      )
+///      (COMMENT addition on unary numerals)",
+/// ).expect("parse of TPDB problem");
+///
+/// assert_eq!(trs.display(&sig), "plus(0 y_) = y_;\nplus(s(x_) y_) = s(plus(x_ y_));");
+/// ```
+pub fn parse_trs_tpdb(sig: &mut Signature, input: &str) -> Result<TRS, ParseError> {
+    parse_cops(sig, input).map(|problem| problem.trs)
+}
+
+/// Parse a string in the COPS format of the Confluence Problems database as a [`Problem`].
+///
+/// The COPS format extends the TPDB format (see [`parse_trs_tpdb`]) with a `CONDITIONTYPE`
+/// declaration, which sets the [`ConditionMode`] of the [`TRS`], and keeps the `COMMENT` as
+/// metadata: each line of the form `@key value` gives the pair `(key, value)`, and the other
+/// lines give a single `comment`.
+///
+/// [`Problem`]: struct.Problem.html
+/// [`parse_trs_tpdb`]: fn.parse_trs_tpdb.html
+/// [`ConditionMode`]: enum.ConditionMode.html
+/// [`TRS`]: struct.TRS.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{ConditionMode, Signature, parse_cops};
+/// let mut sig = Signature::default();
+///
+/// let problem = parse_cops(
+///     &mut sig,
+///     "(CONDITIONTYPE JOIN)
+///      (VAR x)
+///      (RULES
+///        f(x) -> a | x == b
+///        f(x) -> b | x == a
+///      )
+///      (COMMENT
+///      @author Aart Middeldorp
+///      joinability conditions
+///      )",
+/// ).expect("parse of COPS problem");
+///
+/// assert_eq!(problem.trs.len(), 2);
+/// assert_eq!(problem.trs.condition_config().mode, ConditionMode::Join);
+/// assert_eq!(
+///     problem.metadata,
+///     vec![
+///         ("author".to_string(), "Aart Middeldorp".to_string()),
+///         ("comment".to_string(), "joinability conditions".to_string()),
+///     ]
+/// );
+/// ```
+pub fn parse_cops(sig: &mut Signature, input: &str) -> Result<Problem, ParseError> {
+    let mut reader = Reader {
+        parser: Parser::new(sig),
+        tokens: tokenize(input),
+        pos: 0,
+        input_len: input.len(),
+        vars: vec![],
+    };
+    let mut rules = vec![];
+    let mut mode = None;
+    let mut metadata = vec![];
+    while reader.peek().is_some() {
+        reader.expect("(")?;
+        match reader.next() {
+            Some("VAR") => {
+                while reader.peek() != Some(")") {
+                    let name = reader.identifier()?.to_string();
+                    reader.vars.push(name);
+                }
+            }
+            Some("RULES") => {
+                while reader.peek() != Some(")") {
+                    rules.push(reader.rule()?);
+                }
+            }
+            Some("CONDITIONTYPE") => {
+                mode = Some(condition_mode(reader.identifier()?).ok_or(ParseError::ParseFailed)?);
+            }
+            Some("COMMENT") => {
+                let start = reader.offset();
+                reader.skip()?;
+                metadata.extend(comment_metadata(&input[start..reader.offset()]));
+            }
+            Some(_) => reader.skip()?,
+            None => return Err(ParseError::ParseIncomplete),
+        }
+        reader.expect(")")?;
+    }
+    let mut trs = TRS::new(rules);
+    if let Some(mode) = mode {
+        trs.set_condition_config(ConditionConfig {
+            mode,
+            ..trs.condition_config()
+        });
+    }
+    Ok(Problem { trs, metadata })
+}
+
+// The metadata in a COPS comment.
+fn comment_metadata(comment: &str) -> Vec<(String, String)> {
+    let mut metadata = vec![];
+    let mut text = vec![];
+    for line in comment.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(line) = line.strip_prefix('@') {
+            let mut parts = line.splitn(2, char::is_whitespace);
+            let key = parts.next().unwrap_or("").to_string();
+            let value = parts.next().unwrap_or("").trim().to_string();
+            metadata.push((key, value));
+        } else {
+            text.push(line);
+        }
+    }
+    if !text.is_empty() {
+        metadata.push(("comment".to_string(), text.join("\n")));
+    }
+    metadata
+}
+
+impl Problem {
+    /// Serialize a `Problem` in the COPS format, writing its metadata in the `COMMENT`.
+    ///
+    /// See [`parse_cops`] and [`TRS::to_tpdb`] for more information.
+    ///
+    /// [`parse_cops`]: fn.parse_cops.html
+    /// [`TRS::to_tpdb`]: struct.TRS.html#method.to_tpdb
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Problem, Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let trs = parse_trs(&mut sig, "F(x_) = A <= x_ = B;").expect("parse of F(x_) = A <= x_ = B;");
+    /// let problem = Problem {
+    ///     trs,
+    ///     metadata: vec![("origin".to_string(), "COPS #1".to_string())],
+    /// };
+    ///
+    /// assert_eq!(
+    ///     problem.to_cops(&sig),
+    ///     "(CONDITIONTYPE ORIENTED)\n(VAR x)\n(RULES\n  F(x) -> A | x -> B\n)\n(COMMENT\n@origin COPS #1\n)\n"
+    /// );
+    /// ```
+    pub fn to_cops(&self, sig: &Signature) -> String {
+        let mut cops = String::new();
+        if self.is_conditional() {
+            cops.push_str(&format!(
+                "(CONDITIONTYPE {})\n",
+                self.condition_type().to_uppercase()
+            ));
+        }
+        cops.push_str(&self.trs.to_tpdb(sig));
+        if !self.metadata.is_empty() {
+            cops.push_str("(COMMENT\n");
+            for (key, value) in &self.metadata {
+                if key == "comment" {
+                    cops.push_str(value);
+                } else {
+                    cops.push_str(&format!("@{} {}", key, value));
+                }
+                cops.push('\n');
+            }
+            cops.push_str(")\n");
+        }
+        cops
+    }
+}
+
+impl TRS {
+    /// Serialize a `TRS` in the TPDB format. Each clause of a [`Rule`] becomes its own TPDB
+    /// rule, and weights are dropped.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let trs = parse_trs(&mut sig, "PLUS(ZERO y_) = y_; F(x_) = A | B(x_) <= G(x_) = A;")
+    ///     .expect("parse of PLUS(ZERO y_) = y_; F(x_) = A | B(x_) <= G(x_) = A;");
+    ///
+    /// assert_eq!(
+    ///     trs.to_tpdb(&sig),
+    ///     "(VAR y x)\n(RULES\n  PLUS(ZERO, y) -> y\n  F(x) -> A | G(x) -> A\n  F(x) -> B(x) | G(x) -> A\n)\n"
+    /// );
+    /// ```
+    pub fn to_tpdb(&self, sig: &Signature) -> String {
+        let vars = self
+            .rules
+            .iter()
+            .flat_map(Rule::variables)
+            .map(|v| variable_name(sig, &v))
+            .unique()
+            .join(" ");
+        let rules = self
+            .rules
+            .iter()
+            .flat_map(|rule| {
+                let conditions = rule
+                    .conditions
+                    .iter()
+                    .map(|(c, d)| format!("{} -> {}", tpdb_term(sig, c), tpdb_term(sig, d)))
+                    .join(", ");
+                rule.rhs.iter().map(move |rhs| {
+                    let mut line =
+                        format!("  {} -> {}", tpdb_term(sig, &rule.lhs), tpdb_term(sig, rhs));
+                    if !conditions.is_empty() {
+                        line.push_str(" | ");
+                        line.push_str(&conditions);
+                    }
+                    line.push('\n');
+                    line
+                })
+            })
+            .collect::<String>();
+        format!("(VAR {})\n(RULES\n{})\n", vars, rules)
+    }
+}
+
+// Serialize a Term with TPDB syntax.
+fn tpdb_term(sig: &Signature, term: &Term) -> String {
+    match *term {
+        Term::Variable(ref v) => variable_name(sig, v),
+        Term::Application { ref op, ref args } if args.is_empty() => operator_name(sig, op),
+        Term::Application { ref op, ref args } => format!(
+            "{}({})",
+            operator_name(sig, op),
+            args.iter().map(|arg| tpdb_term(sig, arg)).join(", ")
+        ),
+    }
+}
+
+// Split input into special tokens and the identifiers between them, with their offsets.
+fn tokenize(input: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut offset = 0;
+    for word in input.split_whitespace() {
+        offset += input[offset..].find(word).expect("word of input");
+        let mut rest = word;
+        while !rest.is_empty() {
+            let next = rest
+                .char_indices()
+                .filter_map(|(i, _)| {
+                    SPECIAL
+                        .iter()
+                        .find(|s| rest[i..].starts_with(*s))
+                        .map(|s| (i, s.len()))
+                })
+                .next();
+            let len = match next {
+                Some((0, len)) => len,
+                Some((i, _)) => i,
+                None => rest.len(),
+            };
+            tokens.push((offset, &rest[..len]));
+            rest = &rest[len..];
+            offset += len;
+        }
+    }
+    tokens
+}
+
+// A recursive descent parser over TPDB tokens.
+struct Reader<'a, 'b> {
+    parser: Parser<'a>,
+    tokens: Vec<(usize, &'b str)>,
+    pos: usize,
+    input_len: usize,
+    // The names declared by VAR.
+    vars: Vec<String>,
+}
+impl<'a, 'b> Reader<'a, 'b> {
+    fn peek(&self) -> Option<&'b str> {
+        self.tokens.get(self.pos).map(|&(_, token)| token)
+    }
+    // The offset of the next token, or the end of input.
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|&(offset, _)| offset)
+            .unwrap_or(self.input_len)
+    }
+    fn next(&mut self) -> Option<&'b str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            Some(_) => Err(ParseError::ParseFailed),
+            None => Err(ParseError::ParseIncomplete),
+        }
+    }
+    fn identifier(&mut self) -> Result<&'b str, ParseError> {
+        match self.next() {
+            Some(t) if SPECIAL.contains(&t) => Err(ParseError::ParseFailed),
+            Some(t) => Ok(t),
+            None => Err(ParseError::ParseIncomplete),
+        }
+    }
+    // Skip the rest of a declaration, up to its closing paren.
+    fn skip(&mut self) -> Result<(), ParseError> {
+        let mut depth = 0;
+        loop {
+            match self.peek() {
+                Some(")") if depth == 0 => return Ok(()),
+                Some(")") => depth -= 1,
+                Some("(") => depth += 1,
+                Some(_) => (),
+                None => return Err(ParseError::ParseIncomplete),
+            }
+            self.pos += 1;
+        }
+    }
+    fn rule(&mut self) -> Result<Rule, ParseError> {
+        self.parser.clear_variables();
+        let lhs = self.term()?;
+        self.expect("->")?;
+        let rhs = self.term()?;
+        let mut conditions = vec![];
+        if self.peek() == Some("|") {
+            self.pos += 1;
+            loop {
+                let c = self.term()?;
+                match self.next() {
+                    Some("->") | Some("==") => (),
+                    Some(_) => return Err(ParseError::ParseFailed),
+                    None => return Err(ParseError::ParseIncomplete),
+                }
+                conditions.push((c, self.term()?));
+                if self.peek() != Some(",") {
+                    break;
+                }
+                self.pos += 1;
+            }
+        }
+        Rule::new_conditional(lhs, vec![rhs], conditions).ok_or(ParseError::ParseFailed)
+    }
+    fn term(&mut self) -> Result<Term, ParseError> {
+        let name = self.identifier()?;
+        let mut args = vec![];
+        if self.peek() == Some("(") {
+            self.pos += 1;
+            if self.peek() == Some(")") {
+                self.pos += 1;
+            } else {
+                loop {
+                    args.push(self.term()?);
+                    match self.next() {
+                        Some(",") => (),
+                        Some(")") => break,
+                        Some(_) => return Err(ParseError::ParseFailed),
+                        None => return Err(ParseError::ParseIncomplete),
+                    }
+                }
+            }
+        }
+        if self.vars.iter().any(|v| v == name) {
+            if args.is_empty() {
+                Ok(Term::Variable(self.parser.get_var(name)))
+            } else {
+                Err(ParseError::ParseFailed)
+            }
+        } else {
+            let op = self.parser.get_op(name, args.len() as u32);
+            Ok(Term::Application { op, args })
+        }
+    }
+}
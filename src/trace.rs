@@ -19,11 +19,17 @@
 //! let mut term = terms.pop().unwrap();
 //! let mut trace = Trace::new(&trs, &term, 0.5, 1.0, None, Strategy::Normal);
 //!
-//! let expected = vec!["PLUS(3, 1)", "PLUS(2, 2)", "PLUS(1, 3)", "PLUS(0, 4)", "4"];
+//! let expected = vec![
+//!     "PLUS(SUCC(SUCC(SUCC(ZERO))), SUCC(ZERO))",
+//!     "PLUS(SUCC(SUCC(ZERO)), SUCC(SUCC(ZERO)))",
+//!     "PLUS(SUCC(ZERO), SUCC(SUCC(SUCC(ZERO))))",
+//!     "PLUS(ZERO, SUCC(SUCC(SUCC(SUCC(ZERO)))))",
+//!     "SUCC(SUCC(SUCC(SUCC(ZERO))))",
+//! ];
 //! let got = trace
 //!     .by_ref()
 //!     .take(5)
-//!     .map(|n| n.term().pretty())
+//!     .map(|n| n.term().pretty(&sig))
 //!     .collect::<Vec<_>>();
 //! assert_eq!(got, expected);
 //! assert!(trace.next().is_none());
@@ -38,7 +44,7 @@ use std::collections::BinaryHeap;
 use std::f64;
 use std::sync::{Arc, RwLock, Weak};
 
-use {Strategy, Term, TRS};
+use {PTermDist, Strategy, Term, TRS};
 
 /// A `Trace` provides first-class control over [`Term`] rewriting.
 ///
@@ -130,6 +136,26 @@ impl<'a> Trace<'a> {
         self.rewrite(max_steps);
         self.root.leaves(&[TraceState::Normal])
     }
+    /// Give the distinct outcomes within `max_steps` of evaluation with their log probabilities,
+    /// most probable first. The probabilities of `TraceNode`s with the same [`Term`] are summed.
+    ///
+    /// [`Term`]: ../enum.Term.html
+    pub fn terms_by_probability(&mut self, max_steps: usize) -> Vec<(Term, f64)> {
+        let mut outcomes: Vec<(Term, Vec<f64>)> = vec![];
+        for node in self.outcomes(max_steps) {
+            let term = node.term();
+            match outcomes.iter().position(|(t, _)| *t == term) {
+                Some(i) => outcomes[i].1.push(node.log_p()),
+                None => outcomes.push((term, vec![node.log_p()])),
+            }
+        }
+        let mut outcomes: Vec<(Term, f64)> = outcomes
+            .into_iter()
+            .map(|(term, lps)| (term, logsumexp(&lps)))
+            .collect();
+        outcomes.sort_by(|(_, lp1), (_, lp2)| lp2.partial_cmp(lp1).unwrap_or(Ordering::Equal));
+        outcomes
+    }
     /// Sample a possible outcome in proportion to its probability.
     pub fn sample<R: Rng>(&self, rng: &mut R) -> TraceNode {
         let leaves = self.root.leaves(&[TraceState::Normal]);
@@ -427,6 +453,131 @@ impl Iterator for TraceNodeIter {
     }
 }
 
+/// How [`TRS::log_likelihood`] explains an output which differs from an outcome of rewriting.
+///
+/// [`TRS::log_likelihood`]: ../struct.TRS.html#method.log_likelihood
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Noise {
+    /// Outputs must be outcomes, up to renaming [`Variable`]s.
+    ///
+    /// [`Variable`]: ../struct.Variable.html
+    Exact,
+    /// With probability `p`, an outcome is replaced by an arbitrary output, each of which has
+    /// log probability `log_p`.
+    Constant { p: f64, log_p: f64 },
+    /// With probability `p`, an outcome is corrupted into an output with the probability given
+    /// by [`TRS::p_term`].
+    ///
+    /// [`TRS::p_term`]: ../struct.TRS.html#method.p_term
+    Edit { p: f64, dist: PTermDist },
+}
+impl Noise {
+    // The log probability that `outcome` is observed as `output`.
+    fn log_p(&self, outcome: &Term, output: &Term) -> f64 {
+        let exact = if Term::alpha(outcome, output).is_some() {
+            0.0
+        } else {
+            f64::NEG_INFINITY
+        };
+        match *self {
+            Noise::Exact => exact,
+            Noise::Constant { p, log_p } => logsumexp(&[(1.0 - p).ln() + exact, p.ln() + log_p]),
+            Noise::Edit { p, ref dist } => logsumexp(&[
+                (1.0 - p).ln() + exact,
+                p.ln() + TRS::p_term(outcome, output, dist),
+            ]),
+        }
+    }
+}
+
+/// The parameters of [`TRS::log_likelihood`].
+///
+/// [`TRS::log_likelihood`]: ../struct.TRS.html#method.log_likelihood
+#[derive(Debug, Copy, Clone)]
+pub struct LikelihoodParams {
+    /// The probability of taking each rewrite step, as in [`Trace::new`].
+    ///
+    /// [`Trace::new`]: struct.Trace.html#method.new
+    pub p_observe: f64,
+    /// The most evaluation steps explored from each input, as in [`Trace::rewrite`].
+    ///
+    /// [`Trace::rewrite`]: struct.Trace.html#method.rewrite
+    pub max_steps: usize,
+    /// The size beyond which a [`Term`] is no longer rewritten.
+    ///
+    /// [`Term`]: ../enum.Term.html
+    pub max_term_size: Option<usize>,
+    /// The [`Strategy`] used to rewrite each input.
+    ///
+    /// [`Strategy`]: ../enum.Strategy.html
+    pub strategy: Strategy,
+    /// How outputs differing from every outcome are explained.
+    pub noise: Noise,
+}
+
+impl TRS {
+    /// The log probability of observing each output from its input, summed over `data`.
+    ///
+    /// Each input is rewritten in a [`Trace`] for up to `params.max_steps` steps. The probability
+    /// of an output is the probability of each normal form reached, times the probability that
+    /// [`params.noise`] turns that normal form into the output, summed over normal forms. Mass
+    /// which has not reached a normal form within the bound is not counted, so this is a lower
+    /// bound.
+    ///
+    /// [`Trace`]: trace/struct.Trace.html
+    /// [`params.noise`]: trace/enum.Noise.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Strategy, parse, parse_term};
+    /// # use term_rewriting::trace::{LikelihoodParams, Noise};
+    /// let mut sig = Signature::default();
+    /// let (trs, _) = parse(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    /// let b = parse_term(&mut sig, "B").expect("parse of B");
+    /// let d = parse_term(&mut sig, "D").expect("parse of D");
+    /// let mut params = LikelihoodParams {
+    ///     p_observe: 1.0,
+    ///     max_steps: 10,
+    ///     max_term_size: None,
+    ///     strategy: Strategy::Normal,
+    ///     noise: Noise::Exact,
+    /// };
+    ///
+    /// let ll = trs.log_likelihood(&[(a.clone(), b.clone())], &params);
+    /// assert!((ll - 0.5f64.ln()).abs() < 1e-9);
+    ///
+    /// let data = [(a.clone(), b), (a, d)];
+    /// assert_eq!(trs.log_likelihood(&data, &params), std::f64::NEG_INFINITY);
+    ///
+    /// params.noise = Noise::Constant { p: 0.1, log_p: 0.01f64.ln() };
+    /// let ll = trs.log_likelihood(&data, &params);
+    /// let expected = (0.5f64 * 0.9 + 0.001).ln() + 0.001f64.ln();
+    /// assert!((ll - expected).abs() < 1e-9);
+    /// ```
+    pub fn log_likelihood(&self, data: &[(Term, Term)], params: &LikelihoodParams) -> f64 {
+        data.iter()
+            .map(|(input, output)| {
+                let mut trace = Trace::new(
+                    self,
+                    input,
+                    params.p_observe,
+                    1.0,
+                    params.max_term_size,
+                    params.strategy,
+                );
+                let lps: Vec<f64> = trace
+                    .terms_by_probability(params.max_steps)
+                    .iter()
+                    .map(|(outcome, lp)| lp + params.noise.log_p(outcome, output))
+                    .collect();
+                logsumexp(&lps)
+            })
+            .sum()
+    }
+}
+
 fn logsumexp(lps: &[f64]) -> f64 {
     let largest = lps.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
     if largest == f64::NEG_INFINITY {
@@ -0,0 +1,751 @@
+use super::{Operator, OperatorAttributes, Signature, Substitution, Term, Variable};
+use std::vec;
+
+/// An iterator over the [`Substitution`]s found by [`Term::ac_match`] or [`Term::ac_unify`].
+///
+/// [`Substitution`]: struct.Substitution.html
+/// [`Term::ac_match`]: enum.Term.html#method.ac_match
+/// [`Term::ac_unify`]: enum.Term.html#method.ac_unify
+#[derive(Debug, Clone)]
+pub struct Substitutions(vec::IntoIter<Substitution>);
+impl Iterator for Substitutions {
+    type Item = Substitution;
+    fn next(&mut self) -> Option<Substitution> {
+        self.0.next()
+    }
+}
+
+impl Term {
+    /// Every way of matching `pattern` against `term` modulo associativity and commutativity
+    /// of the binary [`Operator`]s in `ac`. Each match is a [`Substitution`] `σ` such that
+    /// `patternσ` and `term` are equal up to rearranging arguments of [`Operator`]s in `ac`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Substitution`]: struct.Substitution.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    /// let plus = sig.new_op(2, Some("PLUS".to_string()));
+    ///
+    /// let pattern = parse_term(&mut sig, "PLUS(x_ PLUS(A y_))").expect("parse of PLUS(x_ PLUS(A y_))");
+    /// let term = parse_term(&mut sig, "PLUS(PLUS(B A) C)").expect("parse of PLUS(PLUS(B A) C)");
+    ///
+    /// let matches: Vec<String> = Term::ac_match(&pattern, &term, &[plus.clone()])
+    ///     .map(|sub| sub.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(matches, vec!["{x_ := B, y_ := C}", "{x_ := C, y_ := B}"]);
+    ///
+    /// // without AC, the pattern does not match.
+    /// assert_eq!(Term::ac_match(&pattern, &term, &[]).count(), 0);
+    /// ```
+    pub fn ac_match(pattern: &Term, term: &Term, ac: &[Operator]) -> Substitutions {
+        let cs = vec![(flatten(pattern, ac), flatten(term, ac))];
+        let mut found = vec![];
+        match_all(ac, cs, Substitution::new(), &mut found);
+        finish(found, &pattern.variables(), ac)
+    }
+    /// Are `s` and `t` equal up to rearranging arguments of the binary [`Operator`]s in `ac`?
+    ///
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    /// let plus = sig.new_op(2, Some("PLUS".to_string()));
+    ///
+    /// let t = parse_term(&mut sig, "EQ(PLUS(A PLUS(B C)) PLUS(PLUS(C A) B))")
+    ///     .expect("parse of EQ(PLUS(A PLUS(B C)) PLUS(PLUS(C A) B))");
+    /// let (s, t) = (&t.args()[0], &t.args()[1]);
+    ///
+    /// assert!(Term::ac_equivalent(s, t, &[plus]));
+    /// assert!(!Term::ac_equivalent(s, t, &[]));
+    /// ```
+    pub fn ac_equivalent(s: &Term, t: &Term, ac: &[Operator]) -> bool {
+        flatten(s, ac) == flatten(t, ac)
+    }
+    /// A complete set of unifiers of `s` and `t` modulo associativity and commutativity of the
+    /// binary [`Operator`]s in `ac`. Each unifier is a [`Substitution`] `σ` such that `sσ` and
+    /// `tσ` are equal up to rearranging arguments of [`Operator`]s in `ac`. Unifiers may
+    /// introduce [`Variable`]s fresh in `sig`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Substitution`]: struct.Substitution.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    /// let plus = sig.new_op(2, Some("PLUS".to_string()));
+    ///
+    /// let t = parse_term(&mut sig, "EQ(PLUS(x_ A) PLUS(B y_))").expect("parse of EQ(PLUS(x_ A) PLUS(B y_))");
+    /// let (s, t) = (&t.args()[0], &t.args()[1]);
+    ///
+    /// let unifiers: Vec<_> = Term::ac_unify(&mut sig, s, t, &[plus]).collect();
+    ///
+    /// assert!(unifiers.iter().any(|sub| sub.display(&sig) == "{x_ := B, y_ := A}"));
+    /// for sub in &unifiers {
+    ///     assert!(Term::ac_equivalent(&sub.apply(s), &sub.apply(t), &[plus]));
+    /// }
+    ///
+    /// // without AC, only the syntactic unifier remains.
+    /// assert_eq!(Term::ac_unify(&mut sig, s, t, &[]).count(), 1);
+    /// ```
+    pub fn ac_unify(sig: &mut Signature, s: &Term, t: &Term, ac: &[Operator]) -> Substitutions {
+        let cs = vec![(flatten(s, ac), flatten(t, ac))];
+        let found = unify_all(sig, ac, cs, Substitution::new());
+        let mut vars = s.variables();
+        for v in t.variables() {
+            if !vars.contains(&v) {
+                vars.push(v);
+            }
+        }
+        finish(found, &vars, ac)
+    }
+    /// The canonical form of the `Term` with respect to the [`OperatorAttributes`] of its
+    /// [`Operator`]s: identity elements are removed, nested applications of associative
+    /// [`Operator`]s are reassociated to the right, and the arguments of commutative
+    /// [`Operator`]s are put in a fixed order. Two `Term`s are equal in the theory given by
+    /// the attributes exactly when their canonical forms are equal.
+    ///
+    /// [`OperatorAttributes`]: struct.OperatorAttributes.html
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorAttributes, parse_term};
+    /// let mut sig = Signature::default();
+    /// let zero = sig.new_op(0, Some("ZERO".to_string()));
+    /// let attrs = OperatorAttributes {
+    ///     associative: true,
+    ///     commutative: true,
+    ///     identity: Some(zero),
+    /// };
+    /// sig.new_op_with_attrs(2, Some("PLUS".to_string()), attrs);
+    ///
+    /// let t = parse_term(&mut sig, "EQ(PLUS(PLUS(B ZERO) A) PLUS(A PLUS(ZERO B)))")
+    ///     .expect("parse of EQ(PLUS(PLUS(B ZERO) A) PLUS(A PLUS(ZERO B)))");
+    /// let (s, t) = (&t.args()[0], &t.args()[1]);
+    ///
+    /// assert_ne!(s, t);
+    /// assert_eq!(s.canonical(&sig), t.canonical(&sig));
+    /// assert_eq!(s.canonical(&sig).display(&sig), "PLUS(B A)");
+    /// ```
+    pub fn canonical(&self, sig: &Signature) -> Term {
+        match *self {
+            Term::Variable(_) => self.clone(),
+            Term::Application { ref op, ref args } => {
+                let args: Vec<Term> = args.iter().map(|arg| arg.canonical(sig)).collect();
+                let attributes = op.attributes(sig);
+                if attributes == OperatorAttributes::default() {
+                    return Term::Application { op: *op, args };
+                }
+                let mut items = vec![];
+                for arg in args {
+                    if attributes.associative {
+                        leaves(op, arg, &mut items);
+                    } else {
+                        items.push(arg);
+                    }
+                }
+                if let Some(e) = attributes.identity {
+                    let e = Term::Application {
+                        op: e,
+                        args: vec![],
+                    };
+                    items.retain(|item| *item != e);
+                    if items.is_empty() {
+                        return e;
+                    }
+                }
+                if items.len() == 1 {
+                    return items.pop().expect("single argument");
+                }
+                if attributes.commutative {
+                    items.sort_by_key(key);
+                }
+                let mut result = items.pop().expect("last argument");
+                while let Some(item) = items.pop() {
+                    result = Term::Application {
+                        op: *op,
+                        args: vec![item, result],
+                    };
+                }
+                result
+            }
+        }
+    }
+    /// Every way of matching `pattern` against `term` modulo the [`OperatorAttributes`] of
+    /// their [`Operator`]s. Both `Term`s are first put in [`canonical`] form, so identity
+    /// elements are only ever removed, never introduced. [`Operator`]s which are associative
+    /// but not commutative are matched up to reassociation of `term` only.
+    ///
+    /// [`OperatorAttributes`]: struct.OperatorAttributes.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`canonical`]: enum.Term.html#method.canonical
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, OperatorAttributes, parse_term};
+    /// let mut sig = Signature::default();
+    /// let attrs = OperatorAttributes { commutative: true, ..OperatorAttributes::default() };
+    /// sig.new_op_with_attrs(2, Some("EQ".to_string()), attrs);
+    ///
+    /// let pattern = parse_term(&mut sig, "EQ(x_ A)").expect("parse of EQ(x_ A)");
+    /// let term = parse_term(&mut sig, "EQ(A B)").expect("parse of EQ(A B)");
+    ///
+    /// let matches: Vec<String> = Term::match_modulo(&sig, &pattern, &term)
+    ///     .map(|sub| sub.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(matches, vec!["{x_ := B}"]);
+    /// assert_eq!(Term::pmatch(vec![(&pattern, &term)]), None);
+    /// ```
+    pub fn match_modulo(sig: &Signature, pattern: &Term, term: &Term) -> Substitutions {
+        let (pattern, term) = (pattern.canonical(sig), term.canonical(sig));
+        let mut ac = vec![];
+        let mut c = vec![];
+        for op in pattern.operators().into_iter().chain(term.operators()) {
+            let attributes = op.attributes(sig);
+            if attributes.commutative && attributes.associative {
+                ac.push(op);
+            } else if attributes.commutative {
+                c.push(op);
+            }
+        }
+        let mut subs: Vec<Substitution> = vec![];
+        for variant in commuted(&pattern, &c) {
+            for sub in Term::ac_match(&variant, &term, &ac) {
+                let sub: Substitution = sub.iter().map(|(v, t)| (*v, t.canonical(sig))).collect();
+                if !subs.contains(&sub) {
+                    subs.push(sub);
+                }
+            }
+        }
+        Substitutions(subs.into_iter())
+    }
+}
+
+// Convert flattened solutions back to binary terms, restricted to `vars`, without duplicates.
+fn finish(found: Vec<Substitution>, vars: &[Variable], ac: &[Operator]) -> Substitutions {
+    let mut subs: Vec<Substitution> = vec![];
+    for sub in found {
+        let sub: Substitution = sub
+            .restrict(vars)
+            .iter()
+            .map(|(v, t)| (*v, unflatten(t, ac)))
+            .collect();
+        if !subs.contains(&sub) {
+            subs.push(sub);
+        }
+    }
+    Substitutions(subs.into_iter())
+}
+
+// Solve the matching problems in `cs` under `sub`, recording each solution in `found`.
+fn match_all(
+    ac: &[Operator],
+    mut cs: Vec<(Term, Term)>,
+    mut sub: Substitution,
+    found: &mut Vec<Substitution>,
+) {
+    while let Some((p, s)) = cs.pop() {
+        match p {
+            Term::Variable(v) => {
+                if let Some(bound) = sub.get(&v) {
+                    if *bound != s {
+                        return;
+                    }
+                    continue;
+                }
+                sub.insert(v, s);
+            }
+            Term::Application { op, args: pargs } => {
+                let sargs = match s {
+                    Term::Application { op: g, args } if g == op => args,
+                    _ => return,
+                };
+                if !ac.contains(&op) {
+                    if pargs.len() != sargs.len() {
+                        return;
+                    }
+                    cs.extend(pargs.into_iter().zip(sargs));
+                    continue;
+                }
+                // match non-variable arguments first, since each takes exactly one argument.
+                let (mut pargs, vars): (Vec<Term>, Vec<Term>) =
+                    pargs.into_iter().partition(|p| !is_var(p));
+                pargs.extend(vars);
+                assign(ac, &op, &pargs, sargs, cs, sub, found);
+                return;
+            }
+        }
+    }
+    found.push(sub);
+}
+
+// Distribute the subject arguments `remaining` among the pattern arguments `pargs` of an AC
+// application headed by `op`, then solve the resulting matching problems.
+fn assign(
+    ac: &[Operator],
+    op: &Operator,
+    pargs: &[Term],
+    remaining: Vec<Term>,
+    cs: Vec<(Term, Term)>,
+    sub: Substitution,
+    found: &mut Vec<Substitution>,
+) {
+    if pargs.is_empty() {
+        if remaining.is_empty() {
+            match_all(ac, cs, sub, found);
+        }
+        return;
+    }
+    let (p, rest) = (&pargs[0], &pargs[1..]);
+    if remaining.len() < pargs.len() {
+        return;
+    }
+    if !is_var(p) {
+        for i in 0..remaining.len() {
+            if remaining[..i].contains(&remaining[i]) {
+                continue;
+            }
+            let mut remaining = remaining.clone();
+            let s = remaining.remove(i);
+            let mut cs = cs.clone();
+            cs.push((p.clone(), s));
+            assign(ac, op, rest, remaining, cs, sub.clone(), found);
+        }
+    } else if rest.is_empty() {
+        let mut cs = cs;
+        cs.push((p.clone(), combine(op, remaining, ac)));
+        match_all(ac, cs, sub, found);
+    } else {
+        let n = remaining.len();
+        for mask in 1..(1usize << n) - 1 {
+            let (taken, left): (Vec<_>, Vec<_>) = remaining
+                .iter()
+                .cloned()
+                .enumerate()
+                .partition(|(i, _)| mask & (1 << i) != 0);
+            let taken = taken.into_iter().map(|(_, t)| t).collect();
+            let left = left.into_iter().map(|(_, t)| t).collect();
+            let mut cs = cs.clone();
+            cs.push((p.clone(), combine(op, taken, ac)));
+            assign(ac, op, rest, left, cs, sub.clone(), found);
+        }
+    }
+}
+
+// Solve the unification problems in `cs` under `sub`, returning each solution.
+fn unify_all(
+    sig: &mut Signature,
+    ac: &[Operator],
+    mut cs: Vec<(Term, Term)>,
+    mut sub: Substitution,
+) -> Vec<Substitution> {
+    while let Some((s, t)) = cs.pop() {
+        let s = flatten(&sub.apply(&s), ac);
+        let t = flatten(&sub.apply(&t), ac);
+        if s == t {
+            continue;
+        }
+        match (s, t) {
+            (Term::Variable(v), t) | (t, Term::Variable(v)) => {
+                if t.variables().contains(&v) {
+                    return vec![];
+                }
+                let binding: Substitution = vec![(v, t)].into_iter().collect();
+                sub = sub.compose(&binding);
+            }
+            (
+                Term::Application { op: f, args: sargs },
+                Term::Application { op: g, args: targs },
+            ) => {
+                if f != g {
+                    return vec![];
+                }
+                if !ac.contains(&f) {
+                    if sargs.len() != targs.len() {
+                        return vec![];
+                    }
+                    cs.extend(sargs.into_iter().zip(targs));
+                    continue;
+                }
+                let (sargs, targs) = cancel(sargs, targs);
+                if sargs.is_empty() && targs.is_empty() {
+                    continue;
+                } else if sargs.is_empty() || targs.is_empty() {
+                    return vec![];
+                }
+                return unify_ac(sig, ac, &f, sargs, targs, cs, sub);
+            }
+        }
+    }
+    vec![sub]
+}
+
+// Unify `f(sargs)` with `f(targs)` for an AC operator `f`, following Stickel: solve the linear
+// Diophantine equation relating the multiplicities of the arguments, then unify each argument
+// with a combination of fresh variables drawn from each admissible set of basis solutions.
+fn unify_ac(
+    sig: &mut Signature,
+    ac: &[Operator],
+    f: &Operator,
+    sargs: Vec<Term>,
+    targs: Vec<Term>,
+    cs: Vec<(Term, Term)>,
+    sub: Substitution,
+) -> Vec<Substitution> {
+    let mut found = vec![];
+    let ss = group(sargs);
+    let ts = group(targs);
+    let a: Vec<usize> = ss.iter().map(|(_, n)| *n).collect();
+    let b: Vec<usize> = ts.iter().map(|(_, n)| *n).collect();
+    let basis = diophantine_basis(&a, &b);
+    let items: Vec<&Term> = ss.iter().chain(ts.iter()).map(|(t, _)| t).collect();
+    for mask in 1..(1usize << basis.len()) {
+        let chosen: Vec<&Vec<usize>> = basis
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| mask & (1 << k) != 0)
+            .map(|(_, solution)| solution)
+            .collect();
+        let admissible = items.iter().enumerate().all(|(i, item)| {
+            let total: usize = chosen.iter().map(|solution| solution[i]).sum();
+            total >= 1 && (is_var(item) || total == 1)
+        });
+        if !admissible {
+            continue;
+        }
+        let fresh: Vec<Term> = chosen
+            .iter()
+            .map(|_| Term::Variable(sig.new_var(None)))
+            .collect();
+        let mut cs = cs.clone();
+        for (i, item) in items.iter().enumerate() {
+            let mut combination = vec![];
+            for (solution, z) in chosen.iter().zip(&fresh) {
+                for _ in 0..solution[i] {
+                    combination.push(z.clone());
+                }
+            }
+            cs.push(((*item).clone(), combine(f, combination, ac)));
+        }
+        found.append(&mut unify_all(sig, ac, cs, sub.clone()));
+    }
+    found
+}
+
+// The minimal nonzero solutions `x ++ y` of `a·x = b·y` over the naturals.
+fn diophantine_basis(a: &[usize], b: &[usize]) -> Vec<Vec<usize>> {
+    let max_a = a.iter().cloned().max().unwrap_or(0);
+    let max_b = b.iter().cloned().max().unwrap_or(0);
+    // components of minimal solutions are bounded by the largest opposing coefficient.
+    let bounds: Vec<usize> = a
+        .iter()
+        .map(|_| max_b)
+        .chain(b.iter().map(|_| max_a))
+        .collect();
+    let mut solutions: Vec<Vec<usize>> = vec![];
+    let mut candidate = vec![0; bounds.len()];
+    loop {
+        // advance `candidate` through every vector within `bounds`, in increasing total.
+        let mut i = 0;
+        while i < candidate.len() && candidate[i] == bounds[i] {
+            candidate[i] = 0;
+            i += 1;
+        }
+        if i == candidate.len() {
+            break;
+        }
+        candidate[i] += 1;
+        let lhs: usize = a.iter().zip(&candidate).map(|(c, x)| c * x).sum();
+        let rhs: usize = b
+            .iter()
+            .zip(&candidate[a.len()..])
+            .map(|(c, y)| c * y)
+            .sum();
+        if lhs == rhs {
+            solutions.push(candidate.clone());
+        }
+    }
+    let minimal: Vec<Vec<usize>> = solutions
+        .iter()
+        .filter(|s| {
+            !solutions
+                .iter()
+                .any(|t| t != *s && t.iter().zip(s.iter()).all(|(x, y)| x <= y))
+        })
+        .cloned()
+        .collect();
+    minimal
+}
+
+// Remove the arguments common to both multisets.
+fn cancel(mut sargs: Vec<Term>, targs: Vec<Term>) -> (Vec<Term>, Vec<Term>) {
+    let mut rest = vec![];
+    for t in targs {
+        match sargs.iter().position(|s| *s == t) {
+            Some(i) => {
+                sargs.remove(i);
+            }
+            None => rest.push(t),
+        }
+    }
+    (sargs, rest)
+}
+
+// Group equal terms, counting their multiplicities.
+fn group(terms: Vec<Term>) -> Vec<(Term, usize)> {
+    let mut groups: Vec<(Term, usize)> = vec![];
+    for t in terms {
+        match groups.iter().position(|(u, _)| *u == t) {
+            Some(i) => groups[i].1 += 1,
+            None => groups.push((t, 1)),
+        }
+    }
+    groups
+}
+
+// The flattened application of the AC operator `op` to `args`, or the only argument.
+fn combine(op: &Operator, mut args: Vec<Term>, ac: &[Operator]) -> Term {
+    if args.len() == 1 {
+        args.pop().expect("single argument")
+    } else {
+        flatten(&Term::Application { op: *op, args }, ac)
+    }
+}
+
+// Flatten nested applications of each operator in `ac` into a single application with its
+// arguments in a canonical order.
+pub(crate) fn flatten(term: &Term, ac: &[Operator]) -> Term {
+    match *term {
+        Term::Variable(_) => term.clone(),
+        Term::Application { ref op, ref args } => {
+            let args: Vec<Term> = args.iter().map(|arg| flatten(arg, ac)).collect();
+            if !ac.contains(op) {
+                return Term::Application { op: *op, args };
+            }
+            let mut flat = vec![];
+            for arg in args {
+                match arg {
+                    Term::Application {
+                        op: ref inner,
+                        ref args,
+                    } if inner == op => flat.extend(args.iter().cloned()),
+                    arg => flat.push(arg),
+                }
+            }
+            flat.sort_by_key(key);
+            Term::Application {
+                op: *op,
+                args: flat,
+            }
+        }
+    }
+}
+
+// Rebuild binary applications of each operator in `ac`, associating to the right.
+pub(crate) fn unflatten(term: &Term, ac: &[Operator]) -> Term {
+    match *term {
+        Term::Variable(_) => term.clone(),
+        Term::Application { ref op, ref args } => {
+            let mut args: Vec<Term> = args.iter().map(|arg| unflatten(arg, ac)).collect();
+            if !ac.contains(op) || args.len() <= 2 {
+                return Term::Application { op: *op, args };
+            }
+            let mut result = args.pop().expect("last argument");
+            while let Some(arg) = args.pop() {
+                result = Term::Application {
+                    op: *op,
+                    args: vec![arg, result],
+                };
+            }
+            result
+        }
+    }
+}
+
+// Collect the arguments of nested applications of `op` in `term`, left to right.
+fn leaves(op: &Operator, term: Term, items: &mut Vec<Term>) {
+    match term {
+        Term::Application { op: inner, args } if inner == *op => {
+            for arg in args {
+                leaves(op, arg, items);
+            }
+        }
+        term => items.push(term),
+    }
+}
+
+// Every variant of `term` obtained by swapping the arguments of applications of operators in
+// `c`.
+fn commuted(term: &Term, c: &[Operator]) -> Vec<Term> {
+    match *term {
+        Term::Variable(_) => vec![term.clone()],
+        Term::Application { ref op, ref args } => {
+            let mut variants: Vec<Vec<Term>> = vec![vec![]];
+            for arg in args {
+                let arg_variants = commuted(arg, c);
+                variants = variants
+                    .into_iter()
+                    .flat_map(|prefix| {
+                        arg_variants.iter().map(move |arg| {
+                            let mut prefix = prefix.clone();
+                            prefix.push(arg.clone());
+                            prefix
+                        })
+                    })
+                    .collect();
+            }
+            let mut terms = vec![];
+            for args in variants {
+                if c.contains(op) && args.len() == 2 && args[0] != args[1] {
+                    terms.push(Term::Application {
+                        op: *op,
+                        args: vec![args[1].clone(), args[0].clone()],
+                    });
+                }
+                terms.push(Term::Application { op: *op, args });
+            }
+            terms
+        }
+    }
+}
+
+// Is `term` a variable?
+fn is_var(term: &Term) -> bool {
+    matches!(*term, Term::Variable(_))
+}
+
+// A key giving a total order on terms within a single signature.
+fn key(term: &Term) -> Vec<usize> {
+    let mut key = vec![];
+    for (t, _) in term.subterms() {
+        match *t {
+            Term::Variable(ref v) => key.extend(vec![0, v.id]),
+            Term::Application { ref op, ref args } => key.extend(vec![1, op.id, args.len()]),
+        }
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn ac_match_test() {
+        let mut sig = Signature::default();
+        let ac = [sig.new_op(2, Some("P".to_string()))];
+
+        let pattern = parse_term(&mut sig, "P(x_ y_)").expect("parse of P(x_ y_)");
+        let term = parse_term(&mut sig, "P(A P(B C))").expect("parse of P(A P(B C))");
+
+        // each of 3 arguments alone, or the other two together, for x_.
+        assert_eq!(Term::ac_match(&pattern, &term, &ac).count(), 6);
+
+        let pattern = parse_term(&mut sig, "P(x_ x_)").expect("parse of P(x_ x_)");
+        let term = parse_term(&mut sig, "P(P(A B) P(B A))").expect("parse of P(P(A B) P(B A))");
+        let matches: Vec<String> = Term::ac_match(&pattern, &term, &ac)
+            .map(|sub| sub.display(&sig))
+            .collect();
+
+        assert_eq!(matches, vec!["{x_ := P(A B)}"]);
+
+        let pattern = parse_term(&mut sig, "F(P(x_ A))").expect("parse of F(P(x_ A))");
+        let term = parse_term(&mut sig, "F(P(B C))").expect("parse of F(P(B C))");
+
+        assert_eq!(Term::ac_match(&pattern, &term, &ac).count(), 0);
+    }
+
+    #[test]
+    fn ac_unify_test() {
+        let mut sig = Signature::default();
+        let ac = [sig.new_op(2, Some("P".to_string()))];
+
+        let t = parse_term(&mut sig, "EQ(P(x_ y_) P(A B))").expect("parse of EQ(P(x_ y_) P(A B))");
+        let (s, t) = (&t.args()[0], &t.args()[1]);
+
+        let unifiers: Vec<String> = Term::ac_unify(&mut sig, s, t, &ac)
+            .map(|sub| sub.display(&sig))
+            .collect();
+
+        assert_eq!(unifiers.len(), 2);
+        assert!(unifiers.contains(&"{x_ := A, y_ := B}".to_string()));
+        assert!(unifiers.contains(&"{x_ := B, y_ := A}".to_string()));
+
+        // x + x = y + y + y has the single most general unifier x = 3z, y = 2z.
+        let t = parse_term(&mut sig, "EQ(P(x_ x_) P(y_ P(y_ y_)))")
+            .expect("parse of EQ(P(x_ x_) P(y_ P(y_ y_)))");
+        let (s, t) = (&t.args()[0], &t.args()[1]);
+        let unifiers: Vec<_> = Term::ac_unify(&mut sig, s, t, &ac).collect();
+
+        assert_eq!(unifiers.len(), 1);
+        assert_eq!(unifiers[0].apply(s).size(), 11);
+    }
+
+    #[test]
+    fn attributes_test() {
+        let mut sig = Signature::default();
+        let one = sig.new_op(0, Some("ONE".to_string()));
+        let attrs = OperatorAttributes {
+            associative: true,
+            commutative: false,
+            identity: Some(one),
+        };
+        let times = sig.new_op_with_attrs(2, Some("T".to_string()), attrs);
+        let plus = sig.new_op(2, Some("P".to_string()));
+        let attrs = OperatorAttributes {
+            associative: true,
+            commutative: true,
+            identity: None,
+        };
+        sig.set_attributes(&plus, attrs);
+
+        let t = parse_term(&mut sig, "T(T(A ONE) T(B C))").expect("parse of T(T(A ONE) T(B C))");
+
+        assert_eq!(t.canonical(&sig).display(&sig), "T(A T(B C))");
+        assert_eq!(t.pretty(&sig), "T(T(A, ONE), B, C)");
+
+        let t = parse_term(&mut sig, "T(A T(B C))").expect("parse of T(A T(B C))");
+
+        assert_eq!(t.pretty(&sig), "T(A, B, C)");
+        assert_eq!(parse_term(&mut sig, "T(A, B, C)"), Ok(t));
+
+        let t = parse_term(&mut sig, "T(C B)").expect("parse of T(C B)");
+
+        assert_eq!(t.canonical(&sig).display(&sig), "T(C B)");
+
+        let t = parse_term(&mut sig, "T(ONE ONE)").expect("parse of T(ONE ONE)");
+
+        assert_eq!(t.canonical(&sig).display(&sig), "ONE");
+
+        let pattern =
+            parse_term(&mut sig, "F(P(x_ A) T(ONE y_))").expect("parse of F(P(x_ A) T(ONE y_))");
+        let term = parse_term(&mut sig, "F(P(P(B A) C) D)").expect("parse of F(P(P(B A) C) D)");
+
+        assert_eq!(Term::match_modulo(&sig, &pattern, &term).count(), 1);
+
+        let sub = Term::match_modulo(&sig, &pattern, &term)
+            .next()
+            .expect("match");
+
+        assert_eq!(sub.display(&sig), "{x_ := P(B C), y_ := D}");
+        assert_eq!(sub.apply(&pattern).canonical(&sig), term.canonical(&sig));
+        assert!(times.is_associative(&sig));
+        assert!(!times.is_commutative(&sig));
+    }
+}
@@ -0,0 +1,441 @@
+use super::{
+    Normalization, NormalizeConfig, Operator, RewriteError, Rule, Strategy, Term, Variable, TRS,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// A handle to a term stored in a [`TermArena`]. It is only meaningful alongside that
+/// [`TermArena`], and only until the [`TermArena`] is cleared.
+///
+/// [`TermArena`]: struct.TermArena.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ArenaTerm(usize);
+
+// The head of a node: a Variable or an Operator with its arity, by id.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Head {
+    Variable(usize),
+    Operator(usize, usize),
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    head: Head,
+    // where the arguments start in `TermArena::args`
+    args: usize,
+    size: usize,
+    // a structural hash, so that unequal terms are usually told apart without a traversal
+    hash: u64,
+}
+
+/// Stores terms over a single [`Signature`] in a few flat buffers, so that rewriting allocates
+/// nothing per node.
+///
+/// Terms are added with [`TermArena::insert`] and referred to by their [`ArenaTerm`] handles.
+/// Rewriting never changes a stored term, and nothing is freed until [`TermArena::clear`],
+/// which keeps the buffers for reuse, e.g. between the jobs of a batch.
+///
+/// Rewriting follows [`TRS::rewrite`] with the first result of each step, and [`Rule`]s are
+/// matched syntactically. Checking the conditions of a conditional [`Rule`] converts the terms
+/// involved into [`Term`]s.
+///
+/// [`Signature`]: struct.Signature.html
+/// [`TermArena::insert`]: struct.TermArena.html#method.insert
+/// [`ArenaTerm`]: struct.ArenaTerm.html
+/// [`TermArena::clear`]: struct.TermArena.html#method.clear
+/// [`TRS::rewrite`]: struct.TRS.html#method.rewrite
+/// [`Rule`]: struct.Rule.html
+/// [`Term`]: enum.Term.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, Strategy, TermArena, NormalizeConfig, Normalization, parse_trs, parse_term};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig,
+/// "PLUS(ZERO y_) = y_;
+/// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS");
+/// let mut arena = TermArena::new();
+///
+/// for (input, output) in vec![
+///     ("PLUS(SUCC(ZERO) SUCC(ZERO))", "SUCC(SUCC(ZERO))"),
+///     ("PLUS(ZERO PLUS(ZERO ZERO))", "ZERO"),
+/// ] {
+///     let term = parse_term(&mut sig, input).expect("parse of input");
+///     match arena.normalize(&trs, &term, Strategy::Normal, NormalizeConfig::default()) {
+///         Ok(Normalization::Normalized(t)) => assert_eq!(t.display(&sig), output),
+///         _ => panic!("normalization failed"),
+///     }
+///     arena.clear();
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TermArena {
+    nodes: Vec<Node>,
+    args: Vec<ArenaTerm>,
+    // scratch space: arguments of nodes under construction, and the bindings of a match
+    stack: Vec<ArenaTerm>,
+    bindings: Vec<(usize, ArenaTerm)>,
+}
+impl TermArena {
+    /// Create an empty `TermArena`.
+    pub fn new() -> TermArena {
+        TermArena::default()
+    }
+    /// The number of nodes stored.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Are no nodes stored?
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// Forget every stored term, keeping the memory for reuse.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.args.clear();
+    }
+    /// Store `term`.
+    pub fn insert(&mut self, term: &Term) -> ArenaTerm {
+        match *term {
+            Term::Variable(ref v) => self.node(Head::Variable(v.id)),
+            Term::Application { ref op, ref args } => {
+                for arg in args {
+                    let arg = self.insert(arg);
+                    self.stack.push(arg);
+                }
+                self.node(Head::Operator(op.id, args.len()))
+            }
+        }
+    }
+    /// Rebuild the [`Term`] stored as `t`.
+    ///
+    /// [`Term`]: enum.Term.html
+    pub fn to_term(&self, t: ArenaTerm) -> Term {
+        match self.nodes[t.0].head {
+            Head::Variable(id) => Term::Variable(Variable { id }),
+            Head::Operator(id, arity) => Term::Application {
+                op: Operator {
+                    id,
+                    arity: arity as u32,
+                },
+                args: self.args_of(t).iter().map(|&a| self.to_term(a)).collect(),
+            },
+        }
+    }
+    /// The size of the term stored as `t`.
+    pub fn size(&self, t: ArenaTerm) -> usize {
+        self.nodes[t.0].size
+    }
+    /// Rewrite `t` one step under `trs` by `strategy`, keeping the first result, as in
+    /// [`TRS::normalize`]. Returns `None` if `t` is in normal form.
+    ///
+    /// [`TRS::normalize`]: struct.TRS.html#method.normalize
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Strategy, TermArena, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig, "F(x_) = G(x_ x_); G(A y_) = y_;").expect("parse of TRS");
+    /// let term = parse_term(&mut sig, "F(F(A))").expect("parse of F(F(A))");
+    /// let mut arena = TermArena::new();
+    /// let t = arena.insert(&term);
+    ///
+    /// let outer = arena.rewrite(&trs, t, Strategy::Normal).expect("rewrite");
+    /// let inner = arena.rewrite(&trs, t, Strategy::Eager).expect("rewrite");
+    ///
+    /// assert_eq!(arena.to_term(outer).display(&sig), "G(F(A) F(A))");
+    /// assert_eq!(arena.to_term(inner).display(&sig), "F(G(A A))");
+    /// ```
+    pub fn rewrite(&mut self, trs: &TRS, t: ArenaTerm, strategy: Strategy) -> Option<ArenaTerm> {
+        match strategy {
+            Strategy::Normal | Strategy::Outermost | Strategy::All => {
+                self.rewrite_outermost(trs, t)
+            }
+            Strategy::Eager => self.rewrite_innermost(trs, t, false),
+            Strategy::ParallelInnermost => self.rewrite_innermost(trs, t, true),
+        }
+    }
+    /// Normalize `term` under `trs` within the limits of `config`, as [`TRS::normalize`] does
+    /// with a [`Strategy`].
+    ///
+    /// [`TRS::normalize`]: struct.TRS.html#method.normalize
+    /// [`Strategy`]: enum.Strategy.html
+    pub fn normalize(
+        &mut self,
+        trs: &TRS,
+        term: &Term,
+        strategy: Strategy,
+        config: NormalizeConfig,
+    ) -> Result<Normalization, RewriteError> {
+        let mut t = self.insert(term);
+        let mut seen: HashMap<u64, Vec<ArenaTerm>> = HashMap::new();
+        let mut steps = 0;
+        loop {
+            if config
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return Ok(Normalization::Interrupted(self.to_term(t)));
+            }
+            if config.detect_cycles {
+                let hash = self.nodes[t.0].hash;
+                let bucket = seen.entry(hash).or_default();
+                if bucket.iter().any(|&s| self.equal(s, t)) {
+                    return Ok(Normalization::Cycle(self.to_term(t)));
+                }
+                bucket.push(t);
+            }
+            match self.rewrite(trs, t, strategy) {
+                None => return Ok(Normalization::Normalized(self.to_term(t))),
+                Some(_) if config.max_steps == Some(steps) => {
+                    return Ok(Normalization::StepLimit(self.to_term(t)))
+                }
+                Some(next) => match config.max_term_size {
+                    Some(limit) if self.size(next) > limit => {
+                        return Err(RewriteError::TermTooLarge(self.to_term(next)))
+                    }
+                    _ => t = next,
+                },
+            }
+            steps += 1;
+        }
+    }
+    // Store a node with `head` whose arguments are on top of the stack.
+    fn node(&mut self, head: Head) -> ArenaTerm {
+        let (mut hash, arity) = match head {
+            Head::Variable(id) => ((id as u64).wrapping_mul(2).wrapping_add(1), 0),
+            Head::Operator(id, arity) => ((id as u64).wrapping_mul(2), arity),
+        };
+        let start = self.args.len();
+        let mut size = 1;
+        for &arg in &self.stack[self.stack.len() - arity..] {
+            let node = self.nodes[arg.0];
+            hash = hash.wrapping_mul(0x0100_0000_01b3) ^ node.hash;
+            size += node.size;
+            self.args.push(arg);
+        }
+        let len = self.stack.len() - arity;
+        self.stack.truncate(len);
+        self.nodes.push(Node {
+            head,
+            args: start,
+            size,
+            hash,
+        });
+        ArenaTerm(self.nodes.len() - 1)
+    }
+    fn args_of(&self, t: ArenaTerm) -> &[ArenaTerm] {
+        let node = self.nodes[t.0];
+        match node.head {
+            Head::Variable(_) => &[],
+            Head::Operator(_, arity) => &self.args[node.args..node.args + arity],
+        }
+    }
+    // Are the terms stored as `s` and `t` equal?
+    fn equal(&self, s: ArenaTerm, t: ArenaTerm) -> bool {
+        let (m, n) = (self.nodes[s.0], self.nodes[t.0]);
+        s == t
+            || (m.hash == n.hash
+                && m.size == n.size
+                && m.head == n.head
+                && self
+                    .args_of(s)
+                    .iter()
+                    .zip(self.args_of(t))
+                    .all(|(&a, &b)| self.equal(a, b)))
+    }
+    // Store `t` with its `i`th argument replaced by `arg`.
+    fn replace_arg(&mut self, t: ArenaTerm, i: usize, arg: ArenaTerm) -> ArenaTerm {
+        let node = self.nodes[t.0];
+        let arity = match node.head {
+            Head::Operator(_, arity) => arity,
+            Head::Variable(_) => 0,
+        };
+        for j in 0..arity {
+            let a = if j == i {
+                arg
+            } else {
+                self.args[node.args + j]
+            };
+            self.stack.push(a);
+        }
+        self.node(node.head)
+    }
+    fn rewrite_outermost(&mut self, trs: &TRS, t: ArenaTerm) -> Option<ArenaTerm> {
+        if let Some(result) = self.rewrite_head(trs, t) {
+            return Some(result);
+        }
+        for i in 0..self.args_of(t).len() {
+            let arg = self.args_of(t)[i];
+            if let Some(result) = self.rewrite_outermost(trs, arg) {
+                return Some(self.replace_arg(t, i, result));
+            }
+        }
+        None
+    }
+    // Rewrite the leftmost-innermost redex, or every innermost redex if `parallel`.
+    fn rewrite_innermost(&mut self, trs: &TRS, t: ArenaTerm, parallel: bool) -> Option<ArenaTerm> {
+        let mut result = None;
+        for i in 0..self.args_of(t).len() {
+            let arg = self.args_of(t)[i];
+            if let Some(new) = self.rewrite_innermost(trs, arg, parallel) {
+                result = Some(self.replace_arg(result.unwrap_or(t), i, new));
+                if !parallel {
+                    break;
+                }
+            }
+        }
+        result.or_else(|| self.rewrite_head(trs, t))
+    }
+    // Rewrite `t` at its root by the first clause of the first matching rule.
+    fn rewrite_head(&mut self, trs: &TRS, t: ArenaTerm) -> Option<ArenaTerm> {
+        if let Head::Variable(_) = self.nodes[t.0].head {
+            return None;
+        }
+        for rule in &trs.rules {
+            self.bindings.clear();
+            if self.pmatch(&rule.lhs, t) && self.satisfies(trs, rule) {
+                return Some(self.instantiate(&rule.rhs[0]));
+            }
+        }
+        None
+    }
+    // Match `pattern` against `t`, adding to the bindings.
+    fn pmatch(&mut self, pattern: &Term, t: ArenaTerm) -> bool {
+        match *pattern {
+            Term::Variable(ref v) => match self.bindings.iter().find(|&&(id, _)| id == v.id) {
+                Some(&(_, bound)) => self.equal(bound, t),
+                None => {
+                    self.bindings.push((v.id, t));
+                    true
+                }
+            },
+            Term::Application { ref op, ref args } => {
+                self.nodes[t.0].head == Head::Operator(op.id, args.len())
+                    && (0..args.len()).all(|i| {
+                        let arg = self.args_of(t)[i];
+                        self.pmatch(&args[i], arg)
+                    })
+            }
+        }
+    }
+    // Do the conditions of `rule` hold under the bindings?
+    fn satisfies(&self, trs: &TRS, rule: &Rule) -> bool {
+        if rule.conditions.is_empty() {
+            return true;
+        }
+        let vars = rule.lhs.variables();
+        let terms: Vec<_> = vars
+            .iter()
+            .map(|v| {
+                let &(_, t) = self
+                    .bindings
+                    .iter()
+                    .find(|&&(id, _)| id == v.id)
+                    .expect("bound variable");
+                self.to_term(t)
+            })
+            .collect();
+        let sub = vars.iter().zip(&terms).collect();
+        trs.satisfies(&rule.conditions, &sub, trs.condition_config.max_depth)
+    }
+    // Store `term` with the bindings substituted.
+    fn instantiate(&mut self, term: &Term) -> ArenaTerm {
+        match *term {
+            Term::Variable(ref v) => match self.bindings.iter().find(|&&(id, _)| id == v.id) {
+                Some(&(_, t)) => t,
+                None => self.node(Head::Variable(v.id)),
+            },
+            Term::Application { ref op, ref args } => {
+                for arg in args {
+                    let arg = self.instantiate(arg);
+                    self.stack.push(arg);
+                }
+                self.node(Head::Operator(op.id, args.len()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::{Normalization, NormalizeConfig, RewriteError, Signature, Strategy};
+    use super::*;
+
+    #[test]
+    fn normalize_agrees_with_trs_test() {
+        let mut sig = Signature::default();
+        let trs = parse_trs(
+            &mut sig,
+            "F(x_ x_) = G(x_); G(H(y_)) = y_ | A; F(x_ y_) = B <= x_ = y_; K(z_) = K(z_);",
+        )
+        .expect("parse of TRS");
+        let mut arena = TermArena::new();
+        let strategies = vec![
+            Strategy::Normal,
+            Strategy::Eager,
+            Strategy::Outermost,
+            Strategy::ParallelInnermost,
+            Strategy::All,
+        ];
+        for input in &[
+            "F(H(A) H(A))",
+            "F(G(H(A)) A)",
+            "P(G(H(A)) G(H(B)))",
+            "F(H(G(H(A))) H(A))",
+        ] {
+            let term = parse_term(&mut sig, input).expect("parse of input");
+            for &strategy in &strategies {
+                let config = NormalizeConfig::default();
+                assert_eq!(
+                    arena.normalize(&trs, &term, strategy, config),
+                    trs.normalize(&term, strategy, config),
+                    "{} by {:?}",
+                    input,
+                    strategy
+                );
+            }
+        }
+        let term = parse_term(&mut sig, "K(A)").expect("parse of K(A)");
+        assert_eq!(
+            arena.normalize(&trs, &term, Strategy::Normal, NormalizeConfig::default()),
+            Ok(Normalization::Cycle(term))
+        );
+    }
+
+    #[test]
+    fn arena_limits_test() {
+        let mut sig = Signature::default();
+        let trs =
+            parse_trs(&mut sig, "D(x_) = P(x_ x_); P(x_ y_) = D(P(x_ y_));").expect("parse of TRS");
+        let term = parse_term(&mut sig, "D(A)").expect("parse of D(A)");
+        let mut arena = TermArena::new();
+        let config = NormalizeConfig {
+            max_steps: Some(3),
+            detect_cycles: false,
+            ..NormalizeConfig::default()
+        };
+
+        let result = arena.normalize(&trs, &term, Strategy::Eager, config);
+
+        assert_eq!(result, trs.normalize(&term, Strategy::Eager, config));
+        let config = NormalizeConfig {
+            max_steps: None,
+            max_term_size: Some(6),
+            ..config
+        };
+        let result = arena.normalize(&trs, &term, Strategy::Eager, config);
+
+        assert!(matches!(result, Err(RewriteError::TermTooLarge(_))));
+        assert_eq!(result, trs.normalize(&term, Strategy::Eager, config));
+        assert!(!arena.is_empty());
+        arena.clear();
+        assert!(arena.is_empty());
+        let t = arena.insert(&term);
+        assert_eq!(arena.to_term(t), term);
+        assert_eq!(arena.size(t), 2);
+    }
+}
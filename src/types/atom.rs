@@ -2,13 +2,16 @@ use super::Signature;
 
 /// A symbol for an unspecified term. Only carries meaning alongside a [`Signature`].
 ///
+/// A `Variable` is a small `Copy` handle; its name lives in the [`Signature`], so looking it up
+/// takes the [`Signature`] as a parameter. `Variable`s from different [`Signature`]s are not
+/// distinguished.
+///
 /// To construct a `Variable`, use [`Signature::new_var`]
 ///
 /// [`Signature`]: struct.Signature.html
 /// [`Signature::new_var`]: struct.Signature.html#method.new_var
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Variable {
-    pub(crate) sig: Signature,
     pub(crate) id: usize,
 }
 impl Variable {
@@ -21,12 +24,13 @@ impl Variable {
     /// let mut sig = Signature::default();
     /// let var = sig.new_var(Some("z".to_string()));
     ///
-    /// assert_eq!(var.name(), Some("z".to_string()));
+    /// assert_eq!(var.name(&sig), Some("z".to_string()));
     /// ```
-    pub fn name(&self) -> Option<String> {
-        self.sig.sig.read().expect("poisoned signature").variables[self.id].clone()
+    pub fn name(&self, sig: &Signature) -> Option<String> {
+        sig.sig.read().expect("poisoned signature").variables[self.id].clone()
     }
-    /// Serialize a `Variable`.
+    /// Serialize a `Variable`. A name which is not an identifier is quoted, as the parser
+    /// reads it.
     ///
     /// # Examples
     ///
@@ -34,29 +38,33 @@ impl Variable {
     /// # use term_rewriting::Signature;
     /// let mut sig = Signature::default();
     /// let var = sig.new_var(Some("z".to_string()));
+    /// let quoted = sig.new_var(Some("z_1".to_string()));
     ///
-    /// assert_eq!(var.display(), "z_");
+    /// assert_eq!(var.display(&sig), "z_");
+    /// assert_eq!(quoted.display(&sig), "'z_1'_");
     /// ```
-    pub fn display(&self) -> String {
-        if let Some(ref name) = self.sig.sig.read().expect("poisoned signature").variables[self.id]
-        {
-            format!("{}_", name)
-        } else {
-            format!("var{}_", self.id)
-        }
+    pub fn display(&self, sig: &Signature) -> String {
+        sig.sig
+            .read()
+            .expect("poisoned signature")
+            .display_var(self.id)
     }
 }
 
 /// A symbol with fixed arity. Only carries meaning alongside a [`Signature`].
 ///
+/// An `Operator` is a small `Copy` handle carrying its arity; its name and other attributes
+/// live in the [`Signature`], so looking them up takes the [`Signature`] as a parameter.
+/// `Operator`s from different [`Signature`]s are not distinguished.
+///
 /// To construct an `Operator`, use [`Signature::new_op`].
 ///
 /// [`Signature`]: struct.Signature.html
 /// [`Signature::new_op`]: struct.Signature.html#method.new_op
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Operator {
-    pub(crate) sig: Signature,
     pub(crate) id: usize,
+    pub(crate) arity: u32,
 }
 impl Operator {
     /// Returns an `Operator`'s arity.
@@ -71,7 +79,7 @@ impl Operator {
     /// assert_eq!(op.arity(), 2);
     /// ```
     pub fn arity(&self) -> u32 {
-        self.sig.sig.read().expect("poisoned signature").operators[self.id].0
+        self.arity
     }
     /// Returns an `Operator`'s name.
     ///
@@ -82,14 +90,54 @@ impl Operator {
     /// let mut sig = Signature::default();
     /// let op = sig.new_op(2, Some("Z".to_string()));
     ///
-    /// assert_eq!(op.name(), Some("Z".to_string()));
+    /// assert_eq!(op.name(&sig), Some("Z".to_string()));
     /// ```
-    pub fn name(&self) -> Option<String> {
-        self.sig.sig.read().expect("poisoned signature").operators[self.id]
+    pub fn name(&self, sig: &Signature) -> Option<String> {
+        sig.sig.read().expect("poisoned signature").operators[self.id]
             .1
             .clone()
     }
-    /// Serialize an `Operator`.
+    /// Returns an `Operator`'s [`OperatorAttributes`].
+    ///
+    /// [`OperatorAttributes`]: struct.OperatorAttributes.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorAttributes};
+    /// let mut sig = Signature::default();
+    /// let op = sig.new_op(2, Some("Z".to_string()));
+    ///
+    /// assert_eq!(op.attributes(&sig), OperatorAttributes::default());
+    /// ```
+    pub fn attributes(&self, sig: &Signature) -> OperatorAttributes {
+        let sig = sig.sig.read().expect("poisoned signature");
+        match sig.attributes.get(&self.id) {
+            Some(&(associative, commutative, identity)) => OperatorAttributes {
+                associative,
+                commutative,
+                identity: identity.map(|id| Operator {
+                    id,
+                    arity: sig.operators[id].0,
+                }),
+            },
+            None => OperatorAttributes::default(),
+        }
+    }
+    /// Is the `Operator` associative?
+    pub fn is_associative(&self, sig: &Signature) -> bool {
+        self.attributes(sig).associative
+    }
+    /// Is the `Operator` commutative?
+    pub fn is_commutative(&self, sig: &Signature) -> bool {
+        self.attributes(sig).commutative
+    }
+    /// The identity element of the `Operator`, if any.
+    pub fn identity(&self, sig: &Signature) -> Option<Operator> {
+        self.attributes(sig).identity
+    }
+    /// Serialize an `Operator`. A name which is not an identifier is quoted, as the parser
+    /// reads it.
     ///
     /// # Examples
     ///
@@ -97,26 +145,44 @@ impl Operator {
     /// # use term_rewriting::Signature;
     /// let mut sig = Signature::default();
     /// let op = sig.new_op(2, Some("Z".to_string()));
+    /// let quoted = sig.new_op(2, Some("don't panic".to_string()));
     ///
-    /// assert_eq!(op.display(), "Z");
+    /// assert_eq!(op.display(&sig), "Z");
+    /// assert_eq!(quoted.display(&sig), r"'don\'t panic'");
     /// ```
-    pub fn display(&self) -> String {
-        if let (_, Some(ref name)) =
-            self.sig.sig.read().expect("poisoned signature").operators[self.id]
-        {
-            name.clone()
-        } else {
-            format!("op{}", self.id)
-        }
+    pub fn display(&self, sig: &Signature) -> String {
+        sig.sig
+            .read()
+            .expect("poisoned signature")
+            .display_op(self.id)
     }
 }
 
+/// The equational theory attached to a binary [`Operator`]: whether it is associative, whether
+/// it is commutative, and which constant, if any, is its identity element.
+///
+/// To attach attributes to an [`Operator`], use [`Signature::set_attributes`] or
+/// [`Signature::new_op_with_attrs`].
+///
+/// [`Operator`]: struct.Operator.html
+/// [`Signature::set_attributes`]: struct.Signature.html#method.set_attributes
+/// [`Signature::new_op_with_attrs`]: struct.Signature.html#method.new_op_with_attrs
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OperatorAttributes {
+    /// `f(x, f(y, z)) = f(f(x, y), z)`
+    pub associative: bool,
+    /// `f(x, y) = f(y, x)`
+    pub commutative: bool,
+    /// `f(e, x) = f(x, e) = x`
+    pub identity: Option<Operator>,
+}
+
 /// `Atom`s are the parts of a [`TRS`] that are not constructed from smaller parts: [`Variable`]s and [`Operator`]s.
 ///
 /// [`TRS`]: struct.TRS.html
 /// [`Variable`]: struct.Variable.html
 /// [`Operator`]: struct.Operator.html
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Atom {
     /// The [`Variable`] variant of an `Atom`.
     ///
@@ -130,7 +196,7 @@ pub enum Atom {
     /// let x = sig.new_var(Some("x".to_string()));
     /// let atom = Atom::Variable(x);
     ///
-    /// assert_eq!(atom.display(), "x_");
+    /// assert_eq!(atom.display(&sig), "x_");
     /// ```
     Variable(Variable),
     /// The [`Operator`] variant of an `Atom`.
@@ -145,7 +211,7 @@ pub enum Atom {
     /// let a = sig.new_op(0, Some("A".to_string()));
     /// let atom = Atom::Operator(a);
     ///
-    /// assert_eq!(atom.display(), "A");
+    /// assert_eq!(atom.display(&sig), "A");
     /// ```
     Operator(Operator),
 }
@@ -161,17 +227,17 @@ impl Atom {
     /// let a = sig.new_op(0, Some("A".to_string()));
     /// let atom = Atom::Operator(a);
     ///
-    /// assert_eq!(atom.display(), "A");
+    /// assert_eq!(atom.display(&sig), "A");
     ///
     /// let x = sig.new_var(Some("x".to_string()));
     /// let atom = Atom::Variable(x);
     ///
-    /// assert_eq!(atom.display(), "x_");
+    /// assert_eq!(atom.display(&sig), "x_");
     /// ```
-    pub fn display(&self) -> String {
+    pub fn display(&self, sig: &Signature) -> String {
         match *self {
-            Atom::Variable(ref v) => v.display(),
-            Atom::Operator(ref o) => o.display(),
+            Atom::Variable(v) => v.display(sig),
+            Atom::Operator(o) => o.display(sig),
         }
     }
 }
@@ -0,0 +1,543 @@
+use super::{Rule, Signature, Term, Variable, TRS};
+use itertools::Itertools;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// A [`Term`] with its [`Variable`]s replaced by de Bruijn-style indices: the `n`th distinct
+/// [`Variable`] encountered in a left-to-right, depth-first traversal is index `n`. Alpha-equivalent
+/// [`Term`]s have equal `DeBruijnTerm`s, so they may be hashed and compared directly.
+///
+/// [`Operator`]s are stored by their position in the [`Signature`], so a `DeBruijnTerm` is only
+/// meaningful alongside the [`Signature`] of the [`Term`] it came from, but its equality and
+/// hash do not change as that [`Signature`] grows.
+///
+/// [`Term`]: enum.Term.html
+/// [`Variable`]: struct.Variable.html
+/// [`Operator`]: struct.Operator.html
+/// [`Signature`]: struct.Signature.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t1 = parse_term(&mut sig, "A(x_ B(y_ x_))").expect("parse of A(x_ B(y_ x_))");
+/// let t2 = parse_term(&mut sig, "A(z_ B(w_ z_))").expect("parse of A(z_ B(w_ z_))");
+/// let t3 = parse_term(&mut sig, "A(x_ B(x_ y_))").expect("parse of A(x_ B(x_ y_))");
+///
+/// assert_eq!(t1.to_debruijn(), t2.to_debruijn());
+/// assert_ne!(t1.to_debruijn(), t3.to_debruijn());
+/// assert_eq!(t1.to_debruijn().display(&sig), "A(#0 B(#1 #0))");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DeBruijnTerm {
+    /// The index of a [`Variable`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    Variable(usize),
+    /// An [`Operator`], by its position in the [`Signature`], applied to arguments.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Signature`]: struct.Signature.html
+    Application { op: usize, args: Vec<DeBruijnTerm> },
+}
+impl DeBruijnTerm {
+    /// Serialize a `DeBruijnTerm`, writing index `n` as `#n` and naming [`Operator`]s from `sig`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    pub fn display(&self, sig: &Signature) -> String {
+        match *self {
+            DeBruijnTerm::Variable(n) => format!("#{}", n),
+            DeBruijnTerm::Application { op, ref args } => {
+                let op = if op < sig.operators().len() {
+                    sig.sig.read().expect("poisoned signature").display_op(op)
+                } else {
+                    format!("op{}", op)
+                };
+                if args.is_empty() {
+                    op
+                } else {
+                    let args_str = args.iter().map(|arg| arg.display(sig)).join(" ");
+                    format!("{}({})", op, args_str)
+                }
+            }
+        }
+    }
+    /// Every index in the `DeBruijnTerm`, in order of first occurrence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "A(y_ B(x_ y_))").expect("parse of A(y_ B(x_ y_))");
+    ///
+    /// assert_eq!(t.to_debruijn().indices(), vec![0, 1]);
+    /// ```
+    pub fn indices(&self) -> Vec<usize> {
+        let mut indices = vec![];
+        self.collect_indices(&mut indices);
+        indices
+    }
+    fn collect_indices(&self, indices: &mut Vec<usize>) {
+        match *self {
+            DeBruijnTerm::Variable(n) => {
+                if !indices.contains(&n) {
+                    indices.push(n)
+                }
+            }
+            DeBruijnTerm::Application { ref args, .. } => {
+                for arg in args {
+                    arg.collect_indices(indices)
+                }
+            }
+        }
+    }
+    /// Convert the `DeBruijnTerm` back into a [`Term`], creating a fresh [`Variable`] in `sig`
+    /// for each index. Returns `None` if `sig` lacks an [`Operator`] or an [`Operator`] has the
+    /// wrong number of arguments.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "A(x_ B(y_ x_))").expect("parse of A(x_ B(y_ x_))");
+    /// let t2 = t.to_debruijn().to_term(&mut sig).expect("conversion");
+    ///
+    /// assert_ne!(t, t2);
+    /// assert!(Term::alpha(&t, &t2).is_some());
+    /// ```
+    pub fn to_term(&self, sig: &mut Signature) -> Option<Term> {
+        self.to_term_with(sig, &mut HashMap::new())
+    }
+    fn to_term_with(
+        &self,
+        sig: &mut Signature,
+        vars: &mut HashMap<usize, Variable>,
+    ) -> Option<Term> {
+        match *self {
+            DeBruijnTerm::Variable(n) => {
+                let var = *vars.entry(n).or_insert_with(|| sig.new_var(None));
+                Some(Term::Variable(var))
+            }
+            DeBruijnTerm::Application { op, ref args } => {
+                if op >= sig.operators().len() {
+                    return None;
+                }
+                let op = sig.sig.read().expect("poisoned signature").op(op);
+                if op.arity() as usize != args.len() {
+                    return None;
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| arg.to_term_with(sig, vars))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Term::Application { op, args })
+            }
+        }
+    }
+}
+
+/// A [`Rule`] with its [`Variable`]s replaced by de Bruijn-style indices, numbered across the
+/// lhs, then the rhs, then the conditions, as in a [`DeBruijnTerm`]. Alpha-equivalent [`Rule`]s
+/// have equal `DeBruijnRule`s. Weights are not included.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Variable`]: struct.Variable.html
+/// [`DeBruijnTerm`]: enum.DeBruijnTerm.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_rule};
+/// let mut sig = Signature::default();
+///
+/// let r1 = parse_rule(&mut sig, "A(x_ y_) = B(y_) | x_").expect("parse of A(x_ y_) = B(y_) | x_");
+/// let r2 = parse_rule(&mut sig, "A(y_ x_) = B(x_) | y_").expect("parse of A(y_ x_) = B(x_) | y_");
+///
+/// assert_eq!(r1.to_debruijn(), r2.to_debruijn());
+/// assert_eq!(r1.to_debruijn().display(&sig), "A(#0 #1) = B(#1) | #0");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeBruijnRule {
+    /// The left hand side (lhs) of the Rule.
+    pub lhs: DeBruijnTerm,
+    /// The right hand sides (rhs) of the Rule.
+    pub rhs: Vec<DeBruijnTerm>,
+    /// The conditions of the Rule.
+    pub conditions: Vec<(DeBruijnTerm, DeBruijnTerm)>,
+}
+impl DeBruijnRule {
+    /// Serialize a `DeBruijnRule`, as in [`DeBruijnTerm::display`].
+    ///
+    /// [`DeBruijnTerm::display`]: enum.DeBruijnTerm.html#method.display
+    pub fn display(&self, sig: &Signature) -> String {
+        let lhs_str = self.lhs.display(sig);
+        let rhs_str = self.rhs.iter().map(|t| t.display(sig)).join(" | ");
+        if self.conditions.is_empty() {
+            format!("{} = {}", lhs_str, rhs_str)
+        } else {
+            let conditions_str = self
+                .conditions
+                .iter()
+                .map(|(c, d)| format!("{} = {}", c.display(sig), d.display(sig)))
+                .join(", ");
+            format!("{} = {} <= {}", lhs_str, rhs_str, conditions_str)
+        }
+    }
+    /// Convert the `DeBruijnRule` back into a [`Rule`], creating a fresh [`Variable`] in `sig`
+    /// for each index. Returns `None` if any [`Term`] cannot be converted or the result is not a
+    /// valid [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Term`]: enum.Term.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(y_) | x_").expect("parse of A(x_ y_) = B(y_) | x_");
+    /// let r2 = r.to_debruijn().to_rule(&mut sig).expect("conversion");
+    ///
+    /// assert!(Rule::alpha(&r, &r2).is_some());
+    /// assert_eq!(r2.to_debruijn(), r.to_debruijn());
+    /// ```
+    pub fn to_rule(&self, sig: &mut Signature) -> Option<Rule> {
+        let mut vars = HashMap::new();
+        let lhs = self.lhs.to_term_with(sig, &mut vars)?;
+        let rhs = self
+            .rhs
+            .iter()
+            .map(|t| t.to_term_with(sig, &mut vars))
+            .collect::<Option<Vec<_>>>()?;
+        let conditions = self
+            .conditions
+            .iter()
+            .map(|(c, d)| {
+                Some((
+                    c.to_term_with(sig, &mut vars)?,
+                    d.to_term_with(sig, &mut vars)?,
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Rule::new_conditional(lhs, rhs, conditions)
+    }
+}
+
+/// The canonical form of a [`TRS`]: the [`DeBruijnRule`] of each of its [`Rule`]s, sorted.
+/// [`TRS`]s which differ only by renaming [`Variable`]s and reordering [`Rule`]s have equal
+/// `DeBruijnTRS`s, so they may be hashed and compared directly, as in [`DeBruijnTerm`].
+///
+/// [`TRS`]: struct.TRS.html
+/// [`DeBruijnRule`]: struct.DeBruijnRule.html
+/// [`Rule`]: struct.Rule.html
+/// [`Variable`]: struct.Variable.html
+/// [`DeBruijnTerm`]: enum.DeBruijnTerm.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, parse_trs};
+/// let mut sig = Signature::default();
+///
+/// let t1 = parse_trs(&mut sig, "A(x_) = x_; B(x_ y_) = A(y_);").expect("parse of t1");
+/// let t2 = parse_trs(&mut sig, "B(y_ x_) = A(x_); A(z_) = z_;").expect("parse of t2");
+/// let t3 = parse_trs(&mut sig, "A(x_) = x_; B(x_ y_) = A(x_);").expect("parse of t3");
+///
+/// assert_eq!(t1.canonical_form(), t2.canonical_form());
+/// assert_ne!(t1.canonical_form(), t3.canonical_form());
+/// assert_eq!(t2.canonical_form().display(&sig), "A(#0) = #0;\nB(#0 #1) = A(#1);");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeBruijnTRS {
+    /// The [`DeBruijnRule`]s, sorted.
+    ///
+    /// [`DeBruijnRule`]: struct.DeBruijnRule.html
+    pub rules: Vec<DeBruijnRule>,
+}
+impl DeBruijnTRS {
+    /// Serialize a `DeBruijnTRS`, as in [`DeBruijnRule::display`].
+    ///
+    /// [`DeBruijnRule::display`]: struct.DeBruijnRule.html#method.display
+    pub fn display(&self, sig: &Signature) -> String {
+        self.rules
+            .iter()
+            .map(|r| format!("{};", r.display(sig)))
+            .join("\n")
+    }
+    /// Convert the `DeBruijnTRS` back into a [`TRS`], as in [`DeBruijnRule::to_rule`].
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`DeBruijnRule::to_rule`]: struct.DeBruijnRule.html#method.to_rule
+    pub fn to_trs(&self, sig: &mut Signature) -> Option<TRS> {
+        self.rules
+            .iter()
+            .map(|r| r.to_rule(sig))
+            .collect::<Option<Vec<_>>>()
+            .map(TRS::new)
+    }
+}
+
+impl Term {
+    /// The [`DeBruijnTerm`] of the `Term`.
+    ///
+    /// [`DeBruijnTerm`]: enum.DeBruijnTerm.html
+    pub fn to_debruijn(&self) -> DeBruijnTerm {
+        self.to_debruijn_with(&mut HashMap::new())
+    }
+    /// A hash of the [`DeBruijnTerm`] of the `Term`, computed without building it, so that
+    /// alpha-equivalent `Term`s hash alike. Use it to bucket or deduplicate `Term`s up to
+    /// renaming, confirming equivalence with [`Term::alpha`].
+    ///
+    /// [`DeBruijnTerm`]: enum.DeBruijnTerm.html
+    /// [`Term::alpha`]: enum.Term.html#method.alpha
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t1 = parse_term(&mut sig, "A(x_ B(y_ x_))").expect("parse of A(x_ B(y_ x_))");
+    /// let t2 = parse_term(&mut sig, "A(z_ B(w_ z_))").expect("parse of A(z_ B(w_ z_))");
+    /// let t3 = parse_term(&mut sig, "A(x_ B(x_ y_))").expect("parse of A(x_ B(x_ y_))");
+    ///
+    /// assert_ne!(t1, t2);
+    /// assert_eq!(t1.alpha_hash(), t2.alpha_hash());
+    /// assert_ne!(t1.alpha_hash(), t3.alpha_hash());
+    /// ```
+    pub fn alpha_hash(&self) -> u64 {
+        let mut state = DefaultHasher::new();
+        self.alpha_hash_with(&mut HashMap::new(), &mut state);
+        state.finish()
+    }
+    // Hash each subterm in preorder, numbering Variables as `to_debruijn_with` does.
+    fn alpha_hash_with<H: Hasher>(&self, indices: &mut HashMap<usize, usize>, state: &mut H) {
+        let mut stack = vec![self];
+        while let Some(term) = stack.pop() {
+            match *term {
+                Term::Variable(ref v) => {
+                    let next = indices.len();
+                    0u8.hash(state);
+                    indices.entry(v.id).or_insert(next).hash(state);
+                }
+                Term::Application { ref op, ref args } => {
+                    1u8.hash(state);
+                    op.id.hash(state);
+                    args.len().hash(state);
+                    stack.extend(args.iter().rev());
+                }
+            }
+        }
+    }
+    // Indices are keyed by Variable id, as in the rest of the crate.
+    fn to_debruijn_with(&self, indices: &mut HashMap<usize, usize>) -> DeBruijnTerm {
+        match *self {
+            Term::Variable(ref v) => {
+                let next = indices.len();
+                DeBruijnTerm::Variable(*indices.entry(v.id).or_insert(next))
+            }
+            Term::Application { ref op, ref args } => DeBruijnTerm::Application {
+                op: op.id,
+                args: args
+                    .iter()
+                    .map(|arg| arg.to_debruijn_with(indices))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl Rule {
+    /// A hash of the [`DeBruijnRule`] of the `Rule`, as in [`Term::alpha_hash`], so that
+    /// alpha-equivalent `Rule`s hash alike.
+    ///
+    /// [`DeBruijnRule`]: struct.DeBruijnRule.html
+    /// [`Term::alpha_hash`]: enum.Term.html#method.alpha_hash
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r1 = parse_rule(&mut sig, "A(x_ y_) = B(y_) | x_").expect("parse of A(x_ y_) = B(y_) | x_");
+    /// let r2 = parse_rule(&mut sig, "A(y_ x_) = B(x_) | y_").expect("parse of A(y_ x_) = B(x_) | y_");
+    /// let r3 = parse_rule(&mut sig, "A(x_ y_) = B(x_) | y_").expect("parse of A(x_ y_) = B(x_) | y_");
+    ///
+    /// assert_eq!(r1.alpha_hash(), r2.alpha_hash());
+    /// assert_ne!(r1.alpha_hash(), r3.alpha_hash());
+    /// ```
+    pub fn alpha_hash(&self) -> u64 {
+        let mut state = DefaultHasher::new();
+        let mut indices = HashMap::new();
+        self.lhs.alpha_hash_with(&mut indices, &mut state);
+        self.rhs.len().hash(&mut state);
+        for rhs in &self.rhs {
+            rhs.alpha_hash_with(&mut indices, &mut state);
+        }
+        self.conditions.len().hash(&mut state);
+        for (c, d) in &self.conditions {
+            c.alpha_hash_with(&mut indices, &mut state);
+            d.alpha_hash_with(&mut indices, &mut state);
+        }
+        state.finish()
+    }
+    /// The [`DeBruijnRule`] of the `Rule`.
+    ///
+    /// [`DeBruijnRule`]: struct.DeBruijnRule.html
+    pub fn to_debruijn(&self) -> DeBruijnRule {
+        let mut indices = HashMap::new();
+        DeBruijnRule {
+            lhs: self.lhs.to_debruijn_with(&mut indices),
+            rhs: self
+                .rhs
+                .iter()
+                .map(|t| t.to_debruijn_with(&mut indices))
+                .collect(),
+            conditions: self
+                .conditions
+                .iter()
+                .map(|(c, d)| {
+                    let c = c.to_debruijn_with(&mut indices);
+                    (c, d.to_debruijn_with(&mut indices))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TRS {
+    /// The [`DeBruijnTRS`] of the `TRS`. Only the [`Rule`]s are considered, not whether the
+    /// `TRS` is deterministic or how it checks conditions.
+    ///
+    /// [`DeBruijnTRS`]: struct.DeBruijnTRS.html
+    /// [`Rule`]: struct.Rule.html
+    pub fn canonical_form(&self) -> DeBruijnTRS {
+        let mut rules: Vec<DeBruijnRule> = self.rules.iter().map(Rule::to_debruijn).collect();
+        rules.sort();
+        DeBruijnTRS { rules }
+    }
+    /// A hash of the [`DeBruijnTRS`] of the `TRS`, as in [`Term::alpha_hash`], so that `TRS`s
+    /// differing only by renaming [`Variable`]s and reordering [`Rule`]s hash alike.
+    ///
+    /// [`DeBruijnTRS`]: struct.DeBruijnTRS.html
+    /// [`Term::alpha_hash`]: enum.Term.html#method.alpha_hash
+    /// [`Variable`]: struct.Variable.html
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t1 = parse_trs(&mut sig, "A(x_) = x_; B = C;").expect("parse of A(x_) = x_; B = C;");
+    /// let t2 = parse_trs(&mut sig, "B = C; A(y_) = y_;").expect("parse of B = C; A(y_) = y_;");
+    /// let t3 = parse_trs(&mut sig, "A(x_) = x_; B = A(C);").expect("parse of A(x_) = x_; B = A(C);");
+    ///
+    /// assert_eq!(t1.alpha_hash(), t2.alpha_hash());
+    /// assert_ne!(t1.alpha_hash(), t3.alpha_hash());
+    /// ```
+    pub fn alpha_hash(&self) -> u64 {
+        let mut state = DefaultHasher::new();
+        self.canonical_form().hash(&mut state);
+        state.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn debruijn_test() {
+        let mut sig = Signature::default();
+        let t = parse_term(&mut sig, "F(A(x_ y_) A(y_ x_) A(z_ w_) A(w_ w_) A(x_ A))")
+            .expect("parse of terms");
+        let terms: HashSet<DeBruijnTerm> = t.args().iter().map(Term::to_debruijn).collect();
+        assert_eq!(terms.len(), 3);
+
+        let t = parse_term(&mut sig, "F(B(x_ C) y_ x_)").expect("parse of F(B(x_ C) y_ x_)");
+        let d = t.to_debruijn();
+        let back = d.to_term(&mut sig).expect("conversion");
+        assert!(Term::alpha(&t, &back).is_some());
+        assert_eq!(back.to_debruijn(), d);
+
+        let bad = DeBruijnTerm::Application {
+            op: d.indices().len() + 100,
+            args: vec![],
+        };
+        assert_eq!(bad.to_term(&mut sig), None);
+        let wrong_arity = DeBruijnTerm::Application {
+            op: 0,
+            args: vec![],
+        };
+        assert_eq!(wrong_arity.to_term(&mut sig), None);
+
+        let r = parse_rule(&mut sig, "F(x_ y_ z_) = G(z_) | x_")
+            .expect("parse of F(x_ y_ z_) = G(z_) | x_");
+        let dr = r.to_debruijn();
+        assert_eq!(dr.display(&sig), "F(#0 #1 #2) = G(#2) | #0");
+        let r2 = dr.to_rule(&mut sig).expect("conversion");
+        assert!(Rule::alpha(&r, &r2).is_some());
+        assert_eq!(r2.to_debruijn(), dr);
+
+        let invalid = DeBruijnRule {
+            lhs: DeBruijnTerm::Variable(0),
+            rhs: vec![DeBruijnTerm::Variable(1)],
+            conditions: vec![],
+        };
+        assert_eq!(invalid.to_rule(&mut sig), None);
+
+        let t = parse_trs(&mut sig, "F(x_ y_ z_) = G(z_) | x_; G(x_) = x_; A = B;")
+            .expect("parse of TRS");
+        let dt = t.canonical_form();
+        let mut shuffled = t.clone();
+        shuffled.rules_mut().reverse();
+        assert_eq!(shuffled.canonical_form(), dt);
+        let back = dt.to_trs(&mut sig).expect("conversion");
+        assert_eq!(back.len(), 3);
+        assert_eq!(back.canonical_form(), dt);
+        assert!(t.rules.iter().all(|r| back.get_clause(r).is_some()));
+        assert_eq!(TRS::new(vec![]).canonical_form().rules, vec![]);
+    }
+
+    #[test]
+    fn alpha_hash_test() {
+        let mut sig = Signature::default();
+        let t = parse_term(&mut sig, "F(A(x_ y_) A(y_ x_) A(z_ w_) A(w_ w_) A(x_ A))")
+            .expect("parse of terms");
+        let hashes: HashSet<u64> = t.args().iter().map(Term::alpha_hash).collect();
+        assert_eq!(hashes.len(), 3);
+
+        let t2 = parse_term(&mut sig, "F(A(a_ b_) A(b_ a_) A(c_ d_) A(d_ d_) A(a_ A))")
+            .expect("parse of terms");
+        assert_ne!(t, t2);
+        assert_eq!(t.alpha_hash(), t2.alpha_hash());
+
+        let r1 = parse_rule(&mut sig, "F(x_ y_) = G(y_) <= H(x_) = A")
+            .expect("parse of F(x_ y_) = G(y_) <= H(x_) = A");
+        let r2 = parse_rule(&mut sig, "F(z_ w_) = G(w_) <= H(z_) = A")
+            .expect("parse of F(z_ w_) = G(w_) <= H(z_) = A");
+        let r3 = parse_rule(&mut sig, "F(z_ w_) = G(w_) <= H(w_) = A")
+            .expect("parse of F(z_ w_) = G(w_) <= H(w_) = A");
+        assert_eq!(r1.alpha_hash(), r2.alpha_hash());
+        assert_ne!(r1.alpha_hash(), r3.alpha_hash());
+    }
+}
@@ -0,0 +1,160 @@
+use super::{Rule, TRS};
+
+/// The clauses found in only one or both of two [`TRS`]s, as computed by [`TRS::diff`].
+///
+/// Each field lists [`Rule`]s restricted to the relevant clauses, in the order of the [`TRS`]
+/// they come from.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`TRS::diff`]: struct.TRS.html#method.diff
+/// [`Rule`]: struct.Rule.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrsDiff {
+    /// The clauses only in the first [`TRS`].
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub left: Vec<Rule>,
+    /// The clauses only in the second [`TRS`].
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub right: Vec<Rule>,
+    /// The clauses in both [`TRS`]s, as written in the first.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub shared: Vec<Rule>,
+}
+impl TrsDiff {
+    /// Do the two [`TRS`]s have the same clauses?
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty() && self.right.is_empty()
+    }
+}
+
+// Is `clause` one of the clauses of `rule`, up to renaming Variables?
+fn has_clause(rule: &Rule, clause: &Rule) -> bool {
+    match rule.contains(clause) {
+        Some(sub) => {
+            rule.conditions.len() == clause.conditions.len()
+                && rule
+                    .conditions
+                    .iter()
+                    .zip(&clause.conditions)
+                    .all(|((s1, t1), (s2, t2))| {
+                        *s1 == s2.substitute(&sub) && *t1 == t2.substitute(&sub)
+                    })
+        }
+        None => false,
+    }
+}
+
+// Split the clauses of `a` into those not in `b` and those in `b`.
+fn partition(a: &TRS, b: &TRS) -> (Vec<Rule>, Vec<Rule>) {
+    let mut only = vec![];
+    let mut shared = vec![];
+    for rule in &a.rules {
+        let (mut here, mut there) = (vec![], vec![]);
+        for clause in rule.clauses() {
+            if b.rules.iter().any(|r| has_clause(r, &clause)) {
+                there.extend(clause.rhs);
+            } else {
+                here.extend(clause.rhs);
+            }
+        }
+        for (rhs, rules) in [(here, &mut only), (there, &mut shared)] {
+            if !rhs.is_empty() {
+                rules.push(Rule {
+                    lhs: rule.lhs.clone(),
+                    rhs,
+                    conditions: rule.conditions.clone(),
+                    weights: None,
+                });
+            }
+        }
+    }
+    (only, shared)
+}
+
+impl TRS {
+    /// Compare the clauses of two `TRS`s, up to renaming [`Variable`]s. Rule order and clause
+    /// weights are ignored.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let learned = parse_trs(&mut sig,
+    /// "PLUS(ZERO x_) = x_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_)) | y_;").expect("parse of learned TRS");
+    /// let truth = parse_trs(&mut sig,
+    /// "PLUS(SUCC(a_) b_) = SUCC(PLUS(a_ b_));
+    /// PLUS(ZERO a_) = a_;
+    /// PLUS(a_ ZERO) = a_;").expect("parse of true TRS");
+    ///
+    /// let diff = TRS::diff(&learned, &truth);
+    ///
+    /// let left: Vec<_> = diff.left.iter().map(|r| r.display(&sig)).collect();
+    /// assert_eq!(left, vec!["PLUS(SUCC(x_) y_) = y_"]);
+    ///
+    /// let right: Vec<_> = diff.right.iter().map(|r| r.display(&sig)).collect();
+    /// assert_eq!(right, vec!["PLUS(a_ ZERO) = a_"]);
+    ///
+    /// let shared: Vec<_> = diff.shared.iter().map(|r| r.display(&sig)).collect();
+    /// assert_eq!(shared, vec!["PLUS(ZERO x_) = x_", "PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_))"]);
+    ///
+    /// assert!(TRS::diff(&truth, &truth).is_empty());
+    /// ```
+    pub fn diff(a: &TRS, b: &TRS) -> TrsDiff {
+        let (left, shared) = partition(a, b);
+        let (right, _) = partition(b, a);
+        TrsDiff {
+            left,
+            right,
+            shared,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn diff_test() {
+        let mut sig = Signature::default();
+        let a = parse_trs(&mut sig, "F(x_ y_) = x_ | G(y_); A = B;").expect("parse of a");
+        let b = parse_trs(&mut sig, "A = B | C; F(y_ x_) = y_ | G(x_);").expect("parse of b");
+
+        let diff = TRS::diff(&a, &b);
+        assert!(diff.left.is_empty());
+        assert_eq!(diff.right.len(), 1);
+        assert_eq!(diff.right[0].display(&sig), "A = C");
+        assert_eq!(diff.shared.len(), 2);
+        assert_eq!(diff.shared[0], a.rules[0]);
+        assert!(!diff.is_empty());
+
+        // swapping the variables makes a different clause
+        let c = parse_trs(&mut sig, "F(x_ y_) = y_ | G(y_); A = B;").expect("parse of c");
+        let diff = TRS::diff(&a, &c);
+        assert_eq!(diff.left[0].display(&sig), "F(x_ y_) = x_");
+        assert_eq!(diff.right[0].display(&sig), "F(x_ y_) = y_");
+
+        // as do different conditions
+        let mut d = a.clone();
+        let x = d.rules[0].lhs.args()[0].clone();
+        d.rules[0].conditions.push((x.clone(), x));
+        let diff = TRS::diff(&a, &d);
+        assert_eq!(diff.left.len(), 1);
+        assert_eq!(diff.right.len(), 1);
+        assert_eq!(diff.right[0].conditions.len(), 1);
+        assert_eq!(diff.shared.len(), 1);
+        assert_eq!(diff.shared[0].display(&sig), "A = B");
+    }
+}
@@ -0,0 +1,408 @@
+use std::fmt;
+
+use super::{Term, TRS};
+
+/// The costs of the edits counted by [`Term::tree_edit_distance`].
+///
+/// [`Term::tree_edit_distance`]: enum.Term.html#method.tree_edit_distance
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EditCosts {
+    /// The cost of inserting a node.
+    pub insert: f64,
+    /// The cost of deleting a node.
+    pub delete: f64,
+    /// The cost of changing the [`Operator`] or [`Variable`] of a node.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    pub relabel: f64,
+}
+impl Default for EditCosts {
+    /// Every edit costs `1`.
+    fn default() -> EditCosts {
+        EditCosts {
+            insert: 1.0,
+            delete: 1.0,
+            relabel: 1.0,
+        }
+    }
+}
+
+// A Term's nodes in postorder, with the postorder index of the leftmost leaf below each and
+// the keyroots: the highest node with each leftmost leaf, in increasing order.
+struct Nodes<'a> {
+    nodes: Vec<&'a Term>,
+    leftmost: Vec<usize>,
+    keyroots: Vec<usize>,
+}
+impl<'a> Nodes<'a> {
+    fn new(term: &'a Term) -> Nodes<'a> {
+        let mut nodes = vec![];
+        let mut leftmost = vec![];
+        // each Term with the index its leftmost leaf will have, once its arguments are done
+        let mut stack = vec![(term, None)];
+        while let Some((t, start)) = stack.pop() {
+            match start {
+                Some(start) => {
+                    nodes.push(t);
+                    leftmost.push(start);
+                }
+                None => {
+                    stack.push((t, Some(nodes.len())));
+                    if let Term::Application { ref args, .. } = *t {
+                        stack.extend(args.iter().rev().map(|arg| (arg, None)));
+                    }
+                }
+            }
+        }
+        let mut seen = vec![false; nodes.len()];
+        let mut keyroots: Vec<_> = (0..nodes.len())
+            .rev()
+            .filter(|&i| !std::mem::replace(&mut seen[leftmost[i]], true))
+            .collect();
+        keyroots.reverse();
+        Nodes {
+            nodes,
+            leftmost,
+            keyroots,
+        }
+    }
+}
+
+// Do `s` and `t` have the same Operator or Variable at the root?
+fn same_label(s: &Term, t: &Term) -> bool {
+    match (s, t) {
+        (Term::Variable(v), Term::Variable(w)) => v == w,
+        (Term::Application { op: o1, .. }, Term::Application { op: o2, .. }) => o1 == o2,
+        _ => false,
+    }
+}
+
+impl Term {
+    /// The least total cost of node insertions, deletions, and relabelings turning `t1` into
+    /// `t2`, treating each as an ordered tree labeled by [`Operator`]s and [`Variable`]s, as
+    /// computed by the [Zhang-Shasha algorithm].
+    ///
+    /// Unlike comparing serializations, this respects the structure of the `Term`s, and is
+    /// deterministic. It takes time and space proportional to the product of their sizes.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [Zhang-Shasha algorithm]: https://doi.org/10.1137/0218082
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, EditCosts, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t1 = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let t2 = parse_term(&mut sig, "F(A C)").expect("parse of F(A C)");
+    /// let t3 = parse_term(&mut sig, "G(A)").expect("parse of G(A)");
+    /// let costs = EditCosts::default();
+    ///
+    /// assert_eq!(Term::tree_edit_distance(&t1, &t1, &costs), 0.0);
+    /// assert_eq!(Term::tree_edit_distance(&t1, &t2, &costs), 1.0);
+    /// assert_eq!(Term::tree_edit_distance(&t1, &t3, &costs), 2.0);
+    ///
+    /// let costs = EditCosts { relabel: 5.0, ..EditCosts::default() };
+    /// assert_eq!(Term::tree_edit_distance(&t1, &t2, &costs), 2.0);
+    /// ```
+    pub fn tree_edit_distance(t1: &Term, t2: &Term, costs: &EditCosts) -> f64 {
+        edit_distance(t1, t2, costs.insert, costs.delete, |s, t| {
+            if same_label(s, t) {
+                0.0
+            } else {
+                costs.relabel
+            }
+        })
+    }
+}
+
+/// The probabilities of each node edit scored by [`TRS::p_term`].
+///
+/// [`TRS::p_term`]: struct.TRS.html#method.p_term
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PTermDist {
+    insert: f64,
+    delete: f64,
+    correct: f64,
+    incorrect: f64,
+}
+impl PTermDist {
+    /// Create a `PTermDist` from the probabilities of inserting a node, deleting a node,
+    /// keeping a node's [`Operator`] or [`Variable`] as it is, and changing it.
+    ///
+    /// Each must be a probability, and since every node is deleted, kept, or changed, the
+    /// last three must sum to `1`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{PTermDist, PTermDistError};
+    /// assert!(PTermDist::new(0.1, 0.1, 0.85, 0.05).is_ok());
+    ///
+    /// assert_eq!(
+    ///     PTermDist::new(1.5, 0.1, 0.85, 0.05),
+    ///     Err(PTermDistError::NotAProbability(1.5)),
+    /// );
+    ///
+    /// match PTermDist::new(0.1, 0.2, 0.9, 0.05) {
+    ///     Err(PTermDistError::MassNotOne(mass)) => assert!((mass - 1.15).abs() < 1e-9),
+    ///     _ => panic!("expected too much mass"),
+    /// }
+    /// ```
+    pub fn new(
+        insert: f64,
+        delete: f64,
+        correct: f64,
+        incorrect: f64,
+    ) -> Result<PTermDist, PTermDistError> {
+        for &p in &[insert, delete, correct, incorrect] {
+            if !(0.0..=1.0).contains(&p) {
+                return Err(PTermDistError::NotAProbability(p));
+            }
+        }
+        let mass = delete + correct + incorrect;
+        if (mass - 1.0).abs() > 1e-9 {
+            return Err(PTermDistError::MassNotOne(mass));
+        }
+        Ok(PTermDist {
+            insert,
+            delete,
+            correct,
+            incorrect,
+        })
+    }
+    /// The probability of inserting a node.
+    pub fn insert(&self) -> f64 {
+        self.insert
+    }
+    /// The probability of deleting a node.
+    pub fn delete(&self) -> f64 {
+        self.delete
+    }
+    /// The probability of keeping a node's [`Operator`] or [`Variable`] as it is.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    pub fn correct(&self) -> f64 {
+        self.correct
+    }
+    /// The probability of changing a node's [`Operator`] or [`Variable`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    pub fn incorrect(&self) -> f64 {
+        self.incorrect
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// The error type for [`PTermDist::new`], reporting invalid probabilities.
+///
+/// [`PTermDist::new`]: struct.PTermDist.html#method.new
+pub enum PTermDistError {
+    /// Returned when a parameter is not between `0` and `1`.
+    NotAProbability(f64),
+    /// Returned when deleting, keeping, and changing a node have a total probability other
+    /// than `1`.
+    MassNotOne(f64),
+}
+impl fmt::Display for PTermDistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PTermDistError::NotAProbability(p) => write!(f, "{} is not a probability", p),
+            PTermDistError::MassNotOne(mass) => write!(
+                f,
+                "deletion, correct and incorrect probabilities sum to {}, not 1",
+                mass
+            ),
+        }
+    }
+}
+impl ::std::error::Error for PTermDistError {
+    fn description(&self) -> &'static str {
+        "invalid edit probabilities"
+    }
+}
+
+impl TRS {
+    /// The log probability of the most likely sequence of node edits turning `x` into `y`,
+    /// where every node of `x` is deleted, kept, or relabeled, every node of `y` not
+    /// accounted for is inserted, and `dist` gives the probability of each edit.
+    ///
+    /// This is [`Term::tree_edit_distance`] with each edit costing its negative log
+    /// probability, so it scores the full structure of both [`Term`]s rather than a
+    /// flattening of them.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`Term::tree_edit_distance`]: enum.Term.html#method.tree_edit_distance
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, PTermDist, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let x = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let y = parse_term(&mut sig, "F(A C)").expect("parse of F(A C)");
+    /// let z = parse_term(&mut sig, "A").expect("parse of A");
+    /// let dist = PTermDist::new(0.1, 0.1, 0.85, 0.05).expect("valid probabilities");
+    ///
+    /// let expected = 0.85f64.ln() * 3.0;
+    /// assert!((TRS::p_term(&x, &x, &dist) - expected).abs() < 1e-9);
+    ///
+    /// let expected = 0.85f64.ln() * 2.0 + 0.05f64.ln();
+    /// assert!((TRS::p_term(&x, &y, &dist) - expected).abs() < 1e-9);
+    ///
+    /// let expected = 0.85f64.ln() + 0.1f64.ln() * 2.0;
+    /// assert!((TRS::p_term(&x, &z, &dist) - expected).abs() < 1e-9);
+    /// ```
+    pub fn p_term(x: &Term, y: &Term, dist: &PTermDist) -> f64 {
+        let (correct, incorrect) = (-dist.correct.ln(), -dist.incorrect.ln());
+        -edit_distance(x, y, -dist.insert.ln(), -dist.delete.ln(), |s, t| {
+            if same_label(s, t) {
+                correct
+            } else {
+                incorrect
+            }
+        })
+    }
+}
+
+// The Zhang-Shasha distance from `t1` to `t2`, where `matched` gives the cost of mapping one
+// node onto another.
+fn edit_distance<F>(t1: &Term, t2: &Term, insert: f64, delete: f64, matched: F) -> f64
+where
+    F: Fn(&Term, &Term) -> f64,
+{
+    let (a, b) = (Nodes::new(t1), Nodes::new(t2));
+    let (n, m) = (a.nodes.len(), b.nodes.len());
+    let mut tree = vec![vec![0.0; m]; n];
+    let mut forest = vec![vec![0.0; m + 1]; n + 1];
+    for &i in &a.keyroots {
+        for &j in &b.keyroots {
+            // distances between the forests a[li..=li + x - 1] and b[lj..=lj + y - 1]
+            let (li, lj) = (a.leftmost[i], b.leftmost[j]);
+            let (rows, cols) = (i - li + 1, j - lj + 1);
+            forest[0][0] = 0.0;
+            for x in 1..=rows {
+                forest[x][0] = forest[x - 1][0] + delete;
+            }
+            for y in 1..=cols {
+                forest[0][y] = forest[0][y - 1] + insert;
+            }
+            for x in 1..=rows {
+                for y in 1..=cols {
+                    let (i1, j1) = (li + x - 1, lj + y - 1);
+                    let edit = (forest[x - 1][y] + delete).min(forest[x][y - 1] + insert);
+                    forest[x][y] = if a.leftmost[i1] == li && b.leftmost[j1] == lj {
+                        let d = edit.min(forest[x - 1][y - 1] + matched(a.nodes[i1], b.nodes[j1]));
+                        tree[i1][j1] = d;
+                        d
+                    } else {
+                        let (p, q) = (a.leftmost[i1] - li, b.leftmost[j1] - lj);
+                        edit.min(forest[p][q] + tree[i1][j1])
+                    };
+                }
+            }
+        }
+    }
+    tree[n - 1][m - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn tree_edit_distance_test() {
+        let mut sig = Signature::default();
+        let terms: Vec<Term> = [
+            "F(A B)",
+            "F(A C)",
+            "G(A)",
+            "F(G(A) B)",
+            "H(F(A B) x_)",
+            "x_",
+            "y_",
+            "CONS(A CONS(B NIL))",
+        ]
+        .iter()
+        .map(|s| parse_term(&mut sig, s).expect("parse of term"))
+        .collect();
+        let costs = EditCosts::default();
+        let d = |i: usize, j: usize| Term::tree_edit_distance(&terms[i], &terms[j], &costs);
+
+        assert_eq!(d(0, 3), 1.0);
+        assert_eq!(d(0, 4), 2.0);
+        assert_eq!(d(5, 6), 1.0);
+        assert_eq!(d(0, 5), 3.0);
+        for i in 0..terms.len() {
+            assert_eq!(d(i, i), 0.0);
+            for j in 0..terms.len() {
+                assert_eq!(d(i, j), d(j, i));
+                assert!(d(i, j) <= (terms[i].size() + terms[j].size()) as f64);
+                for k in 0..terms.len() {
+                    assert!(d(i, k) <= d(i, j) + d(j, k));
+                }
+            }
+        }
+
+        // relabeling is dearer than deleting x_ and inserting every node
+        let costs = EditCosts {
+            insert: 2.0,
+            delete: 3.0,
+            relabel: 100.0,
+        };
+        let d = Term::tree_edit_distance(&terms[5], &terms[7], &costs);
+        assert_eq!(d, 3.0 + 2.0 * terms[7].size() as f64);
+    }
+
+    #[test]
+    fn p_term_test() {
+        let mut sig = Signature::default();
+        let x = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+        let y = parse_term(&mut sig, "G(A B)").expect("parse of G(A B)");
+        let z = parse_term(&mut sig, "CONS(A NIL)").expect("parse of CONS(A NIL)");
+        let dist = PTermDist::new(0.5, 0.5, 0.5, 0.0).expect("valid probabilities");
+
+        assert!((TRS::p_term(&x, &x, &dist) - 0.125f64.ln()).abs() < 1e-9);
+        // with relabeling impossible, F is deleted and G inserted
+        assert!((TRS::p_term(&x, &y, &dist) - 0.0625f64.ln()).abs() < 1e-9);
+        // F and B are deleted, CONS and NIL inserted
+        assert!((TRS::p_term(&x, &z, &dist) - 0.03125f64.ln()).abs() < 1e-9);
+
+        let dist = PTermDist::new(0.0, 0.5, 0.5, 0.0).expect("valid probabilities");
+        assert_eq!(TRS::p_term(&x, &y, &dist), f64::NEG_INFINITY);
+        let dist = PTermDist::new(0.0, 0.1, 0.9, 0.0).expect("valid probabilities");
+        let p = TRS::p_term(&z, &x.args()[0], &dist);
+        assert!((p - 0.009f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn p_term_dist_test() {
+        let dist = PTermDist::new(0.3, 0.2, 0.7, 0.1).expect("valid probabilities");
+        assert_eq!(
+            (
+                dist.insert(),
+                dist.delete(),
+                dist.correct(),
+                dist.incorrect()
+            ),
+            (0.3, 0.2, 0.7, 0.1)
+        );
+        assert_eq!(
+            PTermDist::new(0.3, -0.2, 1.1, 0.1),
+            Err(PTermDistError::NotAProbability(-0.2))
+        );
+        assert!(PTermDist::new(0.3, 0.2, 0.7, 0.2).is_err());
+        assert!(PTermDist::new(0.3, 0.0, 1.0, f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,338 @@
+use super::{Rule, TRSError, Term, TRS};
+
+/// An invertible change to the [`Rule`]s of a [`TRS`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`TRS`]: struct.TRS.html
+#[derive(Debug, Clone, PartialEq)]
+enum Edit {
+    Insert(usize, Rule),
+    Remove(usize, Rule),
+    Replace(usize, Rule, Rule),
+    Move(usize, usize),
+}
+impl Edit {
+    fn apply(&self, trs: &mut TRS) {
+        match *self {
+            Edit::Insert(idx, ref rule) => trs.insert_rule(idx, rule.clone()),
+            Edit::Remove(idx, _) => {
+                trs.remove_rule(idx);
+            }
+            Edit::Replace(idx, _, ref new) => {
+                trs.remove_rule(idx);
+                trs.insert_rule(idx, new.clone());
+            }
+            Edit::Move(i, j) => {
+                let rule = trs.remove_rule(i);
+                trs.insert_rule(j, rule);
+            }
+        }
+    }
+    fn inverse(&self) -> Edit {
+        match *self {
+            Edit::Insert(idx, ref rule) => Edit::Remove(idx, rule.clone()),
+            Edit::Remove(idx, ref rule) => Edit::Insert(idx, rule.clone()),
+            Edit::Replace(idx, ref old, ref new) => Edit::Replace(idx, new.clone(), old.clone()),
+            Edit::Move(i, j) => Edit::Move(j, i),
+        }
+    }
+}
+
+/// A point in the history of a [`TrsEditor`] to return to with [`TrsEditor::revert`].
+///
+/// [`TrsEditor`]: struct.TrsEditor.html
+/// [`TrsEditor::revert`]: struct.TrsEditor.html#method.revert
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    // the number of edits done, and the serial number of the last
+    len: usize,
+    last: Option<usize>,
+}
+
+/// A [`TRS`] which records each edit so that it can be undone and redone.
+///
+/// Each edit stores only the [`Rule`]s it changes, so backing out of a proposed change does
+/// not require a copy of the whole [`TRS`].
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Rule`]: struct.Rule.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, TrsEditor, parse_trs, parse_rule};
+/// let mut sig = Signature::default();
+/// let t = parse_trs(&mut sig, "A = B; C = D;").expect("parse of A = B; C = D;");
+/// let mut editor = TrsEditor::new(t);
+///
+/// let checkpoint = editor.checkpoint();
+/// let rule = parse_rule(&mut sig, "E = F").expect("parse of E = F");
+/// editor.insert(1, rule).expect("inserting E = F");
+/// editor.move_rule(0, 2).expect("moving A = B");
+/// assert_eq!(editor.trs().display(&sig), "E = F;\nC = D;\nA = B;");
+///
+/// assert!(editor.undo());
+/// assert_eq!(editor.trs().display(&sig), "A = B;\nE = F;\nC = D;");
+/// assert!(editor.redo());
+/// assert_eq!(editor.trs().display(&sig), "E = F;\nC = D;\nA = B;");
+///
+/// assert!(editor.revert(checkpoint));
+/// assert_eq!(editor.trs().display(&sig), "A = B;\nC = D;");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrsEditor {
+    trs: TRS,
+    done: Vec<(usize, Edit)>,
+    undone: Vec<(usize, Edit)>,
+    serial: usize,
+}
+impl TrsEditor {
+    /// Start editing `trs` with an empty history.
+    pub fn new(trs: TRS) -> TrsEditor {
+        TrsEditor {
+            trs,
+            done: vec![],
+            undone: vec![],
+            serial: 0,
+        }
+    }
+    /// The [`TRS`] as edited so far.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub fn trs(&self) -> &TRS {
+        &self.trs
+    }
+    /// Stop editing, returning the [`TRS`].
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub fn into_trs(self) -> TRS {
+        self.trs
+    }
+    // Apply and record an edit, forgetting any undone edits.
+    fn record(&mut self, edit: Edit) {
+        edit.apply(&mut self.trs);
+        self.done.push((self.serial, edit));
+        self.serial += 1;
+        self.undone.clear();
+    }
+    /// Insert a [`Rule`], merging it with any [`Rule`] with the same lhs, as in
+    /// [`TRS::insert`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::insert`]: struct.TRS.html#method.insert
+    pub fn insert(&mut self, idx: usize, rule: Rule) -> Result<&mut TrsEditor, TRSError> {
+        match self.trs.get(&rule.lhs) {
+            Some((i, old)) if !self.trs.is_deterministic => {
+                let mut new = old.clone();
+                new.merge(&rule);
+                self.record(Edit::Replace(i, old, new));
+                Ok(self)
+            }
+            _ => self.insert_idx(idx, rule),
+        }
+    }
+    /// Insert a [`Rule`] at index `idx`, as in [`TRS::insert_idx`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::insert_idx`]: struct.TRS.html#method.insert_idx
+    pub fn insert_idx(&mut self, idx: usize, rule: Rule) -> Result<&mut TrsEditor, TRSError> {
+        if self.trs.is_deterministic && rule.len() > 1 {
+            return Err(TRSError::NondeterministicRule);
+        } else if idx > self.trs.len() {
+            return Err(TRSError::InvalidIndex(idx, self.trs.len()));
+        } else if self.trs.get(&rule.lhs).is_some() {
+            return Err(TRSError::AlreadyInTRS);
+        }
+        self.record(Edit::Insert(idx, rule));
+        Ok(self)
+    }
+    /// Remove the [`Rule`] with lhs `lhs`, as in [`TRS::remove`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::remove`]: struct.TRS.html#method.remove
+    pub fn remove(&mut self, lhs: &Term) -> Result<Rule, TRSError> {
+        match self.trs.get(lhs) {
+            Some((idx, _)) => self.remove_idx(idx),
+            None => Err(TRSError::NotInTRS),
+        }
+    }
+    /// Remove the [`Rule`] at index `idx`, as in [`TRS::remove_idx`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::remove_idx`]: struct.TRS.html#method.remove_idx
+    pub fn remove_idx(&mut self, idx: usize) -> Result<Rule, TRSError> {
+        match self.trs.get_idx(idx) {
+            Some(rule) => {
+                self.record(Edit::Remove(idx, rule.clone()));
+                Ok(rule)
+            }
+            None => Err(TRSError::InvalidIndex(idx, self.trs.len())),
+        }
+    }
+    /// Replace the [`Rule`] at index `idx`, returning the old one. Fails as in
+    /// [`TrsEditor::insert_idx`], ignoring the [`Rule`] being replaced.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TrsEditor::insert_idx`]: struct.TrsEditor.html#method.insert_idx
+    pub fn replace(&mut self, idx: usize, rule: Rule) -> Result<Rule, TRSError> {
+        let old = self
+            .trs
+            .get_idx(idx)
+            .ok_or_else(|| TRSError::InvalidIndex(idx, self.trs.len()))?;
+        if self.trs.is_deterministic && rule.len() > 1 {
+            return Err(TRSError::NondeterministicRule);
+        } else if self
+            .trs
+            .rules
+            .iter()
+            .enumerate()
+            .any(|(i, r)| i != idx && Term::alpha(&rule.lhs, &r.lhs).is_some())
+        {
+            return Err(TRSError::AlreadyInTRS);
+        }
+        self.record(Edit::Replace(idx, old.clone(), rule));
+        Ok(old)
+    }
+    /// Move the [`Rule`] at index `i` to index `j`, as in [`TRS::move_rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::move_rule`]: struct.TRS.html#method.move_rule
+    pub fn move_rule(&mut self, i: usize, j: usize) -> Result<&mut TrsEditor, TRSError> {
+        let len = self.trs.len();
+        if i >= len {
+            return Err(TRSError::InvalidIndex(i, len));
+        } else if j >= len {
+            return Err(TRSError::InvalidIndex(j, len));
+        } else if i != j {
+            self.record(Edit::Move(i, j));
+        }
+        Ok(self)
+    }
+    /// Undo the most recent edit not yet undone. Returns `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.done.pop() {
+            Some((serial, edit)) => {
+                edit.inverse().apply(&mut self.trs);
+                self.undone.push((serial, edit));
+                true
+            }
+            None => false,
+        }
+    }
+    /// Redo the most recently undone edit, unless another edit has been made since. Returns
+    /// `false` if there is none.
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some((serial, edit)) => {
+                edit.apply(&mut self.trs);
+                self.done.push((serial, edit));
+                true
+            }
+            None => false,
+        }
+    }
+    /// The current point in the history.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            len: self.done.len(),
+            last: self.done.last().map(|&(serial, _)| serial),
+        }
+    }
+    /// Undo every edit made since `checkpoint`. Returns `false`, changing nothing, if the
+    /// history no longer passes through `checkpoint`, because edits made before it were undone
+    /// or forgotten.
+    pub fn revert(&mut self, checkpoint: Checkpoint) -> bool {
+        let last = match checkpoint.len {
+            0 => None,
+            n => self.done.get(n - 1).map(|&(serial, _)| serial),
+        };
+        if self.done.len() < checkpoint.len || last != checkpoint.last {
+            return false;
+        }
+        while self.done.len() > checkpoint.len {
+            self.undo();
+        }
+        true
+    }
+    /// Forget every edit, so that none can be undone or redone.
+    pub fn clear_history(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn trs_editor_test() {
+        let mut sig = Signature::default();
+        let t = parse_trs(&mut sig, "A = B; C = D | E; F(x_) = x_;").expect("parse of TRS");
+        let mut editor = TrsEditor::new(t.clone());
+        let start = editor.checkpoint();
+
+        // merging clauses is undone as a single replacement
+        let rule = parse_rule(&mut sig, "C = A").expect("parse of C = A");
+        editor.insert(0, rule).expect("merging C = A");
+        assert_eq!(
+            editor.trs().display(&sig),
+            "A = B;\nC = D | E | A;\nF(x_) = x_;"
+        );
+        let merged = editor.checkpoint();
+
+        let a = parse_term(&mut sig, "A").expect("parse of A");
+        assert_eq!(
+            editor.remove(&a).expect("removing A = B").display(&sig),
+            "A = B"
+        );
+        let rule = parse_rule(&mut sig, "F(y_) = G").expect("parse of F(y_) = G");
+        assert_eq!(
+            editor
+                .replace(1, rule.clone())
+                .expect("replacing")
+                .display(&sig),
+            "F(x_) = x_"
+        );
+        assert_eq!(editor.replace(0, rule).err(), Some(TRSError::AlreadyInTRS));
+        assert_eq!(editor.remove(&a).err(), Some(TRSError::NotInTRS));
+        assert_eq!(
+            editor.move_rule(0, 2).err(),
+            Some(TRSError::InvalidIndex(2, 2))
+        );
+        editor.move_rule(0, 1).expect("moving C = D | E | A");
+        assert_eq!(editor.trs().display(&sig), "F(y_) = G;\nC = D | E | A;");
+
+        assert!(editor.revert(merged));
+        assert_eq!(
+            editor.trs().display(&sig),
+            "A = B;\nC = D | E | A;\nF(x_) = x_;"
+        );
+        assert!(editor.redo());
+        assert!(editor.redo());
+        assert!(editor.redo());
+        assert!(!editor.redo());
+        assert_eq!(editor.trs().display(&sig), "F(y_) = G;\nC = D | E | A;");
+
+        // a new edit after undoing forgets the undone edits and checkpoints past them
+        assert!(editor.undo());
+        let late = editor.checkpoint();
+        assert!(editor.undo());
+        editor.remove_idx(0).expect("removing C = D | E | A");
+        assert!(!editor.redo());
+        assert!(!editor.revert(late));
+        assert!(editor.revert(merged));
+        assert!(editor.revert(start));
+        assert_eq!(editor.trs(), &t);
+        assert!(!editor.undo());
+
+        editor
+            .insert_idx(3, parse_rule(&mut sig, "H = A").expect("parse of H = A"))
+            .expect("inserting H = A");
+        editor.clear_history();
+        assert!(!editor.undo());
+        assert_eq!(editor.into_trs().len(), 4);
+    }
+}
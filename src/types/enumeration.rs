@@ -0,0 +1,360 @@
+use super::{Operator, Rule, Signature, Term, Variable, TRS};
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::iter;
+use std::mem;
+use std::rc::Rc;
+
+impl Signature {
+    /// Lazily enumerate every [`Term`] built from the [`Operator`]s and [`Variable`]s of the
+    /// `Signature` with at most `max_size` nodes. See [`Signature::enumerate_terms_with`] for
+    /// the order of enumeration.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature::enumerate_terms_with`]: struct.Signature.html#method.enumerate_terms_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::default();
+    /// sig.new_op(0, Some("Z".to_string()));
+    /// sig.new_op(1, Some("S".to_string()));
+    /// sig.new_var(Some("x".to_string()));
+    ///
+    /// let terms: Vec<String> = sig.enumerate_terms(3).map(|t| t.display(&sig)).collect();
+    ///
+    /// assert_eq!(terms, vec!["x_", "Z", "S(x_)", "S(Z)", "S(S(x_))", "S(S(Z))"]);
+    /// ```
+    pub fn enumerate_terms(&self, max_size: usize) -> TermEnumeration {
+        self.enumerate_terms_with(&self.operators(), &self.variables(), max_size)
+    }
+    /// Lazily enumerate every [`Term`] built from `operators` and `variables` with at most
+    /// `max_size` nodes.
+    ///
+    /// [`Term`]s are enumerated in order of increasing size. [`Term`]s of the same size are
+    /// ordered by their head: `variables`, then `operators`, each in the order given. [`Term`]s
+    /// with the same head are ordered by the sizes of their arguments, then lexicographically by
+    /// the order of the arguments themselves.
+    ///
+    /// [`Term`]: enum.Term.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::default();
+    /// let a = sig.new_op(0, Some("A".to_string()));
+    /// sig.new_op(0, Some("B".to_string()));
+    /// let f = sig.new_op(2, Some("F".to_string()));
+    ///
+    /// let terms: Vec<String> = sig
+    ///     .enumerate_terms_with(&[a, f], &[], 5)
+    ///     .map(|t| t.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     terms,
+    ///     vec!["A", "F(A A)", "F(A F(A A))", "F(F(A A) A)"]
+    /// );
+    /// ```
+    pub fn enumerate_terms_with(
+        &self,
+        operators: &[Operator],
+        variables: &[Variable],
+        max_size: usize,
+    ) -> TermEnumeration {
+        TermEnumeration {
+            operators: operators.to_vec(),
+            variables: variables.to_vec(),
+            max_size,
+            size: 0,
+            by_size: Rc::new(vec![]),
+            current: vec![],
+            terms: Box::new(iter::empty()),
+        }
+    }
+    /// Enumerate every [`TRS`] over the [`Operator`]s of the `Signature` with at most
+    /// `max_rules` single-clause, unconditional [`Rule`]s of at most `max_rule_size` nodes each.
+    /// Adds enough new [`Variable`]s to the `Signature` to write any such [`Rule`]. See
+    /// [`Signature::enumerate_trss_with`] for details.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature::enumerate_trss_with`]: struct.Signature.html#method.enumerate_trss_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::default();
+    /// sig.new_op(0, Some("A".to_string()));
+    /// sig.new_op(1, Some("F".to_string()));
+    ///
+    /// let trss: Vec<String> = sig.enumerate_trss(1, 3).map(|trs| trs.display(&sig)).collect();
+    ///
+    /// assert_eq!(
+    ///     trss,
+    ///     vec!["", "A = A;", "A = F(A);", "F(var0_) = var0_;", "F(var0_) = A;", "F(A) = A;"]
+    /// );
+    /// ```
+    pub fn enumerate_trss(&mut self, max_rules: usize, max_rule_size: usize) -> TRSEnumeration {
+        // an lhs of size n has at most n - 1 Variables, and the rhs has at least one node
+        let variables: Vec<Variable> = (2..max_rule_size).map(|_| self.new_var(None)).collect();
+        self.enumerate_trss_with(&self.operators(), &variables, max_rules, max_rule_size)
+    }
+    /// Enumerate every [`TRS`] built from `operators` and `variables` with at most `max_rules`
+    /// single-clause, unconditional [`Rule`]s of at most `max_rule_size` nodes each.
+    ///
+    /// Each [`TRS`] is enumerated once up to reordering its [`Rule`]s and renaming the
+    /// [`Variable`]s of each [`Rule`], and no [`TRS`] has two alpha-equivalent [`Rule`]s.
+    /// [`TRS`]s are enumerated in order of increasing length, and the [`Rule`]s of each [`TRS`]
+    /// keep the order in which [`Signature::enumerate_terms_with`] enumerates their lhs and rhs.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature::enumerate_terms_with`]: struct.Signature.html#method.enumerate_terms_with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::default();
+    /// let a = sig.new_op(0, Some("A".to_string()));
+    /// let b = sig.new_op(0, Some("B".to_string()));
+    ///
+    /// let trss: Vec<String> = sig
+    ///     .enumerate_trss_with(&[a, b], &[], 2, 2)
+    ///     .map(|trs| trs.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(trss.len(), 1 + 4 + 6);
+    /// assert_eq!(trss[5], "A = A;\nA = B;");
+    /// ```
+    pub fn enumerate_trss_with(
+        &self,
+        operators: &[Operator],
+        variables: &[Variable],
+        max_rules: usize,
+        max_rule_size: usize,
+    ) -> TRSEnumeration {
+        let mut seen = HashSet::new();
+        let mut rules = vec![];
+        let lhss = self
+            .enumerate_terms_with(operators, variables, max_rule_size.saturating_sub(1))
+            .filter(|lhs| matches!(lhs, Term::Application { .. }));
+        for lhs in lhss {
+            let rhss =
+                self.enumerate_terms_with(operators, &lhs.variables(), max_rule_size - lhs.size());
+            for rhs in rhss {
+                let rule = Rule::new(lhs.clone(), vec![rhs]).expect("valid rule");
+                if seen.insert(rule.to_debruijn()) {
+                    rules.push(rule);
+                }
+            }
+        }
+        let rules = Rc::new(rules);
+        let trss = (1..=max_rules).flat_map(move |n| {
+            let rules = Rc::clone(&rules);
+            (0..rules.len())
+                .combinations(n)
+                .map(move |idxs| TRS::new(idxs.into_iter().map(|i| rules[i].clone()).collect()))
+        });
+        TRSEnumeration(Box::new(iter::once(TRS::new(vec![])).chain(trss)))
+    }
+}
+
+/// A lazy iterator over [`TRS`]s.
+///
+/// See [`Signature::enumerate_trss_with`] for more information.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Signature::enumerate_trss_with`]: struct.Signature.html#method.enumerate_trss_with
+pub struct TRSEnumeration(Box<dyn Iterator<Item = TRS>>);
+impl Iterator for TRSEnumeration {
+    type Item = TRS;
+    fn next(&mut self) -> Option<TRS> {
+        self.0.next()
+    }
+}
+
+/// A lazy iterator over [`Term`]s in order of increasing size.
+///
+/// See [`Signature::enumerate_terms_with`] for more information.
+///
+/// [`Term`]: enum.Term.html
+/// [`Signature::enumerate_terms_with`]: struct.Signature.html#method.enumerate_terms_with
+pub struct TermEnumeration {
+    operators: Vec<Operator>,
+    variables: Vec<Variable>,
+    max_size: usize,
+    // The size of the Terms currently being enumerated.
+    size: usize,
+    // Every Term of each size smaller than `size`, starting from size 1.
+    by_size: Rc<Vec<Vec<Term>>>,
+    // The Terms of size `size` enumerated so far.
+    current: Vec<Term>,
+    terms: Box<dyn Iterator<Item = Term>>,
+}
+impl TermEnumeration {
+    // Move on to the Terms of the next size.
+    fn advance(&mut self) {
+        self.terms = Box::new(iter::empty());
+        if self.size > 0 {
+            let current = mem::take(&mut self.current);
+            Rc::make_mut(&mut self.by_size).push(current);
+        }
+        self.size += 1;
+        if self.size == 1 {
+            let variables = self.variables.iter().cloned().map(Term::Variable);
+            let constants = self
+                .operators
+                .iter()
+                .filter(|op| op.arity() == 0)
+                .map(|op| Term::Application {
+                    op: *op,
+                    args: vec![],
+                });
+            self.terms = Box::new(variables.chain(constants).collect_vec().into_iter());
+        } else {
+            let size = self.size;
+            let by_size = Rc::clone(&self.by_size);
+            let operators = self.operators.clone();
+            self.terms = Box::new(operators.into_iter().filter(|op| op.arity() > 0).flat_map(
+                move |op| {
+                    let by_size = Rc::clone(&by_size);
+                    compositions(size - 1, op.arity() as usize)
+                        .into_iter()
+                        .flat_map(move |sizes| {
+                            let op = op;
+                            let by_size = Rc::clone(&by_size);
+                            sizes
+                                .iter()
+                                .map(|s| 0..by_size[s - 1].len())
+                                .multi_cartesian_product()
+                                .map(move |idxs| Term::Application {
+                                    op,
+                                    args: sizes
+                                        .iter()
+                                        .zip(idxs)
+                                        .map(|(s, i)| by_size[s - 1][i].clone())
+                                        .collect(),
+                                })
+                        })
+                },
+            ));
+        }
+    }
+}
+impl Iterator for TermEnumeration {
+    type Item = Term;
+    fn next(&mut self) -> Option<Term> {
+        while self.size <= self.max_size {
+            if let Some(term) = self.terms.next() {
+                self.current.push(term.clone());
+                return Some(term);
+            }
+            if self.size == self.max_size {
+                break;
+            }
+            self.advance();
+        }
+        None
+    }
+}
+
+// Every way of writing `total` as an ordered sum of `parts` positive integers, in lexicographic
+// order.
+fn compositions(total: usize, parts: usize) -> Vec<Vec<usize>> {
+    if parts == 0 {
+        return if total == 0 { vec![vec![]] } else { vec![] };
+    }
+    if total < parts {
+        return vec![];
+    }
+    (1..=total - parts + 1)
+        .flat_map(|first| {
+            compositions(total - first, parts - 1)
+                .into_iter()
+                .map(move |mut rest| {
+                    rest.insert(0, first);
+                    rest
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn compositions_test() {
+        assert_eq!(compositions(3, 2), vec![vec![1, 2], vec![2, 1]]);
+        assert_eq!(compositions(2, 3), Vec::<Vec<usize>>::new());
+        assert_eq!(compositions(0, 0), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn enumerate_trss_test() {
+        let mut sig = Signature::default();
+        let a = sig.new_op(0, Some("A".to_string()));
+        let f = sig.new_op(1, Some("F".to_string()));
+
+        // A = A, A = F(A), F(x_) = x_, F(x_) = A, F(A) = A
+        assert_eq!(sig.enumerate_trss(2, 3).count(), 1 + 5 + 10);
+        assert_eq!(sig.enumerate_trss(0, 3).count(), 1);
+
+        // extra Variables only produce alpha-equivalent Rules
+        let x = sig.new_var(Some("x".to_string()));
+        let y = sig.new_var(Some("y".to_string()));
+        let trss: Vec<TRS> = sig.enumerate_trss_with(&[a, f], &[x, y], 2, 3).collect();
+        assert_eq!(trss.len(), 1 + 5 + 10);
+        for (i, t1) in trss.iter().enumerate() {
+            assert!(trss[i + 1..].iter().all(|t2| !TRS::alphas(t1, t2)));
+            assert!(t1.rules.iter().all(|rule| rule.size() <= 3));
+        }
+    }
+
+    #[test]
+    fn enumerate_terms_test() {
+        let mut sig = Signature::default();
+        let a = sig.new_op(0, Some("A".to_string()));
+        let f = sig.new_op(2, Some("F".to_string()));
+        let g = sig.new_op(1, Some("G".to_string()));
+        let x = sig.new_var(Some("x".to_string()));
+
+        assert_eq!(sig.enumerate_terms(0).count(), 0);
+
+        let terms: Vec<Term> = sig.enumerate_terms(6).collect();
+        // Count the Terms of each size directly: t(n) = [n = 1] * 2 + t(n-1) + sum t(i) t(n-1-i).
+        let mut counts = vec![0, 2];
+        for n in 2..7 {
+            let binary: usize = (1..n - 1).map(|i| counts[i] * counts[n - 1 - i]).sum();
+            counts.push(counts[n - 1] + binary);
+        }
+        assert_eq!(terms.len(), counts.iter().sum::<usize>());
+        assert!(terms.windows(2).all(|w| w[0].size() <= w[1].size()));
+        assert_eq!(terms.iter().unique().count(), terms.len());
+        assert_eq!(terms, sig.enumerate_terms(6).collect::<Vec<_>>());
+
+        let ground: Vec<String> = sig
+            .enumerate_terms_with(&[g, a], &[], 3)
+            .map(|t| t.display(&sig))
+            .collect();
+        assert_eq!(ground, vec!["A", "G(A)", "G(G(A))"]);
+
+        let binary: Vec<String> = sig
+            .enumerate_terms_with(&[f], &[x], 4)
+            .map(|t| t.display(&sig))
+            .collect();
+        assert_eq!(binary, vec!["x_", "F(x_ x_)"]);
+        assert_eq!(sig.enumerate_terms_with(&[], &[], 10).count(), 0);
+    }
+}
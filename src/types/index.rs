@@ -0,0 +1,363 @@
+use super::{Operator, Rule, Term};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A discrimination tree over the left-hand sides of a list of [`Rule`]s, for quickly finding
+/// the [`Rule`]s which might match a [`Term`].
+///
+/// Each left-hand side is stored as its preorder sequence of [`Operator`]s, with every
+/// [`Variable`] standing for any subterm. Looking up a [`Term`] follows only the paths
+/// consistent with it, so the cost depends on the size of the [`Term`] and the number of
+/// candidates rather than on the number of [`Rule`]s. The candidates include every [`Rule`]
+/// which matches, but repeated [`Variable`]s are not checked, so some may not.
+///
+/// Each [`TRS`] keeps an index of its [`Rule`]s, updated as they change, to search for redexes.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Term`]: enum.Term.html
+/// [`Operator`]: struct.Operator.html
+/// [`Variable`]: struct.Variable.html
+/// [`TRS`]: struct.TRS.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, RuleIndex, parse_trs, parse_term};
+/// let mut sig = Signature::default();
+/// let trs = parse_trs(&mut sig,
+/// "F(A x_) = A;
+/// F(x_ x_) = B;
+/// G(x_) = C;
+/// F(B y_) = D;").expect("parse of TRS");
+/// let index = RuleIndex::new(trs.rules());
+///
+/// let term = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+///
+/// assert_eq!(index.candidates(&term), vec![0, 1]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleIndex {
+    nodes: Vec<IndexNode>,
+    rules: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct IndexNode {
+    // children by Operator id
+    operators: HashMap<usize, usize>,
+    // the child for a Variable
+    variable: Option<usize>,
+    // the rules whose left-hand sides end here
+    rules: Vec<usize>,
+}
+
+impl RuleIndex {
+    /// Index the left-hand sides of `rules`, referring to each by its position in `rules`.
+    pub fn new(rules: &[Rule]) -> RuleIndex {
+        let mut index = RuleIndex {
+            nodes: vec![IndexNode::default()],
+            rules: rules.len(),
+        };
+        for (i, rule) in rules.iter().enumerate() {
+            index.add(i, rule);
+        }
+        index
+    }
+    /// Index the left-hand side of `rule` at position `idx`, shifting the positions of the
+    /// [`Rule`]s at `idx` and after up by one, as `Vec::insert` does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx > len`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, RuleIndex, parse_rule, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig, "F(A x_) = A; G(x_) = C;").expect("parse of TRS");
+    /// let mut index = RuleIndex::new(trs.rules());
+    ///
+    /// let rule = parse_rule(&mut sig, "F(x_ B) = B").expect("parse of F(x_ B) = B");
+    /// index.insert(0, &rule);
+    ///
+    /// let term = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    ///
+    /// assert_eq!(index.len(), 3);
+    /// assert_eq!(index.candidates(&term), vec![0, 1]);
+    /// ```
+    pub fn insert(&mut self, idx: usize, rule: &Rule) {
+        assert!(idx <= self.rules, "insertion index out of bounds");
+        for node in &mut self.nodes {
+            for r in &mut node.rules {
+                if *r >= idx {
+                    *r += 1;
+                }
+            }
+        }
+        self.add(idx, rule);
+        self.rules += 1;
+    }
+    /// Stop indexing the [`Rule`] at position `idx`, shifting the positions of the [`Rule`]s
+    /// after it down by one, as `Vec::remove` does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx >= len`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, RuleIndex, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig, "F(A x_) = A; G(x_) = C; F(x_ B) = B;").expect("parse of TRS");
+    /// let mut index = RuleIndex::new(trs.rules());
+    ///
+    /// index.remove(0);
+    ///
+    /// let term = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    ///
+    /// assert_eq!(index.len(), 2);
+    /// assert_eq!(index.candidates(&term), vec![1]);
+    /// ```
+    pub fn remove(&mut self, idx: usize) {
+        assert!(idx < self.rules, "removal index out of bounds");
+        for node in &mut self.nodes {
+            node.rules.retain(|&r| r != idx);
+            for r in &mut node.rules {
+                if *r > idx {
+                    *r -= 1;
+                }
+            }
+        }
+        self.rules -= 1;
+    }
+    // Add the left-hand side of `rule` at position `idx`.
+    fn add(&mut self, idx: usize, rule: &Rule) {
+        let mut node = 0;
+        let mut pending = vec![&rule.lhs];
+        while let Some(t) = pending.pop() {
+            node = self.child(node, t);
+            if let Term::Application { ref args, .. } = *t {
+                pending.extend(args.iter().rev());
+            }
+        }
+        self.nodes[node].rules.push(idx);
+    }
+    // The child of `node` for the head of `t`, creating it if need be.
+    fn child(&mut self, node: usize, t: &Term) -> usize {
+        let next = self.nodes.len();
+        let child = match *t {
+            Term::Variable(_) => *self.nodes[node].variable.get_or_insert(next),
+            Term::Application { ref op, .. } => {
+                *self.nodes[node].operators.entry(op.id).or_insert(next)
+            }
+        };
+        if child == next {
+            self.nodes.push(IndexNode::default());
+        }
+        child
+    }
+    /// The number of [`Rule`]s indexed.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn len(&self) -> usize {
+        self.rules
+    }
+    /// Are no [`Rule`]s indexed?
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn is_empty(&self) -> bool {
+        self.rules == 0
+    }
+    /// The positions of the [`Rule`]s which might match `term`, in increasing order.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn candidates(&self, term: &Term) -> Vec<usize> {
+        let mut found = vec![];
+        self.retrieve(0, &mut vec![term], &mut found);
+        found.sort_unstable();
+        found
+    }
+    // Add the rules below `node` consistent with `pending`, the subterms still to visit in
+    // reverse order, leaving `pending` as it was.
+    fn retrieve(&self, node: usize, pending: &mut Vec<&Term>, found: &mut Vec<usize>) {
+        let node = &self.nodes[node];
+        match pending.pop() {
+            None => found.extend(&node.rules),
+            Some(t) => {
+                if let Some(child) = node.variable {
+                    self.retrieve(child, pending, found);
+                }
+                if let Term::Application { ref op, ref args } = *t {
+                    if let Some(&child) = node.operators.get(&op.id) {
+                        let len = pending.len();
+                        pending.extend(args.iter().rev());
+                        self.retrieve(child, pending, found);
+                        pending.truncate(len);
+                    }
+                }
+                pending.push(t);
+            }
+        }
+    }
+}
+
+// The index of a TRS, which keeps it in sync with its rules. It is a function of the rules,
+// so it is ignored by comparison and hashing.
+#[derive(Clone)]
+pub(crate) struct TrsIndex {
+    tree: RuleIndex,
+    // the positions of the rules, in increasing order, by the root of their left-hand sides
+    roots: HashMap<Operator, Vec<usize>>,
+}
+impl TrsIndex {
+    pub(crate) fn new(rules: &[Rule]) -> TrsIndex {
+        let mut roots: HashMap<Operator, Vec<usize>> = HashMap::new();
+        for (i, rule) in rules.iter().enumerate() {
+            if let Term::Application { op, .. } = rule.lhs {
+                roots.entry(op).or_default().push(i);
+            }
+        }
+        TrsIndex {
+            tree: RuleIndex::new(rules),
+            roots,
+        }
+    }
+    pub(crate) fn insert(&mut self, idx: usize, rule: &Rule) {
+        self.tree.insert(idx, rule);
+        for r in self.roots.values_mut().flat_map(|rules| rules.iter_mut()) {
+            if *r >= idx {
+                *r += 1;
+            }
+        }
+        if let Term::Application { op, .. } = rule.lhs {
+            let rules = self.roots.entry(op).or_default();
+            let pos = rules.binary_search(&idx).unwrap_or_else(|pos| pos);
+            rules.insert(pos, idx);
+        }
+    }
+    pub(crate) fn remove(&mut self, idx: usize) {
+        self.tree.remove(idx);
+        for rules in self.roots.values_mut() {
+            rules.retain(|&r| r != idx);
+            for r in rules.iter_mut() {
+                if *r > idx {
+                    *r -= 1;
+                }
+            }
+        }
+        self.roots.retain(|_, rules| !rules.is_empty());
+    }
+    // The positions of the rules whose left-hand sides have `op` at the root.
+    pub(crate) fn with_root(&self, op: &Operator) -> &[usize] {
+        self.roots.get(op).map_or(&[], Vec::as_slice)
+    }
+    // The positions of the rules which might match `term`, skipping the tree when no left-hand
+    // side has the same root.
+    pub(crate) fn candidates(&self, term: &Term) -> Vec<usize> {
+        match *term {
+            Term::Application { ref op, .. } if self.roots.contains_key(op) => {
+                self.tree.candidates(term)
+            }
+            _ => vec![],
+        }
+    }
+}
+impl fmt::Debug for TrsIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TrsIndex")
+    }
+}
+impl PartialEq for TrsIndex {
+    fn eq(&self, _: &TrsIndex) -> bool {
+        true
+    }
+}
+impl Eq for TrsIndex {}
+impl Hash for TrsIndex {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::{Signature, TRS};
+    use super::*;
+
+    #[test]
+    fn candidates_test() {
+        let mut sig = Signature::default();
+        let trs = parse_trs(
+            &mut sig,
+            "F(A x_) = A; F(x_ x_) = B; G(x_) = C; F(B y_) = D; H(x_ y_) = x_; F(G(A) A) = E;",
+        )
+        .expect("parse of TRS");
+        let index = RuleIndex::new(&trs.rules);
+
+        assert_eq!(index.len(), 6);
+        for (input, expected) in [
+            ("F(A B)", vec![0, 1]),
+            ("F(B A)", vec![1, 3]),
+            ("F(G(A) A)", vec![1, 5]),
+            ("F(G(B) A)", vec![1]),
+            ("G(F(A A))", vec![2]),
+            ("H(A y_)", vec![4]),
+            ("y_", vec![]),
+        ] {
+            let term = parse_term(&mut sig, input).expect("parse of input");
+            assert_eq!(
+                index.candidates(&term),
+                expected,
+                "candidates for {}",
+                input
+            );
+            let matches: Vec<_> = trs
+                .rules
+                .iter()
+                .enumerate()
+                .filter(|(_, rule)| Term::pmatch(vec![(&rule.lhs, &term)]).is_some())
+                .map(|(i, _)| i)
+                .collect();
+            assert!(matches.iter().all(|i| expected.contains(i)));
+        }
+        assert!(RuleIndex::new(&TRS::new(vec![]).rules).is_empty());
+    }
+
+    #[test]
+    fn insert_remove_test() {
+        let mut sig = Signature::default();
+        let mut trs = parse_trs(
+            &mut sig,
+            "F(A x_) = A; F(x_ x_) = B; G(x_) = C; F(B y_) = D;",
+        )
+        .expect("parse of TRS");
+        let mut index = RuleIndex::new(&trs.rules);
+
+        let rule = trs.rules.remove(1);
+        index.remove(1);
+        trs.rules.insert(3, rule.clone());
+        index.insert(3, &rule);
+        trs.rules.insert(0, rule.clone());
+        index.insert(0, &rule);
+        trs.rules.remove(4);
+        index.remove(4);
+
+        let fresh = RuleIndex::new(&trs.rules);
+        assert_eq!(index.len(), fresh.len());
+        for input in &["F(A B)", "F(B A)", "F(B B)", "G(A)", "y_"] {
+            let term = parse_term(&mut sig, input).expect("parse of input");
+            assert_eq!(
+                index.candidates(&term),
+                fresh.candidates(&term),
+                "candidates for {}",
+                input
+            );
+        }
+    }
+}
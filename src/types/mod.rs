@@ -1,14 +1,50 @@
+mod ac;
+mod arena;
 mod atom;
+mod debruijn;
+mod diff;
+mod distance;
+mod editor;
+mod enumeration;
+mod index;
+mod persistent;
+mod position;
+mod prior;
 mod rule;
+mod sampling;
+mod second_order;
+mod shared;
 mod signature;
+mod sorts;
+mod stats;
+mod substitution;
 mod term;
 mod trs;
+mod zipper;
 
+pub use self::ac::*;
+pub use self::arena::*;
 pub use self::atom::*;
+pub use self::debruijn::*;
+pub use self::diff::*;
+pub use self::distance::*;
+pub use self::editor::*;
+pub use self::enumeration::*;
+pub use self::index::RuleIndex;
+pub use self::persistent::*;
+pub use self::position::*;
+pub use self::prior::*;
 pub use self::rule::*;
+pub use self::sampling::*;
+pub use self::second_order::*;
+pub use self::shared::*;
 pub use self::signature::*;
+pub use self::sorts::*;
+pub use self::stats::*;
+pub use self::substitution::*;
 pub use self::term::*;
 pub use self::trs::*;
+pub use self::zipper::*;
 
 /// Represents a place in a [`Term`].
 ///
@@ -0,0 +1,249 @@
+use super::{ConditionConfig, Rule, Signature, TRSError, Term, TRS};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// A [`TRS`] whose [`Rule`]s are shared between clones.
+///
+/// Cloning a `PersistentTRS` copies a single pointer, and its edits are copy-on-write: they
+/// copy the list of [`Rule`] pointers and at most the one [`Rule`] being changed, leaving
+/// every other clone untouched. This suits searches that propose many small edits to a
+/// [`TRS`], keeping each proposal as a `PersistentTRS` and converting it with
+/// [`PersistentTRS::to_trs`] only to rewrite with it.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Rule`]: struct.Rule.html
+/// [`PersistentTRS::to_trs`]: struct.PersistentTRS.html#method.to_trs
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, PersistentTRS, parse_trs, parse_rule};
+/// let mut sig = Signature::default();
+/// let t = parse_trs(&mut sig, "A = B; C = D; E(x_) = x_;").expect("parse of TRS");
+///
+/// let original = PersistentTRS::from(t);
+/// let mut proposal = original.clone();
+/// let rule = parse_rule(&mut sig, "C = A").expect("parse of C = A");
+/// proposal.replace_idx(1, rule).expect("replacing C = D");
+///
+/// assert_eq!(original.display(&sig), "A = B;\nC = D;\nE(x_) = x_;");
+/// assert_eq!(proposal.display(&sig), "A = B;\nC = A;\nE(x_) = x_;");
+/// assert_eq!(proposal.shared_rules(&original), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PersistentTRS {
+    rules: Arc<Vec<Arc<Rule>>>,
+    is_deterministic: bool,
+    condition_config: ConditionConfig,
+}
+impl PersistentTRS {
+    /// The [`TRS`] with the same [`Rule`]s and settings, copying each [`Rule`].
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`Rule`]: struct.Rule.html
+    pub fn to_trs(&self) -> TRS {
+        let mut trs = TRS::new(self.rules.iter().map(|rule| (**rule).clone()).collect());
+        trs.is_deterministic = self.is_deterministic;
+        trs.condition_config = self.condition_config;
+        trs
+    }
+    /// The number of [`Rule`]s in the `PersistentTRS`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+    /// Are there any [`Rule`]s in the `PersistentTRS`?
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+    /// Does the `PersistentTRS` forbid [`Rule`]s with multiple clauses, as in
+    /// [`TRS::is_deterministic`]?
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::is_deterministic`]: struct.TRS.html#method.is_deterministic
+    pub fn is_deterministic(&self) -> bool {
+        self.is_deterministic
+    }
+    /// The [`Rule`]s of the `PersistentTRS`, in order.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn rules(&self) -> impl Iterator<Item = &Rule> {
+        self.rules.iter().map(|rule| &**rule)
+    }
+    /// The [`Rule`] at index `idx`, if any.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn get_idx(&self, idx: usize) -> Option<&Rule> {
+        self.rules.get(idx).map(|rule| &**rule)
+    }
+    /// The [`Rule`] at index `idx`, if any, copied first if it is shared.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn get_idx_mut(&mut self, idx: usize) -> Option<&mut Rule> {
+        if idx < self.rules.len() {
+            Some(Arc::make_mut(&mut Arc::make_mut(&mut self.rules)[idx]))
+        } else {
+            None
+        }
+    }
+    /// Insert a [`Rule`] at index `idx`, failing as in [`TRS::insert_idx`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::insert_idx`]: struct.TRS.html#method.insert_idx
+    pub fn insert_idx(&mut self, idx: usize, rule: Rule) -> Result<&mut PersistentTRS, TRSError> {
+        if self.is_deterministic && rule.len() > 1 {
+            return Err(TRSError::NondeterministicRule);
+        } else if idx > self.rules.len() {
+            return Err(TRSError::InvalidIndex(idx, self.rules.len()));
+        } else if self
+            .rules()
+            .any(|r| Term::alpha(&rule.lhs, &r.lhs).is_some())
+        {
+            return Err(TRSError::AlreadyInTRS);
+        }
+        Arc::make_mut(&mut self.rules).insert(idx, Arc::new(rule));
+        Ok(self)
+    }
+    /// Remove and return the [`Rule`] at index `idx`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn remove_idx(&mut self, idx: usize) -> Result<Rule, TRSError> {
+        if idx < self.rules.len() {
+            let rule = Arc::make_mut(&mut self.rules).remove(idx);
+            Ok(Arc::try_unwrap(rule).unwrap_or_else(|rule| (*rule).clone()))
+        } else {
+            Err(TRSError::InvalidIndex(idx, self.rules.len()))
+        }
+    }
+    /// Replace the [`Rule`] at index `idx`, returning the old one. Fails as in
+    /// [`PersistentTRS::insert_idx`], ignoring the [`Rule`] being replaced.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`PersistentTRS::insert_idx`]: struct.PersistentTRS.html#method.insert_idx
+    pub fn replace_idx(&mut self, idx: usize, rule: Rule) -> Result<Rule, TRSError> {
+        if idx >= self.rules.len() {
+            return Err(TRSError::InvalidIndex(idx, self.rules.len()));
+        } else if self.is_deterministic && rule.len() > 1 {
+            return Err(TRSError::NondeterministicRule);
+        } else if self
+            .rules()
+            .enumerate()
+            .any(|(i, r)| i != idx && Term::alpha(&rule.lhs, &r.lhs).is_some())
+        {
+            return Err(TRSError::AlreadyInTRS);
+        }
+        let old = std::mem::replace(&mut Arc::make_mut(&mut self.rules)[idx], Arc::new(rule));
+        Ok(Arc::try_unwrap(old).unwrap_or_else(|rule| (*rule).clone()))
+    }
+    /// Move the [`Rule`] at index `i` to index `j`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn move_rule(&mut self, i: usize, j: usize) -> Result<&mut PersistentTRS, TRSError> {
+        let len = self.rules.len();
+        if i >= len {
+            return Err(TRSError::InvalidIndex(i, len));
+        } else if j >= len {
+            return Err(TRSError::InvalidIndex(j, len));
+        }
+        if i != j {
+            let rules = Arc::make_mut(&mut self.rules);
+            let rule = rules.remove(i);
+            rules.insert(j, rule);
+        }
+        Ok(self)
+    }
+    /// The number of [`Rule`]s of the `PersistentTRS` stored in the same memory as some
+    /// [`Rule`] of `other`, as left by cloning and editing.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn shared_rules(&self, other: &PersistentTRS) -> usize {
+        let others: HashSet<*const Rule> =
+            other.rules.iter().map(|r| &**r as *const Rule).collect();
+        self.rules
+            .iter()
+            .filter(|r| others.contains(&(&***r as *const Rule)))
+            .count()
+    }
+    /// Serialize the `PersistentTRS`, as in [`TRS::display`].
+    ///
+    /// [`TRS::display`]: struct.TRS.html#method.display
+    pub fn display(&self, sig: &Signature) -> String {
+        let sig = sig.sig.read().expect("poisoned signature");
+        let mut out = String::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            rule.display_into(&sig, &mut out);
+            out.push(';');
+        }
+        out
+    }
+}
+impl From<TRS> for PersistentTRS {
+    fn from(trs: TRS) -> PersistentTRS {
+        PersistentTRS {
+            rules: Arc::new(trs.rules.into_iter().map(Arc::new).collect()),
+            is_deterministic: trs.is_deterministic,
+            condition_config: trs.condition_config,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn persistent_trs_test() {
+        let mut sig = Signature::default();
+        let t = parse_trs(&mut sig, "A = B; C = D | E; F(x_) = x_;").expect("parse of TRS");
+        let original = PersistentTRS::from(t.clone());
+        assert_eq!(original.to_trs(), t);
+        assert_eq!(original.len(), 3);
+        assert_eq!(original.shared_rules(&original.clone()), 3);
+
+        let mut p = original.clone();
+        p.move_rule(0, 2).expect("moving A = B");
+        assert_eq!(p.display(&sig), "C = D | E;\nF(x_) = x_;\nA = B;");
+        assert_eq!(p.shared_rules(&original), 3);
+
+        let removed = p.remove_idx(0).expect("removing C = D | E");
+        assert_eq!(removed.display(&sig), "C = D | E");
+        assert_eq!(p.remove_idx(5), Err(TRSError::InvalidIndex(5, 2)));
+
+        let rule = parse_rule(&mut sig, "F(y_) = A").expect("parse of F(y_) = A");
+        assert_eq!(
+            p.insert_idx(0, rule.clone()).err(),
+            Some(TRSError::AlreadyInTRS)
+        );
+        p.replace_idx(0, rule).expect("replacing F(x_) = x_");
+        let g = parse_term(&mut sig, "G").expect("parse of G");
+        p.get_idx_mut(1).expect("A = B").rhs.push(g);
+        assert_eq!(p.shared_rules(&original), 0);
+        assert_eq!(original.display(&sig), "A = B;\nC = D | E;\nF(x_) = x_;");
+        assert_eq!(
+            p.get_idx(0).map(|r| r.display(&sig)),
+            Some("F(y_) = A".to_string())
+        );
+        assert!(p.get_idx(2).is_none());
+
+        let mut d = t.clone();
+        d.make_deterministic(&mut StdRng::from_seed([0; 32]));
+        let mut d = PersistentTRS::from(d);
+        assert!(d.is_deterministic());
+        let rule = parse_rule(&mut sig, "G = A | B").expect("parse of G = A | B");
+        assert_eq!(
+            d.insert_idx(0, rule).err(),
+            Some(TRSError::NondeterministicRule)
+        );
+        assert!(d.to_trs().is_deterministic());
+    }
+}
@@ -0,0 +1,234 @@
+use super::Place;
+use itertools::Itertools;
+use std::num::ParseIntError;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A path from the root of a [`Term`] or [`Context`] to one of its subterms: the sequence of
+/// argument indices to follow, starting from the root.
+///
+/// A `Position` dereferences to `[usize]`, so it can be used anywhere a [`Place`] is accepted.
+///
+/// [`Term`]: enum.Term.html
+/// [`Context`]: enum.Context.html
+/// [`Place`]: type.Place.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Position, Signature, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term(&mut sig, "F(A G(B C))").expect("parse of F(A G(B C))");
+/// let p = Position::from(vec![1, 0]);
+///
+/// assert_eq!(p.display(), "1.0");
+/// assert_eq!(t.at(&p).map(|t| t.display(&sig)), Some("B".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Position(Vec<usize>);
+impl Position {
+    /// The `Position` of the root, `ε`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::root();
+    ///
+    /// assert!(p.is_root());
+    /// assert_eq!(p.display(), "ε");
+    /// ```
+    pub fn root() -> Position {
+        Position(vec![])
+    }
+    /// Is the `Position` the root?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// assert!(Position::root().is_root());
+    /// assert!(!Position::from(vec![0]).is_root());
+    /// ```
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// The number of steps from the root to the `Position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// assert_eq!(Position::root().depth(), 0);
+    /// assert_eq!(Position::from(vec![1, 2, 0]).depth(), 3);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.0.len()
+    }
+    /// The `Position` immediately above this one, or `None` for the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![1, 2]);
+    ///
+    /// assert_eq!(p.parent(), Some(Position::from(vec![1])));
+    /// assert_eq!(Position::root().parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<Position> {
+        if self.is_root() {
+            None
+        } else {
+            Some(Position(self.0[..self.0.len() - 1].to_vec()))
+        }
+    }
+    /// The `Position` of the `i`th argument of the subterm at this `Position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![1]);
+    ///
+    /// assert_eq!(p.child(2), Position::from(vec![1, 2]));
+    /// ```
+    pub fn child(&self, i: usize) -> Position {
+        let mut steps = self.0.clone();
+        steps.push(i);
+        Position(steps)
+    }
+    /// The `Position` reached by following `other` from this `Position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![1]);
+    /// let q = Position::from(vec![0, 2]);
+    ///
+    /// assert_eq!(p.concat(&q), Position::from(vec![1, 0, 2]));
+    /// ```
+    pub fn concat(&self, other: &Position) -> Position {
+        let mut steps = self.0.clone();
+        steps.extend_from_slice(&other.0);
+        Position(steps)
+    }
+    /// Is this `Position` a (not necessarily proper) prefix of `other`, i.e. is `other` at or
+    /// below this `Position`?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![1]);
+    ///
+    /// assert!(p.is_prefix_of(&Position::from(vec![1, 0])));
+    /// assert!(p.is_prefix_of(&p));
+    /// assert!(!p.is_prefix_of(&Position::from(vec![0, 1])));
+    /// ```
+    pub fn is_prefix_of(&self, other: &Position) -> bool {
+        other.0.starts_with(&self.0)
+    }
+    /// Are the two `Position`s parallel, i.e. is neither a prefix of the other?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![0, 1]);
+    ///
+    /// assert!(p.is_parallel_to(&Position::from(vec![1])));
+    /// assert!(!p.is_parallel_to(&Position::from(vec![0])));
+    /// ```
+    pub fn is_parallel_to(&self, other: &Position) -> bool {
+        !self.is_prefix_of(other) && !other.is_prefix_of(self)
+    }
+    /// The `Position` which leads from `prefix` to this `Position`, if `prefix` is a prefix of
+    /// this `Position`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// let p = Position::from(vec![1, 0, 2]);
+    ///
+    /// assert_eq!(p.strip_prefix(&Position::from(vec![1])), Some(Position::from(vec![0, 2])));
+    /// assert_eq!(p.strip_prefix(&Position::from(vec![0])), None);
+    /// ```
+    pub fn strip_prefix(&self, prefix: &Position) -> Option<Position> {
+        if prefix.is_prefix_of(self) {
+            Some(Position(self.0[prefix.0.len()..].to_vec()))
+        } else {
+            None
+        }
+    }
+    /// Serialize a `Position` as its argument indices separated by `.`, or `ε` for the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Position;
+    /// assert_eq!(Position::from(vec![1, 2, 0]).display(), "1.2.0");
+    /// assert_eq!(Position::root().display(), "ε");
+    /// ```
+    pub fn display(&self) -> String {
+        if self.is_root() {
+            "ε".to_string()
+        } else {
+            self.0.iter().join(".")
+        }
+    }
+}
+impl Deref for Position {
+    type Target = [usize];
+    fn deref(&self) -> &[usize] {
+        &self.0
+    }
+}
+impl From<Place> for Position {
+    fn from(place: Place) -> Position {
+        Position(place)
+    }
+}
+impl From<Position> for Place {
+    fn from(position: Position) -> Place {
+        position.0
+    }
+}
+impl FromStr for Position {
+    type Err = ParseIntError;
+    /// Parse a `Position` in the format produced by [`Position::display`].
+    ///
+    /// [`Position::display`]: struct.Position.html#method.display
+    fn from_str(s: &str) -> Result<Position, ParseIntError> {
+        let s = s.trim();
+        if s == "ε" || s.is_empty() {
+            Ok(Position::root())
+        } else {
+            s.split('.')
+                .map(str::parse)
+                .collect::<Result<Vec<usize>, _>>()
+                .map(Position)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_test() {
+        assert_eq!("1.2.0".parse(), Ok(Position::from(vec![1, 2, 0])));
+        assert_eq!("ε".parse(), Ok(Position::root()));
+        assert_eq!("".parse(), Ok(Position::root()));
+        assert!("1..2".parse::<Position>().is_err());
+        assert!("a".parse::<Position>().is_err());
+
+        let p = Position::from(vec![3, 0, 1]);
+
+        assert_eq!(p.display().parse(), Ok(p));
+    }
+}
@@ -0,0 +1,231 @@
+use std::f64;
+
+use super::{Atom, Rule, Term, TermPcfg, Variable, TRS};
+
+/// A generative model of [`TRS`]s, giving the prior probabilities of [`TRS::log_prior`] and
+/// [`Rule::log_prior`].
+///
+/// A [`TRS`] is a sequence of [`Rule`]s, each added with probability `p_rule`. Each [`Rule`]
+/// has a lhs, some conditions, each added with probability `p_condition`, and at least one rhs,
+/// each further rhs added with probability `p_clause`. Every [`Term`] is drawn from `pcfg`,
+/// except that the lhs is not a [`Variable`] and [`Variable`]s are identified only up to
+/// renaming: the [`Variable`]s of `pcfg` together give the probability of a [`Variable`] node,
+/// which is then either one of the [`Variable`]s already in the [`Rule`] or a new one, chosen
+/// uniformly. New [`Variable`]s may appear in the lhs and conditions, but not the rhs.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`TRS::log_prior`]: struct.TRS.html#method.log_prior
+/// [`Rule`]: struct.Rule.html
+/// [`Rule::log_prior`]: struct.Rule.html#method.log_prior
+/// [`Term`]: enum.Term.html
+/// [`Variable`]: struct.Variable.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrsPrior {
+    /// The distribution of each node of a [`Term`].
+    ///
+    /// [`Term`]: enum.Term.html
+    pub pcfg: TermPcfg,
+    /// The probability of adding each further [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub p_rule: f64,
+    /// The probability of adding each further rhs to a [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub p_clause: f64,
+    /// The probability of adding each further condition to a [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub p_condition: f64,
+}
+impl TrsPrior {
+    /// Create a `TrsPrior` drawing [`Term`]s from `pcfg`, adding each further [`Rule`] and rhs
+    /// with probability `0.5`, and never adding conditions.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`Rule`]: struct.Rule.html
+    pub fn new(pcfg: TermPcfg) -> TrsPrior {
+        TrsPrior {
+            pcfg,
+            p_rule: 0.5,
+            p_clause: 0.5,
+            p_condition: 0.0,
+        }
+    }
+    // The log probability of `term`, with new Variables added to `seen` if `fresh` and
+    // forbidden otherwise.
+    fn log_prob<'a>(&self, term: &'a Term, seen: &mut Vec<&'a Variable>, fresh: bool) -> f64 {
+        let p_variable = self.pcfg.variable_probability();
+        let mut lp = 0.0;
+        let mut stack = vec![term];
+        while let Some(t) = stack.pop() {
+            match *t {
+                Term::Variable(ref v) => {
+                    let choices = seen.len() + if fresh { 1 } else { 0 };
+                    if !seen.contains(&v) {
+                        if !fresh {
+                            return f64::NEG_INFINITY;
+                        }
+                        seen.push(v);
+                    }
+                    lp += p_variable.ln() - (choices as f64).ln();
+                }
+                Term::Application { ref op, ref args } => {
+                    lp += self.pcfg.probability(&Atom::Operator(*op)).ln();
+                    stack.extend(args.iter().rev());
+                }
+            }
+        }
+        lp
+    }
+}
+
+// The log probability of adding `n` further items, each with probability `p`.
+fn geometric(p: f64, n: usize) -> f64 {
+    let stop = (1.0 - p).ln();
+    if n == 0 {
+        stop
+    } else {
+        p.ln() * n as f64 + stop
+    }
+}
+
+impl Rule {
+    /// The natural log of the probability of the `Rule` under `prior`. Clause weights are
+    /// ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Atom, Signature, TermPcfg, TrsPrior, parse_rule};
+    /// let mut sig = Signature::default();
+    /// let z = sig.new_op(0, Some("Z".to_string()));
+    /// let s = sig.new_op(1, Some("S".to_string()));
+    /// let x = sig.new_var(Some("x".to_string()));
+    /// let weights = vec![(Atom::from(z), 1.0), (Atom::from(s), 1.0), (Atom::from(x), 2.0)];
+    /// let prior = TrsPrior::new(TermPcfg::new(weights).expect("a pcfg"));
+    ///
+    /// // S at the root, a new Variable, one rhs, and the Variable again
+    /// let rule = parse_rule(&mut sig, "S(x_) = x_").expect("parse of S(x_) = x_");
+    /// assert!((rule.log_prior(&prior) - 0.0625f64.ln()).abs() < 1e-9);
+    ///
+    /// // a second rhs, then Z
+    /// let rule = parse_rule(&mut sig, "S(x_) = x_ | Z").expect("parse of S(x_) = x_ | Z");
+    /// assert!((rule.log_prior(&prior) - (0.0625f64 * 0.5 * 0.25).ln()).abs() < 1e-9);
+    /// ```
+    pub fn log_prior(&self, prior: &TrsPrior) -> f64 {
+        let mut seen = vec![];
+        let mut lp = prior.log_prob(&self.lhs, &mut seen, true)
+            - (1.0 - prior.pcfg.variable_probability()).ln();
+        lp += geometric(prior.p_condition, self.conditions.len());
+        for (s, t) in &self.conditions {
+            lp += prior.log_prob(s, &mut seen, true) + prior.log_prob(t, &mut seen, true);
+        }
+        lp += geometric(prior.p_clause, self.rhs.len().saturating_sub(1));
+        for rhs in &self.rhs {
+            lp += prior.log_prob(rhs, &mut seen, false);
+        }
+        lp
+    }
+}
+
+impl TRS {
+    /// The natural log of the probability of the `TRS` under `prior`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Atom, Signature, TermPcfg, TrsPrior, parse_trs};
+    /// let mut sig = Signature::default();
+    /// let z = sig.new_op(0, Some("Z".to_string()));
+    /// let s = sig.new_op(1, Some("S".to_string()));
+    /// let x = sig.new_var(Some("x".to_string()));
+    /// let weights = vec![(Atom::from(z), 1.0), (Atom::from(s), 1.0), (Atom::from(x), 2.0)];
+    /// let prior = TrsPrior::new(TermPcfg::new(weights).expect("a pcfg"));
+    ///
+    /// let trs = parse_trs(&mut sig, "S(x_) = x_;").expect("parse of S(x_) = x_;");
+    /// assert!((trs.log_prior(&prior) - 0.015625f64.ln()).abs() < 1e-9);
+    ///
+    /// let bigger = parse_trs(&mut sig, "S(x_) = x_; S(Z) = Z;").expect("parse of TRS");
+    /// assert!(bigger.log_prior(&prior) < trs.log_prior(&prior));
+    /// ```
+    pub fn log_prior(&self, prior: &TrsPrior) -> f64 {
+        self.rules
+            .iter()
+            .fold(geometric(prior.p_rule, self.rules.len()), |lp, rule| {
+                lp + rule.log_prior(prior)
+            })
+    }
+    /// The number of bits needed to describe the `TRS` under `prior`: its negative log prior in
+    /// base 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Atom, Signature, TermPcfg, TrsPrior, parse_trs};
+    /// let mut sig = Signature::default();
+    /// let z = sig.new_op(0, Some("Z".to_string()));
+    /// let s = sig.new_op(1, Some("S".to_string()));
+    /// let x = sig.new_var(Some("x".to_string()));
+    /// let weights = vec![(Atom::from(z), 1.0), (Atom::from(s), 1.0), (Atom::from(x), 2.0)];
+    /// let prior = TrsPrior::new(TermPcfg::new(weights).expect("a pcfg"));
+    ///
+    /// let trs = parse_trs(&mut sig, "S(x_) = x_;").expect("parse of S(x_) = x_;");
+    /// assert!((trs.description_length(&prior) - 6.0).abs() < 1e-9);
+    /// ```
+    pub fn description_length(&self, prior: &TrsPrior) -> f64 {
+        -self.log_prior(prior) / f64::consts::LN_2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn log_prior_test() {
+        let mut sig = Signature::default();
+        let a = sig.new_op(0, Some("A".to_string()));
+        let f = sig.new_op(2, Some("F".to_string()));
+        let x = sig.new_var(Some("x".to_string()));
+        let y = sig.new_var(Some("y".to_string()));
+        let weights = vec![
+            (Atom::from(a), 1.0),
+            (Atom::from(f), 1.0),
+            (Atom::from(x), 1.0),
+            (Atom::from(y), 1.0),
+        ];
+        let mut prior = TrsPrior::new(TermPcfg::new(weights).expect("a pcfg"));
+        let lp = |prior: &TrsPrior, s: &str| {
+            let mut sig = sig.clone();
+            let trs = parse_trs(&mut sig, s).expect("parse of TRS");
+            trs.log_prior(prior)
+        };
+
+        assert_eq!(lp(&prior, ""), 0.5f64.ln());
+        // one Rule, F at the root among the Operators, a new Variable, then either x_ again or
+        // a new y_, one rhs, and then either of two Variables
+        let expected: f64 = 0.25 * 0.5 * 0.5 * 0.25 * 0.5 * 0.25;
+        assert!((lp(&prior, "F(x_ y_) = y_;") - expected.ln()).abs() < 1e-9);
+        assert!((lp(&prior, "F(x_ y_) = x_;") - expected.ln()).abs() < 1e-9);
+        // the rhs may only be x_
+        let expected = expected * 2.0;
+        assert!((lp(&prior, "F(x_ x_) = x_;") - expected.ln()).abs() < 1e-9);
+        // renaming Variables changes nothing
+        assert_eq!(lp(&prior, "F(x_ A) = x_;"), lp(&prior, "F(y_ A) = y_;"));
+        // a second rhs is added with probability 0.5
+        let diff = lp(&prior, "F(x_ A) = x_ | A;") - lp(&prior, "F(x_ A) = x_;");
+        assert!((diff - (0.5f64 * 0.25).ln()).abs() < 1e-9);
+
+        // y_ is new in the rhs unless a condition introduces it
+        let mut rule = parse_rule(&mut sig.clone(), "F(x_ A) = A").expect("parse of rule");
+        rule.rhs = vec![Term::Variable(y)];
+        assert_eq!(rule.log_prior(&prior), f64::NEG_INFINITY);
+        rule.conditions = vec![(Term::Variable(y), rule.lhs.args()[1].clone())];
+        assert_eq!(rule.log_prior(&prior), f64::NEG_INFINITY);
+        prior.p_condition = 0.5;
+        assert!(rule.log_prior(&prior) > f64::NEG_INFINITY);
+    }
+}
@@ -1,6 +1,12 @@
-use super::{Context, Operator, Place, Term, Variable};
+use super::super::pretty::{PrettyConfig, Printer};
+use super::signature::Sig;
+use super::{
+    Context, OpMap, Operator, Place, Position, Signature, Substitution, Term, VarMap, Variable,
+};
 use itertools::Itertools;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::iter;
 
 /// A [`Rule`] with [`Hole`]s; a sort of [`Rule`] template.
@@ -55,7 +61,7 @@ impl RuleContext {
     ///
     /// let r = RuleContext::new(left, vec![b, c]).unwrap();
     ///
-    /// assert_eq!(r.pretty(), "A(B, C, [!]) = B [!] | C");
+    /// assert_eq!(r.pretty(&sig), "A(B, C, [!]) = B [!] | C");
     ///
     /// let left = parse_context(&mut sig, "A(B C [!])").expect("parse of A(B C [!])");
     /// let b = parse_context(&mut sig, "B [!] x_").expect("parse of B [!] x_");
@@ -98,12 +104,20 @@ impl RuleContext {
     /// let rule = parse_rulecontext(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
     ///
-    /// assert_eq!(rule.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = .([!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL))))");
+    /// assert_eq!(rule.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = .([!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL))))");
     /// ```
-    pub fn display(&self) -> String {
-        let lhs_str = self.lhs.display();
-        let rhs_str = self.rhs.iter().map(Context::display).join(" | ");
-        format!("{} = {}", lhs_str, rhs_str)
+    pub fn display(&self, sig: &Signature) -> String {
+        let sig = sig.sig.read().expect("poisoned signature");
+        let mut out = String::new();
+        self.lhs.display_into(&sig, &mut out);
+        out.push_str(" = ");
+        for (i, rhs) in self.rhs.iter().enumerate() {
+            if i > 0 {
+                out.push_str(" | ");
+            }
+            rhs.display_into(&sig, &mut out);
+        }
+        out
     }
     /// A human-readable serialization of the `RuleContext`.
     ///
@@ -116,12 +130,33 @@ impl RuleContext {
     /// let rule = parse_rulecontext(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
     ///
-    /// assert_eq!(rule.pretty(), "A B(x_) [2, 1, 0] 105 = [!] [A, B(x_), 2]");
+    /// assert_eq!(rule.pretty(&sig), "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105 = [!] [A, B(x_), SUCC(SUCC(ZERO))]");
     /// ```
-    pub fn pretty(&self) -> String {
-        let lhs_str = self.lhs.pretty();
-        let rhs_str = self.rhs.iter().map(Context::pretty).join(" | ");
-        format!("{} = {}", lhs_str, rhs_str)
+    pub fn pretty(&self, sig: &Signature) -> String {
+        self.pretty_with(sig, &PrettyConfig::default())
+    }
+    /// A human-readable serialization of the `RuleContext`, as configured by `config`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, PrettyConfig, parse_rulecontext};
+    /// let mut sig = Signature::default();
+    ///
+    /// let rule = parse_rulecontext(&mut sig, "F(x_ CONS(A NIL)) = [!] | G(x_)")
+    ///     .expect("parse of F(x_ CONS(A NIL)) = [!] | G(x_)");
+    ///
+    /// let config = PrettyConfig { max_depth: Some(0), ..PrettyConfig::default() };
+    /// assert_eq!(rule.pretty_with(&sig, &config), "F(…, …) = [!] | G(…)");
+    /// ```
+    pub fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
+        let mut printer = Printer::new(sig, config);
+        printer.item(&self.lhs);
+        for (i, rhs) in self.rhs.iter().enumerate() {
+            printer.text(if i == 0 { " = " } else { " | " });
+            printer.item(rhs);
+        }
+        printer.finish()
     }
     /// Get all the [`subcontexts`] and [`Place`]s in a `RuleContext`.
     ///
@@ -139,7 +174,7 @@ impl RuleContext {
     ///
     /// let subcontexts: Vec<String> = r.subcontexts()
     ///     .iter()
-    ///     .map(|(c, p)| format!("({}, {:?})", c.display(), p))
+    ///     .map(|(c, p)| format!("({}, {:?})", c.display(&sig), p))
     ///     .collect();
     ///
     /// assert_eq!(
@@ -209,12 +244,12 @@ impl RuleContext {
     /// let mut sig = Signature::default();
     ///
     /// let r = parse_rulecontext(&mut sig, "A(x_ [!]) = C(x_)").expect("parse of A(x_ [!]) = C(x_)");
-    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(r_variables, vec!["x_"]);
     ///
     /// let r = parse_rulecontext(&mut sig, "B(y_ z_) = C [!]").expect("parse of B(y_ z_) = C [!]");
-    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(r_variables, vec!["y_", "z_"]);
     /// ```
@@ -232,12 +267,12 @@ impl RuleContext {
     /// let mut sig = Signature::default();
     ///
     /// let r = parse_rulecontext(&mut sig, "A(D E) = C([!])").expect("parse of A(D E) = C([!])");
-    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
     ///
     /// assert_eq!(r_ops, vec!["D", "E", "A", "C"]);
     ///
     /// let r = parse_rulecontext(&mut sig, "B(F x_) = C [!]").expect("parse of B(F x_) = C [!]");
-    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
     ///
     /// assert_eq!(r_ops, vec!["F", "B", "C", "."]);
     /// ```
@@ -258,11 +293,11 @@ impl RuleContext {
     ///
     /// let r = parse_rulecontext(&mut sig, "A(x_ [!]) = B | C(x_ [!])").expect("parse of A(x_ [!]) = B | C(x_ [!])");
     ///
-    /// assert_eq!(r.at(&[0]).unwrap().display(), "A(x_ [!])");
-    /// assert_eq!(r.at(&[0,1]).unwrap().display(), "[!]");
-    /// assert_eq!(r.at(&[0,0]).unwrap().display(), "x_");
-    /// assert_eq!(r.at(&[1]).unwrap().display(), "B");
-    /// assert_eq!(r.at(&[2]).unwrap().display(), "C(x_ [!])");
+    /// assert_eq!(r.at(&[0]).unwrap().display(&sig), "A(x_ [!])");
+    /// assert_eq!(r.at(&[0,1]).unwrap().display(&sig), "[!]");
+    /// assert_eq!(r.at(&[0,0]).unwrap().display(&sig), "x_");
+    /// assert_eq!(r.at(&[1]).unwrap().display(&sig), "B");
+    /// assert_eq!(r.at(&[2]).unwrap().display(&sig), "C(x_ [!])");
     /// ```
     pub fn at(&self, p: &[usize]) -> Option<&Context> {
         if p[0] == 0 {
@@ -287,7 +322,7 @@ impl RuleContext {
     /// let new_r = r.replace(&[1], new_context);
     ///
     /// assert_ne!(r, new_r.clone().unwrap());
-    /// assert_eq!(new_r.unwrap().pretty(), "A(x_) = E [!] | C(x_) | [!]");
+    /// assert_eq!(new_r.unwrap().pretty(&sig), "A(x_) = E [!] | C(x_) | [!]");
     /// ```
     pub fn replace(&self, place: &[usize], subcontext: Context) -> Option<RuleContext> {
         if place[0] == 0 {
@@ -330,7 +365,7 @@ impl RuleContext {
     /// let r = parse_rulecontext(&mut sig, "A(x_) = B | C(x_)").expect("parse of A(x_) = B | C(x_)");
     /// let rule = r.to_rule().expect("converting RuleContext to Rule");
     ///
-    /// assert_eq!(rule.pretty(), "A(x_) = B | C(x_)");
+    /// assert_eq!(rule.pretty(&sig), "A(x_) = B | C(x_)");
     /// ```
     pub fn to_rule(&self) -> Result<Rule, ()> {
         let lhs = self.lhs.to_term()?;
@@ -351,9 +386,14 @@ impl From<Rule> for RuleContext {
 }
 
 /// A rewrite rule equating a left-hand-side [`Term`] with one or more
-/// right-hand-side [`Term`]s.
+/// right-hand-side [`Term`]s, optionally guarded by conditions.
+///
+/// A conditional `Rule` such as `F(x_) = A <= G(x_) = B` only applies where each of its
+/// conditions holds for the matching substitution; see [`TRS::set_condition_config`] for how
+/// conditions are evaluated.
 ///
 /// [`Term`]: enum.Term.html
+/// [`TRS::set_condition_config`]: struct.TRS.html#method.set_condition_config
 ///
 /// # Examples
 ///
@@ -381,12 +421,65 @@ impl From<Rule> for RuleContext {
 /// // Constructing a Rule using parser
 /// let r = parse_rule(&mut sig, "A(x_ y_) = B(x_) | C(y)").expect("parse of A(x_ y_) = B(x_) | C(y_)");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct Rule {
     /// The left hand side (lhs) of the Rule.
     pub lhs: Term,
     /// The right hand sides (rhs) of the Rule.
     pub rhs: Vec<Term>,
+    /// The conditions under which the Rule applies, as pairs of [`Term`]s.
+    ///
+    /// [`Term`]: enum.Term.html
+    pub conditions: Vec<(Term, Term)>,
+    /// The probability of each clause, if the Rule is weighted. Operations adding or removing
+    /// clauses discard the weights; use [`Rule::with_weights`] to set validated weights.
+    ///
+    /// [`Rule::with_weights`]: struct.Rule.html#method.with_weights
+    pub weights: Option<Vec<f64>>,
+}
+impl PartialEq for Rule {
+    fn eq(&self, other: &Rule) -> bool {
+        self.lhs == other.lhs
+            && self.rhs == other.rhs
+            && self.conditions == other.conditions
+            && self.weights == other.weights
+    }
+}
+// Weights are validated to be finite, so equality is reflexive.
+impl Eq for Rule {}
+impl Hash for Rule {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.lhs.hash(state);
+        self.rhs.hash(state);
+        self.conditions.hash(state);
+        self.weights
+            .as_ref()
+            .map(|ws| ws.iter().map(|w| w.to_bits()).collect::<Vec<_>>())
+            .hash(state);
+    }
+}
+// Order by LHS, then RHSs, then conditions, then weights, unweighted first.
+impl PartialOrd for Rule {
+    fn partial_cmp(&self, other: &Rule) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Rule {
+    fn cmp(&self, other: &Rule) -> Ordering {
+        let weights = |rule: &Rule| {
+            rule.weights.as_ref().map(|ws| {
+                ws.iter()
+                    .map(|&w| if w == 0.0 { 0.0 } else { w })
+                    .map(f64::to_bits)
+                    .collect::<Vec<_>>()
+            })
+        };
+        self.lhs
+            .cmp(&other.lhs)
+            .then_with(|| self.rhs.cmp(&other.rhs))
+            .then_with(|| self.conditions.cmp(&other.conditions))
+            .then_with(|| weights(self).cmp(&weights(other)))
+    }
 }
 impl Rule {
     /// Serialize a `Rule`.
@@ -400,12 +493,33 @@ impl Rule {
     /// let rule = parse_rule(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
     ///
-    /// assert_eq!(rule.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))");
+    /// assert_eq!(rule.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))");
     /// ```
-    pub fn display(&self) -> String {
-        let lhs_str = self.lhs.display();
-        let rhs_str = self.rhs.iter().map(Term::display).join(" | ");
-        format!("{} = {}", lhs_str, rhs_str)
+    pub fn display(&self, sig: &Signature) -> String {
+        let sig = sig.sig.read().expect("poisoned signature");
+        let mut out = String::new();
+        self.display_into(&sig, &mut out);
+        out
+    }
+    // Serialize `self` onto `out`.
+    pub(crate) fn display_into(&self, sig: &Sig, out: &mut String) {
+        self.lhs.display_into(sig, out);
+        out.push_str(" = ");
+        for (i, rhs) in self.rhs.iter().enumerate() {
+            if i > 0 {
+                out.push_str(" | ");
+            }
+            rhs.display_into(sig, out);
+            if let Some(ref weights) = self.weights {
+                out.push_str(&format!(" :: {}", weights[i]));
+            }
+        }
+        for (i, (c, d)) in self.conditions.iter().enumerate() {
+            out.push_str(if i == 0 { " <= " } else { ", " });
+            c.display_into(sig, out);
+            out.push_str(" = ");
+            d.display_into(sig, out);
+        }
     }
     /// A human-readable serialization of the `Rule`.
     ///
@@ -418,12 +532,49 @@ impl Rule {
     /// let rule = parse_rule(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
     ///
-    /// assert_eq!(rule.pretty(), "A B(x_) [2, 1, 0] 105 = [A, B(x_), 2]");
+    /// assert_eq!(rule.pretty(&sig), "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105 = [A, B(x_), SUCC(SUCC(ZERO))]");
     /// ```
-    pub fn pretty(&self) -> String {
-        let lhs_str = self.lhs.pretty();
-        let rhs_str = self.rhs.iter().map(Term::pretty).join(" | ");
-        format!("{} = {}", lhs_str, rhs_str)
+    pub fn pretty(&self, sig: &Signature) -> String {
+        self.pretty_with(sig, &PrettyConfig::default())
+    }
+    /// A human-readable serialization of the `Rule`, as configured by `config`.
+    ///
+    /// See [`PrettyConfig`] for more information.
+    ///
+    /// [`PrettyConfig`]: struct.PrettyConfig.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, PrettyConfig, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let rule = parse_rule(&mut sig, "REVERSE(CONS(x_ y_)) = APPEND(REVERSE(y_) CONS(x_ NIL))")
+    ///     .expect("parse of REVERSE(CONS(x_ y_)) = APPEND(REVERSE(y_) CONS(x_ NIL))");
+    ///
+    /// let config = PrettyConfig { width: Some(30), ..PrettyConfig::default() };
+    /// assert_eq!(
+    ///     rule.pretty_with(&sig, &config),
+    ///     "REVERSE(CONS(x_, y_)) = APPEND(\n  REVERSE(y_),\n  [x_]\n)",
+    /// );
+    /// ```
+    pub fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
+        let mut printer = Printer::new(sig, config);
+        printer.item(&self.lhs);
+        for (i, rhs) in self.rhs.iter().enumerate() {
+            printer.text(if i == 0 { " = " } else { " | " });
+            printer.item(rhs);
+            if let Some(ref weights) = self.weights {
+                printer.text(&format!(" :: {}", weights[i]));
+            }
+        }
+        for (i, (c, d)) in self.conditions.iter().enumerate() {
+            printer.text(if i == 0 { " <= " } else { ", " });
+            printer.item(c);
+            printer.text(" = ");
+            printer.item(d);
+        }
+        printer.finish()
     }
     /// The total number of subterms across all [`Term`]s in the `Rule`.
     ///
@@ -440,7 +591,13 @@ impl Rule {
     /// assert_eq!(r.size(), 5);
     /// ```
     pub fn size(&self) -> usize {
-        self.lhs.size() + self.rhs.iter().map(Term::size).sum::<usize>()
+        self.lhs.size()
+            + self.rhs.iter().map(Term::size).sum::<usize>()
+            + self
+                .conditions
+                .iter()
+                .map(|(c, d)| c.size() + d.size())
+                .sum::<usize>()
     }
     /// The number of RHSs in the `Rule`.
     ///
@@ -523,9 +680,98 @@ impl Rule {
     pub fn clauses(&self) -> Vec<Rule> {
         self.rhs
             .iter()
-            .map(|rhs| Rule::new(self.lhs.clone(), vec![rhs.clone()]).unwrap())
+            .map(|rhs| Rule {
+                lhs: self.lhs.clone(),
+                rhs: vec![rhs.clone()],
+                conditions: self.conditions.clone(),
+                weights: None,
+            })
             .collect()
     }
+    /// Does the `Rule` have conditions?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_) = A <= G(x_) = B").expect("parse of F(x_) = A <= G(x_) = B");
+    ///
+    /// assert!(r.is_conditional());
+    /// assert_eq!(r.conditions.len(), 1);
+    /// ```
+    pub fn is_conditional(&self) -> bool {
+        !self.conditions.is_empty()
+    }
+    /// Does the `Rule` assign a weight to each clause?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A = B :: 0.7 | C :: 0.3").expect("parse of A = B :: 0.7 | C :: 0.3");
+    ///
+    /// assert!(r.is_weighted());
+    ///
+    /// let r = parse_rule(&mut sig, "A = B | C").expect("parse of A = B | C");
+    ///
+    /// assert!(!r.is_weighted());
+    /// ```
+    pub fn is_weighted(&self) -> bool {
+        self.weights.is_some()
+    }
+    /// The weight of the `i`th clause, if the `Rule` is weighted and has an `i`th clause.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A = B :: 0.7 | C :: 0.3").expect("parse of A = B :: 0.7 | C :: 0.3");
+    ///
+    /// assert_eq!(r.weight(0), Some(0.7));
+    /// assert_eq!(r.weight(1), Some(0.3));
+    /// assert_eq!(r.weight(2), None);
+    /// ```
+    pub fn weight(&self, i: usize) -> Option<f64> {
+        self.weights.as_ref().and_then(|ws| ws.get(i).cloned())
+    }
+    /// Assign a weight to each clause of the `Rule`. Return `None` unless there is exactly one
+    /// weight per clause, each weight is finite and non-negative, and the weights sum to 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A = B | C").expect("parse of A = B | C");
+    ///
+    /// assert_eq!(r.clone().with_weights(vec![0.5, 0.6]), None);
+    /// assert_eq!(r.clone().with_weights(vec![1.0]), None);
+    ///
+    /// let r = r.with_weights(vec![0.25, 0.75]).expect("weighted rule");
+    ///
+    /// assert_eq!(r.display(&sig), "A = B :: 0.25 | C :: 0.75");
+    /// ```
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Option<Rule> {
+        if Rule::is_normalized(&weights, self.rhs.len()) {
+            self.weights = Some(weights);
+            Some(self)
+        } else {
+            None
+        }
+    }
+    // Are the weights a probability distribution over n clauses?
+    fn is_normalized(weights: &[f64], n: usize) -> bool {
+        weights.len() == n
+            && weights.iter().all(|w| w.is_finite() && *w >= 0.0)
+            && (weights.iter().sum::<f64>() - 1.0).abs() < 1e-6
+    }
     /// logic ensuring that the `lhs` and `rhs` are compatible.
     fn is_valid(lhs: &Term, rhs: &[Term]) -> bool {
         // the lhs must be an application
@@ -569,13 +815,57 @@ impl Rule {
     ///
     /// let left = parse_term(&mut sig, "A").expect("parse of A");
     /// let right = vec![parse_term(&mut sig, "B").expect("parse of B")];
-    /// let r2 = Rule { lhs: left, rhs: right };
+    /// let r2 = Rule { lhs: left, rhs: right, conditions: vec![], weights: None };
     ///
     /// assert_eq!(r, r2);
     /// ```
     pub fn new(lhs: Term, rhs: Vec<Term>) -> Option<Rule> {
-        if Rule::is_valid(&lhs, &rhs) {
-            Some(Rule { lhs, rhs })
+        Rule::new_conditional(lhs, rhs, vec![])
+    }
+    /// Construct a conditional rewrite `Rule`, which rewrites only where each pair of
+    /// `conditions` holds. Return `None` if the `Rule` is not valid.
+    ///
+    /// Besides the requirements of [`Rule::new`], the conditions may only use [`Variable`]s
+    /// appearing in `lhs`, so that matching the `lhs` fully instantiates them.
+    ///
+    /// [`Rule::new`]: #method.new
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "R(F(x_) A EQ(x_ B) TRUE y_)").expect("parse of R(F(x_) A EQ(x_ B) TRUE y_)");
+    /// let args = t.args();
+    ///
+    /// let r = Rule::new_conditional(args[0].clone(), vec![args[1].clone()], vec![(args[2].clone(), args[3].clone())])
+    ///     .expect("conditional rule");
+    ///
+    /// assert_eq!(r.display(&sig), "F(x_) = A <= EQ(x_ B) = TRUE");
+    ///
+    /// let bad = Rule::new_conditional(args[0].clone(), vec![args[1].clone()], vec![(args[4].clone(), args[3].clone())]);
+    ///
+    /// assert_eq!(bad, None);
+    /// ```
+    pub fn new_conditional(
+        lhs: Term,
+        rhs: Vec<Term>,
+        conditions: Vec<(Term, Term)>,
+    ) -> Option<Rule> {
+        let lhs_vars = lhs.variables();
+        let conditions_valid = conditions
+            .iter()
+            .flat_map(|(c, d)| c.variables().into_iter().chain(d.variables()))
+            .all(|v| lhs_vars.contains(&v));
+        if Rule::is_valid(&lhs, &rhs) && conditions_valid {
+            Some(Rule {
+                lhs,
+                rhs,
+                conditions,
+                weights: None,
+            })
         } else {
             None
         }
@@ -593,16 +883,17 @@ impl Rule {
     /// let c = parse_term(&mut sig, "C").expect("parse of C");
     /// let mut r = parse_rule(&mut sig, "A = B").expect("parse of A = B");
     ///
-    /// assert_eq!(r.display(), "A = B");
+    /// assert_eq!(r.display(&sig), "A = B");
     ///
     /// r.add(c);
     ///
-    /// assert_eq!(r.display(), "A = B | C");
+    /// assert_eq!(r.display(&sig), "A = B | C");
     /// ```
     pub fn add(&mut self, t: Term) {
         let self_vars = self.lhs.variables();
         if t.variables().iter().all(|x| self_vars.contains(x)) {
-            self.rhs.push(t)
+            self.rhs.push(t);
+            self.weights = None;
         }
     }
     /// Add clauses to the `Rule` from another `Rule`.
@@ -617,7 +908,7 @@ impl Rule {
     /// let r2 = parse_rule(&mut sig, "A(y_) = C(y_)").expect("parse A(y_) = C(y_)");
     /// r.merge(&r2);
     ///
-    /// assert_eq!(r.display(), "A(x_) = B | C(x_)");
+    /// assert_eq!(r.display(&sig), "A(x_) = B | C(x_)");
     /// ```
     pub fn merge(&mut self, r: &Rule) {
         if let Some(s) = Term::alpha(&r.lhs, &self.lhs) {
@@ -625,6 +916,7 @@ impl Rule {
                 let new_rhs = rhs.substitute(&s);
                 if !self.rhs.contains(&new_rhs) {
                     self.rhs.push(new_rhs);
+                    self.weights = None;
                 }
             }
         }
@@ -641,7 +933,7 @@ impl Rule {
     /// let mut r2 = parse_rule(&mut sig, "A(y_) = B(y_)").expect("parse of A(y_) = B(y_)");
     /// r.discard(&r2);
     ///
-    /// assert_eq!(r.display(), "A(x_) = C");
+    /// assert_eq!(r.display(&sig), "A(x_) = C");
     /// ```
     pub fn discard(&mut self, r: &Rule) -> Option<Rule> {
         if let Some(sub) = Term::alpha(&r.lhs, &self.lhs) {
@@ -650,7 +942,11 @@ impl Rule {
                 .iter()
                 .map(|rhs| rhs.substitute(&sub))
                 .collect::<Vec<Term>>();
+            let n = self.rhs.len();
             self.rhs.retain(|x| !terms.contains(x));
+            if self.rhs.len() != n {
+                self.weights = None;
+            }
             let lhs = r.lhs.substitute(&sub);
             Some(Rule::new(lhs, terms).unwrap())
         } else {
@@ -703,12 +999,12 @@ impl Rule {
     /// let mut sig = Signature::default();
     ///
     /// let r = parse_rule(&mut sig, "A(x_) = C(x_)").expect("parse of A(x_) = C(x_)");
-    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(r_variables, vec!["x_"]);
     ///
     /// let r = parse_rule(&mut sig, "B(y_ z_) = C").expect("parse of B(y_ z_) = C");
-    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+    /// let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(r_variables, vec!["y_", "z_"]);
     /// ```
@@ -726,19 +1022,23 @@ impl Rule {
     /// let mut sig = Signature::default();
     ///
     /// let r = parse_rule(&mut sig, "A(D E) = C").expect("parse of A(D E) = C");
-    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
     ///
     /// assert_eq!(r_ops, vec!["D", "E", "A", "C"]);
     ///
     /// let r = parse_rule(&mut sig, "B(F x_) = C").expect("parse of B(F x_) = C");
-    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+    /// let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
     ///
     /// assert_eq!(r_ops, vec!["F", "B", "C"]);
     /// ```
     pub fn operators(&self) -> Vec<Operator> {
         let lhs = self.lhs.operators().into_iter();
         let rhs = self.rhs.iter().flat_map(Term::operators);
-        lhs.chain(rhs).unique().collect()
+        let conditions = self
+            .conditions
+            .iter()
+            .flat_map(|(c, d)| c.operators().into_iter().chain(d.operators()));
+        lhs.chain(rhs).chain(conditions).unique().collect()
     }
     /// All the subterms and places in a `Rule`.
     ///
@@ -757,7 +1057,7 @@ impl Rule {
     ///
     /// let subterms: Vec<String> = r.subterms()
     ///     .iter()
-    ///     .map(|(t, p)| format!("{}, {:?}", t.display(), p))
+    ///     .map(|(t, p)| format!("{}, {:?}", t.display(&sig), p))
     ///     .collect();
     ///
     /// assert_eq!(
@@ -802,10 +1102,10 @@ impl Rule {
     ///
     /// let r = parse_rule(&mut sig, "A(x_) = B | C(x_)").expect("parse of A(x_) = B | C(x_)");
     ///
-    /// assert_eq!(r.at(&[0]).unwrap().display(), "A(x_)");
-    /// assert_eq!(r.at(&[0,0]).unwrap().display(), "x_");
-    /// assert_eq!(r.at(&[1]).unwrap().display(), "B");
-    /// assert_eq!(r.at(&[2]).unwrap().display(), "C(x_)");
+    /// assert_eq!(r.at(&[0]).unwrap().display(&sig), "A(x_)");
+    /// assert_eq!(r.at(&[0,0]).unwrap().display(&sig), "x_");
+    /// assert_eq!(r.at(&[1]).unwrap().display(&sig), "B");
+    /// assert_eq!(r.at(&[2]).unwrap().display(&sig), "C(x_)");
     /// ```
     pub fn at(&self, p: &[usize]) -> Option<&Term> {
         if p[0] == 0 {
@@ -833,7 +1133,7 @@ impl Rule {
     ///
     /// assert_ne!(r, new_rule.clone().unwrap());
     ///
-    /// assert_eq!(new_rule.unwrap().display(), "A(x_) = E | C(x_)");
+    /// assert_eq!(new_rule.unwrap().display(&sig), "A(x_) = E | C(x_)");
     /// ```
     pub fn replace(&self, place: &[usize], subterm: Term) -> Option<Rule> {
         if place[0] == 0 {
@@ -880,7 +1180,12 @@ impl Rule {
     /// assert_eq!(Rule::pmatch(&r, &r5), Some(expected_map));
     /// ```
     pub fn pmatch<'a>(r1: &'a Rule, r2: &'a Rule) -> Option<HashMap<&'a Variable, &'a Term>> {
-        let cs = iter::once((&r1.lhs, &r2.lhs)).chain(r1.rhs.iter().zip(r2.rhs.iter()));
+        if r1.conditions.len() != r2.conditions.len() {
+            return None;
+        }
+        let cs = iter::once((&r1.lhs, &r2.lhs))
+            .chain(r1.rhs.iter().zip(r2.rhs.iter()))
+            .chain(condition_pairs(r1, r2));
         Term::pmatch(cs.collect())
     }
     /// [`Unify`] two [`Rule`]s.
@@ -920,7 +1225,12 @@ impl Rule {
     /// assert_eq!(Rule::unify(&r, &r5), Some(expected_map));
     /// ```
     pub fn unify<'a>(r1: &'a Rule, r2: &'a Rule) -> Option<HashMap<&'a Variable, &'a Term>> {
-        let cs = iter::once((&r1.lhs, &r2.lhs)).chain(r1.rhs.iter().zip(r2.rhs.iter()));
+        if r1.conditions.len() != r2.conditions.len() {
+            return None;
+        }
+        let cs = iter::once((&r1.lhs, &r2.lhs))
+            .chain(r1.rhs.iter().zip(r2.rhs.iter()))
+            .chain(condition_pairs(r1, r2));
         Term::unify(cs.collect())
     }
     /// Compute the [`Alpha Equivalence`] between two `Rule`s.
@@ -958,6 +1268,114 @@ impl Rule {
             None
         }
     }
+    /// The least general `Rule` of which both `r1` and `r2` are instances, together with
+    /// [`Substitution`]s `σ` and `τ` taking it to `r1` and `r2`, respectively. The LHSs are
+    /// generalized together with corresponding RHS clauses, so a disagreement shared between
+    /// the LHS and a clause becomes the same [`Variable`] in both.
+    ///
+    /// Returns `None` if the `Rule`s have different numbers of clauses, or if a clause
+    /// disagrees somewhere the LHSs do not, which would leave a [`Variable`] in the RHS that
+    /// is not in the LHS.
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r1 = parse_rule(&mut sig, "F(A B) = G(A) | B").expect("parse of F(A B) = G(A) | B");
+    /// let r2 = parse_rule(&mut sig, "F(C B) = G(C) | B").expect("parse of F(C B) = G(C) | B");
+    ///
+    /// let (r, sub1, sub2) = Rule::antiunify(&mut sig, &r1, &r2).expect("generalization");
+    ///
+    /// assert_eq!(r.display(&sig), "F(var0_ B) = G(var0_) | B");
+    /// assert_eq!(sub1.display(&sig), "{var0_ := A}");
+    /// assert_eq!(sub2.display(&sig), "{var0_ := C}");
+    ///
+    /// let r3 = parse_rule(&mut sig, "F(A B) = G(D) | B").expect("parse of F(A B) = G(D) | B");
+    ///
+    /// assert_eq!(Rule::antiunify(&mut sig, &r1, &r3), None);
+    /// ```
+    pub fn antiunify(
+        sig: &mut Signature,
+        r1: &Rule,
+        r2: &Rule,
+    ) -> Option<(Rule, Substitution, Substitution)> {
+        if r1.rhs.len() != r2.rhs.len() || r1.conditions.len() != r2.conditions.len() {
+            return None;
+        }
+        let mut pairs = vec![];
+        let lhs = Term::antiunify_helper(sig, &r1.lhs, &r2.lhs, &mut pairs);
+        let rhs = r1
+            .rhs
+            .iter()
+            .zip(&r2.rhs)
+            .map(|(s, t)| Term::antiunify_helper(sig, s, t, &mut pairs))
+            .collect();
+        let conditions = r1
+            .conditions
+            .iter()
+            .zip(&r2.conditions)
+            .map(|((c1, d1), (c2, d2))| {
+                (
+                    Term::antiunify_helper(sig, c1, c2, &mut pairs),
+                    Term::antiunify_helper(sig, d1, d2, &mut pairs),
+                )
+            })
+            .collect();
+        let rule = Rule::new_conditional(lhs, rhs, conditions)?;
+        let mut sub1 = Substitution::new();
+        let mut sub2 = Substitution::new();
+        for (s, t, v) in pairs {
+            sub1.insert(v, s);
+            sub2.insert(v, t);
+        }
+        Some((rule, sub1, sub2))
+    }
+    /// Does `general` subsume `specific`? That is, is there a single substitution `σ` taking
+    /// the LHS of `general` to the LHS of `specific` under which every clause of `specific`
+    /// is a clause of `general`? Then every rewrite by `specific` is also a rewrite by
+    /// `general`, and `specific` is redundant alongside it.
+    ///
+    /// Unlike [`Rule::pmatch`], clauses need not line up one-to-one, and `general` may have
+    /// clauses `specific` lacks.
+    ///
+    /// [`Rule::pmatch`]: #method.pmatch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let general = parse_rule(&mut sig, "F(x_ y_) = x_ | y_").expect("parse of F(x_ y_) = x_ | y_");
+    /// let specific = parse_rule(&mut sig, "F(A B) = B").expect("parse of F(A B) = B");
+    ///
+    /// assert!(Rule::subsumes(&general, &specific));
+    /// assert!(!Rule::subsumes(&specific, &general));
+    /// assert_eq!(Rule::pmatch(&general, &specific), None);
+    ///
+    /// let other = parse_rule(&mut sig, "F(A B) = C").expect("parse of F(A B) = C");
+    ///
+    /// assert!(!Rule::subsumes(&general, &other));
+    /// ```
+    pub fn subsumes(general: &Rule, specific: &Rule) -> bool {
+        match Term::pmatch(vec![(&general.lhs, &specific.lhs)]) {
+            Some(sub) => {
+                let sub = Substitution::from(sub);
+                let clauses: Vec<Term> = general.rhs.iter().map(|rhs| sub.apply(rhs)).collect();
+                specific.rhs.iter().all(|rhs| clauses.contains(rhs))
+                    && general
+                        .conditions
+                        .iter()
+                        .all(|(c, d)| specific.conditions.contains(&(sub.apply(c), sub.apply(d))))
+            }
+            None => false,
+        }
+    }
     /// Substitute through a `Rule`.
     ///
     /// # Examples
@@ -977,15 +1395,233 @@ impl Rule {
     ///
     /// let r2 = r.substitute(&substitution);
     ///
-    /// assert_eq!(r2.display(), "A(C y_) = A(C) | B(y_)");
+    /// assert_eq!(r2.display(&sig), "A(C y_) = A(C) | B(y_)");
     /// ```
     pub fn substitute(&self, sub: &HashMap<&Variable, &Term>) -> Rule {
-        Rule::new(
+        let mut rule = Rule::new_conditional(
             self.lhs.substitute(sub),
             self.rhs.iter().map(|rhs| rhs.substitute(sub)).collect(),
+            self.conditions
+                .iter()
+                .map(|(c, d)| (c.substitute(sub), d.substitute(sub)))
+                .collect(),
+        )
+        .unwrap();
+        rule.weights = self.weights.clone();
+        rule
+    }
+    /// Rebuild the `Rule` with the [`Operator`]s and [`Variable`]s of another [`Signature`], as
+    /// by [`Term::map_signature`]. Returns `None` if either map lacks an [`Atom`] of the `Rule`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`Term::map_signature`]: enum.Term.html#method.map_signature
+    /// [`Atom`]: enum.Atom.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{OpMap, Signature, VarMap, parse_rule};
+    /// let mut sig = Signature::default();
+    /// let rule = parse_rule(&mut sig, "A(x_) = x_ | B").expect("parse of A(x_) = x_ | B");
+    ///
+    /// let mut sig2 = Signature::default();
+    /// let ops: OpMap = sig
+    ///     .operators()
+    ///     .into_iter()
+    ///     .map(|op| (op, sig2.new_op(op.arity(), op.name(&sig).map(|n| n.to_lowercase()))))
+    ///     .collect();
+    /// let vars: VarMap = sig
+    ///     .variables()
+    ///     .into_iter()
+    ///     .map(|v| (v, sig2.new_var(v.name(&sig))))
+    ///     .collect();
+    ///
+    /// let mapped = rule.map_signature(&ops, &vars).expect("mapped rule");
+    ///
+    /// assert_eq!(mapped.display(&sig2), "a(x_) = x_ | b");
+    /// ```
+    pub fn map_signature(&self, ops: &OpMap, vars: &VarMap) -> Option<Rule> {
+        Some(Rule {
+            lhs: self.lhs.map_signature(ops, vars)?,
+            rhs: self
+                .rhs
+                .iter()
+                .map(|rhs| rhs.map_signature(ops, vars))
+                .collect::<Option<_>>()?,
+            conditions: self
+                .conditions
+                .iter()
+                .map(|(c, d)| Some((c.map_signature(ops, vars)?, d.map_signature(ops, vars)?)))
+                .collect::<Option<_>>()?,
+            weights: self.weights.clone(),
+        })
+    }
+    /// Does each [`Variable`] occur at most once in the LHS?
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(x_ x_)").expect("parse of A(x_ y_) = B(x_ x_)");
+    ///
+    /// assert!(r.is_left_linear());
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ x_) = B(x_)").expect("parse of A(x_ x_) = B(x_)");
+    ///
+    /// assert!(!r.is_left_linear());
+    /// ```
+    pub fn is_left_linear(&self) -> bool {
+        is_linear(&self.lhs)
+    }
+    /// Does each [`Variable`] occur at most once in each RHS?
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ x_) = B(x_) | C(x_)").expect("parse of A(x_ x_) = B(x_) | C(x_)");
+    ///
+    /// assert!(r.is_right_linear());
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_) = B | C(x_ x_)").expect("parse of A(x_) = B | C(x_ x_)");
+    ///
+    /// assert!(!r.is_right_linear());
+    /// ```
+    pub fn is_right_linear(&self) -> bool {
+        self.rhs.iter().all(is_linear)
+    }
+    /// Is some RHS of the `Rule` a [`Variable`]?
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B | y_").expect("parse of A(x_ y_) = B | y_");
+    ///
+    /// assert!(r.is_collapsing());
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(y_)").expect("parse of A(x_ y_) = B(y_)");
+    ///
+    /// assert!(!r.is_collapsing());
+    /// ```
+    pub fn is_collapsing(&self) -> bool {
+        for rhs in &self.rhs {
+            if let Term::Variable(_) = *rhs {
+                return true;
+            }
+        }
+        false
+    }
+    /// Does some [`Variable`] occur more often in some RHS than in the LHS?
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(x_ x_)").expect("parse of A(x_ y_) = B(x_ x_)");
+    ///
+    /// assert!(r.is_duplicating());
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ x_) = B(x_ x_) | x_").expect("parse of A(x_ x_) = B(x_ x_) | x_");
+    ///
+    /// assert!(!r.is_duplicating());
+    /// ```
+    pub fn is_duplicating(&self) -> bool {
+        let lhs = self.lhs.variable_occurrences();
+        self.rhs.iter().any(|rhs| {
+            rhs.variable_occurrences()
+                .iter()
+                .any(|(v, n)| *n > lhs.iter().find(|(w, _)| w == v).map_or(0, |(_, m)| *m))
+        })
+    }
+    /// Contract the redex at `position` in `term` using each clause of the `Rule`, or return
+    /// `None` if `position` is not in `term` or the LHS does not match there.
+    ///
+    /// Checking conditions requires the rest of a [`TRS`], so they are ignored here; use
+    /// [`TRS::rewrite_at`] to respect them.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`TRS::rewrite_at`]: struct.TRS.html#method.rewrite_at
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Position, Signature, Rule, parse_rule, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_) = G(x_) | x_").expect("parse of F(x_) = G(x_) | x_");
+    /// let term = parse_term(&mut sig, "H(F(A) F(B))").expect("parse of H(F(A) F(B))");
+    ///
+    /// let rewritten: Vec<String> = r.rewrite_at(&term, &Position::from(vec![1]))
+    ///     .expect("rewrite")
+    ///     .iter()
+    ///     .map(|t| t.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(rewritten, vec!["H(F(A) G(B))", "H(F(A) B)"]);
+    ///
+    /// assert_eq!(r.rewrite_at(&term, &Position::root()), None);
+    /// assert_eq!(r.rewrite_at(&term, &Position::from(vec![2])), None);
+    /// ```
+    pub fn rewrite_at(&self, term: &Term, position: &Position) -> Option<Vec<Term>> {
+        let subterm = term.at(position)?;
+        let sub = Term::pmatch(vec![(&self.lhs, subterm)])?;
+        Some(
+            self.rhs
+                .iter()
+                .map(|rhs| {
+                    term.replace(position, rhs.substitute(&sub))
+                        .expect("position of redex")
+                })
+                .collect(),
         )
-        .unwrap()
     }
+    /// A copy of the `Rule` using [`Variable`]s fresh in `sig`, each named like the one it
+    /// replaces.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub(crate) fn rename_variables(&self, sig: &mut Signature) -> Rule {
+        let fresh: Vec<_> = self
+            .variables()
+            .into_iter()
+            .map(|v| {
+                let w = sig.new_var(v.name(sig));
+                (v, Term::Variable(w))
+            })
+            .collect();
+        let sub = fresh.iter().map(|(v, t)| (v, t)).collect();
+        self.substitute(&sub)
+    }
+}
+// The corresponding sides of the conditions of `r1` and `r2`.
+fn condition_pairs<'a>(r1: &'a Rule, r2: &'a Rule) -> impl Iterator<Item = (&'a Term, &'a Term)> {
+    r1.conditions
+        .iter()
+        .zip(&r2.conditions)
+        .flat_map(|((c1, d1), (c2, d2))| vec![(c1, c2), (d1, d2)])
+}
+
+// Does each variable occur at most once in `term`?
+fn is_linear(term: &Term) -> bool {
+    term.variable_occurrences().iter().all(|(_, n)| *n == 1)
 }
 
 #[cfg(test)]
@@ -1005,7 +1641,7 @@ mod tests {
 
         let r = RuleContext::new(left, vec![b, c]).unwrap();
 
-        assert_eq!(r.pretty(), "A(B, C, [!]) = B [!] | C");
+        assert_eq!(r.pretty(&sig), "A(B, C, [!]) = B [!] | C");
 
         let left = parse_context(&mut sig, "A(B C [!])").expect("parse of A(B C [!])");
         let b = parse_context(&mut sig, "B [!] x_").expect("parse of B [!] x_");
@@ -1026,7 +1662,7 @@ mod tests {
         let rule = parse_rulecontext(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))")
             .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
 
-        assert_eq!(rule.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = .([!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL))))");
+        assert_eq!(rule.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = .([!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL))))");
     }
 
     #[test]
@@ -1036,7 +1672,10 @@ mod tests {
         let rule = parse_rulecontext(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))")
             .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = [!] CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
 
-        assert_eq!(rule.pretty(), "A B(x_) [2, 1, 0] 105 = [!] [A, B(x_), 2]");
+        assert_eq!(
+            rule.pretty(&sig),
+            "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105 = [!] [A, B(x_), SUCC(SUCC(ZERO))]"
+        );
     }
 
     #[test]
@@ -1049,7 +1688,7 @@ mod tests {
         let subcontexts: Vec<String> = r
             .subcontexts()
             .iter()
-            .map(|(c, p)| format!("({}, {:?})", c.display(), p))
+            .map(|(c, p)| format!("({}, {:?})", c.display(&sig), p))
             .collect();
 
         assert_eq!(
@@ -1085,12 +1724,12 @@ mod tests {
 
         let r =
             parse_rulecontext(&mut sig, "A(x_ [!]) = C(x_)").expect("parse of A(x_ [!]) = C(x_)");
-        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(r_variables, vec!["x_"]);
 
         let r = parse_rulecontext(&mut sig, "B(y_ z_) = C [!]").expect("parse of B(y_ z_) = C [!]");
-        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(r_variables, vec!["y_", "z_"]);
     }
@@ -1100,12 +1739,12 @@ mod tests {
         let mut sig = Signature::default();
 
         let r = parse_rulecontext(&mut sig, "A(D E) = C([!])").expect("parse of A(D E) = C([!])");
-        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
 
         assert_eq!(r_ops, vec!["D", "E", "A", "C"]);
 
         let r = parse_rulecontext(&mut sig, "B(F x_) = C [!]").expect("parse of B(F x_) = C [!]");
-        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
 
         assert_eq!(r_ops, vec!["F", "B", "C", "."]);
     }
@@ -1117,11 +1756,11 @@ mod tests {
         let r = parse_rulecontext(&mut sig, "A(x_ [!]) = B | C(x_ [!])")
             .expect("parse of A(x_ [!]) = B | C(x_ [!])");
 
-        assert_eq!(r.at(&[0]).unwrap().display(), "A(x_ [!])");
-        assert_eq!(r.at(&[0, 1]).unwrap().display(), "[!]");
-        assert_eq!(r.at(&[0, 0]).unwrap().display(), "x_");
-        assert_eq!(r.at(&[1]).unwrap().display(), "B");
-        assert_eq!(r.at(&[2]).unwrap().display(), "C(x_ [!])");
+        assert_eq!(r.at(&[0]).unwrap().display(&sig), "A(x_ [!])");
+        assert_eq!(r.at(&[0, 1]).unwrap().display(&sig), "[!]");
+        assert_eq!(r.at(&[0, 0]).unwrap().display(&sig), "x_");
+        assert_eq!(r.at(&[1]).unwrap().display(&sig), "B");
+        assert_eq!(r.at(&[2]).unwrap().display(&sig), "C(x_ [!])");
     }
 
     #[test]
@@ -1134,7 +1773,7 @@ mod tests {
         let new_r = r.replace(&[1], new_context);
 
         assert_ne!(r, new_r.clone().unwrap());
-        assert_eq!(new_r.unwrap().pretty(), "A(x_) = E [!] | C(x_) | [!]");
+        assert_eq!(new_r.unwrap().pretty(&sig), "A(x_) = E [!] | C(x_) | [!]");
     }
 
     #[test]
@@ -1150,7 +1789,7 @@ mod tests {
             parse_rulecontext(&mut sig, "A(x_) = B | C(x_)").expect("parse of A(x_) = B | C(x_)");
         let rule = r.to_rule().expect("converting RuleContext to Rule");
 
-        assert_eq!(rule.pretty(), "A(x_) = B | C(x_)");
+        assert_eq!(rule.pretty(&sig), "A(x_) = B | C(x_)");
     }
 
     #[test]
@@ -1160,7 +1799,7 @@ mod tests {
         let rule = parse_rule(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))")
             .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
 
-        assert_eq!(rule.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))");
+        assert_eq!(rule.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5)) = CONS(A CONS(B(x_) CONS(SUCC(SUCC(ZERO)) NIL)))");
     }
 
     #[test]
@@ -1170,7 +1809,10 @@ mod tests {
         let rule = parse_rule(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))")
             .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5) = CONS(A CONS(B(x_) CONS( SUCC(SUCC(ZERO)) NIL)))");
 
-        assert_eq!(rule.pretty(), "A B(x_) [2, 1, 0] 105 = [A, B(x_), 2]");
+        assert_eq!(
+            rule.pretty(&sig),
+            "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105 = [A, B(x_), SUCC(SUCC(ZERO))]"
+        );
     }
 
     #[test]
@@ -1238,6 +1880,34 @@ mod tests {
     #[test]
     fn is_valid_test() {}
 
+    #[test]
+    fn weights_test() {
+        let mut sig = Signature::default();
+
+        let r = parse_rule(&mut sig, "A(x_) = B(x_) :: 0.4 | C :: 0.6")
+            .expect("parse of A(x_) = B(x_) :: 0.4 | C :: 0.6");
+        let c = parse_term(&mut sig, "C").expect("parse of C");
+
+        assert_eq!(r.weight(1), Some(0.6));
+        assert_eq!(r.clone().with_weights(vec![-0.4, 1.4]), None);
+        assert_ne!(r, r.clone().with_weights(vec![0.5, 0.5]).unwrap());
+
+        let x = &r.variables()[0];
+        let mut sub = HashMap::default();
+        sub.insert(x, &c);
+
+        assert_eq!(
+            r.substitute(&sub).display(&sig),
+            "A(C) = B(C) :: 0.4 | C :: 0.6"
+        );
+
+        let mut r2 = r.clone();
+        r2.add(parse_term(&mut sig, "D").expect("parse of D"));
+
+        assert!(!r2.is_weighted());
+        assert_eq!(r2.display(&sig), "A(x_) = B(x_) | C | D");
+    }
+
     #[test]
     #[ignore]
     fn rule_new_test() {
@@ -1256,6 +1926,8 @@ mod tests {
         let r2 = Rule {
             lhs: left,
             rhs: right,
+            conditions: vec![],
+            weights: None,
         };
 
         assert_eq!(r, r2);
@@ -1268,11 +1940,11 @@ mod tests {
         let c = parse_term(&mut sig, "C").expect("parse of C");
         let mut r = parse_rule(&mut sig, "A = B").expect("parse of A = B");
 
-        assert_eq!(r.display(), "A = B");
+        assert_eq!(r.display(&sig), "A = B");
 
         r.add(c);
 
-        assert_eq!(r.display(), "A = B | C");
+        assert_eq!(r.display(&sig), "A = B | C");
     }
 
     #[test]
@@ -1283,7 +1955,7 @@ mod tests {
         let r2 = parse_rule(&mut sig, "A = C").expect("parse A = C");
         r.merge(&r2);
 
-        assert_eq!(r.display(), "A = B | C");
+        assert_eq!(r.display(&sig), "A = B | C");
     }
 
     #[test]
@@ -1294,7 +1966,7 @@ mod tests {
         let r2 = parse_rule(&mut sig, "A(y_) = B(y_)").expect("parse of A(y_) = B(y_)");
         r.discard(&r2);
 
-        assert_eq!(r.display(), "A(x_) = C");
+        assert_eq!(r.display(&sig), "A(x_) = C");
     }
 
     #[test]
@@ -1307,7 +1979,7 @@ mod tests {
         assert_eq!(r2.contains(&r), None);
 
         {
-            let x = Term::Variable(r.variables()[0].clone());
+            let x = Term::Variable(r.variables()[0]);
             let y = &r2.variables()[0];
             let mut sub = HashMap::new();
             sub.insert(y, &x);
@@ -1321,12 +1993,12 @@ mod tests {
         let mut sig = Signature::default();
 
         let r = parse_rule(&mut sig, "A(x_) = C(x_)").expect("parse of A(x_) = C(x_)");
-        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(r_variables, vec!["x_"]);
 
         let r = parse_rule(&mut sig, "B(y_ z_) = C").expect("parse of B(y_ z_) = C");
-        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display()).collect();
+        let r_variables: Vec<String> = r.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(r_variables, vec!["y_", "z_"]);
     }
@@ -1336,12 +2008,12 @@ mod tests {
         let mut sig = Signature::default();
 
         let r = parse_rule(&mut sig, "A(D E) = C").expect("parse of A(D E) = C");
-        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
 
         assert_eq!(r_ops, vec!["D", "E", "A", "C"]);
 
         let r = parse_rule(&mut sig, "B(F x_) = C").expect("parse of B(F x_) = C");
-        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display()).collect();
+        let r_ops: Vec<String> = r.operators().iter().map(|o| o.display(&sig)).collect();
 
         assert_eq!(r_ops, vec!["F", "B", "C"]);
     }
@@ -1356,7 +2028,7 @@ mod tests {
         let subterms: Vec<String> = r
             .subterms()
             .iter()
-            .map(|(t, p)| format!("{}, {:?}", t.display(), p))
+            .map(|(t, p)| format!("{}, {:?}", t.display(&sig), p))
             .collect();
 
         assert_eq!(
@@ -1379,10 +2051,10 @@ mod tests {
 
         let r = parse_rule(&mut sig, "A(x_) = B | C(x_)").expect("parse of A(x_) = B | C(x_)");
 
-        assert_eq!(r.at(&[0]).unwrap().display(), "A(x_)");
-        assert_eq!(r.at(&[0, 0]).unwrap().display(), "x_");
-        assert_eq!(r.at(&[1]).unwrap().display(), "B");
-        assert_eq!(r.at(&[2]).unwrap().display(), "C(x_)");
+        assert_eq!(r.at(&[0]).unwrap().display(&sig), "A(x_)");
+        assert_eq!(r.at(&[0, 0]).unwrap().display(&sig), "x_");
+        assert_eq!(r.at(&[1]).unwrap().display(&sig), "B");
+        assert_eq!(r.at(&[2]).unwrap().display(&sig), "C(x_)");
     }
 
     #[test]
@@ -1395,7 +2067,7 @@ mod tests {
 
         assert_ne!(r, new_rule.clone().unwrap());
 
-        assert_eq!(new_rule.unwrap().display(), "A(x_) = E | C(x_)");
+        assert_eq!(new_rule.unwrap().display(&sig), "A(x_) = E | C(x_)");
     }
 
     #[test]
@@ -1414,7 +2086,7 @@ mod tests {
 
         {
             let subbee = &r.variables()[0];
-            let subbed = Term::Variable(r5.variables()[0].clone());
+            let subbed = Term::Variable(r5.variables()[0]);
             let mut expected_map = HashMap::default();
             expected_map.insert(subbee, &subbed);
 
@@ -1448,7 +2120,7 @@ mod tests {
 
         {
             let subbee = &r.variables()[0];
-            let subbed = Term::Variable(r5.variables()[0].clone());
+            let subbed = Term::Variable(r5.variables()[0]);
             let mut expected_map = HashMap::default();
             expected_map.insert(subbee, &subbed);
 
@@ -1472,7 +2144,7 @@ mod tests {
 
         {
             let subbee = &r.variables()[0];
-            let subbed = Term::Variable(r5.variables()[0].clone());
+            let subbed = Term::Variable(r5.variables()[0]);
             let mut expected_map = HashMap::default();
             expected_map.insert(subbee, &subbed);
 
@@ -1480,6 +2152,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn antiunify_test() {
+        let mut sig = Signature::default();
+
+        let r1 = parse_rule(&mut sig, "F(x_ S(x_)) = x_ | S(x_)")
+            .expect("parse of F(x_ S(x_)) = x_ | S(x_)");
+        let r2 =
+            parse_rule(&mut sig, "F(Z S(Z)) = Z | S(Z)").expect("parse of F(Z S(Z)) = Z | S(Z)");
+        let (r, sub1, sub2) = Rule::antiunify(&mut sig, &r1, &r2).expect("generalization");
+
+        assert_eq!(r.variables().len(), 1);
+        assert_eq!(sub1.apply(&r.lhs), r1.lhs);
+        assert_eq!(sub2.apply(&r.lhs), r2.lhs);
+        assert_eq!(
+            r.rhs.iter().map(|t| sub2.apply(t)).collect::<Vec<_>>(),
+            r2.rhs
+        );
+        assert!(Rule::alpha(&r, &r1).is_some());
+
+        let r3 = parse_rule(&mut sig, "F(Z S(Z)) = Z").expect("parse of F(Z S(Z)) = Z");
+
+        assert_eq!(Rule::antiunify(&mut sig, &r1, &r3), None);
+    }
+
+    #[test]
+    fn subsumes_test() {
+        let mut sig = Signature::default();
+
+        let general = parse_rule(&mut sig, "F(x_ G(y_)) = G(x_) | y_")
+            .expect("parse of F(x_ G(y_)) = G(x_) | y_");
+        let specific = parse_rule(&mut sig, "F(A G(x_)) = x_ | G(A)")
+            .expect("parse of F(A G(x_)) = x_ | G(A)");
+        let variant = parse_rule(&mut sig, "F(z_ G(w_)) = w_").expect("parse of F(z_ G(w_)) = w_");
+
+        assert!(Rule::subsumes(&general, &specific));
+        assert!(Rule::subsumes(&general, &variant));
+        assert!(!Rule::subsumes(&variant, &general));
+        assert!(Rule::subsumes(&general, &general));
+
+        // the LHS substitution must also be used for the RHS.
+        let unsound = parse_rule(&mut sig, "F(A G(B)) = G(B)").expect("parse of F(A G(B)) = G(B)");
+
+        assert!(!Rule::subsumes(&general, &unsound));
+    }
+
     #[test]
     fn substitute_test() {
         let mut sig = Signature::default();
@@ -1495,6 +2212,6 @@ mod tests {
 
         let r2 = r.substitute(&substitution);
 
-        assert_eq!(r2.display(), "A(C y_) = A(C) | B(y_)");
+        assert_eq!(r2.display(&sig), "A(C y_) = A(C) | B(y_)");
     }
 }
@@ -0,0 +1,766 @@
+use super::{Atom, Operator, Place, Rule, Signature, Term, Variable, TRS};
+use rand::Rng;
+
+/// Settings for [`Signature::sample_term_with`].
+///
+/// [`Signature::sample_term_with`]: struct.Signature.html#method.sample_term_with
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermSampleConfig {
+    /// The relative weight of choosing a [`Variable`] at each node, or `0.0` to sample ground
+    /// [`Term`]s. [`Variable`]s are drawn uniformly from those of the [`Signature`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Term`]: enum.Term.html
+    /// [`Signature`]: struct.Signature.html
+    pub variable_weight: f64,
+    /// The relative weight of choosing each listed [`Operator`] at each node. Unlisted
+    /// [`Operator`]s have weight `1.0`, and [`Operator`]s with weight `0.0` are never chosen.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    pub operator_weights: Vec<(Operator, f64)>,
+}
+impl Default for TermSampleConfig {
+    /// Weigh every [`Operator`] and the [`Variable`]s equally.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    fn default() -> TermSampleConfig {
+        TermSampleConfig {
+            variable_weight: 1.0,
+            operator_weights: vec![],
+        }
+    }
+}
+impl TermSampleConfig {
+    // The weight of `op`.
+    fn weight(&self, op: &Operator) -> f64 {
+        self.operator_weights
+            .iter()
+            .find(|(o, _)| o == op)
+            .map(|(_, w)| *w)
+            .unwrap_or(1.0)
+    }
+}
+
+impl Signature {
+    /// Sample a [`Term`] no deeper than `max_depth` using the default [`TermSampleConfig`],
+    /// choosing uniformly among the [`Operator`]s and [`Variable`]s at each node.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`TermSampleConfig`]: struct.TermSampleConfig.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::default();
+    /// sig.new_op(0, Some("Z".to_string()));
+    /// sig.new_op(2, Some("F".to_string()));
+    /// sig.new_var(Some("x".to_string()));
+    /// let mut r = rand::thread_rng();
+    ///
+    /// let term = sig.sample_term(3, &mut r).expect("a term");
+    ///
+    /// assert!(term.subterms().iter().all(|(_, place)| place.len() <= 3));
+    /// # }
+    /// ```
+    pub fn sample_term<R: Rng>(&self, max_depth: usize, rng: &mut R) -> Option<Term> {
+        self.sample_term_with(&TermSampleConfig::default(), max_depth, rng)
+    }
+    /// Sample a [`Term`] no deeper than `max_depth`, choosing the head of each node according
+    /// to `config`. The root is at depth `0`, and only [`Variable`]s and constants are chosen at
+    /// depth `max_depth`, so sampling always terminates. Returns `None` if no [`Variable`] or
+    /// constant may be chosen.
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, TermSampleConfig};
+    /// let mut sig = Signature::default();
+    /// let z = sig.new_op(0, Some("Z".to_string()));
+    /// let s = sig.new_op(1, Some("S".to_string()));
+    /// sig.new_var(Some("x".to_string()));
+    /// let mut r = rand::thread_rng();
+    ///
+    /// let config = TermSampleConfig {
+    ///     variable_weight: 0.0,
+    ///     operator_weights: vec![(s, 0.0)],
+    /// };
+    ///
+    /// assert_eq!(sig.sample_term_with(&config, 5, &mut r).expect("a term").display(&sig), "Z");
+    ///
+    /// let config = TermSampleConfig {
+    ///     variable_weight: 0.0,
+    ///     operator_weights: vec![(z, 0.0)],
+    /// };
+    ///
+    /// assert_eq!(sig.sample_term_with(&config, 5, &mut r), None);
+    /// # }
+    /// ```
+    pub fn sample_term_with<R: Rng>(
+        &self,
+        config: &TermSampleConfig,
+        max_depth: usize,
+        rng: &mut R,
+    ) -> Option<Term> {
+        self.sample_term_over(config, &self.variables(), max_depth, rng)
+    }
+    /// Sample a [`Rule`] whose lhs is headed by `head`, choosing the rest of each side according
+    /// to `config` as in [`Signature::sample_term_with`]. Both sides are no deeper than
+    /// `max_depth`, and the rhs only uses [`Variable`]s from the lhs. Returns `None` if no such
+    /// [`Rule`] exists.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Signature::sample_term_with`]: struct.Signature.html#method.sample_term_with
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, TermSampleConfig};
+    /// let mut sig = Signature::default();
+    /// sig.new_op(0, Some("Z".to_string()));
+    /// let f = sig.new_op(2, Some("F".to_string()));
+    /// sig.new_var(Some("x".to_string()));
+    /// sig.new_var(Some("y".to_string()));
+    /// let mut r = rand::thread_rng();
+    ///
+    /// let rule = sig
+    ///     .sample_rule(&f, &TermSampleConfig::default(), 2, &mut r)
+    ///     .expect("a rule");
+    ///
+    /// assert_eq!(rule.lhs.head().display(&sig), "F");
+    /// assert!(rule.rhs[0].variables().iter().all(|v| rule.lhs.variables().contains(v)));
+    /// # }
+    /// ```
+    pub fn sample_rule<R: Rng>(
+        &self,
+        head: &Operator,
+        config: &TermSampleConfig,
+        max_depth: usize,
+        rng: &mut R,
+    ) -> Option<Rule> {
+        let variables = self.variables();
+        let (variable_weight, operators) = self.sample_atoms(config, &variables);
+        let args = if head.arity() == 0 {
+            vec![]
+        } else if max_depth > 0 && has_leaf(variable_weight, &operators) {
+            (0..head.arity())
+                .map(|_| sample(&variables, variable_weight, &operators, max_depth - 1, rng))
+                .collect()
+        } else {
+            return None;
+        };
+        let lhs = Term::Application { op: *head, args };
+        let rhs = self.sample_term_over(config, &lhs.variables(), max_depth, rng)?;
+        Rule::new(lhs, vec![rhs])
+    }
+    /// Sample a [`TRS`] of `n` [`Rule`]s, as in [`Signature::sample_rule`]. The head of each
+    /// lhs is chosen according to the [`Operator`] weights of `config`. Returns `None` if some
+    /// [`Rule`] cannot be sampled.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Signature::sample_rule`]: struct.Signature.html#method.sample_rule
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, TermSampleConfig};
+    /// let mut sig = Signature::default();
+    /// sig.new_op(0, Some("Z".to_string()));
+    /// sig.new_op(1, Some("S".to_string()));
+    /// sig.new_var(Some("x".to_string()));
+    /// let mut r = rand::thread_rng();
+    ///
+    /// let trs = sig
+    ///     .sample_trs(3, &TermSampleConfig::default(), 3, &mut r)
+    ///     .expect("a trs");
+    ///
+    /// assert_eq!(trs.len(), 3);
+    /// # }
+    /// ```
+    pub fn sample_trs<R: Rng>(
+        &self,
+        n: usize,
+        config: &TermSampleConfig,
+        max_depth: usize,
+        rng: &mut R,
+    ) -> Option<TRS> {
+        let (_, operators) = self.sample_atoms(config, &[]);
+        let weights: Vec<f64> = operators
+            .iter()
+            .map(|(op, w)| {
+                if max_depth == 0 && op.arity() > 0 {
+                    0.0
+                } else {
+                    *w
+                }
+            })
+            .collect();
+        let rules = (0..n)
+            .map(|_| {
+                let head = &operators[choose_weighted(&weights, rng)?].0;
+                self.sample_rule(head, config, max_depth, rng)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(TRS::new(rules))
+    }
+    // Sample a Term as in `sample_term_with`, but drawing Variables from `variables`.
+    pub(crate) fn sample_term_over<R: Rng>(
+        &self,
+        config: &TermSampleConfig,
+        variables: &[Variable],
+        max_depth: usize,
+        rng: &mut R,
+    ) -> Option<Term> {
+        let (variable_weight, operators) = self.sample_atoms(config, variables);
+        if has_leaf(variable_weight, &operators) {
+            Some(sample(
+                variables,
+                variable_weight,
+                &operators,
+                max_depth,
+                rng,
+            ))
+        } else {
+            None
+        }
+    }
+    // Sample a Term as in `sample_term_over`, but headed by an Operator taking arguments.
+    pub(crate) fn sample_application_over<R: Rng>(
+        &self,
+        config: &TermSampleConfig,
+        variables: &[Variable],
+        max_depth: usize,
+        rng: &mut R,
+    ) -> Option<Term> {
+        let (variable_weight, operators) = self.sample_atoms(config, variables);
+        if max_depth == 0 || !has_leaf(variable_weight, &operators) {
+            return None;
+        }
+        let weights: Vec<f64> = operators
+            .iter()
+            .map(|(op, w)| if op.arity() > 0 { *w } else { 0.0 })
+            .collect();
+        let op = operators[choose_weighted(&weights, rng)?].0;
+        let args = (0..op.arity())
+            .map(|_| sample(variables, variable_weight, &operators, max_depth - 1, rng))
+            .collect();
+        Some(Term::Application { op, args })
+    }
+    // The total weight of `variables` and the Operators with positive weight under `config`.
+    fn sample_atoms(
+        &self,
+        config: &TermSampleConfig,
+        variables: &[Variable],
+    ) -> (f64, Vec<(Operator, f64)>) {
+        let operators: Vec<(Operator, f64)> = self
+            .operators()
+            .into_iter()
+            .map(|op| {
+                let weight = config.weight(&op);
+                (op, weight)
+            })
+            .filter(|(_, w)| *w > 0.0)
+            .collect();
+        let variable_weight = if variables.is_empty() {
+            0.0
+        } else {
+            config.variable_weight.max(0.0)
+        };
+        (variable_weight, operators)
+    }
+}
+
+// Can a Variable or constant be chosen?
+fn has_leaf(variable_weight: f64, operators: &[(Operator, f64)]) -> bool {
+    variable_weight > 0.0 || operators.iter().any(|(op, _)| op.arity() == 0)
+}
+
+// Sample a Term no deeper than `depth`, given that some leaf can be chosen.
+fn sample<R: Rng>(
+    variables: &[Variable],
+    variable_weight: f64,
+    operators: &[(Operator, f64)],
+    depth: usize,
+    rng: &mut R,
+) -> Term {
+    // the first choice is the Variables, the rest are `operators` in order
+    let weights: Vec<f64> = Some(variable_weight)
+        .into_iter()
+        .chain(operators.iter().map(|(op, w)| {
+            if depth == 0 && op.arity() > 0 {
+                0.0
+            } else {
+                *w
+            }
+        }))
+        .collect();
+    match choose_weighted(&weights, rng) {
+        Some(0) => Term::Variable(variables[rng.gen_range(0, variables.len())]),
+        Some(i) => {
+            let op = operators[i - 1].0;
+            let args = (0..op.arity())
+                .map(|_| sample(variables, variable_weight, operators, depth - 1, rng))
+                .collect();
+            Term::Application { op, args }
+        }
+        None => unreachable!("some leaf can be chosen"),
+    }
+}
+
+impl Term {
+    /// Subtree crossover: swap a random subterm of `a` with a random subterm of `b`, returning
+    /// the two offspring. Subterms are chosen uniformly from all places, including the roots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    /// let t = parse_term(&mut sig, "PAIR(F(A) G(B C))").expect("parse of PAIR(F(A) G(B C))");
+    /// let (a, b) = (&t.args()[0], &t.args()[1]);
+    /// let mut r = rand::thread_rng();
+    ///
+    /// let (c, d) = Term::crossover(a, b, &mut r);
+    ///
+    /// assert_eq!(c.size() + d.size(), a.size() + b.size());
+    /// # }
+    /// ```
+    pub fn crossover<R: Rng>(a: &Term, b: &Term, rng: &mut R) -> (Term, Term) {
+        let a_places: Vec<Place> = a.subterms().into_iter().map(|(_, p)| p).collect();
+        let b_places: Vec<Place> = b.subterms().into_iter().map(|(_, p)| p).collect();
+        let a_place = rng.choose(&a_places).expect("a place");
+        let b_place = rng.choose(&b_places).expect("a place");
+        swap_subterms(a, a_place, b, b_place).expect("offspring")
+    }
+    /// Subtree crossover as in [`Term::crossover`], but only swapping subterms of the same
+    /// sort, so that both offspring are well-sorted. Subterms of unknown sort are compatible
+    /// with any sort. Returns `None` if no swap gives well-sorted offspring.
+    ///
+    /// [`Term::crossover`]: enum.Term.html#method.crossover
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, Term, parse};
+    /// let mut sig = Signature::default();
+    /// let (_, terms) = parse(&mut sig,
+    /// "ZERO : Nat;
+    /// SUCC : Nat -> Nat;
+    /// TRUE : Bool;
+    /// ISZERO : Nat -> Bool;
+    /// ISZERO(SUCC(ZERO));
+    /// SUCC(ZERO);").expect("parse of sorted terms");
+    /// let mut r = rand::thread_rng();
+    ///
+    /// for _ in 0..10 {
+    ///     let (c, d) = Term::sorted_crossover(&sig, &terms[0], &terms[1], &mut r).expect("offspring");
+    ///
+    ///     assert!(c.sort(&sig).is_ok() && d.sort(&sig).is_ok());
+    ///     assert_eq!(c.sort(&sig), terms[0].sort(&sig));
+    /// }
+    /// # }
+    /// ```
+    pub fn sorted_crossover<R: Rng>(
+        sig: &Signature,
+        a: &Term,
+        b: &Term,
+        rng: &mut R,
+    ) -> Option<(Term, Term)> {
+        let sorted = |t: &Term| -> Vec<(Option<String>, Place)> {
+            t.subterms()
+                .into_iter()
+                .filter_map(|(s, p)| s.sort(sig).ok().map(|sort| (sort, p)))
+                .collect()
+        };
+        let b_places = sorted(b);
+        let mut pairs: Vec<(&Place, &Place)> = vec![];
+        let a_places = sorted(a);
+        for (a_sort, a_place) in &a_places {
+            for (b_sort, b_place) in &b_places {
+                if a_sort.is_none() || b_sort.is_none() || a_sort == b_sort {
+                    pairs.push((a_place, b_place));
+                }
+            }
+        }
+        rng.shuffle(&mut pairs);
+        pairs.into_iter().find_map(|(a_place, b_place)| {
+            let (c, d) = swap_subterms(a, a_place, b, b_place)?;
+            if c.sort(sig).is_ok() && d.sort(sig).is_ok() {
+                Some((c, d))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+// Swap the subterm of `a` at `a_place` with the subterm of `b` at `b_place`.
+fn swap_subterms(a: &Term, a_place: &[usize], b: &Term, b_place: &[usize]) -> Option<(Term, Term)> {
+    let a_sub = a.at(a_place)?.clone();
+    let b_sub = b.at(b_place)?.clone();
+    Some((a.replace(a_place, b_sub)?, b.replace(b_place, a_sub)?))
+}
+
+/// A probabilistic context-free grammar over [`Term`]s. Each node of a [`Term`] is produced by
+/// choosing its head [`Atom`] with a fixed probability, independent of its context, so the
+/// probability of a [`Term`] is the product of the probabilities of its [`Atom`]s.
+///
+/// [`Term`]: enum.Term.html
+/// [`Atom`]: enum.Atom.html
+///
+/// # Examples
+///
+/// ```
+/// # extern crate rand;
+/// # extern crate term_rewriting;
+/// # fn main(){
+/// # use term_rewriting::{Atom, Signature, TermPcfg, parse_term};
+/// let mut sig = Signature::default();
+/// let z = sig.new_op(0, Some("Z".to_string()));
+/// let s = sig.new_op(1, Some("S".to_string()));
+///
+/// let pcfg = TermPcfg::new(vec![(Atom::from(z), 3.0), (Atom::from(s), 1.0)]).expect("a pcfg");
+///
+/// let term = parse_term(&mut sig, "S(S(Z))").expect("parse of S(S(Z))");
+///
+/// assert!((pcfg.log_prob(&term) - (0.25f64 * 0.25 * 0.75).ln()).abs() < 1e-10);
+///
+/// let mut r = rand::thread_rng();
+/// let sample = pcfg.sample(&mut r).expect("a term");
+///
+/// assert!(pcfg.log_prob(&sample) < 0.0);
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermPcfg {
+    // each Atom with positive probability, summing to 1
+    atoms: Vec<(Atom, f64)>,
+    /// The deepest a sampled [`Term`] may be. [`TermPcfg::sample`] rejects deeper [`Term`]s
+    /// rather than truncating them, so every [`Term`] it returns is scored correctly by
+    /// [`TermPcfg::log_prob`].
+    ///
+    /// [`Term`]: enum.Term.html
+    /// [`TermPcfg::sample`]: struct.TermPcfg.html#method.sample
+    /// [`TermPcfg::log_prob`]: struct.TermPcfg.html#method.log_prob
+    pub max_depth: usize,
+}
+impl TermPcfg {
+    /// Create a `TermPcfg` choosing each [`Atom`] with probability proportional to its weight,
+    /// with a `max_depth` of 100. [`Atom`]s with non-positive weights are never chosen. Returns
+    /// `None` unless some [`Variable`] or constant has positive weight.
+    ///
+    /// [`Atom`]: enum.Atom.html
+    /// [`Variable`]: struct.Variable.html
+    pub fn new(weights: Vec<(Atom, f64)>) -> Option<TermPcfg> {
+        let weights: Vec<(Atom, f64)> = weights.into_iter().filter(|(_, w)| *w > 0.0).collect();
+        let has_leaf = weights.iter().any(|(atom, _)| match atom {
+            Atom::Variable(_) => true,
+            Atom::Operator(op) => op.arity() == 0,
+        });
+        if !has_leaf {
+            return None;
+        }
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        Some(TermPcfg {
+            atoms: weights
+                .into_iter()
+                .map(|(atom, w)| (atom, w / total))
+                .collect(),
+            max_depth: 100,
+        })
+    }
+    /// Create a `TermPcfg` choosing uniformly among the [`Atom`]s of `sig`.
+    ///
+    /// [`Atom`]: enum.Atom.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Atom, Signature, TermPcfg};
+    /// let mut sig = Signature::default();
+    /// let a = sig.new_op(0, Some("A".to_string()));
+    /// sig.new_op(2, Some("F".to_string()));
+    ///
+    /// let pcfg = TermPcfg::uniform(&sig).expect("a pcfg");
+    ///
+    /// assert_eq!(pcfg.probability(&Atom::from(a)), 0.5);
+    /// ```
+    pub fn uniform(sig: &Signature) -> Option<TermPcfg> {
+        TermPcfg::new(sig.atoms().into_iter().map(|atom| (atom, 1.0)).collect())
+    }
+    /// The probability of choosing `atom` at a node.
+    pub fn probability(&self, atom: &Atom) -> f64 {
+        self.atoms
+            .iter()
+            .find(|(a, _)| a == atom)
+            .map(|(_, p)| *p)
+            .unwrap_or(0.0)
+    }
+    // The probability of choosing any Variable at a node.
+    pub(crate) fn variable_probability(&self) -> f64 {
+        self.atoms
+            .iter()
+            .filter(|(atom, _)| match atom {
+                Atom::Variable(_) => true,
+                Atom::Operator(_) => false,
+            })
+            .map(|(_, p)| p)
+            .sum()
+    }
+    /// Sample a [`Term`] from the `TermPcfg`, or return `None` if the [`Term`] sampled is deeper
+    /// than `max_depth`.
+    ///
+    /// [`Term`]: enum.Term.html
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<Term> {
+        self.sample_at(0, rng)
+    }
+    fn sample_at<R: Rng>(&self, depth: usize, rng: &mut R) -> Option<Term> {
+        if depth > self.max_depth {
+            return None;
+        }
+        let weights: Vec<f64> = self.atoms.iter().map(|(_, p)| *p).collect();
+        let i = choose_weighted(&weights, rng).expect("some atom can be chosen");
+        match self.atoms[i].0 {
+            Atom::Variable(ref v) => Some(Term::Variable(*v)),
+            Atom::Operator(ref op) => {
+                let args = (0..op.arity())
+                    .map(|_| self.sample_at(depth + 1, rng))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Term::Application { op: *op, args })
+            }
+        }
+    }
+    /// The natural log of the probability of producing `term`, or negative infinity if it
+    /// contains an [`Atom`] which is never chosen. `max_depth` is ignored.
+    ///
+    /// [`Atom`]: enum.Atom.html
+    pub fn log_prob(&self, term: &Term) -> f64 {
+        let head = self.probability(&term.head()).ln();
+        term.args()
+            .iter()
+            .fold(head, |lp, arg| lp + self.log_prob(arg))
+    }
+}
+
+// Choose an index with probability proportional to its weight in `weights`, or return `None` if
+// no weight is positive.
+pub(crate) fn choose_weighted<R: Rng>(weights: &[f64], rng: &mut R) -> Option<usize> {
+    let total: f64 = weights.iter().filter(|w| **w > 0.0).sum();
+    // fall back to the last positive weight to absorb rounding error
+    let last = weights.iter().rposition(|w| *w > 0.0)?;
+    let mut threshold = rng.gen::<f64>() * total;
+    for (i, w) in weights.iter().enumerate() {
+        if *w > 0.0 {
+            if threshold < *w {
+                return Some(i);
+            }
+            threshold -= w;
+        }
+    }
+    Some(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn sample_term_test() {
+        let mut sig = Signature::default();
+        let a = sig.new_op(0, Some("A".to_string()));
+        let f = sig.new_op(2, Some("F".to_string()));
+        let g = sig.new_op(1, Some("G".to_string()));
+        let x = sig.new_var(Some("x".to_string()));
+        let mut r = StdRng::from_seed([0; 32]);
+
+        for depth in 0..6 {
+            for _ in 0..50 {
+                let term = sig.sample_term(depth, &mut r).expect("a term");
+                assert!(term
+                    .subterms()
+                    .iter()
+                    .all(|(_, place)| place.len() <= depth));
+            }
+        }
+
+        let config = TermSampleConfig {
+            variable_weight: 0.0,
+            operator_weights: vec![(a, 0.0)],
+        };
+        assert_eq!(sig.sample_term_with(&config, 4, &mut r), None);
+
+        let config = TermSampleConfig {
+            variable_weight: 0.0,
+            operator_weights: vec![(g, 0.0)],
+        };
+        for _ in 0..50 {
+            let term = sig.sample_term_with(&config, 4, &mut r).expect("a term");
+            assert!(term.variables().is_empty());
+            assert!(term.operators().iter().all(|op| *op == a || *op == f));
+        }
+
+        let config = TermSampleConfig {
+            variable_weight: 1.0,
+            operator_weights: vec![(a, 0.0)],
+        };
+        let term = sig.sample_term_with(&config, 0, &mut r).expect("a term");
+        assert_eq!(term, Term::Variable(x));
+    }
+
+    #[test]
+    fn sample_rule_test() {
+        let mut sig = Signature::default();
+        let a = sig.new_op(0, Some("A".to_string()));
+        let f = sig.new_op(2, Some("F".to_string()));
+        let g = sig.new_op(1, Some("G".to_string()));
+        sig.new_var(Some("x".to_string()));
+        sig.new_var(Some("y".to_string()));
+        let mut r = StdRng::from_seed([0; 32]);
+        let config = TermSampleConfig::default();
+
+        for _ in 0..50 {
+            let rule = sig.sample_rule(&f, &config, 3, &mut r).expect("a rule");
+            assert_eq!(rule.lhs.head(), Atom::from(f));
+            let lhs_vars = rule.lhs.variables();
+            assert!(rule.rhs[0].variables().iter().all(|v| lhs_vars.contains(v)));
+        }
+        assert_eq!(sig.sample_rule(&g, &config, 0, &mut r), None);
+        let rule = sig.sample_rule(&a, &config, 0, &mut r).expect("a rule");
+        assert_eq!(rule.display(&sig), "A = A");
+
+        let config = TermSampleConfig {
+            variable_weight: 1.0,
+            operator_weights: vec![(a, 0.0)],
+        };
+        assert_eq!(sig.sample_trs(2, &config, 0, &mut r), None);
+        let trs = sig.sample_trs(5, &config, 2, &mut r).expect("a trs");
+        assert_eq!(trs.len(), 5);
+        let excluded = &config.operator_weights[0].0;
+        assert!(trs
+            .rules
+            .iter()
+            .all(|rule| !rule.operators().contains(excluded)));
+    }
+
+    #[test]
+    fn crossover_test() {
+        let mut sig = Signature::default();
+        let t = parse_term(&mut sig, "PAIR(F(A x_) G(B))").expect("parse of PAIR(F(A x_) G(B))");
+        let (a, b) = (&t.args()[0], &t.args()[1]);
+        let mut r = StdRng::from_seed([0; 32]);
+
+        let atoms: Vec<Atom> = a.atoms().into_iter().chain(b.atoms()).collect();
+        for _ in 0..50 {
+            let (c, d) = Term::crossover(a, b, &mut r);
+            assert_eq!(c.size() + d.size(), a.size() + b.size());
+            let mut offspring_atoms: Vec<Atom> = c.atoms().into_iter().chain(d.atoms()).collect();
+            offspring_atoms.retain(|atom| !atoms.contains(atom));
+            assert!(offspring_atoms.is_empty());
+        }
+
+        let (_, terms) = parse(
+            &mut sig,
+            "ZERO : Nat;
+            TRUE : Bool;
+            NOT : Bool -> Bool;
+            SUCC : Nat -> Nat;
+            ISZERO : Nat -> Bool;
+            NOT(NOT(TRUE));
+            SUCC(SUCC(ZERO));
+            NOT(ISZERO(SUCC(ZERO)));",
+        )
+        .expect("parse of sorted terms");
+        assert_eq!(
+            Term::sorted_crossover(&sig, &terms[0], &terms[1], &mut r),
+            None
+        );
+        for _ in 0..50 {
+            let (c, d) =
+                Term::sorted_crossover(&sig, &terms[2], &terms[1], &mut r).expect("offspring");
+            assert_eq!(c.sort(&sig), Ok(Some("Bool".to_string())));
+            assert_eq!(d.sort(&sig), Ok(Some("Nat".to_string())));
+        }
+    }
+
+    #[test]
+    fn pcfg_test() {
+        let mut sig = Signature::default();
+        let z = sig.new_op(0, Some("Z".to_string()));
+        let s = sig.new_op(1, Some("S".to_string()));
+        let x = sig.new_var(Some("x".to_string()));
+        let mut r = StdRng::from_seed([0; 32]);
+
+        assert_eq!(TermPcfg::new(vec![(Atom::from(s), 1.0)]), None);
+        assert_eq!(
+            TermPcfg::new(vec![(Atom::from(s), 1.0), (Atom::from(z), 0.0)]),
+            None
+        );
+
+        let pcfg = TermPcfg::new(vec![(Atom::from(z), 1.0), (Atom::from(s), 1.0)]).expect("a pcfg");
+        assert_eq!(pcfg.probability(&Atom::from(x)), 0.0);
+        assert_eq!(pcfg.log_prob(&Term::Variable(x)), f64::NEG_INFINITY);
+
+        // the empirical frequency of each sample should match its probability
+        let mut zs = 0;
+        for _ in 0..2000 {
+            let term = pcfg.sample(&mut r).expect("a term");
+            if term.size() == 1 {
+                zs += 1;
+                assert_eq!(pcfg.log_prob(&term), 0.5f64.ln());
+            }
+        }
+        assert!(zs > 900 && zs < 1100);
+
+        let mut shallow = pcfg.clone();
+        shallow.max_depth = 0;
+        let samples: Vec<Option<Term>> = (0..100).map(|_| shallow.sample(&mut r)).collect();
+        assert!(samples.iter().any(Option::is_none));
+        assert!(samples.iter().flatten().all(|t| t.size() == 1));
+    }
+
+    #[test]
+    fn choose_weighted_test() {
+        let mut r = StdRng::from_seed([0; 32]);
+        assert_eq!(choose_weighted(&[], &mut r), None);
+        assert_eq!(choose_weighted(&[0.0, -1.0], &mut r), None);
+        for _ in 0..20 {
+            assert_eq!(choose_weighted(&[0.0, 2.0, 0.0], &mut r), Some(1));
+        }
+        let mut counts = [0; 2];
+        for _ in 0..1000 {
+            counts[choose_weighted(&[1.0, 3.0], &mut r).expect("choice")] += 1;
+        }
+        assert!(counts[0] > 150 && counts[0] < 350);
+    }
+}
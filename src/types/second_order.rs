@@ -0,0 +1,265 @@
+use super::{Context, Operator, Signature, Substitution, Term, Variable};
+
+/// A pattern for second-order matching: a [`Term`] which may also contain context
+/// metavariables. A metavariable `F` applied to a pattern `p`, written `F[p]`, matches any
+/// [`Term`] `C[t]` such that `C` is a [`Context`] with one hole and `t` matches `p`.
+///
+/// Metavariables are identified by unary [`Operator`]s, so patterns can be written with the
+/// parser and converted with [`MetaPattern::from_term`].
+///
+/// [`Term`]: enum.Term.html
+/// [`Context`]: enum.Context.html
+/// [`Operator`]: struct.Operator.html
+/// [`MetaPattern::from_term`]: enum.MetaPattern.html#method.from_term
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{MetaPattern, Signature, parse_term};
+/// let mut sig = Signature::default();
+/// let c = sig.new_op(1, Some("C".to_string()));
+///
+/// let t = parse_term(&mut sig, "PAIR(C(H(x_)) G(H(A) B))").expect("parse of patterns");
+/// let (pattern, term) = (&t.args()[0], &t.args()[1]);
+///
+/// let pattern = MetaPattern::from_term(pattern, &[c]);
+///
+/// assert_eq!(pattern.display(&sig), "C[H(x_)]");
+///
+/// let matches = pattern.matches(term);
+///
+/// assert_eq!(matches.len(), 1);
+/// assert_eq!(matches[0].contexts[0].1.display(&sig), "G([!] B)");
+/// assert_eq!(matches[0].substitution.display(&sig), "{x_ := A}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetaPattern {
+    /// A first-order [`Variable`], matching any [`Term`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Term`]: enum.Term.html
+    Variable(Variable),
+    /// An [`Operator`] applied to patterns.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    Application {
+        op: Operator,
+        args: Vec<MetaPattern>,
+    },
+    /// A context metavariable applied to a pattern.
+    Context {
+        meta: Operator,
+        arg: Box<MetaPattern>,
+    },
+}
+
+/// A second-order matcher found by [`MetaPattern::matches`].
+///
+/// [`MetaPattern::matches`]: enum.MetaPattern.html#method.matches
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MetaMatch {
+    /// The binding of each first-order [`Variable`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub substitution: Substitution,
+    /// The binding of each metavariable to a [`Context`] with one hole.
+    ///
+    /// [`Context`]: enum.Context.html
+    pub contexts: Vec<(Operator, Context)>,
+}
+impl MetaMatch {
+    /// The [`Context`] bound to the metavariable `meta`, if any.
+    ///
+    /// [`Context`]: enum.Context.html
+    pub fn context(&self, meta: &Operator) -> Option<&Context> {
+        self.contexts
+            .iter()
+            .find(|(m, _)| m == meta)
+            .map(|(_, context)| context)
+    }
+}
+
+impl MetaPattern {
+    /// Convert `term` into a `MetaPattern`, treating each application of one of the unary
+    /// [`Operator`]s in `metavariables` as an application of a context metavariable.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    pub fn from_term(term: &Term, metavariables: &[Operator]) -> MetaPattern {
+        match *term {
+            Term::Variable(ref v) => MetaPattern::Variable(*v),
+            Term::Application { ref op, ref args }
+                if args.len() == 1 && metavariables.contains(op) =>
+            {
+                MetaPattern::Context {
+                    meta: *op,
+                    arg: Box::new(MetaPattern::from_term(&args[0], metavariables)),
+                }
+            }
+            Term::Application { ref op, ref args } => MetaPattern::Application {
+                op: *op,
+                args: args
+                    .iter()
+                    .map(|arg| MetaPattern::from_term(arg, metavariables))
+                    .collect(),
+            },
+        }
+    }
+    /// Serialize a `MetaPattern`, writing metavariable applications as `F[p]`.
+    pub fn display(&self, sig: &Signature) -> String {
+        match *self {
+            MetaPattern::Variable(ref v) => v.display(sig),
+            MetaPattern::Application { ref op, ref args } if args.is_empty() => op.display(sig),
+            MetaPattern::Application { ref op, ref args } => {
+                let args: Vec<String> = args.iter().map(|arg| arg.display(sig)).collect();
+                format!("{}({})", op.display(sig), args.join(" "))
+            }
+            MetaPattern::Context { ref meta, ref arg } => {
+                format!("{}[{}]", meta.display(sig), arg.display(sig))
+            }
+        }
+    }
+    /// Every second-order matcher of the `MetaPattern` against `term`: every [`MetaMatch`]
+    /// under which [`MetaPattern::instantiate`] gives `term`. A metavariable occurring more
+    /// than once must be bound to the same [`Context`] each time.
+    ///
+    /// [`MetaMatch`]: struct.MetaMatch.html
+    /// [`MetaPattern::instantiate`]: enum.MetaPattern.html#method.instantiate
+    /// [`Context`]: enum.Context.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{MetaPattern, Signature, parse_term};
+    /// let mut sig = Signature::default();
+    /// let c = sig.new_op(1, Some("C".to_string()));
+    ///
+    /// let t = parse_term(&mut sig, "PAIR(C(x_) F(A G(B)))").expect("parse of patterns");
+    /// let (pattern, term) = (&t.args()[0], &t.args()[1]);
+    ///
+    /// let pattern = MetaPattern::from_term(pattern, &[c]);
+    /// let contexts: Vec<String> = pattern
+    ///     .matches(term)
+    ///     .iter()
+    ///     .map(|m| m.contexts[0].1.display(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(contexts, vec!["[!]", "F([!] G(B))", "F(A [!])", "F(A G([!]))"]);
+    /// ```
+    pub fn matches(&self, term: &Term) -> Vec<MetaMatch> {
+        self.match_with(term, MetaMatch::default())
+    }
+    fn match_with(&self, term: &Term, m: MetaMatch) -> Vec<MetaMatch> {
+        match (self, term) {
+            (MetaPattern::Variable(v), _) => match m.substitution.get(v) {
+                Some(t) if t != term => vec![],
+                Some(_) => vec![m],
+                None => {
+                    let mut m = m;
+                    m.substitution.insert(*v, term.clone());
+                    vec![m]
+                }
+            },
+            (
+                MetaPattern::Application { op, args },
+                Term::Application {
+                    op: t_op,
+                    args: t_args,
+                },
+            ) if op == t_op && args.len() == t_args.len() => {
+                args.iter().zip(t_args).fold(vec![m], |ms, (arg, t_arg)| {
+                    ms.into_iter()
+                        .flat_map(|m| arg.match_with(t_arg, m))
+                        .collect()
+                })
+            }
+            (MetaPattern::Application { .. }, _) => vec![],
+            (MetaPattern::Context { meta, arg }, _) => term
+                .subterms()
+                .into_iter()
+                .flat_map(|(subterm, place)| {
+                    let context = Context::from_term_with_hole(term, &place).expect("context");
+                    let mut m = m.clone();
+                    match m.context(meta) {
+                        Some(bound) if *bound != context => return vec![],
+                        Some(_) => (),
+                        None => m.contexts.push((*meta, context)),
+                    }
+                    arg.match_with(subterm, m)
+                })
+                .collect(),
+        }
+    }
+    /// Instantiate the `MetaPattern` with the bindings of `m`, filling the [`Context`] of each
+    /// metavariable. Returns `None` if some [`Variable`] or metavariable is unbound.
+    ///
+    /// [`Context`]: enum.Context.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{MetaPattern, Signature, parse_term};
+    /// let mut sig = Signature::default();
+    /// let c = sig.new_op(1, Some("C".to_string()));
+    ///
+    /// // the schema C[NOT(NOT(x_))] => C[x_]
+    /// let t = parse_term(&mut sig, "PAIR(C(NOT(NOT(x_))) C(x_))").expect("parse of schema");
+    /// let lhs = MetaPattern::from_term(&t.args()[0], &[c.clone()]);
+    /// let rhs = MetaPattern::from_term(&t.args()[1], &[c]);
+    ///
+    /// let term = parse_term(&mut sig, "AND(A NOT(NOT(B)))").expect("parse of AND(A NOT(NOT(B)))");
+    /// let m = &lhs.matches(&term)[0];
+    ///
+    /// assert_eq!(rhs.instantiate(m).expect("instance").display(&sig), "AND(A B)");
+    /// ```
+    pub fn instantiate(&self, m: &MetaMatch) -> Option<Term> {
+        match *self {
+            MetaPattern::Variable(ref v) => m.substitution.get(v).cloned(),
+            MetaPattern::Application { ref op, ref args } => Some(Term::Application {
+                op: *op,
+                args: args
+                    .iter()
+                    .map(|arg| arg.instantiate(m))
+                    .collect::<Option<Vec<_>>>()?,
+            }),
+            MetaPattern::Context { ref meta, ref arg } => {
+                m.context(meta)?.fill(&[arg.instantiate(m)?])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn second_order_match_test() {
+        let mut sig = Signature::default();
+        let c = sig.new_op(1, Some("C".to_string()));
+        let t = parse_term(
+            &mut sig,
+            "PAIR(F(C(x_) C(x_)) F(G(A) G(A)) F(G(A) H(A)) F(G(A) G(B)))",
+        )
+        .expect("parse of terms");
+        let args = t.args();
+        let pattern = MetaPattern::from_term(&args[0], ::std::slice::from_ref(&c));
+
+        assert_eq!(pattern.display(&sig), "F(C[x_] C[x_])");
+
+        // C := [!] with x_ := G(A), or C := G([!]) with x_ := A
+        let matches = pattern.matches(&args[1]);
+        assert_eq!(matches.len(), 2);
+        for m in &matches {
+            assert_eq!(pattern.instantiate(m), Some(args[1].clone()));
+        }
+
+        assert!(pattern.matches(&args[2]).is_empty());
+        assert!(pattern.matches(&args[3]).is_empty());
+
+        assert_eq!(pattern.instantiate(&MetaMatch::default()), None);
+        assert_eq!(matches[0].context(&c), Some(&Context::Hole));
+    }
+}
@@ -0,0 +1,333 @@
+use super::{Atom, Operator, Rule, Term, Variable};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// A [`Term`] stored as a directed acyclic graph in which equal subterms are a single node.
+///
+/// `SharedTerm`s are created by a [`TermPool`], which hash-conses them: within a pool, equal
+/// `SharedTerm`s are the same node, so they are compared and hashed in constant time, and a
+/// subterm duplicated by rewriting is shared rather than copied. `SharedTerm`s from different
+/// [`TermPool`]s are never equal.
+///
+/// [`Term`]: enum.Term.html
+/// [`TermPool`]: struct.TermPool.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, TermPool, parse_term};
+/// let mut sig = Signature::default();
+/// let t = parse_term(&mut sig, "F(G(A) G(A))").expect("parse of F(G(A) G(A))");
+///
+/// let mut pool = TermPool::new();
+/// let shared = pool.intern(&t);
+/// let args = shared.args();
+///
+/// assert_eq!(args[0], args[1]);
+/// assert_eq!(shared.size(), 5);
+/// assert_eq!(shared.dag_size(), 3);
+/// assert_eq!(shared.to_term(), t);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedTerm(Rc<Node>);
+
+// The contents of a SharedTerm, which are also the keys of a TermPool.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Node {
+    Variable(Variable),
+    Application { op: Operator, args: Vec<SharedTerm> },
+}
+
+impl SharedTerm {
+    /// The head of the `SharedTerm`.
+    pub fn head(&self) -> Atom {
+        match *self.0 {
+            Node::Variable(ref v) => Atom::Variable(*v),
+            Node::Application { ref op, .. } => Atom::Operator(*op),
+        }
+    }
+    /// The arguments of the `SharedTerm`, which are empty for a [`Variable`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub fn args(&self) -> &[SharedTerm] {
+        match *self.0 {
+            Node::Variable(_) => &[],
+            Node::Application { ref args, .. } => args,
+        }
+    }
+    /// The size of the [`Term`] the `SharedTerm` represents, saturating at `usize::MAX`.
+    ///
+    /// [`Term`]: enum.Term.html
+    pub fn size(&self) -> usize {
+        self.size_with(&mut HashMap::new())
+    }
+    fn size_with(&self, sizes: &mut HashMap<SharedTerm, usize>) -> usize {
+        if let Some(&size) = sizes.get(self) {
+            return size;
+        }
+        let size = self.args().iter().fold(1usize, |size, arg| {
+            size.saturating_add(arg.size_with(sizes))
+        });
+        sizes.insert(self.clone(), size);
+        size
+    }
+    /// The number of distinct nodes in the `SharedTerm`.
+    pub fn dag_size(&self) -> usize {
+        let mut seen = HashMap::new();
+        let mut stack = vec![self];
+        while let Some(t) = stack.pop() {
+            if seen.insert(t.clone(), ()).is_none() {
+                stack.extend(t.args());
+            }
+        }
+        seen.len()
+    }
+    /// Unfold the `SharedTerm` into a [`Term`], which may be exponentially larger.
+    ///
+    /// [`Term`]: enum.Term.html
+    pub fn to_term(&self) -> Term {
+        match *self.0 {
+            Node::Variable(ref v) => Term::Variable(*v),
+            Node::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args.iter().map(SharedTerm::to_term).collect(),
+            },
+        }
+    }
+    /// Match `pattern` against the `SharedTerm`, giving the `SharedTerm` to which each
+    /// [`Variable`] of `pattern` is bound, if any. Repeated [`Variable`]s are checked in
+    /// constant time.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermPool, parse_term};
+    /// let mut sig = Signature::default();
+    /// let pattern = parse_term(&mut sig, "F(x_ x_)").expect("parse of F(x_ x_)");
+    /// let t1 = parse_term(&mut sig, "F(G(A) G(A))").expect("parse of F(G(A) G(A))");
+    /// let t2 = parse_term(&mut sig, "F(G(A) A)").expect("parse of F(G(A) A)");
+    ///
+    /// let mut pool = TermPool::new();
+    /// let t1 = pool.intern(&t1);
+    /// let t2 = pool.intern(&t2);
+    ///
+    /// let sub = t1.pmatch(&pattern).expect("match");
+    /// assert_eq!(sub[&pattern.variables()[0]].to_term().display(&sig), "G(A)");
+    /// assert!(t2.pmatch(&pattern).is_none());
+    /// ```
+    pub fn pmatch<'a>(&self, pattern: &'a Term) -> Option<HashMap<&'a Variable, SharedTerm>> {
+        let mut sub = HashMap::new();
+        if self.pmatch_with(pattern, &mut sub) {
+            Some(sub)
+        } else {
+            None
+        }
+    }
+    fn pmatch_with<'a>(
+        &self,
+        pattern: &'a Term,
+        sub: &mut HashMap<&'a Variable, SharedTerm>,
+    ) -> bool {
+        match (pattern, &*self.0) {
+            (Term::Variable(v), _) => sub.entry(v).or_insert_with(|| self.clone()) == self,
+            (
+                Term::Application { op, args },
+                Node::Application {
+                    op: op2,
+                    args: args2,
+                },
+            ) => {
+                op == op2
+                    && args.len() == args2.len()
+                    && args.iter().zip(args2).all(|(p, t)| t.pmatch_with(p, sub))
+            }
+            _ => false,
+        }
+    }
+}
+// `SharedTerm`s are equal when they are the same node.
+impl PartialEq for SharedTerm {
+    fn eq(&self, other: &SharedTerm) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+impl Eq for SharedTerm {}
+impl Hash for SharedTerm {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (&*self.0 as *const Node).hash(state);
+    }
+}
+
+/// Creates [`SharedTerm`]s, keeping one node for each distinct [`Term`] it has seen.
+///
+/// Nodes live as long as the pool, so a long-running computation may want a fresh pool from
+/// time to time.
+///
+/// [`SharedTerm`]: struct.SharedTerm.html
+/// [`Term`]: enum.Term.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, TermPool, parse_rule, parse_term};
+/// let mut sig = Signature::default();
+/// let rule = parse_rule(&mut sig, "D(x_) = P(x_ x_)").expect("parse of D(x_) = P(x_ x_)");
+/// let a = parse_term(&mut sig, "A").expect("parse of A");
+/// let d = sig.operator("D", 1).expect("D");
+///
+/// // Each step doubles the size of the term, but adds one node to its graph.
+/// let mut pool = TermPool::new();
+/// let mut shared = pool.intern(&a);
+/// for _ in 0..40 {
+///     let redex = pool.application(d.clone(), vec![shared]).expect("D(...)");
+///     shared = pool.rewrite_root(&rule, &redex).expect("rewrite").remove(0);
+/// }
+///
+/// assert_eq!(shared.size(), (1 << 41) - 1);
+/// assert_eq!(shared.dag_size(), 41);
+/// ```
+#[derive(Debug, Default)]
+pub struct TermPool {
+    nodes: HashMap<Node, SharedTerm>,
+}
+impl TermPool {
+    /// Create an empty `TermPool`.
+    pub fn new() -> TermPool {
+        TermPool::default()
+    }
+    /// The number of nodes in the `TermPool`.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    /// Does the `TermPool` have no nodes?
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    fn node(&mut self, node: Node) -> SharedTerm {
+        self.nodes
+            .entry(node)
+            .or_insert_with_key(|node| SharedTerm(Rc::new(node.clone())))
+            .clone()
+    }
+    /// The [`SharedTerm`] for a [`Variable`].
+    ///
+    /// [`SharedTerm`]: struct.SharedTerm.html
+    /// [`Variable`]: struct.Variable.html
+    pub fn variable(&mut self, var: Variable) -> SharedTerm {
+        self.node(Node::Variable(var))
+    }
+    /// The [`SharedTerm`] applying `op` to `args`, or `None` if the number of `args` differs
+    /// from the arity of `op`.
+    ///
+    /// [`SharedTerm`]: struct.SharedTerm.html
+    pub fn application(&mut self, op: Operator, args: Vec<SharedTerm>) -> Option<SharedTerm> {
+        if op.arity() as usize == args.len() {
+            Some(self.node(Node::Application { op, args }))
+        } else {
+            None
+        }
+    }
+    /// The [`SharedTerm`] for a [`Term`].
+    ///
+    /// [`SharedTerm`]: struct.SharedTerm.html
+    /// [`Term`]: enum.Term.html
+    pub fn intern(&mut self, term: &Term) -> SharedTerm {
+        match *term {
+            Term::Variable(ref v) => self.variable(*v),
+            Term::Application { ref op, ref args } => {
+                let args = args.iter().map(|arg| self.intern(arg)).collect();
+                self.node(Node::Application { op: *op, args })
+            }
+        }
+    }
+    /// Replace the [`Variable`]s of `term` bound by `sub`, sharing the bound `SharedTerm`s
+    /// rather than copying them.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub fn instantiate(&mut self, term: &Term, sub: &HashMap<&Variable, SharedTerm>) -> SharedTerm {
+        match *term {
+            Term::Variable(ref v) => match sub.get(v) {
+                Some(t) => t.clone(),
+                None => self.variable(*v),
+            },
+            Term::Application { ref op, ref args } => {
+                let args = args.iter().map(|arg| self.instantiate(arg, sub)).collect();
+                self.node(Node::Application { op: *op, args })
+            }
+        }
+    }
+    /// Rewrite `term` at its root by an unconditional `rule`, giving one `SharedTerm` per
+    /// right-hand side, or `None` if `rule` doesn't apply.
+    pub fn rewrite_root(&mut self, rule: &Rule, term: &SharedTerm) -> Option<Vec<SharedTerm>> {
+        if !rule.conditions.is_empty() {
+            return None;
+        }
+        let sub = term.pmatch(&rule.lhs)?;
+        Some(
+            rule.rhs
+                .iter()
+                .map(|rhs| self.instantiate(rhs, &sub))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn intern_shares_test() {
+        let mut sig = Signature::default();
+        let t =
+            parse_term(&mut sig, "F(G(x_ A) G(x_ A) x_)").expect("parse of F(G(x_ A) G(x_ A) x_)");
+        let mut pool = TermPool::new();
+
+        let shared = pool.intern(&t);
+        let again = pool.intern(&t);
+
+        assert_eq!(shared, again);
+        assert_eq!(pool.len(), 4);
+        assert_eq!(shared.dag_size(), 4);
+        assert_eq!(shared.size(), 8);
+        assert_eq!(shared.to_term(), t);
+        assert_eq!(shared.to_term(), t);
+        assert_eq!(shared.args()[2], shared.args()[0].args()[0]);
+
+        let other = TermPool::new().intern(&t);
+        assert_ne!(shared, other);
+    }
+
+    #[test]
+    fn rewrite_root_test() {
+        let mut sig = Signature::default();
+        let rule = parse_rule(&mut sig, "F(x_ y_) = G(y_ x_ y_) | y_")
+            .expect("parse of F(x_ y_) = G(y_ x_ y_) | y_");
+        let conditional = parse_rule(&mut sig, "F(x_ y_) = x_ <= x_ = A")
+            .expect("parse of F(x_ y_) = x_ <= x_ = A");
+        let t = parse_term(&mut sig, "F(A H(B))").expect("parse of F(A H(B))");
+        let mut pool = TermPool::new();
+        let t = pool.intern(&t);
+
+        let rewrites = pool.rewrite_root(&rule, &t).expect("rewrites");
+
+        assert_eq!(rewrites.len(), 2);
+        assert_eq!(rewrites[0].to_term().display(&sig), "G(H(B) A H(B))");
+        assert_eq!(rewrites[0].args()[0], rewrites[1]);
+        assert_eq!(rewrites[1], t.args()[1]);
+        assert!(pool.rewrite_root(&conditional, &t).is_none());
+        assert!(pool.rewrite_root(&rule, &rewrites[1]).is_none());
+
+        let op = match t.head() {
+            Atom::Operator(op) => op,
+            Atom::Variable(_) => unreachable!(),
+        };
+        assert!(pool.application(op, vec![]).is_none());
+        assert_eq!(pool.application(op, t.args().to_vec()), Some(t));
+    }
+}
@@ -1,4 +1,6 @@
-use super::{Atom, Context, Operator, Rule, Term, Variable, TRS};
+use super::super::parser::quote_name;
+use super::super::pretty::DisplayStyle;
+use super::{Atom, Context, Operator, OperatorAttributes, OperatorSort, Rule, Term, Variable, TRS};
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
@@ -61,7 +63,7 @@ impl Signature {
     /// ]);
     /// let ops = sig.operators();
     ///
-    /// let op_names: Vec<String> = ops.iter().map(|op| op.display()).collect();
+    /// let op_names: Vec<String> = ops.iter().map(|op| op.display(&sig)).collect();
     /// assert_eq!(op_names, vec![".", "S", "K"]);
     ///
     /// let mut sig2 = Signature::default();
@@ -96,21 +98,13 @@ impl Signature {
     ///     (0, Some("K".to_string())),
     /// ]);
     ///
-    /// let ops: Vec<String> = sig.operators().iter().map(|op| op.display()).collect();;
+    /// let ops: Vec<String> = sig.operators().iter().map(|op| op.display(&sig)).collect();;
     ///
     /// assert_eq!(ops, vec![".", "S", "K"]);
     ///```
     pub fn operators(&self) -> Vec<Operator> {
-        self.sig
-            .read()
-            .expect("poisoned signature")
-            .operators()
-            .into_iter()
-            .map(|id| Operator {
-                id,
-                sig: self.clone(),
-            })
-            .collect()
+        let sig = self.sig.read().expect("poisoned signature");
+        sig.operators().into_iter().map(|id| sig.op(id)).collect()
     }
     /// Returns every [`Variable`] known to the `Signature`, in the order they were created.
     ///
@@ -128,7 +122,7 @@ impl Signature {
     ///
     /// parse_term(&mut sig, "A(x_ y_)").expect("parse of A(x_ y_)");
     ///
-    /// let vars: Vec<String> = sig.variables().iter().map(|v| v.display()).collect();
+    /// let vars: Vec<String> = sig.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(vars, vec!["x_", "y_"]);
     ///```
@@ -138,10 +132,7 @@ impl Signature {
             .expect("poisoned signature")
             .variables()
             .into_iter()
-            .map(|id| Variable {
-                id,
-                sig: self.clone(),
-            })
+            .map(|id| Variable { id })
             .collect()
     }
     /// Returns every [`Atom`] known to the `Signature`.
@@ -156,7 +147,7 @@ impl Signature {
     ///
     /// parse_term(&mut sig, "A(x_ B(y_))").expect("parse of A(x_ B(y_))");
     ///
-    /// let atoms: Vec<String> = sig.atoms().iter().map(|a| a.display()).collect();
+    /// let atoms: Vec<String> = sig.atoms().iter().map(|a| a.display(&sig)).collect();
     ///
     /// assert_eq!(atoms, vec!["x_", "y_", "B", "A"]);
     /// ```
@@ -165,6 +156,107 @@ impl Signature {
         let ops = self.operators().into_iter().map(Atom::Operator);
         vars.chain(ops).collect()
     }
+    /// Returns the [`Operator`] named `name` with arity `arity` which has the lowest `id`, if
+    /// there is one.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::new(vec![
+    ///     (2, Some(".".to_string())),
+    ///     (0, Some("S".to_string())),
+    ///     (1, Some("S".to_string())),
+    /// ]);
+    ///
+    /// assert_eq!(sig.operator("S", 1).map(|op| op.arity()), Some(1));
+    /// assert!(sig.operator("S", 2).is_none());
+    /// assert!(sig.operator("K", 0).is_none());
+    /// ```
+    pub fn operator(&self, name: &str, arity: u32) -> Option<Operator> {
+        let id = self
+            .sig
+            .read()
+            .expect("poisoned signature")
+            .find_op(name, arity)?;
+        Some(Operator { id, arity })
+    }
+    /// Returns every [`Operator`] with arity `arity`, in the order they were created.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::Signature;
+    /// let mut sig = Signature::new(vec![
+    ///     (2, Some(".".to_string())),
+    ///     (0, Some("S".to_string())),
+    ///     (0, Some("K".to_string())),
+    /// ]);
+    ///
+    /// let ops: Vec<String> = sig.operators_with_arity(0).iter().map(|op| op.display(&sig)).collect();
+    ///
+    /// assert_eq!(ops, vec!["S", "K"]);
+    /// ```
+    pub fn operators_with_arity(&self, arity: u32) -> Vec<Operator> {
+        self.sig
+            .read()
+            .expect("poisoned signature")
+            .by_arity
+            .get(&arity)
+            .map_or_else(Vec::new, |ids| {
+                ids.iter().map(|&id| Operator { id, arity }).collect()
+            })
+    }
+    /// Returns the [`Variable`] named `name` which has the lowest `id`, if there is one.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// parse_term(&mut sig, "A(x_ y_)").expect("parse of A(x_ y_)");
+    ///
+    /// assert_eq!(sig.variable("y").map(|v| v.display(&sig)), Some("y_".to_string()));
+    /// assert!(sig.variable("z").is_none());
+    /// ```
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        let id = *self
+            .sig
+            .read()
+            .expect("poisoned signature")
+            .var_names
+            .get(name)?
+            .first()?;
+        Some(Variable { id })
+    }
+    /// Does the `Signature` have an [`Operator`] or a [`Variable`] named `name`?
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// parse_term(&mut sig, "A(x_ B)").expect("parse of A(x_ B)");
+    ///
+    /// assert!(sig.contains("A"));
+    /// assert!(sig.contains("x"));
+    /// assert!(!sig.contains("C"));
+    /// ```
+    pub fn contains(&self, name: &str) -> bool {
+        let sig = self.sig.read().expect("poisoned signature");
+        sig.op_names.contains_key(name) || sig.var_names.contains_key(name)
+    }
     /// Create a new [`Operator`] distinct from all existing [`Operator`]s.
     ///
     /// [`Operator`]: struct.Operator.html
@@ -189,9 +281,167 @@ impl Signature {
             .write()
             .expect("poisoned signature")
             .new_op(arity, name);
-        Operator {
-            id,
-            sig: self.clone(),
+        Operator { id, arity }
+    }
+    /// Create a new [`Operator`] with the given [`OperatorAttributes`].
+    ///
+    /// See [`Signature::set_attributes`] for more information.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`OperatorAttributes`]: struct.OperatorAttributes.html
+    /// [`Signature::set_attributes`]: #method.set_attributes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorAttributes};
+    /// let mut sig = Signature::default();
+    /// let zero = sig.new_op(0, Some("ZERO".to_string()));
+    ///
+    /// let attrs = OperatorAttributes {
+    ///     associative: true,
+    ///     commutative: true,
+    ///     identity: Some(zero.clone()),
+    /// };
+    /// let plus = sig.new_op_with_attrs(2, Some("PLUS".to_string()), attrs);
+    ///
+    /// assert!(plus.is_associative(&sig));
+    /// assert!(plus.is_commutative(&sig));
+    /// assert_eq!(plus.identity(&sig), Some(zero));
+    /// ```
+    pub fn new_op_with_attrs(
+        &mut self,
+        arity: u32,
+        name: Option<String>,
+        attributes: OperatorAttributes,
+    ) -> Operator {
+        let op = self.new_op(arity, name);
+        self.set_attributes(&op, attributes);
+        op
+    }
+    /// Record the [`OperatorAttributes`] of `op`, replacing any it had.
+    ///
+    /// Associativity, commutativity, and identities only make sense for binary [`Operator`]s,
+    /// so the attributes of other [`Operator`]s are ignored, as is an identity which is not a
+    /// constant of this `Signature`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`OperatorAttributes`]: struct.OperatorAttributes.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorAttributes};
+    /// let mut sig = Signature::default();
+    /// let f = sig.new_op(2, Some("F".to_string()));
+    /// let g = sig.new_op(1, Some("G".to_string()));
+    ///
+    /// let attrs = OperatorAttributes { commutative: true, ..OperatorAttributes::default() };
+    /// sig.set_attributes(&f, attrs.clone());
+    /// sig.set_attributes(&g, attrs);
+    ///
+    /// assert!(f.is_commutative(&sig));
+    /// assert!(!g.is_commutative(&sig));
+    /// ```
+    pub fn set_attributes(&mut self, op: &Operator, attributes: OperatorAttributes) {
+        let mut sig = self.sig.write().expect("poisoned signature");
+        if op.id >= sig.operators.len() || sig.operators[op.id].0 != 2 {
+            return;
+        }
+        let identity = attributes.identity.and_then(|e| {
+            if e.id < sig.operators.len() && sig.operators[e.id].0 == 0 {
+                Some(e.id)
+            } else {
+                None
+            }
+        });
+        if attributes.associative || attributes.commutative || identity.is_some() {
+            sig.attributes.insert(
+                op.id,
+                (attributes.associative, attributes.commutative, identity),
+            );
+        } else {
+            sig.attributes.remove(&op.id);
+        }
+    }
+    /// Declare the [`OperatorSort`] of `op`, replacing any it had. A sort whose number of
+    /// arguments differs from the arity of `op` is ignored.
+    ///
+    /// [`OperatorSort`]: struct.OperatorSort.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorSort};
+    /// let mut sig = Signature::default();
+    /// let eq = sig.new_op(2, Some("EQ".to_string()));
+    ///
+    /// let sort = OperatorSort { args: vec!["Nat".to_string()], result: "Bool".to_string() };
+    /// sig.set_sort(&eq, sort);
+    ///
+    /// assert_eq!(eq.sort(&sig), None);
+    ///
+    /// let sort = OperatorSort {
+    ///     args: vec!["Nat".to_string(), "Nat".to_string()],
+    ///     result: "Bool".to_string(),
+    /// };
+    /// sig.set_sort(&eq, sort);
+    ///
+    /// assert_eq!(eq.sort(&sig).unwrap().display(), "Nat -> Nat -> Bool");
+    /// ```
+    pub fn set_sort(&mut self, op: &Operator, sort: OperatorSort) {
+        let mut sig = self.sig.write().expect("poisoned signature");
+        if op.id < sig.operators.len() && sig.operators[op.id].0 as usize == sort.args.len() {
+            sig.sorts.insert(op.id, (sort.args, sort.result));
+        }
+    }
+    /// Record the [`DisplayStyle`] with which [`Term::pretty`] and its relatives write
+    /// applications of `op`, replacing any it had. An [`Infix`] style for an [`Operator`] which
+    /// isn't binary is ignored, as is a [`Mixfix`] template whose number of `_`s differs from
+    /// the arity of `op`.
+    ///
+    /// Arguments written with a style are parenthesized, except that the right argument of an
+    /// associative [`Operator`] applied to itself is not. Output using styles doesn't parse.
+    ///
+    /// [`DisplayStyle`]: enum.DisplayStyle.html
+    /// [`Term::pretty`]: enum.Term.html#method.pretty
+    /// [`Infix`]: enum.DisplayStyle.html#variant.Infix
+    /// [`Operator`]: struct.Operator.html
+    /// [`Mixfix`]: enum.DisplayStyle.html#variant.Mixfix
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, DisplayStyle, parse_term};
+    /// let mut sig = Signature::default();
+    /// let plus = sig.new_op(2, Some("PLUS".to_string()));
+    /// let times = sig.new_op(2, Some("TIMES".to_string()));
+    /// let ite = sig.new_op(3, Some("ITE".to_string()));
+    ///
+    /// sig.set_display_style(&plus, DisplayStyle::Infix("+".to_string()));
+    /// sig.set_display_style(&times, DisplayStyle::Infix("*".to_string()));
+    /// sig.set_display_style(&ite, DisplayStyle::Mixfix("if _ then _ else _".to_string()));
+    ///
+    /// let term = parse_term(&mut sig, "ITE(B PLUS(x_ TIMES(y_ z_)) F(x_))")
+    ///     .expect("parse of ITE(B PLUS(x_ TIMES(y_ z_)) F(x_))");
+    ///
+    /// assert_eq!(term.pretty(&sig), "if B then (x_ + (y_ * z_)) else F(x_)");
+    /// ```
+    pub fn set_display_style(&mut self, op: &Operator, style: DisplayStyle) {
+        let mut sig = self.sig.write().expect("poisoned signature");
+        if op.id >= sig.operators.len() {
+            return;
+        }
+        let arity = sig.operators[op.id].0 as usize;
+        match style {
+            DisplayStyle::Prefix => {
+                sig.styles.remove(&op.id);
+            }
+            DisplayStyle::Infix(_) if arity != 2 => (),
+            DisplayStyle::Mixfix(ref template) if template.matches('_').count() != arity => (),
+            style => {
+                sig.styles.insert(op.id, style);
+            }
         }
     }
     /// Create a new [`Variable`] distinct from all existing [`Variable`]s.
@@ -211,10 +461,7 @@ impl Signature {
     /// ```
     pub fn new_var(&mut self, name: Option<String>) -> Variable {
         let id = self.sig.write().expect("poisoned signature").new_var(name);
-        Variable {
-            id,
-            sig: self.clone(),
-        }
+        Variable { id }
     }
     /// Merge two `Signature`s. All [`Term`]s, [`Context`]s, [`Rule`]s, and [`TRS`]s associated
     /// with the `other` `Signature` should be `reified` using methods provided
@@ -245,7 +492,7 @@ impl Signature {
     ///
     /// sig1.merge(&sig2, MergeStrategy::DistinctOperators);
     ///
-    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display(&sig1)).collect();
     ///
     /// assert_eq!(ops, vec![".", "S", "K", "A", "B", "C"]);
     ///
@@ -264,7 +511,7 @@ impl Signature {
     ///
     /// sig1.merge(&sig2, MergeStrategy::SameOperators);
     ///
-    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display(&sig1)).collect();
     ///
     /// assert_eq!(ops, vec![".", "S", "K"]);
     ///
@@ -298,7 +545,7 @@ impl Signature {
     ///
     /// sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName);
     ///
-    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+    /// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display(&sig1)).collect();
     ///
     /// assert_eq!(ops, vec![".", "S", "K", "A", "B"]);
     /// ```
@@ -306,7 +553,79 @@ impl Signature {
         self.sig
             .write()
             .expect("poisoned signature")
-            .merge(&other, strategy)
+            .merge(other, strategy)
+    }
+    /// Create a new `Signature` combining `self` and `other`, leaving both unchanged. The new
+    /// `Signature` starts as a copy of `self`, into which `other` is merged as by
+    /// [`Signature::merge`]. The [`Operator`]s and [`Variable`]s of `self` are those of the new
+    /// `Signature`; the returned [`OpMap`] and [`VarMap`] send those of `other` to their
+    /// counterparts, so that [`Term`]s, [`Context`]s, [`Rule`]s, and [`TRS`]s associated with
+    /// `other` can be moved over with [`Term::map_signature`] and friends.
+    ///
+    /// [`Signature::merge`]: struct.Signature.html#method.merge
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`OpMap`]: type.OpMap.html
+    /// [`VarMap`]: type.VarMap.html
+    /// [`Term`]: enum.Term.html
+    /// [`Context`]: enum.Context.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS`]: struct.TRS.html
+    /// [`Term::map_signature`]: enum.Term.html#method.map_signature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{MergeStrategy, Signature, parse_trs};
+    /// let mut sig1 = Signature::default();
+    /// let trs1 = parse_trs(&mut sig1, "PLUS(ZERO x_) = x_;").expect("parse of PLUS(ZERO x_) = x_;");
+    /// let mut sig2 = Signature::default();
+    /// let trs2 = parse_trs(&mut sig2, "TIMES(ZERO x_) = ZERO;")
+    ///     .expect("parse of TIMES(ZERO x_) = ZERO;");
+    ///
+    /// let (sig, ops, vars) = sig1
+    ///     .union(&sig2, MergeStrategy::OperatorsByArityAndName)
+    ///     .unwrap();
+    ///
+    /// let names: Vec<String> = sig.operators().iter().map(|op| op.display(&sig)).collect();
+    /// assert_eq!(names, vec!["ZERO", "PLUS", "TIMES"]);
+    /// assert_eq!(sig1.operators().len(), 2);
+    /// assert_eq!(sig2.operators().len(), 2);
+    ///
+    /// let mut trs = trs1.clone();
+    /// let mapped = trs2.map_signature(&ops, &vars).expect("mapped TRS");
+    /// trs.rules_mut().extend_from_slice(mapped.rules());
+    ///
+    /// assert_eq!(trs.display(&sig), "PLUS(ZERO x_) = x_;\nTIMES(ZERO x_) = ZERO;");
+    /// ```
+    pub fn union(
+        &self,
+        other: &Signature,
+        strategy: MergeStrategy,
+    ) -> Result<(Signature, OpMap, VarMap), ()> {
+        let sig = Signature {
+            sig: Arc::new(RwLock::new(
+                self.sig.read().expect("poisoned signature").clone(),
+            )),
+        };
+        // merge a copy of `other`, which the merge drains
+        let copy = Signature {
+            sig: Arc::new(RwLock::new(
+                other.sig.read().expect("poisoned signature").clone(),
+            )),
+        };
+        let change = sig.merge(&copy, strategy)?;
+        let ops = other
+            .operators()
+            .into_iter()
+            .map(|op| (op, change.reify_operator(&op)))
+            .collect();
+        let vars = other
+            .variables()
+            .into_iter()
+            .map(|var| (var, change.reify_variable(&var)))
+            .collect();
+        Ok((sig, ops, vars))
     }
 }
 impl fmt::Debug for Signature {
@@ -324,10 +643,12 @@ impl Default for Signature {
 }
 impl PartialEq for Signature {
     fn eq(&self, other: &Signature) -> bool {
-        self.sig
-            .read()
-            .expect("poisoned signature")
-            .eq(&other.sig.read().expect("poisoned signature"))
+        Arc::ptr_eq(&self.sig, &other.sig)
+            || self
+                .sig
+                .read()
+                .expect("poisoned signature")
+                .eq(&other.sig.read().expect("poisoned signature"))
     }
 }
 impl Eq for Signature {}
@@ -345,12 +666,77 @@ pub(crate) struct Sig {
     /// Stores the name for every [`Variable`].
     /// [`Variable`]: struct.Variable.html
     pub(crate) variables: Vec<Option<String>>,
+    /// Stores the (associative, commutative, identity) attributes of [`Operator`]s which have
+    /// any.
+    /// [`Operator`]: struct.Operator.html
+    pub(crate) attributes: HashMap<usize, (bool, bool, Option<usize>)>,
+    /// Stores the (argument sorts, result sort) of [`Operator`]s with declared sorts.
+    /// [`Operator`]: struct.Operator.html
+    pub(crate) sorts: HashMap<usize, (Vec<String>, String)>,
+    /// Stores the [`DisplayStyle`] of [`Operator`]s which aren't written prefix.
+    /// [`DisplayStyle`]: enum.DisplayStyle.html
+    /// [`Operator`]: struct.Operator.html
+    pub(crate) styles: HashMap<usize, DisplayStyle>,
+    /// Indexes the `id`s of named [`Operator`]s by name, in increasing order.
+    /// [`Operator`]: struct.Operator.html
+    pub(crate) op_names: HashMap<String, Vec<usize>>,
+    /// Indexes the `id`s of [`Operator`]s by arity, in increasing order.
+    /// [`Operator`]: struct.Operator.html
+    pub(crate) by_arity: HashMap<u32, Vec<usize>>,
+    /// Indexes the `id`s of named [`Variable`]s by name, in increasing order.
+    /// [`Variable`]: struct.Variable.html
+    pub(crate) var_names: HashMap<String, Vec<usize>>,
 }
 impl Sig {
     pub fn new(operator_spec: Vec<(u32, Option<String>)>) -> Sig {
-        Sig {
+        let mut sig = Sig {
             operators: operator_spec,
-            variables: vec![],
+            ..Sig::default()
+        };
+        sig.index_from(0, 0);
+        sig
+    }
+    // Add the Operators and Variables from `ops` and `vars` on to the indices.
+    fn index_from(&mut self, ops: usize, vars: usize) {
+        for (id, &(arity, ref name)) in self.operators.iter().enumerate().skip(ops) {
+            self.by_arity.entry(arity).or_default().push(id);
+            if let Some(name) = name {
+                self.op_names.entry(name.clone()).or_default().push(id);
+            }
+        }
+        for (id, name) in self.variables.iter().enumerate().skip(vars) {
+            if let Some(name) = name {
+                self.var_names.entry(name.clone()).or_default().push(id);
+            }
+        }
+    }
+    // The lowest `id` of an Operator named `name` with arity `arity`.
+    pub(crate) fn find_op(&self, name: &str, arity: u32) -> Option<usize> {
+        self.op_names
+            .get(name)?
+            .iter()
+            .cloned()
+            .find(|&id| self.operators[id].0 == arity)
+    }
+    // Serialize the Operator with id `id`, as `Operator::display` does.
+    pub(crate) fn display_op(&self, id: usize) -> String {
+        match self.operators[id].1 {
+            Some(ref name) => quote_name(name),
+            None => format!("op{}", id),
+        }
+    }
+    // Serialize the Variable with id `id`, as `Variable::display` does.
+    pub(crate) fn display_var(&self, id: usize) -> String {
+        match self.variables[id] {
+            Some(ref name) => format!("{}_", quote_name(name)),
+            None => format!("var{}_", id),
+        }
+    }
+    // The Operator with id `id`.
+    pub(crate) fn op(&self, id: usize) -> Operator {
+        Operator {
+            id,
+            arity: self.operators[id].0,
         }
     }
     pub fn operators(&self) -> Vec<usize> {
@@ -361,10 +747,12 @@ impl Sig {
     }
     pub fn new_op(&mut self, arity: u32, name: Option<String>) -> usize {
         self.operators.push((arity, name));
+        self.index_from(self.operators.len() - 1, self.variables.len());
         self.operators.len() - 1
     }
     pub fn new_var(&mut self, name: Option<String>) -> usize {
         self.variables.push(name);
+        self.index_from(self.operators.len(), self.variables.len() - 1);
         self.variables.len() - 1
     }
     pub fn merge(
@@ -373,6 +761,7 @@ impl Sig {
         strategy: MergeStrategy,
     ) -> Result<SignatureChange, ()> {
         let mut other = other.sig.write().expect("poisoned signature");
+        let (old_ops, old_vars) = (self.operators.len(), self.variables.len());
         let op_map =
             match strategy {
                 MergeStrategy::SameOperators => {
@@ -421,8 +810,31 @@ impl Sig {
                     temp_map
                 }
             };
+        for (id, &(associative, commutative, identity)) in &other.attributes {
+            let identity = identity.map(|e| op_map[&e]);
+            self.attributes
+                .entry(op_map[id])
+                .or_insert((associative, commutative, identity));
+        }
+        for (id, sort) in &other.sorts {
+            self.sorts.entry(op_map[id]).or_insert_with(|| sort.clone());
+        }
+        for (id, style) in &other.styles {
+            self.styles
+                .entry(op_map[id])
+                .or_insert_with(|| style.clone());
+        }
+        if other.operators.is_empty() {
+            other.attributes.clear();
+            other.sorts.clear();
+            other.styles.clear();
+            other.op_names.clear();
+            other.by_arity.clear();
+        }
         let delta_var = self.variables.len();
         self.variables.append(&mut other.variables);
+        other.var_names.clear();
+        self.index_from(old_ops, old_vars);
         Ok(SignatureChange { op_map, delta_var })
     }
 }
@@ -431,13 +843,23 @@ impl Default for Sig {
         Sig {
             operators: Vec::new(),
             variables: Vec::new(),
+            attributes: HashMap::new(),
+            sorts: HashMap::new(),
+            styles: HashMap::new(),
+            op_names: HashMap::new(),
+            by_arity: HashMap::new(),
+            var_names: HashMap::new(),
         }
     }
 }
 impl Hash for Sig {
+    // hash only what `eq` compares: the number of variables and the operator arities.
     fn hash<H: Hasher>(&self, state: &mut H) {
-        self.variables.hash(state);
-        self.operators.hash(state);
+        self.variables.len().hash(state);
+        self.operators.len().hash(state);
+        for &(arity, _) in &self.operators {
+            arity.hash(state);
+        }
     }
 }
 impl PartialEq for Sig {
@@ -452,6 +874,24 @@ impl PartialEq for Sig {
     }
 }
 
+/// A map from the [`Operator`]s of one [`Signature`] to those of another, as returned by
+/// [`Signature::union`] and taken by [`Term::map_signature`].
+///
+/// [`Operator`]: struct.Operator.html
+/// [`Signature`]: struct.Signature.html
+/// [`Signature::union`]: struct.Signature.html#method.union
+/// [`Term::map_signature`]: enum.Term.html#method.map_signature
+pub type OpMap = HashMap<Operator, Operator>;
+
+/// A map from the [`Variable`]s of one [`Signature`] to those of another, as returned by
+/// [`Signature::union`] and taken by [`Term::map_signature`].
+///
+/// [`Variable`]: struct.Variable.html
+/// [`Signature`]: struct.Signature.html
+/// [`Signature::union`]: struct.Signature.html#method.union
+/// [`Term::map_signature`]: enum.Term.html#method.map_signature
+pub type VarMap = HashMap<Variable, Variable>;
+
 /// Specifies how to merge two signatures.
 /// See [`Signature::merge`].
 ///
@@ -493,23 +933,71 @@ pub enum MergeStrategy {
 ///
 /// let term = parse_term(&mut sig2, "A B").unwrap();
 ///
-/// assert_eq!(term.pretty(), "A B");
+/// assert_eq!(term.pretty(&sig2), "A B");
 ///
 /// let sigchange = sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName).unwrap();
 ///
-/// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+/// let ops: Vec<String> = sig1.operators().iter().map(|op| op.display(&sig1)).collect();
 ///
 /// assert_eq!(ops, vec![".", "S", "K", "A", "B"]);
 ///
-/// let term = sigchange.reify_term(&sig1, term);
+/// let term = sigchange.reify_term(term);
 ///
-/// assert_eq!(term.pretty(), "A B");
+/// assert_eq!(term.pretty(&sig1), "A B");
 /// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SignatureChange {
     op_map: HashMap<usize, usize>,
     delta_var: usize,
 }
 impl SignatureChange {
+    /// Reifies [`Operator`] for use with another [`Signature`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Signature`]: struct.Signature.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{MergeStrategy, Signature};
+    /// let mut sig1 = Signature::new(vec![(0, Some("S".to_string()))]);
+    /// let mut sig2 = Signature::new(vec![(0, Some("K".to_string()))]);
+    /// let k = sig2.operators()[0].clone();
+    ///
+    /// let sigchange = sig1.merge(&sig2, MergeStrategy::DistinctOperators).unwrap();
+    ///
+    /// assert_eq!(sigchange.reify_operator(&k), sig1.operators()[1]);
+    /// ```
+    pub fn reify_operator(&self, op: &Operator) -> Operator {
+        Operator {
+            id: self.op_map[&op.id],
+            arity: op.arity,
+        }
+    }
+    /// Reifies [`Variable`] for use with another [`Signature`].
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{MergeStrategy, Signature};
+    /// let mut sig1 = Signature::default();
+    /// let x = sig1.new_var(Some("x".to_string()));
+    /// let mut sig2 = Signature::default();
+    /// let y = sig2.new_var(Some("y".to_string()));
+    ///
+    /// let sigchange = sig1.merge(&sig2, MergeStrategy::DistinctOperators).unwrap();
+    ///
+    /// assert_eq!(sigchange.reify_variable(&y), sig1.variables()[1]);
+    /// assert_ne!(sigchange.reify_variable(&y), x);
+    /// ```
+    pub fn reify_variable(&self, var: &Variable) -> Variable {
+        Variable {
+            id: var.id + self.delta_var,
+        }
+    }
     /// Reifies [`Term`] for use with another [`Signature`].
     ///
     /// [`Term`]: struct.Term.html
@@ -530,32 +1018,17 @@ impl SignatureChange {
     ///
     /// let sigchange = sig1.merge(&sig2, MergeStrategy::DistinctOperators).unwrap();
     ///
-    /// let term = sigchange.reify_term(&sig1, term);
+    /// let term = sigchange.reify_term(term);
     ///
-    /// assert_eq!(term.pretty(), "A B");
+    /// assert_eq!(term.pretty(&sig1), "A B");
     /// ```
-    pub fn reify_term(&self, sig: &Signature, term: Term) -> Term {
+    pub fn reify_term(&self, term: Term) -> Term {
         match term {
-            Term::Variable(Variable { id, .. }) => {
-                let id = id + self.delta_var;
-                Term::Variable(Variable {
-                    id,
-                    sig: sig.clone(),
-                })
-            }
-            Term::Application {
-                op: Operator { id, .. },
-                args,
-            } => {
-                let id = self.op_map[&id];
-                Term::Application {
-                    op: Operator {
-                        id,
-                        sig: sig.clone(),
-                    },
-                    args: args.into_iter().map(|t| self.reify_term(sig, t)).collect(),
-                }
-            }
+            Term::Variable(v) => Term::Variable(self.reify_variable(&v)),
+            Term::Application { op, args } => Term::Application {
+                op: self.reify_operator(&op),
+                args: args.into_iter().map(|t| self.reify_term(t)).collect(),
+            },
         }
     }
     /// Reifies [`Context`] for use with another [`Signature`].
@@ -578,36 +1051,18 @@ impl SignatureChange {
     ///
     /// let sigchange = sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName).unwrap();
     ///
-    /// let context = sigchange.reify_context(&sig1, context);
+    /// let context = sigchange.reify_context(context);
     ///
-    /// assert_eq!(context.pretty(), "A([!], B)");
+    /// assert_eq!(context.pretty(&sig1), "A([!], B)");
     /// ```
-    pub fn reify_context(&self, sig: &Signature, context: Context) -> Context {
+    pub fn reify_context(&self, context: Context) -> Context {
         match context {
             Context::Hole => Context::Hole,
-            Context::Variable(Variable { id, .. }) => {
-                let id = id + self.delta_var;
-                Context::Variable(Variable {
-                    id,
-                    sig: sig.clone(),
-                })
-            }
-            Context::Application {
-                op: Operator { id, .. },
-                args,
-            } => {
-                let id = self.op_map[&id];
-                Context::Application {
-                    op: Operator {
-                        id,
-                        sig: sig.clone(),
-                    },
-                    args: args
-                        .into_iter()
-                        .map(|t| self.reify_context(sig, t))
-                        .collect(),
-                }
-            }
+            Context::Variable(v) => Context::Variable(self.reify_variable(&v)),
+            Context::Application { op, args } => Context::Application {
+                op: self.reify_operator(&op),
+                args: args.into_iter().map(|t| self.reify_context(t)).collect(),
+            },
         }
     }
     /// Reifies [`Rule`] for use with another [`Signature`].
@@ -630,15 +1085,29 @@ impl SignatureChange {
     ///
     /// let sigchange = sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName).unwrap();
     ///
-    /// let rule = sigchange.reify_rule(&sig1, rule);
+    /// let rule = sigchange.reify_rule(rule);
     ///
-    /// assert_eq!(rule.pretty(), "A = B | C");
+    /// assert_eq!(rule.pretty(&sig1), "A = B | C");
     /// ```
-    pub fn reify_rule(&self, sig: &Signature, rule: Rule) -> Rule {
-        let Rule { lhs, rhs } = rule;
-        let lhs = self.reify_term(sig, lhs);
-        let rhs = rhs.into_iter().map(|t| self.reify_term(sig, t)).collect();
-        Rule { lhs, rhs }
+    pub fn reify_rule(&self, rule: Rule) -> Rule {
+        let Rule {
+            lhs,
+            rhs,
+            conditions,
+            weights,
+        } = rule;
+        let lhs = self.reify_term(lhs);
+        let rhs = rhs.into_iter().map(|t| self.reify_term(t)).collect();
+        let conditions = conditions
+            .into_iter()
+            .map(|(c, d)| (self.reify_term(c), self.reify_term(d)))
+            .collect();
+        Rule {
+            lhs,
+            rhs,
+            conditions,
+            weights,
+        }
     }
     /// Reifies [`TRS`] for use with another [`Signature`].
     ///
@@ -662,19 +1131,17 @@ impl SignatureChange {
     ///
     /// let sigchange = sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName).unwrap();
     ///
-    /// let trs = sigchange.reify_trs(&sig1, trs);
+    /// let trs = sigchange.reify_trs(trs);
     ///
-    /// assert_eq!(trs.pretty(),
+    /// assert_eq!(trs.pretty(&sig1),
     /// "A = B;
     /// C = B;");
     /// ```
-    pub fn reify_trs(&self, sig: &Signature, trs: TRS) -> TRS {
-        let rules = trs
-            .rules
-            .into_iter()
-            .map(|r| self.reify_rule(sig, r))
-            .collect();
-        TRS { rules, ..trs }
+    pub fn reify_trs(&self, trs: TRS) -> TRS {
+        let rules = trs.rules.into_iter().map(|r| self.reify_rule(r)).collect();
+        let mut trs = TRS { rules, ..trs };
+        trs.reindex();
+        trs
     }
 }
 
@@ -693,7 +1160,7 @@ mod tests {
         ]);
         let mut ops = sig.operators();
 
-        let mut op_names: Vec<String> = ops.iter().map(|op| op.display()).collect();
+        let mut op_names: Vec<String> = ops.iter().map(|op| op.display(&sig)).collect();
         assert_eq!(op_names, vec![".", "S", "K"]);
 
         let mut sig2 = Signature::default();
@@ -703,7 +1170,7 @@ mod tests {
 
         ops = sig2.operators();
 
-        op_names = ops.iter().map(|op| op.display()).collect();
+        op_names = ops.iter().map(|op| op.display(&sig)).collect();
         assert_eq!(op_names, vec![".", "S", "K"]);
 
         assert_eq!(sig, sig2);
@@ -724,7 +1191,7 @@ mod tests {
             (0, Some("K".to_string())),
         ]);
 
-        let ops: Vec<String> = sig.operators().iter().map(|op| op.display()).collect();
+        let ops: Vec<String> = sig.operators().iter().map(|op| op.display(&sig)).collect();
 
         assert_eq!(ops, vec![".", "S", "K"]);
     }
@@ -740,7 +1207,7 @@ mod tests {
 
         parse_term(&mut sig, "A(x_ y_)").expect("parse of A(x_ y_)");
 
-        let vars: Vec<String> = sig.variables().iter().map(|v| v.display()).collect();
+        let vars: Vec<String> = sig.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(vars, vec!["x_", "y_"]);
     }
@@ -751,7 +1218,7 @@ mod tests {
 
         parse_term(&mut sig, "A(x_ B(y_))").expect("parse of A(x_ B(y_))");
 
-        let atoms: Vec<String> = sig.atoms().iter().map(|a| a.display()).collect();
+        let atoms: Vec<String> = sig.atoms().iter().map(|a| a.display(&sig)).collect();
 
         assert_eq!(atoms, vec!["x_", "y_", "B", "A"]);
     }
@@ -799,7 +1266,11 @@ mod tests {
         sig1.merge(&sig2, MergeStrategy::DistinctOperators)
             .expect("merge of distinct operators");
 
-        let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+        let ops: Vec<String> = sig1
+            .operators()
+            .iter()
+            .map(|op| op.display(&sig1))
+            .collect();
 
         assert_eq!(ops, vec![".", "S", "K", "A", "B", "C"]);
 
@@ -819,7 +1290,11 @@ mod tests {
         sig1.merge(&sig2, MergeStrategy::SameOperators)
             .expect("merge of same operators");
 
-        let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+        let ops: Vec<String> = sig1
+            .operators()
+            .iter()
+            .map(|op| op.display(&sig1))
+            .collect();
 
         assert_eq!(ops, vec![".", "S", "K"]);
 
@@ -854,7 +1329,11 @@ mod tests {
         sig1.merge(&sig2, MergeStrategy::OperatorsByArityAndName)
             .expect("merge of same arity and name");
 
-        let ops: Vec<String> = sig1.operators().iter().map(|op| op.display()).collect();
+        let ops: Vec<String> = sig1
+            .operators()
+            .iter()
+            .map(|op| op.display(&sig1))
+            .collect();
 
         assert_eq!(ops, vec![".", "S", "K", "A", "B"]);
     }
@@ -875,9 +1354,9 @@ mod tests {
 
         let sigchange = sig1.merge(&sig2, MergeStrategy::DistinctOperators).unwrap();
 
-        let term = sigchange.reify_term(&sig1, term);
+        let term = sigchange.reify_term(term);
 
-        assert_eq!(term.pretty(), "A B");
+        assert_eq!(term.pretty(&sig1), "A B");
     }
 
     #[test]
@@ -895,9 +1374,9 @@ mod tests {
             .merge(&sig2, MergeStrategy::OperatorsByArityAndName)
             .unwrap();
 
-        let context = sigchange.reify_context(&sig1, context);
+        let context = sigchange.reify_context(context);
 
-        assert_eq!(context.pretty(), "A([!], B)");
+        assert_eq!(context.pretty(&sig1), "A([!], B)");
     }
 
     #[test]
@@ -915,9 +1394,9 @@ mod tests {
             .merge(&sig2, MergeStrategy::OperatorsByArityAndName)
             .unwrap();
 
-        let rule = sigchange.reify_rule(&sig1, rule);
+        let rule = sigchange.reify_rule(rule);
 
-        assert_eq!(rule.pretty(), "A = B | C");
+        assert_eq!(rule.pretty(&sig1), "A = B | C");
     }
 
     #[test]
@@ -935,8 +1414,36 @@ mod tests {
             .merge(&sig2, MergeStrategy::OperatorsByArityAndName)
             .unwrap();
 
-        let trs = sigchange.reify_trs(&sig1, trs);
+        let trs = sigchange.reify_trs(trs);
+
+        assert_eq!(trs.pretty(&sig1), "A = B;\nC = B;");
+    }
+
+    #[test]
+    fn union_test() {
+        let mut sig1 = Signature::default();
+        let trs1 = parse_trs(&mut sig1, "F(x_) = A;").unwrap();
+        let mut sig2 = Signature::default();
+        let trs2 = parse_trs(&mut sig2, "F(y_) = B;").unwrap();
+
+        let (sig, ops, vars) = sig1.union(&sig2, MergeStrategy::DistinctOperators).unwrap();
+
+        assert_eq!(sig.operators().len(), 4);
+        assert_eq!(sig.variables().len(), 2);
+        assert_eq!(sig1.operators().len(), 2);
+        assert_eq!(sig2.operators().len(), 2);
+        assert_eq!(sig2.variables().len(), 1);
+        let rule1 = trs1.rules[0].clone();
+        let rule2 = trs2.rules[0].map_signature(&ops, &vars).unwrap();
+        assert_eq!(rule1.pretty(&sig), "F(x_) = A");
+        assert_eq!(rule2.pretty(&sig), "F(y_) = B");
+        assert_ne!(rule1.lhs, rule2.lhs);
+
+        let (sig, ops, _) = sig1
+            .union(&sig2, MergeStrategy::OperatorsByArityAndName)
+            .unwrap();
 
-        assert_eq!(trs.pretty(), "A = B;\nC = B;");
+        assert_eq!(sig.operators().len(), 3);
+        assert_eq!(ops[&sig2.operators()[0]], sig1.operators()[0]);
     }
 }
@@ -0,0 +1,329 @@
+use super::{Context, Operator, Rule, Signature, Term};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The sort of an [`Operator`]: the sorts of its arguments, in order, and the sort of its
+/// result. Sorts are identified by name.
+///
+/// To declare the sort of an [`Operator`], use [`Signature::set_sort`].
+///
+/// [`Operator`]: struct.Operator.html
+/// [`Signature::set_sort`]: struct.Signature.html#method.set_sort
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::OperatorSort;
+/// let sort = OperatorSort {
+///     args: vec!["Nat".to_string(), "Nat".to_string()],
+///     result: "Bool".to_string(),
+/// };
+///
+/// assert_eq!(sort.display(), "Nat -> Nat -> Bool");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OperatorSort {
+    /// The sorts of the arguments.
+    pub args: Vec<String>,
+    /// The sort of the result.
+    pub result: String,
+}
+impl OperatorSort {
+    /// Serialize an `OperatorSort`, as in a sort declaration.
+    pub fn display(&self) -> String {
+        self.args
+            .iter()
+            .chain(Some(&self.result))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// The error type for sort checking: a [`Term`] occurs where a different sort is expected.
+///
+/// [`Term`]: enum.Term.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortError {
+    /// The ill-sorted `Term`.
+    pub term: Term,
+    /// The sort required by the `Term`'s context.
+    pub expected: String,
+    /// The sort of the `Term`.
+    pub found: String,
+}
+impl fmt::Display for SortError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ill-sorted term: expected sort {}, found sort {}",
+            self.expected, self.found
+        )
+    }
+}
+impl ::std::error::Error for SortError {
+    fn description(&self) -> &'static str {
+        "sort error"
+    }
+}
+
+impl Operator {
+    /// The declared [`OperatorSort`] of the `Operator`, if any.
+    ///
+    /// [`OperatorSort`]: struct.OperatorSort.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorSort};
+    /// let mut sig = Signature::default();
+    /// let succ = sig.new_op(1, Some("SUCC".to_string()));
+    ///
+    /// assert_eq!(succ.sort(&sig), None);
+    ///
+    /// let sort = OperatorSort { args: vec!["Nat".to_string()], result: "Nat".to_string() };
+    /// sig.set_sort(&succ, sort.clone());
+    ///
+    /// assert_eq!(succ.sort(&sig), Some(sort));
+    /// ```
+    pub fn sort(&self, sig: &Signature) -> Option<OperatorSort> {
+        sig.sig
+            .read()
+            .expect("poisoned signature")
+            .sorts
+            .get(&self.id)
+            .map(|(args, result)| OperatorSort {
+                args: args.clone(),
+                result: result.clone(),
+            })
+    }
+}
+
+impl Term {
+    /// Check that the `Term` is well-sorted and return its sort. The sort of a [`Variable`]
+    /// is inferred from where it occurs, and [`Operator`]s without a declared sort accept
+    /// arguments of any sort. Returns `Ok(None)` if the sort of the `Term` is unknown.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse};
+    /// let mut sig = Signature::default();
+    ///
+    /// let (_, terms) = parse(&mut sig,
+    /// "ZERO : Nat;
+    /// TRUE : Bool;
+    /// EQ : Nat -> Nat -> Bool;
+    /// EQ(ZERO x_);
+    /// F(EQ(ZERO ZERO));").expect("parse of sorted terms");
+    ///
+    /// assert_eq!(terms[0].sort(&sig), Ok(Some("Bool".to_string())));
+    /// assert_eq!(terms[1].sort(&sig), Ok(None));
+    ///
+    /// let term = terms[0].replace(&[1], terms[1].args()[0].clone()).unwrap();
+    /// let error = term.sort(&sig).unwrap_err();
+    ///
+    /// assert_eq!(error.term.display(&sig), "EQ(ZERO ZERO)");
+    /// assert_eq!(error.to_string(), "ill-sorted term: expected sort Nat, found sort Bool");
+    /// ```
+    pub fn sort(&self, sig: &Signature) -> Result<Option<String>, SortError> {
+        infer(sig, self, None, &mut HashMap::new())
+    }
+}
+
+impl Context {
+    /// The sort expected at each hole of the `Context`, in the order given by
+    /// [`Context::holes`]: the declared sort of the argument it fills, or `None` if the hole is
+    /// the root or an argument of an [`Operator`] without a declared sort.
+    ///
+    /// [`Context::holes`]: enum.Context.html#method.holes
+    /// [`Operator`]: struct.Operator.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, OperatorSort, parse_context};
+    /// let mut sig = Signature::default();
+    ///
+    /// let op = sig.new_op(3, Some("IF".to_string()));
+    /// sig.set_sort(&op, OperatorSort {
+    ///     args: vec!["Bool".to_string(), "Nat".to_string(), "Nat".to_string()],
+    ///     result: "Nat".to_string(),
+    /// });
+    ///
+    /// let context = parse_context(&mut sig, "IF([!] F([!]) ZERO)").expect("parse of IF([!] F([!]) ZERO)");
+    ///
+    /// assert_eq!(context.hole_sorts(&sig), vec![Some("Bool".to_string()), None]);
+    /// ```
+    pub fn hole_sorts(&self, sig: &Signature) -> Vec<Option<String>> {
+        let mut sorts = vec![];
+        self.collect_hole_sorts(sig, None, &mut sorts);
+        sorts
+    }
+    fn collect_hole_sorts(
+        &self,
+        sig: &Signature,
+        expected: Option<&String>,
+        sorts: &mut Vec<Option<String>>,
+    ) {
+        match *self {
+            Context::Hole => sorts.push(expected.cloned()),
+            Context::Variable(_) => (),
+            Context::Application { ref op, ref args } => {
+                let sort = op.sort(sig);
+                for (i, arg) in args.iter().enumerate() {
+                    arg.collect_hole_sorts(sig, sort.as_ref().map(|s| &s.args[i]), sorts);
+                }
+            }
+        }
+    }
+}
+
+impl Rule {
+    /// Check that the `Rule` is well-sorted and return its sort. Each RHS must have the sort
+    /// of the LHS, the two sides of each condition must share a sort, and each [`Variable`]
+    /// must have one sort throughout the `Rule`. Returns `Ok(None)` if the sort of the LHS is
+    /// unknown.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs, parse_rule, ParseError};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "ZERO : Nat;
+    /// SUCC : Nat -> Nat;
+    /// TRUE : Bool;
+    /// POS : Nat -> Bool;
+    /// POS(SUCC(x_)) = TRUE;").expect("parse of sorted TRS");
+    ///
+    /// assert_eq!(t.rules()[0].sort(&sig), Ok(Some("Bool".to_string())));
+    ///
+    /// match parse_rule(&mut sig, "POS(x_) = x_") {
+    ///     Err(ParseError::IllSorted(e)) => assert_eq!(e.to_string(), "ill-sorted term: expected sort Bool, found sort Nat"),
+    ///     _ => panic!("ill-sorted rule parsed"),
+    /// }
+    /// ```
+    pub fn sort(&self, sig: &Signature) -> Result<Option<String>, SortError> {
+        let mut env = HashMap::new();
+        let sort = infer(sig, &self.lhs, None, &mut env)?;
+        for rhs in &self.rhs {
+            infer(sig, rhs, sort.as_ref(), &mut env)?;
+        }
+        for (c, d) in &self.conditions {
+            let condition_sort = infer(sig, c, None, &mut env)?;
+            infer(sig, d, condition_sort.as_ref(), &mut env)?;
+        }
+        Ok(sort)
+    }
+}
+
+// Infer the sort of `term`, checking it against `expected` and recording the sorts of
+// variables in `env`.
+fn infer(
+    sig: &Signature,
+    term: &Term,
+    expected: Option<&String>,
+    env: &mut HashMap<usize, String>,
+) -> Result<Option<String>, SortError> {
+    let found = match *term {
+        Term::Variable(ref v) => match (env.get(&v.id).cloned(), expected) {
+            (None, Some(expected)) => {
+                env.insert(v.id, expected.clone());
+                return Ok(Some(expected.clone()));
+            }
+            (found, _) => found,
+        },
+        Term::Application { ref op, ref args } => match op.sort(sig) {
+            Some(OperatorSort {
+                args: ref arg_sorts,
+                ref result,
+            }) => {
+                for (arg, arg_sort) in args.iter().zip(arg_sorts) {
+                    infer(sig, arg, Some(arg_sort), env)?;
+                }
+                Some(result.clone())
+            }
+            None => {
+                for arg in args {
+                    infer(sig, arg, None, env)?;
+                }
+                None
+            }
+        },
+    };
+    match (found, expected) {
+        (Some(ref found), Some(expected)) if found != expected => Err(SortError {
+            term: term.clone(),
+            expected: expected.clone(),
+            found: found.clone(),
+        }),
+        (found, _) => Ok(found),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn sort_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "ZERO : Nat; SUCC : Nat -> Nat; NIL : List; CONS : Nat -> List -> List;
+             LEN : List -> Nat;
+             LEN(NIL) = ZERO;
+             LEN(CONS(x_ y_)) = SUCC(LEN(y_)) <= LEN(y_) = x_;
+             F(x_) = G(x_);",
+        )
+        .expect("parse of sorted TRS");
+
+        assert_eq!(t.len(), 3);
+        assert_eq!(t.rules[1].sort(&sig), Ok(Some("Nat".to_string())));
+        assert_eq!(t.rules[2].sort(&sig), Ok(None));
+
+        let cons = &t.rules[1].lhs.args()[0];
+        let len = &t.rules[1].lhs;
+
+        assert_eq!(cons.sort(&sig), Ok(Some("List".to_string())));
+        assert_eq!(
+            cons.replace(&[0], len.clone()).unwrap().sort(&sig),
+            Ok(Some("List".to_string()))
+        );
+        assert_eq!(
+            cons.replace(&[1], len.clone()).unwrap().sort(&sig),
+            Err(SortError {
+                term: len.clone(),
+                expected: "List".to_string(),
+                found: "Nat".to_string(),
+            })
+        );
+
+        let r = parse_rule(&mut sig, "LEN(CONS(x_ x_)) = ZERO");
+
+        assert!(match r {
+            Err(ParseError::IllSorted(ref e)) => e.term.display(&sig) == "x_",
+            _ => false,
+        });
+
+        let r = parse_rule(&mut sig, "LEN(x_) = ZERO <= x_ = ZERO");
+
+        assert!(match r {
+            Err(ParseError::IllSorted(ref e)) => e.expected == "List" && e.found == "Nat",
+            _ => false,
+        });
+
+        assert!(parse_term(&mut sig, "SUCC(NIL)").is_err());
+        assert!(parse_term(&mut sig, "H(SUCC(x_) CONS(x_ NIL))").is_ok());
+    }
+}
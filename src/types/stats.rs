@@ -0,0 +1,425 @@
+use super::{Rule, Signature, Term, Variable, TRS};
+use std::collections::HashSet;
+
+/// Summary measurements of a [`Term`], [`Rule`], or [`TRS`], gathered in a single traversal.
+///
+/// For a [`Rule`], the counts cover every [`Term`] in it, and `height` is the greatest height of
+/// any of them. For a [`TRS`], they cover every [`Rule`], except that `distinct_variables` sums
+/// the distinct [`Variable`]s of each [`Rule`], since [`Variable`]s are local to a [`Rule`].
+///
+/// [`Term`]: enum.Term.html
+/// [`Rule`]: struct.Rule.html
+/// [`TRS`]: struct.TRS.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, TermStats, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term(&mut sig, "F(x_ G(x_ A) y_)").expect("parse of F(x_ G(x_ A) y_)");
+///
+/// assert_eq!(
+///     t.stats(),
+///     TermStats {
+///         size: 6,
+///         height: 2,
+///         operators: 3,
+///         variables: 3,
+///         distinct_operators: 3,
+///         distinct_variables: 2,
+///     }
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TermStats {
+    /// The number of subterms.
+    pub size: usize,
+    /// The number of edges on the longest path from the root to a leaf.
+    pub height: usize,
+    /// The number of occurrences of [`Operator`]s.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    pub operators: usize,
+    /// The number of occurrences of [`Variable`]s.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub variables: usize,
+    /// The number of distinct [`Operator`]s.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    pub distinct_operators: usize,
+    /// The number of distinct [`Variable`]s.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub distinct_variables: usize,
+}
+
+// Running totals for `TermStats`, remembering which atoms have been seen by id.
+#[derive(Default)]
+struct Tally {
+    stats: TermStats,
+    operators: HashSet<usize>,
+    variables: HashSet<usize>,
+}
+impl Tally {
+    fn add(&mut self, term: &Term) {
+        let mut stack = vec![(term, 0)];
+        while let Some((term, depth)) = stack.pop() {
+            self.stats.size += 1;
+            self.stats.height = self.stats.height.max(depth);
+            match *term {
+                Term::Variable(ref v) => {
+                    self.stats.variables += 1;
+                    self.variables.insert(v.id);
+                }
+                Term::Application { ref op, ref args } => {
+                    self.stats.operators += 1;
+                    self.operators.insert(op.id);
+                    stack.extend(args.iter().map(|arg| (arg, depth + 1)));
+                }
+            }
+        }
+    }
+    fn add_rule(&mut self, rule: &Rule) {
+        self.add(&rule.lhs);
+        for rhs in &rule.rhs {
+            self.add(rhs);
+        }
+        for (c, d) in &rule.conditions {
+            self.add(c);
+            self.add(d);
+        }
+    }
+    fn finish(self) -> TermStats {
+        TermStats {
+            distinct_operators: self.operators.len(),
+            distinct_variables: self.variables.len(),
+            ..self.stats
+        }
+    }
+}
+
+// Count `term`'s occurrences of each Operator of `sig` into `counts`, ignoring any others.
+fn count_symbols(term: &Term, counts: &mut [usize]) {
+    let mut stack = vec![term];
+    while let Some(term) = stack.pop() {
+        if let Term::Application { ref op, ref args } = *term {
+            if let Some(n) = counts.get_mut(op.id) {
+                *n += 1;
+            }
+            stack.extend(args);
+        }
+    }
+}
+
+// Count the occurrences of each Variable of `term` into `occurrences`, in order of first
+// occurrence.
+fn count_variables<'a>(term: &'a Term, occurrences: &mut Vec<(&'a Variable, usize)>) {
+    let mut stack = vec![term];
+    while let Some(term) = stack.pop() {
+        match *term {
+            Term::Variable(ref v) => match occurrences.iter().position(|(w, _)| *w == v) {
+                Some(i) => occurrences[i].1 += 1,
+                None => occurrences.push((v, 1)),
+            },
+            Term::Application { ref args, .. } => stack.extend(args.iter().rev()),
+        }
+    }
+}
+
+impl Term {
+    /// The number of edges on the longest path from the root of the `Term` to a leaf, so that
+    /// [`Variable`]s and constants have height `0`.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A G(H(x_)))").expect("parse of F(A G(H(x_)))");
+    ///
+    /// assert_eq!(t.height(), 3);
+    /// assert_eq!(t.args()[0].height(), 0);
+    /// ```
+    pub fn height(&self) -> usize {
+        self.stats().height
+    }
+    /// The number of occurrences in the `Term` of each [`Operator`] of `sig`, indexed as in
+    /// [`Signature::operators`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Signature::operators`]: struct.Signature.html#method.operators
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A G(A x_))").expect("parse of F(A G(A x_))");
+    /// let counts: Vec<_> = sig
+    ///     .operators()
+    ///     .iter()
+    ///     .map(|op| op.display(&sig))
+    ///     .zip(t.symbol_counts(&sig))
+    ///     .collect();
+    ///
+    /// assert_eq!(counts, vec![("A".to_string(), 2), ("G".to_string(), 1), ("F".to_string(), 1)]);
+    /// ```
+    pub fn symbol_counts(&self, sig: &Signature) -> Vec<usize> {
+        let mut counts = vec![0; sig.operators().len()];
+        count_symbols(self, &mut counts);
+        counts
+    }
+    /// The number of occurrences of each [`Variable`] in the `Term`, in order of first
+    /// occurrence.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(y_ G(x_ y_))").expect("parse of F(y_ G(x_ y_))");
+    /// let occurrences: Vec<_> = t
+    ///     .variable_occurrences()
+    ///     .into_iter()
+    ///     .map(|(v, n)| (v.display(&sig), n))
+    ///     .collect();
+    ///
+    /// assert_eq!(occurrences, vec![("y_".to_string(), 2), ("x_".to_string(), 1)]);
+    /// ```
+    pub fn variable_occurrences(&self) -> Vec<(&Variable, usize)> {
+        let mut occurrences = vec![];
+        count_variables(self, &mut occurrences);
+        occurrences
+    }
+    /// The [`TermStats`] of the `Term`.
+    ///
+    /// [`TermStats`]: struct.TermStats.html
+    pub fn stats(&self) -> TermStats {
+        let mut tally = Tally::default();
+        tally.add(self);
+        tally.finish()
+    }
+}
+
+impl Rule {
+    /// The greatest [`Term::height`] of any [`Term`] in the `Rule`.
+    ///
+    /// [`Term::height`]: enum.Term.html#method.height
+    /// [`Term`]: enum.Term.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_) = G(H(x_)) | A").expect("parse of F(x_) = G(H(x_)) | A");
+    ///
+    /// assert_eq!(r.height(), 2);
+    /// ```
+    pub fn height(&self) -> usize {
+        self.stats().height
+    }
+    /// The number of occurrences in the `Rule` of each [`Operator`] of `sig`, as in
+    /// [`Term::symbol_counts`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Term::symbol_counts`]: enum.Term.html#method.symbol_counts
+    pub fn symbol_counts(&self, sig: &Signature) -> Vec<usize> {
+        let mut counts = vec![0; sig.operators().len()];
+        for term in self.terms() {
+            count_symbols(term, &mut counts);
+        }
+        counts
+    }
+    /// The number of occurrences of each [`Variable`] in the `Rule`, in order of first
+    /// occurrence, reading the LHS, then each RHS, then each condition.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_ y_) = G(y_ y_)").expect("parse of F(x_ y_) = G(y_ y_)");
+    /// let occurrences: Vec<_> = r
+    ///     .variable_occurrences()
+    ///     .into_iter()
+    ///     .map(|(v, n)| (v.display(&sig), n))
+    ///     .collect();
+    ///
+    /// assert_eq!(occurrences, vec![("x_".to_string(), 1), ("y_".to_string(), 3)]);
+    /// ```
+    pub fn variable_occurrences(&self) -> Vec<(&Variable, usize)> {
+        let mut occurrences = vec![];
+        for term in self.terms() {
+            count_variables(term, &mut occurrences);
+        }
+        occurrences
+    }
+    /// The [`TermStats`] of the `Rule`.
+    ///
+    /// [`TermStats`]: struct.TermStats.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let r = parse_rule(&mut sig, "F(x_ y_) = G(y_) | x_").expect("parse of F(x_ y_) = G(y_) | x_");
+    /// let stats = r.stats();
+    ///
+    /// assert_eq!(stats.size, r.size());
+    /// assert_eq!((stats.operators, stats.variables, stats.distinct_variables), (2, 4, 2));
+    /// ```
+    pub fn stats(&self) -> TermStats {
+        let mut tally = Tally::default();
+        tally.add_rule(self);
+        tally.finish()
+    }
+    // Every Term in the Rule: the LHS, each RHS, then both sides of each condition.
+    fn terms(&self) -> impl Iterator<Item = &Term> {
+        let conditions = self.conditions.iter().flat_map(|(c, d)| vec![c, d]);
+        Some(&self.lhs)
+            .into_iter()
+            .chain(&self.rhs)
+            .chain(conditions)
+    }
+}
+
+impl TRS {
+    /// The greatest [`Rule::height`] of any [`Rule`] in the `TRS`.
+    ///
+    /// [`Rule::height`]: struct.Rule.html#method.height
+    /// [`Rule`]: struct.Rule.html
+    pub fn height(&self) -> usize {
+        self.rules.iter().map(Rule::height).max().unwrap_or(0)
+    }
+    /// The number of occurrences in the `TRS` of each [`Operator`] of `sig`, as in
+    /// [`Term::symbol_counts`].
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Term::symbol_counts`]: enum.Term.html#method.symbol_counts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "F(A) = B; F(B) = A;").expect("parse of F(A) = B; F(B) = A;");
+    ///
+    /// assert_eq!(t.symbol_counts(&sig), vec![2, 2, 2]);
+    /// ```
+    pub fn symbol_counts(&self, sig: &Signature) -> Vec<usize> {
+        let mut counts = vec![0; sig.operators().len()];
+        for term in self.rules.iter().flat_map(Rule::terms) {
+            count_symbols(term, &mut counts);
+        }
+        counts
+    }
+    /// The [`TermStats`] of the `TRS`.
+    ///
+    /// [`TermStats`]: struct.TermStats.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "F(x_) = G(x_); G(x_) = H(H(x_));")
+    ///     .expect("parse of F(x_) = G(x_); G(x_) = H(H(x_));");
+    /// let stats = t.stats();
+    ///
+    /// assert_eq!((stats.size, stats.height), (t.size(), 2));
+    /// assert_eq!((stats.distinct_operators, stats.distinct_variables), (3, 2));
+    /// ```
+    pub fn stats(&self) -> TermStats {
+        let mut tally = Tally::default();
+        let mut distinct_variables = 0;
+        for rule in &self.rules {
+            tally.add_rule(rule);
+            distinct_variables += tally.variables.len();
+            tally.variables.clear();
+        }
+        TermStats {
+            distinct_variables,
+            ..tally.finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn stats_test() {
+        let mut sig = Signature::default();
+        let trs = parse_trs(
+            &mut sig,
+            "F(x_ G(y_)) = G(F(y_ x_)) | x_ <= P(x_) = T; G(A) = B;",
+        )
+        .expect("parse of TRS");
+
+        for rule in &trs.rules {
+            for term in rule.terms() {
+                let stats = term.stats();
+                assert_eq!(stats.size, term.size());
+                assert_eq!(stats.distinct_operators, term.operators().len());
+                assert_eq!(stats.distinct_variables, term.variables().len());
+                assert_eq!(
+                    stats.variables,
+                    term.variable_occurrences().iter().map(|(_, n)| n).sum()
+                );
+                assert_eq!(
+                    stats.operators,
+                    term.symbol_counts(&sig).iter().sum::<usize>()
+                );
+            }
+            let stats = rule.stats();
+            assert_eq!(stats.size, rule.size());
+            assert_eq!(stats.distinct_variables, rule.variables().len());
+        }
+
+        let rule = &trs.rules[0];
+        let occurrences: Vec<_> = rule
+            .variable_occurrences()
+            .into_iter()
+            .map(|(v, n)| (v.display(&sig), n))
+            .collect();
+        assert_eq!(
+            occurrences,
+            vec![("x_".to_string(), 4), ("y_".to_string(), 2)]
+        );
+        assert_eq!(rule.height(), 2);
+
+        let stats = trs.stats();
+        assert_eq!(stats.size, trs.size());
+        assert_eq!(stats.height, 2);
+        assert_eq!(stats.distinct_operators, 6);
+        assert_eq!(stats.distinct_variables, 2);
+        assert_eq!(trs.height(), 2);
+        assert_eq!(
+            trs.symbol_counts(&sig).iter().sum::<usize>(),
+            stats.operators
+        );
+        assert_eq!(TRS::new(vec![]).stats(), TermStats::default());
+    }
+}
@@ -0,0 +1,419 @@
+use super::{Signature, Term, Variable};
+use itertools::Itertools;
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+/// A mapping from [`Variable`]s to [`Term`]s, applied simultaneously.
+///
+/// Bindings are kept in order of [`Variable`] creation, so equal `Substitution`s compare
+/// equal regardless of the order in which they were built.
+///
+/// [`Variable`]: struct.Variable.html
+/// [`Term`]: enum.Term.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, Substitution, Term, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term(&mut sig, "F(x_ y_)").expect("parse of F(x_ y_)");
+/// let a = parse_term(&mut sig, "A").expect("parse of A");
+/// let vars = t.variables();
+///
+/// let mut sub = Substitution::new();
+/// sub.insert(vars[0].clone(), a);
+/// sub.insert(vars[1].clone(), Term::Variable(vars[0].clone()));
+///
+/// assert_eq!(sub.display(&sig), "{x_ := A, y_ := x_}");
+/// assert_eq!(sub.apply(&t).display(&sig), "F(A x_)");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Substitution(Vec<(Variable, Term)>);
+impl Substitution {
+    /// Create an empty `Substitution`, the identity.
+    pub fn new() -> Substitution {
+        Substitution(vec![])
+    }
+    /// The number of bindings in the `Substitution`.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Does the `Substitution` have no bindings?
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Bind `var` to `term`, returning the `Term` to which `var` was previously bound, if any.
+    pub fn insert(&mut self, var: Variable, term: Term) -> Option<Term> {
+        match self.0.binary_search_by_key(&var.id, |(v, _)| v.id) {
+            Ok(i) => Some(::std::mem::replace(&mut self.0[i].1, term)),
+            Err(i) => {
+                self.0.insert(i, (var, term));
+                None
+            }
+        }
+    }
+    /// Remove the binding of `var`, returning the `Term` to which it was bound, if any.
+    pub fn remove(&mut self, var: &Variable) -> Option<Term> {
+        match self.0.binary_search_by_key(&var.id, |(v, _)| v.id) {
+            Ok(i) => Some(self.0.remove(i).1),
+            Err(_) => None,
+        }
+    }
+    /// The `Term` to which `var` is bound, if any.
+    pub fn get(&self, var: &Variable) -> Option<&Term> {
+        self.0
+            .binary_search_by_key(&var.id, |(v, _)| v.id)
+            .ok()
+            .map(|i| &self.0[i].1)
+    }
+    /// The bindings of the `Substitution`.
+    pub fn iter(&self) -> impl Iterator<Item = (&Variable, &Term)> {
+        self.0.iter().map(|(v, t)| (v, t))
+    }
+    /// The [`Variable`]s which the `Substitution` changes, i.e. those not bound to themselves.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Substitution, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let x = sig.new_var(Some("x".to_string()));
+    /// let y = sig.new_var(Some("y".to_string()));
+    ///
+    /// let mut sub = Substitution::new();
+    /// sub.insert(x.clone(), Term::Variable(x.clone()));
+    /// sub.insert(y.clone(), Term::Variable(x.clone()));
+    ///
+    /// assert_eq!(sub.domain(), vec![&y]);
+    /// ```
+    pub fn domain(&self) -> Vec<&Variable> {
+        self.0
+            .iter()
+            .filter(|(v, t)| !is_variable(t, v))
+            .map(|(v, _)| v)
+            .collect()
+    }
+    /// The `Term`s to which the [`Variable`]s in the [`domain`] are bound.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    /// [`domain`]: #method.domain
+    pub fn range(&self) -> Vec<&Term> {
+        self.0
+            .iter()
+            .filter(|(v, t)| !is_variable(t, v))
+            .map(|(_, t)| t)
+            .collect()
+    }
+    /// Apply the `Substitution` to `term`, replacing each bound [`Variable`] simultaneously.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub fn apply(&self, term: &Term) -> Term {
+        match *term {
+            Term::Variable(ref v) => self.get(v).cloned().unwrap_or_else(|| term.clone()),
+            Term::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args.iter().map(|arg| self.apply(arg)).collect(),
+            },
+        }
+    }
+    /// The `Substitution` which first applies `self` and then `other`, so that
+    /// `self.compose(&other).apply(t) == other.apply(&self.apply(t))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Substitution, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(x_ G(y_))").expect("parse of F(x_ G(y_))");
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    /// let vars = t.variables();
+    ///
+    /// let mut s1 = Substitution::new();
+    /// s1.insert(vars[0].clone(), t.args()[1].clone());
+    /// let mut s2 = Substitution::new();
+    /// s2.insert(vars[1].clone(), a);
+    ///
+    /// let s = s1.compose(&s2);
+    ///
+    /// assert_eq!(s.display(&sig), "{x_ := G(A), y_ := A}");
+    /// assert_eq!(s.apply(&t), s2.apply(&s1.apply(&t)));
+    /// ```
+    pub fn compose(&self, other: &Substitution) -> Substitution {
+        let mut composed: Substitution = self.0.iter().map(|(v, t)| (*v, other.apply(t))).collect();
+        for (v, t) in &other.0 {
+            if self.get(v).is_none() {
+                composed.insert(*v, t.clone());
+            }
+        }
+        composed.0.retain(|(v, t)| !is_variable(t, v));
+        composed
+    }
+    /// The `Substitution` restricted to the [`Variable`]s in `vars`.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Substitution, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(x_ y_)").expect("parse of F(x_ y_)");
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    /// let vars = t.variables();
+    ///
+    /// let mut sub = Substitution::new();
+    /// sub.insert(vars[0].clone(), a.clone());
+    /// sub.insert(vars[1].clone(), a);
+    ///
+    /// assert_eq!(sub.restrict(&vars[1..]).display(&sig), "{y_ := A}");
+    /// ```
+    pub fn restrict(&self, vars: &[Variable]) -> Substitution {
+        Substitution(
+            self.0
+                .iter()
+                .filter(|(v, _)| vars.contains(v))
+                .cloned()
+                .collect(),
+        )
+    }
+    /// Does the `Substitution` merely rename [`Variable`]s, i.e. is it an injective mapping
+    /// from [`Variable`]s to [`Variable`]s?
+    ///
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Substitution, Term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let x = sig.new_var(Some("x".to_string()));
+    /// let y = sig.new_var(Some("y".to_string()));
+    /// let a = sig.new_op(0, Some("A".to_string()));
+    ///
+    /// let mut sub = Substitution::new();
+    /// sub.insert(x.clone(), Term::Variable(y.clone()));
+    /// sub.insert(y.clone(), Term::Variable(x.clone()));
+    ///
+    /// assert!(sub.is_renaming());
+    ///
+    /// sub.insert(y.clone(), Term::Variable(y.clone()));
+    ///
+    /// assert!(!sub.is_renaming());
+    ///
+    /// sub.insert(y.clone(), Term::Application { op: a, args: vec![] });
+    ///
+    /// assert!(!sub.is_renaming());
+    /// ```
+    pub fn is_renaming(&self) -> bool {
+        let mut targets = vec![];
+        for (_, t) in &self.0 {
+            match *t {
+                Term::Variable(ref w) if !targets.contains(&w) => targets.push(w),
+                _ => return false,
+            }
+        }
+        true
+    }
+    /// A `HashMap` of the bindings, as taken by [`Term::substitute`].
+    ///
+    /// [`Term::substitute`]: enum.Term.html#method.substitute
+    pub fn to_map(&self) -> HashMap<&Variable, &Term> {
+        self.iter().collect()
+    }
+    /// Serialize a `Substitution`.
+    pub fn display(&self, sig: &Signature) -> String {
+        let bindings = self
+            .0
+            .iter()
+            .map(|(v, t)| format!("{} := {}", v.display(sig), t.display(sig)))
+            .join(", ");
+        format!("{{{}}}", bindings)
+    }
+}
+impl FromIterator<(Variable, Term)> for Substitution {
+    fn from_iter<I: IntoIterator<Item = (Variable, Term)>>(iter: I) -> Substitution {
+        let mut sub = Substitution::new();
+        for (v, t) in iter {
+            sub.insert(v, t);
+        }
+        sub
+    }
+}
+impl<'a> From<HashMap<&'a Variable, &'a Term>> for Substitution {
+    fn from(map: HashMap<&'a Variable, &'a Term>) -> Substitution {
+        map.into_iter().map(|(v, t)| (*v, t.clone())).collect()
+    }
+}
+
+/// An incremental unifier: adds equality constraints between [`Term`]s one at a time,
+/// maintaining a most general [`Substitution`] satisfying all of them.
+///
+/// Without the occurs check, a [`Variable`] may be bound to a [`Term`] containing it, so the
+/// result need not be idempotent.
+///
+/// [`Term`]: enum.Term.html
+/// [`Substitution`]: struct.Substitution.html
+/// [`Variable`]: struct.Variable.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, Unifier, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term(&mut sig, "F(x_ G(y_) y_)").expect("parse of F(x_ G(y_) y_)");
+/// let a = parse_term(&mut sig, "A").expect("parse of A");
+/// let args = t.args();
+///
+/// let mut unifier = Unifier::new();
+///
+/// assert!(unifier.unify(&args[0], &args[1]));
+/// assert_eq!(unifier.substitution().display(&sig), "{x_ := G(y_)}");
+///
+/// assert!(unifier.unify(&args[2], &a));
+/// assert_eq!(unifier.substitution().display(&sig), "{x_ := G(A), y_ := A}");
+///
+/// // a failed constraint leaves the unifier unchanged.
+/// assert!(!unifier.unify(&args[0], &a));
+/// assert_eq!(unifier.substitution().display(&sig), "{x_ := G(A), y_ := A}");
+///
+/// let mut unifier = Unifier::new();
+///
+/// assert!(!unifier.unify(&args[0], &t));
+///
+/// let mut unifier = Unifier::new().occurs_check(false);
+///
+/// assert!(unifier.unify(&args[0], &t));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unifier {
+    sub: Substitution,
+    occurs_check: bool,
+}
+impl Unifier {
+    /// Create a `Unifier` with no constraints which performs the occurs check.
+    pub fn new() -> Unifier {
+        Unifier {
+            sub: Substitution::new(),
+            occurs_check: true,
+        }
+    }
+    /// Set whether to perform the occurs check.
+    pub fn occurs_check(mut self, occurs_check: bool) -> Unifier {
+        self.occurs_check = occurs_check;
+        self
+    }
+    /// The most general [`Substitution`] satisfying the constraints so far.
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    pub fn substitution(&self) -> &Substitution {
+        &self.sub
+    }
+    /// Consume the `Unifier`, returning its [`Substitution`].
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    pub fn into_substitution(self) -> Substitution {
+        self.sub
+    }
+    /// Add the constraint `s = t`. Returns `false`, leaving the `Unifier` unchanged, if the
+    /// constraint cannot be satisfied together with those already added.
+    pub fn unify(&mut self, s: &Term, t: &Term) -> bool {
+        self.unify_all(vec![(s, t)])
+    }
+    /// Add each of `constraints`. Returns `false`, leaving the `Unifier` unchanged, if the
+    /// constraints cannot all be satisfied together with those already added.
+    pub fn unify_all<'a, I>(&mut self, constraints: I) -> bool
+    where
+        I: IntoIterator<Item = (&'a Term, &'a Term)>,
+    {
+        let mut sub = self.sub.clone();
+        let mut cs: Vec<(Term, Term)> = constraints
+            .into_iter()
+            .map(|(s, t)| (s.clone(), t.clone()))
+            .collect();
+        cs.reverse();
+        while let Some((s, t)) = cs.pop() {
+            let s = sub.apply(&s);
+            let t = sub.apply(&t);
+            if s == t {
+                continue;
+            }
+            match (s, t) {
+                (Term::Variable(v), t) | (t, Term::Variable(v)) => {
+                    if self.occurs_check && t.variables().contains(&v) {
+                        return false;
+                    }
+                    let binding: Substitution = vec![(v, t)].into_iter().collect();
+                    sub = sub.compose(&binding);
+                }
+                (
+                    Term::Application {
+                        op: ref h1,
+                        args: ref a1,
+                    },
+                    Term::Application {
+                        op: ref h2,
+                        args: ref a2,
+                    },
+                ) if h1 == h2 && a1.len() == a2.len() => {
+                    cs.extend(a1.iter().cloned().zip(a2.iter().cloned()).rev());
+                }
+                _ => return false,
+            }
+        }
+        self.sub = sub;
+        true
+    }
+}
+impl Default for Unifier {
+    fn default() -> Unifier {
+        Unifier::new()
+    }
+}
+
+// Is `term` the variable `var`?
+fn is_variable(term: &Term, var: &Variable) -> bool {
+    if let Term::Variable(ref w) = *term {
+        w == var
+    } else {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
+    use super::*;
+
+    #[test]
+    fn compose_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "F(x_ y_ z_ G(x_))").expect("parse of F(x_ y_ z_ G(x_))");
+        let vars = t.variables();
+        let (x, y, z) = (&vars[0], &vars[1], &vars[2]);
+
+        let mut s1 = Substitution::new();
+        s1.insert(*x, Term::Variable(*y));
+        s1.insert(*z, t.args()[3].clone());
+        let mut s2 = Substitution::new();
+        s2.insert(*y, Term::Variable(*x));
+        s2.insert(*x, parse_term(&mut sig, "A").expect("parse of A"));
+
+        let s = s1.compose(&s2);
+
+        assert_eq!(s.apply(&t), s2.apply(&s1.apply(&t)));
+        assert_eq!(s.display(&sig), "{y_ := x_, z_ := G(A)}");
+        assert_eq!(s.domain(), vec![y, z]);
+        assert_eq!(Substitution::new().compose(&s), s);
+        assert_eq!(s.compose(&Substitution::new()), s);
+    }
+}
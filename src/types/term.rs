@@ -1,8 +1,16 @@
-use super::super::pretty::Pretty;
-use super::{Atom, Operator, Place, Unification, Variable};
+use super::super::pretty::{Pretty, PrettyConfig};
+use super::signature::Sig;
+use super::trs::dot_escape;
+use super::{
+    Atom, OpMap, Operator, Place, Position, Signature, Substitution, Unification, Unifier, VarMap,
+    Variable,
+};
 use itertools::Itertools;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::iter;
+use std::slice;
 
 /// A first-order `Context`: a [`Term`] that may have [`Hole`]s; a sort of [`Term`] template.
 ///
@@ -28,7 +36,7 @@ use std::iter;
 /// // Constructing a Context using the Parser.
 /// let context2 = parse_context(&mut sig, "A(B x_ [!])").expect("parse of A(B x_ [!])");
 ///
-/// assert_eq!(context.display(), context2.display());
+/// assert_eq!(context.display(&sig), context2.display(&sig));
 /// ```
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Context {
@@ -45,7 +53,7 @@ pub enum Context {
     /// let mut sig = Signature::default();
     /// let h2 = parse_context(&mut sig, "[!]").expect("parse of [!]");
     ///
-    /// assert_eq!(h.display(), h2.display());
+    /// assert_eq!(h.display(&sig), h2.display(&sig));
     /// ```
     Hole,
     /// A concrete but unspecified `Context` (e.g. `x`, `y`)
@@ -63,7 +71,7 @@ pub enum Context {
     /// //Contstructing a Context Variable using the parser.
     /// let var2 = parse_context(&mut sig, "x_").expect("parse of x_");
     ///
-    /// assert_eq!(var.display(), var2.display());
+    /// assert_eq!(var.display(&sig), var2.display(&sig));
     /// ```
     Variable(Variable),
     /// An [`Operator`] applied to zero or more `Context`s (e.g. (`f(x, y)`, `g()`)
@@ -99,19 +107,29 @@ impl Context {
     /// let context = parse_context(&mut sig, "x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
     ///     .expect("parse of x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)") ;
     ///
-    /// assert_eq!(context.display(), ".(.(.(.(x_ [!]) A) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
+    /// assert_eq!(context.display(&sig), ".(.(.(.(x_ [!]) A) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
     /// ```
-    pub fn display(&self) -> String {
-        match self {
-            Context::Hole => "[!]".to_string(),
-            Context::Variable(v) => v.display(),
-            Context::Application { op, args } => {
-                let op_str = op.display();
-                if args.is_empty() {
-                    op_str
-                } else {
-                    let args_str = args.iter().map(Context::display).join(" ");
-                    format!("{}({})", op_str, args_str)
+    pub fn display(&self, sig: &Signature) -> String {
+        let mut out = String::new();
+        self.display_into(&sig.sig.read().expect("poisoned signature"), &mut out);
+        out
+    }
+    // Serialize `self` onto `out`.
+    pub(crate) fn display_into(&self, sig: &Sig, out: &mut String) {
+        match *self {
+            Context::Hole => out.push_str("[!]"),
+            Context::Variable(v) => out.push_str(&sig.display_var(v.id)),
+            Context::Application { op, ref args } => {
+                out.push_str(&sig.display_op(op.id));
+                if !args.is_empty() {
+                    out.push('(');
+                    for (i, arg) in args.iter().enumerate() {
+                        if i > 0 {
+                            out.push(' ');
+                        }
+                        arg.display_into(sig, out);
+                    }
+                    out.push(')');
                 }
             }
         }
@@ -127,10 +145,26 @@ impl Context {
     /// let context = parse_context(&mut sig, "x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
     ///     .expect("parse of x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)") ;
     ///
-    /// assert_eq!(context.pretty(), "x_ [!] A [2, 1, 0] 105");
+    /// assert_eq!(context.pretty(&sig), "x_ [!] A [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105");
+    /// ```
+    pub fn pretty(&self, sig: &Signature) -> String {
+        Pretty::pretty(self, sig)
+    }
+    /// A human-readable serialization of the `Context`, as configured by `config`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, PrettyConfig, parse_context};
+    /// let mut sig = Signature::default();
+    ///
+    /// let context = parse_context(&mut sig, "F([!] CONS(A NIL))").expect("parse of F([!] CONS(A NIL))");
+    ///
+    /// let config = PrettyConfig { lists: false, ..PrettyConfig::default() };
+    /// assert_eq!(context.pretty_with(&sig, &config), "F([!], CONS(A, NIL))");
     /// ```
-    pub fn pretty(&self) -> String {
-        Pretty::pretty(self)
+    pub fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
+        Pretty::pretty_with(self, sig, config)
     }
     /// Every [`Atom`] used in the `Context`.
     ///
@@ -144,7 +178,7 @@ impl Context {
     ///
     /// let context = parse_context(&mut sig, "A(B x_ [!])").expect("parse of A(B x_ [!])");
     ///
-    /// let atoms: Vec<String> = context.atoms().iter().map(|a| a.display()).collect();
+    /// let atoms: Vec<String> = context.atoms().iter().map(|a| a.display(&sig)).collect();
     ///
     /// assert_eq!(atoms, vec!["x_", "B", "A"]);
     /// ```
@@ -165,14 +199,14 @@ impl Context {
     ///
     /// let context = parse_context(&mut sig, "A([!]) B y_ z_").expect("parse of A([!]) B y_ z_");
     ///
-    /// let var_names: Vec<String> = context.variables().iter().map(|v| v.display()).collect();
+    /// let var_names: Vec<String> = context.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(var_names, vec!["y_".to_string(), "z_".to_string()]);
     /// ```
     pub fn variables(&self) -> Vec<Variable> {
         match *self {
             Context::Hole => vec![],
-            Context::Variable(ref v) => vec![v.clone()],
+            Context::Variable(ref v) => vec![*v],
             Context::Application { ref args, .. } => {
                 args.iter().flat_map(Context::variables).unique().collect()
             }
@@ -190,7 +224,7 @@ impl Context {
     ///
     /// let context = parse_context(&mut sig, "A([!]) B y_ z_").expect("parse of A([!]) B y_ z_");
     ///
-    /// let op_names: Vec<String> = context.operators().iter().map(|v| v.display()).collect();
+    /// let op_names: Vec<String> = context.operators().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(op_names, vec!["A".to_string(), "B".to_string(), ".".to_string()]);
     /// ```
@@ -198,7 +232,7 @@ impl Context {
         if let Context::Application { ref op, ref args } = *self {
             args.iter()
                 .flat_map(Context::operators)
-                .chain(iter::once(op.clone()))
+                .chain(iter::once(*op))
                 .unique()
                 .collect()
         } else {
@@ -245,13 +279,13 @@ impl Context {
     ///
     /// let context = parse_context(&mut sig, "A(B([!]) z_)").expect("parse of A(B([!]) z_)");
     ///
-    /// assert_eq!(context.head().unwrap().display(), "A");
+    /// assert_eq!(context.head().unwrap().display(&sig), "A");
     /// ```
     pub fn head(&self) -> Option<Atom> {
         match self {
             Context::Hole => None,
-            Context::Variable(v) => Some(Atom::Variable(v.clone())),
-            Context::Application { op, .. } => Some(Atom::Operator(op.clone())),
+            Context::Variable(v) => Some(Atom::Variable(*v)),
+            Context::Application { op, .. } => Some(Atom::Operator(*op)),
         }
     }
     /// The args of the `Context`.
@@ -263,12 +297,12 @@ impl Context {
     /// let mut sig = Signature::default();
     ///
     /// let context = parse_context(&mut sig, "A B").expect("parse of A B");
-    /// let args: Vec<String> = context.args().iter().map(|arg| arg.display()).collect();
+    /// let args: Vec<String> = context.args().iter().map(|arg| arg.display(&sig)).collect();
     ///
     /// assert_eq!(args, vec!["A", "B"]);
     ///
     /// let context = parse_context(&mut sig, "A(y_)").expect("parse of A(y_)");
-    /// let args: Vec<String> = context.args().iter().map(|arg| arg.display()).collect();
+    /// let args: Vec<String> = context.args().iter().map(|arg| arg.display(&sig)).collect();
     ///
     /// assert_eq!(args, vec!["y_"]);
     /// ```
@@ -357,19 +391,19 @@ impl Context {
     ///
     /// let p: &[usize] = &[0];
     ///
-    /// assert_eq!(context.at(p).unwrap().display(), "A");
+    /// assert_eq!(context.at(p).unwrap().display(&sig), "A");
     /// ```
     #[cfg_attr(feature = "cargo-clippy", allow(clippy::ptr_arg))]
     pub fn at(&self, place: &[usize]) -> Option<&Context> {
-        self.at_helper(&*place)
+        self.at_helper(place)
     }
     fn at_helper(&self, place: &[usize]) -> Option<&Context> {
         if place.is_empty() {
             return Some(self);
         }
         match *self {
-            Context::Application { ref args, .. } if place[0] <= args.len() => {
-                args[place[0]].at_helper(&place[1..].to_vec())
+            Context::Application { ref args, .. } if place[0] < args.len() => {
+                args[place[0]].at_helper(&place[1..])
             }
             _ => None,
         }
@@ -391,25 +425,23 @@ impl Context {
     /// let p: &[usize] = &[0];
     /// let new_context = context.replace(p, context2);
     ///
-    /// assert_eq!(new_context.unwrap().pretty(), "B(C [!])");
+    /// assert_eq!(new_context.unwrap().pretty(&sig), "B(C [!])");
     /// ```
     pub fn replace(&self, place: &[usize], subcontext: Context) -> Option<Context> {
-        self.replace_helper(&*place, subcontext)
+        self.replace_helper(place, subcontext)
     }
     fn replace_helper(&self, place: &[usize], subcontext: Context) -> Option<Context> {
         if place.is_empty() {
             Some(subcontext)
         } else {
             match *self {
-                Context::Application { ref op, ref args } if place[0] <= args.len() => {
-                    if let Some(context) =
-                        args[place[0]].replace_helper(&place[1..].to_vec(), subcontext)
-                    {
+                Context::Application { ref op, ref args } if place[0] < args.len() => {
+                    if let Some(context) = args[place[0]].replace_helper(&place[1..], subcontext) {
                         let mut new_args = args.clone();
                         new_args.remove(place[0]);
                         new_args.insert(place[0], context);
                         Some(Context::Application {
-                            op: op.clone(),
+                            op: *op,
                             args: new_args,
                         })
                     } else {
@@ -438,42 +470,147 @@ impl Context {
     ///
     /// let term = context.to_term().expect("converting context to term");
     ///
-    /// assert_eq!(term.display(), "A(B C)");
+    /// assert_eq!(term.display(&sig), "A(B C)");
     /// ```
     pub fn to_term(&self) -> Result<Term, ()> {
         match *self {
             Context::Hole => Err(()),
-            Context::Variable(ref v) => Ok(Term::Variable(v.clone())),
+            Context::Variable(ref v) => Ok(Term::Variable(*v)),
             Context::Application { ref op, ref args } => {
                 let mut mapped_args = vec![];
                 for arg in args {
                     mapped_args.push(arg.to_term()?);
                 }
                 Ok(Term::Application {
-                    op: op.clone(),
+                    op: *op,
                     args: mapped_args,
                 })
             }
         }
     }
+    /// Rebuild the `Context` with the [`Operator`]s and [`Variable`]s of another [`Signature`],
+    /// replacing each [`Operator`] by its image in `ops` and each [`Variable`] by its image in
+    /// `vars`. Returns `None` if either map lacks an [`Atom`] of the `Context`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`Atom`]: enum.Atom.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_context};
+    /// # use std::collections::HashMap;
+    /// let mut sig = Signature::default();
+    /// let context = parse_context(&mut sig, "A(x_ [!])").expect("parse of A(x_ [!])");
+    ///
+    /// let mut sig2 = Signature::default();
+    /// let context_ops = context.operators();
+    /// let mut ops = HashMap::new();
+    /// ops.insert(context_ops[0], sig2.new_op(2, Some("F".to_string())));
+    /// let context_vars = context.variables();
+    /// let mut vars = HashMap::new();
+    /// vars.insert(context_vars[0], sig2.new_var(Some("y".to_string())));
+    ///
+    /// let mapped = context.map_signature(&ops, &vars).expect("mapped context");
+    ///
+    /// assert_eq!(mapped.display(&sig2), "F(y_ [!])");
+    /// assert_eq!(context.map_signature(&ops, &HashMap::new()), None);
+    /// ```
+    pub fn map_signature(&self, ops: &OpMap, vars: &VarMap) -> Option<Context> {
+        match *self {
+            Context::Hole => Some(Context::Hole),
+            Context::Variable(ref v) => vars.get(v).cloned().map(Context::Variable),
+            Context::Application { ref op, ref args } => Some(Context::Application {
+                op: *ops.get(op)?,
+                args: args
+                    .iter()
+                    .map(|arg| arg.map_signature(ops, vars))
+                    .collect::<Option<_>>()?,
+            }),
+        }
+    }
+    /// Fill the holes of the `Context` with `fillers`, in the order given by
+    /// [`Context::holes`], to create a [`Term`]. Returns `None` unless there is exactly one
+    /// filler per hole.
+    ///
+    /// [`Context::holes`]: enum.Context.html#method.holes
+    /// [`Term`]: enum.Term.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_context, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let context = parse_context(&mut sig, "A([!] B([!]))").expect("parse of A([!] B([!]))");
+    /// let t = parse_term(&mut sig, "F(C x_)").expect("parse of F(C x_)");
+    ///
+    /// let term = context.fill(&t.args()).expect("filled context");
+    ///
+    /// assert_eq!(term.display(&sig), "A(C B(x_))");
+    /// assert_eq!(context.fill(&[]), None);
+    /// ```
+    pub fn fill(&self, fillers: &[Term]) -> Option<Term> {
+        if fillers.len() != self.holes().len() {
+            return None;
+        }
+        Some(self.fill_with(&mut fillers.iter()))
+    }
+    fn fill_with(&self, fillers: &mut slice::Iter<Term>) -> Term {
+        match *self {
+            Context::Hole => fillers.next().expect("filler for each hole").clone(),
+            Context::Variable(ref v) => Term::Variable(*v),
+            Context::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args.iter().map(|arg| arg.fill_with(fillers)).collect(),
+            },
+        }
+    }
+    /// Create a `Context` from `term` by replacing its subterm at `place` with a hole. Returns
+    /// `None` if `place` is not in `term`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Context, Position, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let term = parse_term(&mut sig, "A(B C(D))").expect("parse of A(B C(D))");
+    ///
+    /// let context = Context::from_term_with_hole(&term, &Position::from(vec![1, 0]))
+    ///     .expect("context");
+    ///
+    /// assert_eq!(context.display(&sig), "A(B C([!]))");
+    /// assert_eq!(context.fill(&[term.clone()]).expect("filled context").display(&sig), "A(B C(A(B C(D))))");
+    /// assert_eq!(Context::from_term_with_hole(&term, &[0, 0]), None);
+    /// ```
+    pub fn from_term_with_hole(term: &Term, place: &[usize]) -> Option<Context> {
+        Context::from(term.clone()).replace(place, Context::Hole)
+    }
 }
 impl From<Term> for Context {
     fn from(t: Term) -> Context {
-        match t {
-            Term::Variable(v) => Context::Variable(v),
-            Term::Application { op, args } => {
-                let args = args.into_iter().map(Context::from).collect();
-                Context::Application { op, args }
-            }
-        }
+        t.fold(
+            |v| Context::Variable(*v),
+            |op, args| Context::Application { op: *op, args },
+        )
     }
 }
 
 /// A first-order term: either a [`Variable`] or an application of an [`Operator`].
 ///
+/// Copying, comparing, hashing, displaying, substituting into, and rewriting a `Term` use
+/// explicit stacks rather than recursion, so they work on `Term`s of any depth, such as long
+/// `CONS` lists. Dropping a `Term` does recurse, so drop very deep `Term`s with
+/// [`Term::drop_deep`].
+///
+/// [`Term::drop_deep`]: enum.Term.html#method.drop_deep
+///
 /// [`Variable`]: struct.Variable.html
 /// [`Operator`]: struct.Operator.html
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug)]
 pub enum Term {
     /// A concrete but unspecified `Term` (e.g. `x`, `y`).
     /// See [`Variable`] for more information.
@@ -541,23 +678,48 @@ impl Term {
     /// let term = parse_term(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)");
     ///
-    /// assert_eq!(term.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
+    /// assert_eq!(term.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
     /// ```
-    pub fn display(&self) -> String {
-        match self {
-            Term::Variable(ref v) => v.display(),
-            Term::Application { ref op, ref args } => {
-                let op_str = op.display();
-                if args.is_empty() {
-                    op_str
-                } else {
-                    let args_str = args.iter().map(Term::display).join(" ");
-                    format!("{}({})", op_str, args_str)
+    pub fn display(&self, sig: &Signature) -> String {
+        let mut out = String::new();
+        self.display_into(&sig.sig.read().expect("poisoned signature"), &mut out);
+        out
+    }
+    // Serialize `self` onto `out`, using an explicit stack so that deep `Term`s don't overflow.
+    pub(crate) fn display_into(&self, sig: &Sig, out: &mut String) {
+        enum Piece<'a> {
+            Term(&'a Term),
+            Text(&'static str),
+        }
+        let mut todo = vec![Piece::Term(self)];
+        while let Some(piece) = todo.pop() {
+            match piece {
+                Piece::Text(text) => out.push_str(text),
+                Piece::Term(&Term::Variable(v)) => out.push_str(&sig.display_var(v.id)),
+                Piece::Term(&Term::Application { op, ref args }) => {
+                    out.push_str(&sig.display_op(op.id));
+                    if !args.is_empty() {
+                        todo.push(Piece::Text(")"));
+                        for (i, arg) in args.iter().enumerate().rev() {
+                            todo.push(Piece::Term(arg));
+                            if i > 0 {
+                                todo.push(Piece::Text(" "));
+                            }
+                        }
+                        todo.push(Piece::Text("("));
+                    }
                 }
             }
         }
     }
-    /// A human-readable serialization of the `Term`.
+    /// A human-readable serialization of the `Term`, which [`parse_term_pretty`] reads back as
+    /// the same `Term`, up to the names of [`Variable`]s, unless an [`Operator`] has a
+    /// [`DisplayStyle`] other than `Prefix`.
+    ///
+    /// [`parse_term_pretty`]: fn.parse_term_pretty.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`DisplayStyle`]: enum.DisplayStyle.html
     ///
     /// # Examples
     ///
@@ -568,10 +730,109 @@ impl Term {
     /// let term = parse_term(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
     ///     .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)");
     ///
-    /// assert_eq!(term.pretty(), "A B(x_) [2, 1, 0] 105");
+    /// assert_eq!(term.pretty(&sig), "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105");
     /// ```
-    pub fn pretty(&self) -> String {
-        Pretty::pretty(self)
+    pub fn pretty(&self, sig: &Signature) -> String {
+        Pretty::pretty(self, sig)
+    }
+    /// A human-readable serialization of the `Term`, as configured by `config`.
+    ///
+    /// See [`PrettyConfig`] for more information.
+    ///
+    /// [`PrettyConfig`]: struct.PrettyConfig.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, PrettyConfig, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let term = parse_term(&mut sig, "PLUS(SUCC(x_) CONS(A CONS(B NIL)))")
+    ///     .expect("parse of PLUS(SUCC(x_) CONS(A CONS(B NIL)))");
+    ///
+    /// let config = PrettyConfig { width: Some(16), ..PrettyConfig::default() };
+    /// assert_eq!(term.pretty_with(&sig, &config), "PLUS(\n  SUCC(x_),\n  [A, B]\n)");
+    /// ```
+    pub fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
+        Pretty::pretty_with(self, sig, config)
+    }
+    /// Render the tree of the `Term` in the [DOT language] of Graphviz, with a node for each
+    /// subterm labeled by its [`Operator`] or [`Variable`], numbered in preorder. If `sharing`,
+    /// equal subterms share a single node, drawing the `Term` as a DAG.
+    ///
+    /// [DOT language]: https://graphviz.org/doc/info/lang.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let term = parse_term(&mut sig, "F(G(x_) G(x_))").expect("parse of F(G(x_) G(x_))");
+    ///
+    /// assert_eq!(term.to_dot(&sig, false),
+    /// "digraph {
+    ///     0 [label=\"F\"];
+    ///     1 [label=\"G\"];
+    ///     2 [label=\"x_\"];
+    ///     3 [label=\"G\"];
+    ///     4 [label=\"x_\"];
+    ///     0 -> 1;
+    ///     0 -> 3;
+    ///     1 -> 2;
+    ///     3 -> 4;
+    /// }
+    /// ");
+    ///
+    /// assert_eq!(term.to_dot(&sig, true),
+    /// "digraph {
+    ///     0 [label=\"F\"];
+    ///     1 [label=\"G\"];
+    ///     2 [label=\"x_\"];
+    ///     0 -> 1;
+    ///     0 -> 1;
+    ///     1 -> 2;
+    /// }
+    /// ");
+    /// ```
+    pub fn to_dot(&self, sig: &Signature, sharing: bool) -> String {
+        let mut nodes = vec![];
+        self.dot_node(sharing, &mut nodes);
+        let mut dot = String::from("digraph {\n");
+        for (i, (node, _)) in nodes.iter().enumerate() {
+            let label = match **node {
+                Term::Variable(v) => v.display(sig),
+                Term::Application { op, .. } => op.display(sig),
+            };
+            dot.push_str(&format!("    {} [label=\"{}\"];\n", i, dot_escape(&label)));
+        }
+        for (i, (_, children)) in nodes.iter().enumerate() {
+            for child in children {
+                dot.push_str(&format!("    {} -> {};\n", i, child));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    // Add the nodes for `self` and its subterms, each with the indices of its children,
+    // returning the index of `self`.
+    fn dot_node<'a>(&'a self, sharing: bool, nodes: &mut Vec<(&'a Term, Vec<usize>)>) -> usize {
+        if sharing {
+            if let Some(i) = nodes.iter().position(|(node, _)| *node == self) {
+                return i;
+            }
+        }
+        let i = nodes.len();
+        nodes.push((self, vec![]));
+        if let Term::Application { ref args, .. } = *self {
+            for arg in args {
+                let j = arg.dot_node(sharing, nodes);
+                nodes[i].1.push(j);
+            }
+        }
+        i
     }
     /// Every [`Atom`] used in the `Term`.
     ///
@@ -584,7 +845,7 @@ impl Term {
     /// let mut sig = Signature::default();
     ///
     /// let example_term = parse_term(&mut sig, "A(B x_)").expect("parse of A(B x_)");
-    /// let atoms: Vec<String> = example_term.atoms().iter().map(|a| a.display()).collect();
+    /// let atoms: Vec<String> = example_term.atoms().iter().map(|a| a.display(&sig)).collect();
     ///
     /// assert_eq!(atoms, vec!["x_", "B", "A"]);
     /// ```
@@ -604,13 +865,13 @@ impl Term {
     /// let mut sig = Signature::default();
     ///
     /// let t = parse_term(&mut sig, "A B y_ z_").expect("parse of A B y_ z_");
-    /// let var_names: Vec<String> = t.variables().iter().map(|v| v.display()).collect();
+    /// let var_names: Vec<String> = t.variables().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(var_names, vec!["y_", "z_"]);
     /// ```
     pub fn variables(&self) -> Vec<Variable> {
         match *self {
-            Term::Variable(ref v) => vec![v.clone()],
+            Term::Variable(ref v) => vec![*v],
             Term::Application { ref args, .. } => {
                 args.iter().flat_map(Term::variables).unique().collect()
             }
@@ -627,7 +888,7 @@ impl Term {
     /// let mut sig = Signature::default();
     ///
     /// let t = parse_term(&mut sig, "A B y_ z_").expect("parse of A B y_ z_");
-    /// let op_names: Vec<String> = t.operators().iter().map(|v| v.display()).collect();
+    /// let op_names: Vec<String> = t.operators().iter().map(|v| v.display(&sig)).collect();
     ///
     /// assert_eq!(op_names, vec!["A", "B", "."]);
     /// ```
@@ -637,7 +898,7 @@ impl Term {
             Term::Application { ref op, ref args } => args
                 .iter()
                 .flat_map(Term::operators)
-                .chain(iter::once(op.clone()))
+                .chain(iter::once(*op))
                 .unique()
                 .collect(),
         }
@@ -658,8 +919,8 @@ impl Term {
     /// ```
     pub fn head(&self) -> Atom {
         match self {
-            Term::Variable(v) => Atom::Variable(v.clone()),
-            Term::Application { op, .. } => Atom::Operator(op.clone()),
+            Term::Variable(v) => Atom::Variable(*v),
+            Term::Application { op, .. } => Atom::Operator(*op),
         }
     }
     /// The arguments of the `Term`.
@@ -717,23 +978,28 @@ impl Term {
     /// assert_eq!(t.subterms(), vec![(&subterm0, p), (&subterm1, p1)]);
     /// ```
     pub fn subterms(&self) -> Vec<(&Term, Place)> {
-        match *self {
-            Term::Variable(_) => vec![(self, vec![])],
-            Term::Application { ref args, .. } => {
-                let here = iter::once((self, vec![]));
-                let subterms = args.iter().enumerate().flat_map(|(i, arg)| {
-                    arg.subterms()
-                        .into_iter()
-                        .zip(iter::repeat(i))
-                        .map(|((t, p), i)| {
-                            let mut a = vec![i];
-                            a.extend(p);
-                            (t, a)
-                        })
-                });
-                here.chain(subterms).collect()
-            }
-        }
+        self.preorder(vec![]).collect()
+    }
+    /// The [`Position`]s of every subterm of the `Term`, in preorder.
+    ///
+    /// [`Position`]: struct.Position.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A G(B))").expect("parse of F(A G(B))");
+    /// let positions: Vec<String> = t.positions().iter().map(|p| p.display()).collect();
+    ///
+    /// assert_eq!(positions, vec!["ε", "0", "1", "1.0"]);
+    /// ```
+    pub fn positions(&self) -> Vec<Position> {
+        self.subterms()
+            .into_iter()
+            .map(|(_, place)| Position::from(place))
+            .collect()
     }
     /// The number of distinct [`Place`]s in the `Term`.
     ///
@@ -754,7 +1020,42 @@ impl Term {
     /// assert_eq!(t.size(), 2);
     /// ```
     pub fn size(&self) -> usize {
-        self.subterms().len()
+        let mut size = 0;
+        let mut stack = vec![self];
+        while let Some(term) = stack.pop() {
+            size += 1;
+            if let Term::Application { ref args, .. } = *term {
+                stack.extend(args);
+            }
+        }
+        size
+    }
+    /// Drop the `Term` using an explicit stack rather than recursion. Dropping a `Term` the
+    /// usual way recurses once per level, and so can overflow the stack for very deep `Term`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term};
+    /// let mut sig = Signature::default();
+    /// let succ = sig.new_op(1, Some("SUCC".to_string()));
+    /// let zero = sig.new_op(0, Some("ZERO".to_string()));
+    ///
+    /// let mut t = Term::Application { op: zero, args: vec![] };
+    /// for _ in 0..100_000 {
+    ///     t = Term::Application { op: succ, args: vec![t] };
+    /// }
+    ///
+    /// assert_eq!(t.size(), 100_001);
+    /// t.drop_deep();
+    /// ```
+    pub fn drop_deep(self) {
+        let mut stack = vec![self];
+        while let Some(term) = stack.pop() {
+            if let Term::Application { args, .. } = term {
+                stack.extend(args);
+            }
+        }
     }
     /// Get the `subterm` at the given [`Place`] if possible.  Otherwise, return `None`.
     ///
@@ -780,23 +1081,67 @@ impl Term {
     /// ```
     #[cfg_attr(feature = "cargo-clippy", allow(clippy::ptr_arg))]
     pub fn at(&self, place: &[usize]) -> Option<&Term> {
-        self.at_helper(&*place)
+        let mut term = self;
+        for &i in place {
+            term = match *term {
+                Term::Application { ref args, .. } => args.get(i)?,
+                Term::Variable(_) => return None,
+            };
+        }
+        Some(term)
     }
-    fn at_helper(&self, place: &[usize]) -> Option<&Term> {
-        if place.is_empty() {
-            Some(self)
-        } else {
-            match *self {
-                Term::Variable(_) => None,
-                Term::Application { ref args, .. } => {
-                    if place[0] <= args.len() {
-                        args[place[0]].at_helper(&place[1..].to_vec())
-                    } else {
-                        None
-                    }
-                }
-            }
+    /// A mutable reference to the `Term` at the given [`Place`], if any.
+    ///
+    /// [`Place`]: type.Place.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_term(&mut sig, "B(A C)").expect("parse of B(A C)");
+    /// let d = parse_term(&mut sig, "D").expect("parse of D");
+    ///
+    /// *t.at_mut(&[1]).expect("subterm") = d;
+    ///
+    /// assert_eq!(t.display(&sig), "B(A D)");
+    /// assert_eq!(t.at_mut(&[2]), None);
+    /// ```
+    pub fn at_mut(&mut self, place: &[usize]) -> Option<&mut Term> {
+        let mut term = self;
+        for &i in place {
+            term = match *term {
+                Term::Application { ref mut args, .. } => args.get_mut(i)?,
+                Term::Variable(_) => return None,
+            };
         }
+        Some(term)
+    }
+    /// Replace the `Term` at the given [`Place`] with `subterm` without copying the rest of the
+    /// `Term`, returning the `Term` replaced, or `None` (leaving the `Term` unchanged) if there
+    /// is no such [`Place`].
+    ///
+    /// [`Place`]: type.Place.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_term(&mut sig, "B(A G(C))").expect("parse of B(A G(C))");
+    /// let d = parse_term(&mut sig, "D").expect("parse of D");
+    ///
+    /// let old = t.replace_in_place(&[1, 0], d.clone()).expect("replacement");
+    ///
+    /// assert_eq!(old.display(&sig), "C");
+    /// assert_eq!(t.display(&sig), "B(A G(D))");
+    /// assert_eq!(t.replace_in_place(&[0, 0], d), None);
+    /// ```
+    pub fn replace_in_place(&mut self, place: &[usize], subterm: Term) -> Option<Term> {
+        self.at_mut(place)
+            .map(|term| ::std::mem::replace(term, subterm))
     }
     /// Create a copy of the `Term` where the `Term` at the given [`Place`] has been replaced with
     /// `subterm`.
@@ -820,30 +1165,58 @@ impl Term {
     /// ```
     #[cfg_attr(feature = "cargo-clippy", allow(clippy::ptr_arg))]
     pub fn replace(&self, place: &[usize], subterm: Term) -> Option<Term> {
-        self.replace_helper(&*place, subterm)
-    }
-    fn replace_helper(&self, place: &[usize], subterm: Term) -> Option<Term> {
-        if place.is_empty() {
-            Some(subterm)
-        } else {
-            match *self {
-                Term::Application { ref op, ref args } if place[0] <= args.len() => {
-                    if let Some(term) = args[place[0]].replace_helper(&place[1..].to_vec(), subterm)
-                    {
-                        let mut new_args = args.clone();
-                        new_args.remove(place[0]);
-                        new_args.insert(place[0], term);
-                        Some(Term::Application {
-                            op: op.clone(),
-                            args: new_args,
-                        })
-                    } else {
-                        None
-                    }
+        // find the applications along `place`, then rebuild them bottom-up around `subterm`.
+        let mut spine = Vec::with_capacity(place.len());
+        let mut term = self;
+        for &i in place {
+            match *term {
+                Term::Application { ref op, ref args } if i < args.len() => {
+                    spine.push((op, args, i));
+                    term = &args[i];
                 }
-                _ => None,
+                _ => return None,
             }
         }
+        Some(
+            spine
+                .into_iter()
+                .rev()
+                .fold(subterm, |subterm, (op, args, i)| Term::Application {
+                    op: *op,
+                    args: args[..i]
+                        .iter()
+                        .cloned()
+                        .chain(iter::once(subterm))
+                        .chain(args[i + 1..].iter().cloned())
+                        .collect(),
+                }),
+        )
+    }
+    /// Create a copy of the `Term` where the `Term` at `position` has been replaced with
+    /// `subterm`, or `None` if `position` is not a [`Position`] of the `Term`.
+    ///
+    /// [`Position`]: struct.Position.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Position, Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A G(B))").expect("parse of F(A G(B))");
+    /// let c = parse_term(&mut sig, "C").expect("parse of C");
+    ///
+    /// let p = "1.0".parse::<Position>().expect("position");
+    /// let new_term = t.replace_at(&p, c.clone()).expect("replacement");
+    ///
+    /// assert_eq!(new_term.display(&sig), "F(A G(C))");
+    ///
+    /// let p = Position::from(vec![2]);
+    ///
+    /// assert_eq!(t.replace_at(&p, c), None);
+    /// ```
+    pub fn replace_at(&self, position: &Position, subterm: Term) -> Option<Term> {
+        self.replace(position, subterm)
     }
     /// Replace all occurrences of `old_term` with `new_term`
     pub fn replace_all(&self, old_term: &Term, new_term: &Term) -> Term {
@@ -856,7 +1229,7 @@ impl Term {
                     .map(|arg| arg.replace_all(old_term, new_term))
                     .collect_vec();
                 Term::Application {
-                    op: op.clone(),
+                    op: *op,
                     args: new_args,
                 }
             }
@@ -925,12 +1298,62 @@ impl Term {
     /// assert_eq!(subbed_term, expected_term);
     /// ```
     pub fn substitute(&self, sub: &HashMap<&Variable, &Term>) -> Term {
-        match *self {
-            Term::Variable(ref v) => (*(sub.get(v).unwrap_or(&self))).clone(),
-            Term::Application { ref op, ref args } => Term::Application {
-                op: op.clone(),
-                args: args.iter().map(|t| t.substitute(sub)).collect(),
+        self.fold(
+            |v| match sub.get(v) {
+                Some(t) => (*t).clone(),
+                None => Term::Variable(*v),
             },
+            |op, args| Term::Application { op: *op, args },
+        )
+    }
+    /// Rebuild the `Term` with the [`Operator`]s and [`Variable`]s of another [`Signature`],
+    /// replacing each [`Operator`] by its image in `ops` and each [`Variable`] by its image in
+    /// `vars`. Returns `None` if either map lacks an [`Atom`] of the `Term`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`Atom`]: enum.Atom.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// # use std::collections::HashMap;
+    /// let mut sig = Signature::default();
+    /// let term = parse_term(&mut sig, "A(x_ B)").expect("parse of A(x_ B)");
+    ///
+    /// let mut sig2 = Signature::default();
+    /// let f = sig2.new_op(2, Some("F".to_string()));
+    /// let c = sig2.new_op(0, Some("C".to_string()));
+    /// let y = sig2.new_var(Some("y".to_string()));
+    ///
+    /// let sig_ops = sig.operators();
+    /// let mut ops = HashMap::new();
+    /// ops.insert(sig_ops[0], c);
+    /// ops.insert(sig_ops[1], f);
+    /// let sig_vars = sig.variables();
+    /// let mut vars = HashMap::new();
+    /// vars.insert(sig_vars[0], y);
+    ///
+    /// let mapped = term.map_signature(&ops, &vars).expect("mapped term");
+    ///
+    /// assert_eq!(mapped.display(&sig2), "F(y_ C)");
+    ///
+    /// vars.clear();
+    ///
+    /// assert_eq!(term.map_signature(&ops, &vars), None);
+    /// ```
+    pub fn map_signature(&self, ops: &OpMap, vars: &VarMap) -> Option<Term> {
+        match *self {
+            Term::Variable(ref v) => vars.get(v).cloned().map(Term::Variable),
+            Term::Application { ref op, ref args } => Some(Term::Application {
+                op: *ops.get(op)?,
+                args: args
+                    .iter()
+                    .map(|arg| arg.map_signature(ops, vars))
+                    .collect::<Option<_>>()?,
+            }),
         }
     }
     /// Compute the [alpha equivalence] for two `Term`s.
@@ -952,10 +1375,10 @@ impl Term {
     /// let vars = sig.variables();
     /// let (y, z, a, b) = (&vars[0], &vars[1], &vars[2], &vars[3]);
     ///
-    /// assert_eq!(y.display(), "y_".to_string());
-    /// assert_eq!(z.display(), "z_".to_string());
-    /// assert_eq!(a.display(), "a_".to_string());
-    /// assert_eq!(b.display(), "b_".to_string());
+    /// assert_eq!(y.display(&sig), "y_".to_string());
+    /// assert_eq!(z.display(&sig), "z_".to_string());
+    /// assert_eq!(a.display(&sig), "a_".to_string());
+    /// assert_eq!(b.display(&sig), "b_".to_string());
     ///
     /// let ta = Term::Variable(a.clone());
     /// let tb = Term::Variable(b.clone());
@@ -1004,20 +1427,20 @@ impl Term {
         omap: &mut HashMap<Operator, Operator>,
     ) -> bool {
         match (t1, t2) {
-            (&Term::Variable(ref v1), &Term::Variable(ref v2)) => {
-                v2 == vmap.entry(v1.clone()).or_insert_with(|| v2.clone())
+            (Term::Variable(ref v1), Term::Variable(ref v2)) => {
+                v2 == vmap.entry(*v1).or_insert_with(|| *v2)
             }
             (
-                &Term::Application {
+                Term::Application {
                     op: ref op1,
                     args: ref args1,
                 },
-                &Term::Application {
+                Term::Application {
                     op: ref op2,
                     args: ref args2,
                 },
             ) => {
-                op2 == omap.entry(op1.clone()).or_insert_with(|| op2.clone())
+                op2 == omap.entry(*op1).or_insert_with(|| *op2)
                     && args1
                         .iter()
                         .zip(args2)
@@ -1066,6 +1489,91 @@ impl Term {
     pub fn pmatch<'a>(cs: Vec<(&'a Term, &'a Term)>) -> Option<HashMap<&'a Variable, &'a Term>> {
         Term::unify_internal(cs, Unification::Match)
     }
+    /// Lazily find every subterm of the `Term` matched by `pattern`, in preorder, along with
+    /// its [`Place`] and the [`Substitution`] under which `pattern` matches it.
+    ///
+    /// [`Place`]: type.Place.html
+    /// [`Substitution`]: struct.Substitution.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(G(A) H(G(B)))").expect("parse of F(G(A) H(G(B)))");
+    /// let pattern = parse_term(&mut sig, "G(x_)").expect("parse of G(x_)");
+    ///
+    /// let results: Vec<_> = t
+    ///     .query(&pattern)
+    ///     .map(|(place, sub)| (place, sub.display(&sig)))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     results,
+    ///     vec![
+    ///         (vec![0], "{x_ := A}".to_string()),
+    ///         (vec![1, 0], "{x_ := B}".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn query<'a>(&'a self, pattern: &'a Term) -> Query<'a> {
+        Query(Box::new(self.preorder(vec![]).filter_map(
+            move |(subterm, place)| {
+                Term::pmatch(vec![(pattern, subterm)]).map(|sub| (place, Substitution::from(sub)))
+            },
+        )))
+    }
+    /// Lazily iterate over the subterms of the `Term` in preorder, prefixing each [`Place`]
+    /// with `place`.
+    ///
+    /// [`Place`]: type.Place.html
+    fn preorder(&self, place: Place) -> Box<dyn Iterator<Item = (&Term, Place)> + '_> {
+        let mut stack = vec![(self, place)];
+        Box::new(iter::from_fn(move || {
+            let (term, place) = stack.pop()?;
+            if let Term::Application { ref args, .. } = *term {
+                stack.extend(args.iter().enumerate().rev().map(|(i, arg)| {
+                    let mut p = place.clone();
+                    p.push(i);
+                    (arg, p)
+                }));
+            }
+            Some((term, place))
+        }))
+    }
+    /// Visit the subterms of the `Term` in preorder, along with their [`Place`]s, until
+    /// `visit` returns `false`. Unlike [`Term::subterms`], no [`Place`] is built unless
+    /// `visit` builds it.
+    ///
+    /// [`Place`]: type.Place.html
+    /// [`Term::subterms`]: enum.Term.html#method.subterms
+    pub(crate) fn visit_preorder<F>(&self, mut visit: F)
+    where
+        F: FnMut(&Term, &[usize]) -> bool,
+    {
+        let mut place = vec![];
+        if !visit(self, &place) {
+            return;
+        }
+        // each entry is an application and the index of its next argument to visit.
+        let mut stack = vec![(self, 0)];
+        while let Some((term, i)) = stack.pop() {
+            match *term {
+                Term::Application { ref args, .. } if i < args.len() => {
+                    stack.push((term, i + 1));
+                    place.push(i);
+                    if !visit(&args[i], &place) {
+                        return;
+                    }
+                    stack.push((&args[i], 0));
+                }
+                _ => {
+                    place.pop();
+                }
+            }
+        }
+    }
     /// Given a vector of contraints, return a substitution which satisfies the constrants.
     /// If the constraints are not satisfiable, return `None`.
     ///
@@ -1115,50 +1623,154 @@ impl Term {
     pub fn unify<'a>(cs: Vec<(&'a Term, &'a Term)>) -> Option<HashMap<&'a Variable, &'a Term>> {
         Term::unify_internal(cs, Unification::Unify)
     }
+    /// The most general unifier of `s` and `t`, if they unify: an idempotent [`Substitution`]
+    /// `σ` such that `sσ == tσ`.
+    ///
+    /// Use a [`Unifier`] to add constraints incrementally or to disable the occurs check.
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    /// [`Unifier`]: struct.Unifier.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(x_ G(y_))").expect("parse of F(x_ G(y_))");
+    /// let u = parse_term(&mut sig, "F(H(z_) G(z_))").expect("parse of F(H(z_) G(z_))");
+    ///
+    /// let sub = Term::mgu(&t, &u).expect("unifier");
+    ///
+    /// assert_eq!(sub.display(&sig), "{x_ := H(z_), y_ := z_}");
+    /// assert_eq!(sub.apply(&t), sub.apply(&u));
+    ///
+    /// let v = parse_term(&mut sig, "F(x_ x_)").expect("parse of F(x_ x_)");
+    /// let w = parse_term(&mut sig, "F(y_ G(y_))").expect("parse of F(y_ G(y_))");
+    ///
+    /// assert_eq!(Term::mgu(&v, &w), None);
+    /// ```
+    pub fn mgu(s: &Term, t: &Term) -> Option<Substitution> {
+        let mut unifier = Unifier::new();
+        if unifier.unify(s, t) {
+            Some(unifier.into_substitution())
+        } else {
+            None
+        }
+    }
+    /// The least general generalization of `s` and `t`, together with [`Substitution`]s `σ`
+    /// and `τ` such that `gσ == s` and `gτ == t`, where `g` is the generalization. Each
+    /// distinct pair of disagreeing subterms is replaced by the same fresh [`Variable`]
+    /// wherever it occurs, and the fresh [`Variable`]s are added to `sig`.
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Term, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let s = parse_term(&mut sig, "F(A G(A) H(B))").expect("parse of F(A G(A) H(B))");
+    /// let t = parse_term(&mut sig, "F(C G(C) H(B))").expect("parse of F(C G(C) H(B))");
+    ///
+    /// let (g, sub_s, sub_t) = Term::antiunify(&mut sig, &s, &t);
+    ///
+    /// assert_eq!(g.display(&sig), "F(var0_ G(var0_) H(B))");
+    /// assert_eq!(sub_s.display(&sig), "{var0_ := A}");
+    /// assert_eq!(sub_t.display(&sig), "{var0_ := C}");
+    /// assert_eq!(sub_s.apply(&g), s);
+    /// assert_eq!(sub_t.apply(&g), t);
+    /// ```
+    pub fn antiunify(
+        sig: &mut Signature,
+        s: &Term,
+        t: &Term,
+    ) -> (Term, Substitution, Substitution) {
+        let mut pairs = vec![];
+        let g = Term::antiunify_helper(sig, s, t, &mut pairs);
+        let mut sub_s = Substitution::new();
+        let mut sub_t = Substitution::new();
+        for (s, t, v) in pairs {
+            sub_s.insert(v, s);
+            sub_t.insert(v, t);
+        }
+        (g, sub_s, sub_t)
+    }
+    /// Generalize `s` and `t`, reusing the [`Variable`] in `pairs` for repeated disagreements.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub(crate) fn antiunify_helper(
+        sig: &mut Signature,
+        s: &Term,
+        t: &Term,
+        pairs: &mut Vec<(Term, Term, Variable)>,
+    ) -> Term {
+        match (s, t) {
+            _ if s == t => s.clone(),
+            (Term::Application { op: f, args: ss }, Term::Application { op: g, args: ts })
+                if f == g =>
+            {
+                Term::Application {
+                    op: *f,
+                    args: ss
+                        .iter()
+                        .zip(ts)
+                        .map(|(s, t)| Term::antiunify_helper(sig, s, t, pairs))
+                        .collect(),
+                }
+            }
+            _ => {
+                if let Some((_, _, v)) = pairs.iter().find(|(ps, pt, _)| ps == s && pt == t) {
+                    return Term::Variable(*v);
+                }
+                let v = sig.new_var(None);
+                pairs.push((s.clone(), t.clone(), v));
+                Term::Variable(v)
+            }
+        }
+    }
     /// the internal implementation of unify and match.
     fn unify_internal<'a>(
         mut cs: Vec<(&'a Term, &'a Term)>,
         utype: Unification,
     ) -> Option<HashMap<&'a Variable, &'a Term>> {
         let mut subs: HashMap<&Variable, &Term> = HashMap::new();
-        while !cs.is_empty() {
-            let (mut s, mut t) = cs.pop().unwrap();
-
-            while let Term::Variable(ref v) = *s {
-                if subs.contains_key(v) {
-                    s = &subs[v];
-                } else {
-                    break;
-                }
-            }
-
-            while let Term::Variable(ref v) = *t {
-                if subs.contains_key(v) {
-                    t = &subs[v];
-                } else {
-                    break;
+        while let Some((mut s, mut t)) = cs.pop() {
+            if utype == Unification::Match {
+                // only variables in the pattern may be bound, and each only once.
+                if let Term::Variable(ref var) = *s {
+                    match subs.get(var) {
+                        Some(&bound) if bound != t => return None,
+                        Some(_) => (),
+                        None => {
+                            subs.insert(var, t);
+                        }
+                    }
+                    continue;
                 }
+            } else {
+                s = Term::walk(s, &subs);
+                t = Term::walk(t, &subs);
             }
 
-            // if they are equal, you're all done with them.
-            if s != t {
+            // if they are equal, you're all done with them. When matching, `s` may mention
+            // variables bound elsewhere, so equal terms must still be decomposed.
+            if s != t || utype == Unification::Match {
                 match (s, t) {
-                    (Term::Variable(ref var), Term::Variable(_)) => {
-                        subs.insert(var, t);
-                    }
                     (Term::Variable(ref var), t) => {
-                        if !(*t).variables().contains(&&var) {
-                            subs.insert(var, t);
-                        } else {
+                        if Term::occurs(var, t, &subs) {
                             return None;
                         }
+                        subs.insert(var, t);
                     }
                     (s, Term::Variable(ref var)) if utype != Unification::Match => {
-                        if !(*s).variables().contains(&&var) {
-                            subs.insert(var, s);
-                        } else {
+                        if Term::occurs(var, s, &subs) {
                             return None;
                         }
+                        subs.insert(var, s);
                     }
                     (
                         Term::Application {
@@ -1180,13 +1792,195 @@ impl Term {
         }
         Some(subs)
     }
+    /// Follow the bindings of a [`Variable`] until reaching an unbound `Term`.
+    fn walk<'a>(mut t: &'a Term, subs: &HashMap<&'a Variable, &'a Term>) -> &'a Term {
+        while let Term::Variable(ref v) = *t {
+            match subs.get(v) {
+                Some(bound) => t = bound,
+                None => break,
+            }
+        }
+        t
+    }
+    /// Does `var` occur in `t`, looking through the bindings in `subs`?
+    fn occurs(var: &Variable, t: &Term, subs: &HashMap<&Variable, &Term>) -> bool {
+        match *t {
+            Term::Variable(ref v) if v == var => true,
+            Term::Variable(ref v) => match subs.get(v) {
+                Some(t) => Term::occurs(var, t, subs),
+                None => false,
+            },
+            Term::Application { ref args, .. } => args.iter().any(|a| Term::occurs(var, a, subs)),
+        }
+    }
+    /// Apply a substitution produced by [`Term::unify`], chasing the bindings
+    /// of [`Variable`]s until the result mentions no bound [`Variable`].
+    ///
+    /// [`Term::unify`]: enum.Term.html#method.unify
+    /// [`Variable`]: struct.Variable.html
+    pub(crate) fn substitute_fully(&self, sub: &HashMap<&Variable, &Term>) -> Term {
+        match *self {
+            Term::Variable(ref v) => match sub.get(v) {
+                Some(t) => t.substitute_fully(sub),
+                None => self.clone(),
+            },
+            Term::Application { ref op, ref args } => Term::Application {
+                op: *op,
+                args: args.iter().map(|t| t.substitute_fully(sub)).collect(),
+            },
+        }
+    }
+}
+impl Term {
+    // Combine the `Term` bottom-up, applying `variable` to each Variable and `application` to
+    // each Operator and its combined arguments, using explicit stacks rather than recursion.
+    fn fold<T, V, A>(&self, mut variable: V, mut application: A) -> T
+    where
+        V: FnMut(&Variable) -> T,
+        A: FnMut(&Operator, Vec<T>) -> T,
+    {
+        enum Visit<'a> {
+            Enter(&'a Term),
+            Exit(&'a Operator, usize),
+        }
+        let mut todo = vec![Visit::Enter(self)];
+        let mut done = vec![];
+        while let Some(visit) = todo.pop() {
+            match visit {
+                Visit::Enter(Term::Variable(ref v)) => done.push(variable(v)),
+                Visit::Enter(Term::Application { ref op, ref args }) => {
+                    todo.push(Visit::Exit(op, args.len()));
+                    todo.extend(args.iter().rev().map(Visit::Enter));
+                }
+                Visit::Exit(op, n) => {
+                    let args = done.split_off(done.len() - n);
+                    done.push(application(op, args));
+                }
+            }
+        }
+        done.pop().expect("folded term")
+    }
+}
+impl Clone for Term {
+    fn clone(&self) -> Term {
+        match *self {
+            Term::Variable(ref v) => Term::Variable(*v),
+            Term::Application { .. } => self.fold(
+                |v| Term::Variable(*v),
+                |op, args| Term::Application { op: *op, args },
+            ),
+        }
+    }
+}
+impl PartialEq for Term {
+    fn eq(&self, other: &Term) -> bool {
+        let mut pairs = vec![(self, other)];
+        while let Some(pair) = pairs.pop() {
+            match pair {
+                (Term::Variable(ref v), Term::Variable(ref w)) if v == w => (),
+                (
+                    Term::Application {
+                        op: ref o1,
+                        args: ref a1,
+                    },
+                    Term::Application {
+                        op: ref o2,
+                        args: ref a2,
+                    },
+                ) if o1 == o2 && a1.len() == a2.len() => pairs.extend(a1.iter().zip(a2)),
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+impl Eq for Term {}
+impl Hash for Term {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut stack = vec![self];
+        while let Some(term) = stack.pop() {
+            match *term {
+                Term::Variable(ref v) => {
+                    0u8.hash(state);
+                    v.hash(state);
+                }
+                Term::Application { ref op, ref args } => {
+                    1u8.hash(state);
+                    op.hash(state);
+                    args.len().hash(state);
+                    stack.extend(args.iter().rev());
+                }
+            }
+        }
+    }
+}
+// Order first by shape, comparing the number of arguments of each subterm in preorder
+// (Variables having none, and coming before Operators with none), then by the Variables and
+// Operators in preorder.
+impl PartialOrd for Term {
+    fn partial_cmp(&self, other: &Term) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Term {
+    fn cmp(&self, other: &Term) -> Ordering {
+        let shape = |term: &Term| match *term {
+            Term::Variable(_) => None,
+            Term::Application { ref args, .. } => Some(args.len()),
+        };
+        let mut pairs = vec![(self, other)];
+        while let Some((t1, t2)) = pairs.pop() {
+            match shape(t1).cmp(&shape(t2)) {
+                Ordering::Equal => (),
+                unequal => return unequal,
+            }
+            if let (Term::Application { args: a1, .. }, Term::Application { args: a2, .. }) =
+                (t1, t2)
+            {
+                pairs.extend(a1.iter().zip(a2).rev());
+            }
+        }
+        let mut pairs = vec![(self, other)];
+        while let Some(pair) = pairs.pop() {
+            let order = match pair {
+                (Term::Variable(v), Term::Variable(w)) => v.cmp(w),
+                (
+                    Term::Application { op: o1, args: a1 },
+                    Term::Application { op: o2, args: a2 },
+                ) => {
+                    pairs.extend(a1.iter().zip(a2).rev());
+                    o1.cmp(o2)
+                }
+                _ => unreachable!("terms of the same shape"),
+            };
+            if order != Ordering::Equal {
+                return order;
+            }
+        }
+        Ordering::Equal
+    }
+}
+/// A lazy iterator over the subterms of a `Term` matching a pattern.
+///
+/// See [`Term::query`] for more information.
+///
+/// [`Term::query`]: enum.Term.html#method.query
+pub struct Query<'a>(Box<dyn Iterator<Item = (Place, Substitution)> + 'a>);
+impl<'a> Iterator for Query<'a> {
+    type Item = (Place, Substitution);
+    fn next(&mut self) -> Option<(Place, Substitution)> {
+        self.0.next()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::super::super::parser::*;
-    use super::super::{Atom, Context, Signature, Term};
-    use std::collections::HashMap;
+    use super::super::{
+        Atom, Context, Normalization, NormalizeConfig, OpMap, Signature, Strategy, Term, Unifier,
+        VarMap,
+    };
+    use std::collections::{HashMap, HashSet};
 
     #[test]
     fn context_display_test() {
@@ -1196,7 +1990,7 @@ mod tests {
             "x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
             .expect("parse of x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)") ;
 
-        assert_eq!(context.display(),
+        assert_eq!(context.display(&sig),
             ".(.(.(.(x_ [!]) A) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
     }
 
@@ -1207,7 +2001,10 @@ mod tests {
         let context = parse_context(&mut sig, "x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
             .expect("parse of x_ [!] A CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)") ;
 
-        assert_eq!(context.pretty(), "x_ [!] A [2, 1, 0] 105");
+        assert_eq!(
+            context.pretty(&sig),
+            "x_ [!] A [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105"
+        );
     }
 
     #[test]
@@ -1216,7 +2013,7 @@ mod tests {
 
         let context = parse_context(&mut sig, "A(B x_ [!])").expect("parse of A(B x_ [!])");
 
-        let atoms: Vec<String> = context.atoms().iter().map(|a| a.display()).collect();
+        let atoms: Vec<String> = context.atoms().iter().map(|a| a.display(&sig)).collect();
 
         assert_eq!(atoms, vec!["x_", "B", "A"]);
     }
@@ -1227,7 +2024,11 @@ mod tests {
 
         let context = parse_context(&mut sig, "A([!]) B y_ z_").expect("parse of A([!]) B y_ z_");
 
-        let var_names: Vec<String> = context.variables().iter().map(|v| v.display()).collect();
+        let var_names: Vec<String> = context
+            .variables()
+            .iter()
+            .map(|v| v.display(&sig))
+            .collect();
 
         assert_eq!(var_names, vec!["y_".to_string(), "z_".to_string()]);
     }
@@ -1238,7 +2039,11 @@ mod tests {
 
         let context = parse_context(&mut sig, "A([!]) B y_ z_").expect("parse of A([!]) B y_ z_");
 
-        let op_names: Vec<String> = context.operators().iter().map(|v| v.display()).collect();
+        let op_names: Vec<String> = context
+            .operators()
+            .iter()
+            .map(|v| v.display(&sig))
+            .collect();
 
         assert_eq!(
             op_names,
@@ -1265,13 +2070,13 @@ mod tests {
 
         let mut context = parse_context(&mut sig, "A(B([!]) z_)").expect("parse of A(B([!]) z_)");
 
-        assert_eq!(context.head().unwrap().display(), "A");
+        assert_eq!(context.head().unwrap().display(&sig), "A");
 
         sig = Signature::default();
 
         context = parse_context(&mut sig, "z_").expect("parse of z_");
 
-        assert_eq!(context.head().unwrap().display(), "z_");
+        assert_eq!(context.head().unwrap().display(&sig), "z_");
     }
 
     #[test]
@@ -1279,17 +2084,17 @@ mod tests {
         let mut sig = Signature::default();
 
         let mut context = parse_context(&mut sig, "A B").expect("parse of A B");
-        let mut args: Vec<String> = context.args().iter().map(|arg| arg.display()).collect();
+        let mut args: Vec<String> = context.args().iter().map(|arg| arg.display(&sig)).collect();
 
         assert_eq!(args, vec!["A", "B"]);
 
         context = parse_context(&mut sig, "A(y_)").expect("parse of A(y_)");
-        args = context.args().iter().map(|arg| arg.display()).collect();
+        args = context.args().iter().map(|arg| arg.display(&sig)).collect();
 
         assert_eq!(args, vec!["y_"]);
 
         context = parse_context(&mut sig, "y_").expect("parse of y_");
-        args = context.args().iter().map(|arg| arg.display()).collect();
+        args = context.args().iter().map(|arg| arg.display(&sig)).collect();
 
         let vec: Vec<String> = Vec::new();
 
@@ -1340,7 +2145,7 @@ mod tests {
 
         let p: &[usize] = &[0];
 
-        assert_eq!(context.at(p).unwrap().display(), "A");
+        assert_eq!(context.at(p).unwrap().display(&sig), "A");
     }
 
     #[test]
@@ -1353,7 +2158,7 @@ mod tests {
         let p: &[usize] = &[0];
         let new_context = context.replace(p, context2);
 
-        assert_eq!(new_context.unwrap().pretty(), "B(C [!])");
+        assert_eq!(new_context.unwrap().pretty(&sig), "B(C [!])");
     }
 
     #[test]
@@ -1368,7 +2173,28 @@ mod tests {
 
         let term = context.to_term().expect("converting context to term");
 
-        assert_eq!(term.display(), "A(B C)");
+        assert_eq!(term.display(&sig), "A(B C)");
+    }
+
+    #[test]
+    fn fill_test() {
+        let mut sig = Signature::default();
+
+        let term = parse_term(&mut sig, "A(B(x_) C)").expect("parse of A(B(x_) C)");
+
+        for (_, place) in term.subterms() {
+            let context = Context::from_term_with_hole(&term, &place).expect("context");
+            assert_eq!(context.holes(), vec![place.clone()]);
+            let subterm = term.at(&place).expect("subterm").clone();
+            assert_eq!(context.fill(&[subterm]), Some(term.clone()));
+        }
+
+        assert_eq!(Context::from_term_with_hole(&term, &[2]), None);
+
+        let context = parse_context(&mut sig, "[!]").expect("parse of [!]");
+
+        assert_eq!(context.fill(&[term.clone(), term.clone()]), None);
+        assert_eq!(context.fill(::std::slice::from_ref(&term)), Some(term));
     }
 
     #[test]
@@ -1378,7 +2204,7 @@ mod tests {
         let term = parse_term(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
             .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)");
 
-        assert_eq!(term.display(), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
+        assert_eq!(term.display(&sig), ".(.(.(A B(x_)) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL)))) DECC(DECC(DIGIT(1) 0) 5))");
     }
 
     #[test]
@@ -1388,7 +2214,10 @@ mod tests {
         let term = parse_term(&mut sig, "A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)")
              .expect("parse of A B(x_) CONS(SUCC(SUCC(ZERO)) CONS(SUCC(ZERO) CONS(ZERO NIL))) DECC(DECC(DIGIT(1) 0) 5)");
 
-        assert_eq!(term.pretty(), "A B(x_) [2, 1, 0] 105");
+        assert_eq!(
+            term.pretty(&sig),
+            "A B(x_) [SUCC(SUCC(ZERO)), SUCC(ZERO), ZERO] 105"
+        );
     }
 
     #[test]
@@ -1396,7 +2225,11 @@ mod tests {
         let mut sig = Signature::default();
 
         let example_term = parse_term(&mut sig, "A(B x_)").expect("parse of A(B x_)");
-        let atoms: Vec<String> = example_term.atoms().iter().map(|a| a.display()).collect();
+        let atoms: Vec<String> = example_term
+            .atoms()
+            .iter()
+            .map(|a| a.display(&sig))
+            .collect();
 
         assert_eq!(atoms, vec!["x_", "B", "A"]);
     }
@@ -1406,7 +2239,7 @@ mod tests {
         let mut sig = Signature::default();
 
         let t = parse_term(&mut sig, "A B y_ z_").expect("parse of A B y_ z_");
-        let var_names: Vec<String> = t.variables().iter().map(|v| v.display()).collect();
+        let var_names: Vec<String> = t.variables().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(var_names, vec!["y_", "z_"]);
     }
@@ -1416,7 +2249,7 @@ mod tests {
         let mut sig = Signature::default();
 
         let t = parse_term(&mut sig, "A B y_ z_").expect("parse of A B y_ z_");
-        let op_names: Vec<String> = t.operators().iter().map(|v| v.display()).collect();
+        let op_names: Vec<String> = t.operators().iter().map(|v| v.display(&sig)).collect();
 
         assert_eq!(op_names, vec!["A", "B", "."]);
     }
@@ -1458,15 +2291,15 @@ mod tests {
         let p1: Vec<usize> = vec![0];
         let t = parse_term(&mut sig, "A(B)").expect("parse of A(B)");
         let subterm0 = Term::Application {
-            op: a.clone(),
+            op: a,
             args: vec![Term::Application {
-                op: b.clone(),
+                op: b,
                 args: vec![],
             }],
         };
 
         let subterm1 = Term::Application {
-            op: b.clone(),
+            op: b,
             args: vec![],
         };
 
@@ -1501,6 +2334,25 @@ mod tests {
         let args = vec![];
 
         assert_eq!(t.at(p), Some(&Term::Application { op, args }));
+
+        let p: &[usize] = &[1];
+
+        assert_eq!(t.at(p), None);
+    }
+
+    #[test]
+    fn term_positions_test() {
+        let mut sig = Signature::default();
+        let t = parse_term(&mut sig, "F(A G(B x_))").expect("parse of F(A G(B x_))");
+
+        for p in t.positions() {
+            assert!(t.at(&p).is_some());
+            if let Some(parent) = p.parent() {
+                assert!(parent.is_prefix_of(&p));
+                assert_eq!(parent.child(p[p.len() - 1]), p);
+            }
+        }
+        assert_eq!(t.positions().len(), t.size());
     }
 
     #[test]
@@ -1517,6 +2369,24 @@ mod tests {
         assert_eq!(new_term, Some(expected_term));
     }
 
+    #[test]
+    fn term_replace_in_place_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "F(A G(B x_))").expect("parse of F(A G(B x_))");
+        let c = parse_term(&mut sig, "C").expect("parse of C");
+
+        for p in t.positions() {
+            let mut t2 = t.clone();
+            let old = t2.replace_in_place(&p, c.clone());
+            assert_eq!(old.as_ref(), t.at(&p));
+            assert_eq!(Some(t2), t.replace(&p, c.clone()));
+        }
+        let mut t2 = t.clone();
+        assert_eq!(t2.replace_in_place(&[1, 1, 0], c), None);
+        assert_eq!(t2, t);
+    }
+
     #[test]
     fn term_substitute_test() {
         let mut sig = Signature::default();
@@ -1549,17 +2419,12 @@ mod tests {
         let t3 = parse_term(&mut sig, "S K y_").expect("parse of S K y_");
 
         let vars = sig.variables();
-        let (y, z, a, b) = (
-            vars[0].clone(),
-            vars[1].clone(),
-            vars[2].clone(),
-            vars[3].clone(),
-        );
+        let (y, z, a, b) = (vars[0], vars[1], vars[2], vars[3]);
 
-        assert_eq!(y.display(), "y_".to_string());
-        assert_eq!(z.display(), "z_".to_string());
-        assert_eq!(a.display(), "a_".to_string());
-        assert_eq!(b.display(), "b_".to_string());
+        assert_eq!(y.display(&sig), "y_".to_string());
+        assert_eq!(z.display(&sig), "z_".to_string());
+        assert_eq!(a.display(&sig), "a_".to_string());
+        assert_eq!(b.display(&sig), "b_".to_string());
 
         {
             let ta = Term::Variable(a);
@@ -1604,7 +2469,7 @@ mod tests {
         // maps variable x in term t2 to variable y in term t3
         {
             let subbee = &t2.variables()[0];
-            let subbed = Term::Variable(t3.variables()[0].clone());
+            let subbed = Term::Variable(t3.variables()[0]);
             let mut expected_sub = HashMap::new();
             expected_sub.insert(subbee, &subbed);
 
@@ -1614,6 +2479,24 @@ mod tests {
         assert_eq!(Term::pmatch(vec![(&t3, &t4)]), None);
     }
 
+    #[test]
+    fn query_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "F(x_ G(F(A A)) F(A B))")
+            .expect("parse of F(x_ G(F(A A)) F(A B))");
+        let pattern = parse_term(&mut sig, "F(y_ y_)").expect("parse of F(y_ y_)");
+
+        let places: Vec<_> = t.query(&pattern).map(|(place, _)| place).collect();
+        assert_eq!(places, vec![vec![1, 0]]);
+
+        let pattern = parse_term(&mut sig, "z_").expect("parse of z_");
+        assert_eq!(t.query(&pattern).count(), t.size());
+        for (place, sub) in t.query(&pattern) {
+            assert_eq!(sub.apply(&pattern), *t.at(&place).expect("subterm"));
+        }
+    }
+
     #[test]
     fn unify_test() {
         let mut sig = Signature::default();
@@ -1630,7 +2513,7 @@ mod tests {
             // maps variable x in term t2 to constant A in term t
             let subbee = &t2.variables()[0];
             let subbed = Term::Application {
-                op: t.operators()[0].clone(),
+                op: t.operators()[0],
                 args: vec![],
             };
             let mut expected_sub = HashMap::new();
@@ -1642,7 +2525,7 @@ mod tests {
         {
             // maps variable x in term t2 to variable y in term t3
             let subbee = &t2.variables()[0];
-            let subbed = Term::Variable(t3.variables()[0].clone());
+            let subbed = Term::Variable(t3.variables()[0]);
             let mut expected_sub = HashMap::new();
             expected_sub.insert(subbee, &subbed);
 
@@ -1651,4 +2534,217 @@ mod tests {
 
         assert_eq!(Term::unify(vec![(&t3, &t4)]), None);
     }
+
+    #[test]
+    fn pmatch_binding_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "F(x_ x_)").expect("parse of F(x_ x_)");
+
+        let t2 = parse_term(&mut sig, "F(y_ A)").expect("parse of F(y_ A)");
+
+        let t3 = parse_term(&mut sig, "F(x_ y_)").expect("parse of F(x_ y_)");
+
+        let t4 = parse_term(&mut sig, "F(G(A) G(A))").expect("parse of F(G(A) G(A))");
+
+        // a variable bound in one place must match the same term everywhere
+        assert_eq!(Term::pmatch(vec![(&t, &t2)]), None);
+
+        // variables in the subject are never bound
+        assert_eq!(Term::pmatch(vec![(&t, &t3)]), None);
+
+        // maps variable x in term t to G(A) in term t4
+        {
+            let subbee = &t.variables()[0];
+            let args = t4.args();
+            let mut expected_sub = HashMap::new();
+            expected_sub.insert(subbee, &args[0]);
+
+            assert_eq!(Term::pmatch(vec![(&t, &t4)]), Some(expected_sub));
+        }
+
+        // equal subterms still bind the variables they mention
+        let t5 = parse_term(&mut sig, "F(G(x_) x_)").expect("parse of F(G(x_) x_)");
+        let args = t5.args();
+
+        assert_eq!(
+            Term::pmatch(vec![(&args[0], &args[0]), (&args[1], &t2)]),
+            None
+        );
+    }
+
+    #[test]
+    fn unify_binding_test() {
+        let mut sig = Signature::default();
+
+        // the occurs check looks through earlier bindings
+        let t = parse_term(&mut sig, "P(F(x_ y_) F(G(y_) G(x_)))")
+            .expect("parse of P(F(x_ y_) F(G(y_) G(x_)))");
+        let args = t.args();
+
+        assert_eq!(Term::unify(vec![(&args[0], &args[1])]), None);
+
+        let t2 =
+            parse_term(&mut sig, "Q(x_ G(y_) y_ G(x_))").expect("parse of Q(x_ G(y_) y_ G(x_))");
+        let args = t2.args();
+
+        assert_eq!(
+            Term::unify(vec![(&args[0], &args[1]), (&args[2], &args[3])]),
+            None
+        );
+
+        // bindings may mention bound variables, so the unifier is applied in full
+        let t4 = parse_term(&mut sig, "P(F(x_ G(y_) y_) F(G(z_) x_ G(A)))")
+            .expect("parse of P(F(x_ G(y_) y_) F(G(z_) x_ G(A)))");
+        let args = t4.args();
+        let sub = Term::unify(vec![(&args[0], &args[1])]).expect("unification");
+
+        assert_eq!(
+            args[0].substitute_fully(&sub),
+            args[1].substitute_fully(&sub)
+        );
+        assert!(args[0].substitute_fully(&sub).variables().is_empty());
+    }
+
+    #[test]
+    fn mgu_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "P(F(x_ G(y_) z_) F(H(z_) w_ A))")
+            .expect("parse of P(F(x_ G(y_) z_) F(H(z_) w_ A))");
+        let args = t.args();
+        let sub = Term::mgu(&args[0], &args[1]).expect("unifier");
+
+        assert_eq!(sub.apply(&args[0]), sub.apply(&args[1]));
+        assert_eq!(sub.apply(&args[0]).display(&sig), "F(H(A) G(y_) A)");
+        assert_eq!(sub.compose(&sub), sub);
+
+        // the occurs check looks through earlier bindings.
+        let t = parse_term(&mut sig, "P(F(x_ y_) F(G(y_) G(x_)))")
+            .expect("parse of P(F(x_ y_) F(G(y_) G(x_)))");
+        let args = t.args();
+
+        assert_eq!(Term::mgu(&args[0], &args[1]), None);
+
+        let mut unifier = Unifier::new().occurs_check(false);
+
+        assert!(unifier.unify_all(vec![(&args[0], &args[1])]));
+    }
+
+    #[test]
+    fn antiunify_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "P(F(x_ G(A) A B) F(x_ G(y_) y_ C))")
+            .expect("parse of P(F(x_ G(A) A B) F(x_ G(y_) y_ C))");
+        let args = t.args();
+        let (g, sub_s, sub_t) = Term::antiunify(&mut sig, &args[0], &args[1]);
+
+        assert_eq!(g.display(&sig), "F(x_ G(var2_) var2_ var3_)");
+        assert_eq!(sub_s.apply(&g), args[0]);
+        assert_eq!(sub_t.apply(&g), args[1]);
+        assert_eq!(sub_t.display(&sig), "{var2_ := y_, var3_ := C}");
+
+        let (g, sub_s, sub_t) = Term::antiunify(&mut sig, &args[0], &args[0]);
+
+        assert_eq!(g, args[0]);
+        assert!(sub_s.is_empty() && sub_t.is_empty());
+    }
+
+    #[test]
+    fn map_signature_test() {
+        let mut sig = Signature::default();
+        let t = parse_term(&mut sig, "F(x_ G(y_ x_))").expect("parse of F(x_ G(y_ x_))");
+        let mut sig2 = Signature::default();
+        let ops: OpMap = sig
+            .operators()
+            .into_iter()
+            .map(|op| (op, sig2.new_op(op.arity(), op.name(&sig))))
+            .collect();
+        let vars: VarMap = sig
+            .variables()
+            .into_iter()
+            .map(|v| (v, sig2.new_var(v.name(&sig))))
+            .collect();
+
+        let t2 = t.map_signature(&ops, &vars).expect("mapped term");
+
+        assert_eq!(t2.display(&sig2), "F(x_ G(y_ x_))");
+        assert_eq!(t2.variables(), sig2.variables());
+
+        // and back again
+        let back_ops = ops.iter().map(|(&k, &v)| (v, k)).collect();
+        let back_vars = vars.iter().map(|(&k, &v)| (v, k)).collect();
+
+        assert_eq!(t2.map_signature(&back_ops, &back_vars), Some(t));
+    }
+
+    #[test]
+    fn deep_term_test() {
+        let mut sig = Signature::default();
+        let cons = sig.new_op(2, Some("CONS".to_string()));
+        let nil = sig.new_op(0, Some("NIL".to_string()));
+        let a = parse_term(&mut sig, "A").expect("parse of A");
+        let x = sig.new_var(Some("x".to_string()));
+
+        let depth = 100_000;
+        let mut t = Term::Variable(x);
+        for _ in 0..depth {
+            t = Term::Application {
+                op: cons,
+                args: vec![a.clone(), t],
+            };
+        }
+
+        let copy = t.clone();
+        assert_eq!(copy, t);
+        assert_eq!(t.size(), 2 * depth + 1);
+
+        let mut terms = HashSet::new();
+        terms.insert(t.clone());
+        assert!(terms.contains(&copy));
+
+        let display = t.display(&sig);
+        assert!(display.starts_with("CONS(A CONS(A "));
+        assert!(display.ends_with(&format!(" x_{}", ")".repeat(depth))));
+        assert_eq!(display.len(), depth * "CONS(A )".len() + "x_".len());
+
+        let nil = Term::Application {
+            op: nil,
+            args: vec![],
+        };
+        let mut sub = HashMap::new();
+        sub.insert(&x, &nil);
+        let list = t.substitute(&sub);
+        assert_ne!(list, t);
+        assert!(list
+            .display(&sig)
+            .ends_with(&format!(" NIL{}", ")".repeat(depth))));
+
+        let trs = parse_trs(&mut sig, "CONS(A NIL) = B;").expect("parse of CONS(A NIL) = B;");
+        let reduced = format!(" B{}", ")".repeat(depth - 1));
+        for &strategy in &[
+            Strategy::Normal,
+            Strategy::Eager,
+            Strategy::ParallelInnermost,
+        ] {
+            let rewritten = trs.rewrite(&list, strategy).expect("rewrite");
+            assert_eq!(rewritten.len(), 1);
+            assert!(rewritten[0].display(&sig).ends_with(&reduced));
+            rewritten.into_iter().for_each(Term::drop_deep);
+        }
+        match trs.normalize(&list, Strategy::Normal, NormalizeConfig::default()) {
+            Ok(Normalization::Normalized(nf)) => {
+                assert_eq!(nf.size(), 2 * depth - 1);
+                assert!(nf.display(&sig).ends_with(&reduced));
+                nf.drop_deep();
+            }
+            _ => panic!("expected a normal form"),
+        }
+
+        t.drop_deep();
+        copy.drop_deep();
+        terms.into_iter().for_each(Term::drop_deep);
+        list.drop_deep();
+    }
 }
@@ -1,8 +1,22 @@
-use super::{Operator, Rule, Term};
+use super::super::pretty::PrettyConfig;
+use super::index::TrsIndex;
+use super::{
+    Context, DeBruijnTerm, OpMap, Operator, Place, Position, Rule, Signature, Substitution, Term,
+    VarMap, Variable,
+};
 use itertools::Itertools;
 use rand::seq::sample_iter;
-use rand::Rng;
+use rand::{Rng, RngCore};
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
 
 /// A first-order term rewriting system.
 ///
@@ -32,7 +46,25 @@ use std::fmt;
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
 pub struct TRS {
     pub(crate) is_deterministic: bool,
-    pub rules: Vec<Rule>,
+    pub(crate) condition_config: ConditionConfig,
+    // changing the rules means updating the index: see `TRS::rules_mut`
+    pub(crate) rules: Vec<Rule>,
+    pub(crate) index: TrsIndex,
+    pub(crate) semi_equational: SemiEquational,
+}
+// Order by the Rules, in order, then by the settings.
+impl PartialOrd for TRS {
+    fn partial_cmp(&self, other: &TRS) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for TRS {
+    fn cmp(&self, other: &TRS) -> cmp::Ordering {
+        self.rules
+            .cmp(&other.rules)
+            .then_with(|| self.is_deterministic.cmp(&other.is_deterministic))
+            .then_with(|| self.condition_config.cmp(&other.condition_config))
+    }
 }
 impl TRS {
     /// Constructs a [`Term Rewriting System`] from a list of [`Rule`]s.
@@ -51,7 +83,7 @@ impl TRS {
     /// let r2 = parse_rule(&mut sig, "D(y_) = D(E)").expect("parse of D(y_) = D(E)");
     /// let new_trs = TRS::new(vec![r0, r1, r2]);
     ///
-    /// assert_eq!(new_trs.display(),
+    /// assert_eq!(new_trs.display(&sig),
     /// "A = B;
     /// C(x_) = x_;
     /// D(y_) = D(E);"
@@ -59,15 +91,66 @@ impl TRS {
     /// ```
     pub fn new(rules: Vec<Rule>) -> TRS {
         TRS {
+            index: TrsIndex::new(&rules),
             rules,
             is_deterministic: false,
+            condition_config: ConditionConfig::default(),
+            semi_equational: SemiEquational::default(),
         }
     }
-    /// Make the `TRS` [`deterministic`] and restrict it to be so until further notice.
+    /// How the conditions of conditional [`Rule`]s are evaluated.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub fn condition_config(&self) -> ConditionConfig {
+        self.condition_config
+    }
+    /// Set how the conditions of conditional [`Rule`]s are evaluated.
+    ///
+    /// A conditional [`Rule`] applies to a `Term` when its LHS matches with some substitution
+    /// `σ` and, for each condition `c = d`, `cσ` and `dσ` are related as `config.mode`
+    /// requires, within `config.max_steps` rewrite steps. Evaluating a condition may itself
+    /// use conditional [`Rule`]s, nested at most `config.max_depth` deep; a condition which
+    /// cannot be shown to hold within these bounds is taken not to hold.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Strategy, TRS, ConditionConfig, ConditionMode, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "EVEN(ZERO) = T;
+    /// EVEN(S(S(x_))) = EVEN(x_);
+    /// HALF(x_) = YES <= EVEN(x_) = TRUE;
+    /// TRUE = T;").expect("parse of EVEN and HALF rules");
+    ///
+    /// let two = parse_term(&mut sig, "HALF(S(S(ZERO)))").expect("parse of HALF(S(S(ZERO)))");
+    /// let one = parse_term(&mut sig, "HALF(S(ZERO))").expect("parse of HALF(S(ZERO))");
+    ///
+    /// // EVEN(S(S(ZERO))) rewrites to T, never to TRUE.
+    /// assert_eq!(t.rewrite(&two, Strategy::Normal), None);
+    ///
+    /// // but EVEN(S(S(ZERO))) and TRUE are joinable.
+    /// t.set_condition_config(ConditionConfig { mode: ConditionMode::Join, ..ConditionConfig::default() });
+    ///
+    /// assert_eq!(t.rewrite(&two, Strategy::Normal).expect("rewrite")[0].display(&sig), "YES");
+    /// assert_eq!(t.rewrite(&one, Strategy::Normal), None);
+    /// ```
+    pub fn set_condition_config(&mut self, config: ConditionConfig) {
+        self.condition_config = config;
+        self.semi_equational = SemiEquational::default();
+    }
+    /// Make the `TRS` [`deterministic`] and restrict it to be so until further notice, keeping
+    /// one clause of each [`Rule`] chosen at random. See [`TRS::make_deterministic_with`] to
+    /// choose the clauses and learn which were dropped.
     ///
     /// Return `true` if the `TRS` was changed, otherwise `false`.
     ///
     /// [`deterministic`]: http://en.wikipedia.org/wiki/Deterministic_system
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::make_deterministic_with`]: struct.TRS.html#method.make_deterministic_with
     ///
     /// # Examples
     ///
@@ -84,25 +167,25 @@ impl TRS {
     /// D = E;").expect("parse of A = B | C; D = E");
     /// let mut r = rand::thread_rng();
     ///
-    /// let str_before = t.display();
+    /// let str_before = t.display(&sig);
     ///
     /// assert!(t.make_deterministic(& mut r));
     ///
-    /// assert_ne!(t.display(), str_before);
+    /// assert_ne!(t.display(&sig), str_before);
     ///
-    /// let str_before = t.display();
+    /// let str_before = t.display(&sig);
     /// let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
     ///
     /// if t.insert_idx(1, r.clone()).is_err() {
     ///     assert!(true);
     /// }
     ///
-    /// assert_eq!(str_before, t.display());
+    /// assert_eq!(str_before, t.display(&sig));
     ///
-    /// assert!((t.display() ==
+    /// assert!((t.display(&sig) ==
     /// "A = B;
     /// D = E;") ||
-    ///     (t.display() ==
+    ///     (t.display(&sig) ==
     /// "A = C;
     /// D = E;"));
     /// # }
@@ -124,6 +207,77 @@ impl TRS {
             false
         }
     }
+    /// Make the `TRS` [`deterministic`] and restrict it to be so until further notice, keeping
+    /// the clause of each [`Rule`] chosen by `policy`.
+    ///
+    /// Return the dropped clauses, as one [`Rule`] for each [`Rule`] which lost any, in order.
+    /// A `TRS` already deterministic is left unchanged and drops nothing. Fails with
+    /// [`TRSError::InvalidIndex`], changing nothing, if [`ClausePolicy::Choose`] picks a clause
+    /// which does not exist.
+    ///
+    /// [`deterministic`]: http://en.wikipedia.org/wiki/Deterministic_system
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRSError::InvalidIndex`]: enum.TRSError.html#variant.InvalidIndex
+    /// [`ClausePolicy::Choose`]: enum.ClausePolicy.html#variant.Choose
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, ClausePolicy, parse_trs};
+    /// let mut sig = Signature::default();
+    /// let original = parse_trs(&mut sig, "A = B | C; D = E; F = G | H | I;").expect("parse of TRS");
+    ///
+    /// let mut t = original.clone();
+    /// let dropped = t.make_deterministic_with(ClausePolicy::First).expect("first clauses");
+    /// assert_eq!(t.display(&sig), "A = B;\nD = E;\nF = G;");
+    /// let dropped: Vec<_> = dropped.iter().map(|r| r.display(&sig)).collect();
+    /// assert_eq!(dropped, vec!["A = C", "F = H | I"]);
+    ///
+    /// // choose the last clause of each Rule
+    /// let mut t = original.clone();
+    /// t.make_deterministic_with(ClausePolicy::Choose(&mut |rule| rule.rhs.len() - 1))
+    ///     .expect("last clauses");
+    /// assert_eq!(t.display(&sig), "A = C;\nD = E;\nF = I;");
+    /// assert!(t.is_deterministic());
+    /// ```
+    pub fn make_deterministic_with(
+        &mut self,
+        mut policy: ClausePolicy,
+    ) -> Result<Vec<Rule>, TRSError> {
+        if self.is_deterministic {
+            return Ok(vec![]);
+        }
+        let mut choices = Vec::with_capacity(self.rules.len());
+        for rule in &self.rules {
+            let n = rule.rhs.len();
+            let choice = match policy {
+                _ if n < 2 => 0,
+                ClausePolicy::First => 0,
+                ClausePolicy::Random(ref mut rng) => rng.gen_range(0, n),
+                ClausePolicy::Choose(ref mut choose) => choose(rule),
+            };
+            if n > 0 && choice >= n {
+                return Err(TRSError::InvalidIndex(choice, n));
+            }
+            choices.push(choice);
+        }
+        let mut dropped = vec![];
+        for (rule, choice) in self.rules.iter_mut().zip(choices) {
+            rule.weights = None;
+            if rule.rhs.len() > 1 {
+                let kept = rule.rhs.remove(choice);
+                let rhs = ::std::mem::replace(&mut rule.rhs, vec![kept]);
+                dropped.push(Rule {
+                    lhs: rule.lhs.clone(),
+                    rhs,
+                    conditions: rule.conditions.clone(),
+                    weights: None,
+                });
+            }
+        }
+        self.is_deterministic = true;
+        Ok(dropped)
+    }
     /// Remove any [`determinism`] restriction the `TRS` might be under.
     ///
     /// Return `true` if the `TRS` was changed, otherwise `false`.
@@ -149,20 +303,20 @@ impl TRS {
     ///
     /// t.make_deterministic(& mut r);
     ///
-    /// let str_before = t.display();
+    /// let str_before = t.display(&sig);
     /// let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
     /// assert!(t.insert_idx(1, r.clone()).is_err());
-    /// assert_eq!(str_before, t.display());
+    /// assert_eq!(str_before, t.display(&sig));
     ///
     /// assert!(t.make_nondeterministic());
     ///
     /// t.insert_idx(1, r).expect("inserting C = B | D");
     ///
-    /// assert!((t.display() ==
+    /// assert!((t.display(&sig) ==
     /// "A = B;
     /// C = B | D;
     /// D = E;") ||
-    ///     (t.display() ==
+    ///     (t.display(&sig) ==
     /// "A = C;
     /// C = B | D;
     /// D = E;"));
@@ -246,6 +400,85 @@ impl TRS {
     pub fn is_empty(&self) -> bool {
         self.rules.is_empty()
     }
+    /// The [`Rule`]s of the `TRS`, in order.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B;\nC = D | E;").expect("parse of A = B; C = D | E;");
+    ///
+    /// assert_eq!(t.rules().len(), 2);
+    /// assert_eq!(t.rules()[1].display(&sig), "C = D | E");
+    /// ```
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+    /// Mutable access to the [`Rule`]s of the `TRS`. The `TRS` rebuilds the index it uses to
+    /// find the [`Rule`]s which might match a [`Term`] once the returned [`RulesMut`] is
+    /// dropped, so prefer methods like [`TRS::insert`] for small changes.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Term`]: enum.Term.html
+    /// [`RulesMut`]: struct.RulesMut.html
+    /// [`TRS::insert`]: struct.TRS.html#method.insert
+    ///
+    /// # Examples
+    /// ```
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_rule, parse_term, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig, "A = B;").expect("parse of A = B;");
+    /// t.rules_mut()[0] = parse_rule(&mut sig, "C = D").expect("parse of C = D");
+    ///
+    /// let term = parse_term(&mut sig, "C").expect("parse of C");
+    /// let rewritten = t.rewrite(&term, Strategy::Normal).expect("rewrite");
+    ///
+    /// assert_eq!(rewritten[0].display(&sig), "D");
+    /// ```
+    pub fn rules_mut(&mut self) -> RulesMut<'_> {
+        RulesMut { trs: self }
+    }
+    /// Is every [`Rule`] in the `TRS` weighted?
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B :: 0.5 | C :: 0.5; C = D :: 1;").expect("parse of A = B :: 0.5 | C :: 0.5; C = D :: 1;");
+    ///
+    /// assert!(t.is_weighted());
+    ///
+    /// let t = parse_trs(&mut sig, "A = B :: 0.5 | C :: 0.5; C = D;").expect("parse of A = B :: 0.5 | C :: 0.5; C = D;");
+    ///
+    /// assert!(!t.is_weighted());
+    /// ```
+    pub fn is_weighted(&self) -> bool {
+        self.rules.iter().all(Rule::is_weighted)
+    }
+    /// The weight of clause `clause` of the `rule`th [`Rule`], if that [`Rule`] is weighted.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B; C = D :: 0.25 | E :: 0.75;").expect("parse of A = B; C = D :: 0.25 | E :: 0.75;");
+    ///
+    /// assert_eq!(t.weight(0, 0), None);
+    /// assert_eq!(t.weight(1, 1), Some(0.75));
+    /// ```
+    pub fn weight(&self, rule: usize, clause: usize) -> Option<f64> {
+        self.rules.get(rule).and_then(|r| r.weight(clause))
+    }
     /// Return the number of total number of subterms across all [`Rule`]s in the `TRS`.
     ///
     /// See [`Term`] for more information.
@@ -282,7 +515,7 @@ impl TRS {
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// assert_eq!(t.display(),
+    /// assert_eq!(t.display(&sig),
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;");
@@ -295,18 +528,31 @@ impl TRS {
     ///     CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
     ///     B C D E = B C | D E;");
     ///
-    /// assert_eq!(trs.display(),
+    /// assert_eq!(trs.display(&sig),
     /// "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
     /// CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
     /// .(.(.(B C) D) E) = .(B C) | .(D E);");
     /// ```
-    pub fn display(&self) -> String {
-        self.rules
-            .iter()
-            .map(|r| format!("{};", r.display()))
-            .join("\n")
+    pub fn display(&self, sig: &Signature) -> String {
+        let sig = sig.sig.read().expect("poisoned signature");
+        let mut out = String::new();
+        for (i, rule) in self.rules.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            rule.display_into(&sig, &mut out);
+            out.push(';');
+        }
+        out
     }
-    /// A human-readable serialization of the `TRS`.
+    /// A human-readable serialization of the `TRS`, which [`parse_trs_pretty`] reads back as the
+    /// same `TRS`, up to the names of [`Variable`]s, unless an [`Operator`] has a
+    /// [`DisplayStyle`] other than `Prefix`.
+    ///
+    /// [`parse_trs_pretty`]: fn.parse_trs_pretty.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Operator`]: struct.Operator.html
+    /// [`DisplayStyle`]: enum.DisplayStyle.html
     ///
     /// # Examples
     ///
@@ -322,15 +568,36 @@ impl TRS {
     ///     CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
     ///     B C D E = B C | D E;");
     ///
-    /// assert_eq!(trs.pretty(),
-    /// "A(x_, y_, z_) = A(x_, 105, 2);
+    /// assert_eq!(trs.pretty(&sig),
+    /// "A(x_, y_, z_) = A(x_, 105, SUCC(SUCC(ZERO)));
     /// [B, C, D] = [C, D];
     /// B C D E = B C | D E;");
     /// ```
-    pub fn pretty(&self) -> String {
+    pub fn pretty(&self, sig: &Signature) -> String {
+        self.pretty_with(sig, &PrettyConfig::default())
+    }
+    /// A human-readable serialization of the `TRS`, as configured by `config`.
+    ///
+    /// See [`PrettyConfig`] for more information.
+    ///
+    /// [`PrettyConfig`]: struct.PrettyConfig.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, PrettyConfig, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let trs = parse_trs(&mut sig, "F(CONS(A NIL)) = G(A B); G(x_ y_) = y_;")
+    ///     .expect("parse of F(CONS(A NIL)) = G(A B); G(x_ y_) = y_;");
+    ///
+    /// let config = PrettyConfig { lists: false, ..PrettyConfig::default() };
+    /// assert_eq!(trs.pretty_with(&sig, &config), "F(CONS(A, NIL)) = G(A, B);\nG(x_, y_) = y_;");
+    /// ```
+    pub fn pretty_with(&self, sig: &Signature, config: &PrettyConfig) -> String {
         self.rules
             .iter()
-            .map(|r| format!("{};", r.pretty()))
+            .map(|r| format!("{};", r.pretty_with(sig, config)))
             .join("\n")
     }
     /// All the clauses in the `TRS`.
@@ -371,7 +638,7 @@ impl TRS {
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// let ops: Vec<String> = t.operators().iter().map(|o| o.display()).collect();
+    /// let ops: Vec<String> = t.operators().iter().map(|o| o.display(&sig)).collect();
     ///
     /// assert_eq!(ops, vec!["A", "B", "C", "D", "E", "F", "G"]);
     /// ```
@@ -504,1014 +771,4704 @@ impl TRS {
     pub fn alphas(trs1: &TRS, trs2: &TRS) -> bool {
         TRS::pmatches(trs2.clone(), trs1.clone()) && TRS::pmatches(trs1.clone(), trs2.clone())
     }
+    /// Rebuild the `TRS` with the [`Operator`]s and [`Variable`]s of another [`Signature`], as
+    /// by [`Term::map_signature`]. Returns `None` if either map lacks an [`Atom`] of the `TRS`.
+    ///
+    /// [`Operator`]: struct.Operator.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`Term::map_signature`]: enum.Term.html#method.map_signature
+    /// [`Atom`]: enum.Atom.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{OpMap, Signature, VarMap, parse_trs};
+    /// let mut sig = Signature::default();
+    /// let trs = parse_trs(&mut sig, "A(x_) = B;\nB = C;").expect("parse of A(x_) = B; B = C;");
+    ///
+    /// // export the TRS to a Signature of its own
+    /// let mut sig2 = Signature::default();
+    /// let ops: OpMap = sig
+    ///     .operators()
+    ///     .into_iter()
+    ///     .map(|op| (op, sig2.new_op(op.arity(), op.name(&sig))))
+    ///     .collect();
+    /// let vars: VarMap = sig
+    ///     .variables()
+    ///     .into_iter()
+    ///     .map(|v| (v, sig2.new_var(v.name(&sig))))
+    ///     .collect();
+    ///
+    /// let mapped = trs.map_signature(&ops, &vars).expect("mapped TRS");
+    ///
+    /// assert_eq!(mapped.display(&sig2), "A(x_) = B;\nB = C;");
+    /// ```
+    pub fn map_signature(&self, ops: &OpMap, vars: &VarMap) -> Option<TRS> {
+        let rules: Vec<Rule> = self
+            .rules
+            .iter()
+            .map(|rule| rule.map_signature(ops, vars))
+            .collect::<Option<_>>()?;
+        Some(TRS {
+            index: TrsIndex::new(&rules),
+            rules,
+            is_deterministic: self.is_deterministic,
+            condition_config: self.condition_config,
+            semi_equational: SemiEquational::default(),
+        })
+    }
     // Return rewrites modifying the entire term, if possible, else None.
     fn rewrite_head(&self, term: &Term) -> Option<Vec<Term>> {
-        for rule in &self.rules {
-            if let Some(ref sub) = Term::pmatch(vec![(&rule.lhs, &term)]) {
+        for r in self.candidates(term) {
+            let rule = &self.rules[r];
+            if let Some(ref sub) = self.matches(rule, term, self.condition_config.max_depth) {
                 return Some(rule.rhs.iter().map(|x| x.substitute(sub)).collect());
             }
         }
         None
     }
-    // Return rewrites modifying subterms, if possible, else None.
-    fn rewrite_args(&self, term: &Term, strategy: Strategy) -> Option<Vec<Term>> {
-        if let Term::Application { ref op, ref args } = *term {
-            for (i, arg) in args.iter().enumerate() {
-                if let Some(v) = self.rewrite(arg, strategy) {
-                    let res = v
-                        .iter()
-                        .map(|x| {
-                            let mut args = args.clone();
-                            args[i] = x.clone();
-                            Term::Application {
-                                op: op.clone(),
-                                args,
-                            }
-                        })
-                        .collect();
-                    return Some(res);
+    // Return the result of simultaneously rewriting every innermost redex with the first
+    // matching rule, if possible, else None.
+    fn rewrite_parallel_innermost(&self, term: &Term) -> Option<Term> {
+        enum Visit<'a> {
+            Enter(&'a Term),
+            Exit(&'a Term),
+        }
+        // the rewrite of each finished subterm, if any, computed bottom-up.
+        let mut todo = vec![Visit::Enter(term)];
+        let mut done: Vec<Option<Term>> = vec![];
+        while let Some(visit) = todo.pop() {
+            match visit {
+                Visit::Enter(subterm) => match *subterm {
+                    Term::Variable(_) => done.push(None),
+                    Term::Application { ref args, .. } => {
+                        todo.push(Visit::Exit(subterm));
+                        todo.extend(args.iter().rev().map(Visit::Enter));
+                    }
+                },
+                Visit::Exit(subterm) => {
+                    let (op, args) = match *subterm {
+                        Term::Application { ref op, ref args } => (op, args),
+                        Term::Variable(_) => unreachable!(),
+                    };
+                    let rewrites = done.split_off(done.len() - args.len());
+                    let rewrite = if rewrites.iter().any(Option::is_some) {
+                        let args = rewrites
+                            .into_iter()
+                            .zip(args)
+                            .map(|(rewrite, arg)| rewrite.unwrap_or_else(|| arg.clone()))
+                            .collect();
+                        Some(Term::Application { op: *op, args })
+                    } else {
+                        self.rewrite_head(subterm)
+                            .and_then(|rewrites| rewrites.into_iter().next())
+                    };
+                    done.push(rewrite);
                 }
             }
-            None
-        } else {
-            None
         }
+        done.pop().expect("rewritten term")
     }
-    // performs all possible rewrites, else None.
-    fn rewrite_all(&self, term: &Term) -> Option<Vec<Term>> {
-        match term {
-            Term::Variable(_) => None,
-            Term::Application { ref args, .. } => {
-                // rewrite head
-                let mut rewrites = self.rewrite_head(term).unwrap_or_else(|| vec![]);
-                // rewrite subterms
-                for (i, arg) in args.iter().enumerate() {
-                    for rewrite in self.rewrite_all(arg).unwrap_or_else(|| vec![]) {
-                        rewrites.push(term.replace(&[i], rewrite).unwrap());
+    // The place of the leftmost-innermost redex and the index of the first rule matching it, if
+    // any.
+    fn innermost_redex(&self, term: &Term) -> Option<(Place, usize)> {
+        // each entry is a subterm and the index of its next argument to visit; `place` is the
+        // place of the topmost entry.
+        let mut place = vec![];
+        let mut stack = vec![(term, 0)];
+        while let Some((subterm, i)) = stack.pop() {
+            match *subterm {
+                Term::Application { ref args, .. } if i < args.len() => {
+                    stack.push((subterm, i + 1));
+                    stack.push((&args[i], 0));
+                    place.push(i);
+                }
+                Term::Application { .. } => {
+                    if let Some(rule) = self.first_match(subterm) {
+                        return Some((place, rule));
                     }
+                    place.pop();
+                }
+                Term::Variable(_) => {
+                    place.pop();
                 }
-                Some(rewrites)
             }
         }
+        None
     }
-    /// Perform a single rewrite step.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term};
-    /// let mut sig = Signature::default();
-    ///
-    /// let t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
-    ///
-    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
-    ///
-    /// let rewritten_terms = &t.rewrite(&term, Strategy::Normal).unwrap();
-    /// assert_eq!(rewritten_terms.len(), 1);
-    /// assert_eq!(rewritten_terms[0].display(), "J(G K(C A))");
-    ///
-    /// let rewritten_terms = &t.rewrite(&term, Strategy::Eager).unwrap();
-    /// assert_eq!(rewritten_terms.len(), 2);
-    /// assert_eq!(rewritten_terms[0].display(), "J(F(D) K(C A))");
-    /// assert_eq!(rewritten_terms[1].display(), "J(F(E) K(C A))");
-    ///
-    /// let rewritten_terms = &t.rewrite(&term, Strategy::All).unwrap();
-    /// assert_eq!(rewritten_terms.len(), 6);
-    /// assert_eq!(rewritten_terms[0].display(), "J(G K(C A))");
-    /// assert_eq!(rewritten_terms[1].display(), "J(F(D) K(C A))");
-    /// assert_eq!(rewritten_terms[2].display(), "J(F(E) K(C A))");
-    /// assert_eq!(rewritten_terms[3].display(), "J(F(C) K(D A))");
-    /// assert_eq!(rewritten_terms[4].display(), "J(F(C) K(E A))");
-    /// assert_eq!(rewritten_terms[5].display(), "J(F(C) K(C B))");
-    /// ```
-    pub fn rewrite(&self, term: &Term, strategy: Strategy) -> Option<Vec<Term>> {
-        match *term {
-            Term::Variable(_) => None,
-            ref app => match strategy {
-                Strategy::Normal => self
-                    .rewrite_head(app)
-                    .or_else(|| self.rewrite_args(app, strategy)),
-                Strategy::Eager => self
-                    .rewrite_args(app, strategy)
-                    .or_else(|| self.rewrite_head(app)),
-                Strategy::All => self.rewrite_all(app),
-            },
+    // The place of the leftmost-outermost redex and the index of the first rule matching it, if
+    // any.
+    fn outermost_redex(&self, term: &Term) -> Option<(Place, usize)> {
+        let mut redex = None;
+        term.visit_preorder(|subterm, place| match self.first_match(subterm) {
+            Some(rule) => {
+                redex = Some((place.to_vec(), rule));
+                false
+            }
+            None => true,
+        });
+        redex
+    }
+    // The index of the first rule matching `term`, if any.
+    fn first_match(&self, term: &Term) -> Option<usize> {
+        self.candidates(term).into_iter().find(|&r| {
+            self.matches(&self.rules[r], term, self.condition_config.max_depth)
+                .is_some()
+        })
+    }
+    // The positions of the rules which might match `term`, as given by the index.
+    fn candidates(&self, term: &Term) -> Vec<usize> {
+        self.index.candidates(term)
+    }
+    // The positions of the rules whose left-hand sides have the same root as `lhs`, and so
+    // might be alpha-equivalent to it.
+    fn same_root(&self, lhs: &Term) -> Vec<usize> {
+        match *lhs {
+            Term::Application { ref op, .. } => self.index.with_root(op).to_vec(),
+            Term::Variable(_) => vec![],
         }
     }
-    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; return both
-    /// the [`Rule`] and its index if possible
+    // Match `rule` against `term`, checking its conditions with rules nested at most `depth`
+    // deep.
+    fn matches<'a>(
+        &self,
+        rule: &'a Rule,
+        term: &'a Term,
+        depth: usize,
+    ) -> Option<HashMap<&'a Variable, &'a Term>> {
+        let sub = Term::pmatch(vec![(&rule.lhs, term)])?;
+        if rule.conditions.is_empty() || self.satisfies(&rule.conditions, &sub, depth) {
+            Some(sub)
+        } else {
+            None
+        }
+    }
+    // Do `conditions` hold under `sub`, using conditional rules nested at most `depth` deep?
+    pub(crate) fn satisfies(
+        &self,
+        conditions: &[(Term, Term)],
+        sub: &HashMap<&Variable, &Term>,
+        depth: usize,
+    ) -> bool {
+        if depth == 0 {
+            return false;
+        }
+        let config = self.condition_config;
+        let trs = match config.mode {
+            ConditionMode::SemiEquational => self.semi_equational(),
+            ConditionMode::Oriented | ConditionMode::Join => self,
+        };
+        conditions.iter().all(|(c, d)| {
+            let c = c.substitute(sub);
+            let d = d.substitute(sub);
+            match config.mode {
+                ConditionMode::Oriented | ConditionMode::SemiEquational => trs
+                    .reducts_within(&c, Some(config.max_steps), depth - 1)
+                    .contains(&d),
+                ConditionMode::Join => trs.joins(&c, &d, Some(config.max_steps), depth - 1),
+            }
+        })
+    }
+    // The `TRS` in which semi-equational conditions are evaluated: these rules plus the
+    // reverse of each clause, evaluating its own conditions as oriented. It is built on first
+    // use and kept until the rules or the `ConditionConfig` change.
+    fn semi_equational(&self) -> &TRS {
+        self.semi_equational.0.get_or_init(|| {
+            let mut rules = self.rules.clone();
+            rules.extend(
+                self.rules
+                    .iter()
+                    .flat_map(Rule::clauses)
+                    .filter_map(|mut clause| {
+                        Rule::new_conditional(
+                            clause.rhs.remove(0),
+                            vec![clause.lhs],
+                            clause.conditions,
+                        )
+                    }),
+            );
+            let mut trs = TRS::new(rules);
+            trs.condition_config = ConditionConfig {
+                mode: ConditionMode::Oriented,
+                ..self.condition_config
+            };
+            Box::new(trs)
+        })
+    }
+    // Lazily rewrite `term` at `place` using each of `rules` which matches there, nesting
+    // conditional rules at most `depth` deep.
+    fn rewrites_at<'a, I>(
+        &'a self,
+        term: &'a Term,
+        place: Place,
+        rules: I,
+        depth: usize,
+    ) -> RewritesWithProvenance<'a>
+    where
+        I: Iterator<Item = usize> + 'a,
+    {
+        let subterm = term.at(&place).expect("place of redex");
+        RewritesWithProvenance(Box::new(rules.flat_map(move |r| {
+            let rule = &self.rules[r];
+            let place = place.clone();
+            self.matches(rule, subterm, depth)
+                .into_iter()
+                .flat_map(move |sub| {
+                    let place = place.clone();
+                    rule.rhs.iter().enumerate().map(move |(c, rhs)| Rewrite {
+                        term: term
+                            .replace(&place, rhs.substitute(&sub))
+                            .expect("place of redex"),
+                        rule: r,
+                        clause: c,
+                        place: place.clone(),
+                    })
+                })
+        })))
+    }
+    /// Every redex in `term`: each [`Position`] paired with the index of each [`Rule`] whose
+    /// LHS matches there, without contracting any of them. Redexes are listed in preorder and
+    /// then by [`Rule`].
     ///
+    /// [`Position`]: struct.Position.html
     /// [`Rule`]: struct.Rule.html
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
     /// let mut sig = Signature::default();
     ///
     /// let t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
-    ///
-    /// let a = parse_term(&mut sig, "A").expect("parse of A");
-    ///
-    /// assert_eq!(t.get(&a).unwrap().1.display(), "A = B");
-    ///
-    /// let c = parse_term(&mut sig, "C").expect("parse of C");
-    ///
-    /// assert_eq!(t.get(&c).unwrap().1.display(), "C = D | E");
+    /// "F(x_) = G(x_);
+    /// F(A) = B;
+    /// A = C;").expect("parse of F(x_) = G(x_); F(A) = B; A = C;");
+    /// let term = parse_term(&mut sig, "H(F(A) A)").expect("parse of H(F(A) A)");
+    ///
+    /// let redexes: Vec<(String, usize)> = t.redexes(&term)
+    ///     .iter()
+    ///     .map(|(p, r)| (p.display(), *r))
+    ///     .collect();
+    ///
+    /// assert_eq!(redexes, vec![
+    ///     ("0".to_string(), 0),
+    ///     ("0".to_string(), 1),
+    ///     ("0.0".to_string(), 2),
+    ///     ("1".to_string(), 2),
+    /// ]);
     /// ```
-    pub fn get(&self, lhs: &Term) -> Option<(usize, Rule)> {
-        for (idx, rule) in self.rules.iter().enumerate() {
-            if Term::alpha(lhs, &rule.lhs).is_some() {
-                return Some((idx, rule.clone()));
+    pub fn redexes(&self, term: &Term) -> Vec<(Position, usize)> {
+        let mut redexes = vec![];
+        term.visit_preorder(|subterm, place| {
+            for r in self.candidates(subterm) {
+                if self
+                    .matches(&self.rules[r], subterm, self.condition_config.max_depth)
+                    .is_some()
+                {
+                    redexes.push((Position::from(place.to_vec()), r));
+                }
             }
-        }
-        None
+            true
+        });
+        redexes
     }
-    /// Query a `TRS` for a [`Rule`] based on its index; return the [`Rule`] if
-    /// possible.
+    /// Contract exactly the redex at `position` in `term`, using each clause of each [`Rule`]
+    /// whose LHS matches there, in order. Returns `None` if `position` is not in `term` or no
+    /// [`Rule`] matches there.
     ///
     /// [`Rule`]: struct.Rule.html
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Position, Signature, TRS, parse_trs, parse_term};
     /// let mut sig = Signature::default();
     ///
     /// let t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    /// "F(x_) = G(x_);
+    /// F(A) = B | C;
+    /// A = D;").expect("parse of F(x_) = G(x_); F(A) = B | C; A = D;");
+    /// let term = parse_term(&mut sig, "H(F(A) A)").expect("parse of H(F(A) A)");
     ///
-    /// assert_eq!(t.get_idx(0).unwrap().display(), "A = B");
+    /// let rewritten: Vec<String> = t.rewrite_at(&term, &Position::from(vec![0]))
+    ///     .expect("rewrite")
+    ///     .iter()
+    ///     .map(|t| t.display(&sig))
+    ///     .collect();
     ///
-    /// assert_eq!(t.get_idx(1).unwrap().display(), "C = D | E");
+    /// assert_eq!(rewritten, vec!["H(G(A) A)", "H(B A)", "H(C A)"]);
+    ///
+    /// assert_eq!(t.rewrite_at(&term, &Position::root()), None);
     /// ```
-    pub fn get_idx(&self, idx: usize) -> Option<Rule> {
-        if self.rules.len() > idx {
-            Some(self.rules[idx].clone())
-        } else {
+    pub fn rewrite_at(&self, term: &Term, position: &Position) -> Option<Vec<Term>> {
+        let candidates = self.candidates(term.at(position)?);
+        let rewrites: Vec<Term> = self
+            .rewrites_at(
+                term,
+                position.to_vec(),
+                candidates.into_iter(),
+                self.condition_config.max_depth,
+            )
+            .map(|rewrite| rewrite.term)
+            .collect();
+        if rewrites.is_empty() {
             None
+        } else {
+            Some(rewrites)
         }
     }
-    /// Query a `TRS` for specific [`Rule`] clauses; return them if possible.
+    /// Contract the redex at `position` in `term` in place, using the first clause of the first
+    /// [`Rule`] whose LHS matches there. Only the redex is rebuilt; the rest of `term` is left
+    /// as it was. Returns the index of the [`Rule`] used, or `None` (leaving `term` unchanged)
+    /// if `position` is not in `term` or no [`Rule`] matches there.
     ///
     /// [`Rule`]: struct.Rule.html
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Position, Signature, TRS, parse_trs, parse_term};
     /// let mut sig = Signature::default();
     ///
     /// let t = parse_trs(&mut sig,
-    /// "A(a_ b_) = B(b_ b_);
-    /// D(c_ e_) = D(E F);").expect("parse of A(a_ b_) = B(b_ b_); D(c_ e_) = D(E F);");
-    ///
-    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(y_ y_)").expect("parse of A(x_ y_) = B(y_ y_)");
+    /// "F(A) = B | C;
+    /// F(x_) = G(x_);").expect("parse of F(A) = B | C; F(x_) = G(x_);");
+    /// let mut term = parse_term(&mut sig, "H(F(A) F(D))").expect("parse of H(F(A) F(D))");
     ///
-    /// assert_eq!(t.get_clause(&r).unwrap().1.display(), "A(a_ b_) = B(b_ b_)");
+    /// assert_eq!(t.rewrite_in_place_at(&mut term, &Position::from(vec![1])), Some(1));
+    /// assert_eq!(term.display(&sig), "H(F(A) G(D))");
     ///
-    /// let r = parse_rule(&mut sig, "D(w_ q_) = D(E F)").expect("parse of D(w_ q_) = D(E F)");
+    /// assert_eq!(t.rewrite_in_place_at(&mut term, &Position::from(vec![0])), Some(0));
+    /// assert_eq!(term.display(&sig), "H(B G(D))");
     ///
-    /// assert_eq!(t.get_clause(&r).unwrap().1.display(), "D(c_ e_) = D(E F)");
+    /// assert_eq!(t.rewrite_in_place_at(&mut term, &Position::root()), None);
+    /// assert_eq!(term.display(&sig), "H(B G(D))");
     /// ```
-    pub fn get_clause(&self, rule: &Rule) -> Option<(usize, Rule)> {
-        for (i, r) in self.rules.iter().enumerate() {
-            if let Some(sub) = r.contains(rule) {
-                return Some((i, rule.substitute(&sub)));
-            }
-        }
-        None
+    pub fn rewrite_in_place_at(&self, term: &mut Term, position: &Position) -> Option<usize> {
+        let subterm = term.at_mut(position)?;
+        let (r, reduct) = self.candidates(subterm).into_iter().find_map(|r| {
+            let rule = &self.rules[r];
+            let sub = self.matches(rule, subterm, self.condition_config.max_depth)?;
+            Some((r, rule.rhs.first()?.substitute(&sub)))
+        })?;
+        *subterm = reduct;
+        Some(r)
     }
-    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; delete and
-    /// return the [`Rule`] if it exists.
+    /// Perform a single rewrite step like [`rewrite`], but append the results to `buffer`
+    /// rather than allocating a new `Vec`, so that one buffer can be reused across many steps.
+    /// Returns the number of results appended.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`rewrite`]: #method.rewrite
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
-    ///
-    /// let a = parse_term(&mut sig, "A").expect("parse of A");
-    /// let c = parse_term(&mut sig, "C").expect("parse of C");
-    ///
-    /// assert_eq!(t.remove(&a).expect("removing A = B").display(), "A = B");
+    /// let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+    /// let mut buffer = vec![];
     ///
-    /// assert_eq!(t.remove(&c).expect("removing C = D").display(), "C = D | E");
+    /// for input in &["F(A)", "F(B)", "G(A A)"] {
+    ///     let term = parse_term(&mut sig, input).expect("parse of input");
+    ///     buffer.clear();
+    ///     let n = t.rewrite_into(&term, Strategy::Normal, &mut buffer);
+    ///     assert_eq!(n, buffer.len());
+    /// }
     ///
-    /// assert_eq!(t.display(), "F(x_) = G;");
+    /// let results: Vec<String> = buffer.iter().map(|t| t.display(&sig)).collect();
+    /// assert_eq!(results, vec!["G(B A)", "G(C A)"]);
     /// ```
-    pub fn remove(&mut self, lhs: &Term) -> Result<Rule, TRSError> {
-        if let Some((idx, _)) = self.get(lhs) {
-            Ok(self.rules.remove(idx))
-        } else {
-            Err(TRSError::NotInTRS)
-        }
+    pub fn rewrite_into(&self, term: &Term, strategy: Strategy, buffer: &mut Vec<Term>) -> usize {
+        let before = buffer.len();
+        buffer.extend(self.rewrites(term, strategy));
+        buffer.len() - before
     }
-    /// Query a `TRS` for a [`Rule`] based on its index; delete and return the
-    /// [`Rule`] if it exists.
+    /// Lazily perform a single rewrite step, computing each rewrite only when it is needed.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// The rewrites are those of [`rewrite`], but taking the first few rewrites of a large
+    /// `Term` doesn't pay for the rest.
+    ///
+    /// [`rewrite`]: #method.rewrite
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
+    /// let t = parse_trs(&mut sig,
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// assert_eq!(t.remove_idx(0).expect("removing A = B").display(), "A = B");
+    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
     ///
-    /// assert_eq!(t.remove_idx(0).expect("removing C = D").display(), "C = D | E");
+    /// let rewritten_terms: Vec<String> = t.rewrites(&term, Strategy::All)
+    ///     .take(2)
+    ///     .map(|t| t.display(&sig))
+    ///     .collect();
+    /// assert_eq!(rewritten_terms, vec!["J(G K(C A))", "J(F(D) K(C A))"]);
     ///
-    /// assert_eq!(t.display(), "F(x_) = G;");
+    /// let rewritten_terms: Vec<String> = t.rewrites(&term, Strategy::Eager)
+    ///     .map(|t| t.display(&sig))
+    ///     .collect();
+    /// assert_eq!(rewritten_terms, vec!["J(F(D) K(C A))", "J(F(E) K(C A))"]);
     /// ```
-    pub fn remove_idx(&mut self, idx: usize) -> Result<Rule, TRSError> {
-        if self.rules.len() > idx {
-            Ok(self.rules.remove(idx))
-        } else {
-            Err(TRSError::InvalidIndex(idx, self.rules.len()))
-        }
+    pub fn rewrites<'a>(&'a self, term: &'a Term, strategy: Strategy) -> Rewrites<'a> {
+        Rewrites(Box::new(
+            self.rewrites_with_provenance(term, strategy)
+                .map(|rewrite| rewrite.term),
+        ))
     }
-    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; delete and
-    /// return the [`Rule`] if it exists.
+    /// Lazily perform a single rewrite step like [`rewrites`], recording for each rewrite the
+    /// [`Rule`], clause, and [`Place`] which produced it.
     ///
+    /// [`Strategy::ParallelInnermost`] rewrites several [`Place`]s at once; its single rewrite
+    /// records the leftmost-innermost of them.
+    ///
+    /// [`rewrites`]: #method.rewrites
     /// [`Rule`]: struct.Rule.html
+    /// [`Place`]: type.Place.html
+    /// [`Strategy::ParallelInnermost`]: enum.Strategy.html#variant.ParallelInnermost
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
+    /// let t = parse_trs(&mut sig,
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// let r = parse_rule(&mut sig, "C = D").expect("parse of C = D");
-    ///
-    /// assert_eq!(t.remove_clauses(&r).expect("removing C = D").display(), "C = D");
+    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// C = E;
-    /// F(x_) = G;");
+    /// let rewrites: Vec<String> = t.rewrites_with_provenance(&term, Strategy::All)
+    ///     .map(|r| format!("{} {} {} {:?}", r.term.display(&sig), r.rule, r.clause, r.place))
+    ///     .collect();
+    /// assert_eq!(rewrites, vec![
+    ///     "J(G K(C A)) 2 0 [0]",
+    ///     "J(F(D) K(C A)) 1 0 [0, 0]",
+    ///     "J(F(E) K(C A)) 1 1 [0, 0]",
+    ///     "J(F(C) K(D A)) 1 0 [1, 0]",
+    ///     "J(F(C) K(E A)) 1 1 [1, 0]",
+    ///     "J(F(C) K(C B)) 0 0 [1, 1]",
+    /// ]);
     /// ```
-    pub fn remove_clauses(&mut self, rule: &Rule) -> Result<Rule, TRSError> {
-        self.rules
-            .iter_mut()
-            .filter_map(|r| r.discard(&rule))
-            .next()
-            .ok_or(TRSError::NotInTRS)
-            .and_then(|discarded| {
-                self.rules.retain(|rule| !rule.is_empty());
-                Ok(discarded)
-            })
+    pub fn rewrites_with_provenance<'a>(
+        &'a self,
+        term: &'a Term,
+        strategy: Strategy,
+    ) -> RewritesWithProvenance<'a> {
+        let none = || RewritesWithProvenance(Box::new(iter::empty()));
+        if let Term::Variable(_) = *term {
+            return none();
+        }
+        // find the redexes while the rules are indexed; only rewriting them is lazy
+        match strategy {
+            Strategy::Normal => match self.outermost_redex(term) {
+                Some((place, r)) => {
+                    self.rewrites_at(term, place, iter::once(r), self.condition_config.max_depth)
+                }
+                None => none(),
+            },
+            Strategy::Eager => match self.innermost_redex(term) {
+                Some((place, r)) => {
+                    self.rewrites_at(term, place, iter::once(r), self.condition_config.max_depth)
+                }
+                None => none(),
+            },
+            Strategy::Outermost => match self.outermost_redex(term) {
+                Some((place, _)) => {
+                    let rules = self.candidates(term.at(&place).expect("place of redex"));
+                    self.rewrites_at(
+                        term,
+                        place,
+                        rules.into_iter(),
+                        self.condition_config.max_depth,
+                    )
+                }
+                None => none(),
+            },
+            Strategy::ParallelInnermost => {
+                let rewrite = self.rewrite_parallel_innermost(term).map(|t| {
+                    let (place, rule) = self.innermost_redex(term).expect("innermost redex");
+                    Rewrite {
+                        term: t,
+                        rule,
+                        clause: 0,
+                        place,
+                    }
+                });
+                RewritesWithProvenance(Box::new(rewrite.into_iter()))
+            }
+            Strategy::All => {
+                let mut redexes = vec![];
+                term.visit_preorder(|subterm, place| {
+                    if let Term::Application { .. } = *subterm {
+                        if let Some(r) = self.first_match(subterm) {
+                            redexes.push((place.to_vec(), r));
+                        }
+                    }
+                    true
+                });
+                RewritesWithProvenance(Box::new(redexes.into_iter().flat_map(move |(place, r)| {
+                    self.rewrites_at(term, place, iter::once(r), self.condition_config.max_depth)
+                })))
+            }
+        }
     }
-    /// Try to merge a [`Rule`] with an existing [`Rule`] or else insert it at index `i` in the `TRS` if possible.
-    ///
-    /// [`Rule`]: struct.Rule.html
+    /// Perform a single rewrite step.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
+    /// let t = parse_trs(&mut sig,
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// let r = parse_rule(&mut sig, "D = G").expect("parse of D = G");
+    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
     ///
-    /// t.insert(1, r).expect("inserting D = G at index 1");
+    /// let rewritten_terms = &t.rewrite(&term, Strategy::Normal).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 1);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// D = G;
-    /// C = D | E;
-    /// F(x_) = G;");
+    /// let rewritten_terms = &t.rewrite(&term, Strategy::Eager).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 2);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(F(D) K(C A))");
+    /// assert_eq!(rewritten_terms[1].display(&sig), "J(F(E) K(C A))");
     ///
-    /// let r = parse_rule(&mut sig, "D = A").expect("parse of D = A");
+    /// let rewritten_terms = &t.rewrite(&term, Strategy::Outermost).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 1);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
     ///
-    /// t.insert(0, r).expect("inserting D = A with D = G");
+    /// let rewritten_terms = &t.rewrite(&term, Strategy::ParallelInnermost).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 1);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(F(D) K(D B))");
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// D = G | A;
-    /// C = D | E;
-    /// F(x_) = G;");
+    /// let rewritten_terms = &t.rewrite(&term, Strategy::All).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 6);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
+    /// assert_eq!(rewritten_terms[1].display(&sig), "J(F(D) K(C A))");
+    /// assert_eq!(rewritten_terms[2].display(&sig), "J(F(E) K(C A))");
+    /// assert_eq!(rewritten_terms[3].display(&sig), "J(F(C) K(D A))");
+    /// assert_eq!(rewritten_terms[4].display(&sig), "J(F(C) K(E A))");
+    /// assert_eq!(rewritten_terms[5].display(&sig), "J(F(C) K(C B))");
     /// ```
-    pub fn insert(&mut self, idx: usize, rule: Rule) -> Result<&mut TRS, TRSError> {
-        if self.insert_clauses(&rule).is_err() {
-            self.insert_idx(idx, rule)
-        } else {
-            Ok(self)
-        }
+    pub fn rewrite(&self, term: &Term, strategy: Strategy) -> Option<Vec<Term>> {
+        self.rewrite_with(term, &strategy)
     }
-    /// Insert a [`Rule`] at index `i` in the `TRS` if possible.
+    /// Perform a single rewrite step using a [`RewriteStrategy`], which may be user-defined.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`RewriteStrategy`]: trait.RewriteStrategy.html
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term, RewriteStrategy};
+    /// // Rewrite only the deepest redex, preferring the rightmost, using the first matching rule.
+    /// struct DeepestRightmost;
+    /// impl RewriteStrategy for DeepestRightmost {
+    ///     fn rewrite(&self, trs: &TRS, term: &Term) -> Option<Vec<Term>> {
+    ///         let mut subterms = term.subterms();
+    ///         subterms.sort_by(|(_, p1), (_, p2)| p2.len().cmp(&p1.len()).then(p2.cmp(p1)));
+    ///         for (subterm, place) in subterms {
+    ///             for rule in trs.rules() {
+    ///                 if let Some(sub) = Term::pmatch(vec![(&rule.lhs, subterm)]) {
+    ///                     let rhs = rule.rhs[0].substitute(&sub);
+    ///                     return Some(vec![term.replace(&place, rhs).unwrap()]);
+    ///                 }
+    ///             }
+    ///         }
+    ///         None
+    ///     }
+    /// }
+    ///
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
+    /// let t = parse_trs(&mut sig,
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// let r = parse_rule(&mut sig, "D = G").expect("parse of D = G");
+    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
     ///
-    /// t.insert_idx(1, r).expect("inserting D = G at index 1");
+    /// let rewritten_terms = &t.rewrite_with(&term, &DeepestRightmost).unwrap();
+    /// assert_eq!(rewritten_terms.len(), 1);
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(F(C) K(C B))");
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// D = G;
-    /// C = D | E;
-    /// F(x_) = G;");
+    /// let rewritten_terms = &t.rewrite_with(&term, &Strategy::Normal).unwrap();
+    /// assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
     /// ```
-    pub fn insert_idx(&mut self, idx: usize, rule: Rule) -> Result<&mut TRS, TRSError> {
-        if self.is_deterministic && rule.len() > 1 {
-            return Err(TRSError::NondeterministicRule);
-        } else if idx > self.rules.len() {
-            return Err(TRSError::InvalidIndex(idx, self.rules.len()));
-        } else if self.get(&rule.lhs).is_some() {
-            return Err(TRSError::AlreadyInTRS);
-        }
-        self.rules.insert(idx, rule);
-        Ok(self)
+    pub fn rewrite_with<S: RewriteStrategy + ?Sized>(
+        &self,
+        term: &Term,
+        strategy: &S,
+    ) -> Option<Vec<Term>> {
+        strategy.rewrite(self, term)
     }
-    /// Inserts a series of [`Rule`]s into the `TRS` at the index provided if possible.
+    /// Perform a single rewrite step like [`rewrite_with`], but fail with
+    /// [`RewriteError::TermTooLarge`] if some result has more than `max_term_size` subterms.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`rewrite_with`]: #method.rewrite_with
+    /// [`RewriteError::TermTooLarge`]: enum.RewriteError.html#variant.TermTooLarge
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term, RewriteError};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = H;").expect("parse of A = B; C = D | E; F(x_) = H;");
-    ///
-    /// let r0 = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
-    /// let r1 = parse_rule(&mut sig, "B = C").expect("parse of B = C");
-    /// let r2 = parse_rule(&mut sig, "E = F | B").expect("parse of E = F | B");
+    /// let t = parse_trs(&mut sig, "F(x_) = G(x_ x_);").expect("parse of F(x_) = G(x_ x_);");
+    /// let term = parse_term(&mut sig, "F(F(A))").expect("parse of F(F(A))");
     ///
-    /// t.inserts_idx(2, vec![r0, r1, r2]).expect("inserting 3 rules at index 2");
+    /// let rewritten_terms = t.rewrite_bounded(&term, &Strategy::Normal, 5).unwrap().unwrap();
+    /// assert_eq!(rewritten_terms[0].display(&sig), "G(F(A) F(A))");
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// C = D | E;
-    /// G(y_) = y_;
-    /// B = C;
-    /// E = F | B;
-    /// F(x_) = H;");
+    /// match t.rewrite_bounded(&term, &Strategy::Normal, 4) {
+    ///     Err(RewriteError::TermTooLarge(t)) => assert_eq!(t.display(&sig), "G(F(A) F(A))"),
+    ///     _ => panic!("expected a term that is too large"),
+    /// }
     /// ```
-    pub fn inserts_idx(&mut self, idx: usize, rules: Vec<Rule>) -> Result<&mut TRS, TRSError> {
-        for rule in rules.into_iter().rev() {
-            self.insert_idx(idx, rule)?;
+    pub fn rewrite_bounded<S: RewriteStrategy + ?Sized>(
+        &self,
+        term: &Term,
+        strategy: &S,
+        max_term_size: usize,
+    ) -> Result<Option<Vec<Term>>, RewriteError> {
+        match self.rewrite_with(term, strategy) {
+            Some(rewrites) => match rewrites.iter().position(|t| t.size() > max_term_size) {
+                Some(i) => Err(RewriteError::TermTooLarge(rewrites[i].clone())),
+                None => Ok(Some(rewrites)),
+            },
+            None => Ok(None),
         }
-        Ok(self)
     }
-    /// Merge a [`Rule`] with an existing [`Rule`] in the `TRS` if possible.
+    /// Perform a single rewrite step chosen uniformly at random from those performed by
+    /// `strategy`. With [`Strategy::All`], this samples uniformly from the applicable rule and
+    /// position pairs.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`Strategy::All`]: enum.Strategy.html#variant.All
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, Term, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
+    /// let t = parse_trs(&mut sig,
     /// "A = B;
     /// C = D | E;
     /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
     ///
-    /// let r = parse_rule(&mut sig, "A = H").expect("parse of A = H");
+    /// let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
+    /// let mut r = rand::thread_rng();
     ///
-    /// let t = t.insert_clauses(&r).expect("inserting A = H with A = B");
+    /// let rewritten_term = t.rewrite_random(&term, &Strategy::All, &mut r).unwrap();
+    /// assert!(t.rewrite(&term, Strategy::All).unwrap().contains(&rewritten_term));
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B | H;
-    /// C = D | E;
-    /// F(x_) = G;");
+    /// let rewritten_term = t.rewrite_random(&term, &Strategy::Normal, &mut r).unwrap();
+    /// assert_eq!(rewritten_term.display(&sig), "J(G K(C A))");
+    ///
+    /// let term = parse_term(&mut sig, "J(B D)").expect("parse of J(B D)");
+    /// assert_eq!(t.rewrite_random(&term, &Strategy::All, &mut r), None);
+    /// # }
     /// ```
-    pub fn insert_clauses(&mut self, rule: &Rule) -> Result<&mut TRS, TRSError> {
-        if self.is_deterministic {
-            Err(TRSError::NondeterministicRule)
-        } else if let Some((idx, _)) = self.get(&rule.lhs) {
-            self.rules[idx].merge(rule);
-            Ok(self)
+    pub fn rewrite_random<S: RewriteStrategy + ?Sized, R: Rng>(
+        &self,
+        term: &Term,
+        strategy: &S,
+        rng: &mut R,
+    ) -> Option<Term> {
+        let mut rewrites = self.rewrite_with(term, strategy)?;
+        if rewrites.is_empty() {
+            None
         } else {
-            Err(TRSError::NotInTRS)
+            let i = rng.gen_range(0, rewrites.len());
+            Some(rewrites.swap_remove(i))
         }
     }
-    /// Insert new [`Rule`] clauses if possible and move the entire [`Rule`] if
-    /// necessary to be the first in the `TRS`.
+    /// Sample a derivation of up to `steps` rewrite steps from `term`, stopping early at a
+    /// normal form. Each step contracts the leftmost-outermost redex with the first matching
+    /// [`Rule`], as [`Strategy::Normal`] does, choosing a clause according to the [`Rule`]'s
+    /// weights; clauses of unweighted [`Rule`]s are chosen uniformly.
     ///
     /// [`Rule`]: struct.Rule.html
+    /// [`Strategy::Normal`]: enum.Strategy.html#variant.Normal
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # extern crate rand;
+    /// # extern crate term_rewriting;
+    /// # fn main(){
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
-    ///
-    /// let r = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
+    /// let t = parse_trs(&mut sig,
+    /// "COIN = HEADS :: 0.5 | TAILS :: 0.5;
+    /// HEADS = WIN :: 1;").expect("parse of COIN = HEADS :: 0.5 | TAILS :: 0.5; HEADS = WIN :: 1;");
+    /// let term = parse_term(&mut sig, "COIN").expect("parse of COIN");
+    /// let mut r = rand::thread_rng();
     ///
-    /// t.push(r).expect("inserting G(y_) = y_ at index 0");
+    /// let derivation = t.sample_derivation(&term, 10, &mut r);
+    /// let trajectory: Vec<String> = derivation.trajectory.iter().map(|t| t.display(&sig)).collect();
     ///
-    /// assert_eq!(t.display(),
-    /// "G(y_) = y_;
-    /// A = B;
-    /// C = D | E;
-    /// F(x_) = G;");
+    /// assert!(trajectory == vec!["COIN", "HEADS", "WIN"] || trajectory == vec!["COIN", "TAILS"]);
+    /// assert!((derivation.log_probability - 0.5f64.ln()).abs() < 1e-9);
+    /// # }
     /// ```
-    pub fn push(&mut self, rule: Rule) -> Result<&mut TRS, TRSError> {
-        let lhs = rule.lhs.clone();
-        self.insert(0, rule)?
-            .get(&lhs)
-            .ok_or(TRSError::NotInTRS)
-            .and_then(move |(idx, _)| self.move_rule(idx, 0))
+    pub fn sample_derivation<R: Rng>(&self, term: &Term, steps: usize, rng: &mut R) -> Derivation {
+        let mut trajectory = vec![term.clone()];
+        let mut log_probability = 0.0;
+        for _ in 0..steps {
+            let rewrites: Vec<Rewrite> = self
+                .rewrites_with_provenance(&trajectory[trajectory.len() - 1], Strategy::Normal)
+                .collect();
+            if rewrites.is_empty() {
+                break;
+            }
+            let rule = &self.rules[rewrites[0].rule];
+            let weights: Vec<f64> = match rule.weights {
+                Some(ref weights) => weights.clone(),
+                None => vec![1.0 / rule.len() as f64; rule.len()],
+            };
+            let mut threshold = rng.gen::<f64>();
+            // fall back to the last clause with positive weight to absorb rounding error
+            let mut choice = weights.iter().rposition(|w| *w > 0.0).unwrap_or(0);
+            for (c, w) in weights.iter().enumerate() {
+                if *w > 0.0 && threshold < *w {
+                    choice = c;
+                    break;
+                }
+                threshold -= w;
+            }
+            log_probability += weights[choice].ln();
+            let rewrite = rewrites
+                .into_iter()
+                .find(|rewrite| rewrite.clause == choice)
+                .expect("rewrite for each clause");
+            trajectory.push(rewrite.term);
+        }
+        Derivation {
+            trajectory,
+            log_probability,
+        }
     }
-    /// Inserts a series of [`Rule`]s at the beginning of the `TRS` if possible.
+    /// Repeatedly rewrite `term` using `strategy` until it reaches a normal form, following
+    /// the first result of each step. `config` limits the number of steps and the size of
+    /// rewritten `Term`s, and controls cycle detection.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// Returns [`RewriteError::TermTooLarge`] if a step would exceed the size limit.
+    ///
+    /// `strategy` may be a [`Strategy`] or any other [`RewriteStrategy`].
+    ///
+    /// [`Strategy`]: enum.Strategy.html
+    /// [`RewriteStrategy`]: trait.RewriteStrategy.html
+    /// [`RewriteError::TermTooLarge`]: enum.RewriteError.html#variant.TermTooLarge
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term, NormalizeConfig, Normalization, RewriteError};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = H;").expect("parse of A = B; C = D | E; F(x_) = H;");
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO y_) = y_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));
+    /// A = B;
+    /// B = A;").expect("parse of PLUS rules and A = B; B = A;");
+    /// let config = NormalizeConfig::default();
     ///
-    /// let r0 = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
-    /// let r1 = parse_rule(&mut sig, "B = C").expect("parse of B = C");
-    /// let r2 = parse_rule(&mut sig, "E = F | B").expect("parse of E = F | B");
+    /// let term = parse_term(&mut sig, "PLUS(SUCC(SUCC(ZERO)) ZERO)").expect("parse of PLUS(SUCC(SUCC(ZERO)) ZERO)");
+    /// match t.normalize(&term, Strategy::Normal, config) {
+    ///     Ok(Normalization::Normalized(nf)) => assert_eq!(nf.display(&sig), "SUCC(SUCC(ZERO))"),
+    ///     _ => panic!("expected a normal form"),
+    /// }
     ///
-    /// t.pushes(vec![r0, r1, r2]).expect("inserting 3 rules at index 0");
+    /// let limited = NormalizeConfig { max_steps: Some(2), ..config };
+    /// match t.normalize(&term, Strategy::Normal, limited) {
+    ///     Ok(Normalization::StepLimit(t)) => assert_eq!(t.display(&sig), "SUCC(SUCC(PLUS(ZERO ZERO)))"),
+    ///     _ => panic!("expected the step limit"),
+    /// }
     ///
-    /// assert_eq!(t.display(),
-    /// "G(y_) = y_;
-    /// B = C;
-    /// E = F | B;
-    /// A = B;
-    /// C = D | E;
-    /// F(x_) = H;");
+    /// let limited = NormalizeConfig { max_term_size: Some(4), ..config };
+    /// match t.normalize(&term, Strategy::Normal, limited) {
+    ///     Err(RewriteError::TermTooLarge(t)) => assert_eq!(t.display(&sig), "SUCC(PLUS(SUCC(ZERO) ZERO))"),
+    ///     _ => panic!("expected a term that is too large"),
+    /// }
+    ///
+    /// let term = parse_term(&mut sig, "A").expect("parse of A");
+    /// match t.normalize(&term, Strategy::Normal, config) {
+    ///     Ok(Normalization::Cycle(t)) => assert_eq!(t.display(&sig), "A"),
+    ///     _ => panic!("expected a cycle"),
+    /// }
     /// ```
-    pub fn pushes(&mut self, rules: Vec<Rule>) -> Result<&mut TRS, TRSError> {
-        for rule in rules.into_iter().rev() {
-            self.push(rule)?;
-        }
-        Ok(self)
+    pub fn normalize<S: RewriteStrategy>(
+        &self,
+        term: &Term,
+        strategy: S,
+        config: NormalizeConfig,
+    ) -> Result<Normalization, RewriteError> {
+        self.normalize_until(term, strategy, config, &AtomicBool::new(false))
     }
-    /// Move a [`Rule`] from index `i` to `j` if possible.
+    /// [`normalize`] `term`, checking `cancel` between steps. Once `cancel` is set, or the
+    /// deadline in `config` passes, returns [`Normalization::Interrupted`] with the most recent
+    /// `Term`.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`normalize`]: #method.normalize
+    /// [`Normalization::Interrupted`]: enum.Normalization.html#variant.Interrupted
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term, NormalizeConfig, Normalization};
+    /// # use std::sync::atomic::AtomicBool;
+    /// # use std::time::Instant;
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;
-    /// H = I;").expect("parse of A = B; C = D | E; F(x_) = G; H = I;");
+    /// let t = parse_trs(&mut sig, "F(x_) = F(G(x_));").expect("parse of F(x_) = F(G(x_));");
+    /// let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+    /// let config = NormalizeConfig { max_steps: None, ..NormalizeConfig::default() };
     ///
-    /// t.move_rule(0, 2).expect("moving rule from index 0 to index 2");
+    /// let cancel = AtomicBool::new(true);
+    /// match t.normalize_until(&term, Strategy::Normal, config, &cancel) {
+    ///     Ok(Normalization::Interrupted(t)) => assert_eq!(t.display(&sig), "F(A)"),
+    ///     _ => panic!("expected an interruption"),
+    /// }
     ///
-    /// assert_eq!(t.display(),
-    /// "C = D | E;
-    /// F(x_) = G;
-    /// A = B;
-    /// H = I;");
+    /// let config = NormalizeConfig { deadline: Some(Instant::now()), ..config };
+    /// match t.normalize(&term, Strategy::Normal, config) {
+    ///     Ok(Normalization::Interrupted(_)) => (),
+    ///     _ => panic!("expected an interruption"),
+    /// }
     /// ```
-    pub fn move_rule(&mut self, i: usize, j: usize) -> Result<&mut TRS, TRSError> {
-        if i != j {
-            let rule = self.remove_idx(i)?;
-            self.insert(j, rule)
-        } else {
-            Ok(self)
-        }
+    pub fn normalize_until<S: RewriteStrategy>(
+        &self,
+        term: &Term,
+        strategy: S,
+        config: NormalizeConfig,
+        cancel: &AtomicBool,
+    ) -> Result<Normalization, RewriteError> {
+        self.normalize_memoized(term, strategy, config, cancel, None)
     }
-    /// Remove some [`Rule`] clauses while also inserting others if possible.
+    /// [`normalize`] `term`, consulting `cache` for the normal forms of the `Term`s visited and
+    /// recording them once a normal form is reached. A cached normal form is used only if it
+    /// was reached within the limits of `config`, so the result is as [`normalize`] would give.
     ///
-    /// The index `i` is used only in the case that the new clauses cannot be
-    /// added to an existing [`Rule`].
+    /// `cache` must only be used with this `TRS` and `strategy`; [`clear`] it after changing
+    /// either.
     ///
-    /// [`Rule`]: struct.Rule.html
+    /// [`normalize`]: #method.normalize
+    /// [`clear`]: struct.NormalFormCache.html#method.clear
     ///
     /// # Examples
     ///
     /// ```
-    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term, NormalizeConfig, Normalization, NormalFormCache};
     /// let mut sig = Signature::default();
     ///
-    /// let mut t = parse_trs(&mut sig,
-    /// "A = B;
-    /// C = D | E;
-    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO y_) = y_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS rules");
+    /// let config = NormalizeConfig::default();
+    /// let mut cache = NormalFormCache::new();
+    ///
+    /// let term = parse_term(&mut sig, "PLUS(SUCC(SUCC(ZERO)) y_)").expect("parse of PLUS(SUCC(SUCC(ZERO)) y_)");
+    /// match t.normalize_cached(&term, Strategy::Normal, config, &mut cache) {
+    ///     Ok(Normalization::Normalized(nf)) => assert_eq!(nf.display(&sig), "SUCC(SUCC(y_))"),
+    ///     _ => panic!("expected a normal form"),
+    /// }
+    /// assert_eq!(cache.len(), 4);
     ///
-    /// let r = parse_rule(&mut sig, "C = D").expect("parse of C = D");
-    /// let r_new = parse_rule(&mut sig, "C = A").expect("parse of C = A");
+    /// let term = parse_term(&mut sig, "SUCC(PLUS(SUCC(ZERO) z_))").expect("parse of SUCC(PLUS(SUCC(ZERO) z_))");
+    /// assert_eq!(cache.get(&term).unwrap().display(&sig), "SUCC(SUCC(z_))");
+    /// ```
+    pub fn normalize_cached<S: RewriteStrategy>(
+        &self,
+        term: &Term,
+        strategy: S,
+        config: NormalizeConfig,
+        cache: &mut NormalFormCache,
+    ) -> Result<Normalization, RewriteError> {
+        let cancel = AtomicBool::new(false);
+        self.normalize_memoized(term, strategy, config, &cancel, Some(cache))
+    }
+    fn normalize_memoized<S: RewriteStrategy>(
+        &self,
+        term: &Term,
+        strategy: S,
+        config: NormalizeConfig,
+        cancel: &AtomicBool,
+        mut cache: Option<&mut NormalFormCache>,
+    ) -> Result<Normalization, RewriteError> {
+        let mut term = term.clone();
+        let mut seen = HashSet::new();
+        // the Terms visited, recorded in the cache once a normal form is reached
+        let mut path = vec![];
+        let mut steps = 0;
+        let normalization = loop {
+            let past_deadline = match config.deadline {
+                Some(deadline) => Instant::now() >= deadline,
+                None => false,
+            };
+            if past_deadline || cancel.load(Ordering::Relaxed) {
+                break Ok(Normalization::Interrupted(term));
+            }
+            if let Some(ref mut cache) = cache {
+                let max_steps = config.max_steps.map(|max| max - steps);
+                if let Some(entry) = cache.lookup(&term, max_steps, config.max_term_size) {
+                    path.push(term);
+                    cache.record(path, &entry);
+                    break Ok(Normalization::Normalized(entry.normal_form));
+                }
+            }
+            if config.detect_cycles && !seen.insert(term.clone()) {
+                break Ok(Normalization::Cycle(term));
+            }
+            let next = self
+                .rewrite_with(&term, &strategy)
+                .and_then(|ts| ts.into_iter().next());
+            match next {
+                None => {
+                    if let Some(cache) = cache {
+                        path.push(term.clone());
+                        cache.record(path, &CacheEntry::normal(&term));
+                    }
+                    break Ok(Normalization::Normalized(term));
+                }
+                Some(next) if config.max_steps == Some(steps) => {
+                    next.drop_deep();
+                    break Ok(Normalization::StepLimit(term));
+                }
+                Some(next) => match config.max_term_size {
+                    Some(limit) if next.size() > limit => {
+                        break Err(RewriteError::TermTooLarge(next))
+                    }
+                    _ => {
+                        let previous = ::std::mem::replace(&mut term, next);
+                        if cache.is_some() {
+                            path.push(previous);
+                        } else {
+                            previous.drop_deep();
+                        }
+                    }
+                },
+            }
+            steps += 1;
+        };
+        // the Terms visited may be too deep to drop recursively.
+        seen.into_iter().for_each(Term::drop_deep);
+        normalization
+    }
+    /// All the [`CriticalPair`]s of the `TRS`, found by overlapping the left-hand side of each
+    /// [`Rule`] with a non-variable subterm of each left-hand side, including its own. The
+    /// trivial overlap of a clause with itself at the root is omitted.
     ///
-    /// t.replace(0, &r, r_new).expect("replaceing C = D with C = A");
+    /// [`Rule`]s sharing [`Variable`]s are renamed apart before overlapping them, which adds
+    /// fresh [`Variable`]s to `sig`.
+    ///
+    /// [`CriticalPair`]: struct.CriticalPair.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(t.display(),
-    /// "A = B;
-    /// C = E | A;
-    /// F(x_) = G;");
     /// ```
-    pub fn replace(&mut self, idx: usize, rule1: &Rule, rule2: Rule) -> Result<&mut TRS, TRSError> {
-        self.remove_clauses(rule1)?;
-        self.insert(idx, rule2)
-    }
-}
-
-#[derive(Debug, Copy, Clone)]
-pub enum Strategy {
-    /// Perform only the leftmost-innermost rewrite
-    Normal,
-    /// Perform only the leftmost-innermost rewrite
-    Eager,
-    /// Perform all possible rewrites
-    All,
-}
-impl fmt::Display for Strategy {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Strategy::Normal => write!(f, "Normal"),
-            Strategy::Eager => write!(f, "Eager"),
-            Strategy::All => write!(f, "All"),
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "F(G(x_)) = A;
+    /// G(B) = C;").expect("parse of F(G(x_)) = A; G(B) = C;");
+    ///
+    /// let pairs = t.critical_pairs(&mut sig);
+    ///
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!(pairs[0].peak.display(&sig), "F(G(B))");
+    /// assert_eq!(pairs[0].left.display(&sig), "A");
+    /// assert_eq!(pairs[0].right.display(&sig), "F(C)");
+    /// assert_eq!((pairs[0].outer, pairs[0].inner), (0, 1));
+    /// assert_eq!(pairs[0].place, vec![0]);
+    /// ```
+    pub fn critical_pairs(&self, sig: &mut Signature) -> Vec<CriticalPair> {
+        let mut pairs = vec![];
+        for (i, outer) in self.rules.iter().enumerate() {
+            for (j, inner) in self.rules.iter().enumerate() {
+                pairs.append(&mut CriticalPair::overlaps(sig, outer, i, inner, j));
+            }
         }
+        pairs
     }
-}
-
-#[derive(Debug, Clone)]
-/// The error type for [`TRS`] manipulations.
-///
-/// [`TRS`]: struct.TRS.html
-pub enum TRSError {
-    /// Returned when requesting to edit a rule that is not in the TRS.
+    /// Is the `TRS` [locally confluent]? Check that each of its [`CriticalPair`]s is joinable,
+    /// with each side taking at most `max_steps` rewrite steps, and return the first
+    /// [`CriticalPair`] found not to be as a counterexample.
     ///
-    /// See [`TRS::get`] for more information.
+    /// Failing to join a [`CriticalPair`] within `max_steps` steps does not mean it cannot be
+    /// joined, so a counterexample is only conclusive for small enough `TRS`s.
     ///
-    /// [`TRS::get`]: struct.TRS.html#method.get
-    NotInTRS,
-    /// Returned when attempting to insert a rule into a TRS that already exists.
+    /// [locally confluent]: https://en.wikipedia.org/wiki/Confluence_(abstract_rewriting)#Local_confluence
+    /// [`CriticalPair`]: struct.CriticalPair.html
     ///
-    /// See [`TRS::insert`] for more information.
+    /// # Examples
     ///
-    /// [`TRS::insert`]: struct.TRS.html#method.insert
-    AlreadyInTRS,
-    /// Returned when attempting to insert a rule with multiple RHSs into a deterministic TRS.
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
     ///
-    /// See [`TRS::insert`] and [`TRS::make_deterministic`] for more information.
+    /// let t = parse_trs(&mut sig,
+    /// "F(G(x_)) = A;
+    /// G(B) = C;").expect("parse of F(G(x_)) = A; G(B) = C;");
     ///
-    /// [`TRS::insert`]: struct.TRS.html#method.insert
-    /// [`TRS::make_deterministic`]: struct.TRS.html#method.make_deterministic
-    NondeterministicRule,
-    /// Returned when requesting the rule at an index that is out of the range of indicies for the TRS.
+    /// let cp = t.is_locally_confluent(&mut sig, 10).unwrap_err();
     ///
-    /// See [`TRS::get_idx`] for more information.
+    /// assert_eq!(cp.left.display(&sig), "A");
+    /// assert_eq!(cp.right.display(&sig), "F(C)");
     ///
-    /// [`TRS::get_idx`]: struct.TRS.html#method.get_idx
-    InvalidIndex(usize, usize),
-}
-impl fmt::Display for TRSError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            TRSError::NotInTRS => write!(f, "query rule not in TRS"),
-            TRSError::AlreadyInTRS => write!(f, "pre-existing rule with same LHS in TRS"),
-            TRSError::NondeterministicRule => {
-                write!(f, "proposed rule is nondeterministic in deterministic TRS")
+    /// let t = parse_trs(&mut sig,
+    /// "F(G(x_)) = A;
+    /// G(B) = C;
+    /// F(C) = A;").expect("parse of F(G(x_)) = A; G(B) = C; F(C) = A;");
+    ///
+    /// assert!(t.is_locally_confluent(&mut sig, 10).is_ok());
+    /// ```
+    pub fn is_locally_confluent(
+        &self,
+        sig: &mut Signature,
+        max_steps: usize,
+    ) -> Result<(), Box<CriticalPair>> {
+        self.critical_pairs(sig)
+            .into_iter()
+            .find(|cp| {
+                !self.joins(
+                    &cp.left,
+                    &cp.right,
+                    Some(max_steps),
+                    self.condition_config.max_depth,
+                )
+            })
+            .map_or(Ok(()), |cp| Err(Box::new(cp)))
+    }
+    /// Is the `TRS` [confluent], assuming it is terminating? By [Newman's lemma], a terminating
+    /// `TRS` is confluent if and only if each of its [`CriticalPair`]s is joinable. Return the
+    /// first [`CriticalPair`] that is not as a counterexample.
+    ///
+    /// Each side of each [`CriticalPair`] is rewritten until no new [`Term`]s appear, so this
+    /// method does not return if the `TRS` is not terminating.
+    ///
+    /// [confluent]: https://en.wikipedia.org/wiki/Confluence_(abstract_rewriting)
+    /// [Newman's lemma]: https://en.wikipedia.org/wiki/Newman%27s_lemma
+    /// [`CriticalPair`]: struct.CriticalPair.html
+    /// [`Term`]: enum.Term.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO x_) = x_;
+    /// PLUS(x_ ZERO) = x_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS rules");
+    ///
+    /// assert!(t.is_confluent_if_terminating(&mut sig).is_ok());
+    ///
+    /// let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+    ///
+    /// assert!(t.is_confluent_if_terminating(&mut sig).is_err());
+    /// ```
+    pub fn is_confluent_if_terminating(
+        &self,
+        sig: &mut Signature,
+    ) -> Result<(), Box<CriticalPair>> {
+        self.critical_pairs(sig)
+            .into_iter()
+            .find(|cp| !self.joins(&cp.left, &cp.right, None, self.condition_config.max_depth))
+            .map_or(Ok(()), |cp| Err(Box::new(cp)))
+    }
+    /// Is each [`Rule`] of the `TRS` left-linear? See [`Rule::is_left_linear`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::is_left_linear`]: struct.Rule.html#method.is_left_linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ y_) = B(x_ x_);
+    /// C(x_) = x_;").expect("parse of A(x_ y_) = B(x_ x_); C(x_) = x_;");
+    ///
+    /// assert!(t.is_left_linear());
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ x_) = B;
+    /// C(x_) = x_;").expect("parse of A(x_ x_) = B; C(x_) = x_;");
+    ///
+    /// assert!(!t.is_left_linear());
+    /// ```
+    pub fn is_left_linear(&self) -> bool {
+        self.rules.iter().all(Rule::is_left_linear)
+    }
+    /// Is each [`Rule`] of the `TRS` right-linear? See [`Rule::is_right_linear`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::is_right_linear`]: struct.Rule.html#method.is_right_linear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ x_) = B(x_);
+    /// C(x_) = x_;").expect("parse of A(x_ x_) = B(x_); C(x_) = x_;");
+    ///
+    /// assert!(t.is_right_linear());
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ y_) = B(x_ x_);
+    /// C(x_) = x_;").expect("parse of A(x_ y_) = B(x_ x_); C(x_) = x_;");
+    ///
+    /// assert!(!t.is_right_linear());
+    /// ```
+    pub fn is_right_linear(&self) -> bool {
+        self.rules.iter().all(Rule::is_right_linear)
+    }
+    /// The indices of the collapsing [`Rule`]s of the `TRS`. See [`Rule::is_collapsing`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::is_collapsing`]: struct.Rule.html#method.is_collapsing
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ y_) = B(x_ x_);
+    /// C(x_) = x_;").expect("parse of A(x_ y_) = B(x_ x_); C(x_) = x_;");
+    ///
+    /// assert_eq!(t.collapsing_rules(), vec![1]);
+    /// ```
+    pub fn collapsing_rules(&self) -> Vec<usize> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.is_collapsing())
+            .map(|(i, _)| i)
+            .collect()
+    }
+    /// The indices of the duplicating [`Rule`]s of the `TRS`. See [`Rule::is_duplicating`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::is_duplicating`]: struct.Rule.html#method.is_duplicating
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(x_ y_) = B(x_ x_);
+    /// C(x_) = x_;").expect("parse of A(x_ y_) = B(x_ x_); C(x_) = x_;");
+    ///
+    /// assert_eq!(t.duplicating_rules(), vec![0]);
+    /// ```
+    pub fn duplicating_rules(&self) -> Vec<usize> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.is_duplicating())
+            .map(|(i, _)| i)
+            .collect()
+    }
+    /// Do any two [`Rule`]s of the `TRS` overlap? A [`Rule`] overlaps another when its LHS unifies
+    /// with a non-variable subterm of the other's LHS, though each clause trivially overlaps
+    /// itself at the root. Distinct clauses of a single [`Rule`] overlap at the root.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO x_) = x_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS rules");
+    ///
+    /// assert!(!t.is_overlapping(&mut sig));
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO x_) = x_;
+    /// PLUS(x_ ZERO) = x_;").expect("parse of PLUS(ZERO x_) = x_; PLUS(x_ ZERO) = x_;");
+    ///
+    /// assert!(t.is_overlapping(&mut sig));
+    /// ```
+    pub fn is_overlapping(&self, sig: &mut Signature) -> bool {
+        self.rules.iter().enumerate().any(|(i, outer)| {
+            self.rules
+                .iter()
+                .enumerate()
+                .any(|(j, inner)| !CriticalPair::overlaps(sig, outer, i, inner, j).is_empty())
+        })
+    }
+    /// Is the `TRS` [orthogonal]? An orthogonal `TRS` is left-linear and has no overlapping
+    /// [`Rule`]s, which makes it confluent.
+    ///
+    /// [orthogonal]: https://en.wikipedia.org/wiki/Orthogonality_(term_rewriting)
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO x_) = x_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS rules");
+    ///
+    /// assert!(t.is_orthogonal(&mut sig));
+    ///
+    /// let t = parse_trs(&mut sig, "EQ(x_ x_) = TRUE;").expect("parse of EQ(x_ x_) = TRUE;");
+    ///
+    /// assert!(!t.is_orthogonal(&mut sig));
+    /// ```
+    pub fn is_orthogonal(&self, sig: &mut Signature) -> bool {
+        self.is_left_linear() && !self.is_overlapping(sig)
+    }
+    /// Is the `TRS` weakly orthogonal? A weakly orthogonal `TRS` is left-linear and each of its
+    /// [`CriticalPair`]s is trivial, which makes it confluent.
+    ///
+    /// [`CriticalPair`]: struct.CriticalPair.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "OR(TRUE x_) = TRUE;
+    /// OR(x_ TRUE) = TRUE;").expect("parse of OR(TRUE x_) = TRUE; OR(x_ TRUE) = TRUE;");
+    ///
+    /// assert!(!t.is_orthogonal(&mut sig));
+    /// assert!(t.is_weakly_orthogonal(&mut sig));
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "F(A x_) = B;
+    /// F(x_ A) = C;").expect("parse of F(A x_) = B; F(x_ A) = C;");
+    ///
+    /// assert!(!t.is_weakly_orthogonal(&mut sig));
+    /// ```
+    pub fn is_weakly_orthogonal(&self, sig: &mut Signature) -> bool {
+        self.is_left_linear()
+            && self
+                .critical_pairs(sig)
+                .iter()
+                .all(CriticalPair::is_trivial)
+    }
+    /// Search the rewrite graph of `term`, up to `max_steps` steps deep, for a loop: a
+    /// derivation `s ->+ C[sσ]` for some [`Context`] `C` and substitution `σ`. A loop proves
+    /// that the `TRS` does not terminate. Returns a [`NontermCertificate`] recording the loop.
+    ///
+    /// [`Context`]: enum.Context.html
+    /// [`NontermCertificate`]: struct.NontermCertificate.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "F(x_) = G(x_);
+    /// G(SUCC(x_)) = H(F(x_));").expect("parse of F(x_) = G(x_); G(SUCC(x_)) = H(F(x_));");
+    /// let term = parse_term(&mut sig, "F(SUCC(SUCC(A)))").expect("parse of F(SUCC(SUCC(A)))");
+    ///
+    /// assert_eq!(t.find_loop(&term, 10), None);
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "F(x_) = G(x_);
+    /// G(x_) = H(F(SUCC(x_)));").expect("parse of F(x_) = G(x_); G(x_) = H(F(SUCC(x_)));");
+    ///
+    /// // the derivation diverges, but no ground term recurs in it.
+    /// assert_eq!(t.find_loop(&term, 10), None);
+    ///
+    /// let term = parse_term(&mut sig, "F(x_)").expect("parse of F(x_)");
+    /// let certificate = t.find_loop(&term, 10).expect("loop");
+    ///
+    /// assert_eq!(certificate.display(&sig), "F(x_) -> G(x_) -> H(F(SUCC(x_))); C = H([!]); {x_ := SUCC(x_)}");
+    /// assert!(certificate.check(&t));
+    /// ```
+    pub fn find_loop(&self, term: &Term, max_steps: usize) -> Option<NontermCertificate> {
+        let mut terms = vec![term.clone()];
+        let mut parents = vec![None];
+        let mut seen = HashSet::new();
+        seen.insert(term.clone());
+        let mut frontier = vec![0];
+        for _ in 0..max_steps {
+            let mut next = vec![];
+            for i in frontier {
+                let derivation = derivation(&terms, &parents, i);
+                for reduct in self.one_step_reducts(&terms[i], self.condition_config.max_depth) {
+                    for (start, s) in derivation.iter().enumerate() {
+                        for (u, place) in reduct.subterms() {
+                            if let Some(sub) = Term::pmatch(vec![(s, u)]) {
+                                let substitution = Substitution::from(sub);
+                                let context = Context::from(reduct.clone())
+                                    .replace(&place, Context::Hole)
+                                    .expect("place of subterm");
+                                let mut derivation = derivation[start..].to_vec();
+                                derivation.push(reduct.clone());
+                                return Some(NontermCertificate {
+                                    derivation,
+                                    context,
+                                    substitution,
+                                });
+                            }
+                        }
+                    }
+                    if seen.insert(reduct.clone()) {
+                        terms.push(reduct);
+                        parents.push(Some(i));
+                        next.push(terms.len() - 1);
+                    }
+                }
             }
-            TRSError::InvalidIndex(length, max_length) => {
-                write!(f, "index {} greater than max index {}", length, max_length)
+            frontier = next;
+        }
+        None
+    }
+    /// Explore the `Term`s reachable from `term` in at most `max_depth` steps, breadth-first.
+    /// Each step may use any clause of any [`Rule`] at any [`Place`]. `Term`s which are
+    /// alpha-equivalent to one already explored are not explored again.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Place`]: type.Place.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B | C;
+    /// B = C;
+    /// F(x_) = G(x_);").expect("parse of A = B | C; B = C; F(x_) = G(x_);");
+    /// let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+    ///
+    /// let graph = t.reachable(&term, 2);
+    /// let nodes: Vec<String> = graph.nodes.iter().map(|t| t.display(&sig)).collect();
+    ///
+    /// assert_eq!(nodes, vec!["F(A)", "G(A)", "F(B)", "F(C)", "G(B)", "G(C)"]);
+    /// assert_eq!(graph.edges.len(), 8);
+    /// assert_eq!(graph.successors(0), vec![1, 2, 3]);
+    /// ```
+    pub fn reachable(&self, term: &Term, max_depth: usize) -> RewriteGraph {
+        let mut graph = RewriteGraph {
+            nodes: vec![term.clone()],
+            edges: vec![],
+        };
+        let mut frontier = vec![0];
+        for _ in 0..max_depth {
+            let mut next = vec![];
+            for source in frontier {
+                let rewrites: Vec<Rewrite> = self
+                    .one_step_rewrites(&graph.nodes[source], self.condition_config.max_depth)
+                    .collect();
+                for rewrite in rewrites {
+                    let target = match graph.find(&rewrite.term) {
+                        Some(target) => target,
+                        None => {
+                            graph.nodes.push(rewrite.term);
+                            next.push(graph.nodes.len() - 1);
+                            graph.nodes.len() - 1
+                        }
+                    };
+                    graph.edges.push(RewriteEdge {
+                        source,
+                        target,
+                        rule: rewrite.rule,
+                        clause: rewrite.clause,
+                        place: rewrite.place,
+                    });
+                }
             }
+            frontier = next;
         }
+        graph
     }
-}
-impl ::std::error::Error for TRSError {
-    fn description(&self) -> &'static str {
-        "TRS error"
+    /// Explore the `Term`s reachable from `term` in at most `max_depth` steps and serialize
+    /// the resulting [`RewriteGraph`] in the [DOT] language.
+    ///
+    /// See [`TRS::reachable`] and [`RewriteGraph::to_dot`] for more information.
+    ///
+    /// [`RewriteGraph`]: struct.RewriteGraph.html
+    /// [DOT]: https://graphviz.gitlab.io/_pages/doc/info/lang.html
+    /// [`TRS::reachable`]: struct.TRS.html#method.reachable
+    /// [`RewriteGraph::to_dot`]: struct.RewriteGraph.html#method.to_dot
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+    /// let term = parse_term(&mut sig, "A").expect("parse of A");
+    ///
+    /// assert_eq!(t.reachable_dot(&sig, &term, 1), t.reachable(&term, 1).to_dot(&sig, &t));
+    /// ```
+    pub fn reachable_dot(&self, sig: &Signature, term: &Term, max_depth: usize) -> String {
+        self.reachable(term, max_depth).to_dot(sig, self)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::super::super::parser::*;
-    use super::super::Signature;
+    /// Search, breadth-first, for a derivation of at most `max_steps` steps from `s` to a
+    /// `Term` alpha-equivalent to `t`, rewriting according to `strategy`. Returns the shortest
+    /// such derivation, including `s` and the final `Term`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, Strategy, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B | C;
+    /// C = D;
+    /// B = E;
+    /// F(x_) = G(x_);").expect("parse of A = B | C; C = D; B = E; F(x_) = G(x_);");
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    /// let d = parse_term(&mut sig, "D").expect("parse of D");
+    ///
+    /// let derivation = t.rewrites_to(&a, &d, &Strategy::All, 5).expect("derivation");
+    /// let derivation: Vec<String> = derivation.iter().map(|t| t.display(&sig)).collect();
+    ///
+    /// assert_eq!(derivation, vec!["A", "C", "D"]);
+    ///
+    /// assert_eq!(t.rewrites_to(&a, &d, &Strategy::All, 1), None);
+    /// assert_eq!(t.rewrites_to(&d, &a, &Strategy::All, 5), None);
+    ///
+    /// let f = parse_term(&mut sig, "F(x_)").expect("parse of F(x_)");
+    /// let g = parse_term(&mut sig, "G(y_)").expect("parse of G(y_)");
+    ///
+    /// assert_eq!(t.rewrites_to(&f, &g, &Strategy::Normal, 1).map(|d| d.len()), Some(2));
+    /// ```
+    pub fn rewrites_to<S: RewriteStrategy + ?Sized>(
+        &self,
+        s: &Term,
+        t: &Term,
+        strategy: &S,
+        max_steps: usize,
+    ) -> Option<Vec<Term>> {
+        let size = t.size();
+        let is_target = |term: &Term| term.size() == size && Term::alpha(t, term).is_some();
+        let mut terms = vec![s.clone()];
+        let mut parents = vec![None];
+        let mut seen = HashSet::new();
+        seen.insert(s.clone());
+        let mut frontier = vec![0];
+        let mut steps = 0;
+        loop {
+            if let Some(&i) = frontier.iter().find(|&&i| is_target(&terms[i])) {
+                return Some(derivation(&terms, &parents, i));
+            }
+            if frontier.is_empty() || steps == max_steps {
+                return None;
+            }
+            let mut next = vec![];
+            for i in frontier {
+                for reduct in strategy.rewrite(self, &terms[i]).unwrap_or_default() {
+                    if seen.insert(reduct.clone()) {
+                        terms.push(reduct);
+                        parents.push(Some(i));
+                        next.push(terms.len() - 1);
+                    }
+                }
+            }
+            frontier = next;
+            steps += 1;
+        }
+    }
+    /// Search for a `Term` to which both `s` and `t` rewrite, using any clause of any [`Rule`]
+    /// at any [`Place`] within `limits`. Returns the common reduct with the shortest combined
+    /// derivations, along with both derivations.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Place`]: type.Place.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, SearchLimits, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B | C;
+    /// B = D;
+    /// C = D;
+    /// E = F;").expect("parse of A = B | C; B = D; C = D; E = F;");
+    /// let b = parse_term(&mut sig, "B").expect("parse of B");
+    /// let c = parse_term(&mut sig, "C").expect("parse of C");
+    /// let e = parse_term(&mut sig, "E").expect("parse of E");
+    ///
+    /// let join = t.joinable(&b, &c, SearchLimits::default()).expect("join");
+    ///
+    /// assert_eq!(join.reduct.display(&sig), "D");
+    /// assert_eq!(join.left, vec![b.clone(), join.reduct.clone()]);
+    /// assert_eq!(join.right, vec![c.clone(), join.reduct.clone()]);
+    ///
+    /// assert_eq!(t.joinable(&b, &e, SearchLimits::default()), None);
+    /// ```
+    pub fn joinable(&self, s: &Term, t: &Term, limits: SearchLimits) -> Option<Join> {
+        let (s_terms, s_parents, s_depths) = self.search(s, &limits);
+        let (t_terms, t_parents, t_depths) = self.search(t, &limits);
+        let mut best: Option<(usize, usize, usize)> = None;
+        for (i, term) in s_terms.iter().enumerate() {
+            if let Some(j) = t_terms.iter().position(|u| u == term) {
+                let cost = s_depths[i] + t_depths[j];
+                if best.is_none() || Some(cost) < best.map(|(_, _, c)| c) {
+                    best = Some((i, j, cost));
+                }
+            }
+        }
+        best.map(|(i, j, _)| Join {
+            reduct: s_terms[i].clone(),
+            left: derivation(&s_terms, &s_parents, i),
+            right: derivation(&t_terms, &t_parents, j),
+        })
+    }
+    /// Explore every nondeterministic rewrite of `term`, using any clause of any [`Rule`] at
+    /// any [`Place`] within `limits`, and collect the distinct normal forms reached. Each normal
+    /// form is paired with the number of distinct derivations reaching it, and normal forms are
+    /// listed in order of discovery.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`Place`]: type.Place.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, SearchLimits, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B | C;
+    /// B = D | E;
+    /// C = D;").expect("parse of A = B | C; B = D | E; C = D;");
+    /// let term = parse_term(&mut sig, "A").expect("parse of A");
+    ///
+    /// let forms: Vec<(String, usize)> = t.normal_forms(&term, SearchLimits::default())
+    ///     .iter()
+    ///     .map(|(t, n)| (t.display(&sig), *n))
+    ///     .collect();
+    ///
+    /// assert_eq!(forms, vec![("D".to_string(), 2), ("E".to_string(), 1)]);
+    /// ```
+    pub fn normal_forms(&self, term: &Term, limits: SearchLimits) -> Vec<(Term, usize)> {
+        let mut forms: Vec<(Term, usize)> = vec![];
+        let mut frontier = vec![(term.clone(), 1)];
+        let mut visited = 1;
+        for steps in 0..=limits.max_steps {
+            let mut next: Vec<(Term, usize)> = vec![];
+            let mut indices = HashMap::new();
+            for (t, count) in frontier {
+                let reducts = self.one_step_reducts(&t, self.condition_config.max_depth);
+                if reducts.is_empty() {
+                    match forms.iter().position(|(form, _)| *form == t) {
+                        Some(i) => forms[i].1 = forms[i].1.saturating_add(count),
+                        None => forms.push((t, count)),
+                    }
+                    continue;
+                }
+                if steps == limits.max_steps {
+                    continue;
+                }
+                for reduct in reducts {
+                    if limits.max_term_size.iter().any(|&n| reduct.size() > n) {
+                        continue;
+                    }
+                    if let Some(&i) = indices.get(&reduct) {
+                        let entry: &mut (Term, usize) = &mut next[i];
+                        entry.1 = entry.1.saturating_add(count);
+                    } else if limits.max_terms.iter().all(|&n| visited < n) {
+                        visited += 1;
+                        indices.insert(reduct.clone(), next.len());
+                        next.push((reduct, count));
+                    }
+                }
+            }
+            frontier = next;
+        }
+        forms
+    }
+    /// Lazily perform a single [narrowing] step: unify a non-variable subterm of `term` with
+    /// the LHS of a [`Rule`], then rewrite the instantiated `term` with one of its clauses.
+    /// [`Rule`]s sharing [`Variable`]s with `term` are renamed apart first, which adds fresh
+    /// [`Variable`]s to their [`Signature`]. Conditional [`Rule`]s are not used.
+    ///
+    /// [`NarrowingStrategy`] selects the [`Position`]s narrowed; results are produced in
+    /// preorder of [`Position`], then by [`Rule`] and clause, and each is computed only when
+    /// it is needed.
+    ///
+    /// [narrowing]: https://en.wikipedia.org/wiki/Narrowing_(computer_science)
+    /// [`Rule`]: struct.Rule.html
+    /// [`Variable`]: struct.Variable.html
+    /// [`Signature`]: struct.Signature.html
+    /// [`NarrowingStrategy`]: enum.NarrowingStrategy.html
+    /// [`Position`]: struct.Position.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{NarrowingStrategy, Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "PLUS(ZERO y_) = y_;
+    /// PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));").expect("parse of PLUS(ZERO y_) = y_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));");
+    /// let term = parse_term(&mut sig, "PLUS(z_ ZERO)").expect("parse of PLUS(z_ ZERO)");
+    ///
+    /// let narrowings: Vec<String> = t.narrow(&mut sig, &term, NarrowingStrategy::All)
+    ///     .map(|n| format!("{} {} {} {}", n.term.display(&sig), n.substitution.display(&sig), n.rule, n.position.display()))
+    ///     .collect();
+    ///
+    /// assert_eq!(narrowings, vec![
+    ///     "ZERO {z_ := ZERO} 0 ε",
+    ///     "SUCC(PLUS(x_ ZERO)) {z_ := SUCC(x_)} 1 ε",
+    /// ]);
+    /// ```
+    pub fn narrow<'a>(
+        &'a self,
+        sig: &mut Signature,
+        term: &'a Term,
+        strategy: NarrowingStrategy,
+    ) -> Narrowings<'a> {
+        let vars = term.variables();
+        let rules: Rc<Vec<Rule>> = Rc::new(
+            self.rules
+                .iter()
+                .map(|rule| {
+                    if rule.variables().iter().any(|v| vars.contains(v)) {
+                        rule.rename_variables(sig)
+                    } else {
+                        rule.clone()
+                    }
+                })
+                .collect(),
+        );
+        let positions: Vec<Position> = term
+            .positions()
+            .into_iter()
+            .filter(|p| matches!(term.at(p), Some(Term::Application { .. })))
+            .collect();
+        let positions = match strategy {
+            NarrowingStrategy::All => positions,
+            NarrowingStrategy::Innermost | NarrowingStrategy::Outermost => {
+                let narrowable: Vec<Position> = positions
+                    .into_iter()
+                    .filter(|p| {
+                        let subterm = term.at(p).expect("position of subterm");
+                        rules.iter().any(|rule| {
+                            !rule.is_conditional() && Term::mgu(&rule.lhs, subterm).is_some()
+                        })
+                    })
+                    .collect();
+                narrowable
+                    .iter()
+                    .filter(|p| {
+                        !narrowable.iter().any(|q| match strategy {
+                            NarrowingStrategy::Innermost => q != *p && p.is_prefix_of(q),
+                            _ => q != *p && q.is_prefix_of(p),
+                        })
+                    })
+                    .cloned()
+                    .collect()
+            }
+        };
+        Narrowings(Box::new(positions.into_iter().flat_map(move |position| {
+            let rules = rules.clone();
+            let vars = vars.clone();
+            (0..rules.len()).flat_map(move |i| narrowings_at(term, &position, &rules[i], i, &vars))
+        })))
+    }
+    // Explore `term`'s reducts breadth-first within `limits`, returning each term, its parent's
+    // index, and its depth.
+    fn search(
+        &self,
+        term: &Term,
+        limits: &SearchLimits,
+    ) -> (Vec<Term>, Vec<Option<usize>>, Vec<usize>) {
+        let mut terms = vec![term.clone()];
+        let mut parents = vec![None];
+        let mut depths = vec![0];
+        let mut seen = HashSet::new();
+        seen.insert(term.clone());
+        let mut frontier = vec![0];
+        for depth in 1..=limits.max_steps {
+            let mut next = vec![];
+            for i in frontier {
+                for reduct in self.one_step_reducts(&terms[i], self.condition_config.max_depth) {
+                    if limits.max_terms.iter().any(|&n| terms.len() >= n) {
+                        return (terms, parents, depths);
+                    }
+                    if limits.max_term_size.iter().any(|&n| reduct.size() > n) {
+                        continue;
+                    }
+                    if seen.insert(reduct.clone()) {
+                        terms.push(reduct);
+                        parents.push(Some(i));
+                        depths.push(depth);
+                        next.push(terms.len() - 1);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        (terms, parents, depths)
+    }
+    // Do `s` and `t` rewrite to a common term in at most `max_steps` steps each, nesting
+    // conditional rules at most `depth` deep?
+    fn joins(&self, s: &Term, t: &Term, max_steps: Option<usize>, depth: usize) -> bool {
+        let s_reducts = self.reducts_within(s, max_steps, depth);
+        let t_reducts = self.reducts_within(t, max_steps, depth);
+        !s_reducts.is_disjoint(&t_reducts)
+    }
+    // Every term reachable from `term` in at most `max_steps` steps, nesting conditional rules
+    // at most `depth` deep.
+    fn reducts_within(&self, term: &Term, max_steps: Option<usize>, depth: usize) -> HashSet<Term> {
+        let mut seen = HashSet::new();
+        seen.insert(term.clone());
+        let mut frontier = vec![term.clone()];
+        let mut steps = 0;
+        while !frontier.is_empty() && max_steps != Some(steps) {
+            let mut next = vec![];
+            for t in &frontier {
+                for reduct in self.one_step_reducts(t, depth) {
+                    if seen.insert(reduct.clone()) {
+                        next.push(reduct);
+                    }
+                }
+            }
+            frontier = next;
+            steps += 1;
+        }
+        seen
+    }
+    // Every term reachable from `term` in a single step by any clause at any place, nesting
+    // conditional rules at most `depth` deep.
+    fn one_step_reducts(&self, term: &Term, depth: usize) -> Vec<Term> {
+        self.one_step_rewrites(term, depth)
+            .map(|r| r.term)
+            .collect()
+    }
+    // Every rewrite of `term` by any clause at any place, nesting conditional rules at most
+    // `depth` deep.
+    fn one_step_rewrites<'a>(&'a self, term: &'a Term, depth: usize) -> RewritesWithProvenance<'a> {
+        RewritesWithProvenance(Box::new(term.subterms().into_iter().flat_map(
+            move |(_, place)| self.rewrites_at(term, place, 0..self.rules.len(), depth),
+        )))
+    }
+    /// Remove redundant clutter from the `TRS`, returning a [`Simplification`] listing what
+    /// was removed:
+    ///
+    /// - trivial clauses, whose RHS is identical to the LHS;
+    /// - clauses alpha-equivalent to a clause kept earlier in the `TRS`;
+    /// - [`Rule`]s subsumed by another [`Rule`] (see [`Rule::subsumes`]).
+    ///
+    /// [`Rule`]s left with no clauses are removed. Simplifying preserves the rewrite relation
+    /// of the `TRS` as a whole, but may change which [`Rule`] matches a `Term` first, and so
+    /// the results of [`Strategy`]s using only the first matching [`Rule`].
+    ///
+    /// [`Simplification`]: struct.Simplification.html
+    /// [`Rule`]: struct.Rule.html
+    /// [`Rule::subsumes`]: struct.Rule.html#method.subsumes
+    /// [`Strategy`]: enum.Strategy.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "F(x_) = x_ | F(x_);
+    /// G(y_) = A | y_ | A;
+    /// F(z_) = z_;
+    /// G(B) = A;").expect("parse of F(x_) = x_ | F(x_); G(y_) = A | y_ | A; F(z_) = z_; G(B) = A;");
+    ///
+    /// let report = t.simplify();
+    ///
+    /// assert_eq!(t.display(&sig), "F(x_) = x_;\nG(y_) = A | y_;");
+    /// assert_eq!(report.trivial.len(), 1);
+    /// assert_eq!(report.duplicates.len(), 2);
+    /// assert_eq!(report.subsumed[0].display(&sig), "G(B) = A");
+    /// ```
+    pub fn simplify(&mut self) -> Simplification {
+        let mut report = Simplification::default();
+        let mut kept: Vec<Rule> = vec![];
+        let mut emptied = vec![];
+        for (i, rule) in self.rules.iter_mut().enumerate() {
+            let mut rhs = vec![];
+            for clause in rule.clauses() {
+                if clause.rhs[0] == clause.lhs {
+                    report.trivial.push(clause);
+                } else if kept.iter().any(|k| Rule::alpha(k, &clause).is_some()) {
+                    report.duplicates.push(clause);
+                } else {
+                    rhs.push(clause.rhs[0].clone());
+                    kept.push(clause);
+                }
+            }
+            if rhs.is_empty() && !rule.rhs.is_empty() {
+                emptied.push(i);
+            }
+            if rhs.len() != rule.rhs.len() {
+                rule.weights = None;
+            }
+            rule.rhs = rhs;
+        }
+        for i in emptied.into_iter().rev() {
+            self.remove_rule(i);
+        }
+        let mut i = 0;
+        while i < self.rules.len() {
+            let subsumed = (0..self.rules.len())
+                .any(|j| j != i && Rule::subsumes(&self.rules[j], &self.rules[i]));
+            if subsumed {
+                report.subsumed.push(self.remove_rule(i));
+            } else {
+                i += 1;
+            }
+        }
+        report
+    }
+    /// Inter-reduce the `TRS`: rewrite the RHSs of each [`Rule`] to normal form using the
+    /// other [`Rule`]s, within the limits of `config`. If `reduce_lhs` is `true`, first remove
+    /// each [`Rule`] whose LHS can be rewritten by another remaining [`Rule`]; these are
+    /// returned.
+    ///
+    /// [`Rule`]s are reduced in order, each with the others as already reduced. An RHS whose
+    /// normalization exceeds `config` is left at the last `Term` reached, or unchanged if it
+    /// grows too large; repeated clauses are merged, summing their weights.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, NormalizeConfig, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "F(x_) = G(H(x_)) | G(x_);
+    /// H(y_) = y_;
+    /// F(H(z_)) = A;").expect("parse of F(x_) = G(H(x_)) | G(x_); H(y_) = y_; F(H(z_)) = A;");
+    ///
+    /// let removed = t.interreduce(true, NormalizeConfig::default());
+    ///
+    /// assert_eq!(removed[0].display(&sig), "F(H(z_)) = A");
+    /// assert_eq!(t.display(&sig), "F(x_) = G(x_);\nH(y_) = y_;");
+    /// ```
+    pub fn interreduce(&mut self, reduce_lhs: bool, config: NormalizeConfig) -> Vec<Rule> {
+        let mut removed = vec![];
+        if reduce_lhs {
+            let mut i = 0;
+            while i < self.rules.len() {
+                let rule = self.remove_rule(i);
+                if self.redexes(&rule.lhs).is_empty() {
+                    self.insert_rule(i, rule);
+                    i += 1;
+                } else {
+                    removed.push(rule);
+                }
+            }
+        }
+        for i in 0..self.rules.len() {
+            let mut rule = self.remove_rule(i);
+            let mut rhs: Vec<Term> = vec![];
+            let mut weights: Vec<f64> = vec![];
+            for (c, clause) in rule.rhs.iter().enumerate() {
+                let reduct = match self.normalize(clause, Strategy::Normal, config) {
+                    Ok(Normalization::Normalized(t))
+                    | Ok(Normalization::StepLimit(t))
+                    | Ok(Normalization::Cycle(t))
+                    | Ok(Normalization::Interrupted(t)) => t,
+                    Err(_) => clause.clone(),
+                };
+                // clauses reducing to the same RHS pool their weights
+                let weight = rule.weight(c).unwrap_or(0.0);
+                match rhs.iter().position(|t| *t == reduct) {
+                    Some(j) => weights[j] += weight,
+                    None => {
+                        rhs.push(reduct);
+                        weights.push(weight);
+                    }
+                }
+            }
+            if rule.is_weighted() {
+                rule.weights = Some(weights);
+            }
+            rule.rhs = rhs;
+            self.insert_rule(i, rule);
+        }
+        removed
+    }
+    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; return both
+    /// the [`Rule`] and its index if possible
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    ///
+    /// assert_eq!(t.get(&a).unwrap().1.display(&sig), "A = B");
+    ///
+    /// let c = parse_term(&mut sig, "C").expect("parse of C");
+    ///
+    /// assert_eq!(t.get(&c).unwrap().1.display(&sig), "C = D | E");
+    /// ```
+    pub fn get(&self, lhs: &Term) -> Option<(usize, Rule)> {
+        for idx in self.same_root(lhs) {
+            let rule = &self.rules[idx];
+            if Term::alpha(lhs, &rule.lhs).is_some() {
+                return Some((idx, rule.clone()));
+            }
+        }
+        None
+    }
+    /// Query a `TRS` for a [`Rule`] based on its index; return the [`Rule`] if
+    /// possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// assert_eq!(t.get_idx(0).unwrap().display(&sig), "A = B");
+    ///
+    /// assert_eq!(t.get_idx(1).unwrap().display(&sig), "C = D | E");
+    /// ```
+    pub fn get_idx(&self, idx: usize) -> Option<Rule> {
+        if self.rules.len() > idx {
+            Some(self.rules[idx].clone())
+        } else {
+            None
+        }
+    }
+    /// Query a `TRS` for specific [`Rule`] clauses; return them if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "A(a_ b_) = B(b_ b_);
+    /// D(c_ e_) = D(E F);").expect("parse of A(a_ b_) = B(b_ b_); D(c_ e_) = D(E F);");
+    ///
+    /// let r = parse_rule(&mut sig, "A(x_ y_) = B(y_ y_)").expect("parse of A(x_ y_) = B(y_ y_)");
+    ///
+    /// assert_eq!(t.get_clause(&r).unwrap().1.display(&sig), "A(a_ b_) = B(b_ b_)");
+    ///
+    /// let r = parse_rule(&mut sig, "D(w_ q_) = D(E F)").expect("parse of D(w_ q_) = D(E F)");
+    ///
+    /// assert_eq!(t.get_clause(&r).unwrap().1.display(&sig), "D(c_ e_) = D(E F)");
+    /// ```
+    pub fn get_clause(&self, rule: &Rule) -> Option<(usize, Rule)> {
+        for i in self.same_root(&rule.lhs) {
+            if let Some(sub) = self.rules[i].contains(rule) {
+                return Some((i, rule.substitute(&sub)));
+            }
+        }
+        None
+    }
+    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; delete and
+    /// return the [`Rule`] if it exists.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let a = parse_term(&mut sig, "A").expect("parse of A");
+    /// let c = parse_term(&mut sig, "C").expect("parse of C");
+    ///
+    /// assert_eq!(t.remove(&a).expect("removing A = B").display(&sig), "A = B");
+    ///
+    /// assert_eq!(t.remove(&c).expect("removing C = D").display(&sig), "C = D | E");
+    ///
+    /// assert_eq!(t.display(&sig), "F(x_) = G;");
+    /// ```
+    pub fn remove(&mut self, lhs: &Term) -> Result<Rule, TRSError> {
+        if let Some((idx, _)) = self.get(lhs) {
+            Ok(self.remove_rule(idx))
+        } else {
+            Err(TRSError::NotInTRS)
+        }
+    }
+    /// Query a `TRS` for a [`Rule`] based on its index; delete and return the
+    /// [`Rule`] if it exists.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// assert_eq!(t.remove_idx(0).expect("removing A = B").display(&sig), "A = B");
+    ///
+    /// assert_eq!(t.remove_idx(0).expect("removing C = D").display(&sig), "C = D | E");
+    ///
+    /// assert_eq!(t.display(&sig), "F(x_) = G;");
+    /// ```
+    pub fn remove_idx(&mut self, idx: usize) -> Result<Rule, TRSError> {
+        if self.rules.len() > idx {
+            Ok(self.remove_rule(idx))
+        } else {
+            Err(TRSError::InvalidIndex(idx, self.rules.len()))
+        }
+    }
+    /// Query a `TRS` for a [`Rule`] based on its left-hand-side; delete and
+    /// return the [`Rule`] if it exists.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "C = D").expect("parse of C = D");
+    ///
+    /// assert_eq!(t.remove_clauses(&r).expect("removing C = D").display(&sig), "C = D");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// C = E;
+    /// F(x_) = G;");
+    /// ```
+    pub fn remove_clauses(&mut self, rule: &Rule) -> Result<Rule, TRSError> {
+        self.rules
+            .iter_mut()
+            .filter_map(|r| r.discard(&rule))
+            .next()
+            .ok_or(TRSError::NotInTRS)
+            .and_then(|discarded| {
+                self.rules.retain(|rule| !rule.is_empty());
+                self.reindex();
+                Ok(discarded)
+            })
+    }
+    /// Try to merge a [`Rule`] with an existing [`Rule`] or else insert it at index `i` in the `TRS` if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "D = G").expect("parse of D = G");
+    ///
+    /// t.insert(1, r).expect("inserting D = G at index 1");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// D = G;
+    /// C = D | E;
+    /// F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "D = A").expect("parse of D = A");
+    ///
+    /// t.insert(0, r).expect("inserting D = A with D = G");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// D = G | A;
+    /// C = D | E;
+    /// F(x_) = G;");
+    /// ```
+    pub fn insert(&mut self, idx: usize, rule: Rule) -> Result<&mut TRS, TRSError> {
+        if self.insert_clauses(&rule).is_err() {
+            self.insert_idx(idx, rule)
+        } else {
+            Ok(self)
+        }
+    }
+    /// Insert a [`Rule`] at index `i` in the `TRS` if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "D = G").expect("parse of D = G");
+    ///
+    /// t.insert_idx(1, r).expect("inserting D = G at index 1");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// D = G;
+    /// C = D | E;
+    /// F(x_) = G;");
+    /// ```
+    pub fn insert_idx(&mut self, idx: usize, rule: Rule) -> Result<&mut TRS, TRSError> {
+        if self.is_deterministic && rule.len() > 1 {
+            return Err(TRSError::NondeterministicRule);
+        } else if idx > self.rules.len() {
+            return Err(TRSError::InvalidIndex(idx, self.rules.len()));
+        } else if self.get(&rule.lhs).is_some() {
+            return Err(TRSError::AlreadyInTRS);
+        }
+        self.insert_rule(idx, rule);
+        Ok(self)
+    }
+    /// Inserts a series of [`Rule`]s into the `TRS` at the index provided if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = H;").expect("parse of A = B; C = D | E; F(x_) = H;");
+    ///
+    /// let r0 = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
+    /// let r1 = parse_rule(&mut sig, "B = C").expect("parse of B = C");
+    /// let r2 = parse_rule(&mut sig, "E = F | B").expect("parse of E = F | B");
+    ///
+    /// t.inserts_idx(2, vec![r0, r1, r2]).expect("inserting 3 rules at index 2");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// C = D | E;
+    /// G(y_) = y_;
+    /// B = C;
+    /// E = F | B;
+    /// F(x_) = H;");
+    /// ```
+    pub fn inserts_idx(&mut self, idx: usize, rules: Vec<Rule>) -> Result<&mut TRS, TRSError> {
+        for rule in rules.into_iter().rev() {
+            self.insert_idx(idx, rule)?;
+        }
+        Ok(self)
+    }
+    /// Merge a [`Rule`] with an existing [`Rule`] in the `TRS` if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "A = H").expect("parse of A = H");
+    ///
+    /// let t = t.insert_clauses(&r).expect("inserting A = H with A = B");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B | H;
+    /// C = D | E;
+    /// F(x_) = G;");
+    /// ```
+    pub fn insert_clauses(&mut self, rule: &Rule) -> Result<&mut TRS, TRSError> {
+        if self.is_deterministic {
+            Err(TRSError::NondeterministicRule)
+        } else if let Some((idx, _)) = self.get(&rule.lhs) {
+            self.rules[idx].merge(rule);
+            Ok(self)
+        } else {
+            Err(TRSError::NotInTRS)
+        }
+    }
+    /// Insert new [`Rule`] clauses if possible and move the entire [`Rule`] if
+    /// necessary to be the first in the `TRS`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
+    ///
+    /// t.push(r).expect("inserting G(y_) = y_ at index 0");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "G(y_) = y_;
+    /// A = B;
+    /// C = D | E;
+    /// F(x_) = G;");
+    /// ```
+    pub fn push(&mut self, rule: Rule) -> Result<&mut TRS, TRSError> {
+        let lhs = rule.lhs.clone();
+        self.insert(0, rule)?
+            .get(&lhs)
+            .ok_or(TRSError::NotInTRS)
+            .and_then(move |(idx, _)| self.move_rule(idx, 0))
+    }
+    /// Inserts a series of [`Rule`]s at the beginning of the `TRS` if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = H;").expect("parse of A = B; C = D | E; F(x_) = H;");
+    ///
+    /// let r0 = parse_rule(&mut sig, "G(y_) = y_").expect("parse of G(y_) = y_");
+    /// let r1 = parse_rule(&mut sig, "B = C").expect("parse of B = C");
+    /// let r2 = parse_rule(&mut sig, "E = F | B").expect("parse of E = F | B");
+    ///
+    /// t.pushes(vec![r0, r1, r2]).expect("inserting 3 rules at index 0");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "G(y_) = y_;
+    /// B = C;
+    /// E = F | B;
+    /// A = B;
+    /// C = D | E;
+    /// F(x_) = H;");
+    /// ```
+    pub fn pushes(&mut self, rules: Vec<Rule>) -> Result<&mut TRS, TRSError> {
+        for rule in rules.into_iter().rev() {
+            self.push(rule)?;
+        }
+        Ok(self)
+    }
+    /// Move a [`Rule`] from index `i` to `j` if possible.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;
+    /// H = I;").expect("parse of A = B; C = D | E; F(x_) = G; H = I;");
+    ///
+    /// t.move_rule(0, 2).expect("moving rule from index 0 to index 2");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "C = D | E;
+    /// F(x_) = G;
+    /// A = B;
+    /// H = I;");
+    /// ```
+    pub fn move_rule(&mut self, i: usize, j: usize) -> Result<&mut TRS, TRSError> {
+        if i != j {
+            let rule = self.remove_idx(i)?;
+            self.insert(j, rule)
+        } else {
+            Ok(self)
+        }
+    }
+    /// Remove some [`Rule`] clauses while also inserting others if possible.
+    ///
+    /// The index `i` is used only in the case that the new clauses cannot be
+    /// added to an existing [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, Term, parse_term, Rule, parse_rule};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// C = D | E;
+    /// F(x_) = G;").expect("parse of A = B; C = D | E; F(x_) = G;");
+    ///
+    /// let r = parse_rule(&mut sig, "C = D").expect("parse of C = D");
+    /// let r_new = parse_rule(&mut sig, "C = A").expect("parse of C = A");
+    ///
+    /// t.replace(0, &r, r_new).expect("replaceing C = D with C = A");
+    ///
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// C = E | A;
+    /// F(x_) = G;");
+    /// ```
+    pub fn replace(&mut self, idx: usize, rule1: &Rule, rule2: Rule) -> Result<&mut TRS, TRSError> {
+        self.remove_clauses(rule1)?;
+        self.insert(idx, rule2)
+    }
+    /// Add the [`Rule`]s of `other` to the end of the `TRS`, resolving [`Rule`]s whose lhs is
+    /// alpha-equivalent to that of an existing [`Rule`] according to `policy`. Return the
+    /// indices of each such pair of [`Rule`]s, in the `TRS` and in `other`. If an error occurs,
+    /// the `TRS` is left unchanged.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, TRSError, UnionPolicy, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let mut t = parse_trs(&mut sig,
+    /// "A = B;
+    /// F(x_) = G(x_);").expect("parse of A = B; F(x_) = G(x_);");
+    /// let other = parse_trs(&mut sig,
+    /// "C = D;
+    /// F(y_) = y_;").expect("parse of C = D; F(y_) = y_;");
+    ///
+    /// assert_eq!(t.union(&other, UnionPolicy::Error), Err(TRSError::AlreadyInTRS));
+    ///
+    /// let mut left = t.clone();
+    /// assert_eq!(left.union(&other, UnionPolicy::PreferLeft), Ok(vec![(1, 1)]));
+    /// assert_eq!(left.display(&sig),
+    /// "A = B;
+    /// F(x_) = G(x_);
+    /// C = D;");
+    ///
+    /// assert_eq!(t.union(&other, UnionPolicy::MergeClauses), Ok(vec![(1, 1)]));
+    /// assert_eq!(t.display(&sig),
+    /// "A = B;
+    /// F(x_) = G(x_) | x_;
+    /// C = D;");
+    /// ```
+    pub fn union(
+        &mut self,
+        other: &TRS,
+        policy: UnionPolicy,
+    ) -> Result<Vec<(usize, usize)>, TRSError> {
+        let mut rules = self.rules.clone();
+        let mut overlaps = vec![];
+        for (j, rule) in other.rules.iter().enumerate() {
+            let existing = rules
+                .iter()
+                .position(|r| Term::alpha(&rule.lhs, &r.lhs).is_some());
+            match existing {
+                Some(i) => {
+                    overlaps.push((i, j));
+                    match policy {
+                        UnionPolicy::MergeClauses => rules[i].merge(rule),
+                        UnionPolicy::PreferLeft => continue,
+                        UnionPolicy::Error => return Err(TRSError::AlreadyInTRS),
+                    }
+                    if self.is_deterministic && rules[i].len() > 1 {
+                        return Err(TRSError::NondeterministicRule);
+                    }
+                }
+                None if self.is_deterministic && rule.len() > 1 => {
+                    return Err(TRSError::NondeterministicRule);
+                }
+                None => rules.push(rule.clone()),
+            }
+        }
+        self.rules = rules;
+        self.reindex();
+        Ok(overlaps)
+    }
+    // Rebuild the index used to find the rules which might match a term.
+    pub(crate) fn reindex(&mut self) {
+        self.index = TrsIndex::new(&self.rules);
+        self.semi_equational = SemiEquational::default();
+    }
+    // Insert `rule` at `idx`, keeping the index in sync.
+    pub(crate) fn insert_rule(&mut self, idx: usize, rule: Rule) {
+        self.rules.insert(idx, rule);
+        self.index.insert(idx, &self.rules[idx]);
+        self.semi_equational = SemiEquational::default();
+    }
+    // Remove the rule at `idx`, keeping the index in sync.
+    pub(crate) fn remove_rule(&mut self, idx: usize) -> Rule {
+        let rule = self.rules.remove(idx);
+        self.index.remove(idx);
+        self.semi_equational = SemiEquational::default();
+        rule
+    }
+}
+
+/// Mutable access to the [`Rule`]s of a [`TRS`], which rebuilds the index of the [`TRS`] when
+/// dropped. See [`TRS::rules_mut`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`TRS`]: struct.TRS.html
+/// [`TRS::rules_mut`]: struct.TRS.html#method.rules_mut
+#[derive(Debug)]
+pub struct RulesMut<'a> {
+    trs: &'a mut TRS,
+}
+impl<'a> Deref for RulesMut<'a> {
+    type Target = Vec<Rule>;
+    fn deref(&self) -> &Vec<Rule> {
+        &self.trs.rules
+    }
+}
+impl<'a> DerefMut for RulesMut<'a> {
+    fn deref_mut(&mut self) -> &mut Vec<Rule> {
+        &mut self.trs.rules
+    }
+}
+impl<'a> Drop for RulesMut<'a> {
+    fn drop(&mut self) {
+        self.trs.reindex();
+    }
+}
+
+/// A pair of [`Term`]s arising from two overlapping [`Rule`]s: the `inner` [`Rule`] rewrites the
+/// `peak` at `place`, while the `outer` [`Rule`] rewrites it at the root.
+///
+/// See [`TRS::critical_pairs`] for more information.
+///
+/// [`Term`]: enum.Term.html
+/// [`Rule`]: struct.Rule.html
+/// [`TRS::critical_pairs`]: struct.TRS.html#method.critical_pairs
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CriticalPair {
+    /// The most general `Term` rewritten by both [`Rule`]s.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub peak: Term,
+    /// The `peak` rewritten by the `outer` [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub left: Term,
+    /// The `peak` rewritten by the `inner` [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub right: Term,
+    /// The index of the [`Rule`] applied at the root of the `peak`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub outer: usize,
+    /// The index of the [`Rule`] applied at `place`.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub inner: usize,
+    /// The [`Place`] in the `peak` rewritten by the `inner` [`Rule`].
+    ///
+    /// [`Place`]: type.Place.html
+    /// [`Rule`]: struct.Rule.html
+    pub place: Place,
+}
+impl CriticalPair {
+    /// Is the `CriticalPair` trivially joinable, i.e. are its two sides identical?
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig,
+    /// "F(G(x_)) = A;
+    /// G(B) = C;").expect("parse of F(G(x_)) = A; G(B) = C;");
+    ///
+    /// assert!(!t.critical_pairs(&mut sig)[0].is_trivial());
+    /// ```
+    pub fn is_trivial(&self) -> bool {
+        self.left == self.right
+    }
+    // Overlap the LHS of `inner` with each non-variable subterm of the LHS of `outer`.
+    pub(crate) fn overlaps(
+        sig: &mut Signature,
+        outer: &Rule,
+        outer_idx: usize,
+        inner: &Rule,
+        inner_idx: usize,
+    ) -> Vec<CriticalPair> {
+        let outer_vars = outer.variables();
+        let inner = if inner.variables().iter().any(|v| outer_vars.contains(v)) {
+            inner.rename_variables(sig)
+        } else {
+            inner.clone()
+        };
+        let mut pairs = vec![];
+        for (subterm, place) in outer.lhs.subterms() {
+            if let Term::Variable(_) = *subterm {
+                continue;
+            }
+            if let Some(sub) = Term::unify(vec![(subterm, &inner.lhs)]) {
+                let peak = outer.lhs.substitute_fully(&sub);
+                for (oi, o_rhs) in outer.rhs.iter().enumerate() {
+                    for (ii, i_rhs) in inner.rhs.iter().enumerate() {
+                        if place.is_empty() && outer_idx == inner_idx && oi == ii {
+                            continue;
+                        }
+                        let right = outer
+                            .lhs
+                            .replace(&place, i_rhs.clone())
+                            .expect("place of subterm");
+                        pairs.push(CriticalPair {
+                            peak: peak.clone(),
+                            left: o_rhs.substitute_fully(&sub),
+                            right: right.substitute_fully(&sub),
+                            outer: outer_idx,
+                            inner: inner_idx,
+                            place: place.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// Evidence that a [`TRS`] does not terminate: a derivation `s ->+ C[sσ]` for some [`Context`]
+/// `C` and substitution `σ`, which can be repeated forever.
+///
+/// See [`TRS::find_loop`] for more information.
+///
+/// [`TRS`]: struct.TRS.html
+/// [`Context`]: enum.Context.html
+/// [`TRS::find_loop`]: struct.TRS.html#method.find_loop
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NontermCertificate {
+    /// The derivation from `s` to `C[sσ]`.
+    pub derivation: Vec<Term>,
+    /// The [`Context`] `C`, which has a single [`Hole`].
+    ///
+    /// [`Context`]: enum.Context.html
+    /// [`Hole`]: enum.Context.html#variant.Hole
+    pub context: Context,
+    /// The [`Substitution`] `σ`.
+    ///
+    /// [`Substitution`]: struct.Substitution.html
+    pub substitution: Substitution,
+}
+impl NontermCertificate {
+    /// Check the `NontermCertificate` against `trs`: each step of the derivation must be a
+    /// single rewrite by `trs`, and the derivation must end in `C[sσ]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "F(x_) = G(F(x_));").expect("parse of F(x_) = G(F(x_));");
+    /// let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+    /// let mut certificate = t.find_loop(&term, 10).expect("loop");
+    ///
+    /// assert!(certificate.check(&t));
+    ///
+    /// let u = parse_trs(&mut sig, "F(x_) = G(x_);").expect("parse of F(x_) = G(x_);");
+    ///
+    /// assert!(!certificate.check(&u));
+    ///
+    /// certificate.context = certificate.context.args()[0].clone();
+    ///
+    /// assert!(!certificate.check(&t));
+    /// ```
+    pub fn check(&self, trs: &TRS) -> bool {
+        let steps_valid = self.derivation.len() > 1
+            && self.derivation.windows(2).all(|w| {
+                trs.one_step_reducts(&w[0], trs.condition_config.max_depth)
+                    .contains(&w[1])
+            });
+        let holes = self.context.holes();
+        if !steps_valid || holes.len() != 1 {
+            return false;
+        }
+        let instance = self.substitution.apply(&self.derivation[0]);
+        let last = self
+            .context
+            .replace(&holes[0], Context::from(instance))
+            .and_then(|c| c.to_term().ok());
+        last.as_ref() == self.derivation.last()
+    }
+    /// Serialize a `NontermCertificate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "F(x_) = G(F(x_));").expect("parse of F(x_) = G(F(x_));");
+    /// let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+    ///
+    /// assert_eq!(t.find_loop(&term, 10).expect("loop").display(&sig), "F(A) -> G(F(A)); C = G([!]); {}");
+    /// ```
+    pub fn display(&self, sig: &Signature) -> String {
+        let derivation = self.derivation.iter().map(|t| t.display(sig)).join(" -> ");
+        let variables = self
+            .derivation
+            .first()
+            .map(Term::variables)
+            .unwrap_or_default();
+        format!(
+            "{}; C = {}; {}",
+            derivation,
+            self.context.display(sig),
+            self.substitution.restrict(&variables).display(sig)
+        )
+    }
+}
+
+/// A way of choosing which rewrites to perform in a single step.
+///
+/// [`Strategy`] implements the built-in strategies; implement `RewriteStrategy` to use custom
+/// ones with [`TRS::rewrite_with`] and [`TRS::normalize`].
+///
+/// [`Strategy`]: enum.Strategy.html
+/// [`TRS::rewrite_with`]: struct.TRS.html#method.rewrite_with
+/// [`TRS::normalize`]: struct.TRS.html#method.normalize
+pub trait RewriteStrategy {
+    /// The results of a single rewrite step from `term` in `trs`, or `None` if the strategy
+    /// performs no rewrites.
+    fn rewrite(&self, trs: &TRS, term: &Term) -> Option<Vec<Term>>;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum Strategy {
+    /// Perform only the leftmost-outermost rewrite, using the first matching rule
+    Normal,
+    /// Perform only the leftmost-innermost rewrite
+    Eager,
+    /// Perform only the leftmost-outermost rewrite, using every matching rule
+    Outermost,
+    /// Perform every innermost rewrite simultaneously, using the first matching rule
+    ParallelInnermost,
+    /// Perform all possible rewrites
+    All,
+}
+impl RewriteStrategy for Strategy {
+    fn rewrite(&self, trs: &TRS, term: &Term) -> Option<Vec<Term>> {
+        let rewrites: Vec<Term> = trs.rewrites(term, *self).collect();
+        match (*self, term) {
+            (_, Term::Variable(_)) => None,
+            (Strategy::All, _) => Some(rewrites),
+            _ if rewrites.is_empty() => None,
+            _ => Some(rewrites),
+        }
+    }
+}
+
+/// A single rewrite step, recording the [`Rule`], clause, and [`Place`] which produced it.
+///
+/// See [`TRS::rewrites_with_provenance`] for more information.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Place`]: type.Place.html
+/// [`TRS::rewrites_with_provenance`]: struct.TRS.html#method.rewrites_with_provenance
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rewrite {
+    /// The rewritten `Term`.
+    pub term: Term,
+    /// The index of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub rule: usize,
+    /// The index of the RHS of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub clause: usize,
+    /// The [`Place`] of the rewritten subterm.
+    ///
+    /// [`Place`]: type.Place.html
+    pub place: Place,
+}
+
+/// The [`Position`]s narrowed by [`TRS::narrow`].
+///
+/// [`Position`]: struct.Position.html
+/// [`TRS::narrow`]: struct.TRS.html#method.narrow
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NarrowingStrategy {
+    /// Narrow at every non-variable position
+    All,
+    /// Narrow only at narrowable positions with no narrowable position below them
+    Innermost,
+    /// Narrow only at narrowable positions with no narrowable position above them
+    Outermost,
+}
+
+/// A single narrowing step, recording the unifier, [`Rule`], clause, and [`Position`] which
+/// produced it.
+///
+/// See [`TRS::narrow`] for more information.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`Position`]: struct.Position.html
+/// [`TRS::narrow`]: struct.TRS.html#method.narrow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Narrowing {
+    /// The narrowed `Term`.
+    pub term: Term,
+    /// The unifier, restricted to the [`Variable`]s of the original `Term`.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub substitution: Substitution,
+    /// The index of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub rule: usize,
+    /// The index of the RHS of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub clause: usize,
+    /// The [`Position`] of the narrowed subterm.
+    ///
+    /// [`Position`]: struct.Position.html
+    pub position: Position,
+}
+
+/// A sampled derivation, with the probability of having sampled it.
+///
+/// See [`TRS::sample_derivation`] for more information.
+///
+/// [`TRS::sample_derivation`]: struct.TRS.html#method.sample_derivation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Derivation {
+    /// The `Term`s visited, starting with the initial `Term`.
+    pub trajectory: Vec<Term>,
+    /// The natural log of the probability of the steps taken.
+    pub log_probability: f64,
+}
+
+/// A graph of `Term`s connected by single rewrite steps.
+///
+/// See [`TRS::reachable`] for more information.
+///
+/// [`TRS::reachable`]: struct.TRS.html#method.reachable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteGraph {
+    /// The `Term`s in the graph, no two of which are alpha-equivalent.
+    pub nodes: Vec<Term>,
+    /// The rewrite steps between the `Term`s in the graph.
+    pub edges: Vec<RewriteEdge>,
+}
+impl RewriteGraph {
+    /// The index of the node alpha-equivalent to `term`, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "F(x_) = G(x_);").expect("parse of F(x_) = G(x_);");
+    /// let term = parse_term(&mut sig, "F(x_)").expect("parse of F(x_)");
+    /// let graph = t.reachable(&term, 1);
+    ///
+    /// let term = parse_term(&mut sig, "G(y_)").expect("parse of G(y_)");
+    /// assert_eq!(graph.find(&term), Some(1));
+    ///
+    /// let term = parse_term(&mut sig, "G(A)").expect("parse of G(A)");
+    /// assert_eq!(graph.find(&term), None);
+    /// ```
+    pub fn find(&self, term: &Term) -> Option<usize> {
+        let size = term.size();
+        self.nodes
+            .iter()
+            .position(|node| node.size() == size && Term::alpha(node, term).is_some())
+    }
+    /// Serialize the `RewriteGraph` in the [DOT] language. Nodes are labeled with their
+    /// `Term`s and edges with the clause of the [`Rule`] in `trs` used for the step.
+    ///
+    /// [DOT]: https://graphviz.gitlab.io/_pages/doc/info/lang.html
+    /// [`Rule`]: struct.Rule.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TRS, parse_trs, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_trs(&mut sig, "A = B | C; B = C;").expect("parse of A = B | C; B = C;");
+    /// let term = parse_term(&mut sig, "A").expect("parse of A");
+    /// let graph = t.reachable(&term, 2);
+    ///
+    /// assert_eq!(graph.to_dot(&sig, &t),
+    /// "digraph {
+    ///     0 [label=\"A\"];
+    ///     1 [label=\"B\"];
+    ///     2 [label=\"C\"];
+    ///     0 -> 1 [label=\"A = B\"];
+    ///     0 -> 2 [label=\"A = C\"];
+    ///     1 -> 2 [label=\"B = C\"];
+    /// }
+    /// ");
+    /// ```
+    pub fn to_dot(&self, sig: &Signature, trs: &TRS) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (i, node) in self.nodes.iter().enumerate() {
+            dot.push_str(&format!(
+                "    {} [label=\"{}\"];\n",
+                i,
+                dot_escape(&node.display(sig))
+            ));
+        }
+        for edge in &self.edges {
+            let rule = &trs.rules[edge.rule];
+            let label = format!(
+                "{} = {}",
+                rule.lhs.display(sig),
+                rule.rhs[edge.clause].display(sig)
+            );
+            dot.push_str(&format!(
+                "    {} -> {} [label=\"{}\"];\n",
+                edge.source,
+                edge.target,
+                dot_escape(&label)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+    /// The indices of the nodes reachable from node `i` in a single step, in order of first
+    /// discovery.
+    pub fn successors(&self, i: usize) -> Vec<usize> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.source == i)
+            .map(|edge| edge.target)
+            .unique()
+            .collect()
+    }
+}
+
+// The derivation ending in `terms[i]`, following `parents` back to the root.
+fn derivation(terms: &[Term], parents: &[Option<usize>], i: usize) -> Vec<Term> {
+    let mut derivation = vec![];
+    let mut ancestor = Some(i);
+    while let Some(j) = ancestor {
+        derivation.push(terms[j].clone());
+        ancestor = parents[j];
+    }
+    derivation.reverse();
+    derivation
+}
+
+// Narrow `term` at `position` with each clause of `rule`, restricting unifiers to `vars`.
+fn narrowings_at(
+    term: &Term,
+    position: &Position,
+    rule: &Rule,
+    idx: usize,
+    vars: &[Variable],
+) -> Vec<Narrowing> {
+    if rule.is_conditional() {
+        return vec![];
+    }
+    let subterm = term.at(position).expect("position of subterm");
+    match Term::mgu(&rule.lhs, subterm) {
+        Some(sub) => rule
+            .rhs
+            .iter()
+            .enumerate()
+            .map(|(clause, rhs)| Narrowing {
+                term: sub.apply(
+                    &term
+                        .replace_at(position, rhs.clone())
+                        .expect("position of subterm"),
+                ),
+                substitution: sub.restrict(vars),
+                rule: idx,
+                clause,
+                position: position.clone(),
+            })
+            .collect(),
+        None => vec![],
+    }
+}
+// Escape `s` for use in a double-quoted DOT string.
+pub(crate) fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// A single rewrite step in a [`RewriteGraph`].
+///
+/// [`RewriteGraph`]: struct.RewriteGraph.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RewriteEdge {
+    /// The index of the rewritten `Term`.
+    pub source: usize,
+    /// The index of the resulting `Term`.
+    pub target: usize,
+    /// The index of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub rule: usize,
+    /// The index of the RHS of the [`Rule`] used.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub clause: usize,
+    /// The [`Place`] of the rewritten subterm.
+    ///
+    /// [`Place`]: type.Place.html
+    pub place: Place,
+}
+
+/// A lazy iterator over the [`Rewrite`]s of a `Term`.
+///
+/// See [`TRS::rewrites_with_provenance`] for more information.
+///
+/// [`Rewrite`]: struct.Rewrite.html
+/// [`TRS::rewrites_with_provenance`]: struct.TRS.html#method.rewrites_with_provenance
+pub struct RewritesWithProvenance<'a>(Box<dyn Iterator<Item = Rewrite> + 'a>);
+impl<'a> Iterator for RewritesWithProvenance<'a> {
+    type Item = Rewrite;
+    fn next(&mut self) -> Option<Rewrite> {
+        self.0.next()
+    }
+}
+
+/// A lazy iterator over the [`Narrowing`]s of a `Term`.
+///
+/// See [`TRS::narrow`] for more information.
+///
+/// [`Narrowing`]: struct.Narrowing.html
+/// [`TRS::narrow`]: struct.TRS.html#method.narrow
+pub struct Narrowings<'a>(Box<dyn Iterator<Item = Narrowing> + 'a>);
+impl<'a> Iterator for Narrowings<'a> {
+    type Item = Narrowing;
+    fn next(&mut self) -> Option<Narrowing> {
+        self.0.next()
+    }
+}
+
+/// A lazy iterator over the rewrites of a `Term`.
+///
+/// See [`TRS::rewrites`] for more information.
+///
+/// [`TRS::rewrites`]: struct.TRS.html#method.rewrites
+pub struct Rewrites<'a>(Box<dyn Iterator<Item = Term> + 'a>);
+impl<'a> Iterator for Rewrites<'a> {
+    type Item = Term;
+    fn next(&mut self) -> Option<Term> {
+        self.0.next()
+    }
+}
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Strategy::Normal => write!(f, "Normal"),
+            Strategy::Eager => write!(f, "Eager"),
+            Strategy::Outermost => write!(f, "Outermost"),
+            Strategy::ParallelInnermost => write!(f, "ParallelInnermost"),
+            Strategy::All => write!(f, "All"),
+        }
+    }
+}
+
+/// How the conditions of conditional [`Rule`]s are checked.
+///
+/// See [`TRS::set_condition_config`] for more information.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`TRS::set_condition_config`]: struct.TRS.html#method.set_condition_config
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConditionMode {
+    /// `c = d` holds if `c` rewrites to `d`
+    Oriented,
+    /// `c = d` holds if `c` and `d` rewrite to a common `Term`
+    Join,
+    /// `c = d` holds if `c` and `d` are convertible, rewriting in either direction
+    SemiEquational,
+}
+
+/// Evaluation settings for the conditions of conditional [`Rule`]s.
+///
+/// See [`TRS::set_condition_config`] for more information.
+///
+/// [`Rule`]: struct.Rule.html
+/// [`TRS::set_condition_config`]: struct.TRS.html#method.set_condition_config
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConditionConfig {
+    /// How each condition is checked.
+    pub mode: ConditionMode,
+    /// The largest number of rewrite steps taken from each side of a condition.
+    pub max_steps: usize,
+    /// The deepest nesting of conditional [`Rule`]s used while checking a condition.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub max_depth: usize,
+}
+impl Default for ConditionConfig {
+    /// Check oriented conditions within 100 steps, nesting at most 5 deep.
+    fn default() -> ConditionConfig {
+        ConditionConfig {
+            mode: ConditionMode::Oriented,
+            max_steps: 100,
+            max_depth: 5,
+        }
+    }
+}
+
+// The cached `TRS` for evaluating semi-equational conditions: see `TRS::semi_equational`. Like
+// the index, it is derived from the rules, so it is ignored when comparing and hashing.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SemiEquational(OnceLock<Box<TRS>>);
+impl PartialEq for SemiEquational {
+    fn eq(&self, _: &SemiEquational) -> bool {
+        true
+    }
+}
+impl Eq for SemiEquational {}
+impl Hash for SemiEquational {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+/// Limits on [`TRS::normalize`].
+///
+/// [`TRS::normalize`]: struct.TRS.html#method.normalize
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NormalizeConfig {
+    /// The largest number of steps to take, or `None` to take as many as needed.
+    pub max_steps: Option<usize>,
+    /// The largest number of subterms a rewritten `Term` may have, or `None` for no limit.
+    pub max_term_size: Option<usize>,
+    /// The time after which to stop, or `None` for no limit.
+    pub deadline: Option<Instant>,
+    /// Whether to stop upon revisiting a `Term`.
+    pub detect_cycles: bool,
+}
+impl Default for NormalizeConfig {
+    /// Take at most 1000 steps of any size with no deadline, detecting cycles.
+    fn default() -> NormalizeConfig {
+        NormalizeConfig {
+            max_steps: Some(1000),
+            max_term_size: None,
+            deadline: None,
+            detect_cycles: true,
+        }
+    }
+}
+
+/// Limits on exhaustive rewriting searches such as [`TRS::joinable`].
+///
+/// [`TRS::joinable`]: struct.TRS.html#method.joinable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// The largest number of steps to take from the starting `Term`.
+    pub max_steps: usize,
+    /// The largest number of `Term`s to visit, or `None` for no limit.
+    pub max_terms: Option<usize>,
+    /// The largest number of subterms a visited `Term` may have, or `None` for no limit.
+    pub max_term_size: Option<usize>,
+}
+impl Default for SearchLimits {
+    /// Take at most 100 steps, visiting at most 10,000 `Term`s of any size.
+    fn default() -> SearchLimits {
+        SearchLimits {
+            max_steps: 100,
+            max_terms: Some(10_000),
+            max_term_size: None,
+        }
+    }
+}
+
+/// A common reduct of two `Term`s, as found by [`TRS::joinable`].
+///
+/// [`TRS::joinable`]: struct.TRS.html#method.joinable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Join {
+    /// The `Term` to which both `Term`s rewrite.
+    pub reduct: Term,
+    /// The derivation from the first `Term` to `reduct`.
+    pub left: Vec<Term>,
+    /// The derivation from the second `Term` to `reduct`.
+    pub right: Vec<Term>,
+}
+
+/// The redundant clauses and [`Rule`]s removed by [`TRS::simplify`].
+///
+/// [`Rule`]: struct.Rule.html
+/// [`TRS::simplify`]: struct.TRS.html#method.simplify
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Simplification {
+    /// Clauses whose RHS is identical to the LHS.
+    pub trivial: Vec<Rule>,
+    /// Clauses alpha-equivalent to a clause kept earlier.
+    pub duplicates: Vec<Rule>,
+    /// [`Rule`]s subsumed by another [`Rule`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    pub subsumed: Vec<Rule>,
+}
+impl Simplification {
+    /// Did [`TRS::simplify`] remove nothing?
+    ///
+    /// [`TRS::simplify`]: struct.TRS.html#method.simplify
+    pub fn is_empty(&self) -> bool {
+        self.trivial.is_empty() && self.duplicates.is_empty() && self.subsumed.is_empty()
+    }
+}
+
+/// The outcome of [`TRS::normalize`].
+///
+/// [`TRS::normalize`]: struct.TRS.html#method.normalize
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Normalization {
+    /// A normal form was reached.
+    Normalized(Term),
+    /// The step limit was reached; holds the last `Term` visited.
+    StepLimit(Term),
+    /// A `Term` was visited twice; holds the repeated `Term`.
+    Cycle(Term),
+    /// Normalization was cancelled or ran past its deadline; holds the last `Term` visited.
+    Interrupted(Term),
+}
+
+/// Normal forms found by [`TRS::normalize_cached`], keyed by the [`DeBruijnTerm`]s of the
+/// `Term`s they were reached from, so that alpha-equivalent `Term`s share an entry.
+///
+/// Entries are only valid for the [`TRS`] and strategy that produced them: [`clear`] the cache
+/// after changing either.
+///
+/// [`TRS::normalize_cached`]: struct.TRS.html#method.normalize_cached
+/// [`DeBruijnTerm`]: enum.DeBruijnTerm.html
+/// [`TRS`]: struct.TRS.html
+/// [`clear`]: #method.clear
+#[derive(Debug, Clone, Default)]
+pub struct NormalFormCache {
+    entries: HashMap<DeBruijnTerm, CacheEntry>,
+}
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    // the Variables of the cached Term, in order of first occurrence
+    variables: Vec<Variable>,
+    normal_form: Term,
+    // the number of steps taken to reach the normal form
+    steps: usize,
+    // the size of the largest Term rewritten to along the way
+    largest: usize,
+}
+impl CacheEntry {
+    fn normal(term: &Term) -> CacheEntry {
+        CacheEntry {
+            variables: term.variables(),
+            normal_form: term.clone(),
+            steps: 0,
+            largest: 0,
+        }
+    }
+}
+impl NormalFormCache {
+    /// An empty `NormalFormCache`.
+    pub fn new() -> NormalFormCache {
+        NormalFormCache::default()
+    }
+    /// The number of `Term`s with a cached normal form.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Is the `NormalFormCache` empty?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Forget every cached normal form, as when the [`TRS`] has changed.
+    ///
+    /// [`TRS`]: struct.TRS.html
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+    /// The cached normal form of `term`, if any, renamed to use the [`Variable`]s of `term`.
+    ///
+    /// [`Variable`]: struct.Variable.html
+    pub fn get(&self, term: &Term) -> Option<Term> {
+        self.lookup(term, None, None).map(|entry| entry.normal_form)
+    }
+    // The entry for `term` if reached within the limits, renamed to use the Variables of `term`.
+    fn lookup(
+        &self,
+        term: &Term,
+        max_steps: Option<usize>,
+        max_term_size: Option<usize>,
+    ) -> Option<CacheEntry> {
+        let entry = self.entries.get(&term.to_debruijn())?;
+        if max_steps.is_some_and(|max| entry.steps > max)
+            || max_term_size.is_some_and(|max| entry.largest > max)
+        {
+            return None;
+        }
+        let variables = term.variables();
+        let renaming: Vec<_> = variables.iter().cloned().map(Term::Variable).collect();
+        let sub: HashMap<_, _> = entry.variables.iter().zip(&renaming).collect();
+        Some(CacheEntry {
+            normal_form: entry.normal_form.substitute(&sub),
+            variables,
+            ..*entry
+        })
+    }
+    // Record that each Term of `path` rewrites to the next, and the last has the given entry.
+    fn record(&mut self, mut path: Vec<Term>, last: &CacheEntry) {
+        let (mut steps, mut largest) = (last.steps, last.largest);
+        let mut size = path.last().map_or(0, Term::size);
+        while let Some(term) = path.pop() {
+            let entry = CacheEntry {
+                variables: term.variables(),
+                normal_form: last.normal_form.clone(),
+                steps,
+                largest,
+            };
+            largest = largest.max(size);
+            steps += 1;
+            size = path.last().map_or(0, Term::size);
+            self.entries.entry(term.to_debruijn()).or_insert(entry);
+        }
+    }
+}
+
+/// How [`TRS::make_deterministic_with`] chooses the clause to keep from each [`Rule`].
+///
+/// [`TRS::make_deterministic_with`]: struct.TRS.html#method.make_deterministic_with
+/// [`Rule`]: struct.Rule.html
+pub enum ClausePolicy<'a> {
+    /// Keep the first clause.
+    First,
+    /// Keep a clause chosen uniformly at random.
+    Random(&'a mut dyn RngCore),
+    /// Keep the clause at the index returned for the [`Rule`]. Only called for [`Rule`]s with
+    /// several clauses.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    Choose(&'a mut dyn FnMut(&Rule) -> usize),
+}
+
+/// How [`TRS::union`] resolves a [`Rule`] whose lhs is alpha-equivalent to that of an existing
+/// [`Rule`].
+///
+/// [`TRS::union`]: struct.TRS.html#method.union
+/// [`Rule`]: struct.Rule.html
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnionPolicy {
+    /// Add its clauses to the existing [`Rule`], as in [`TRS::insert_clauses`].
+    ///
+    /// [`Rule`]: struct.Rule.html
+    /// [`TRS::insert_clauses`]: struct.TRS.html#method.insert_clauses
+    MergeClauses,
+    /// Keep the existing [`Rule`] and discard the new one.
+    ///
+    /// [`Rule`]: struct.Rule.html
+    PreferLeft,
+    /// Fail with [`TRSError::AlreadyInTRS`].
+    ///
+    /// [`TRSError::AlreadyInTRS`]: enum.TRSError.html#variant.AlreadyInTRS
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The error type for guarded rewriting.
+pub enum RewriteError {
+    /// Returned when rewriting produces a `Term` larger than allowed; holds that `Term`.
+    ///
+    /// See [`TRS::rewrite_bounded`] and [`TRS::normalize`] for more information.
+    ///
+    /// [`TRS::rewrite_bounded`]: struct.TRS.html#method.rewrite_bounded
+    /// [`TRS::normalize`]: struct.TRS.html#method.normalize
+    TermTooLarge(Term),
+}
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RewriteError::TermTooLarge(ref term) => {
+                write!(f, "rewritten term of size {} is too large", term.size())
+            }
+        }
+    }
+}
+impl ::std::error::Error for RewriteError {
+    fn description(&self) -> &'static str {
+        "rewrite error"
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The error type for [`TRS`] manipulations.
+///
+/// [`TRS`]: struct.TRS.html
+pub enum TRSError {
+    /// Returned when requesting to edit a rule that is not in the TRS.
+    ///
+    /// See [`TRS::get`] for more information.
+    ///
+    /// [`TRS::get`]: struct.TRS.html#method.get
+    NotInTRS,
+    /// Returned when attempting to insert a rule into a TRS that already exists.
+    ///
+    /// See [`TRS::insert`] for more information.
+    ///
+    /// [`TRS::insert`]: struct.TRS.html#method.insert
+    AlreadyInTRS,
+    /// Returned when attempting to insert a rule with multiple RHSs into a deterministic TRS.
+    ///
+    /// See [`TRS::insert`] and [`TRS::make_deterministic`] for more information.
+    ///
+    /// [`TRS::insert`]: struct.TRS.html#method.insert
+    /// [`TRS::make_deterministic`]: struct.TRS.html#method.make_deterministic
+    NondeterministicRule,
+    /// Returned when requesting the rule at an index that is out of the range of indicies for the TRS.
+    ///
+    /// See [`TRS::get_idx`] for more information.
+    ///
+    /// [`TRS::get_idx`]: struct.TRS.html#method.get_idx
+    InvalidIndex(usize, usize),
+}
+impl fmt::Display for TRSError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TRSError::NotInTRS => write!(f, "query rule not in TRS"),
+            TRSError::AlreadyInTRS => write!(f, "pre-existing rule with same LHS in TRS"),
+            TRSError::NondeterministicRule => {
+                write!(f, "proposed rule is nondeterministic in deterministic TRS")
+            }
+            TRSError::InvalidIndex(length, max_length) => {
+                write!(f, "index {} greater than max index {}", length, max_length)
+            }
+        }
+    }
+}
+impl ::std::error::Error for TRSError {
+    fn description(&self) -> &'static str {
+        "TRS error"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::Signature;
     use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn new_test() {
+        let mut sig = Signature::default();
+
+        let r0 = parse_rule(&mut sig, "A = B").expect("parse of A = B");
+        let r1 = parse_rule(&mut sig, "C(x_) = x_").expect("parse of C(x_) = x_");
+        let r2 = parse_rule(&mut sig, "D(y_) = D(E)").expect("parse of D(y_) = D(E)");
+        let new_trs = TRS::new(vec![r0, r1, r2]);
+
+        assert_eq!(new_trs.display(&sig), "A = B;\nC(x_) = x_;\nD(y_) = D(E);");
+    }
+
+    #[test]
+    fn make_deterministic_test() {
+        let mut sig = Signature::default();
+        let mut t = parse_trs(
+            &mut sig,
+            "A = B | C;
+            D = E;",
+        )
+        .expect("parse of A = B | C; D = E");
+
+        let mut r = rand::thread_rng();
+
+        let str_before = t.display(&sig);
+
+        assert!(t.make_deterministic(&mut r));
+
+        assert_ne!(t.display(&sig), str_before);
+
+        let str_before = t.display(&sig);
+        let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
+
+        if t.insert_idx(1, r.clone()).is_err() {
+            assert!(true);
+        }
+
+        assert_eq!(str_before, t.display(&sig));
+
+        assert!((t.display(&sig) == "A = B;\nD = E;") || (t.display(&sig) == "A = C;\nD = E;"));
+    }
+
+    #[test]
+    fn make_deterministic_with_test() {
+        let mut sig = Signature::default();
+        let mut original = parse_trs(&mut sig, "A = B | C; D = E; F = G | H | I;")
+            .expect("parse of A = B | C; D = E; F = G | H | I;");
+        let x = parse_term(&mut sig, "X").expect("parse of X");
+        original.rules[2].conditions.push((x.clone(), x));
+
+        let mut t = original.clone();
+        assert_eq!(
+            t.make_deterministic_with(ClausePolicy::Choose(&mut |_| 2)),
+            Err(TRSError::InvalidIndex(2, 2))
+        );
+        assert_eq!(t, original);
+
+        let mut seen = vec![];
+        let dropped = t
+            .make_deterministic_with(ClausePolicy::Choose(&mut |rule| {
+                seen.push(rule.display(&sig));
+                1
+            }))
+            .expect("second clauses");
+        assert_eq!(seen, vec!["A = B | C", "F = G | H | I <= X = X"]);
+        assert_eq!(t.display(&sig), "A = C;\nD = E;\nF = H <= X = X;");
+        assert_eq!(dropped.len(), 2);
+        assert_eq!(dropped[0].display(&sig), "A = B");
+        assert_eq!(dropped[1].display(&sig), "F = G | I <= X = X");
+        assert!(t.is_deterministic());
+        assert_eq!(t.make_deterministic_with(ClausePolicy::First), Ok(vec![]));
+
+        let mut rng = StdRng::from_seed([0; 32]);
+        let mut t = original.clone();
+        let dropped = t
+            .make_deterministic_with(ClausePolicy::Random(&mut rng))
+            .expect("random clauses");
+        for (rule, rest) in [(&t.rules[0], &dropped[0]), (&t.rules[2], &dropped[1])] {
+            let mut clauses = rest.rhs.clone();
+            clauses.extend(rule.rhs.iter().cloned());
+            clauses.sort_by_key(|t| t.display(&sig));
+            let i = original.get(&rule.lhs).expect("an original rule").0;
+            let mut expected = original.rules[i].rhs.clone();
+            expected.sort_by_key(|t| t.display(&sig));
+            assert_eq!(clauses, expected);
+        }
+    }
+
+    #[test]
+    fn make_nondeterministic_test() {
+        let mut sig = Signature::default();
+
+        let mut t = parse_trs(&mut sig, "A = B | C; D = E;").expect("parse of A = B | C; D = E");
+
+        let mut r = rand::thread_rng();
+
+        t.make_deterministic(&mut r);
+
+        let str_before = t.display(&sig);
+        let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
+        assert!(t.insert_idx(1, r.clone()).is_err());
+        assert_eq!(str_before, t.display(&sig));
+
+        assert!(t.make_nondeterministic());
+
+        t.insert_idx(1, r).expect("inserting C = B | D");
+
+        assert!(
+            (t.display(&sig) == "A = B;\nC = B | D;\nD = E;")
+                || (t.display(&sig) == "A = C;\nC = B | D;\nD = E;")
+        );
+    }
+
+    #[test]
+    fn is_deterministic_test() {
+        let mut sig = Signature::default();
+
+        let mut t = parse_trs(
+            &mut sig,
+            "A = B | C;
+            D = E;",
+        )
+        .expect("parse of A = B | C; D = E");
+        let mut r = rand::thread_rng();
+
+        assert!(!t.is_deterministic());
+
+        t.make_deterministic(&mut r);
+
+        assert!(t.is_deterministic());
+    }
+
+    #[test]
+    fn len_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        assert_eq!(t.len(), 3);
+    }
+
+    #[test]
+    fn is_empty_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        assert!(!t.is_empty());
+
+        let t = parse_trs(&mut sig, "").expect("parse of blank string");
+
+        assert!(t.is_empty());
+    }
+
+    #[test]
+    fn size_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        assert_eq!(t.size(), 8);
+    }
+
+    #[test]
+    fn display_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        assert_eq!(t.display(&sig), "A = B;\nC = D | E;\nF(x_) = G;");
+
+        let trs = parse_trs(&mut sig,
+        "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO))); CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL)); B C D E = B C | D E;")
+            .expect("parse of A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
+            CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
+            B C D E = B C | D E;");
+
+        assert_eq!(trs.display(&sig),
+        "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));\nCONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));\n.(.(.(B C) D) E) = .(B C) | .(D E);");
+    }
+
+    #[test]
+    fn pretty_test() {
+        let mut sig = Signature::default();
+
+        let trs = parse_trs(
+            &mut sig,
+            "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
+        CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
+        B C D E = B C | D E;",
+        )
+        .expect(
+            "parse of A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
+             CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
+             B C D E = B C | D E;",
+        );
+
+        assert_eq!(
+            trs.pretty(&sig),
+            "A(x_, y_, z_) = A(x_, 105, SUCC(SUCC(ZERO)));\n[B, C, D] = [C, D];\nB C D E = B C | D E;"
+        );
+    }
+
+    #[test]
+    fn clauses_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F = G;",
+        )
+        .expect("parse of A = B; C = D | E; F = G;");
+
+        let r0 = parse_rule(&mut sig, "A = B").expect("parse of A = B");
+        let r1 = parse_rule(&mut sig, "C = D").expect("parse of C = D");
+        let r2 = parse_rule(&mut sig, "C = E").expect("parse of C = E");
+        let r3 = parse_rule(&mut sig, "F = G").expect("parse of F = G");
+
+        assert_eq!(t.clauses(), vec![r0, r1, r2, r3]);
+    }
+
+    #[test]
+    fn operators_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        let ops: Vec<String> = t.operators().iter().map(|o| o.display(&sig)).collect();
+
+        assert_eq!(ops, vec!["A", "B", "C", "D", "E", "F", "G"]);
+    }
+
+    #[test]
+    fn unifies_test() {
+        let mut sig = Signature::default();
+
+        let t0 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        let t1 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(H) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(H) = G;");
+
+        assert!(TRS::unifies(t0.clone(), t1));
+
+        let t2 = parse_trs(
+            &mut sig,
+            "B = A;
+            C = D | E;
+            F(y_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(y_) = G;");
+
+        assert!(!TRS::unifies(t0, t2));
+    }
+
+    #[test]
+    fn pmatches() {
+        let mut sig = Signature::default();
+
+        let t0 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        let t1 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(H) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(H) = G;");
+
+        assert!(TRS::pmatches(t0.clone(), t1));
+
+        let t2 = parse_trs(
+            &mut sig,
+            "B = A;
+            C = D | E;
+            F(y_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(y_) = G;");
+
+        assert!(!TRS::pmatches(t0.clone(), t2));
+
+        let t3 = parse_trs(
+            &mut sig,
+            "A = B | C;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B | C; C = D | E; F(x_) = G");
+
+        assert!(TRS::pmatches(t0.clone(), t3));
+
+        let t4 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D;
+            D = E;",
+        )
+        .expect("parse of A = B; C = D; D = E;");
+
+        assert!(!TRS::pmatches(t0, t4));
+    }
 
     #[test]
-    fn new_test() {
+    fn alphas_test() {
         let mut sig = Signature::default();
 
-        let r0 = parse_rule(&mut sig, "A = B").expect("parse of A = B");
-        let r1 = parse_rule(&mut sig, "C(x_) = x_").expect("parse of C(x_) = x_");
-        let r2 = parse_rule(&mut sig, "D(y_) = D(E)").expect("parse of D(y_) = D(E)");
-        let new_trs = TRS::new(vec![r0, r1, r2]);
+        let t0 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
+
+        let t1 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(H) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(H) = G;");
+
+        assert!(!TRS::alphas(&t0, &t1));
+
+        let t2 = parse_trs(
+            &mut sig,
+            "A = B;
+            C = D | E;
+            F(y_) = G;",
+        )
+        .expect("parse of A = B; C = D | E; F(y_) = G;");
 
-        assert_eq!(new_trs.display(), "A = B;\nC(x_) = x_;\nD(y_) = D(E);");
+        assert!(TRS::alphas(&t0, &t2));
     }
 
     #[test]
-    fn make_deterministic_test() {
+    fn rewrite_test() {
         let mut sig = Signature::default();
-        let mut t = parse_trs(
+
+        let t = parse_trs(
             &mut sig,
-            "A = B | C;
-            D = E;",
+            "A = B;
+            C = D | E;
+            F(x_) = G;",
         )
-        .expect("parse of A = B | C; D = E");
-
-        let mut r = rand::thread_rng();
-
-        let str_before = t.display();
+        .expect("parse of A = B; C = D | E; F(x_) = G;");
 
-        assert!(t.make_deterministic(&mut r));
+        let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
 
-        assert_ne!(t.display(), str_before);
+        let rewritten_terms = &t.rewrite(&term, Strategy::Normal).unwrap();
+        assert_eq!(rewritten_terms.len(), 1);
+        assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
 
-        let str_before = t.display();
-        let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
+        let rewritten_terms = &t.rewrite(&term, Strategy::Eager).unwrap();
+        assert_eq!(rewritten_terms.len(), 2);
+        assert_eq!(rewritten_terms[0].display(&sig), "J(F(D) K(C A))");
+        assert_eq!(rewritten_terms[1].display(&sig), "J(F(E) K(C A))");
 
-        if t.insert_idx(1, r.clone()).is_err() {
-            assert!(true);
-        }
+        let rewritten_terms = &t.rewrite(&term, Strategy::All).unwrap();
+        assert_eq!(rewritten_terms.len(), 6);
+        assert_eq!(rewritten_terms[0].display(&sig), "J(G K(C A))");
+        assert_eq!(rewritten_terms[1].display(&sig), "J(F(D) K(C A))");
+        assert_eq!(rewritten_terms[2].display(&sig), "J(F(E) K(C A))");
+        assert_eq!(rewritten_terms[3].display(&sig), "J(F(C) K(D A))");
+        assert_eq!(rewritten_terms[4].display(&sig), "J(F(C) K(E A))");
+        assert_eq!(rewritten_terms[5].display(&sig), "J(F(C) K(C B))");
+    }
 
-        assert_eq!(str_before, t.display());
+    #[test]
+    fn critical_pairs_test() {
+        let mut sig = Signature::default();
 
-        assert!((t.display() == "A = B;\nD = E;") || (t.display() == "A = C;\nD = E;"));
+        let t = parse_trs(&mut sig, "F(G(x_)) = A; G(B) = C;")
+            .expect("parse of F(G(x_)) = A; G(B) = C;");
+        let pairs = t.critical_pairs(&mut sig);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].peak.display(&sig), "F(G(B))");
+        assert_eq!(pairs[0].left.display(&sig), "A");
+        assert_eq!(pairs[0].right.display(&sig), "F(C)");
+        assert_eq!((pairs[0].outer, pairs[0].inner), (0, 1));
+        assert_eq!(pairs[0].place, vec![0]);
+        assert!(!pairs[0].is_trivial());
+
+        // a rule overlaps itself once renamed apart.
+        let t = parse_trs(&mut sig, "F(F(x_)) = G(x_);").expect("parse of F(F(x_)) = G(x_);");
+        let pairs = t.critical_pairs(&mut sig);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].peak.display(&sig), "F(F(F(x_)))");
+        assert_eq!(pairs[0].left.display(&sig), "G(F(x_))");
+        assert_eq!(pairs[0].right.display(&sig), "F(G(x_))");
+
+        // distinct clauses of one rule overlap at the root.
+        let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+        let pairs = t.critical_pairs(&mut sig);
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].left.display(&sig), "B");
+        assert_eq!(pairs[0].right.display(&sig), "C");
     }
 
     #[test]
-    fn make_nondeterministic_test() {
+    fn is_locally_confluent_test() {
         let mut sig = Signature::default();
 
-        let mut t = parse_trs(&mut sig, "A = B | C; D = E;").expect("parse of A = B | C; D = E");
-
-        let mut r = rand::thread_rng();
+        let t = parse_trs(&mut sig, "F(G(x_)) = A; G(B) = C;")
+            .expect("parse of F(G(x_)) = A; G(B) = C;");
+        let cp = t.is_locally_confluent(&mut sig, 10).unwrap_err();
 
-        t.make_deterministic(&mut r);
+        assert_eq!(cp.left.display(&sig), "A");
+        assert_eq!(cp.right.display(&sig), "F(C)");
 
-        let str_before = t.display();
-        let r = parse_rule(&mut sig, "C = B | D").expect("parse of C = B | D");
-        assert!(t.insert_idx(1, r.clone()).is_err());
-        assert_eq!(str_before, t.display());
+        let t = parse_trs(&mut sig, "F(G(x_)) = A; G(B) = C; F(C) = A;")
+            .expect("parse of F(G(x_)) = A; G(B) = C; F(C) = A;");
 
-        assert!(t.make_nondeterministic());
+        assert!(t.is_locally_confluent(&mut sig, 10).is_ok());
 
-        t.insert_idx(1, r).expect("inserting C = B | D");
+        // joining this pair takes two steps on the right.
+        let t = parse_trs(&mut sig, "F(G(x_)) = A; G(B) = C; F(C) = D; D = A;")
+            .expect("parse of F(G(x_)) = A; G(B) = C; F(C) = D; D = A;");
 
-        assert!(
-            (t.display() == "A = B;\nC = B | D;\nD = E;")
-                || (t.display() == "A = C;\nC = B | D;\nD = E;")
-        );
+        assert!(t.is_locally_confluent(&mut sig, 1).is_err());
+        assert!(t.is_locally_confluent(&mut sig, 2).is_ok());
     }
 
     #[test]
-    fn is_deterministic_test() {
+    fn is_confluent_if_terminating_test() {
         let mut sig = Signature::default();
 
-        let mut t = parse_trs(
+        let t = parse_trs(
             &mut sig,
-            "A = B | C;
-            D = E;",
+            "PLUS(ZERO x_) = x_; PLUS(x_ ZERO) = x_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));",
         )
-        .expect("parse of A = B | C; D = E");
-        let mut r = rand::thread_rng();
+        .expect("parse of PLUS rules");
 
-        assert!(!t.is_deterministic());
+        assert!(t.is_confluent_if_terminating(&mut sig).is_ok());
 
-        t.make_deterministic(&mut r);
+        let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+        let cp = t.is_confluent_if_terminating(&mut sig).unwrap_err();
 
-        assert!(t.is_deterministic());
+        assert_eq!(cp.left.display(&sig), "B");
+        assert_eq!(cp.right.display(&sig), "C");
     }
 
     #[test]
-    fn len_test() {
+    fn rule_shape_test() {
         let mut sig = Signature::default();
 
         let t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "A(x_ y_) = B(x_ x_); C(x_ x_) = x_; D(x_) = E | x_; F(x_ y_) = G(y_ x_);",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect(
+            "parse of A(x_ y_) = B(x_ x_); C(x_ x_) = x_; D(x_) = E | x_; F(x_ y_) = G(y_ x_);",
+        );
 
-        assert_eq!(t.len(), 3);
+        assert!(!t.is_left_linear());
+        assert!(!t.is_right_linear());
+        assert_eq!(t.collapsing_rules(), vec![1, 2]);
+        assert_eq!(t.duplicating_rules(), vec![0]);
     }
 
     #[test]
-    fn is_empty_test() {
+    fn is_overlapping_test() {
         let mut sig = Signature::default();
 
         let t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "PLUS(ZERO x_) = x_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect("parse of PLUS rules");
 
-        assert!(!t.is_empty());
+        assert!(!t.is_overlapping(&mut sig));
 
-        let t = parse_trs(&mut sig, "").expect("parse of blank string");
+        let t = parse_trs(&mut sig, "PLUS(ZERO x_) = x_; PLUS(x_ ZERO) = x_;")
+            .expect("parse of PLUS(ZERO x_) = x_; PLUS(x_ ZERO) = x_;");
 
-        assert!(t.is_empty());
+        assert!(t.is_overlapping(&mut sig));
+
+        let t = parse_trs(&mut sig, "F(F(x_)) = x_;").expect("parse of F(F(x_)) = x_;");
+
+        assert!(t.is_overlapping(&mut sig));
+
+        let t = parse_trs(&mut sig, "A = B | C;").expect("parse of A = B | C;");
+
+        assert!(t.is_overlapping(&mut sig));
     }
 
     #[test]
-    fn size_test() {
+    fn is_orthogonal_test() {
         let mut sig = Signature::default();
 
         let t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "PLUS(ZERO x_) = x_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect("parse of PLUS rules");
 
-        assert_eq!(t.size(), 8);
+        assert!(t.is_orthogonal(&mut sig));
+        assert!(t.is_weakly_orthogonal(&mut sig));
+
+        let t = parse_trs(&mut sig, "EQ(x_ x_) = TRUE;").expect("parse of EQ(x_ x_) = TRUE;");
+
+        assert!(!t.is_orthogonal(&mut sig));
+        assert!(!t.is_weakly_orthogonal(&mut sig));
     }
 
     #[test]
-    fn display_test() {
+    fn is_weakly_orthogonal_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "OR(TRUE x_) = TRUE; OR(x_ TRUE) = TRUE;")
+            .expect("parse of OR(TRUE x_) = TRUE; OR(x_ TRUE) = TRUE;");
+
+        assert!(!t.is_orthogonal(&mut sig));
+        assert!(t.is_weakly_orthogonal(&mut sig));
+
+        let t = parse_trs(&mut sig, "F(A x_) = B; F(x_ A) = C;")
+            .expect("parse of F(A x_) = B; F(x_ A) = C;");
+
+        assert!(!t.is_weakly_orthogonal(&mut sig));
+    }
+
+    #[test]
+    fn find_loop_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(x_) = F(x_);").expect("parse of F(x_) = F(x_);");
+        let term = parse_term(&mut sig, "G(F(A))").expect("parse of G(F(A))");
+        let certificate = t.find_loop(&term, 1).expect("loop");
+
+        assert_eq!(certificate.display(&sig), "G(F(A)) -> G(F(A)); C = [!]; {}");
+        assert!(certificate.check(&t));
+
+        let t = parse_trs(&mut sig, "F(SUCC(x_)) = F(x_);").expect("parse of F(SUCC(x_)) = F(x_);");
+        let term = parse_term(&mut sig, "F(SUCC(SUCC(A)))").expect("parse of F(SUCC(SUCC(A)))");
+
+        assert_eq!(t.find_loop(&term, 10), None);
+
+        // the loop only appears after some steps.
+        let t = parse_trs(&mut sig, "F(x_) = G(x_); G(A) = B | F(B); F(B) = C(F(B));")
+            .expect("parse of F(x_) = G(x_); G(A) = B | F(B); F(B) = C(F(B));");
+        let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+
+        assert_eq!(t.find_loop(&term, 1), None);
+
+        let certificate = t.find_loop(&term, 3).expect("loop");
+
+        assert_eq!(certificate.display(&sig), "F(B) -> C(F(B)); C = C([!]); {}");
+        assert!(certificate.check(&t));
+    }
+
+    #[test]
+    fn rewrites_test() {
         let mut sig = Signature::default();
 
         let t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "F(x_) = G(x_) | x_; F(A) = H; A = B | C; G(B) = D;",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect("parse of F(x_) = G(x_) | x_; F(A) = H; A = B | C; G(B) = D;");
+        let term = parse_term(&mut sig, "K(F(A) G(A) G(B) x_)").expect("parse of K(...)");
+
+        for strategy in &[
+            Strategy::Normal,
+            Strategy::Eager,
+            Strategy::Outermost,
+            Strategy::ParallelInnermost,
+            Strategy::All,
+        ] {
+            let rewrites: Vec<Term> = t.rewrites(&term, *strategy).collect();
+            assert_eq!(Some(rewrites), t.rewrite(&term, *strategy));
+        }
 
-        assert_eq!(t.display(), "A = B;\nC = D | E;\nF(x_) = G;");
+        let all: Vec<String> = t
+            .rewrites(&term, Strategy::All)
+            .map(|t| t.display(&sig))
+            .collect();
 
-        let trs = parse_trs(&mut sig,
-        "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO))); CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL)); B C D E = B C | D E;")
-            .expect("parse of A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
-            CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
-            B C D E = B C | D E;");
+        assert_eq!(
+            all,
+            vec![
+                "K(G(A) G(A) G(B) x_)",
+                "K(A G(A) G(B) x_)",
+                "K(F(B) G(A) G(B) x_)",
+                "K(F(C) G(A) G(B) x_)",
+                "K(F(A) G(B) G(B) x_)",
+                "K(F(A) G(C) G(B) x_)",
+                "K(F(A) G(A) D x_)",
+            ]
+        );
+
+        let term = parse_term(&mut sig, "K(B x_)").expect("parse of K(B x_)");
+
+        assert_eq!(t.rewrites(&term, Strategy::All).count(), 0);
+        assert_eq!(t.rewrite(&term, Strategy::All), Some(vec![]));
+        assert_eq!(t.rewrite(&term, Strategy::Eager), None);
+    }
+
+    #[test]
+    fn rewrites_with_provenance_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(x_) = G(x_) | x_; F(A) = H; A = B | C;")
+            .expect("parse of F(x_) = G(x_) | x_; F(A) = H; A = B | C;");
+        let term = parse_term(&mut sig, "K(F(A) A)").expect("parse of K(F(A) A)");
+        let provenance = |strategy| -> Vec<(usize, usize, Place)> {
+            t.rewrites_with_provenance(&term, strategy)
+                .map(|r| (r.rule, r.clause, r.place))
+                .collect()
+        };
+
+        assert_eq!(
+            provenance(Strategy::Normal),
+            vec![(0, 0, vec![0]), (0, 1, vec![0])]
+        );
+        assert_eq!(
+            provenance(Strategy::Outermost),
+            vec![(0, 0, vec![0]), (0, 1, vec![0]), (1, 0, vec![0])]
+        );
+        assert_eq!(
+            provenance(Strategy::Eager),
+            vec![(2, 0, vec![0, 0]), (2, 1, vec![0, 0])]
+        );
+        assert_eq!(
+            provenance(Strategy::ParallelInnermost),
+            vec![(2, 0, vec![0, 0])]
+        );
+
+        for rewrite in t.rewrites_with_provenance(&term, Strategy::All) {
+            let rule = &t.rules[rewrite.rule];
+            let sub = Term::pmatch(vec![(&rule.lhs, term.at(&rewrite.place).unwrap())]).unwrap();
+            let expected = term
+                .replace(&rewrite.place, rule.rhs[rewrite.clause].substitute(&sub))
+                .unwrap();
+            assert_eq!(rewrite.term, expected);
+        }
+    }
+
+    #[test]
+    fn rewrite_outermost_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(x_) = G; F(A) = H | I; A = B;")
+            .expect("parse of F(x_) = G; F(A) = H | I; A = B;");
+
+        let term = parse_term(&mut sig, "K(A F(A))").expect("parse of K(A F(A))");
+        let rewritten_terms: Vec<String> = t
+            .rewrite(&term, Strategy::Outermost)
+            .unwrap()
+            .iter()
+            .map(|t| t.display(&sig))
+            .collect();
+
+        assert_eq!(rewritten_terms, vec!["K(B F(A))"]);
+
+        let term = parse_term(&mut sig, "K(F(A) A)").expect("parse of K(F(A) A)");
+        let rewritten_terms: Vec<String> = t
+            .rewrite(&term, Strategy::Outermost)
+            .unwrap()
+            .iter()
+            .map(|t| t.display(&sig))
+            .collect();
+
+        assert_eq!(rewritten_terms, vec!["K(G A)", "K(H A)", "K(I A)"]);
+
+        let term = parse_term(&mut sig, "K(B B)").expect("parse of K(B B)");
+
+        assert_eq!(t.rewrite(&term, Strategy::Outermost), None);
+    }
+
+    #[test]
+    fn rewrite_parallel_innermost_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(x_) = G(x_); A = B | C; H(B B) = D;")
+            .expect("parse of F(x_) = G(x_); A = B | C; H(B B) = D;");
+
+        let term = parse_term(&mut sig, "F(H(A A))").expect("parse of F(H(A A))");
+        let mut rewritten_terms = vec![];
+        let mut term = term;
+        while let Some(mut ts) = t.rewrite(&term, Strategy::ParallelInnermost) {
+            assert_eq!(ts.len(), 1);
+            term = ts.remove(0);
+            rewritten_terms.push(term.display(&sig));
+        }
+
+        assert_eq!(rewritten_terms, vec!["F(H(B B))", "F(D)", "G(D)"]);
+    }
+
+    #[test]
+    fn rewrite_random_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "A = B; C = D | E; F(x_) = G;")
+            .expect("parse of A = B; C = D | E; F(x_) = G;");
+        let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
+        let mut r = StdRng::from_seed([0; 32]);
+
+        let all = t.rewrite(&term, Strategy::All).unwrap();
+        let mut seen = HashSet::new();
+        for _ in 0..200 {
+            let rewritten_term = t.rewrite_random(&term, &Strategy::All, &mut r).unwrap();
+            assert!(all.contains(&rewritten_term));
+            seen.insert(rewritten_term);
+        }
+
+        assert_eq!(seen.len(), all.len());
+
+        let term = parse_term(&mut sig, "J(B D)").expect("parse of J(B D)");
+
+        assert_eq!(t.rewrite_random(&term, &Strategy::Eager, &mut r), None);
+    }
+
+    #[test]
+    fn sample_derivation_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "A = B :: 0.9 | C :: 0.1 | D :: 0; B = A | C;")
+            .expect("parse of A = B :: 0.9 | C :: 0.1 | D :: 0; B = A | C;");
+        let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+        let mut r = StdRng::from_seed([0; 32]);
+
+        let derivation = t.sample_derivation(&term, 0, &mut r);
+
+        assert_eq!(derivation.trajectory, vec![term.clone()]);
+        assert_eq!(derivation.log_probability, 0.0);
+
+        let mut ends = HashSet::new();
+        for _ in 0..200 {
+            let derivation = t.sample_derivation(&term, 50, &mut r);
+            let steps: Vec<String> = derivation
+                .trajectory
+                .iter()
+                .map(|t| t.display(&sig))
+                .collect();
+            let expected: f64 = steps
+                .windows(2)
+                .map(|w| match (w[0].as_str(), w[1].as_str()) {
+                    ("F(A)", "F(B)") => 0.9f64.ln(),
+                    ("F(A)", "F(C)") => 0.1f64.ln(),
+                    ("F(B)", _) => 0.5f64.ln(),
+                    step => panic!("unexpected step {:?}", step),
+                })
+                .sum();
+
+            assert!(steps.len() <= 51);
+            assert!((derivation.log_probability - expected).abs() < 1e-9);
+            ends.insert(steps[steps.len() - 1].clone());
+        }
+
+        assert!(ends.contains("F(C)"));
+        assert!(!ends.contains("F(D)"));
+    }
+
+    #[test]
+    fn normalize_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "A = B; B = C; C = A; D = E | F;")
+            .expect("parse of A = B; B = C; C = A; D = E | F;");
+        let config = NormalizeConfig::default();
+
+        let term = parse_term(&mut sig, "G(A D)").expect("parse of G(A D)");
+
+        assert_eq!(
+            t.normalize(&term, Strategy::Normal, config),
+            Ok(Normalization::Cycle(term.clone()))
+        );
+
+        let config = NormalizeConfig {
+            max_steps: Some(10),
+            detect_cycles: false,
+            ..NormalizeConfig::default()
+        };
+        let c = parse_term(&mut sig, "G(B D)").expect("parse of G(B D)");
+
+        assert_eq!(
+            t.normalize(&term, Strategy::Normal, config),
+            Ok(Normalization::StepLimit(c))
+        );
+
+        let term = parse_term(&mut sig, "G(E D)").expect("parse of G(E D)");
+        let nf = parse_term(&mut sig, "G(E E)").expect("parse of G(E E)");
+
+        assert_eq!(
+            t.normalize(&term, Strategy::Eager, config),
+            Ok(Normalization::Normalized(nf.clone()))
+        );
+        assert_eq!(
+            t.normalize(&term, Strategy::All, config),
+            Ok(Normalization::Normalized(nf))
+        );
+
+        let t = parse_trs(&mut sig, "F(x_) = F(G(x_));").expect("parse of F(x_) = F(G(x_));");
+        let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+        let config = NormalizeConfig {
+            max_term_size: Some(4),
+            ..NormalizeConfig::default()
+        };
+        let too_large = parse_term(&mut sig, "F(G(G(G(A))))").expect("parse of F(G(G(G(A))))");
+
+        assert_eq!(
+            t.normalize(&term, Strategy::Normal, config),
+            Err(RewriteError::TermTooLarge(too_large))
+        );
+    }
+
+    #[test]
+    fn normalize_until_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(x_) = F(G(x_)); G(x_) = x_;")
+            .expect("parse of F(x_) = F(G(x_)); G(x_) = x_;");
+        let term = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+        let config = NormalizeConfig {
+            max_steps: None,
+            detect_cycles: false,
+            ..NormalizeConfig::default()
+        };
+        let cancel = Arc::new(AtomicBool::new(false));
+        let canceller = {
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(10));
+                cancel.store(true, Ordering::Relaxed);
+            })
+        };
+
+        match t.normalize_until(&term, Strategy::Normal, config, &cancel) {
+            Ok(Normalization::Interrupted(_)) => (),
+            result => panic!("expected an interruption, got {:?}", result),
+        }
+        canceller.join().unwrap();
+
+        let config = NormalizeConfig {
+            deadline: Some(Instant::now() + Duration::from_millis(10)),
+            ..config
+        };
+
+        match t.normalize(&term, Strategy::Normal, config) {
+            Ok(Normalization::Interrupted(_)) => (),
+            result => panic!("expected an interruption, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn reachable_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "P(x_ y_) = P(y_ x_) | F(x_); F(x_) = F(G(x_));")
+            .expect("parse of P(x_ y_) = P(y_ x_) | F(x_); F(x_) = F(G(x_));");
+        let term = parse_term(&mut sig, "P(a_ b_)").expect("parse of P(a_ b_)");
+
+        let graph = t.reachable(&term, 0);
+
+        assert_eq!(graph.nodes, vec![term.clone()]);
+        assert!(graph.edges.is_empty());
+
+        // P(b_ a_) is alpha-equivalent to P(a_ b_), but F(b_) is not to F(a_).
+        let graph = t.reachable(&term, 2);
+        let nodes: Vec<String> = graph.nodes.iter().map(|t| t.display(&sig)).collect();
+
+        assert_eq!(nodes, vec!["P(a_ b_)", "F(a_)", "F(G(a_))"]);
+
+        let edges: Vec<(usize, usize, usize, usize)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source, e.target, e.rule, e.clause))
+            .collect();
 
-        assert_eq!(trs.display(),
-        "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));\nCONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));\n.(.(.(B C) D) E) = .(B C) | .(D E);");
+        assert_eq!(edges, vec![(0, 0, 0, 0), (0, 1, 0, 1), (1, 2, 1, 0)]);
     }
 
     #[test]
-    fn pretty_test() {
+    fn rewrites_to_test() {
         let mut sig = Signature::default();
 
-        let trs = parse_trs(
-            &mut sig,
-            "A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
-        CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
-        B C D E = B C | D E;",
-        )
-        .expect(
-            "parse of A(x_ y_ z_) = A(x_ DECC(DECC(DIGIT(1) 0) 5) SUCC(SUCC(ZERO)));
-             CONS(B CONS(C CONS(D NIL))) = CONS(C CONS(D NIL));
-             B C D E = B C | D E;",
-        );
+        let t = parse_trs(&mut sig, "F(x_ y_) = F(y_ x_) | G(x_); G(A) = B;")
+            .expect("parse of F(x_ y_) = F(y_ x_) | G(x_); G(A) = B;");
+        let s = parse_term(&mut sig, "F(z_ A)").expect("parse of F(z_ A)");
+        let b = parse_term(&mut sig, "B").expect("parse of B");
 
+        let derivation = t
+            .rewrites_to(&s, &b, &Strategy::All, 3)
+            .expect("derivation");
+        let derivation: Vec<String> = derivation.iter().map(|t| t.display(&sig)).collect();
+
+        assert_eq!(derivation, vec!["F(z_ A)", "F(A z_)", "G(A)", "B"]);
+        assert_eq!(t.rewrites_to(&s, &b, &Strategy::All, 2), None);
         assert_eq!(
-            trs.pretty(),
-            "A(x_, y_, z_) = A(x_, 105, 2);\n[B, C, D] = [C, D];\nB C D E = B C | D E;"
+            t.rewrites_to(&s, &s, &Strategy::All, 0),
+            Some(vec![s.clone()])
         );
     }
 
     #[test]
-    fn clauses_test() {
+    fn joinable_test() {
         let mut sig = Signature::default();
 
-        let t = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F = G;",
-        )
-        .expect("parse of A = B; C = D | E; F = G;");
-
-        let r0 = parse_rule(&mut sig, "A = B").expect("parse of A = B");
-        let r1 = parse_rule(&mut sig, "C = D").expect("parse of C = D");
-        let r2 = parse_rule(&mut sig, "C = E").expect("parse of C = E");
-        let r3 = parse_rule(&mut sig, "F = G").expect("parse of F = G");
-
-        assert_eq!(t.clauses(), vec![r0, r1, r2, r3]);
+        let t = parse_trs(&mut sig, "F(x_) = G(x_); G(A) = B; H(x_) = B;")
+            .expect("parse of F(x_) = G(x_); G(A) = B; H(x_) = B;");
+        let s = parse_term(&mut sig, "F(A)").expect("parse of F(A)");
+        let u = parse_term(&mut sig, "H(C)").expect("parse of H(C)");
+
+        let join = t.joinable(&s, &u, SearchLimits::default()).expect("join");
+        let left: Vec<String> = join.left.iter().map(|t| t.display(&sig)).collect();
+        let right: Vec<String> = join.right.iter().map(|t| t.display(&sig)).collect();
+
+        assert_eq!(join.reduct.display(&sig), "B");
+        assert_eq!(left, vec!["F(A)", "G(A)", "B"]);
+        assert_eq!(right, vec!["H(C)", "B"]);
+
+        let limits = SearchLimits {
+            max_steps: 1,
+            ..SearchLimits::default()
+        };
+        assert_eq!(t.joinable(&s, &u, limits), None);
+
+        let limits = SearchLimits {
+            max_terms: Some(2),
+            ..SearchLimits::default()
+        };
+        assert_eq!(t.joinable(&s, &u, limits), None);
     }
 
     #[test]
-    fn operators_test() {
+    fn normal_forms_test() {
         let mut sig = Signature::default();
 
-        let t = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        let t = parse_trs(&mut sig, "F(x_ y_) = x_ | y_; A = B;")
+            .expect("parse of F(x_ y_) = x_ | y_; A = B;");
+        let term = parse_term(&mut sig, "F(A C)").expect("parse of F(A C)");
 
-        let ops: Vec<String> = t.operators().iter().map(|o| o.display()).collect();
+        let forms: Vec<(String, usize)> = t
+            .normal_forms(&term, SearchLimits::default())
+            .iter()
+            .map(|(t, n)| (t.display(&sig), *n))
+            .collect();
 
-        assert_eq!(ops, vec!["A", "B", "C", "D", "E", "F", "G"]);
+        assert_eq!(forms, vec![("C".to_string(), 2), ("B".to_string(), 2)]);
+
+        let t = parse_trs(&mut sig, "A = A | B;").expect("parse of A = A | B;");
+        let term = parse_term(&mut sig, "A").expect("parse of A");
+        let limits = SearchLimits {
+            max_steps: 3,
+            ..SearchLimits::default()
+        };
+
+        let forms = t.normal_forms(&term, limits);
+
+        assert_eq!(forms.len(), 1);
+        assert_eq!(forms[0].1, 3);
     }
 
     #[test]
-    fn unifies_test() {
+    fn redexes_test() {
         let mut sig = Signature::default();
 
-        let t0 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        let t = parse_trs(&mut sig, "F(x_ x_) = x_; G(x_) = A;")
+            .expect("parse of F(x_ x_) = x_; G(x_) = A;");
+        let term = parse_term(&mut sig, "F(G(y_) G(y_))").expect("parse of F(G(y_) G(y_))");
 
-        let t1 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(H) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(H) = G;");
+        let redexes: Vec<(String, usize)> = t
+            .redexes(&term)
+            .iter()
+            .map(|(p, r)| (p.display(), *r))
+            .collect();
 
-        assert!(TRS::unifies(t0.clone(), t1));
+        assert_eq!(
+            redexes,
+            vec![
+                ("ε".to_string(), 0),
+                ("0".to_string(), 1),
+                ("1".to_string(), 1)
+            ]
+        );
 
-        let t2 = parse_trs(
-            &mut sig,
-            "B = A;
-            C = D | E;
-            F(y_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(y_) = G;");
+        let term = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
 
-        assert!(!TRS::unifies(t0, t2));
+        assert!(t.redexes(&term).is_empty());
     }
 
     #[test]
-    fn pmatches() {
+    fn rewrite_at_test() {
         let mut sig = Signature::default();
 
-        let t0 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        let t = parse_trs(&mut sig, "F(x_ x_) = x_; G(x_) = A | B;")
+            .expect("parse of F(x_ x_) = x_; G(x_) = A | B;");
+        let term = parse_term(&mut sig, "F(G(y_) G(y_))").expect("parse of F(G(y_) G(y_))");
 
-        let t1 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(H) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(H) = G;");
+        for (position, r) in t.redexes(&term) {
+            let by_rule = t.rules[r]
+                .rewrite_at(&term, &position)
+                .expect("rule rewrite");
+            let by_trs = t.rewrite_at(&term, &position).expect("trs rewrite");
 
-        assert!(TRS::pmatches(t0.clone(), t1));
+            assert!(by_rule.iter().all(|u| by_trs.contains(u)));
+        }
 
-        let t2 = parse_trs(
-            &mut sig,
-            "B = A;
-            C = D | E;
-            F(y_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(y_) = G;");
+        let position = Position::from(vec![1]);
+        let rewritten: Vec<String> = t
+            .rewrite_at(&term, &position)
+            .expect("rewrite")
+            .iter()
+            .map(|t| t.display(&sig))
+            .collect();
 
-        assert!(!TRS::pmatches(t0.clone(), t2));
+        assert_eq!(rewritten, vec!["F(G(y_) A)", "F(G(y_) B)"]);
+        assert_eq!(t.rewrite_at(&term, &Position::from(vec![1, 0])), None);
+        assert_eq!(t.rewrite_at(&term, &Position::from(vec![1, 1])), None);
+    }
 
-        let t3 = parse_trs(
-            &mut sig,
-            "A = B | C;
-            C = D | E;
-            F(x_) = G;",
-        )
-        .expect("parse of A = B | C; C = D | E; F(x_) = G");
+    #[test]
+    fn narrow_test() {
+        let mut sig = Signature::default();
 
-        assert!(TRS::pmatches(t0.clone(), t3));
+        let t = parse_trs(&mut sig, "F(G(A)) = B; G(x_) = C | D;")
+            .expect("parse of F(G(A)) = B; G(x_) = C | D;");
+        let term = parse_term(&mut sig, "F(G(y_))").expect("parse of F(G(y_))");
+        let mut narrow = |strategy| {
+            t.narrow(&mut sig, &term, strategy)
+                .map(|n| {
+                    format!(
+                        "{} {} {}",
+                        n.term.display(&sig),
+                        n.substitution.display(&sig),
+                        n.position.display()
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
 
-        let t4 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D;
-            D = E;",
-        )
-        .expect("parse of A = B; C = D; D = E;");
+        assert_eq!(
+            narrow(NarrowingStrategy::All),
+            vec!["B {y_ := A} ε", "F(C) {} 0", "F(D) {} 0"]
+        );
+        assert_eq!(
+            narrow(NarrowingStrategy::Innermost),
+            vec!["F(C) {} 0", "F(D) {} 0"]
+        );
+        assert_eq!(narrow(NarrowingStrategy::Outermost), vec!["B {y_ := A} ε"]);
 
-        assert!(!TRS::pmatches(t0, t4));
+        let n = t
+            .narrow(&mut sig, &term, NarrowingStrategy::All)
+            .next()
+            .expect("narrowing");
+
+        assert_eq!((n.rule, n.clause), (0, 0));
+        assert_eq!(
+            t.rewrite_at(&n.substitution.apply(&term), &n.position),
+            Some(vec![n.term])
+        );
     }
 
     #[test]
-    fn alphas_test() {
+    fn simplify_test() {
         let mut sig = Signature::default();
 
-        let t0 = parse_trs(
+        let mut t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "A = A; F(x_ y_) = y_ | x_; F(B z_) = z_; G(w_) = H;",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect("parse of A = A; F(x_ y_) = y_ | x_; F(B z_) = z_; G(w_) = H;");
+        let rewrites = |t: &TRS, s: &str| {
+            let mut sig = sig.clone();
+            let term = parse_term(&mut sig, s).expect("parse of term");
+            t.rewrite(&term, Strategy::All)
+                .unwrap_or_default()
+                .iter()
+                .map(|t| t.display(&sig))
+                .sorted()
+        };
+        let before = rewrites(&t, "F(B G(C))");
+        let report = t.simplify();
+
+        assert_eq!(t.display(&sig), "F(x_ y_) = y_ | x_;\nG(w_) = H;");
+        assert_eq!(report.trivial[0].display(&sig), "A = A");
+        assert!(report.duplicates.is_empty());
+        assert_eq!(report.subsumed[0].display(&sig), "F(B z_) = z_");
+        assert_eq!(rewrites(&t, "F(B G(C))"), before);
+        assert!(t.simplify().is_empty());
+    }
 
-        let t1 = parse_trs(
+    #[test]
+    fn interreduce_test() {
+        let mut sig = Signature::default();
+
+        let mut t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(H) = G;",
+            "PLUS(ZERO x_) = x_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_)); TWO = PLUS(SUCC(ZERO) SUCC(ZERO)); PLUS(ZERO ZERO) = ZERO;",
         )
-        .expect("parse of A = B; C = D | E; F(H) = G;");
+        .expect("parse of PLUS rules");
+        let mut without_lhs = t.clone();
 
-        assert!(!TRS::alphas(&t0, &t1));
+        let removed = without_lhs.interreduce(false, NormalizeConfig::default());
 
-        let t2 = parse_trs(
-            &mut sig,
-            "A = B;
-            C = D | E;
-            F(y_) = G;",
-        )
-        .expect("parse of A = B; C = D | E; F(y_) = G;");
+        assert!(removed.is_empty());
+        assert_eq!(without_lhs.len(), 4);
+        assert_eq!(without_lhs.rules[2].display(&sig), "TWO = SUCC(SUCC(ZERO))");
 
-        assert!(TRS::alphas(&t0, &t2));
+        let removed = t.interreduce(true, NormalizeConfig::default());
+
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].display(&sig), "PLUS(ZERO ZERO) = ZERO");
+        assert_eq!(t.rules[2].display(&sig), "TWO = SUCC(SUCC(ZERO))");
+
+        // interreducing again changes nothing.
+        let before = t.clone();
+        t.interreduce(true, NormalizeConfig::default());
+
+        assert_eq!(t, before);
     }
 
     #[test]
-    fn rewrite_test() {
+    fn conditional_rewrite_test() {
         let mut sig = Signature::default();
 
-        let t = parse_trs(
+        let mut t = parse_trs(
             &mut sig,
-            "A = B;
-            C = D | E;
-            F(x_) = G;",
+            "LT(x_ y_) = TRUE <= LESS(x_ y_) = TRUE; LESS(ZERO S(y_)) = TRUE; LESS(S(x_) S(y_)) = LESS(x_ y_); MAX(x_ y_) = y_ <= LT(x_ y_) = TRUE;",
         )
-        .expect("parse of A = B; C = D | E; F(x_) = G;");
+        .expect("parse of MAX rules");
+        let term = parse_term(&mut sig, "MAX(S(ZERO) S(S(ZERO)))").expect("parse of MAX term");
 
-        let term = parse_term(&mut sig, "J(F(C) K(C A))").expect("parse of J(F(C) K(C A))");
+        assert_eq!(
+            t.rewrite(&term, Strategy::Normal).expect("rewrite")[0].display(&sig),
+            "S(S(ZERO))"
+        );
 
-        let rewritten_terms = &t.rewrite(&term, Strategy::Normal).unwrap();
-        assert_eq!(rewritten_terms.len(), 1);
-        assert_eq!(rewritten_terms[0].display(), "J(G K(C A))");
+        // MAX needs LT, which in turn needs LESS: two levels of conditions.
+        let shallow = ConditionConfig {
+            max_depth: 1,
+            ..ConditionConfig::default()
+        };
+        t.set_condition_config(shallow);
 
-        let rewritten_terms = &t.rewrite(&term, Strategy::Eager).unwrap();
-        assert_eq!(rewritten_terms.len(), 2);
-        assert_eq!(rewritten_terms[0].display(), "J(F(D) K(C A))");
-        assert_eq!(rewritten_terms[1].display(), "J(F(E) K(C A))");
+        assert_eq!(t.rewrite(&term, Strategy::Normal), None);
 
-        let rewritten_terms = &t.rewrite(&term, Strategy::All).unwrap();
-        assert_eq!(rewritten_terms.len(), 6);
-        assert_eq!(rewritten_terms[0].display(), "J(G K(C A))");
-        assert_eq!(rewritten_terms[1].display(), "J(F(D) K(C A))");
-        assert_eq!(rewritten_terms[2].display(), "J(F(E) K(C A))");
-        assert_eq!(rewritten_terms[3].display(), "J(F(C) K(D A))");
-        assert_eq!(rewritten_terms[4].display(), "J(F(C) K(E A))");
-        assert_eq!(rewritten_terms[5].display(), "J(F(C) K(C B))");
+        let term = parse_term(&mut sig, "MAX(S(S(ZERO)) S(ZERO))").expect("parse of MAX term");
+        t.set_condition_config(ConditionConfig::default());
+
+        assert_eq!(t.rewrite(&term, Strategy::Normal), None);
+
+        // semi-equational conditions may rewrite backwards.
+        let mut t = parse_trs(&mut sig, "F(x_) = OK <= A = x_; B = A;")
+            .expect("parse of F(x_) = OK <= A = x_; B = A;");
+        let term = parse_term(&mut sig, "F(B)").expect("parse of F(B)");
+
+        assert_eq!(t.rewrite_at(&term, &Position::root()), None);
+
+        t.set_condition_config(ConditionConfig {
+            mode: ConditionMode::SemiEquational,
+            ..ConditionConfig::default()
+        });
+
+        assert_eq!(
+            t.rewrite_at(&term, &Position::root()).expect("rewrite")[0].display(&sig),
+            "OK"
+        );
+
+        // the reversed rules follow edits to the rules.
+        t.rules_mut()[1] = parse_rule(&mut sig, "C = A").expect("parse of C = A");
+        let other = parse_term(&mut sig, "F(C)").expect("parse of F(C)");
+
+        assert_eq!(t.rewrite_at(&term, &Position::root()), None);
+        assert_eq!(
+            t.rewrite_at(&other, &Position::root()).expect("rewrite")[0].display(&sig),
+            "OK"
+        );
+
+        // and changes to the settings.
+        t.set_condition_config(ConditionConfig {
+            mode: ConditionMode::SemiEquational,
+            max_steps: 0,
+            ..ConditionConfig::default()
+        });
+
+        assert_eq!(t.rewrite_at(&other, &Position::root()), None);
     }
 
     #[test]
@@ -1528,11 +5485,11 @@ mod tests {
 
         let a = parse_term(&mut sig, "A").expect("parse of A");
 
-        assert_eq!(t.get(&a).unwrap().1.display(), "A = B");
+        assert_eq!(t.get(&a).unwrap().1.display(&sig), "A = B");
 
         let c = parse_term(&mut sig, "C").expect("parse of C");
 
-        assert_eq!(t.get(&c).unwrap().1.display(), "C = D | E");
+        assert_eq!(t.get(&c).unwrap().1.display(&sig), "C = D | E");
     }
 
     #[test]
@@ -1547,9 +5504,9 @@ mod tests {
         )
         .expect("parse of A = B; C = D | E; F(x_) = G;");
 
-        assert_eq!(t.get_idx(0).unwrap().display(), "A = B");
+        assert_eq!(t.get_idx(0).unwrap().display(&sig), "A = B");
 
-        assert_eq!(t.get_idx(1).unwrap().display(), "C = D | E");
+        assert_eq!(t.get_idx(1).unwrap().display(&sig), "C = D | E");
     }
 
     #[test]
@@ -1565,11 +5522,17 @@ mod tests {
 
         let r = parse_rule(&mut sig, "A(x_ y_) = B(y_ y_)").expect("parse of A(x_ y_) = B(y_ y_)");
 
-        assert_eq!(t.get_clause(&r).unwrap().1.display(), "A(a_ b_) = B(b_ b_)");
+        assert_eq!(
+            t.get_clause(&r).unwrap().1.display(&sig),
+            "A(a_ b_) = B(b_ b_)"
+        );
 
         let r = parse_rule(&mut sig, "D(w_ q_) = D(E F)").expect("parse of D(w_ q_) = D(E F)");
 
-        assert_eq!(t.get_clause(&r).unwrap().1.display(), "D(c_ e_) = D(E F)");
+        assert_eq!(
+            t.get_clause(&r).unwrap().1.display(&sig),
+            "D(c_ e_) = D(E F)"
+        );
     }
 
     #[test]
@@ -1587,11 +5550,14 @@ mod tests {
         let a = parse_term(&mut sig, "A").expect("parse of A");
         let c = parse_term(&mut sig, "C").expect("parse of C");
 
-        assert_eq!(t.remove(&a).expect("removing A = B").display(), "A = B");
+        assert_eq!(t.remove(&a).expect("removing A = B").display(&sig), "A = B");
 
-        assert_eq!(t.remove(&c).expect("removing C = D").display(), "C = D | E");
+        assert_eq!(
+            t.remove(&c).expect("removing C = D").display(&sig),
+            "C = D | E"
+        );
 
-        assert_eq!(t.display(), "F(x_) = G;");
+        assert_eq!(t.display(&sig), "F(x_) = G;");
     }
 
     #[test]
@@ -1606,14 +5572,17 @@ mod tests {
         )
         .expect("parse of A = B; C = D | E; F(x_) = G;");
 
-        assert_eq!(t.remove_idx(0).expect("removing A = B").display(), "A = B");
+        assert_eq!(
+            t.remove_idx(0).expect("removing A = B").display(&sig),
+            "A = B"
+        );
 
         assert_eq!(
-            t.remove_idx(0).expect("removing C = D").display(),
+            t.remove_idx(0).expect("removing C = D").display(&sig),
             "C = D | E"
         );
 
-        assert_eq!(t.display(), "F(x_) = G;");
+        assert_eq!(t.display(&sig), "F(x_) = G;");
     }
 
     #[test]
@@ -1631,11 +5600,11 @@ mod tests {
         let r = parse_rule(&mut sig, "C = D").expect("parse of C = D");
 
         assert_eq!(
-            t.remove_clauses(&r).expect("removing C = D").display(),
+            t.remove_clauses(&r).expect("removing C = D").display(&sig),
             "C = D"
         );
 
-        assert_eq!(t.display(), "A = B;\nC = E;\nF(x_) = G;");
+        assert_eq!(t.display(&sig), "A = B;\nC = E;\nF(x_) = G;");
     }
 
     #[test]
@@ -1654,13 +5623,16 @@ mod tests {
 
         t.insert(1, r).expect("inserting D = G at index 1");
 
-        assert_eq!(t.display(), "A = B;\nD = G;\nC = D | E;\nF(x_) = G;");
+        assert_eq!(t.display(&sig), "A = B;\nD = G;\nC = D | E;\nF(x_) = G;");
 
         let r = parse_rule(&mut sig, "D = A").expect("parse of D = A");
 
         t.insert(0, r).expect("inserting D = A with D = G");
 
-        assert_eq!(t.display(), "A = B;\nD = G | A;\nC = D | E;\nF(x_) = G;");
+        assert_eq!(
+            t.display(&sig),
+            "A = B;\nD = G | A;\nC = D | E;\nF(x_) = G;"
+        );
     }
 
     #[test]
@@ -1679,7 +5651,7 @@ mod tests {
 
         t.insert_idx(1, r).expect("inserting D = G at index 1");
 
-        assert_eq!(t.display(), "A = B;\nD = G;\nC = D | E;\nF(x_) = G;");
+        assert_eq!(t.display(&sig), "A = B;\nD = G;\nC = D | E;\nF(x_) = G;");
     }
 
     #[test]
@@ -1702,7 +5674,7 @@ mod tests {
             .expect("inserting 3 rules at index 2");
 
         assert_eq!(
-            t.display(),
+            t.display(&sig),
             "A = B;\nC = D | E;\nG(y_) = y_;\nB = C;\nE = F | B;\nF(x_) = H;"
         );
     }
@@ -1723,7 +5695,7 @@ mod tests {
 
         let t = t.insert_clauses(&r).expect("inserting A = H with A = B");
 
-        assert_eq!(t.display(), "A = B | H;\nC = D | E;\nF(x_) = G;");
+        assert_eq!(t.display(&sig), "A = B | H;\nC = D | E;\nF(x_) = G;");
     }
 
     #[test]
@@ -1742,7 +5714,10 @@ mod tests {
 
         t.push(r).expect("inserting G(y_) = y_ at index 0");
 
-        assert_eq!(t.display(), "G(y_) = y_;\nA = B;\nC = D | E;\nF(x_) = G;");
+        assert_eq!(
+            t.display(&sig),
+            "G(y_) = y_;\nA = B;\nC = D | E;\nF(x_) = G;"
+        );
     }
 
     #[test]
@@ -1765,7 +5740,7 @@ mod tests {
             .expect("inserting 3 rules at index 0");
 
         assert_eq!(
-            t.display(),
+            t.display(&sig),
             "G(y_) = y_;\nB = C;\nE = F | B;\nA = B;\nC = D | E;\nF(x_) = H;"
         );
     }
@@ -1786,7 +5761,7 @@ mod tests {
         t.move_rule(0, 2)
             .expect("moving rule from index 0 to index 2");
 
-        assert_eq!(t.display(), "C = D | E;\nF(x_) = G;\nA = B;\nH = I;");
+        assert_eq!(t.display(&sig), "C = D | E;\nF(x_) = G;\nA = B;\nH = I;");
     }
 
     #[test]
@@ -1807,6 +5782,213 @@ mod tests {
         t.replace(0, &r, r_new)
             .expect("replaceing C = D with C = A");
 
-        assert_eq!(t.display(), "A = B;\nC = E | A;\nF(x_) = G;");
+        assert_eq!(t.display(&sig), "A = B;\nC = E | A;\nF(x_) = G;");
+    }
+
+    #[test]
+    fn root_lookup_test() {
+        let mut sig = Signature::default();
+
+        let mut t = parse_trs(&mut sig, "F(A x_) = B; F(x_ A) = C; G(x_) = x_;")
+            .expect("parse of F(A x_) = B; F(x_ A) = C; G(x_) = x_;");
+        let h = parse_rule(&mut sig, "H(y_) = A").expect("parse of H(y_) = A");
+        t.insert_idx(3, h).expect("insert of H(y_) = A");
+
+        let h = parse_term(&mut sig, "H(z_)").expect("parse of H(z_)");
+        assert_eq!(t.get(&h).map(|(i, _)| i), Some(3));
+        let g = parse_term(&mut sig, "G(F(A A))").expect("parse of G(F(A A))");
+        assert_eq!(t.get(&g), None);
+
+        let r = parse_rule(&mut sig, "F(y_ A) = C").expect("parse of F(y_ A) = C");
+        assert_eq!(t.get_clause(&r).map(|(i, _)| i), Some(1));
+
+        let term = parse_term(&mut sig, "H(F(A A))").expect("parse of H(F(A A))");
+        let rewrites: Vec<_> = t
+            .rewrite(&term, Strategy::All)
+            .expect("rewrites of H(F(A A))")
+            .iter()
+            .map(|t| t.display(&sig))
+            .collect();
+        assert_eq!(rewrites, vec!["A", "H(B)"]);
+
+        // replacing a rule keeps the number of rules, but changes its root
+        t.rules_mut()[3] = parse_rule(&mut sig, "K(y_) = A").expect("parse of K(y_) = A");
+        let k = parse_term(&mut sig, "K(z_)").expect("parse of K(z_)");
+        assert_eq!(t.get(&k).map(|(i, _)| i), Some(3));
+        assert_eq!(t.get(&h), None);
+
+        let r = parse_rule(&mut sig, "K(x_) = B").expect("parse of K(x_) = B");
+        t.insert(0, r).expect("insert of K(x_) = B");
+        assert_eq!(t.len(), 4);
+        assert_eq!(t.rules()[3].display(&sig), "K(y_) = A | B");
+    }
+
+    #[test]
+    fn normalize_cached_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(
+            &mut sig,
+            "PLUS(ZERO y_) = y_; PLUS(SUCC(x_) y_) = SUCC(PLUS(x_ y_));",
+        )
+        .expect("parse of PLUS rules");
+        let mut cache = NormalFormCache::new();
+        let config = NormalizeConfig::default();
+
+        let term = parse_term(&mut sig, "PLUS(SUCC(SUCC(ZERO)) x_)").expect("parse of term");
+        let nf = t.normalize_cached(&term, Strategy::Normal, config, &mut cache);
+        assert_eq!(nf, t.normalize(&term, Strategy::Normal, config));
+        assert_eq!(cache.len(), 4);
+
+        // alpha-equivalent Terms share entries, renamed to their own Variables
+        let term = parse_term(&mut sig, "PLUS(SUCC(SUCC(ZERO)) z_)").expect("parse of term");
+        match t.normalize_cached(&term, Strategy::Normal, config, &mut cache) {
+            Ok(Normalization::Normalized(nf)) => assert_eq!(nf.display(&sig), "SUCC(SUCC(z_))"),
+            result => panic!("expected a normal form, got {:?}", result),
+        }
+        assert_eq!(cache.len(), 4);
+
+        // cached normal forms reached beyond the limits are not used
+        for limited in [
+            NormalizeConfig {
+                max_steps: Some(2),
+                ..config
+            },
+            NormalizeConfig {
+                max_term_size: Some(4),
+                ..config
+            },
+        ] {
+            assert_eq!(
+                t.normalize_cached(&term, Strategy::Normal, limited, &mut cache),
+                t.normalize(&term, Strategy::Normal, limited)
+            );
+        }
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn rewrite_in_place_at_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_trs(&mut sig, "F(A) = B | C; F(x_) = G(x_); A = D;")
+            .expect("parse of F(A) = B | C; F(x_) = G(x_); A = D;");
+        let term = parse_term(&mut sig, "H(F(A) F(F(A)) x_)").expect("parse of term");
+
+        for p in term.positions() {
+            let mut rewritten = term.clone();
+            let rule = t.rewrite_in_place_at(&mut rewritten, &p);
+            match t.rewrite_at(&term, &p) {
+                Some(rewrites) => {
+                    assert!(rule.is_some());
+                    assert_eq!(rewritten, rewrites[0]);
+                }
+                None => {
+                    assert_eq!(rule, None);
+                    assert_eq!(rewritten, term);
+                }
+            }
+        }
+
+        let mut buffer = vec![];
+        for strategy in [Strategy::Normal, Strategy::All] {
+            let n = t.rewrite_into(&term, strategy, &mut buffer);
+            assert_eq!(n, t.rewrites(&term, strategy).count());
+        }
+        assert_eq!(buffer.len(), 2 + 7);
+    }
+
+    #[test]
+    fn union_test() {
+        let mut sig = Signature::default();
+        let mut t =
+            parse_trs(&mut sig, "A = B; F(x_ y_) = x_;").expect("parse of A = B; F(x_ y_) = x_;");
+        let other = parse_trs(&mut sig, "F(y_ x_) = x_ | y_; C = D; C = E;")
+            .expect("parse of F(y_ x_) = x_ | y_; C = D; C = E;");
+
+        // later Rules of `other` may overlap earlier ones
+        let mut merged = t.clone();
+        let overlaps = merged
+            .union(&other, UnionPolicy::MergeClauses)
+            .expect("merging clauses");
+        assert_eq!(overlaps, vec![(1, 0), (2, 2)]);
+        assert_eq!(
+            merged.display(&sig),
+            "A = B;\nF(x_ y_) = x_ | y_;\nC = D | E;"
+        );
+
+        let overlaps = t
+            .union(&t.clone(), UnionPolicy::PreferLeft)
+            .expect("no change");
+        assert_eq!(overlaps, vec![(0, 0), (1, 1)]);
+        assert_eq!(t.display(&sig), "A = B;\nF(x_ y_) = x_;");
+
+        // a deterministic TRS is left unchanged by a failed union
+        let mut r = StdRng::from_seed([0; 32]);
+        t.make_deterministic(&mut r);
+        assert_eq!(
+            t.union(&other, UnionPolicy::MergeClauses),
+            Err(TRSError::NondeterministicRule)
+        );
+        assert_eq!(t.display(&sig), "A = B;\nF(x_ y_) = x_;");
+        // the nondeterministic Rule of `other` is discarded
+        let overlaps = t.union(&other, UnionPolicy::PreferLeft);
+        assert_eq!(overlaps, Ok(vec![(1, 0), (2, 2)]));
+        assert_eq!(t.display(&sig), "A = B;\nF(x_ y_) = x_;\nC = D;");
+    }
+
+    #[test]
+    fn reindex_test() {
+        let mut sig = Signature::default();
+        let mut t = parse_trs(&mut sig, "A = B; F(x_) = x_; G(A) = C;").expect("parse of TRS");
+        let terms: Vec<Term> = ["A", "F(A)", "G(A)", "H(B)", "G(F(A))"]
+            .iter()
+            .map(|s| parse_term(&mut sig, s).expect("parse of term"))
+            .collect();
+        let same_rewrites = |t: &TRS| {
+            let fresh = TRS::new(t.rules.clone());
+            terms.iter().all(|term| {
+                t.rewrite(term, Strategy::All) == fresh.rewrite(term, Strategy::All)
+                    && t.redexes(term) == fresh.redexes(term)
+                    && t.get(term) == fresh.get(term)
+            }) && t
+                .rules
+                .iter()
+                .all(|r| t.get_clause(r) == fresh.get_clause(r))
+        };
+
+        let h = parse_rule(&mut sig, "H(x_) = A").expect("parse of H(x_) = A");
+        t.insert_idx(1, h).expect("insert");
+        assert!(same_rewrites(&t));
+        t.move_rule(0, 3).expect("move");
+        assert!(same_rewrites(&t));
+        t.remove_idx(1).expect("remove");
+        assert!(same_rewrites(&t));
+        let a = parse_rule(&mut sig, "A = B").expect("parse of A = B");
+        t.remove_clauses(&a).expect("remove clauses");
+        assert!(same_rewrites(&t));
+
+        t.rules_mut()[0] = parse_rule(&mut sig, "H(B) = C").expect("parse of H(B) = C");
+        assert!(same_rewrites(&t));
+        t.rules_mut().reverse();
+        assert!(same_rewrites(&t));
+    }
+
+    #[test]
+    fn rules_mut_test() {
+        let mut sig = Signature::default();
+        let mut t = parse_trs(&mut sig, "A = B; C = D;").expect("parse of A = B; C = D;");
+        let e = parse_term(&mut sig, "E").expect("parse of E");
+        assert_eq!(t.rewrite(&e, Strategy::Normal), None);
+
+        // replacing a rule keeps the number of rules, but not what the index holds
+        t.rules_mut()[0] = parse_rule(&mut sig, "E = F").expect("parse of E = F");
+        let rewritten = t.rewrite(&e, Strategy::Normal).expect("rewrite of E");
+        assert_eq!(rewritten[0].display(&sig), "F");
+
+        let a = parse_term(&mut sig, "A").expect("parse of A");
+        assert_eq!(t.rewrite(&a, Strategy::Normal), None);
     }
 }
@@ -0,0 +1,287 @@
+use super::{Operator, Position, Term};
+use std::mem;
+
+/// A cursor into a [`Term`] supporting navigation and in-place editing. Moving the focus and
+/// replacing it only touches the path from the root to the focus, so editing deep subterms
+/// does not require cloning the whole [`Term`].
+///
+/// [`Term`]: enum.Term.html
+///
+/// # Examples
+///
+/// ```
+/// # use term_rewriting::{Signature, TermZipper, parse_term};
+/// let mut sig = Signature::default();
+///
+/// let t = parse_term(&mut sig, "F(A G(B C))").expect("parse of F(A G(B C))");
+/// let d = parse_term(&mut sig, "D").expect("parse of D");
+///
+/// let mut zipper = TermZipper::new(t);
+///
+/// assert!(zipper.down(1));
+/// assert!(zipper.down(0));
+/// assert!(zipper.right());
+/// assert_eq!(zipper.focus().display(&sig), "C");
+///
+/// zipper.replace_focus(d);
+///
+/// assert_eq!(zipper.rebuild().display(&sig), "F(A G(B D))");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermZipper {
+    focus: Term,
+    path: Vec<Crumb>,
+}
+
+// The part of an application left behind when moving down into one of its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Crumb {
+    op: Operator,
+    // The arguments left of the focus, in order.
+    left: Vec<Term>,
+    // The arguments right of the focus, in reverse order.
+    right: Vec<Term>,
+}
+
+impl TermZipper {
+    /// Create a `TermZipper` focused on the root of `term`.
+    pub fn new(term: Term) -> TermZipper {
+        TermZipper {
+            focus: term,
+            path: vec![],
+        }
+    }
+    /// The `Term` currently in focus.
+    pub fn focus(&self) -> &Term {
+        &self.focus
+    }
+    /// Is the focus the root of the `Term`?
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+    /// The [`Position`] of the focus.
+    ///
+    /// [`Position`]: struct.Position.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A G(B C))").expect("parse of F(A G(B C))");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// zipper.down(1);
+    /// zipper.down(1);
+    ///
+    /// assert_eq!(zipper.position().display(), "1.1");
+    /// ```
+    pub fn position(&self) -> Position {
+        Position::from(
+            self.path
+                .iter()
+                .map(|crumb| crumb.left.len())
+                .collect::<Vec<_>>(),
+        )
+    }
+    /// Move the focus to its `i`th argument. Returns `false`, leaving the focus unchanged, if
+    /// the focus has no `i`th argument.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// assert!(!zipper.down(2));
+    /// assert!(zipper.down(1));
+    /// assert_eq!(zipper.focus().display(&sig), "B");
+    /// assert!(!zipper.down(0));
+    /// ```
+    pub fn down(&mut self, i: usize) -> bool {
+        let (op, mut left, mut right) = match self.focus {
+            Term::Application {
+                ref op,
+                ref mut args,
+            } if i < args.len() => {
+                let mut left = mem::take(args);
+                let right = left.split_off(i + 1);
+                (*op, left, right)
+            }
+            _ => return false,
+        };
+        right.reverse();
+        self.focus = left.pop().expect("argument i");
+        self.path.push(Crumb { op, left, right });
+        true
+    }
+    /// Move the focus to its parent. Returns `false` if the focus is the root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// assert!(!zipper.up());
+    ///
+    /// zipper.down(0);
+    ///
+    /// assert!(zipper.up());
+    /// assert_eq!(zipper.focus().display(&sig), "F(A B)");
+    /// ```
+    pub fn up(&mut self) -> bool {
+        match self.path.pop() {
+            Some(Crumb {
+                op,
+                mut left,
+                mut right,
+            }) => {
+                let parent = Term::Application { op, args: vec![] };
+                let focus = mem::replace(&mut self.focus, parent);
+                left.push(focus);
+                right.reverse();
+                left.append(&mut right);
+                if let Term::Application { ref mut args, .. } = self.focus {
+                    *args = left;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    /// Move the focus to its left sibling. Returns `false` if there is no left sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// zipper.down(1);
+    ///
+    /// assert!(zipper.left());
+    /// assert_eq!(zipper.focus().display(&sig), "A");
+    /// assert!(!zipper.left());
+    /// ```
+    pub fn left(&mut self) -> bool {
+        match self.path.last_mut() {
+            Some(ref mut crumb) if !crumb.left.is_empty() => {
+                let sibling = crumb.left.pop().expect("left sibling");
+                crumb.right.push(mem::replace(&mut self.focus, sibling));
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Move the focus to its right sibling. Returns `false` if there is no right sibling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// zipper.down(0);
+    ///
+    /// assert!(zipper.right());
+    /// assert_eq!(zipper.focus().display(&sig), "B");
+    /// assert!(!zipper.right());
+    /// ```
+    pub fn right(&mut self) -> bool {
+        match self.path.last_mut() {
+            Some(ref mut crumb) if !crumb.right.is_empty() => {
+                let sibling = crumb.right.pop().expect("right sibling");
+                crumb.left.push(mem::replace(&mut self.focus, sibling));
+                true
+            }
+            _ => false,
+        }
+    }
+    /// Replace the focus with `term`, returning the old focus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use term_rewriting::{Signature, TermZipper, parse_term};
+    /// let mut sig = Signature::default();
+    ///
+    /// let t = parse_term(&mut sig, "F(A B)").expect("parse of F(A B)");
+    /// let c = parse_term(&mut sig, "C").expect("parse of C");
+    /// let mut zipper = TermZipper::new(t);
+    ///
+    /// zipper.down(0);
+    /// let old = zipper.replace_focus(c);
+    ///
+    /// assert_eq!(old.display(&sig), "A");
+    /// assert_eq!(zipper.rebuild().display(&sig), "F(C B)");
+    /// ```
+    pub fn replace_focus(&mut self, term: Term) -> Term {
+        mem::replace(&mut self.focus, term)
+    }
+    /// Move the focus back to the root and return the whole, possibly edited, `Term`.
+    pub fn rebuild(mut self) -> Term {
+        while self.up() {}
+        self.focus
+    }
+}
+impl From<Term> for TermZipper {
+    fn from(term: Term) -> TermZipper {
+        TermZipper::new(term)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::parser::*;
+    use super::super::{Position, Signature};
+    use super::*;
+
+    #[test]
+    fn zipper_test() {
+        let mut sig = Signature::default();
+
+        let t = parse_term(&mut sig, "F(A G(B C x_) H)").expect("parse of F(A G(B C x_) H)");
+        let mut zipper = TermZipper::new(t.clone());
+
+        for p in t.positions() {
+            let mut z = TermZipper::new(t.clone());
+            for &i in p.iter() {
+                assert!(z.down(i));
+            }
+
+            assert_eq!(z.position(), p);
+            assert_eq!(Some(z.focus()), t.at(&p));
+            assert_eq!(z.rebuild(), t);
+        }
+
+        assert!(zipper.down(1));
+        assert!(zipper.down(2));
+        assert!(zipper.left());
+        assert!(zipper.left());
+        assert!(!zipper.left());
+        assert_eq!(zipper.position(), Position::from(vec![1, 0]));
+        assert!(zipper.right());
+        assert!(zipper.right());
+        assert!(!zipper.right());
+        assert!(zipper.up());
+        assert!(zipper.right());
+        assert_eq!(zipper.focus().display(&sig), "H");
+        assert!(zipper.up());
+        assert!(zipper.is_root());
+        assert_eq!(zipper.rebuild(), t);
+    }
+}
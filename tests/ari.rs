@@ -0,0 +1,78 @@
+extern crate term_rewriting;
+
+use term_rewriting::*;
+
+#[test]
+fn ari_round_trip() {
+    let input = "; @origin COPS #7
+        ; @author Bernhard Gramlich
+        ; a comment without a key is not metadata
+        (format CTRS join)
+        (fun f 1)
+        (fun |g h| 2)
+        (fun a 0)
+        (fun b 0)
+        (rule (f x) a (= x (|g h| b x)) (= x a)) ; a trailing comment
+        (rule (|g h| x y) b)";
+    let mut sig = Signature::default();
+    let problem = parse_ari(&mut sig, input).expect("parse of ARI problem");
+
+    assert_eq!(problem.trs.len(), 2);
+    assert_eq!(problem.trs.condition_config().mode, ConditionMode::Join);
+    assert_eq!(
+        problem.metadata,
+        vec![
+            ("origin".to_string(), "COPS #7".to_string()),
+            ("author".to_string(), "Bernhard Gramlich".to_string()),
+        ]
+    );
+
+    let output = problem.to_ari(&sig);
+    let mut sig2 = Signature::default();
+    let problem2 = parse_ari(&mut sig2, &output).expect("parse of exported ARI problem");
+
+    assert_eq!(problem2.trs.display(&sig2), problem.trs.display(&sig));
+    assert_eq!(problem2.metadata, problem.metadata);
+    assert_eq!(problem2.to_ari(&sig2), output);
+}
+
+#[test]
+fn ari_and_cops_agree() {
+    let mut sig = Signature::default();
+    let problem = parse_ari(
+        &mut sig,
+        "; @origin COPS #1
+         (format TRS)
+         (fun f 2)
+         (fun g 1)
+         (rule (f x (g y)) (g (f x y)))",
+    )
+    .expect("parse of ARI problem");
+
+    let mut sig2 = Signature::default();
+    let problem2 = parse_cops(&mut sig2, &problem.to_cops(&sig)).expect("parse of COPS problem");
+
+    assert_eq!(problem2.trs.display(&sig2), problem.trs.display(&sig));
+    assert_eq!(problem2.metadata, problem.metadata);
+    assert_eq!(problem2.to_ari(&sig2), problem.to_ari(&sig));
+}
+
+#[test]
+fn ari_rejects_malformed_input() {
+    let mut sig = Signature::default();
+    let bad = vec![
+        // unbalanced parens
+        "(format TRS) (fun f 1) (rule (f x) x",
+        // wrong arity
+        "(format TRS) (fun f 1) (rule (f x x) x)",
+        // applied variable
+        "(format TRS) (rule (f x) x)",
+        // unsupported format
+        "(format LCTRS)",
+        // fresh variable on the right-hand side
+        "(format TRS) (fun f 1) (rule (f x) y)",
+    ];
+    for input in bad {
+        assert!(parse_ari(&mut sig, input).is_err(), "{}", input);
+    }
+}
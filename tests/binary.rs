@@ -0,0 +1,64 @@
+extern crate term_rewriting;
+
+use term_rewriting::*;
+
+#[test]
+fn binary_round_trip() {
+    let mut sig = Signature::default();
+    let mut trs = parse_trs(
+        &mut sig,
+        "S x_ y_ z_ = x_ z_ (y_ z_);
+         F(x_ y_) = x_ | y_ <= EQ(x_ y_) = TRUE;",
+    )
+    .expect("parse of TRS");
+    trs.rules_mut()[1].weights = Some(vec![0.25, 0.75]);
+    let unnamed = sig.new_op(0, None);
+    trs.rules_mut().push(
+        Rule::new(
+            Term::Application {
+                op: unnamed,
+                args: vec![],
+            },
+            vec![parse_term(&mut sig, "TRUE").expect("parse of TRUE")],
+        )
+        .expect("rule"),
+    );
+    let bytes = trs.to_bytes(&sig);
+
+    let mut sig2 = Signature::default();
+    let trs2 = decode_trs(&mut sig2, &bytes).expect("decoded TRS");
+
+    assert_eq!(trs2.len(), 3);
+    for (r1, r2) in trs.rules().iter().zip(trs2.rules()).take(2) {
+        assert_eq!(r2.display(&sig2), r1.display(&sig));
+    }
+    assert_eq!(trs2.rules()[2].lhs.operators()[0].name(&sig2), None);
+    assert_eq!(trs2.rules()[1].weights, Some(vec![0.25, 0.75]));
+    assert_eq!(trs2.to_bytes(&sig2), bytes);
+    assert!(bytes.len() * 4 < trs.to_json(&sig).len());
+
+    let rule = decode_rule(&mut sig2, &trs.rules()[1].to_bytes(&sig)).expect("decoded rule");
+    assert_eq!(rule.display(&sig2), trs.rules()[1].display(&sig));
+}
+
+#[test]
+fn binary_rejects_malformed_input() {
+    let mut sig = Signature::default();
+    let t = parse_term(&mut sig, "F(G(x_) A)").expect("parse of F(G(x_) A)");
+    let bytes = t.to_bytes(&sig);
+
+    for n in 0..bytes.len() {
+        assert!(decode_term(&mut sig, &bytes[..n]).is_err());
+    }
+
+    let mut extended = bytes.clone();
+    extended.push(0);
+    assert!(decode_term(&mut sig, &extended).is_err());
+
+    let mut future = bytes.clone();
+    future[0] += 1;
+    assert!(decode_term(&mut sig, &future).is_err());
+
+    assert!(decode_trs(&mut sig, &bytes).is_err());
+    assert!(decode_term(&mut sig, &bytes).is_ok());
+}
@@ -0,0 +1,70 @@
+extern crate term_rewriting;
+
+use term_rewriting::{combinators::*, *};
+
+// The result of taking up to `limit` steps with `Strategy::Normal`.
+fn rewrite_steps(trs: &TRS, term: &Term, limit: usize) -> Normalization {
+    let mut term = term.clone();
+    for _ in 0..limit {
+        match trs.rewrite(&term, Strategy::Normal) {
+            Some(mut ts) => term = ts.remove(0),
+            None => return Normalization::Normalized(term),
+        }
+    }
+    match trs.rewrite(&term, Strategy::Normal) {
+        Some(_) => Normalization::StepLimit(term),
+        None => Normalization::Normalized(term),
+    }
+}
+
+#[test]
+fn reduce_cl_agrees_with_normal_strategy() {
+    let mut sig = Signature::default();
+    let (cl, trs) = install(&mut sig);
+    let terms = [
+        "S K K x_",
+        "S (K (S I)) K x_ y_",
+        "K I (S I I (S I I)) F(S K K A)",
+        "S I I (S I I)",
+        "S (S K K) (S K K) (S (K (S I I)) K) B",
+        "F(I (K A B) x_) (I I) (K (I C))",
+        "S (K S) K (S I I) (K A) B",
+    ];
+
+    for input in terms.iter() {
+        let term = parse_term(&mut sig, input).expect("parse of CL term");
+        for limit in 0..12 {
+            assert_eq!(
+                cl.reduce_cl(&term, limit),
+                rewrite_steps(&trs, &term, limit),
+                "{} within {} steps",
+                input,
+                limit
+            );
+        }
+    }
+}
+
+#[test]
+fn install_reuses_operators() {
+    let mut sig = Signature::default();
+    let s = sig.new_op(0, Some("S".to_string()));
+    let (cl, _) = install(&mut sig);
+    let (cl2, _) = install(&mut sig);
+
+    assert_eq!(cl.s, s);
+    assert_eq!(cl, cl2);
+    assert_eq!(sig.operators().len(), 4);
+
+    let i = Term::Application {
+        op: cl.i,
+        args: vec![],
+    };
+    let k = Term::Application {
+        op: cl.k,
+        args: vec![],
+    };
+    let term = cl.apply(i, k.clone());
+
+    assert_eq!(cl.reduce_cl(&term, 1), Normalization::Normalized(k));
+}
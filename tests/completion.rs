@@ -0,0 +1,87 @@
+extern crate term_rewriting;
+
+use term_rewriting::{completion::*, orderings::*, *};
+
+fn normal_form(trs: &TRS, term: &Term) -> Term {
+    let mut term = term.clone();
+    while let Some(mut ts) = trs.rewrite(&term, Strategy::Normal) {
+        term = ts.remove(0);
+    }
+    term
+}
+
+#[test]
+fn complete_group() {
+    let mut sig = Signature::default();
+    let inv = sig.new_op(1, Some("I".to_string()));
+    let times = sig.new_op(2, Some("TIMES".to_string()));
+    let e = sig.new_op(0, Some("E".to_string()));
+    let axioms = parse_trs(
+        &mut sig,
+        "TIMES(E x_) = x_;
+         TIMES(I(x_) x_) = E;
+         TIMES(TIMES(x_ y_) z_) = TIMES(x_ TIMES(y_ z_));",
+    )
+    .expect("parse of group axioms");
+    let lpo = Lpo::new(vec![inv, times, e]);
+
+    let group = complete_trs(&mut sig, &axioms, &lpo, 100).expect("completion of group axioms");
+
+    assert_eq!(group.len(), 10);
+    assert!(group.rules().iter().all(|r| lpo.orients(r)));
+    assert!(group
+        .critical_pairs(&mut sig)
+        .iter()
+        .all(|cp| normal_form(&group, &cp.left) == normal_form(&group, &cp.right)));
+
+    let term = parse_term(&mut sig, "TIMES(I(TIMES(A B)) TIMES(A TIMES(B C)))")
+        .expect("parse of TIMES(I(TIMES(A B)) TIMES(A TIMES(B C)))");
+    assert_eq!(normal_form(&group, &term).display(&sig), "C");
+}
+
+#[test]
+fn complete_step_limit() {
+    let mut sig = Signature::default();
+    let inv = sig.new_op(1, Some("I".to_string()));
+    let times = sig.new_op(2, Some("TIMES".to_string()));
+    let e = sig.new_op(0, Some("E".to_string()));
+    let axioms = parse_trs(
+        &mut sig,
+        "TIMES(E x_) = x_;
+         TIMES(I(x_) x_) = E;
+         TIMES(TIMES(x_ y_) z_) = TIMES(x_ TIMES(y_ z_));",
+    )
+    .expect("parse of group axioms");
+    let lpo = Lpo::new(vec![inv, times, e]);
+
+    match complete_trs(&mut sig, &axioms, &lpo, 3) {
+        Err(CompletionError::StepLimit { rules, equations }) => {
+            assert_eq!(rules.len(), 3);
+            assert!(!equations.is_empty());
+        }
+        other => panic!(
+            "expected step limit, got {:?}",
+            other.map(|t| t.display(&sig))
+        ),
+    }
+}
+
+#[test]
+fn complete_unorientable() {
+    let mut sig = Signature::default();
+    let trs = parse_trs(&mut sig, "PLUS(x_ y_) = PLUS(y_ x_);")
+        .expect("parse of PLUS(x_ y_) = PLUS(y_ x_);");
+    let plus = sig.operators()[0];
+
+    match complete_trs(&mut sig, &trs, &Lpo::new(vec![plus]), 10) {
+        Err(CompletionError::Unorientable { lhs, rhs, rules }) => {
+            assert_eq!(lhs.display(&sig), "PLUS(x_ y_)");
+            assert_eq!(rhs.display(&sig), "PLUS(y_ x_)");
+            assert!(rules.is_empty());
+        }
+        other => panic!(
+            "expected unorientable, got {:?}",
+            other.map(|t| t.display(&sig))
+        ),
+    }
+}
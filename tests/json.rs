@@ -0,0 +1,105 @@
+extern crate term_rewriting;
+
+use term_rewriting::*;
+
+#[test]
+fn json_round_trip() {
+    let mut sig = Signature::default();
+    let mut trs = parse_trs(
+        &mut sig,
+        "S x_ y_ z_ = x_ z_ (y_ z_);
+         F(x_ y_) = x_ | y_ <= EQ(x_ y_) = TRUE;",
+    )
+    .expect("parse of TRS");
+    trs.rules_mut()[1].weights = Some(vec![0.25, 0.75]);
+    let json = trs.to_json(&sig);
+
+    let mut sig2 = Signature::default();
+    let trs2 = parse_trs_json(&mut sig2, &json).expect("parse of JSON bundle");
+
+    assert_eq!(trs2.display(&sig2), trs.display(&sig));
+    assert_eq!(trs2.rules()[1].weights, Some(vec![0.25, 0.75]));
+    assert_eq!(trs2.to_json(&sig2), json);
+}
+
+#[test]
+fn json_reuses_operators_and_keeps_unnamed_atoms() {
+    let mut sig = Signature::default();
+    let f = sig.new_op(1, None);
+    let quoted = sig.new_op(0, Some("say \"hi\"\\\n".to_string()));
+    let v = sig.new_var(None);
+    let t = Term::Application {
+        op: f,
+        args: vec![Term::Application {
+            op: quoted,
+            args: vec![],
+        }],
+    };
+    let t = Term::Application {
+        op: sig.new_op(2, Some("P".to_string())),
+        args: vec![t, Term::Variable(v)],
+    };
+
+    let mut sig2 = Signature::default();
+    let p = sig2.new_op(2, Some("P".to_string()));
+    let t2 = parse_term_json(&mut sig2, &t.to_json(&sig)).expect("parse of JSON bundle");
+
+    assert_eq!(t2.head(), Atom::Operator(p));
+    assert_eq!(t2.operators().len(), 3);
+    assert_eq!(
+        t2.operators()[0].name(&sig2),
+        Some("say \"hi\"\\\n".to_string())
+    );
+    assert_eq!(t2.variables()[0].name(&sig2), None);
+    assert_eq!(sig2.operators().len(), 3);
+}
+
+#[test]
+fn json_rejects_malformed_input() {
+    let mut sig = Signature::default();
+    let bad = vec![
+        // truncated
+        r#"{"operators":[["A",0]],"variables":[],"term":{"op":0,"args":[]}"#,
+        // unknown operator index
+        r#"{"operators":[["A",0]],"variables":[],"term":{"op":1,"args":[]}}"#,
+        // wrong arity
+        r#"{"operators":[["A",1]],"variables":[],"term":{"op":0,"args":[]}}"#,
+        // trailing input
+        r#"{"operators":[],"variables":["x"],"term":{"var":0}} {}"#,
+    ];
+    for input in bad {
+        assert!(parse_term_json(&mut sig, input).is_err(), "{}", input);
+    }
+    let fresh = r#"{"operators":[["F",1]],"variables":["x","y"],"rule":{"lhs":{"op":0,"args":[{"var":0}]},"rhs":[{"var":1}],"conditions":[]}}"#;
+    assert!(parse_rule_json(&mut sig, fresh).is_err());
+}
+
+#[test]
+fn json_and_binary_resolve_operators_by_name_and_arity() {
+    let mut sig = Signature::default();
+    let trs =
+        parse_trs(&mut sig, "F(A x_) = G(B); G(x_) = F(x_ x_); K = A;").expect("parse of TRS");
+
+    // the same symbols, created in another order, alongside others
+    let mut sig2 = Signature::default();
+    let ops = parse_term(&mut sig2, "H(B G(F(C A)) F)")
+        .expect("parse of H(B G(F(C A)) F)")
+        .operators();
+    let before = sig2.operators().len();
+
+    let from_json = parse_trs_json(&mut sig2, &trs.to_json(&sig)).expect("parse of JSON bundle");
+    let from_bytes = decode_trs(&mut sig2, &trs.to_bytes(&sig)).expect("decoded TRS");
+
+    for loaded in &[from_json, from_bytes] {
+        assert_eq!(loaded.display(&sig2), trs.display(&sig));
+        for op in loaded.operators() {
+            assert!(
+                ops.contains(&op) || op.display(&sig2) == "K",
+                "{}",
+                op.display(&sig2)
+            );
+        }
+    }
+    // only K is new, and it is created once
+    assert_eq!(sig2.operators().len(), before + 1);
+}
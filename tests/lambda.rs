@@ -0,0 +1,82 @@
+extern crate term_rewriting;
+
+use term_rewriting::{combinators, lambda::*, *};
+
+fn normal_form(sig: &mut Signature, lambda: &Lambda, term: &Term) -> Term {
+    match lambda.normalize(sig, term, 1000) {
+        Normalization::Normalized(t) => t,
+        n => panic!("no normal form for {}: {:?}", term.display(sig), n),
+    }
+}
+
+#[test]
+fn church_numerals() {
+    let mut sig = Signature::default();
+    let lambda = Lambda::new(&mut sig);
+
+    let t = parse_term(
+        &mut sig,
+        "F(LAMBDA(m_ LAMBDA(n_ LAMBDA(f_ LAMBDA(x_ (m_ f_ (n_ f_ x_))))))
+           LAMBDA(f_ LAMBDA(x_ (f_ (f_ x_))))
+           LAMBDA(f_ LAMBDA(x_ (f_ (f_ (f_ x_)))))
+           G(g_ y_))",
+    )
+    .expect("parse of Church numerals");
+    let args = t.args();
+    let (plus, two, three, free) = (&args[0], &args[1], &args[2], args[3].args());
+
+    let sum = lambda.apply(lambda.apply(plus.clone(), two.clone()), three.clone());
+    let applied = lambda.apply(lambda.apply(sum, free[0].clone()), free[1].clone());
+
+    assert_eq!(
+        normal_form(&mut sig, &lambda, &applied).pretty(&sig),
+        "g_ (g_ (g_ (g_ (g_ y_))))"
+    );
+}
+
+#[test]
+fn substitution_avoids_capture() {
+    let mut sig = Signature::default();
+    let lambda = Lambda::new(&mut sig);
+
+    let t = parse_term(&mut sig, "F(LAMBDA(x_ LAMBDA(y_ (x_ y_))) y_)")
+        .expect("parse of F(LAMBDA(x_ LAMBDA(y_ (x_ y_))) y_)");
+    let args = t.args();
+    let term = lambda.apply(args[0].clone(), args[1].clone());
+    let normal = normal_form(&mut sig, &lambda, &term);
+
+    let (bound, body) = lambda.binding(&normal).expect("abstraction");
+
+    assert_ne!(Term::Variable(*bound), args[1]);
+    assert_eq!(lambda.free_variables(&normal), args[1].variables());
+    assert_eq!(*body, lambda.apply(args[1].clone(), Term::Variable(*bound)));
+}
+
+#[test]
+fn combinator_round_trip() {
+    let mut sig = Signature::default();
+    let (cl, _) = combinators::install(&mut sig);
+    let lambda = Lambda::new(&mut sig);
+
+    let t = parse_term(
+        &mut sig,
+        "F(LAMBDA(x_ LAMBDA(y_ (y_ x_)))
+           LAMBDA(f_ LAMBDA(x_ (f_ (f_ x_))))
+           LAMBDA(x_ LAMBDA(y_ LAMBDA(z_ (x_ (y_ z_)))))
+           LAMBDA(x_ (x_ (LAMBDA(y_ y_) z_))))",
+    )
+    .expect("parse of lambda terms");
+
+    for term in t.args() {
+        let cl_term = lambda.to_combinators(&term, &cl).expect("translation");
+
+        assert!(!cl_term.operators().contains(&lambda.lambda));
+
+        let back = lambda.from_combinators(&mut sig, &cl_term, &cl);
+
+        assert!(lambda.alpha_equivalent(
+            &normal_form(&mut sig, &lambda, &back),
+            &normal_form(&mut sig, &lambda, &term)
+        ));
+    }
+}
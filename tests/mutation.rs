@@ -0,0 +1,85 @@
+extern crate rand;
+extern crate term_rewriting;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use term_rewriting::{mutation::*, *};
+
+// Does `rule` satisfy the variable condition?
+fn is_valid(rule: &Rule) -> bool {
+    let lhs_vars = rule.lhs.variables();
+    let conditions = rule.conditions.iter().flat_map(|(c, d)| vec![c, d]);
+    match rule.lhs {
+        Term::Variable(_) => false,
+        Term::Application { .. } => rule
+            .rhs
+            .iter()
+            .chain(conditions)
+            .all(|t| t.variables().iter().all(|v| lhs_vars.contains(v))),
+    }
+}
+
+#[test]
+fn term_mutations_respect_their_shape() {
+    let mut sig = Signature::default();
+    let term = parse_term(&mut sig, "F(G(x_) H(A y_) B)").expect("parse of F(G(x_) H(A y_) B)");
+    parse_term(&mut sig, "K(C D)").expect("parse of K(C D)");
+    let config = TermSampleConfig::default();
+    let mut r = StdRng::from_seed([0; 32]);
+
+    for _ in 0..100 {
+        let mutant = point_mutation(&sig, &term, &mut r).expect("a point mutation");
+        assert_eq!(mutant.size(), term.size());
+        assert_eq!(mutant.variables(), term.variables());
+        assert_ne!(mutant, term);
+
+        let mutant = shrink(&term, &mut r).expect("a shrink");
+        assert!(mutant.size() < term.size());
+
+        let mutant = grow(&sig, &term, &config, 2, &mut r).expect("a grow");
+        assert!(mutant.size() > term.size());
+
+        let mutant = rename_variable(&sig, &term, &mut r).expect("a renaming");
+        assert_eq!(mutant.size(), term.size());
+        assert_eq!(mutant.operators(), term.operators());
+
+        replace_subterm(&sig, &term, &config, 2, &mut r).expect("a replacement");
+    }
+
+    let x = parse_term(&mut sig, "x_").expect("parse of x_");
+    assert_eq!(shrink(&x, &mut r), None);
+    assert_eq!(point_mutation(&sig, &x, &mut r), None);
+}
+
+#[test]
+fn rule_mutations_keep_the_variable_condition() {
+    let mut sig = Signature::default();
+    let trs = parse_trs(
+        &mut sig,
+        "F(x_ G(y_)) = H(y_ x_);
+         G(F(x_ A)) = x_;
+         H(A x_) = B | G(x_);
+         K(x_ y_) = y_ <= x_ = A;",
+    )
+    .expect("parse of rules");
+    parse_term(&mut sig, "J(C D)").expect("parse of J(C D)");
+    let config = TermSampleConfig::default();
+    let mut r = StdRng::from_seed([0; 32]);
+
+    for rule in trs.rules() {
+        for _ in 0..100 {
+            let mutants = vec![
+                replace_rule_subterm(&sig, rule, &config, 2, &mut r),
+                point_mutation_rule(&sig, rule, &mut r),
+                rename_rule_variable(&sig, rule, &mut r),
+                grow_rule(&sig, rule, &config, 2, &mut r),
+                shrink_rule(&sig, rule, &mut r),
+            ];
+            for mutant in mutants.into_iter().flatten() {
+                assert!(is_valid(&mutant), "{}", mutant.display(&sig));
+                assert_eq!(mutant.len(), rule.len());
+                assert_eq!(mutant.conditions.len(), rule.conditions.len());
+            }
+        }
+    }
+}
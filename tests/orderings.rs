@@ -0,0 +1,108 @@
+extern crate term_rewriting;
+
+use term_rewriting::{orderings::*, *};
+
+#[test]
+fn lpo_orients() {
+    let mut sig = Signature::default();
+    let ack = sig.new_op(2, Some("ACK".to_string()));
+    let succ = sig.new_op(1, Some("SUCC".to_string()));
+    let trs = parse_trs(
+        &mut sig,
+        "ACK(ZERO y_) = SUCC(y_);
+         ACK(SUCC(x_) ZERO) = ACK(x_ SUCC(ZERO));
+         ACK(SUCC(x_) SUCC(y_)) = ACK(x_ ACK(SUCC(x_) y_));",
+    )
+    .expect("parse of ackermann");
+    let lpo = Lpo::new(vec![ack, succ]);
+
+    assert!(trs.rules().iter().all(|r| lpo.orients(r)));
+
+    // without ACK > SUCC, the first rule cannot be oriented.
+    let lpo = Lpo::new(vec![succ, ack]);
+    assert!(!lpo.orients(&trs.rules()[0]));
+}
+
+#[test]
+fn lpo_compare() {
+    let mut sig = Signature::default();
+    let f = sig.new_op(1, Some("F".to_string()));
+    let g = sig.new_op(1, Some("G".to_string()));
+    let term = parse_term(&mut sig, "P(F(G(x_)) G(F(x_)) x_)").expect("parse of P(...)");
+    let args = term.args();
+    let lpo = Lpo::new(vec![f, g]);
+
+    assert!(lpo.gt(&args[0], &args[1]));
+    assert!(!lpo.gt(&args[1], &args[0]));
+    assert!(lpo.gt(&args[0], &args[2]));
+    assert!(!lpo.gt(&args[2], &args[0]));
+    assert!(lpo.ge(&args[2], &args[2]));
+    assert!(!lpo.gt(&args[2], &args[2]));
+
+    assert_eq!(
+        lpo.precedence().compare(&f, &g),
+        Some(::std::cmp::Ordering::Greater)
+    );
+    let h = sig.new_op(0, Some("H".to_string()));
+    assert_eq!(lpo.precedence().compare(&f, &h), None);
+}
+
+#[test]
+fn kbo_orients() {
+    let mut sig = Signature::default();
+    let f = sig.new_op(1, Some("F".to_string()));
+    let g = sig.new_op(2, Some("G".to_string()));
+    let a = sig.new_op(0, Some("A".to_string()));
+    let trs = parse_trs(
+        &mut sig,
+        "F(F(x_)) = G(x_ A);
+         G(x_ x_) = F(x_);",
+    )
+    .expect("parse of F(F(x_)) = G(x_ A); G(x_ x_) = F(x_);");
+    let kbo = Kbo::new(vec![(f, 2), (g, 2), (a, 1)], 1, vec![f, g, a]).expect("admissible weights");
+
+    assert!(kbo.orients_trs(&trs));
+
+    // duplicating rules cannot be oriented by KBO.
+    let dup = parse_rule(&mut sig, "F(x_) = G(x_ x_)").expect("parse of F(x_) = G(x_ x_)");
+    assert!(!kbo.orients(&dup));
+}
+
+#[test]
+fn kbo_compare() {
+    let mut sig = Signature::default();
+    let f = sig.new_op(1, Some("F".to_string()));
+    let g = sig.new_op(1, Some("G".to_string()));
+    let term = parse_term(&mut sig, "P(F(G(x_)) G(F(x_)) F(F(x_)) x_)").expect("parse of P(...)");
+    let args = term.args();
+    let kbo = Kbo::new(vec![(f, 0)], 1, vec![f, g]).expect("admissible weights");
+
+    // equal weights, so the precedence decides.
+    assert!(kbo.gt(&args[0], &args[1]));
+    assert!(!kbo.gt(&args[1], &args[0]));
+    // F(F(x_)) and x_ have equal weight.
+    assert!(kbo.gt(&args[2], &args[3]));
+    assert!(!kbo.gt(&args[3], &args[2]));
+    assert!(!kbo.gt(&args[3], &args[3]));
+}
+
+#[test]
+fn kbo_admissibility() {
+    let mut sig = Signature::default();
+    let f = sig.new_op(1, Some("F".to_string()));
+    let a = sig.new_op(0, Some("A".to_string()));
+
+    assert_eq!(
+        Kbo::new(vec![], 0, vec![]),
+        Err(KboError::VariableWeightZero)
+    );
+    assert_eq!(
+        Kbo::new(vec![(a, 1)], 2, vec![]),
+        Err(KboError::ConstantTooLight(a))
+    );
+    assert_eq!(
+        Kbo::new(vec![(f, 0), (a, 1)], 1, vec![a, f]),
+        Err(KboError::UnaryWeightZero(f))
+    );
+    assert!(Kbo::new(vec![(f, 0), (a, 1)], 1, vec![f, a]).is_ok());
+}
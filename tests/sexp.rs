@@ -0,0 +1,51 @@
+extern crate term_rewriting;
+
+use term_rewriting::*;
+
+#[test]
+fn sexp_round_trip() {
+    let mut sig = Signature::default();
+    let trs = parse_trs(
+        &mut sig,
+        "S x_ y_ z_ = x_ z_ (y_ z_);
+         PLUS(SUCC(x_) y_) = PLUS(x_ SUCC(y_)) | SUCC(PLUS(x_ y_));
+         F(x_ y_) = x_ <= EQ(x_ y_) = TRUE;",
+    )
+    .expect("parse of TRS");
+
+    let sexp = trs.to_sexp(&sig);
+    let trs2 = parse_trs_sexp(&mut sig, &sexp).expect("parse of s-expression TRS");
+
+    assert!(TRS::alphas(&trs, &trs2));
+    assert_eq!(trs2.to_sexp(&sig), sexp);
+    for rule in trs.rules() {
+        let sexp = rule.to_sexp(&sig);
+        let rule2 = parse_rule_sexp(&mut sig, &sexp).expect("parse of s-expression rule");
+        assert_eq!(rule2.to_sexp(&sig), rule.to_sexp(&sig));
+        assert_eq!(rule2.conditions.len(), rule.conditions.len());
+    }
+}
+
+#[test]
+fn sexp_is_canonical_up_to_variable_names() {
+    let mut sig = Signature::default();
+    let t1 = parse_term(&mut sig, "F(x_ G(y_ x_))").expect("parse of F(x_ G(y_ x_))");
+    let t2 = parse_term(&mut sig, "F(b_ G(a_ b_))").expect("parse of F(b_ G(a_ b_))");
+    let t3 = parse_term(&mut sig, "F(x_ G(x_ y_))").expect("parse of F(x_ G(x_ y_))");
+
+    assert_eq!(t1.to_sexp(&sig), "(F v0 (G v1 v0))");
+    assert_eq!(t1.to_sexp(&sig), t2.to_sexp(&sig));
+    assert_ne!(t1.to_sexp(&sig), t3.to_sexp(&sig));
+}
+
+#[test]
+fn sexp_rejects_malformed_input() {
+    let mut sig = Signature::default();
+    assert!(parse_term_sexp(&mut sig, "(F (A)").is_err());
+    assert!(parse_term_sexp(&mut sig, "(F) (G)").is_err());
+    assert!(parse_term_sexp(&mut sig, "((F) x)").is_err());
+    assert!(parse_rule_sexp(&mut sig, "(= x (A))").is_err());
+    assert!(parse_rule_sexp(&mut sig, "(= (F x) y)").is_err());
+    assert!(parse_rule_sexp(&mut sig, "(<= (= (F x) x) (= x))").is_err());
+    assert!(parse_trs_sexp(&mut sig, "(= (A) (B))").is_err());
+}
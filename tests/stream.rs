@@ -0,0 +1,78 @@
+extern crate term_rewriting;
+
+use std::io::{self, BufRead, BufReader, Read};
+use term_rewriting::*;
+
+#[test]
+fn stream_matches_parse_trs() {
+    let input = "ops: F/1, G/0;
+        # a comment with a ; in it
+        F(x_) = G; F(G) =
+          F(F(G)); # trailing comment
+        F('a;b') = \"x;y\";
+        don't = G;";
+    let mut sig = Signature::default();
+    let rules = parse_trs_stream(&mut sig, BufReader::new(input.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("parse of stream");
+    let displays = rules.iter().map(|r| r.display(&sig)).collect::<Vec<_>>();
+
+    let mut sig2 = Signature::default();
+    let trs = parse_trs(&mut sig2, input).expect("parse of TRS");
+    let expected = trs
+        .rules()
+        .iter()
+        .map(|r| r.display(&sig))
+        .collect::<Vec<_>>();
+
+    assert_eq!(displays, expected);
+    assert_eq!(sig.operators().len(), sig2.operators().len());
+}
+
+#[test]
+fn stream_reports_positions_in_whole_input() {
+    let mut sig = Signature::default();
+    let input = "A = B;\nC = D; E = (F;\nG = H;";
+    let mut rules = parse_trs_stream(&mut sig, input.as_bytes());
+
+    assert!(rules.next().unwrap().is_ok());
+    assert!(rules.next().unwrap().is_ok());
+    match rules.next() {
+        Some(Err(ParseError::Syntax(e))) => {
+            assert_eq!((e.offset, e.line, e.column), (20, 2, 14));
+            assert_eq!(e.source, "C = D; E = (F;");
+        }
+        other => panic!("expected a syntax error, found {:?}", other),
+    }
+    assert!(rules.next().is_none());
+}
+
+#[test]
+fn stream_rejects_unterminated_input() {
+    let mut sig = Signature::default();
+    let mut rules = parse_trs_stream(&mut sig, "A = B;\nC = D".as_bytes());
+
+    assert!(rules.next().unwrap().is_ok());
+    assert!(rules.next().unwrap().is_err());
+    assert!(rules.next().is_none());
+}
+
+struct Failing;
+impl Read for Failing {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk on fire"))
+    }
+}
+
+#[test]
+fn stream_reports_io_errors() {
+    let mut sig = Signature::default();
+    let source: Box<dyn BufRead> = Box::new(BufReader::new(Failing));
+    let mut rules = parse_trs_stream(&mut sig, source);
+
+    assert_eq!(
+        rules.next().unwrap().unwrap_err(),
+        ParseError::Io("disk on fire".to_string())
+    );
+    assert!(rules.next().is_none());
+}
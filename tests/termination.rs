@@ -0,0 +1,58 @@
+extern crate term_rewriting;
+
+use term_rewriting::orderings::Lpo;
+use term_rewriting::termination::dp::*;
+use term_rewriting::*;
+
+#[test]
+fn dp_ackermann() {
+    let mut sig = Signature::default();
+    let trs = parse_trs(
+        &mut sig,
+        "ACK(ZERO y_) = SUCC(y_);
+         ACK(SUCC(x_) ZERO) = ACK(x_ SUCC(ZERO));
+         ACK(SUCC(x_) SUCC(y_)) = ACK(x_ ACK(SUCC(x_) y_));",
+    )
+    .expect("parse of ackermann");
+
+    let pairs: Vec<String> = dependency_pairs(&mut sig, &trs)
+        .iter()
+        .map(|p| p.display(&sig))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![
+            "ACK#(SUCC(x_) ZERO) -> ACK#(x_ SUCC(ZERO))",
+            "ACK#(SUCC(x_) SUCC(y_)) -> ACK#(x_ ACK(SUCC(x_) y_))",
+            "ACK#(SUCC(x_) SUCC(y_)) -> ACK#(SUCC(x_) y_)",
+        ]
+    );
+    assert_eq!(
+        DependencyGraph::new(&mut sig, &trs).sccs(),
+        vec![vec![0, 1, 2]]
+    );
+
+    // the subterm criterion removes two pairs, and then the third.
+    assert!(prove_termination(&mut sig, &trs, &[&SubtermCriterion]).is_ok());
+}
+
+#[test]
+fn dp_nonterminating() {
+    let mut sig = Signature::default();
+    let trs = parse_trs(
+        &mut sig,
+        "F(A x_) = G(x_);
+         G(B) = F(A B);
+         H(x_) = F(A x_);",
+    )
+    .expect("parse of F(A x_) = G(x_); G(B) = F(A B); H(x_) = F(A x_);");
+    let lpo = ReductionPairProcessor::new(Lpo::new(vec![]));
+
+    let cycles = prove_termination(&mut sig, &trs, &[&lpo, &SubtermCriterion]).unwrap_err();
+    let cycles: Vec<Vec<String>> = cycles
+        .iter()
+        .map(|c| c.iter().map(|p| p.display(&sig)).collect())
+        .collect();
+
+    assert_eq!(cycles, vec![vec!["F#(A x_) -> G#(x_)", "G#(B) -> F#(A B)"]]);
+}
@@ -0,0 +1,85 @@
+extern crate term_rewriting;
+
+use term_rewriting::*;
+
+#[test]
+fn tpdb_round_trip() {
+    let input = "(VAR x y xs)
+         (COMMENT list reversal (with an accumulator))
+         (RULES
+           rev(xs) -> revacc(xs,nil)
+           revacc(nil, y) -> y
+           revacc(cons(x, xs), y) -> revacc(xs, cons(x, y))
+           f(x) -> x | eq(x, nil()) == true
+         )
+         (STRATEGY INNERMOST)";
+    let mut sig = Signature::default();
+    let trs = parse_trs_tpdb(&mut sig, input).expect("parse of TPDB problem");
+
+    assert_eq!(trs.len(), 4);
+    assert_eq!(trs.rules()[2].lhs.display(&sig), "revacc(cons(x_ xs_) y_)");
+    assert_eq!(
+        trs.rules()[3].display(&sig),
+        "f(x_) = x_ <= eq(x_ nil) = true"
+    );
+
+    let output = trs.to_tpdb(&sig);
+    let mut sig2 = Signature::default();
+    let trs2 = parse_trs_tpdb(&mut sig2, &output).expect("parse of exported TPDB problem");
+
+    assert_eq!(trs2.display(&sig2), trs.display(&sig));
+    assert_eq!(trs2.to_tpdb(&sig2), output);
+}
+
+#[test]
+fn tpdb_rejects_malformed_input() {
+    let mut sig = Signature::default();
+    let bad = vec![
+        // missing closing paren
+        "(VAR x) (RULES f(x) -> x",
+        // applied variable
+        "(VAR x) (RULES x(a) -> a)",
+        // fresh variable on the right-hand side
+        "(VAR x y) (RULES f(x) -> y)",
+        // relative rule
+        "(VAR x) (RULES f(x) ->= x)",
+    ];
+    for input in bad {
+        assert!(parse_trs_tpdb(&mut sig, input).is_err(), "{}", input);
+    }
+}
+
+#[test]
+fn cops_round_trip() {
+    let input = "(CONDITIONTYPE SEMI-EQUATIONAL)
+         (VAR x y)
+         (RULES
+           f(x, y) -> x | x == y
+           g(x) -> f(x, x)
+         )
+         (COMMENT
+           @origin COPS #42
+           doi:10.1007/978-3-540-32033-3_27
+           Example 3 (with a nested comment)
+         )";
+    let mut sig = Signature::default();
+    let problem = parse_cops(&mut sig, input).expect("parse of COPS problem");
+
+    assert_eq!(
+        problem.trs.condition_config().mode,
+        ConditionMode::SemiEquational
+    );
+    assert_eq!(problem.metadata.len(), 2);
+    assert_eq!(
+        problem.metadata[1].1,
+        "doi:10.1007/978-3-540-32033-3_27\nExample 3 (with a nested comment)"
+    );
+
+    let output = problem.to_cops(&sig);
+    let mut sig2 = Signature::default();
+    let problem2 = parse_cops(&mut sig2, &output).expect("parse of exported COPS problem");
+
+    assert_eq!(problem2.trs.display(&sig2), problem.trs.display(&sig));
+    assert_eq!(problem2.metadata, problem.metadata);
+    assert_eq!(problem2.to_cops(&sig2), output);
+}
@@ -14,14 +14,20 @@ fn trace_step() {
     let (trs, mut terms) = parse(&mut sig, inp).unwrap();
     let mut term = terms.pop().unwrap();
     let mut trace = Trace::new(&trs, &term, 0.2, 1.0, None, Strategy::Normal);
-    let expected = vec!["PLUS(3, 1)", "PLUS(2, 2)", "PLUS(1, 3)", "PLUS(0, 4)", "4"];
+    let expected = vec![
+        "PLUS(SUCC(SUCC(SUCC(ZERO))), SUCC(ZERO))",
+        "PLUS(SUCC(SUCC(ZERO)), SUCC(SUCC(ZERO)))",
+        "PLUS(SUCC(ZERO), SUCC(SUCC(SUCC(ZERO))))",
+        "PLUS(ZERO, SUCC(SUCC(SUCC(SUCC(ZERO)))))",
+        "SUCC(SUCC(SUCC(SUCC(ZERO))))",
+    ];
     let mut node;
     for x in expected {
         node = trace
             .next()
-            .unwrap_or_else(|| panic!("trace step from {} unsuccessful", term.pretty()));
+            .unwrap_or_else(|| panic!("trace step from {} unsuccessful", term.pretty(&sig)));
         term = node.term();
-        assert_eq!(term.pretty(), x)
+        assert_eq!(term.pretty(&sig), x)
     }
     assert!(trace.next().is_none());
 }
@@ -43,7 +49,10 @@ fn trace_rewrite() {
     // leaves
     let leaf_terms_unobserved = trace.root().leaf_terms(&[TraceState::Unobserved]);
     assert_eq!(leaf_terms_unobserved.len(), 1);
-    assert_eq!(leaf_terms_unobserved[0].pretty(), "PLUS(0, 4)");
+    assert_eq!(
+        leaf_terms_unobserved[0].pretty(&sig),
+        "PLUS(ZERO, SUCC(SUCC(SUCC(SUCC(ZERO)))))"
+    );
     let leaf_terms_other = trace.root().leaf_terms(&[
         TraceState::Normal,
         TraceState::Rewritten,
@@ -63,9 +72,18 @@ fn trace_rewrite() {
         .map(|n| n.term())
         .collect::<Vec<_>>();
     assert_eq!(terms_r.len(), 3);
-    assert_eq!(terms_r[0].pretty(), "PLUS(3, 1)");
-    assert_eq!(terms_r[1].pretty(), "PLUS(2, 2)");
-    assert_eq!(terms_r[2].pretty(), "PLUS(1, 3)");
+    assert_eq!(
+        terms_r[0].pretty(&sig),
+        "PLUS(SUCC(SUCC(SUCC(ZERO))), SUCC(ZERO))"
+    );
+    assert_eq!(
+        terms_r[1].pretty(&sig),
+        "PLUS(SUCC(SUCC(ZERO)), SUCC(SUCC(ZERO)))"
+    );
+    assert_eq!(
+        terms_r[2].pretty(&sig),
+        "PLUS(SUCC(ZERO), SUCC(SUCC(SUCC(ZERO))))"
+    );
     let terms_u = trace
         .root()
         .progeny(&[TraceState::Unobserved])
@@ -73,5 +91,75 @@ fn trace_rewrite() {
         .map(|n| n.term())
         .collect::<Vec<_>>();
     assert_eq!(terms_u.len(), 1);
-    assert_eq!(terms_u[0].pretty(), "PLUS(0, 4)");
+    assert_eq!(
+        terms_u[0].pretty(&sig),
+        "PLUS(ZERO, SUCC(SUCC(SUCC(SUCC(ZERO)))))"
+    );
+}
+
+#[test]
+fn trace_terms_by_probability() {
+    let mut sig = Signature::default();
+    let inp = "
+         A = B | C(D) | E;
+         C(x_) = B | x_;
+
+         A;"
+    .trim();
+    let (trs, mut terms) = parse(&mut sig, inp).unwrap();
+    let term = terms.pop().unwrap();
+    let mut trace = Trace::new(&trs, &term, 1.0, 1.0, None, Strategy::Normal);
+
+    let outcomes = trace
+        .terms_by_probability(10)
+        .into_iter()
+        .map(|(t, lp)| (t.display(&sig), lp.exp()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].0, "B");
+    assert!((outcomes[0].1 - 0.5).abs() < 1e-9);
+    assert_eq!(outcomes[1].0, "E");
+    assert!((outcomes[1].1 - 1.0 / 3.0).abs() < 1e-9);
+    assert_eq!(outcomes[2].0, "D");
+    assert!((outcomes[2].1 - 1.0 / 6.0).abs() < 1e-9);
+}
+
+#[test]
+fn trace_log_likelihood() {
+    let mut sig = Signature::default();
+    let inp = "
+         PLUS(SUCC(x_) y_) = PLUS(x_ SUCC(y_));
+         PLUS(ZERO x_) = x_;
+
+         PLUS(SUCC(ZERO) SUCC(ZERO));
+         SUCC(SUCC(ZERO));
+         SUCC(SUCC(SUCC(ZERO)));"
+        .trim();
+    let (trs, terms) = parse(&mut sig, inp).unwrap();
+    let mut params = LikelihoodParams {
+        p_observe: 0.5,
+        max_steps: 10,
+        max_term_size: None,
+        strategy: Strategy::Normal,
+        noise: Noise::Exact,
+    };
+    let right = [(terms[0].clone(), terms[1].clone())];
+    let wrong = [(terms[0].clone(), terms[2].clone())];
+
+    // two steps, each observed with probability 0.5
+    assert!((trs.log_likelihood(&right, &params) - 0.25f64.ln()).abs() < 1e-9);
+    assert_eq!(trs.log_likelihood(&wrong, &params), f64::NEG_INFINITY);
+    assert_eq!(trs.log_likelihood(&[], &params), 0.0);
+
+    // SUCC is inserted once
+    let dist = PTermDist::new(0.1, 0.1, 0.8, 0.1).unwrap();
+    params.noise = Noise::Edit { p: 0.2, dist };
+    let expected = 0.25f64.ln() + 0.2f64.ln() + TRS::p_term(&terms[1], &terms[2], &dist);
+    assert!((trs.log_likelihood(&wrong, &params) - expected).abs() < 1e-9);
+    assert!(trs.log_likelihood(&right, &params) > trs.log_likelihood(&wrong, &params));
+
+    // nothing is reached in too few steps
+    params.max_steps = 2;
+    assert_eq!(trs.log_likelihood(&right, &params), f64::NEG_INFINITY);
 }
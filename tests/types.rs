@@ -1,6 +1,6 @@
 extern crate term_rewriting;
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use term_rewriting::*;
 
@@ -62,59 +62,59 @@ fn signature_parse() {
     let y2 = &vars[4];
 
     let s_lhs = Term::Application {
-        op: a.clone(),
+        op: *a,
         args: vec![
             Term::Application {
-                op: a.clone(),
+                op: *a,
                 args: vec![
                     Term::Application {
-                        op: a.clone(),
+                        op: *a,
                         args: vec![
                             Term::Application {
-                                op: s.clone(),
+                                op: *s,
                                 args: vec![],
                             },
-                            Term::Variable(x.clone()),
+                            Term::Variable(*x),
                         ],
                     },
-                    Term::Variable(y.clone()),
+                    Term::Variable(*y),
                 ],
             },
-            Term::Variable(z.clone()),
+            Term::Variable(*z),
         ],
     };
     let s_rhs = vec![Term::Application {
-        op: a.clone(),
+        op: *a,
         args: vec![
             Term::Application {
-                op: a.clone(),
-                args: vec![Term::Variable(x.clone()), Term::Variable(z.clone())],
+                op: *a,
+                args: vec![Term::Variable(*x), Term::Variable(*z)],
             },
             Term::Application {
-                op: a.clone(),
-                args: vec![Term::Variable(y.clone()), Term::Variable(z.clone())],
+                op: *a,
+                args: vec![Term::Variable(*y), Term::Variable(*z)],
             },
         ],
     }];
     let s_rule = Rule::new(s_lhs, s_rhs).expect("new S rule");
 
     let k_lhs = Term::Application {
-        op: a.clone(),
+        op: *a,
         args: vec![
             Term::Application {
-                op: a.clone(),
+                op: *a,
                 args: vec![
                     Term::Application {
-                        op: k.clone(),
+                        op: *k,
                         args: vec![],
                     },
-                    Term::Variable(x2.clone()),
+                    Term::Variable(*x2),
                 ],
             },
-            Term::Variable(y2.clone()),
+            Term::Variable(*y2),
         ],
     };
-    let k_rhs = vec![Term::Variable(x2.clone())];
+    let k_rhs = vec![Term::Variable(*x2)];
     let k_rule = Rule::new(k_lhs, k_rhs).expect("new K rule");
 
     let trs2 = TRS::new(vec![s_rule, k_rule]);
@@ -140,11 +140,11 @@ fn unify_test() {
     let y2 = &vars[2];
 
     let t1_0 = Term::Application {
-        op: s.clone(),
+        op: s,
         args: vec![],
     };
     let t1_1 = Term::Application {
-        op: k.clone(),
+        op: k,
         args: vec![],
     };
     let mut hm1 = HashMap::new();
@@ -154,23 +154,23 @@ fn unify_test() {
     assert_eq!(None, Term::unify(vec![(&t1, &t3)]));
     assert_eq!(None, Term::unify(vec![(&t2, &t3)]));
     let t2 = Term::Application {
-        op: a.clone(),
+        op: a,
         args: vec![
             Term::Application {
-                op: a.clone(),
+                op: a,
                 args: vec![
                     Term::Application {
-                        op: k.clone(),
+                        op: k,
                         args: vec![],
                     },
                     Term::Application {
-                        op: k.clone(),
+                        op: k,
                         args: vec![],
                     },
                 ],
             },
             Term::Application {
-                op: k.clone(),
+                op: k,
                 args: vec![],
             },
         ],
@@ -204,10 +204,10 @@ fn display_variable() {
     let v1 = sig.new_var(None);
     let v2 = sig.new_var(Some("blah".to_string()));
 
-    assert_eq!(v1.display(), "var0_".to_string());
-    assert_eq!(v1.name(), None);
-    assert_eq!(v2.display(), "blah_".to_string());
-    assert_eq!(v2.name(), Some("blah".to_string()));
+    assert_eq!(v1.display(&sig), "var0_".to_string());
+    assert_eq!(v1.name(&sig), None);
+    assert_eq!(v2.display(&sig), "blah_".to_string());
+    assert_eq!(v2.name(&sig), Some("blah".to_string()));
 }
 
 #[test]
@@ -216,9 +216,9 @@ fn operator_methods() {
 
     let o = sig.new_op(0, None);
 
-    assert_eq!(o.display(), "op0");
+    assert_eq!(o.display(&sig), "op0");
     assert_eq!(o.arity(), 0);
-    assert_eq!(o.name(), None);
+    assert_eq!(o.name(&sig), None);
 }
 
 #[test]
@@ -245,10 +245,38 @@ fn atom_methods() {
     assert_ne!(a2, a3);
     assert_ne!(a1, a2);
     // test display
-    assert_eq!(a0.display(), "op0");
-    assert_eq!(a1.display(), "A");
-    assert_eq!(a2.display(), "var0_");
-    assert_eq!(a3.display(), "X_");
+    assert_eq!(a0.display(&sig), "op0");
+    assert_eq!(a1.display(&sig), "A");
+    assert_eq!(a2.display(&sig), "var0_");
+    assert_eq!(a3.display(&sig), "X_");
+}
+
+#[test]
+fn atom_equality_and_hashing() {
+    let mut sig1 = Signature::default();
+    let mut sig2 = Signature::default();
+    let a = sig1.new_op(0, Some("A".to_string()));
+    let b = sig1.new_op(0, Some("B".to_string()));
+    let x = sig1.new_var(Some("x".to_string()));
+    let a2 = sig2.new_op(0, Some("A".to_string()));
+    sig2.new_op(0, Some("B".to_string()));
+    sig2.new_var(Some("x".to_string()));
+
+    // Atoms are compared by id and arity, whatever their Signature.
+    assert_eq!(a, a2);
+    assert_ne!(a, b);
+    let mut ops = HashMap::new();
+    ops.insert(a, 1);
+    ops.insert(b, 2);
+    assert_eq!(ops.get(&a2), Some(&1));
+    sig2.new_op(0, Some("C".to_string()));
+    assert_ne!(sig1, sig2);
+    assert_eq!(a, a2);
+    assert_eq!(ops.get(&a2), Some(&1));
+
+    let mut vars = HashMap::new();
+    vars.insert(x, 0);
+    assert_eq!(vars.get(&x), Some(&0));
 }
 
 #[test]
@@ -314,7 +342,7 @@ fn parse_display_roundtrip_term() {
     let mut sig = Signature::default();
     let s = "foo(bar(x_) y_ baz)";
     let term = parse_term(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
-    let ns = term.display();
+    let ns = term.display(&sig);
     assert_eq!(s, ns);
 }
 
@@ -323,7 +351,7 @@ fn parse_display_roundtrip_rule() {
     let mut sig = Signature::default();
     let s = "foo(bar(x_) y_ baz) = bar(y_) | buzz";
     let rule = parse_rule(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
-    let ns = rule.display();
+    let ns = rule.display(&sig);
     assert_eq!(s, ns);
 }
 
@@ -332,7 +360,7 @@ fn parse_display_roundtrip_trs() {
     let mut sig = Signature::default();
     let s = "foo(bar(x_) y_ baz) = bar(y_) | buzz;\nbar(baz) = foo(bar(baz) bar(baz) baz);";
     let trs = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
-    let ns = trs.display();
+    let ns = trs.display(&sig);
     assert_eq!(s, ns);
 }
 
@@ -344,8 +372,8 @@ fn pretty_term_application() {
         (0, Some("K".to_string())),
     ]);
     let t = parse_term(&mut sig, "S K S K").expect("parse of S K S K");
-    assert_eq!(t.display(), ".(.(.(S K) S) K)");
-    assert_eq!(t.pretty(), "S K S K");
+    assert_eq!(t.display(&sig), ".(.(.(S K) S) K)");
+    assert_eq!(t.pretty(&sig), "S K S K");
 }
 
 #[test]
@@ -357,12 +385,12 @@ fn pretty_term_list() {
     ]);
     let t = parse_term(&mut sig, "CONS(A CONS(A CONS(A NIL)))")
         .expect("parse of CONS(A CONS(A CONS(A NIL)))");
-    assert_eq!(t.display(), "CONS(A CONS(A CONS(A NIL)))");
-    assert_eq!(t.pretty(), "[A, A, A]");
+    assert_eq!(t.display(&sig), "CONS(A CONS(A CONS(A NIL)))");
+    assert_eq!(t.pretty(&sig), "[A, A, A]");
 
     let t = parse_term(&mut sig, "CONS(A A)").expect("parse of term");
-    assert_eq!(t.display(), "CONS(A A)");
-    assert_eq!(t.pretty(), "CONS(A, A)");
+    assert_eq!(t.display(&sig), "CONS(A A)");
+    assert_eq!(t.pretty(&sig), "CONS(A, A)");
 }
 
 #[test]
@@ -387,24 +415,40 @@ fn pretty_term_number() {
     // test normal unary
     let t = parse_term(&mut sig, "FOO(SUCC(SUCC(SUCC(ZERO))) SUCC(ZERO))")
         .expect("parse of FOO(SUCC(SUCC(SUCC(ZERO))) SUCC(ZERO))");
-    assert_eq!(t.display(), "FOO(SUCC(SUCC(SUCC(ZERO))) SUCC(ZERO))");
-    assert_eq!(t.pretty(), "FOO(3, 1)");
+    assert_eq!(t.display(&sig), "FOO(SUCC(SUCC(SUCC(ZERO))) SUCC(ZERO))");
+    assert_eq!(t.pretty(&sig), "FOO(SUCC(SUCC(SUCC(ZERO))), SUCC(ZERO))");
 
     // test broken unary
     let t = parse_term(&mut sig, "SUCC(SUCC(ONE))").expect("parse of term");
-    assert_eq!(t.display(), "SUCC(SUCC(ONE))");
-    assert_eq!(t.pretty(), "SUCC(SUCC(ONE))");
+    assert_eq!(t.display(&sig), "SUCC(SUCC(ONE))");
+    assert_eq!(t.pretty(&sig), "SUCC(SUCC(ONE))");
 
     // test normal decimal
-    let t = parse_term(&mut sig, "FOO(DIGIT(ZERO) DECC(DECC(DECC(DECC(DECC(DECC(DECC(DECC(DIGIT(NINE) EIGHT) SEVEN) SIX) FIVE) FOUR) THREE) TWO) ONE))")
-        .expect("parse of term");
-    assert_eq!(t.display(), "FOO(DIGIT(ZERO) DECC(DECC(DECC(DECC(DECC(DECC(DECC(DECC(DIGIT(NINE) EIGHT) SEVEN) SIX) FIVE) FOUR) THREE) TWO) ONE))");
-    assert_eq!(t.pretty(), "FOO(0, 987654321)");
+    let t = parse_term(
+        &mut sig,
+        "FOO(DIGIT(0) DECC(DECC(DECC(DECC(DECC(DECC(DECC(DECC(DIGIT(9) 8) 7) 6) 5) 4) 3) 2) 1))",
+    )
+    .expect("parse of term");
+    assert_eq!(
+        t.display(&sig),
+        "FOO(DIGIT(0) DECC(DECC(DECC(DECC(DECC(DECC(DECC(DECC(DIGIT(9) 8) 7) 6) 5) 4) 3) 2) 1))"
+    );
+    assert_eq!(t.pretty(&sig), "FOO(0, 987654321)");
+
+    // test named digits
+    let t = parse_term(&mut sig, "DECC(DIGIT(FOUR) TWO)").expect("parse of term");
+    assert_eq!(t.display(&sig), "DECC(DIGIT(FOUR) TWO)");
+    assert_eq!(t.pretty(&sig), "DECC(DIGIT(FOUR), TWO)");
+
+    // test digits outside numbers
+    let t = parse_term(&mut sig, "FOO(4 DIGIT(DIGIT(4)))").expect("parse of term");
+    assert_eq!(t.display(&sig), "FOO(4 DIGIT(DIGIT(4)))");
+    assert_eq!(t.pretty(&sig), "FOO('4', DIGIT(4))");
 
     // test broken decimal
     let t = parse_term(&mut sig, "DIGIT(FOO(ONE NINE))").expect("parse of term");
-    assert_eq!(t.display(), "DIGIT(FOO(ONE NINE))");
-    assert_eq!(t.pretty(), "DIGIT(FOO(ONE, NINE))");
+    assert_eq!(t.display(&sig), "DIGIT(FOO(ONE NINE))");
+    assert_eq!(t.pretty(&sig), "DIGIT(FOO(ONE, NINE))");
 }
 
 #[test]
@@ -416,8 +460,8 @@ fn pretty_term_nonspecial() {
     ]);
     let t = parse_term(&mut sig, "BAZ(BAZ(BAR(BAR(FOO)) FOO) FOO)")
         .expect("parse of BAZ(BAZ(BAR(BAR(FOO)) FOO) FOO)");
-    assert_eq!(t.display(), "BAZ(BAZ(BAR(BAR(FOO)) FOO) FOO)");
-    assert_eq!(t.pretty(), "BAZ(BAZ(BAR(BAR(FOO)), FOO), FOO)");
+    assert_eq!(t.display(&sig), "BAZ(BAZ(BAR(BAR(FOO)) FOO) FOO)");
+    assert_eq!(t.pretty(&sig), "BAZ(BAZ(BAR(BAR(FOO)), FOO), FOO)");
 }
 
 #[test]
@@ -440,7 +484,7 @@ fn pretty_trs() {
         BAZ(FOO BAR(x_)) = BAZ(x_ FOO) | SUCC(x_);";
     let (trs, _) = parse(&mut sig, s).expect("parse of pretty_trs");
     assert_eq!(
-        trs.display(),
+        trs.display(&sig),
         "\
 .(.(.(S x_) y_) z_) = .(.(x_ z_) .(y_ z_));
 .(.(K x_) y_) = x_;
@@ -448,11 +492,254 @@ CONS(FOO CONS(FOO NIL)) = SUCC(SUCC(ZERO));
 BAZ(FOO BAR(x_)) = BAZ(x_ FOO) | SUCC(x_);"
     );
     assert_eq!(
-        trs.pretty(),
+        trs.pretty(&sig),
         "\
 S x_ y_ z_ = x_ z_ (y_ z_);
 K x_ y_ = x_;
-[FOO, FOO] = 2;
+[FOO, FOO] = SUCC(SUCC(ZERO));
 BAZ(FOO, BAR(x_)) = BAZ(x_, FOO) | SUCC(x_);"
     );
 }
+
+#[test]
+fn parse_pretty_roundtrip() {
+    let mut sig = Signature::default();
+    let s = "S x_ y_ z_ = x_ z_ (y_ z_);
+        CONS(SUCC(ZERO) CONS(ZERO NIL)) = FOO(.(BAR(ZERO) A) \"ab\" 'a b');
+        FOO(x_) = BAR(.(.(A B) C)) | BAZ(SUCC(SUCC(ZERO))) <= x_ = 12;";
+    let trs = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    let pretty = trs.pretty(&sig);
+    assert_eq!(
+        pretty,
+        "S x_ y_ z_ = x_ z_ (y_ z_);
+[SUCC(ZERO), ZERO] = FOO(BAR(ZERO) A, \"ab\", 'a b');
+FOO(x_) = BAR(A B C) | BAZ(SUCC(SUCC(ZERO))) <= x_ = '12';"
+    );
+    let trs2 =
+        parse_trs_pretty(&mut sig, &pretty).unwrap_or_else(|_| panic!("parse of {}", pretty));
+    assert!(TRS::alphas(&trs, &trs2));
+
+    let mut sig = Signature::default();
+    let s = "F(G(x_ y_) .(A .(B C)) DECC(DIGIT(4) 2) DIGIT(FIVE))";
+    let term = parse_term(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    let pretty = term.pretty(&sig);
+    assert_eq!(pretty, "F(G(x_, y_), A (B C), 42, DIGIT(FIVE))");
+    let term2 =
+        parse_term_pretty(&mut sig, &pretty).unwrap_or_else(|_| panic!("parse of {}", pretty));
+    assert!(Term::alpha(&term, &term2).is_some());
+
+    // digits are identifiers to parse_term, but numerals to parse_term_pretty, which reads
+    // them the same way whatever the signature
+    let s = "H(42 10)";
+    let term = parse_term(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    assert_eq!(term.display(&sig), "H(42 10)");
+    let pretty = term.pretty(&sig);
+    assert_eq!(pretty, "H('42', '10')");
+    let term2 =
+        parse_term_pretty(&mut sig, &pretty).unwrap_or_else(|_| panic!("parse of {}", pretty));
+    assert_eq!(term2, term);
+    let term3 = parse_term_pretty(&mut sig, "H(43, 10)").expect("parse of H(43, 10)");
+    assert_eq!(term3.display(&sig), "H(DECC(DIGIT(4) 3) DECC(DIGIT(1) 0))");
+    let mut sig = Signature::default();
+    let term4 = parse_term_pretty(&mut sig, "H(43, 10)").expect("parse of H(43, 10)");
+    assert_eq!(term4.display(&sig), "H(DECC(DIGIT(4) 3) DECC(DIGIT(1) 0))");
+}
+
+#[test]
+fn parse_pretty_roundtrip_exhaustive() {
+    let mut sig = Signature::new(vec![
+        (0, Some("ZERO".to_string())),
+        (1, Some("SUCC".to_string())),
+        (1, Some("DIGIT".to_string())),
+        (2, Some("DECC".to_string())),
+        (0, Some("0".to_string())),
+        (0, Some("4".to_string())),
+        (0, Some("FOUR".to_string())),
+        (0, Some("'a'".to_string())),
+        (2, Some("CONS".to_string())),
+        (0, Some("NIL".to_string())),
+        (2, Some(".".to_string())),
+    ]);
+    sig.new_var(Some("x".to_string()));
+    let terms: Vec<Term> = sig.enumerate_terms(5).collect();
+    assert!(terms.len() > 10_000);
+    for term in terms {
+        let pretty = term.pretty(&sig);
+        let term2 =
+            parse_term_pretty(&mut sig, &pretty).unwrap_or_else(|_| panic!("parse of {}", pretty));
+        assert!(
+            Term::alpha(&term, &term2).is_some(),
+            "{} reads back as {}",
+            term.display(&sig),
+            term2.display(&sig)
+        );
+    }
+}
+
+#[test]
+fn pretty_config() {
+    let mut sig = Signature::default();
+    let s = "MAP(f_ CONS(x_ xs_)) = CONS(.(f_ x_) MAP(f_ xs_)) <= x_ = DECC(DIGIT(4) 2);";
+    let trs = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+
+    let config = PrettyConfig {
+        width: Some(24),
+        indent: 4,
+        ..PrettyConfig::default()
+    };
+    let pretty = trs.pretty_with(&sig, &config);
+    assert_eq!(
+        pretty,
+        "MAP(f_, CONS(x_, xs_)) = CONS(
+    f_ x_,
+    MAP(f_, xs_)
+) <= x_ = 42;"
+    );
+    let trs2 =
+        parse_trs_pretty(&mut sig, &pretty).unwrap_or_else(|_| panic!("parse of {}", pretty));
+    assert!(TRS::alphas(&trs, &trs2));
+
+    let config = PrettyConfig {
+        numbers: false,
+        max_depth: Some(1),
+        ..PrettyConfig::default()
+    };
+    assert_eq!(
+        trs.pretty_with(&sig, &config),
+        "MAP(f_, CONS(…, …)) = CONS(… …, MAP(…, …)) <= x_ = DECC(DIGIT(…), '2');"
+    );
+}
+
+#[test]
+fn display_styles() {
+    let mut sig = Signature::default();
+    let s = "PLUS(x_ PLUS(y_ z_)) = PLUS(PLUS(x_ y_) z_);\nITE(TRUE x_ y_) = NOT(PLUS(x_ y_));";
+    let trs = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    let plus = sig.operator("PLUS", 2).unwrap();
+    let ite = sig.operator("ITE", 3).unwrap();
+    let not = sig.operator("NOT", 1).unwrap();
+
+    sig.set_display_style(&plus, DisplayStyle::Infix("+".to_string()));
+    sig.set_display_style(&ite, DisplayStyle::Mixfix("if _ then _ else _".to_string()));
+    // neither fits the arity of its operator
+    sig.set_display_style(&not, DisplayStyle::Infix("!".to_string()));
+    sig.set_display_style(&ite, DisplayStyle::Mixfix("_ ? _".to_string()));
+    assert_eq!(not.display_style(&sig), DisplayStyle::Prefix);
+    assert_eq!(
+        trs.pretty(&sig),
+        "x_ + (y_ + z_) = (x_ + y_) + z_;\nif TRUE then x_ else y_ = NOT(x_ + y_);"
+    );
+
+    let attributes = OperatorAttributes {
+        associative: true,
+        ..OperatorAttributes::default()
+    };
+    sig.set_attributes(&plus, attributes);
+    assert_eq!(trs.rules()[0].pretty(&sig), "x_ + y_ + z_ = (x_ + y_) + z_");
+
+    sig.set_display_style(&plus, DisplayStyle::Prefix);
+    assert_eq!(
+        trs.rules()[0].pretty(&sig),
+        "PLUS(x_, y_, z_) = PLUS(PLUS(x_, y_), z_)"
+    );
+}
+
+#[test]
+fn display_formats() {
+    let mut sig = Signature::default();
+    let s = "F(x_ 'G H') = A | B(x_) <= x_ = A;\nA = B;";
+    let trs = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+
+    assert_eq!(trs.display(&sig), s);
+    assert_eq!(
+        format!("<{}>", trs.rules()[0].display(&sig)),
+        "<F(x_ 'G H') = A | B(x_) <= x_ = A>"
+    );
+    assert_eq!(trs.rules()[0].lhs.display(&sig), "F(x_ 'G H')");
+    assert_eq!(
+        format!(
+            "{} and {}",
+            trs.rules()[0].lhs.args()[0].display(&sig),
+            trs.rules()[0].rhs[0].display(&sig)
+        ),
+        "x_ and A"
+    );
+
+    let rule =
+        parse_rulecontext(&mut sig, "F([!] x_) = [!] | x_").expect("parse of F([!] x_) = [!] | x_");
+    assert_eq!(rule.display(&sig), "F([!] x_) = [!] | x_");
+    assert_eq!(rule.lhs.display(&sig), "F([!] x_)");
+    assert_eq!(Atom::from(sig.operators()[0]).display(&sig), "'G H'");
+}
+
+#[test]
+fn signature_queries() {
+    let mut sig = Signature::default();
+    let s = "F(x_ G(y_)) = F(G(x_) y_);\nG(x_) = H;";
+    parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    let h = sig.new_op(1, Some("H".to_string()));
+
+    assert_eq!(sig.operator("H", 0).map(|op| op.arity()), Some(0));
+    assert_eq!(sig.operator("H", 1), Some(h));
+    assert!(sig.operator("F", 1).is_none());
+    let unary: Vec<_> = sig
+        .operators_with_arity(1)
+        .iter()
+        .map(|op| op.display(&sig))
+        .collect();
+    assert_eq!(unary, vec!["G", "H"]);
+    assert!(sig.operators_with_arity(3).is_empty());
+    assert_eq!(sig.variable("x"), Some(sig.variables()[0]));
+    assert!(sig.variable("z").is_none());
+    assert!(sig.contains("y") && sig.contains("F") && !sig.contains("z"));
+
+    // merged Operators and Variables are indexed, too
+    let sig2 = Signature::new(vec![(2, Some("K".to_string()))]);
+    sig2.clone().new_var(Some("z".to_string()));
+    sig.merge(&sig2, MergeStrategy::DistinctOperators).unwrap();
+    assert_eq!(sig.operators_with_arity(2).len(), 2);
+    assert!(sig.operator("K", 2).is_some() && sig.variable("z").is_some());
+    assert!(!sig2.contains("K"));
+}
+
+#[test]
+fn total_orders() {
+    let mut sig = Signature::default();
+    let terms: Vec<Term> = [
+        "F(A B)", "x_", "F(y_ A)", "A", "G(A)", "F(B A)", "B", "y_", "F(A B)",
+    ]
+    .iter()
+    .map(|s| parse_term(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s)))
+    .collect();
+
+    // shapes first (Variables, then constants, then G(_), then F(_ _)), then symbols
+    let sorted: Vec<_> = terms
+        .iter()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|t| t.display(&sig))
+        .collect();
+    assert_eq!(
+        sorted,
+        vec!["x_", "y_", "A", "B", "G(A)", "F(y_ A)", "F(A B)", "F(B A)"]
+    );
+    for t1 in &terms {
+        for t2 in &terms {
+            assert_eq!(t1 == t2, t1.cmp(t2) == std::cmp::Ordering::Equal);
+            assert_eq!(t1.cmp(t2), t2.cmp(t1).reverse());
+        }
+    }
+
+    let s = "F(x_ B) = A; F(A x_) = B; G(x_) = x_ | A;";
+    let trs1 = parse_trs(&mut sig, s).unwrap_or_else(|_| panic!("parse of {}", s));
+    let mut rules = trs1.rules().to_vec();
+    rules.sort();
+    let sorted: Vec<_> = rules.iter().map(|r| r.display(&sig)).collect();
+    assert_eq!(sorted, vec!["G(x_) = x_ | A", "F(x_ B) = A", "F(A x_) = B"]);
+
+    let mut trs2 = trs1.clone();
+    trs2.rules_mut().sort();
+    assert!(trs1 > trs2);
+    *trs2.rules_mut() = trs1.rules().to_vec();
+    assert_eq!(trs1.cmp(&trs2), std::cmp::Ordering::Equal);
+}